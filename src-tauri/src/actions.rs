@@ -0,0 +1,646 @@
+// 命令面板（Ctrl+K）的操作目录：每个操作在 ACTIONS 中声明 id、显示名、参数与可用条件，
+// list_actions 按当前上下文计算是否可用，invoke_action 校验参数与可用性后分派到对应命令。
+// 新增操作只需在 ACTIONS 中登记
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::{
+    config::config,
+    connectivity::{set_offline_mode, SetOfflineModeReq},
+    defer::WarnDefer,
+    image_cache::{
+        check_cache_freshness, delete_file_cache, download_project_files, FileDownloadInfo,
+    },
+    integrity::{run_integrity_check, RunIntegrityCheckReq},
+    project::{cached_proj_snapshot, get_project_files, GetProjectFilesReq, ResProjectEnriched},
+    publish::{publish_projs_bulk, PublishProjsBulkReq},
+    session::current_identity,
+    storage::{
+        cache_metadata::{get_cached_project_metadata, CachedProjectMetadata},
+        LOCAL_STORAGE,
+    },
+};
+
+const DISABLED_CODE: &str = "action_disabled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgKind {
+    String,
+    Bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+    // 未提供时从上下文中取值（"team_id" / "proj_id"）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_context: Option<&'static str>,
+}
+
+const PROJ_ARG: ArgSpec = ArgSpec {
+    name: "proj_id",
+    kind: ArgKind::String,
+    required: true,
+    from_context: Some("proj_id"),
+};
+
+// 计算可用性所需的状态
+struct ActionState {
+    proj_id: Option<String>,
+    project: Option<ResProjectEnriched>,
+    cached: Option<CachedProjectMetadata>,
+    user_id: Option<String>,
+    offline: bool,
+}
+
+type ActionFuture = Pin<Box<dyn Future<Output = Result<ActionOutcome, String>> + Send>>;
+type Dispatch = fn(AppHandle, Map<String, Value>) -> ActionFuture;
+
+struct ActionDef {
+    id: &'static str,
+    label: &'static str,
+    args: &'static [ArgSpec],
+    // Err 为不可用的原因
+    available: fn(&ActionState) -> Result<(), &'static str>,
+    // None 表示由前端执行（如页面跳转），后端只校验参数
+    dispatch: Option<Dispatch>,
+}
+
+const ACTIONS: &[ActionDef] = &[
+    ActionDef {
+        id: "publish_project",
+        label: "发布当前项目",
+        args: &[PROJ_ARG],
+        available: can_publish,
+        dispatch: Some(dispatch_publish),
+    },
+    ActionDef {
+        id: "download_cache",
+        label: "下载图片缓存",
+        args: &[PROJ_ARG],
+        available: can_download,
+        dispatch: Some(dispatch_download),
+    },
+    ActionDef {
+        id: "check_cache_freshness",
+        label: "检查缓存是否过期",
+        args: &[PROJ_ARG],
+        available: can_check_freshness,
+        dispatch: Some(dispatch_check_freshness),
+    },
+    ActionDef {
+        id: "delete_cache",
+        label: "删除图片缓存",
+        args: &[PROJ_ARG],
+        available: is_cached,
+        dispatch: Some(dispatch_delete_cache),
+    },
+    ActionDef {
+        id: "switch_team",
+        label: "切换汉化组",
+        args: &[ArgSpec {
+            name: "team_id",
+            kind: ArgKind::String,
+            required: true,
+            from_context: None,
+        }],
+        available: always,
+        dispatch: None,
+    },
+    ActionDef {
+        id: "toggle_offline_mode",
+        label: "切换离线模式",
+        args: &[ArgSpec {
+            name: "enabled",
+            kind: ArgKind::Bool,
+            required: false,
+            from_context: None,
+        }],
+        available: always,
+        dispatch: Some(dispatch_toggle_offline),
+    },
+    ActionDef {
+        id: "run_integrity_check",
+        label: "检查本地数据",
+        args: &[ArgSpec {
+            name: "fix",
+            kind: ArgKind::Bool,
+            required: false,
+            from_context: None,
+        }],
+        available: always,
+        dispatch: Some(dispatch_integrity_check),
+    },
+];
+
+// ========== 可用条件 ==========
+
+fn always(_: &ActionState) -> Result<(), &'static str> {
+    Ok(())
+}
+
+fn online(state: &ActionState) -> Result<(), &'static str> {
+    if state.offline {
+        return Err("离线模式下不可用");
+    }
+
+    Ok(())
+}
+
+fn has_proj(state: &ActionState) -> Result<(), &'static str> {
+    if state.proj_id.is_none() {
+        return Err("未选择项目");
+    }
+
+    Ok(())
+}
+
+fn can_publish(state: &ActionState) -> Result<(), &'static str> {
+    has_proj(state)?;
+    online(state)?;
+
+    let project = state.project.as_ref().ok_or("项目信息未加载")?;
+
+    if !project.has_poprako {
+        return Err("项目未关联 PopRaKo");
+    }
+
+    if project.is_published == Some(true) {
+        return Err("项目已发布");
+    }
+
+    let principal = match (&state.user_id, &project.principals) {
+        (Some(user_id), Some(principals)) => principals.iter().any(|id| id == user_id),
+        _ => false,
+    };
+
+    if !principal {
+        return Err("仅项目负责人可发布");
+    }
+
+    Ok(())
+}
+
+fn is_cached(state: &ActionState) -> Result<(), &'static str> {
+    has_proj(state)?;
+
+    if state.cached.is_none() {
+        return Err("项目尚未缓存");
+    }
+
+    Ok(())
+}
+
+fn can_download(state: &ActionState) -> Result<(), &'static str> {
+    has_proj(state)?;
+    online(state)?;
+
+    if state
+        .cached
+        .as_ref()
+        .is_some_and(|cached| cached.status == "completed")
+    {
+        return Err("项目已缓存");
+    }
+
+    Ok(())
+}
+
+fn can_check_freshness(state: &ActionState) -> Result<(), &'static str> {
+    is_cached(state)?;
+    online(state)
+}
+
+async fn load_state(proj_id: Option<String>) -> ActionState {
+    let project = proj_id.as_deref().and_then(cached_proj_snapshot);
+
+    let cached = match (&proj_id, LOCAL_STORAGE.get()) {
+        (Some(proj_id), Some(storage)) => get_cached_project_metadata(storage.pool(), proj_id)
+            .await
+            .unwrap_or_else(|err| {
+                tracing::warn!(%proj_id, error = %err, "actions.cache_metadata.failed");
+                None
+            }),
+        _ => None,
+    };
+
+    ActionState {
+        proj_id,
+        project,
+        cached,
+        user_id: current_identity().poprako_user_id,
+        offline: config().offline_mode,
+    }
+}
+
+// ========== 参数 ==========
+
+fn fill_args(
+    def: &ActionDef,
+    args: Value,
+    context: &ActionContext,
+) -> Result<Map<String, Value>, String> {
+    let mut args = match args {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => return Err("参数必须是 JSON 对象".to_string()),
+    };
+
+    for spec in def.args {
+        let from_context = match spec.from_context {
+            Some("team_id") => context.team_id.clone(),
+            Some("proj_id") => context.proj_id.clone(),
+            _ => None,
+        };
+
+        if matches!(args.get(spec.name), None | Some(Value::Null)) {
+            match from_context {
+                Some(value) => {
+                    args.insert(spec.name.to_string(), Value::String(value));
+                }
+                None if spec.required => {
+                    return Err(format!("缺少参数 {}", spec.name));
+                }
+                None => continue,
+            }
+        }
+
+        let matches = match spec.kind {
+            ArgKind::String => args[spec.name].is_string(),
+            ArgKind::Bool => args[spec.name].is_boolean(),
+        };
+
+        if !matches {
+            return Err(format!("参数 {} 类型错误", spec.name));
+        }
+    }
+
+    if let Some(unknown) = args
+        .keys()
+        .find(|key| !def.args.iter().any(|spec| spec.name == key.as_str()))
+    {
+        return Err(format!("未知参数 {}", unknown));
+    }
+
+    Ok(args)
+}
+
+// 已经过 fill_args 校验
+fn str_arg(args: &Map<String, Value>, name: &str) -> String {
+    args.get(name)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn bool_arg(args: &Map<String, Value>, name: &str) -> Option<bool> {
+    args.get(name).and_then(Value::as_bool)
+}
+
+fn done<T: Serialize>(result: T) -> Result<ActionOutcome, String> {
+    let result = serde_json::to_value(result).map_err(|err| format!("序列化结果失败: {}", err))?;
+
+    Ok(ActionOutcome::Done { result })
+}
+
+// ========== 分派 ==========
+
+fn dispatch_publish(app: AppHandle, args: Map<String, Value>) -> ActionFuture {
+    Box::pin(async move {
+        let reply = publish_projs_bulk(
+            app,
+            PublishProjsBulkReq {
+                proj_ids: vec![str_arg(&args, "proj_id")],
+                require_complete: true,
+                check_sources: false,
+                dry_run: false,
+            },
+        )
+        .await?;
+
+        done(reply)
+    })
+}
+
+fn dispatch_download(app: AppHandle, args: Map<String, Value>) -> ActionFuture {
+    Box::pin(async move {
+        let proj_id = str_arg(&args, "proj_id");
+
+        let files = get_project_files(GetProjectFilesReq {
+            project_id: proj_id.clone().into(),
+            target_id: None,
+            sort: None,
+        })
+        .await?;
+
+        let name = cached_proj_snapshot(&proj_id)
+            .map(|project| project.name)
+            .unwrap_or_else(|| proj_id.clone());
+
+        let files = files
+            .into_iter()
+            .filter_map(|file| {
+                Some(FileDownloadInfo {
+                    url: file.url?,
+                    id: Some(file.id),
+                })
+            })
+            .collect();
+
+        // 与手动触发的下载重叠时等待其结束，不重复下载
+        let report =
+            download_project_files(app, proj_id.into(), name, files, None, Some(true)).await?;
+
+        done(report)
+    })
+}
+
+fn dispatch_check_freshness(_: AppHandle, args: Map<String, Value>) -> ActionFuture {
+    Box::pin(async move { done(check_cache_freshness(str_arg(&args, "proj_id").into()).await?) })
+}
+
+fn dispatch_delete_cache(_: AppHandle, args: Map<String, Value>) -> ActionFuture {
+    Box::pin(async move { done(delete_file_cache(str_arg(&args, "proj_id").into()).await?) })
+}
+
+fn dispatch_toggle_offline(app: AppHandle, args: Map<String, Value>) -> ActionFuture {
+    Box::pin(async move {
+        let enabled = bool_arg(&args, "enabled").unwrap_or(!config().offline_mode);
+
+        done(set_offline_mode(app, SetOfflineModeReq { enabled }).await?)
+    })
+}
+
+fn dispatch_integrity_check(_: AppHandle, args: Map<String, Value>) -> ActionFuture {
+    Box::pin(async move {
+        let fix = bool_arg(&args, "fix").unwrap_or(false);
+
+        done(run_integrity_check(RunIntegrityCheckReq { fix }).await?)
+    })
+}
+
+// ========== 命令 ==========
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActionContext {
+    #[serde(default)]
+    pub team_id: Option<String>,
+    #[serde(default)]
+    pub proj_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub args: &'static [ArgSpec],
+    pub enabled: bool,
+    // 不可用的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<&'static str>,
+    // 由前端执行
+    pub frontend: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListActionsReq {
+    #[serde(default)]
+    pub context: ActionContext,
+}
+
+#[tauri::command]
+pub async fn list_actions(payload: ListActionsReq) -> Result<Vec<ActionInfo>, String> {
+    let state = load_state(payload.context.proj_id.clone()).await;
+
+    let actions: Vec<ActionInfo> = ACTIONS
+        .iter()
+        .map(|def| {
+            let available = (def.available)(&state);
+
+            ActionInfo {
+                id: def.id,
+                label: def.label,
+                args: def.args,
+                enabled: available.is_ok(),
+                disabled_reason: available.err(),
+                frontend: def.dispatch.is_none(),
+            }
+        })
+        .collect();
+
+    tracing::debug!(
+        proj_id = ?payload.context.proj_id,
+        enabled = actions.iter().filter(|a| a.enabled).count(),
+        "actions.list"
+    );
+
+    Ok(actions)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ActionOutcome {
+    // 已由后端执行，result 为对应命令的返回值
+    Done {
+        result: Value,
+    },
+    // 需要前端执行，args 已补全并校验
+    Frontend {
+        action_id: String,
+        args: Map<String, Value>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvokeActionReq {
+    pub action_id: String,
+    #[serde(default)]
+    pub args: Value,
+    #[serde(default)]
+    pub context: ActionContext,
+}
+
+#[tauri::command]
+pub async fn invoke_action(
+    app: AppHandle,
+    payload: InvokeActionReq,
+) -> Result<ActionOutcome, String> {
+    tracing::info!(action_id = %payload.action_id, "actions.invoke.start");
+
+    let mut defer = WarnDefer::new("actions.invoke");
+
+    let def = ACTIONS
+        .iter()
+        .find(|def| def.id == payload.action_id)
+        .ok_or_else(|| format!("未知操作 {}", payload.action_id))?;
+
+    let args = fill_args(def, payload.args, &payload.context)?;
+
+    // 可用性按实际参数中的项目计算
+    let proj_id = args
+        .get("proj_id")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let state = load_state(proj_id).await;
+
+    if let Err(reason) = (def.available)(&state) {
+        tracing::warn!(action_id = %def.id, reason, "actions.invoke.disabled");
+
+        return Err(serde_json::json!({
+            "code": DISABLED_CODE,
+            "action_id": def.id,
+            "reason": reason,
+        })
+        .to_string());
+    }
+
+    let outcome = match def.dispatch {
+        Some(dispatch) => dispatch(app, args).await?,
+        None => ActionOutcome::Frontend {
+            action_id: def.id.to_string(),
+            args,
+        },
+    };
+
+    tracing::info!(action_id = %def.id, "actions.invoke.ok");
+
+    defer.success();
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::enriched_fixture;
+
+    fn def(id: &str) -> &'static ActionDef {
+        ACTIONS.iter().find(|def| def.id == id).unwrap()
+    }
+
+    fn context(proj_id: Option<&str>) -> ActionContext {
+        ActionContext {
+            team_id: Some("team-ctx".to_string()),
+            proj_id: proj_id.map(str::to_string),
+        }
+    }
+
+    fn state(project: Option<ResProjectEnriched>, cached_status: Option<&str>) -> ActionState {
+        ActionState {
+            proj_id: Some("act-proj".to_string()),
+            project,
+            cached: cached_status.map(|status| CachedProjectMetadata {
+                project_id: "act-proj".to_string(),
+                project_name: "项目".to_string(),
+                status: status.to_string(),
+                file_count: 1,
+                total_size_bytes: 1,
+                cached_at: 0,
+                remote_file_count: None,
+                missing_count: None,
+                remote_checked_at: None,
+                remote_deleted: false,
+            }),
+            user_id: Some("u-principal".to_string()),
+            offline: false,
+        }
+    }
+
+    fn principal_project() -> ResProjectEnriched {
+        let mut project = enriched_fixture("act-proj", "team-ctx");
+        project.principals = Some(vec!["u-principal".into()]);
+        project
+    }
+
+    #[test]
+    fn action_ids_are_unique() {
+        let ids: HashSet<&str> = ACTIONS.iter().map(|def| def.id).collect();
+
+        assert_eq!(ids.len(), ACTIONS.len());
+    }
+
+    #[test]
+    fn args_are_filled_from_context_and_validated() {
+        let args = fill_args(def("delete_cache"), Value::Null, &context(Some("p1"))).unwrap();
+        assert_eq!(Value::Object(args), json!({ "proj_id": "p1" }));
+
+        // 显式参数优先于上下文
+        let args = fill_args(
+            def("delete_cache"),
+            json!({ "proj_id": "p2" }),
+            &context(Some("p1")),
+        )
+        .unwrap();
+        assert_eq!(str_arg(&args, "proj_id"), "p2");
+
+        let missing = fill_args(def("delete_cache"), json!({}), &context(None)).unwrap_err();
+        assert!(missing.contains("proj_id"));
+
+        // switch_team 的 team_id 不从上下文取
+        assert!(fill_args(def("switch_team"), Value::Null, &context(None)).is_err());
+
+        let optional = fill_args(def("run_integrity_check"), Value::Null, &context(None)).unwrap();
+        assert_eq!(bool_arg(&optional, "fix"), None);
+
+        for bad in [
+            json!({ "fix": "yes" }),
+            json!({ "extra": 1 }),
+            json!([true]),
+        ] {
+            assert!(fill_args(def("run_integrity_check"), bad, &context(None)).is_err());
+        }
+    }
+
+    #[test]
+    fn publishing_requires_an_unpublished_project_led_by_the_user() {
+        assert_eq!(can_publish(&state(Some(principal_project()), None)), Ok(()));
+
+        assert_eq!(can_publish(&state(None, None)), Err("项目信息未加载"));
+
+        let mut published = principal_project();
+        published.is_published = Some(true);
+        assert_eq!(
+            can_publish(&state(Some(published), None)),
+            Err("项目已发布")
+        );
+
+        let mut someone_elses = principal_project();
+        someone_elses.principals = Some(vec!["u-other".into()]);
+        assert_eq!(
+            can_publish(&state(Some(someone_elses), None)),
+            Err("仅项目负责人可发布")
+        );
+
+        let mut offline = state(Some(principal_project()), None);
+        offline.offline = true;
+        assert_eq!(can_publish(&offline), Err("离线模式下不可用"));
+    }
+
+    #[test]
+    fn cache_actions_follow_cache_state() {
+        assert!(can_download(&state(None, None)).is_ok());
+        assert!(can_download(&state(None, Some("partial"))).is_ok());
+        assert_eq!(
+            can_download(&state(None, Some("completed"))),
+            Err("项目已缓存")
+        );
+
+        assert_eq!(is_cached(&state(None, None)), Err("项目尚未缓存"));
+        assert!(can_check_freshness(&state(None, Some("completed"))).is_ok());
+
+        let mut no_project = state(None, Some("completed"));
+        no_project.proj_id = None;
+        assert_eq!(is_cached(&no_project), Err("未选择项目"));
+    }
+}
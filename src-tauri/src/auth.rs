@@ -1,10 +1,14 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use serde::{Deserialize, Serialize};
 
-use crate::{defer::WarnDefer, http::moetran_post_opt};
+use crate::{defer::WarnDefer, error::AppError, http::moetran_post_opt};
 
 // ================== Captcha 与登录 Token DTO 定义 ==================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResCaptcha {
     pub image: String,
     pub info: String,
@@ -23,17 +27,122 @@ pub struct ReqToken {
 pub struct ResToken {
     pub token: String,
 }
+// ================== 验证码缓存 ==================
+// 登录表单重新挂载时会再次请求验证码，频繁请求会被 Moetran 限流而无法登录。
+// 有效期内重复请求返回同一张验证码；向 Moetran 的请求按每分钟次数限制；验证码只能使用一次，登录请求后即失效
+
+// Moetran 验证码约 2 分钟过期，留出余量
+const CAPTCHA_VALID_FOR: Duration = Duration::from_secs(110);
+const CAPTCHA_BUDGET_WINDOW: Duration = Duration::from_secs(60);
+// 每分钟最多向 Moetran 请求的验证码次数
+const CAPTCHA_BUDGET: usize = 5;
+
+const CAPTCHA_RATE_LIMITED_CODE: &str = "captcha_rate_limited";
+
+struct CaptchaSession {
+    cached: Option<(ResCaptcha, Instant)>,
+    // 最近一个窗口内向 Moetran 请求的时间
+    fetches: VecDeque<Instant>,
+}
+
+impl CaptchaSession {
+    const fn new() -> Self {
+        Self {
+            cached: None,
+            fetches: VecDeque::new(),
+        }
+    }
+
+    fn valid_cached(&self, now: Instant) -> Option<ResCaptcha> {
+        let (captcha, fetched_at) = self.cached.as_ref()?;
+
+        (now.duration_since(*fetched_at) < CAPTCHA_VALID_FOR).then(|| captcha.clone())
+    }
+
+    // 占用一次请求额度；额度用完时返回还需等待的秒数
+    fn take_budget(&mut self, now: Instant) -> Result<(), u64> {
+        while self
+            .fetches
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= CAPTCHA_BUDGET_WINDOW)
+        {
+            self.fetches.pop_front();
+        }
+
+        if self.fetches.len() >= CAPTCHA_BUDGET {
+            let oldest = self.fetches[0];
+            let wait = CAPTCHA_BUDGET_WINDOW.saturating_sub(now.duration_since(oldest));
+
+            return Err(wait.as_secs().max(1));
+        }
+
+        self.fetches.push_back(now);
+
+        Ok(())
+    }
+
+    fn store(&mut self, captcha: ResCaptcha, now: Instant) {
+        self.cached = Some((captcha, now));
+    }
+
+    // 登录请求使用过的验证码失效；info 不同（已被更新的验证码替换）时保留
+    fn invalidate(&mut self, info: &str) {
+        if self
+            .cached
+            .as_ref()
+            .is_some_and(|(captcha, _)| captcha.info == info)
+        {
+            self.cached = None;
+        }
+    }
+}
+
+static CAPTCHA_SESSION: Mutex<CaptchaSession> = Mutex::new(CaptchaSession::new());
+
+fn with_captcha_session<T>(f: impl FnOnce(&mut CaptchaSession) -> T) -> T {
+    match CAPTCHA_SESSION.lock() {
+        Ok(mut guard) => f(&mut guard),
+        Err(poisoned) => f(&mut poisoned.into_inner()),
+    }
+}
+
+fn captcha_rate_limited_error(retry_after: u64) -> String {
+    serde_json::json!({
+        "code": CAPTCHA_RATE_LIMITED_CODE,
+        "message": "验证码请求过于频繁，请稍后再试",
+        "retry_after": retry_after,
+    })
+    .to_string()
+}
+
 // ================== 获取验证码图与验证码信息 ==================
 // 说明：通过后端代理拉取验证码，避免跨域问题；返回图像与 info 标识。
+// 有效期内返回缓存的验证码，force_new 为 true 时（用户点击“换一张”）重新获取
 #[tauri::command]
-pub async fn get_captcha() -> Result<ResCaptcha, String> {
-    tracing::info!("captcha.request.start");
+pub async fn get_captcha(force_new: Option<bool>) -> Result<ResCaptcha, AppError> {
+    let force_new = force_new.unwrap_or(false);
+
+    if !force_new {
+        if let Some(cached) = with_captcha_session(|session| session.valid_cached(Instant::now())) {
+            tracing::debug!(info = %cached.info, "captcha.request.cached");
+            return Ok(cached);
+        }
+    }
+
+    if let Err(retry_after) = with_captcha_session(|session| session.take_budget(Instant::now())) {
+        tracing::warn!(retry_after, "captcha.request.rate_limited");
+        return Err(AppError::Other(captcha_rate_limited_error(retry_after)));
+    }
+
+    tracing::info!(force_new, "captcha.request.start");
 
     let mut defer = WarnDefer::new("captcha.request");
 
     let body = moetran_post_opt::<serde_json::Value, ResCaptcha>("captchas", None)
         .await
-        .map_err(|err| format!("Captcha request failed: {}", err))?;
+        .map_err(|err| err.context("Captcha request failed"))?;
+
+    with_captcha_session(|session| session.store(body.clone(), Instant::now()));
 
     tracing::info!(info = %body.info, "captcha.request.ok");
 
@@ -45,14 +154,19 @@ pub async fn get_captcha() -> Result<ResCaptcha, String> {
 // ================== 申请登录访问 Token ==================
 // 输入：邮箱、密码、验证码及其 info；输出：用户访问 token。
 #[tauri::command]
-pub async fn aquire_token(payload: ReqToken) -> Result<ResToken, String> {
+pub async fn aquire_token(payload: ReqToken) -> Result<ResToken, AppError> {
     tracing::info!(email = %payload.email, "token.request.start");
 
     let mut defer = WarnDefer::new("token.request");
 
-    let body = moetran_post_opt::<ReqToken, ResToken>("user/token", Some(payload))
-        .await
-        .map_err(|err| format!("Token request failed: {}", err))?;
+    let captcha_info = payload.captcha_info.clone();
+
+    let result = moetran_post_opt::<ReqToken, ResToken>("user/token", Some(payload)).await;
+
+    // 无论登录成功与否，验证码都已被使用
+    with_captcha_session(|session| session.invalidate(&captcha_info));
+
+    let body = result.map_err(|err| err.context("Token request failed"))?;
 
     tracing::info!(token_len = body.token.len(), "token.request.ok");
 
@@ -60,3 +174,79 @@ pub async fn aquire_token(payload: ReqToken) -> Result<ResToken, String> {
 
     Ok(body)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn captcha(info: &str) -> ResCaptcha {
+        ResCaptcha {
+            image: "data:image/png;base64,".to_string(),
+            info: info.to_string(),
+        }
+    }
+
+    #[test]
+    fn captcha_is_reused_within_its_validity_window() {
+        let start = Instant::now();
+        let mut session = CaptchaSession::new();
+
+        assert!(session.valid_cached(start).is_none());
+
+        session.store(captcha("c1"), start);
+        assert_eq!(
+            session
+                .valid_cached(start + Duration::from_secs(60))
+                .unwrap()
+                .info,
+            "c1"
+        );
+        assert!(session.valid_cached(start + CAPTCHA_VALID_FOR).is_none());
+    }
+
+    #[test]
+    fn only_the_used_captcha_is_invalidated() {
+        let now = Instant::now();
+        let mut session = CaptchaSession::new();
+        session.store(captcha("c2"), now);
+
+        // 已被新验证码替换的旧 info 不影响当前缓存
+        session.invalidate("c1");
+        assert!(session.valid_cached(now).is_some());
+
+        session.invalidate("c2");
+        assert!(session.valid_cached(now).is_none());
+    }
+
+    #[test]
+    fn fetch_budget_refills_as_the_window_slides() {
+        let start = Instant::now();
+        let mut session = CaptchaSession::new();
+
+        for n in 0..CAPTCHA_BUDGET as u64 {
+            session.take_budget(start + Duration::from_secs(n)).unwrap();
+        }
+
+        // 最早一次请求在第 0 秒，第 10 秒时还需等待 50 秒
+        assert_eq!(
+            session.take_budget(start + Duration::from_secs(10)),
+            Err(50)
+        );
+        assert_eq!(
+            session.take_budget(start + Duration::from_millis(59_900)),
+            Err(1)
+        );
+
+        assert!(session.take_budget(start + CAPTCHA_BUDGET_WINDOW).is_ok());
+        assert!(session.take_budget(start + CAPTCHA_BUDGET_WINDOW).is_err());
+    }
+
+    #[test]
+    fn rate_limited_error_carries_retry_after() {
+        let body: serde_json::Value =
+            serde_json::from_str(&captcha_rate_limited_error(12)).unwrap();
+
+        assert_eq!(body["code"], CAPTCHA_RATE_LIMITED_CODE);
+        assert_eq!(body["retry_after"], 12);
+    }
+}
@@ -0,0 +1,214 @@
+// 登录过期（后端返回 401）的处理：清除内存中的 token，并发出事件让前端弹出重新登录对话框。
+// http 层拿不到 AppHandle，启动时在此保存一份；同一后端在重新登录前只通知一次，
+// 避免过期后每个请求都触发一次对话框。启动时订阅 token 变更，写入与过期的那个不同的 token
+// （重新登录、刷新、切换演示模式）后重新开始监视；从数据库重新读出同一个过期 token 不算
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex, OnceLock,
+};
+
+use tauri::AppHandle;
+
+use crate::{
+    connectivity::Backend,
+    events::{emit_event, MoetranAuthExpired, PoprakoAuthExpired},
+    token,
+};
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+struct ExpiryState {
+    notified: AtomicBool,
+    // 收到 401 时正在使用的 token
+    expired_token: Mutex<Option<String>>,
+}
+
+impl ExpiryState {
+    const fn new() -> Self {
+        Self {
+            notified: AtomicBool::new(false),
+            expired_token: Mutex::new(None),
+        }
+    }
+
+    // 记录过期；返回是否需要通知（重新监视后的第一次）
+    fn mark_expired(&self, token: Option<String>) -> bool {
+        if let Some(token) = token {
+            if let Ok(mut expired) = self.expired_token.lock() {
+                *expired = Some(token);
+            }
+        }
+
+        !self.notified.swap(true, Ordering::SeqCst)
+    }
+
+    // token 被写入后调用；换成了另一个 token 时重新监视，返回是否重新监视
+    fn token_changed(&self, token: Option<&str>) -> bool {
+        let Some(token) = token else {
+            return false;
+        };
+
+        let Ok(mut expired) = self.expired_token.lock() else {
+            return false;
+        };
+
+        if expired.as_deref() == Some(token) {
+            return false;
+        }
+
+        *expired = None;
+
+        self.notified.swap(false, Ordering::SeqCst)
+    }
+}
+
+static MOETRAN_EXPIRY: ExpiryState = ExpiryState::new();
+
+static POPRAKO_EXPIRY: ExpiryState = ExpiryState::new();
+
+fn expiry_state(backend: Backend) -> &'static ExpiryState {
+    match backend {
+        Backend::Moetran => &MOETRAN_EXPIRY,
+        Backend::Poprako => &POPRAKO_EXPIRY,
+    }
+}
+
+fn cached_token(backend: Backend) -> Option<String> {
+    match backend {
+        Backend::Moetran => token::cached_moetran_token(),
+        Backend::Poprako => token::cached_poprako_token(),
+    }
+}
+
+// 启动时保存 AppHandle，之后 http 层即可发出过期事件
+pub(crate) fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+
+    for backend in [Backend::Moetran, Backend::Poprako] {
+        tauri::async_runtime::spawn(watch_token_changes(backend));
+    }
+}
+
+async fn watch_token_changes(backend: Backend) {
+    let mut changes = token::subscribe(backend);
+
+    loop {
+        let token = changes.changed().await;
+
+        if expiry_state(backend).token_changed(token.as_deref()) {
+            tracing::info!(?backend, "auth.expiry.rearmed");
+        }
+    }
+}
+
+// http 层收到 401 时调用
+pub(crate) fn handle_auth_expired(backend: Backend) {
+    let current = cached_token(backend);
+
+    token::forget_cached_token(backend);
+
+    if !expiry_state(backend).mark_expired(current) {
+        return;
+    }
+
+    tracing::warn!(?backend, "auth.expired");
+
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+
+    match backend {
+        Backend::Moetran => emit_event(app, MoetranAuthExpired {}),
+        Backend::Poprako => emit_event(app, PoprakoAuthExpired {}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::Value;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        http::moetran_get,
+        test_support::{MockBackends, TEST_MOETRAN_TOKEN, TEST_POPRAKO_TOKEN},
+    };
+
+    #[test]
+    fn notifies_once_until_another_token_is_written() {
+        let state = ExpiryState::new();
+
+        assert!(state.mark_expired(Some("old".to_string())));
+        assert!(!state.mark_expired(None));
+
+        // 401 后清除 token、从数据库重新读出同一个过期 token，都不重新监视
+        assert!(!state.token_changed(None));
+        assert!(!state.token_changed(Some("old")));
+        assert!(!state.mark_expired(Some("old".to_string())));
+
+        assert!(state.token_changed(Some("new")));
+        assert!(state.mark_expired(Some("new".to_string())));
+    }
+
+    #[test]
+    fn token_written_before_any_expiry_does_not_rearm() {
+        let state = ExpiryState::new();
+
+        assert!(!state.token_changed(Some("first")));
+        assert!(state.mark_expired(Some("first".to_string())));
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("condition not reached in time");
+    }
+
+    fn notified() -> bool {
+        MOETRAN_EXPIRY.notified.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn watcher_rearms_after_a_new_login() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({})))
+            .mount(&backends.moetran)
+            .await;
+
+        MOETRAN_EXPIRY.token_changed(Some("reset-before-test"));
+        tokio::spawn(watch_token_changes(Backend::Moetran));
+
+        let err = moetran_get::<Value>("user/info", None).await.unwrap_err();
+
+        assert!(matches!(
+            err.root(),
+            crate::error::AppError::AuthExpired { .. }
+        ));
+        assert!(notified());
+        assert_eq!(token::cached_moetran_token(), None);
+
+        // 重新读出的仍是过期的 token：不再通知
+        token::use_demo_tokens(Some((TEST_MOETRAN_TOKEN, TEST_POPRAKO_TOKEN)));
+        let _ = moetran_get::<Value>("user/info", None).await;
+        assert!(notified());
+
+        // 重新登录后恢复监视
+        token::use_demo_tokens(Some(("relogin-token", TEST_POPRAKO_TOKEN)));
+        wait_until(|| !notified()).await;
+
+        let _ = moetran_get::<Value>("user/info", None).await;
+        assert!(notified());
+    }
+}
@@ -0,0 +1,385 @@
+// 一次完成登录：申请 Moetran token → 保存 → 获取用户信息 → 同步 PopRaKo → 保存 → 拉取汉化组 → 身份检查。
+// 前端原先分六次调用，中途失败会停在半登录状态（Moetran token 已保存但 PopRaKo 未同步、用户信息未加载）。
+// Moetran 部分要么全部完成要么不留下任何 token；PopRaKo 同步失败时以仅 Moetran 模式登录；
+// 汉化组拉取失败不影响登录。每一步的失败都记录在返回结果中，原有的分步命令保留
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{
+    auth::{aquire_token, ReqToken},
+    defer::WarnDefer,
+    session::{refresh_identity, SessionIdentity},
+    team::{get_user_teams, GetUserTeamsReq, ResTeam},
+    token::{remove_moetran_token, remove_poprako_token, save_moetran_token, save_poprako_token},
+    user::{get_user_info, sync_user, ReqSync, ResUser},
+};
+
+// 登录后拉取的汉化组数量（与面板页一致）
+const BOOTSTRAP_TEAMS_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionMode {
+    // Moetran 与 PopRaKo 都已登录
+    Full,
+    // 只登录了 Moetran，PopRaKo 功能不可用
+    MoetranOnly,
+    // 未登录，本地没有保存任何 token
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrapStep {
+    AcquireToken,
+    SaveMoetranToken,
+    UserInfo,
+    SyncPoprako,
+    SavePoprakoToken,
+    Teams,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapFailure {
+    pub step: BootstrapStep,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Session {
+    pub mode: SessionMode,
+    pub user: Option<ResUser>,
+    // 拉取失败时为 None（与“没有汉化组”的空列表区分）
+    pub teams: Option<Vec<ResTeam>>,
+    pub poprako_synced: bool,
+    // 未登录时为 None
+    pub identity: Option<SessionIdentity>,
+    // 按发生顺序
+    pub failures: Vec<BootstrapFailure>,
+}
+
+impl Session {
+    fn logged_out(failures: Vec<BootstrapFailure>) -> Self {
+        Self {
+            mode: SessionMode::None,
+            user: None,
+            teams: None,
+            poprako_synced: false,
+            identity: None,
+            failures,
+        }
+    }
+}
+
+fn failure(step: BootstrapStep, message: impl Into<String>) -> BootstrapFailure {
+    let message = message.into();
+
+    tracing::warn!(?step, error = %message, "session.bootstrap.step_failed");
+
+    BootstrapFailure { step, message }
+}
+
+// Moetran 部分：成功时返回用户信息；失败时撤销已保存的 token
+async fn bootstrap_moetran(
+    payload: ReqToken,
+    failures: &mut Vec<BootstrapFailure>,
+) -> Option<ResUser> {
+    let token = match aquire_token(payload).await {
+        Ok(reply) => reply.token,
+        Err(err) => {
+            failures.push(failure(BootstrapStep::AcquireToken, err));
+            return None;
+        }
+    };
+
+    if let Err(err) = save_moetran_token(token).await {
+        failures.push(failure(BootstrapStep::SaveMoetranToken, err));
+        return None;
+    }
+
+    match get_user_info().await {
+        Ok(user) => Some(user),
+        Err(err) => {
+            failures.push(failure(BootstrapStep::UserInfo, err));
+
+            if let Err(err) = remove_moetran_token().await {
+                tracing::warn!(error = %err, "session.bootstrap.rollback_failed");
+            }
+
+            None
+        }
+    }
+}
+
+// PopRaKo 部分：失败时移除本地残留的 PopRaKo token（可能属于之前登录的账号）
+async fn bootstrap_poprako(
+    user: &ResUser,
+    email: String,
+    failures: &mut Vec<BootstrapFailure>,
+) -> bool {
+    let synced = match sync_user(ReqSync {
+        user_id: user.id.clone(),
+        username: user.name.clone(),
+        email,
+    })
+    .await
+    {
+        Ok(reply) => match save_poprako_token(reply.token).await {
+            Ok(()) => true,
+            Err(err) => {
+                failures.push(failure(BootstrapStep::SavePoprakoToken, err));
+                false
+            }
+        },
+        Err(err) => {
+            failures.push(failure(BootstrapStep::SyncPoprako, err));
+            false
+        }
+    };
+
+    if !synced {
+        if let Err(err) = remove_poprako_token().await {
+            tracing::warn!(error = %err, "session.bootstrap.rollback_failed");
+        }
+    }
+
+    synced
+}
+
+// 登录流程本身（身份检查需要 AppHandle 发事件，由命令在之后补上）
+async fn run_bootstrap(payload: ReqToken) -> Session {
+    let mut failures = Vec::new();
+    let email = payload.email.clone();
+
+    let Some(user) = bootstrap_moetran(payload, &mut failures).await else {
+        return Session::logged_out(failures);
+    };
+
+    let poprako_synced = bootstrap_poprako(&user, email, &mut failures).await;
+
+    let teams = match get_user_teams(GetUserTeamsReq {
+        page: 1,
+        limit: BOOTSTRAP_TEAMS_LIMIT,
+    })
+    .await
+    {
+        Ok(teams) => Some(teams),
+        Err(err) => {
+            failures.push(failure(BootstrapStep::Teams, err));
+            None
+        }
+    };
+
+    let mode = if poprako_synced {
+        SessionMode::Full
+    } else {
+        SessionMode::MoetranOnly
+    };
+
+    Session {
+        mode,
+        user: Some(user),
+        teams,
+        poprako_synced,
+        identity: None,
+        failures,
+    }
+}
+
+#[tauri::command]
+pub async fn bootstrap_session(app: AppHandle, payload: ReqToken) -> Result<Session, String> {
+    tracing::info!(email = %payload.email, "session.bootstrap.start");
+
+    let mut defer = WarnDefer::new("session.bootstrap");
+
+    let mut session = run_bootstrap(payload).await;
+
+    // 登录失败也是正常结果（如验证码错误），由 failures 说明原因
+    if session.mode != SessionMode::None {
+        session.identity = Some(refresh_identity(&app).await);
+    }
+
+    tracing::info!(
+        mode = ?session.mode,
+        user_id = ?session.user.as_ref().map(|user| &user.id),
+        teams = session.teams.as_ref().map(Vec::len),
+        failures = session.failures.len(),
+        "session.bootstrap.ok"
+    );
+
+    defer.success();
+
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_partial_json, header, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        test_support::{local_storage, MockBackends, TEST_MOETRAN_TOKEN},
+        token::{cached_moetran_token, cached_poprako_token},
+    };
+
+    fn login() -> ReqToken {
+        ReqToken {
+            email: "boot@example.com".to_string(),
+            password: "secret".to_string(),
+            captcha: "abcd".to_string(),
+            captcha_info: "captcha-1".to_string(),
+        }
+    }
+
+    fn steps(session: &Session) -> Vec<BootstrapStep> {
+        session
+            .failures
+            .iter()
+            .map(|failure| failure.step)
+            .collect()
+    }
+
+    async fn mount_moetran_login(backends: &MockBackends) {
+        Mock::given(method("POST"))
+            .and(path("/v1/user/token"))
+            .and(body_partial_json(json!({ "captcha": "abcd" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": "boot-mtr" })))
+            .mount(&backends.moetran)
+            .await;
+
+        // 用户信息必须用新申请到的 token 获取
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .and(header("authorization", "Bearer boot-mtr"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "boot-user",
+                "name": "登录测试",
+                "has_avatar": false,
+                "avatar": "",
+            })))
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/teams"))
+            .and(query_param(
+                "limit",
+                BOOTSTRAP_TEAMS_LIMIT.to_string().as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": "boot-team",
+                "avatar": "",
+                "has_avatar": false,
+                "name": "汉化组",
+            }])))
+            .mount(&backends.moetran)
+            .await;
+    }
+
+    async fn clear_saved_tokens() {
+        remove_moetran_token().await.unwrap();
+        remove_poprako_token().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn full_login_saves_both_tokens_and_loads_teams() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+        mount_moetran_login(&backends).await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/sync"))
+            .and(body_partial_json(json!({
+                "user_id": "boot-user",
+                "email": "boot@example.com",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": { "token": "boot-pop" },
+                "message": null,
+            })))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        let session = run_bootstrap(login()).await;
+
+        assert_eq!(session.mode, SessionMode::Full);
+        assert!(session.poprako_synced);
+        assert!(session.failures.is_empty());
+        assert_eq!(session.user.as_ref().unwrap().id, "boot-user");
+        assert_eq!(session.teams.as_ref().unwrap()[0].id, "boot-team");
+        assert_eq!(cached_moetran_token().as_deref(), Some("boot-mtr"));
+        assert_eq!(cached_poprako_token().as_deref(), Some("boot-pop"));
+
+        clear_saved_tokens().await;
+    }
+
+    #[tokio::test]
+    async fn poprako_down_falls_back_to_moetran_only() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+        mount_moetran_login(&backends).await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/sync"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_raw("<html>502 Bad Gateway</html>", "text/html"),
+            )
+            .mount(&backends.poprako)
+            .await;
+
+        let session = run_bootstrap(login()).await;
+
+        assert_eq!(session.mode, SessionMode::MoetranOnly);
+        assert!(!session.poprako_synced);
+        assert_eq!(steps(&session), [BootstrapStep::SyncPoprako]);
+        // 之前账号残留的 PopRaKo token 被清除，Moetran 部分照常完成
+        assert_eq!(cached_poprako_token(), None);
+        assert_eq!(cached_moetran_token().as_deref(), Some("boot-mtr"));
+        assert!(session.teams.is_some());
+
+        clear_saved_tokens().await;
+    }
+
+    #[tokio::test]
+    async fn bad_captcha_persists_nothing() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/user/token"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_json(json!({ "message": "验证码错误" })),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/sync"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&backends.poprako)
+            .await;
+
+        let session = run_bootstrap(login()).await;
+
+        assert_eq!(session.mode, SessionMode::None);
+        assert!(session.user.is_none() && session.teams.is_none());
+        assert_eq!(steps(&session), [BootstrapStep::AcquireToken]);
+        assert!(session.failures[0].message.contains("验证码错误"));
+        assert_eq!(cached_moetran_token().as_deref(), Some(TEST_MOETRAN_TOKEN));
+    }
+}
@@ -0,0 +1,1244 @@
+// 离线活动用的缓存打包：一台机器把选定项目的图片缓存、清单与元数据导出为单个归档文件，
+// 其他机器导入后无需联网即可打开这些项目。归档格式为 MAGIC + 版本号，之后逐条记录
+// [u32 头长度][JSON 头][内容][内容的 SHA-256]，以头长度 0 结束；每个项目依次为 元数据、清单（有时）、图片文件。
+// 读写都按块流式处理，不把整个归档读入内存。导入时逐条校验摘要，损坏的文件单独拒绝
+// （清单中改为未下载，之后联网时可重新下载补齐），不影响归档中的其余内容
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter},
+};
+
+use crate::{
+    defer::WarnDefer,
+    disk_space::{available_space, insufficient_disk_error},
+    error::AppError,
+    image_cache::{
+        collect_cached_sizes, get_cache_dir, invalidate_memory_cache, manifest_missing_files,
+        MANIFEST_FILE,
+    },
+    storage::{
+        cache_metadata::{
+            get_cached_project_metadata, upsert_cached_project, CachedProjectMetadata,
+        },
+        LOCAL_STORAGE,
+    },
+};
+
+const ARCHIVE_MAGIC: &[u8; 8] = b"MTPCACHE";
+const ARCHIVE_VERSION: u32 = 1;
+
+// 单条记录头的长度上限，超过视为归档损坏
+const MAX_HEADER_LEN: u32 = 64 * 1024;
+
+// 元数据与清单整体读入内存，超过此大小视为归档损坏
+const MAX_INLINE_ENTRY: u64 = 16 * 1024 * 1024;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+const DIGEST_LEN: usize = 32;
+
+// 导入中的文件先写入临时文件，校验通过后再改名（缓存扫描会忽略 .tmp）
+const IMPORT_TMP_SUFFIX: &str = ".tmp";
+
+const STATUS_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum EntryKind {
+    Metadata,
+    Manifest,
+    File,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntryHeader {
+    kind: EntryKind,
+    project_id: String,
+    // 图片文件名（仅 File）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    Export,
+    Import,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CacheTransferProgress {
+    pub kind: TransferKind,
+    pub total_bytes: u64,
+    pub processed_bytes: u64,
+    pub projects_done: usize,
+}
+
+struct TransferJob {
+    kind: TransferKind,
+    cancel: AtomicBool,
+    total_bytes: AtomicU64,
+    processed_bytes: AtomicU64,
+    projects_done: AtomicUsize,
+}
+
+impl TransferJob {
+    fn progress(&self) -> CacheTransferProgress {
+        CacheTransferProgress {
+            kind: self.kind,
+            total_bytes: self.total_bytes.load(Ordering::Relaxed),
+            processed_bytes: self.processed_bytes.load(Ordering::Relaxed),
+            projects_done: self.projects_done.load(Ordering::Relaxed),
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn advance(&self, bytes: usize) {
+        self.processed_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+// 导出与导入都读写整个缓存目录，同时只允许一个任务
+static TRANSFER_JOB: Mutex<Option<Arc<TransferJob>>> = Mutex::new(None);
+
+// 持有期间占用任务登记，drop 时注销
+struct TransferJobGuard {
+    job: Arc<TransferJob>,
+}
+
+impl Drop for TransferJobGuard {
+    fn drop(&mut self) {
+        if let Ok(mut current) = TRANSFER_JOB.lock() {
+            if current
+                .as_ref()
+                .is_some_and(|job| Arc::ptr_eq(job, &self.job))
+            {
+                *current = None;
+            }
+        }
+    }
+}
+
+fn claim_transfer(kind: TransferKind) -> Result<TransferJobGuard, String> {
+    let mut current = TRANSFER_JOB
+        .lock()
+        .map_err(|_| "cache transfer lock poisoned".to_string())?;
+
+    if current.is_some() {
+        return Err("已有缓存导出 / 导入任务在进行，请等待完成或先取消".to_string());
+    }
+
+    let job = Arc::new(TransferJob {
+        kind,
+        cancel: AtomicBool::new(false),
+        total_bytes: AtomicU64::new(0),
+        processed_bytes: AtomicU64::new(0),
+        projects_done: AtomicUsize::new(0),
+    });
+
+    *current = Some(job.clone());
+
+    Ok(TransferJobGuard { job })
+}
+
+// 归档中的项目 id 与文件名会拼进本地路径，只接受单级、非特殊的名称
+fn is_safe_component(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':', '\0'])
+}
+
+fn truncated(err: std::io::Error) -> String {
+    format!("归档文件不完整或已损坏: {}", err)
+}
+
+// ========== 写入 ==========
+
+async fn write_header<W: AsyncWrite + Unpin>(
+    out: &mut W,
+    header: &EntryHeader,
+) -> Result<(), String> {
+    let json = serde_json::to_vec(header).map_err(|e| format!("序列化归档记录失败: {}", e))?;
+
+    out.write_u32_le(json.len() as u32)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+    out.write_all(&json)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))
+}
+
+async fn write_inline_entry<W: AsyncWrite + Unpin>(
+    out: &mut W,
+    kind: EntryKind,
+    project_id: &str,
+    data: &[u8],
+) -> Result<(), String> {
+    write_header(
+        out,
+        &EntryHeader {
+            kind,
+            project_id: project_id.to_string(),
+            name: None,
+            size: data.len() as u64,
+        },
+    )
+    .await?;
+
+    let digest = Sha256::digest(data);
+
+    out.write_all(data)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+    out.write_all(&digest)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))
+}
+
+// 按块复制文件内容并计算摘要；文件在导出过程中被改动（长度变化）时报错
+async fn write_file_entry<W: AsyncWrite + Unpin>(
+    out: &mut W,
+    project_id: &str,
+    name: &str,
+    path: &Path,
+    size: u64,
+    job: &TransferJob,
+) -> Result<(), String> {
+    write_header(
+        out,
+        &EntryHeader {
+            kind: EntryKind::File,
+            project_id: project_id.to_string(),
+            name: Some(name.to_string()),
+            size,
+        },
+    )
+    .await?;
+
+    let file = fs::File::open(path)
+        .await
+        .map_err(|e| format!("读取缓存文件 {} 失败: {}", name, e))?;
+    let mut input = BufReader::new(file).take(size);
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut copied = 0u64;
+
+    loop {
+        let n = input
+            .read(&mut buf)
+            .await
+            .map_err(|e| format!("读取缓存文件 {} 失败: {}", name, e))?;
+
+        if n == 0 {
+            break;
+        }
+
+        hasher.update(&buf[..n]);
+        out.write_all(&buf[..n])
+            .await
+            .map_err(|e| format!("写入归档失败: {}", e))?;
+
+        copied += n as u64;
+        job.advance(n);
+    }
+
+    if copied != size {
+        return Err(format!("缓存文件 {} 在导出过程中被修改", name));
+    }
+
+    out.write_all(&hasher.finalize())
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))
+}
+
+struct ExportPlan {
+    metadata: CachedProjectMetadata,
+    dir: PathBuf,
+    manifest: Option<Vec<u8>>,
+    files: Vec<(String, u64)>,
+}
+
+// 写入全部项目；取消时返回 None（调用方删除未完成的归档）
+async fn write_archive(
+    job: &TransferJob,
+    plans: &[ExportPlan],
+    path: &Path,
+) -> Result<Option<(usize, u64)>, String> {
+    let file = fs::File::create(path)
+        .await
+        .map_err(|e| format!("创建归档文件失败: {}", e))?;
+    let mut out = BufWriter::new(file);
+
+    out.write_all(ARCHIVE_MAGIC)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+    out.write_u32_le(ARCHIVE_VERSION)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+
+    let mut files = 0;
+    let mut bytes = 0;
+
+    for plan in plans {
+        let project_id = &plan.metadata.project_id;
+        let metadata = serde_json::to_vec(&plan.metadata)
+            .map_err(|e| format!("序列化缓存元数据失败: {}", e))?;
+
+        write_inline_entry(&mut out, EntryKind::Metadata, project_id, &metadata).await?;
+
+        if let Some(manifest) = &plan.manifest {
+            write_inline_entry(&mut out, EntryKind::Manifest, project_id, manifest).await?;
+            job.advance(manifest.len());
+        }
+
+        for (name, size) in &plan.files {
+            if job.cancelled() {
+                return Ok(None);
+            }
+
+            write_file_entry(&mut out, project_id, name, &plan.dir.join(name), *size, job).await?;
+
+            files += 1;
+            bytes += size;
+        }
+
+        job.projects_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    out.write_u32_le(0)
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+    out.flush()
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+    out.into_inner()
+        .sync_all()
+        .await
+        .map_err(|e| format!("写入归档失败: {}", e))?;
+
+    Ok(Some((files, bytes)))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportCachesReq {
+    pub project_ids: Vec<String>,
+    pub dest_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportCachesReport {
+    pub dest_path: String,
+    pub projects: usize,
+    pub files: usize,
+    pub bytes: u64,
+    // 本机没有缓存、未写入归档的项目
+    pub not_cached: Vec<String>,
+    // 已取消：未生成归档文件
+    pub cancelled: bool,
+}
+
+async fn run_export(
+    job: &TransferJob,
+    payload: &ExportCachesReq,
+) -> Result<ExportCachesReport, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let mut plans = Vec::new();
+    let mut not_cached = Vec::new();
+    let mut seen = HashSet::new();
+
+    for project_id in &payload.project_ids {
+        if !seen.insert(project_id.as_str()) {
+            continue;
+        }
+
+        let dir = get_cache_dir(project_id);
+
+        let metadata = if is_safe_component(project_id) && dir.is_dir() {
+            get_cached_project_metadata(storage.pool(), project_id).await?
+        } else {
+            None
+        };
+
+        let Some(metadata) = metadata else {
+            not_cached.push(project_id.clone());
+            continue;
+        };
+
+        let files = collect_cached_sizes(&dir)
+            .await?
+            .into_iter()
+            .filter(|(name, _)| !name.ends_with(IMPORT_TMP_SUFFIX))
+            .collect();
+        let manifest = fs::read(dir.join(MANIFEST_FILE)).await.ok();
+
+        plans.push(ExportPlan {
+            metadata,
+            dir,
+            manifest,
+            files,
+        });
+    }
+
+    if plans.is_empty() {
+        return Err("所选项目在本机都没有图片缓存".to_string());
+    }
+
+    let total: u64 = plans
+        .iter()
+        .map(|plan| {
+            plan.files.iter().map(|(_, size)| size).sum::<u64>()
+                + plan.manifest.as_ref().map_or(0, |m| m.len() as u64)
+        })
+        .sum();
+
+    job.total_bytes.store(total, Ordering::Relaxed);
+
+    let dest = PathBuf::from(&payload.dest_path);
+    let partial = PathBuf::from(format!("{}.partial", payload.dest_path));
+
+    let written = match write_archive(job, &plans, &partial).await {
+        Ok(written) => written,
+        Err(err) => {
+            let _ = fs::remove_file(&partial).await;
+            return Err(err);
+        }
+    };
+
+    let Some((files, bytes)) = written else {
+        let _ = fs::remove_file(&partial).await;
+
+        return Ok(ExportCachesReport {
+            dest_path: payload.dest_path.clone(),
+            projects: 0,
+            files: 0,
+            bytes: 0,
+            not_cached,
+            cancelled: true,
+        });
+    };
+
+    fs::rename(&partial, &dest)
+        .await
+        .map_err(|e| format!("保存归档文件失败: {}", e))?;
+
+    Ok(ExportCachesReport {
+        dest_path: payload.dest_path.clone(),
+        projects: plans.len(),
+        files,
+        bytes,
+        not_cached,
+        cancelled: false,
+    })
+}
+
+// 把选定项目的图片缓存导出为单个归档文件
+#[tauri::command]
+pub async fn export_caches(payload: ExportCachesReq) -> Result<ExportCachesReport, String> {
+    tracing::info!(
+        projects = payload.project_ids.len(),
+        dest_path = %payload.dest_path,
+        "cache_transfer.export.start"
+    );
+
+    let mut defer = WarnDefer::new("cache_transfer.export");
+
+    let guard = claim_transfer(TransferKind::Export)?;
+
+    let report = run_export(&guard.job, &payload).await?;
+
+    tracing::info!(
+        projects = report.projects,
+        files = report.files,
+        bytes = report.bytes,
+        not_cached = report.not_cached.len(),
+        cancelled = report.cancelled,
+        "cache_transfer.export.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+// ========== 导入 ==========
+
+// 读取下一条记录头；遇到结束标记时返回 None
+async fn read_header<R: AsyncRead + Unpin>(input: &mut R) -> Result<Option<EntryHeader>, String> {
+    let len = input.read_u32_le().await.map_err(truncated)?;
+
+    if len == 0 {
+        return Ok(None);
+    }
+
+    if len > MAX_HEADER_LEN {
+        return Err("归档格式错误：记录头过长".to_string());
+    }
+
+    let mut buf = vec![0u8; len as usize];
+    input.read_exact(&mut buf).await.map_err(truncated)?;
+
+    serde_json::from_slice(&buf)
+        .map(Some)
+        .map_err(|e| format!("归档格式错误：无法解析记录头: {}", e))
+}
+
+// 按块读出一条记录的内容（写入 dest，或为 None 时丢弃）并校验摘要，返回摘要是否一致
+async fn copy_entry<R, W>(
+    input: &mut R,
+    size: u64,
+    mut dest: Option<&mut W>,
+    job: &TransferJob,
+) -> Result<bool, String>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut remaining = size;
+
+    while remaining > 0 {
+        let n = remaining.min(CHUNK_SIZE as u64) as usize;
+
+        input.read_exact(&mut buf[..n]).await.map_err(truncated)?;
+        hasher.update(&buf[..n]);
+
+        if let Some(dest) = dest.as_mut() {
+            dest.write_all(&buf[..n])
+                .await
+                .map_err(|e| format!("写入缓存文件失败: {}", e))?;
+        }
+
+        remaining -= n as u64;
+        job.advance(n);
+    }
+
+    let mut expected = [0u8; DIGEST_LEN];
+    input.read_exact(&mut expected).await.map_err(truncated)?;
+
+    Ok(hasher.finalize().as_slice() == expected)
+}
+
+async fn skip_entry<R: AsyncRead + Unpin>(
+    input: &mut R,
+    size: u64,
+    job: &TransferJob,
+) -> Result<(), String> {
+    copy_entry(input, size, None::<&mut tokio::io::Sink>, job)
+        .await
+        .map(|_| ())
+}
+
+// 元数据与清单：整体读入内存，摘要不一致时返回 None
+async fn read_inline<R: AsyncRead + Unpin>(
+    input: &mut R,
+    size: u64,
+    job: &TransferJob,
+) -> Result<Option<Vec<u8>>, String> {
+    if size > MAX_INLINE_ENTRY {
+        return Err("归档格式错误：记录过大".to_string());
+    }
+
+    let mut data = Vec::with_capacity(size as usize);
+    let valid = copy_entry(input, size, Some(&mut data), job).await?;
+
+    Ok(valid.then_some(data))
+}
+
+// 写入临时文件，摘要一致时改名为正式文件，否则删除，返回是否接受
+async fn import_file<R: AsyncRead + Unpin>(
+    input: &mut R,
+    dir: &Path,
+    name: &str,
+    size: u64,
+    job: &TransferJob,
+) -> Result<bool, String> {
+    let tmp_path = dir.join(format!("{}{}", name, IMPORT_TMP_SUFFIX));
+
+    let file = fs::File::create(&tmp_path)
+        .await
+        .map_err(|e| format!("创建缓存文件失败: {}", e))?;
+    let mut out = BufWriter::new(file);
+
+    let copied = copy_entry(input, size, Some(&mut out), job).await;
+    let flushed = match copied {
+        Ok(valid) => out
+            .flush()
+            .await
+            .map(|_| valid)
+            .map_err(|e| format!("写入缓存文件失败: {}", e)),
+        Err(err) => Err(err),
+    };
+
+    match flushed {
+        Ok(true) => {
+            fs::rename(&tmp_path, dir.join(name))
+                .await
+                .map_err(|e| format!("保存缓存文件失败: {}", e))?;
+            Ok(true)
+        }
+        Ok(false) => {
+            let _ = fs::remove_file(&tmp_path).await;
+            Ok(false)
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&tmp_path).await;
+            Err(err)
+        }
+    }
+}
+
+// 本机已有同一项目的缓存时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverwritePolicy {
+    // 保留本机的缓存，跳过该项目
+    #[default]
+    SkipExisting,
+    // 归档中的缓存时间更新时替换
+    ReplaceOlder,
+    AlwaysReplace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectImportOutcome {
+    // 本机原来没有该项目的缓存
+    Imported,
+    Replaced,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedProject {
+    pub project_id: String,
+    pub project_name: String,
+    pub outcome: ProjectImportOutcome,
+    pub files_written: usize,
+    // 摘要不一致（或名称非法）而未导入的文件
+    pub rejected: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportCachesReq {
+    pub src_path: String,
+    #[serde(default)]
+    pub overwrite_policy: OverwritePolicy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportCachesReport {
+    pub projects: Vec<ImportedProject>,
+    pub cancelled: bool,
+    // 归档在中途损坏（截断、格式错误）或写入失败时的错误；此前已导入的项目保留
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// 导入中的项目；accept 为 false 时跳过其后续记录
+struct ProjectImport {
+    metadata: Option<CachedProjectMetadata>,
+    accept: bool,
+    report: ImportedProject,
+}
+
+impl ProjectImport {
+    fn skipped(project_id: &str, project_name: &str) -> Self {
+        Self {
+            metadata: None,
+            accept: false,
+            report: ImportedProject {
+                project_id: project_id.to_string(),
+                project_name: project_name.to_string(),
+                outcome: ProjectImportOutcome::Skipped,
+                files_written: 0,
+                rejected: Vec::new(),
+            },
+        }
+    }
+}
+
+fn should_replace(
+    policy: OverwritePolicy,
+    local: &CachedProjectMetadata,
+    incoming: &CachedProjectMetadata,
+) -> bool {
+    match policy {
+        OverwritePolicy::SkipExisting => false,
+        OverwritePolicy::ReplaceOlder => local.cached_at < incoming.cached_at,
+        OverwritePolicy::AlwaysReplace => true,
+    }
+}
+
+// 项目的元数据记录：摘要不一致或无法解析时整个项目跳过（无法确认其余记录属于哪个项目）
+async fn begin_project<R: AsyncRead + Unpin>(
+    input: &mut R,
+    header: &EntryHeader,
+    policy: OverwritePolicy,
+    job: &TransferJob,
+) -> Result<ProjectImport, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let data = read_inline(input, header.size, job).await?;
+
+    let metadata = data
+        .and_then(|data| serde_json::from_slice::<CachedProjectMetadata>(&data).ok())
+        .filter(|metadata| {
+            metadata.project_id == header.project_id && is_safe_component(&metadata.project_id)
+        });
+
+    let Some(metadata) = metadata else {
+        tracing::warn!(project_id = %header.project_id, "cache_transfer.import.metadata_rejected");
+
+        let mut skipped = ProjectImport::skipped(&header.project_id, "");
+        skipped.report.rejected.push("metadata".to_string());
+
+        return Ok(skipped);
+    };
+
+    let local = get_cached_project_metadata(storage.pool(), &metadata.project_id).await?;
+
+    let outcome = match &local {
+        None => ProjectImportOutcome::Imported,
+        Some(local) if should_replace(policy, local, &metadata) => ProjectImportOutcome::Replaced,
+        Some(_) => {
+            return Ok(ProjectImport::skipped(
+                &metadata.project_id,
+                &metadata.project_name,
+            ))
+        }
+    };
+
+    fs::create_dir_all(get_cache_dir(&metadata.project_id))
+        .await
+        .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+
+    Ok(ProjectImport {
+        accept: true,
+        report: ImportedProject {
+            project_id: metadata.project_id.clone(),
+            project_name: metadata.project_name.clone(),
+            outcome,
+            files_written: 0,
+            rejected: Vec::new(),
+        },
+        metadata: Some(metadata),
+    })
+}
+
+// 项目的最后一条记录之后（或导入中止时）：清单中缺失的文件改为未下载，按实际文件重新统计并写入元数据
+async fn finish_project(project: ProjectImport) -> Result<ImportedProject, String> {
+    let Some(mut metadata) = project.metadata.filter(|_| project.accept) else {
+        return Ok(project.report);
+    };
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let dir = get_cache_dir(&metadata.project_id);
+
+    let missing = manifest_missing_files(&dir, true).await?;
+    let sizes = collect_cached_sizes(&dir).await?;
+
+    metadata.file_count = sizes.len() as i64;
+    metadata.total_size_bytes = sizes.iter().map(|(_, size)| *size as i64).sum();
+
+    // 有文件未能导入时标记为未完成，联网后重新下载会补齐
+    if !missing.is_empty() || !project.report.rejected.is_empty() {
+        metadata.status = STATUS_FAILED.to_string();
+    }
+
+    upsert_cached_project(storage.pool(), &metadata).await?;
+
+    invalidate_memory_cache(&metadata.project_id);
+
+    Ok(project.report)
+}
+
+async fn import_entries<R: AsyncRead + Unpin>(
+    input: &mut R,
+    policy: OverwritePolicy,
+    job: &TransferJob,
+    projects: &mut Vec<ImportedProject>,
+) -> Result<bool, String> {
+    let mut current: Option<ProjectImport> = None;
+
+    let result = loop {
+        if job.cancelled() {
+            break Ok(true);
+        }
+
+        let header = match read_header(input).await {
+            Ok(Some(header)) => header,
+            Ok(None) => break Ok(false),
+            Err(err) => break Err(err),
+        };
+
+        if header.kind == EntryKind::Metadata {
+            if let Some(project) = current.take() {
+                projects.push(finish_project(project).await?);
+                job.projects_done.fetch_add(1, Ordering::Relaxed);
+            }
+
+            match begin_project(input, &header, policy, job).await {
+                Ok(project) => current = Some(project),
+                Err(err) => break Err(err),
+            }
+
+            continue;
+        }
+
+        let Some(project) = current
+            .as_mut()
+            .filter(|project| project.report.project_id == header.project_id)
+        else {
+            break Err("归档格式错误：记录不属于当前项目".to_string());
+        };
+
+        if !project.accept {
+            if let Err(err) = skip_entry(input, header.size, job).await {
+                break Err(err);
+            }
+            continue;
+        }
+
+        let dir = get_cache_dir(&project.report.project_id);
+
+        let step = match header.kind {
+            EntryKind::Manifest => match read_inline(input, header.size, job).await {
+                Ok(Some(data)) => fs::write(dir.join(MANIFEST_FILE), data)
+                    .await
+                    .map_err(|e| format!("写入缓存清单失败: {}", e)),
+                Ok(None) => {
+                    project.report.rejected.push(MANIFEST_FILE.to_string());
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            },
+            _ => {
+                let name = header.name.clone().unwrap_or_default();
+
+                if !is_safe_component(&name) || name == MANIFEST_FILE {
+                    project.report.rejected.push(name);
+                    skip_entry(input, header.size, job).await
+                } else {
+                    match import_file(input, &dir, &name, header.size, job).await {
+                        Ok(true) => {
+                            project.report.files_written += 1;
+                            Ok(())
+                        }
+                        Ok(false) => {
+                            tracing::warn!(project_id = %header.project_id, %name, "cache_transfer.import.file_rejected");
+                            project.report.rejected.push(name);
+                            Ok(())
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+            }
+        };
+
+        if let Err(err) = step {
+            break Err(err);
+        }
+    };
+
+    // 中止时已写入的部分同样整理元数据，保证缓存状态与实际文件一致
+    if let Some(project) = current.take() {
+        projects.push(finish_project(project).await?);
+        job.projects_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    result
+}
+
+async fn run_import(
+    job: &TransferJob,
+    payload: &ImportCachesReq,
+) -> Result<ImportCachesReport, AppError> {
+    let file = fs::File::open(&payload.src_path)
+        .await
+        .map_err(|e| format!("打开归档文件失败: {}", e))?;
+
+    let archive_len = file
+        .metadata()
+        .await
+        .map_err(|e| format!("读取归档文件信息失败: {}", e))?
+        .len();
+
+    job.total_bytes.store(archive_len, Ordering::Relaxed);
+
+    // 归档内容基本都是图片，按归档大小估算所需空间
+    if let Ok(available) = available_space() {
+        if available < archive_len {
+            return Err(insufficient_disk_error(archive_len, available));
+        }
+    }
+
+    let mut input = BufReader::new(file);
+
+    let mut magic = [0u8; 8];
+    input.read_exact(&mut magic).await.map_err(truncated)?;
+
+    if &magic != ARCHIVE_MAGIC {
+        return Err(AppError::Other("不是缓存归档文件".to_string()));
+    }
+
+    let version = input.read_u32_le().await.map_err(truncated)?;
+
+    if version != ARCHIVE_VERSION {
+        return Err(AppError::Other(format!(
+            "不支持的缓存归档版本: {}",
+            version
+        )));
+    }
+
+    let mut projects = Vec::new();
+
+    let (cancelled, error) =
+        match import_entries(&mut input, payload.overwrite_policy, job, &mut projects).await {
+            Ok(cancelled) => (cancelled, None),
+            Err(err) => (false, Some(err)),
+        };
+
+    Ok(ImportCachesReport {
+        projects,
+        cancelled,
+        error,
+    })
+}
+
+// 导入缓存归档；归档中途损坏或被取消时返回已导入部分的结果（而不是错误）
+#[tauri::command]
+pub async fn import_caches(payload: ImportCachesReq) -> Result<ImportCachesReport, AppError> {
+    tracing::info!(
+        src_path = %payload.src_path,
+        policy = ?payload.overwrite_policy,
+        "cache_transfer.import.start"
+    );
+
+    let mut defer = WarnDefer::new("cache_transfer.import");
+
+    let guard = claim_transfer(TransferKind::Import)?;
+
+    let report = run_import(&guard.job, &payload).await?;
+
+    tracing::info!(
+        projects = report.projects.len(),
+        rejected = report
+            .projects
+            .iter()
+            .map(|project| project.rejected.len())
+            .sum::<usize>(),
+        cancelled = report.cancelled,
+        error = ?report.error,
+        "cache_transfer.import.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+// 取消进行中的导出 / 导入（当前文件处理完后停止），返回是否存在该任务
+#[tauri::command]
+pub async fn cancel_cache_transfer() -> Result<bool, String> {
+    let current = TRANSFER_JOB
+        .lock()
+        .map_err(|_| "cache transfer lock poisoned".to_string())?;
+
+    match current.as_ref() {
+        Some(job) => {
+            job.cancel.store(true, Ordering::Relaxed);
+            tracing::info!(progress = ?job.progress(), "cache_transfer.cancel.ok");
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// 查询进行中的导出 / 导入进度；没有任务时返回 None
+#[tauri::command]
+pub async fn get_cache_transfer_progress() -> Result<Option<CacheTransferProgress>, String> {
+    let current = TRANSFER_JOB
+        .lock()
+        .map_err(|_| "cache transfer lock poisoned".to_string())?;
+
+    Ok(current.as_ref().map(|job| job.progress()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn job(kind: TransferKind) -> TransferJob {
+        TransferJob {
+            kind,
+            cancel: AtomicBool::new(false),
+            total_bytes: AtomicU64::new(0),
+            processed_bytes: AtomicU64::new(0),
+            projects_done: AtomicUsize::new(0),
+        }
+    }
+
+    fn metadata(cached_at: i64) -> CachedProjectMetadata {
+        CachedProjectMetadata {
+            project_id: "p1".to_string(),
+            project_name: "项目".to_string(),
+            status: "completed".to_string(),
+            file_count: 0,
+            total_size_bytes: 0,
+            cached_at,
+            remote_file_count: None,
+            missing_count: None,
+            remote_checked_at: None,
+            remote_deleted: false,
+        }
+    }
+
+    #[test]
+    fn only_single_plain_path_components_are_accepted() {
+        assert!(is_safe_component("0001.png"));
+        assert!(is_safe_component("project-1"));
+
+        for name in ["", ".", "..", "a/b", "a\\b", "c:", "x\0"] {
+            assert!(!is_safe_component(name), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn overwrite_policy_decides_replacement() {
+        let (older, newer) = (metadata(1), metadata(2));
+
+        assert!(!should_replace(
+            OverwritePolicy::SkipExisting,
+            &older,
+            &newer
+        ));
+        assert!(should_replace(
+            OverwritePolicy::ReplaceOlder,
+            &older,
+            &newer
+        ));
+        assert!(!should_replace(
+            OverwritePolicy::ReplaceOlder,
+            &newer,
+            &older
+        ));
+        assert!(!should_replace(
+            OverwritePolicy::ReplaceOlder,
+            &older,
+            &older
+        ));
+        assert!(should_replace(
+            OverwritePolicy::AlwaysReplace,
+            &newer,
+            &older
+        ));
+    }
+
+    #[tokio::test]
+    async fn inline_entries_round_trip_and_corruption_is_detected() {
+        let job = job(TransferKind::Export);
+        let mut archive = Vec::new();
+
+        write_inline_entry(&mut archive, EntryKind::Manifest, "p1", b"[1, 2]")
+            .await
+            .unwrap();
+        write_inline_entry(&mut archive, EntryKind::Metadata, "p1", b"{}")
+            .await
+            .unwrap();
+        archive.extend_from_slice(&0u32.to_le_bytes());
+
+        // 第二条记录的内容被改动
+        let corrupt_at = archive.len() - 4 - DIGEST_LEN - 1;
+        archive[corrupt_at] ^= 0xFF;
+
+        let mut input = Cursor::new(archive);
+
+        let header = read_header(&mut input).await.unwrap().unwrap();
+        assert_eq!(
+            (header.kind, header.project_id.as_str()),
+            (EntryKind::Manifest, "p1")
+        );
+        assert_eq!(
+            read_inline(&mut input, header.size, &job)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(&b"[1, 2]"[..])
+        );
+
+        let header = read_header(&mut input).await.unwrap().unwrap();
+        assert_eq!(
+            read_inline(&mut input, header.size, &job).await.unwrap(),
+            None
+        );
+
+        assert!(read_header(&mut input).await.unwrap().is_none());
+        assert_eq!(job.progress().processed_bytes, 8);
+    }
+
+    #[tokio::test]
+    async fn malformed_archives_are_errors() {
+        let mut long_header = Cursor::new((MAX_HEADER_LEN + 1).to_le_bytes().to_vec());
+        assert!(read_header(&mut long_header)
+            .await
+            .unwrap_err()
+            .contains("记录头过长"));
+
+        let mut truncated_header = Cursor::new([5u8, 0, 0, 0, b'{'].to_vec());
+        assert!(read_header(&mut truncated_header)
+            .await
+            .unwrap_err()
+            .contains("不完整"));
+
+        let mut oversized = Cursor::new(Vec::new());
+        assert!(read_inline(
+            &mut oversized,
+            MAX_INLINE_ENTRY + 1,
+            &job(TransferKind::Import)
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn files_are_copied_with_digests_and_bad_ones_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.png");
+        let content = vec![7u8; CHUNK_SIZE + 10];
+        std::fs::write(&source, &content).unwrap();
+
+        let export_job = job(TransferKind::Export);
+        let mut archive = Vec::new();
+
+        for _ in 0..2 {
+            write_file_entry(
+                &mut archive,
+                "p1",
+                "0001.png",
+                &source,
+                content.len() as u64,
+                &export_job,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            export_job.progress().processed_bytes,
+            2 * content.len() as u64
+        );
+
+        // 第二条的摘要被改动
+        let last = archive.len() - 1;
+        archive[last] ^= 0xFF;
+
+        let dest = dir.path().join("imported");
+        std::fs::create_dir(&dest).unwrap();
+
+        let import_job = job(TransferKind::Import);
+        let mut input = Cursor::new(archive);
+
+        for (name, expected) in [("a.png", true), ("b.png", false)] {
+            let header = read_header(&mut input).await.unwrap().unwrap();
+            assert_eq!(header.name.as_deref(), Some("0001.png"));
+
+            let accepted = import_file(&mut input, &dest, name, header.size, &import_job)
+                .await
+                .unwrap();
+            assert_eq!(accepted, expected);
+        }
+
+        assert_eq!(std::fs::read(dest.join("a.png")).unwrap(), content);
+        assert!(!dest.join("b.png").exists());
+        assert!(!dest.join(format!("b.png{}", IMPORT_TMP_SUFFIX)).exists());
+
+        // 导出过程中文件变短
+        std::fs::write(&source, b"short").unwrap();
+        let error = write_file_entry(&mut Vec::new(), "p1", "0001.png", &source, 100, &export_job)
+            .await
+            .unwrap_err();
+        assert!(error.contains("被修改"));
+    }
+
+    #[tokio::test]
+    async fn foreign_or_newer_archives_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let import = |name: &str, bytes: Vec<u8>| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+
+            async move {
+                run_import(
+                    &job(TransferKind::Import),
+                    &ImportCachesReq {
+                        src_path: path.to_string_lossy().to_string(),
+                        overwrite_policy: OverwritePolicy::default(),
+                    },
+                )
+                .await
+            }
+        };
+
+        let error = import("zip", b"PK\x03\x04 not an archive".to_vec())
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "不是缓存归档文件");
+
+        let mut newer = ARCHIVE_MAGIC.to_vec();
+        newer.extend_from_slice(&(ARCHIVE_VERSION + 1).to_le_bytes());
+        assert!(import("newer", newer)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("版本"));
+
+        // 只有结束标记的空归档
+        let mut empty = ARCHIVE_MAGIC.to_vec();
+        empty.extend_from_slice(&ARCHIVE_VERSION.to_le_bytes());
+        empty.extend_from_slice(&0u32.to_le_bytes());
+        let report = import("empty", empty).await.unwrap();
+        assert!(report.projects.is_empty() && !report.cancelled && report.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn only_one_transfer_runs_at_a_time() {
+        let guard = claim_transfer(TransferKind::Export).unwrap();
+        assert!(claim_transfer(TransferKind::Import).is_err());
+
+        let progress = get_cache_transfer_progress().await.unwrap().unwrap();
+        assert_eq!(progress.kind, TransferKind::Export);
+
+        assert!(cancel_cache_transfer().await.unwrap());
+        assert!(guard.job.cancelled());
+
+        drop(guard);
+        assert!(get_cache_transfer_progress().await.unwrap().is_none());
+        assert!(!cancel_cache_transfer().await.unwrap());
+        drop(claim_transfer(TransferKind::Import).unwrap());
+    }
+}
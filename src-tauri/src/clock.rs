@@ -0,0 +1,21 @@
+// 当前时间（Unix 秒）。各模块的时间戳统一从这里取，不再各自用 SystemTime 计算
+use time::OffsetDateTime;
+
+pub(crate) fn unix_now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_system_clock() {
+        let system = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        assert!((unix_now() - system).abs() <= 1);
+    }
+}
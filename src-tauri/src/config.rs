@@ -0,0 +1,740 @@
+// 应用配置：统一解析所有可调项，优先级为 环境变量（含 .env） > 设置表 > 内置默认值
+// 启动时从环境变量解析一次；存储就绪后合并设置表；可运行时调整的项修改后立即生效并通知前端
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::AppError;
+use crate::events::{emit_event, ConfigChanged};
+use crate::session;
+use crate::storage::{settings, LOCAL_STORAGE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Env,
+    Db,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigEntry {
+    pub key: &'static str,
+    pub value: String,
+    pub source: ConfigSource,
+    // false 表示只能通过环境变量设置，修改后需重启
+    pub runtime_tunable: bool,
+}
+
+// 环境变量名及其取值的规范化函数（返回 None 表示非法，忽略）
+type EnvReader = (&'static str, fn(&str) -> Option<String>);
+
+struct KeySpec {
+    key: &'static str,
+    env: &'static [EnvReader],
+    runtime_tunable: bool,
+    normalize: fn(&str) -> Option<String>,
+    default: fn() -> String,
+}
+
+fn normalize_non_empty(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+
+    (!raw.is_empty()).then(|| raw.to_string())
+}
+
+// 补全末尾的 '/'（否则 Url::join 会替换掉最后一段路径，如 /api/v1 + projs 得到 /api/projs），
+// 并校验为带主机名、不含查询参数与片段的 http / https URL
+fn normalize_base_url(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+
+    if raw.is_empty() {
+        return None;
+    }
+
+    let normalized = if raw.ends_with('/') {
+        raw.to_string()
+    } else {
+        format!("{}/", raw)
+    };
+
+    let url = reqwest::Url::parse(&normalized).ok()?;
+
+    let valid = matches!(url.scheme(), "http" | "https")
+        && url.host_str().is_some()
+        && url.query().is_none()
+        && url.fragment().is_none();
+
+    valid.then_some(normalized)
+}
+
+// 兼容旧的 MOETRAN_URL（只含域名，不含 /v1）
+fn normalize_legacy_moetran_url(raw: &str) -> Option<String> {
+    normalize_base_url(&format!("{}/v1/", raw.trim().trim_end_matches('/')))
+}
+
+fn normalize_positive_int(raw: &str) -> Option<String> {
+    raw.trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|value| *value > 0)
+        .map(|value| value.to_string())
+}
+
+fn normalize_bool(raw: &str) -> Option<String> {
+    match raw.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some("true".to_string()),
+        "0" | "false" | "no" | "off" => Some("false".to_string()),
+        _ => None,
+    }
+}
+
+// 未指定时：RUST_LOG 含 debug 则使用本地 PopRaKo，否则使用线上地址
+fn default_poprako_api_base() -> String {
+    let use_local = std::env::var("RUST_LOG")
+        .map(|v| v.to_lowercase().contains("debug"))
+        .unwrap_or(false);
+
+    if use_local {
+        "http://127.0.0.1:8080/api/v1/".to_string()
+    } else {
+        "https://hatsu1ki-lb-site.com/api/v1/".to_string()
+    }
+}
+
+// 图片磁盘缓存默认上限：2 GB
+const DEFAULT_IMAGE_CACHE_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+const KEY_SPECS: &[KeySpec] = &[
+    KeySpec {
+        key: "app_dir",
+        env: &[("APP_DIR", normalize_non_empty)],
+        runtime_tunable: false,
+        normalize: normalize_non_empty,
+        default: || "./".to_string(),
+    },
+    KeySpec {
+        key: "moetran_api_base",
+        env: &[
+            ("MOETRAN_API_BASE", normalize_base_url),
+            ("MOETRAN_URL", normalize_legacy_moetran_url),
+        ],
+        runtime_tunable: true,
+        normalize: normalize_base_url,
+        default: || "https://api.moetran.com/v1/".to_string(),
+    },
+    KeySpec {
+        key: "poprako_api_base",
+        env: &[("POPRAKO_API_BASE", normalize_base_url)],
+        runtime_tunable: true,
+        normalize: normalize_base_url,
+        default: default_poprako_api_base,
+    },
+    KeySpec {
+        key: "draft_retention_days",
+        env: &[("DRAFT_RETENTION_DAYS", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "14".to_string(),
+    },
+    KeySpec {
+        key: "usage_retention_days",
+        env: &[("USAGE_RETENTION_DAYS", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "90".to_string(),
+    },
+    KeySpec {
+        key: "strict_dto_validation",
+        env: &[("STRICT_DTO_VALIDATION", normalize_bool)],
+        runtime_tunable: true,
+        normalize: normalize_bool,
+        // 调试构建默认开启
+        default: || cfg!(debug_assertions).to_string(),
+    },
+    KeySpec {
+        key: "offline_mode",
+        env: &[("OFFLINE_MODE", normalize_bool)],
+        runtime_tunable: true,
+        normalize: normalize_bool,
+        default: || "false".to_string(),
+    },
+    KeySpec {
+        key: "demo_mode",
+        env: &[("DEMO_MODE", normalize_bool)],
+        runtime_tunable: true,
+        normalize: normalize_bool,
+        default: || "false".to_string(),
+    },
+    KeySpec {
+        key: "enrichment_chunk_size",
+        env: &[("ENRICHMENT_CHUNK_SIZE", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "50".to_string(),
+    },
+    KeySpec {
+        key: "retry_window_minutes",
+        env: &[("RETRY_WINDOW_MINUTES", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "30".to_string(),
+    },
+    KeySpec {
+        key: "integrity_check_on_startup",
+        env: &[("INTEGRITY_CHECK_ON_STARTUP", normalize_bool)],
+        runtime_tunable: true,
+        normalize: normalize_bool,
+        default: || "false".to_string(),
+    },
+    KeySpec {
+        key: "image_memory_cache_mb",
+        env: &[("IMAGE_MEMORY_CACHE_MB", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "128".to_string(),
+    },
+    KeySpec {
+        key: "verify_submits",
+        env: &[("VERIFY_SUBMITS", normalize_bool)],
+        runtime_tunable: true,
+        normalize: normalize_bool,
+        default: || "false".to_string(),
+    },
+    KeySpec {
+        key: "project_snapshot_history",
+        env: &[("PROJECT_SNAPSHOT_HISTORY", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "10".to_string(),
+    },
+    KeySpec {
+        key: "member_info_ttl_secs",
+        env: &[("MEMBER_INFO_TTL_SECS", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || "300".to_string(),
+    },
+    KeySpec {
+        key: "image_cache_max_bytes",
+        env: &[("IMAGE_CACHE_MAX_BYTES", normalize_positive_int)],
+        runtime_tunable: true,
+        normalize: normalize_positive_int,
+        default: || DEFAULT_IMAGE_CACHE_MAX_BYTES.to_string(),
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub app_dir: PathBuf,
+    pub moetran_api_base: String,
+    pub poprako_api_base: String,
+    pub draft_retention_days: i64,
+    pub usage_retention_days: i64,
+    pub strict_dto_validation: bool,
+    // 开启后所有网络请求立即失败（AppError::Offline），只使用本地缓存
+    pub offline_mode: bool,
+    // 开启后请求由进程内的演示数据应答，不访问真实后端（通过 set_demo_mode 切换）
+    pub demo_mode: bool,
+    // PopRaKo projs/search 单次请求的项目 id 数上限，超出时分批请求
+    pub enrichment_chunk_size: usize,
+    // 失败写操作的重试 token 有效期
+    pub retry_window_minutes: i64,
+    // 启动时运行轻量的本地数据完整性检查（只报告，不修复）
+    pub integrity_check_on_startup: bool,
+    // 内存中已编码图片（整页与分块）的总大小上限
+    pub image_memory_cache_mb: usize,
+    // 每个项目保留的状态历史快照条数
+    pub project_snapshot_history: usize,
+    // 提交翻译几分钟后在后台确认其仍然存在（见 translation_verify）
+    pub verify_submits: bool,
+    // 当前用户在各 team 中成员信息（members/info）的进程内缓存有效期
+    pub member_info_ttl_secs: u64,
+    // 图片磁盘缓存（data/images）的总大小上限，超出时按最近读取时间淘汰整个项目
+    pub image_cache_max_bytes: u64,
+    entries: Vec<ConfigEntry>,
+}
+
+impl AppConfig {
+    fn value(&self, key: &str) -> &str {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .map(|entry| entry.value.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn entries(&self) -> &[ConfigEntry] {
+        &self.entries
+    }
+}
+
+// 按优先级解析全部配置项；设置表只对可运行时调整的项生效
+fn resolve(env: impl Fn(&str) -> Option<String>, db: &HashMap<String, String>) -> AppConfig {
+    let entries: Vec<ConfigEntry> = KEY_SPECS
+        .iter()
+        .map(|spec| {
+            let from_env = spec
+                .env
+                .iter()
+                .find_map(|(name, normalize)| env(name).and_then(|raw| normalize(&raw)));
+
+            let from_db = spec
+                .runtime_tunable
+                .then(|| db.get(spec.key).and_then(|raw| (spec.normalize)(raw)))
+                .flatten();
+
+            let (value, source) = match (from_env, from_db) {
+                (Some(value), _) => (value, ConfigSource::Env),
+                (None, Some(value)) => (value, ConfigSource::Db),
+                (None, None) => ((spec.default)(), ConfigSource::Default),
+            };
+
+            ConfigEntry {
+                key: spec.key,
+                value,
+                source,
+                runtime_tunable: spec.runtime_tunable,
+            }
+        })
+        .collect();
+
+    let mut config = AppConfig {
+        app_dir: PathBuf::new(),
+        moetran_api_base: String::new(),
+        poprako_api_base: String::new(),
+        draft_retention_days: 0,
+        usage_retention_days: 0,
+        strict_dto_validation: false,
+        offline_mode: false,
+        demo_mode: false,
+        enrichment_chunk_size: 0,
+        retry_window_minutes: 0,
+        integrity_check_on_startup: false,
+        image_memory_cache_mb: 0,
+        project_snapshot_history: 0,
+        verify_submits: false,
+        member_info_ttl_secs: 0,
+        image_cache_max_bytes: 0,
+        entries,
+    };
+
+    // 所有值均已规范化，解析不会失败；保险起见仍回退到默认值
+    config.app_dir = PathBuf::from(config.value("app_dir"));
+    config.moetran_api_base = config.value("moetran_api_base").to_string();
+    config.poprako_api_base = config.value("poprako_api_base").to_string();
+    config.draft_retention_days = config.value("draft_retention_days").parse().unwrap_or(14);
+    config.usage_retention_days = config.value("usage_retention_days").parse().unwrap_or(90);
+    config.strict_dto_validation = config.value("strict_dto_validation") == "true";
+    config.offline_mode = config.value("offline_mode") == "true";
+    config.demo_mode = config.value("demo_mode") == "true";
+    config.enrichment_chunk_size = config.value("enrichment_chunk_size").parse().unwrap_or(50);
+    config.retry_window_minutes = config.value("retry_window_minutes").parse().unwrap_or(30);
+    config.integrity_check_on_startup = config.value("integrity_check_on_startup") == "true";
+    config.image_memory_cache_mb = config.value("image_memory_cache_mb").parse().unwrap_or(128);
+    config.project_snapshot_history = config
+        .value("project_snapshot_history")
+        .parse()
+        .unwrap_or(10);
+    config.verify_submits = config.value("verify_submits") == "true";
+    config.member_info_ttl_secs = config.value("member_info_ttl_secs").parse().unwrap_or(300);
+    config.image_cache_max_bytes = config
+        .value("image_cache_max_bytes")
+        .parse()
+        .unwrap_or(DEFAULT_IMAGE_CACHE_MAX_BYTES);
+
+    config
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+static CONFIG: LazyLock<RwLock<Arc<AppConfig>>> = LazyLock::new(|| {
+    // .env 为可选项，不存在时直接使用进程环境变量
+    if let Err(err) = dotenvy::dotenv() {
+        tracing::debug!(%err, "No .env file loaded");
+    }
+
+    RwLock::new(Arc::new(resolve(env_var, &HashMap::new())))
+});
+
+// 获取当前生效的配置
+pub fn config() -> Arc<AppConfig> {
+    match CONFIG.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+// 测试用：修改当前配置（如把 API 地址指向本地 mock 服务），不经过环境变量与设置表
+#[cfg(test)]
+pub(crate) fn update_for_test(update: impl FnOnce(&mut AppConfig)) {
+    let mut guard = CONFIG
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut next = (**guard).clone();
+    update(&mut next);
+
+    *guard = Arc::new(next);
+}
+
+// 启动时调用：加载 .env 并按环境变量解析配置
+pub fn init() {
+    LazyLock::force(&CONFIG);
+}
+
+// 重新读取设置表并更新配置，返回值是否发生变化
+pub async fn reload_from_db() -> Result<bool, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let db = settings::list_settings(storage.pool()).await?;
+
+    let next = resolve(env_var, &db);
+
+    let mut guard = CONFIG
+        .write()
+        .map_err(|_| "config lock poisoned".to_string())?;
+
+    let changed = guard
+        .entries
+        .iter()
+        .zip(next.entries.iter())
+        .any(|(a, b)| a.value != b.value || a.source != b.source);
+
+    for entry in next.entries() {
+        tracing::info!(key = entry.key, value = %entry.value, source = ?entry.source, "config.resolved");
+    }
+
+    *guard = Arc::new(next);
+
+    Ok(changed)
+}
+
+#[tauri::command]
+pub async fn get_effective_config() -> Result<Vec<ConfigEntry>, String> {
+    Ok(config().entries().to_vec())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetConfigValueReq {
+    pub key: String,
+    // None 表示删除设置表中的值，恢复为环境变量或默认值
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+// 写入（或删除）可运行时调整的配置项并重新加载，返回生效值是否发生变化
+pub(crate) async fn set_runtime_value(key: &str, value: Option<&str>) -> Result<bool, String> {
+    let spec = KEY_SPECS
+        .iter()
+        .find(|spec| spec.key == key)
+        .ok_or_else(|| format!("未知的配置项: {}", key))?;
+
+    if !spec.runtime_tunable {
+        return Err(format!("配置项 {} 只能通过环境变量设置", spec.key));
+    }
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    match value {
+        Some(raw) => {
+            let value =
+                (spec.normalize)(raw).ok_or_else(|| format!("配置项 {} 的值非法", spec.key))?;
+
+            settings::save_setting(storage.pool(), spec.key, &value).await?;
+        }
+        None => settings::delete_setting(storage.pool(), spec.key).await?,
+    }
+
+    reload_from_db().await
+}
+
+// 修改可运行时调整的配置项，生效后向前端发送 "config-changed" 事件
+#[tauri::command]
+pub async fn set_config_value(
+    app: AppHandle,
+    payload: SetConfigValueReq,
+) -> Result<Vec<ConfigEntry>, String> {
+    tracing::info!(key = %payload.key, "config.set.start");
+
+    // 切换演示模式还需替换请求提供者与 token，只能通过 set_demo_mode
+    if payload.key == "demo_mode" {
+        return Err("请通过 set_demo_mode 切换演示模式".to_string());
+    }
+
+    let changed = set_runtime_value(&payload.key, payload.value.as_deref()).await?;
+
+    let entries = config().entries().to_vec();
+
+    if changed {
+        emit_event(&app, ConfigChanged(entries.clone()));
+    }
+
+    tracing::info!(key = %payload.key, changed, "config.set.ok");
+
+    Ok(entries)
+}
+
+// 可运行时调整的配置项（偏好配置文件可导入导出的范围）
+pub(crate) fn runtime_tunable_keys() -> Vec<&'static str> {
+    KEY_SPECS
+        .iter()
+        .filter(|spec| spec.runtime_tunable)
+        .map(|spec| spec.key)
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiEndpointsReply {
+    pub moetran_api_base: ConfigEntry,
+    pub poprako_api_base: ConfigEntry,
+}
+
+fn api_endpoints() -> ApiEndpointsReply {
+    let config = config();
+    let entry = |key: &str| {
+        config
+            .entries()
+            .iter()
+            .find(|entry| entry.key == key)
+            .cloned()
+            .expect("API base keys are always resolved")
+    };
+
+    ApiEndpointsReply {
+        moetran_api_base: entry("moetran_api_base"),
+        poprako_api_base: entry("poprako_api_base"),
+    }
+}
+
+// 当前生效的 Moetran / PopRaKo API 地址及其来源
+#[tauri::command]
+pub async fn get_api_endpoints() -> Result<ApiEndpointsReply, AppError> {
+    Ok(api_endpoints())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetApiEndpointsReq {
+    // None 表示不修改，空字符串表示恢复为环境变量或默认值
+    #[serde(default)]
+    pub moetran_api_base: Option<String>,
+    #[serde(default)]
+    pub poprako_api_base: Option<String>,
+}
+
+// 修改 API 地址：两个地址都校验通过后才写入，下一次请求即使用新地址（http 层按新地址重建客户端）。
+// 环境变量指定的地址优先，设置表中的值不会生效（见返回的 source）
+#[tauri::command]
+pub async fn set_api_endpoints(
+    app: AppHandle,
+    payload: SetApiEndpointsReq,
+) -> Result<ApiEndpointsReply, AppError> {
+    tracing::info!(
+        moetran = ?payload.moetran_api_base,
+        poprako = ?payload.poprako_api_base,
+        "config.api_endpoints.set.start"
+    );
+
+    let mut updates = Vec::new();
+
+    for (key, raw) in [
+        ("moetran_api_base", &payload.moetran_api_base),
+        ("poprako_api_base", &payload.poprako_api_base),
+    ] {
+        let Some(raw) = raw.as_deref().map(str::trim) else {
+            continue;
+        };
+
+        if raw.is_empty() {
+            updates.push((key, None));
+            continue;
+        }
+
+        let value = normalize_base_url(raw).ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "{} 不是合法的 API 地址（需为 http / https 地址，不含查询参数）: {}",
+                key, raw
+            ))
+        })?;
+
+        updates.push((key, Some(value)));
+    }
+
+    let mut changed = false;
+
+    for (key, value) in updates {
+        changed |= set_runtime_value(key, value.as_deref()).await?;
+    }
+
+    if changed {
+        // 换了后端即可能换了账号，重新检查会话身份
+        session::reset_identity();
+
+        emit_event(&app, ConfigChanged(config().entries().to_vec()));
+    }
+
+    let reply = api_endpoints();
+
+    tracing::info!(
+        moetran = %reply.moetran_api_base.value,
+        poprako = %reply.poprako_api_base.value,
+        changed,
+        "config.api_endpoints.set.ok"
+    );
+
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_urls_must_be_http_and_end_with_a_slash() {
+        assert_eq!(
+            normalize_base_url(" https://api.example.com/v1 ").as_deref(),
+            Some("https://api.example.com/v1/")
+        );
+        assert_eq!(
+            normalize_base_url("http://127.0.0.1:8080/api/v1/").as_deref(),
+            Some("http://127.0.0.1:8080/api/v1/")
+        );
+
+        for invalid in [
+            "",
+            "ftp://example.com/v1/",
+            "file:///tmp/api/",
+            "api.example.com/v1",
+            "https://example.com/v1/?token=1",
+            "https://example.com/v1/#top",
+        ] {
+            assert_eq!(normalize_base_url(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn legacy_moetran_url_gets_the_api_path() {
+        assert_eq!(
+            normalize_legacy_moetran_url("https://moetran.example.com/").as_deref(),
+            Some("https://moetran.example.com/v1/")
+        );
+    }
+
+    #[test]
+    fn normalized_base_keeps_its_path_when_joined() {
+        let base = normalize_base_url("https://example.com/api/v1").unwrap();
+        let url = reqwest::Url::parse(&base)
+            .unwrap()
+            .join("projects/p1")
+            .unwrap();
+
+        assert_eq!(url.as_str(), "https://example.com/api/v1/projects/p1");
+    }
+
+    fn entry<'a>(config: &'a AppConfig, key: &str) -> &'a ConfigEntry {
+        config
+            .entries()
+            .iter()
+            .find(|entry| entry.key == key)
+            .unwrap()
+    }
+
+    #[test]
+    fn env_beats_settings_which_beat_defaults() {
+        let env = |name: &str| match name {
+            "DRAFT_RETENTION_DAYS" => Some(" 30 ".to_string()),
+            // 非法值忽略，继续看设置表
+            "ENRICHMENT_CHUNK_SIZE" => Some("-5".to_string()),
+            "OFFLINE_MODE" => Some("yes".to_string()),
+            _ => None,
+        };
+        let db: HashMap<String, String> = [
+            ("draft_retention_days", "7"),
+            ("enrichment_chunk_size", "20"),
+            ("retry_window_minutes", "not a number"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+        let config = resolve(env, &db);
+
+        assert_eq!(config.draft_retention_days, 30);
+        assert_eq!(
+            entry(&config, "draft_retention_days").source,
+            ConfigSource::Env
+        );
+
+        assert_eq!(config.enrichment_chunk_size, 20);
+        assert_eq!(
+            entry(&config, "enrichment_chunk_size").source,
+            ConfigSource::Db
+        );
+
+        assert_eq!(config.retry_window_minutes, 30);
+        assert_eq!(
+            entry(&config, "retry_window_minutes").source,
+            ConfigSource::Default
+        );
+
+        assert!(config.offline_mode);
+        assert_eq!(entry(&config, "offline_mode").value, "true");
+    }
+
+    #[test]
+    fn settings_do_not_override_env_only_keys() {
+        let db: HashMap<String, String> =
+            [("app_dir".to_string(), "/tmp/elsewhere".to_string())].into();
+
+        let config = resolve(|_| None, &db);
+
+        assert_eq!(config.app_dir, PathBuf::from("./"));
+        assert_eq!(entry(&config, "app_dir").source, ConfigSource::Default);
+        assert!(!entry(&config, "app_dir").runtime_tunable);
+    }
+
+    #[test]
+    fn legacy_moetran_url_is_used_when_api_base_is_unset() {
+        let legacy =
+            |name: &str| (name == "MOETRAN_URL").then(|| "https://mt.example.com".to_string());
+        assert_eq!(
+            resolve(legacy, &HashMap::new()).moetran_api_base,
+            "https://mt.example.com/v1/"
+        );
+
+        let both = |name: &str| match name {
+            "MOETRAN_API_BASE" => Some("https://api.example.com/v2".to_string()),
+            "MOETRAN_URL" => Some("https://mt.example.com".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            resolve(both, &HashMap::new()).moetran_api_base,
+            "https://api.example.com/v2/"
+        );
+    }
+
+    #[test]
+    fn scalar_values_are_normalized() {
+        assert_eq!(normalize_bool(" ON ").as_deref(), Some("true"));
+        assert_eq!(normalize_bool("0").as_deref(), Some("false"));
+        assert_eq!(normalize_bool("maybe"), None);
+
+        assert_eq!(normalize_positive_int(" 42 ").as_deref(), Some("42"));
+        assert_eq!(normalize_positive_int("0"), None);
+        assert_eq!(normalize_positive_int("1.5"), None);
+
+        assert_eq!(normalize_non_empty("  "), None);
+        assert_eq!(normalize_non_empty(" x ").as_deref(), Some("x"));
+    }
+}
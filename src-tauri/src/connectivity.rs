@@ -0,0 +1,400 @@
+// 后端连通性状态（供前端离线横幅判断使用）。状态主要来自各请求的结果（observe），
+// 另有一个后台检查定时刷新 PopRaKo 健康状态，空闲时也能发现服务端恢复或降级
+use std::{sync::RwLock, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{
+    config::{config, set_runtime_value},
+    error::AppError,
+    events::{emit_event, OfflineModeChanged, PoprakoHealthChanged},
+    poprako_health::{self, HealthState},
+};
+
+// 后台检查的间隔
+const CONNECTIVITY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+pub enum Backend {
+    Moetran,
+    Poprako,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BackendStatus {
+    // 尚未发出过请求
+    Unknown,
+    Online,
+    // 后端返回维护页 / 网关错误页：服务宕机，而不是某个请求本身出错
+    Down {
+        status: u16,
+        retry_after: Option<u64>,
+        excerpt: String,
+    },
+    // 请求未能送达（断网、DNS 失败、超时等）
+    Unreachable {
+        reason: String,
+    },
+}
+
+static MOETRAN_STATUS: RwLock<BackendStatus> = RwLock::new(BackendStatus::Unknown);
+
+static POPRAKO_STATUS: RwLock<BackendStatus> = RwLock::new(BackendStatus::Unknown);
+
+fn status_slot(backend: Backend) -> &'static RwLock<BackendStatus> {
+    match backend {
+        Backend::Moetran => &MOETRAN_STATUS,
+        Backend::Poprako => &POPRAKO_STATUS,
+    }
+}
+
+// 根据一次请求的结果更新对应后端的连通性状态，并原样返回结果
+pub(crate) fn observe<R>(backend: Backend, result: Result<R, AppError>) -> Result<R, AppError> {
+    let next = match &result {
+        // 4xx / 解析失败等说明服务端可达
+        Ok(_)
+        | Err(
+            AppError::Other(_)
+            | AppError::Validation(_)
+            | AppError::AuthExpired { .. }
+            | AppError::PoprakoBusiness { .. }
+            | AppError::MoetranHttp { .. }
+            | AppError::PoprakoHttp { .. },
+        ) => BackendStatus::Online,
+        Err(AppError::ServiceUnavailable {
+            status,
+            retry_after,
+            excerpt,
+        }) => BackendStatus::Down {
+            status: *status,
+            retry_after: *retry_after,
+            excerpt: excerpt.clone(),
+        },
+        Err(AppError::Network(reason)) => BackendStatus::Unreachable {
+            reason: reason.clone(),
+        },
+        // 离线模式下请求未发出、或因整体时限中止，无从判断后端状态
+        Err(AppError::Offline | AppError::DeadlineExceeded) => return result,
+        // HTTP 层不会产生以下错误
+        Err(
+            AppError::Storage(_)
+            | AppError::InvalidInput(_)
+            | AppError::NotFound(_)
+            | AppError::Conflict(_)
+            | AppError::DeleteBlocked(_)
+            | AppError::DuplicateName(_)
+            | AppError::InsufficientDisk { .. }
+            | AppError::AlreadyInProgress { .. }
+            | AppError::Retryable { .. }
+            | AppError::Context { .. },
+        ) => return result,
+    };
+
+    if let Ok(mut guard) = status_slot(backend).write() {
+        if *guard != next {
+            tracing::info!(?backend, ?next, "connectivity.status.changed");
+        }
+
+        *guard = next;
+    }
+
+    result
+}
+
+pub(crate) fn current_status(backend: Backend) -> BackendStatus {
+    status_slot(backend)
+        .read()
+        .map(|guard| guard.clone())
+        .unwrap_or(BackendStatus::Unknown)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectivityReply {
+    pub moetran: BackendStatus,
+    pub poprako: BackendStatus,
+    // PopRaKo 自报的健康状态：poprako 为 online 而此项为 degraded 时是服务端问题，而非本机网络
+    pub poprako_health: HealthState,
+    // 用户手动开启的离线模式（此时上面两项为开启前最后一次观测的状态）
+    pub offline_mode: bool,
+}
+
+// 获取最近一次观测到的后端连通性（前端据此显示离线 / 维护横幅）
+#[tauri::command]
+pub async fn get_connectivity_status() -> Result<ConnectivityReply, String> {
+    let reply = ConnectivityReply {
+        moetran: current_status(Backend::Moetran),
+        poprako: current_status(Backend::Poprako),
+        poprako_health: poprako_health::current_state(),
+        offline_mode: config().offline_mode,
+    };
+
+    tracing::debug!(?reply, "connectivity.status.get");
+
+    Ok(reply)
+}
+
+// 后台连通性检查：定时刷新 PopRaKo 健康状态（健康检查请求本身也经 observe 更新 PopRaKo 的连通性），
+// 变化时发送 "poprako-health-changed"
+pub(crate) fn spawn_connectivity_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Some(health) = poprako_health::refresh_if_due().await {
+                emit_event(&app, PoprakoHealthChanged(health));
+            }
+
+            tokio::time::sleep(CONNECTIVITY_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetOfflineModeReq {
+    pub enabled: bool,
+}
+
+// 开关离线模式：开启后所有网络请求立即失败，读操作尽量使用本地缓存，PopRaKo 写操作进入重试队列。
+// 设置持久化到本地，切换后发送 "offline-mode-changed" 事件供各视图刷新
+#[tauri::command]
+pub async fn set_offline_mode(
+    app: AppHandle,
+    payload: SetOfflineModeReq,
+) -> Result<ConnectivityReply, String> {
+    tracing::info!(
+        enabled = payload.enabled,
+        "connectivity.offline_mode.set.start"
+    );
+
+    let value = if payload.enabled { "true" } else { "false" };
+
+    let changed = set_runtime_value("offline_mode", Some(value)).await?;
+
+    if changed {
+        let event = OfflineModeChanged {
+            enabled: payload.enabled,
+        };
+
+        emit_event(&app, event);
+    }
+
+    tracing::info!(
+        enabled = payload.enabled,
+        changed,
+        "connectivity.offline_mode.set.ok"
+    );
+
+    get_connectivity_status().await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{any, method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        config,
+        member::{fetch_all_members, get_all_members, GetAllMembersReq},
+        project::{
+            get_team_projects_enriched, load_page_sources, snapshot_page_sources,
+            GetPageSourcesReq, GetTeamProjectsEnrichedReq,
+        },
+        test_support::{local_storage, MockBackends},
+    };
+
+    fn observe_err(error: AppError) {
+        let _ = observe::<()>(Backend::Poprako, Err(error));
+    }
+
+    #[tokio::test]
+    async fn request_outcomes_drive_backend_status() {
+        // 状态是进程级的，持有 MockBackends 避免与其他请求测试交错
+        let _backends = MockBackends::start().await;
+
+        observe_err(AppError::ServiceUnavailable {
+            status: 503,
+            retry_after: Some(30),
+            excerpt: "维护中".to_string(),
+        });
+        assert_eq!(
+            serde_json::to_value(current_status(Backend::Poprako)).unwrap(),
+            json!({ "state": "down", "status": 503, "retry_after": 30, "excerpt": "维护中" })
+        );
+
+        // 离线模式与整体时限中止不改变已有判断
+        observe_err(AppError::Offline);
+        observe_err(AppError::DeadlineExceeded);
+        assert!(matches!(
+            current_status(Backend::Poprako),
+            BackendStatus::Down { status: 503, .. }
+        ));
+
+        observe_err(AppError::Network("dns error".to_string()));
+        assert_eq!(
+            current_status(Backend::Poprako),
+            BackendStatus::Unreachable {
+                reason: "dns error".to_string()
+            }
+        );
+
+        // 4xx 说明服务端可达
+        observe_err(AppError::PoprakoHttp {
+            status: 404,
+            body: String::new(),
+        });
+        assert_eq!(current_status(Backend::Poprako), BackendStatus::Online);
+
+        observe_err(AppError::Network("reset".to_string()));
+        assert_eq!(observe(Backend::Poprako, Ok(7)).unwrap(), 7);
+        assert_eq!(current_status(Backend::Poprako), BackendStatus::Online);
+    }
+
+    async fn mount_json(
+        server: &wiremock::MockServer,
+        http_method: &str,
+        route: &str,
+        body: serde_json::Value,
+    ) {
+        Mock::given(method(http_method))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    fn team_req() -> GetTeamProjectsEnrichedReq {
+        GetTeamProjectsEnrichedReq {
+            team_id: "team-offline".into(),
+            page: 1,
+            limit: 20,
+            deadline_ms: None,
+        }
+    }
+
+    fn sources_req() -> GetPageSourcesReq {
+        GetPageSourcesReq {
+            file_id: "offline-file".into(),
+            target_id: "offline-target".into(),
+            reading_direction: None,
+            allow_stale: false,
+            mark_viewed: false,
+            project_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_mode_serves_caches_without_any_request() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+
+        // 在线时拉取一次，写入各处缓存
+        mount_json(
+            &backends.moetran,
+            "GET",
+            "/v1/teams/team-offline/projects",
+            json!([{
+                "id": "off-p1",
+                "name": "离线项目",
+                "source_count": 3,
+                "translated_source_count": 1,
+                "checked_source_count": 0,
+                "team": { "id": "team-offline", "avatar": "", "has_avatar": false, "name": "汉化组" },
+                "project_set": { "id": "default", "name": "默认项目集" },
+            }]),
+        )
+        .await;
+        mount_json(
+            &backends.poprako,
+            "POST",
+            "/v1/projs/search",
+            json!({ "code": 200, "data": [], "message": null }),
+        )
+        .await;
+        mount_json(
+            &backends.moetran,
+            "GET",
+            "/v1/files/offline-file/sources",
+            json!([{
+                "id": "off-s1",
+                "x": 0.5,
+                "y": 0.5,
+                "position_type": 1,
+                "my_translation": null,
+                "translations": [],
+            }]),
+        )
+        .await;
+        mount_json(
+            &backends.poprako,
+            "POST",
+            "/v1/members/search",
+            json!({
+                "code": 200,
+                "data": [{
+                    "member_id": "off-m1",
+                    "user_id": "off-u1",
+                    "username": "akira",
+                    "is_admin": false,
+                    "is_translator": true,
+                    "is_proofreader": false,
+                    "is_typesetter": false,
+                    "is_principal": false,
+                }],
+                "message": null,
+                "total": 1,
+            }),
+        )
+        .await;
+
+        get_team_projects_enriched(team_req()).await.unwrap();
+        load_page_sources(&sources_req()).await.unwrap();
+        fetch_all_members("team-offline").await.unwrap();
+
+        // 开启离线模式后任何请求都不应到达服务端
+        for server in [&backends.moetran, &backends.poprako] {
+            server.reset().await;
+
+            Mock::given(any())
+                .respond_with(ResponseTemplate::new(500))
+                .expect(0)
+                .mount(server)
+                .await;
+        }
+
+        config::update_for_test(|config| config.offline_mode = true);
+
+        let projects = get_team_projects_enriched(team_req()).await;
+        let sources = snapshot_page_sources(&sources_req()).await;
+        let directory = fetch_all_members("team-offline").await;
+        let all_members = get_all_members(GetAllMembersReq {
+            team_id: "team-offline".into(),
+        })
+        .await;
+        let uncached = fetch_all_members("team-never-fetched").await;
+
+        config::update_for_test(|config| config.offline_mode = false);
+
+        let projects = projects.unwrap();
+        assert_eq!(projects.items.len(), 1);
+        assert_eq!(projects.items[0].stale, Some(true));
+
+        let sources = sources.unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].stale, Some(true));
+
+        assert_eq!(directory.unwrap()[0].username, "akira");
+
+        let all_members = all_members.unwrap();
+        assert!(all_members.stale);
+        assert_eq!(all_members.items.len(), 1);
+
+        assert!(uncached.unwrap_err().starts_with("offline_mode"));
+
+        backends.moetran.verify().await;
+        backends.poprako.verify().await;
+    }
+}
@@ -0,0 +1,312 @@
+// 项目贡献统计：遍历项目（某 target）下全部 source，按翻译作者 / 校对者计数，
+// 并与 PopRaKo 成员角色对照，区分指派角色内的贡献与临时帮忙；用于生成汉化名单
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    defer::WarnDefer,
+    http::moetran_get,
+    project::{
+        fetch_poprako_proj, get_project_files, GetProjectFilesReq, MemberRoles, MoetranSource,
+        MoetranUserBrief,
+    },
+};
+
+// 同时拉取 sources 的文件数上限
+const CONTRIBUTION_FETCH_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContributorStat {
+    // None 表示作者信息缺失（匿名或旧数据）的汇总桶
+    pub user_id: Option<String>,
+    pub name: String,
+    // 提交的翻译数
+    pub translations: u64,
+    // 其中被选定的翻译数
+    pub selected_translations: u64,
+    // 作为校对者修改过的翻译数
+    pub proofreads: u64,
+    // PopRaKo 中的角色；None 表示不是项目成员（或 PopRaKo 不可达）
+    pub roles: Option<MemberRoles>,
+    // 存在超出所分配角色的贡献（如未分配翻译却提交了翻译）
+    pub drive_by: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContributionsReport {
+    pub project_id: String,
+    pub target_id: String,
+    pub file_count: usize,
+    pub source_count: usize,
+    // 按翻译数、校对数倒序；unknown 桶（若有）排在最后
+    pub contributors: Vec<ContributorStat>,
+    // Moetran 不记录嵌字，名单中的嵌字仅来自 PopRaKo 角色
+    pub typesetters: Vec<String>,
+    // PopRaKo 成员角色是否成功获取
+    pub roles_known: bool,
+}
+
+impl ContributionsReport {
+    fn names_where(&self, pred: impl Fn(&ContributorStat) -> bool) -> String {
+        self.contributors
+            .iter()
+            .filter(|c| c.user_id.is_some() && pred(c))
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join("、")
+    }
+
+    // 按模板渲染名单文本，支持 {translators} / {proofreaders} / {typesetters} 占位符
+    pub fn format_credits(&self, template: &str) -> String {
+        template
+            .replace("{translators}", &self.names_where(|c| c.translations > 0))
+            .replace("{proofreaders}", &self.names_where(|c| c.proofreads > 0))
+            .replace("{typesetters}", &self.typesetters.join("、"))
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    name: String,
+    translations: u64,
+    selected_translations: u64,
+    proofreads: u64,
+}
+
+// 作者信息缺失（或 id 为空）时归入 unknown 桶（key 为 None）
+fn author_key(user: Option<&MoetranUserBrief>) -> (Option<String>, String) {
+    match user {
+        Some(user) if !user.id.is_empty() => (Some(user.id.to_string()), user.name.clone()),
+        _ => (None, "未知".to_string()),
+    }
+}
+
+fn tally_sources(tallies: &mut HashMap<Option<String>, Tally>, sources: &[MoetranSource]) {
+    for source in sources {
+        // my_translation 通常也出现在 translations 中，按 id 去重
+        let mut seen = HashSet::new();
+
+        for translation in source
+            .translations
+            .iter()
+            .chain(source.my_translation.iter())
+        {
+            if !seen.insert(translation.id.as_str()) {
+                continue;
+            }
+
+            let (key, name) = author_key(translation.user.as_ref());
+            let entry = tallies.entry(key).or_insert_with(|| Tally {
+                name,
+                ..Default::default()
+            });
+            entry.translations += 1;
+            if translation.selected {
+                entry.selected_translations += 1;
+            }
+
+            let proofread = translation
+                .proofread_content
+                .as_deref()
+                .is_some_and(|content| !content.trim().is_empty());
+
+            if proofread {
+                let (key, name) = author_key(translation.proofreader.as_ref());
+                let entry = tallies.entry(key).or_insert_with(|| Tally {
+                    name,
+                    ..Default::default()
+                });
+                entry.proofreads += 1;
+            }
+        }
+    }
+}
+
+pub(crate) async fn fetch_file_sources(
+    file_id: String,
+    target_id: String,
+) -> Result<Vec<MoetranSource>, String> {
+    let endpoint = format!("files/{}/sources", file_id);
+
+    let mut query = HashMap::new();
+    query.insert("target_id", target_id);
+    query.insert("paging", "false".to_string());
+
+    moetran_get::<Vec<MoetranSource>>(&endpoint, Some(&query))
+        .await
+        .map_err(|err| format!("获取文件 {} 的 sources 失败: {}", file_id, err))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetProjectContributionsReq {
+    pub project_id: String,
+    pub target_id: String,
+}
+
+pub async fn build_contributions_report(
+    project_id: &str,
+    target_id: &str,
+) -> Result<ContributionsReport, String> {
+    let files = get_project_files(GetProjectFilesReq {
+        project_id: project_id.into(),
+        target_id: Some(target_id.into()),
+        sort: None,
+    })
+    .await?;
+
+    let semaphore = Arc::new(Semaphore::new(CONTRIBUTION_FETCH_CONCURRENCY));
+    let mut set = JoinSet::new();
+
+    for file in &files {
+        let semaphore = semaphore.clone();
+        let file_id = file.id.clone();
+        let target_id = target_id.to_string();
+
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            fetch_file_sources(file_id.into(), target_id).await
+        });
+    }
+
+    let mut tallies: HashMap<Option<String>, Tally> = HashMap::new();
+    let mut source_count = 0;
+
+    while let Some(joined) = set.join_next().await {
+        let sources = joined.map_err(|err| format!("统计任务异常: {}", err))??;
+
+        source_count += sources.len();
+        tally_sources(&mut tallies, &sources);
+    }
+
+    // PopRaKo 不可达时仍返回 Moetran 统计，只是无法区分角色
+    let members = match fetch_poprako_proj(project_id).await {
+        Ok(proj) => proj.and_then(|proj| proj.members),
+        Err(err) => {
+            tracing::warn!(%project_id, error = %err, "contributions.roles.fetch.failed");
+            None
+        }
+    };
+
+    let roles_by_user: HashMap<&str, MemberRoles> = members
+        .iter()
+        .flatten()
+        .map(|m| {
+            (
+                m.user_id.as_str(),
+                MemberRoles {
+                    is_translator: m.is_translator,
+                    is_proofreader: m.is_proofreader,
+                    is_typesetter: m.is_typesetter,
+                    is_redrawer: m.is_redrawer,
+                },
+            )
+        })
+        .collect();
+
+    let mut contributors: Vec<ContributorStat> = tallies
+        .into_iter()
+        .map(|(user_id, tally)| {
+            let roles = user_id
+                .as_deref()
+                .and_then(|id| roles_by_user.get(id))
+                .cloned();
+
+            let drive_by = user_id.is_some()
+                && members.is_some()
+                && ((tally.translations > 0 && !roles.as_ref().is_some_and(|r| r.is_translator))
+                    || (tally.proofreads > 0 && !roles.as_ref().is_some_and(|r| r.is_proofreader)));
+
+            ContributorStat {
+                user_id,
+                name: tally.name,
+                translations: tally.translations,
+                selected_translations: tally.selected_translations,
+                proofreads: tally.proofreads,
+                roles,
+                drive_by,
+            }
+        })
+        .collect();
+
+    contributors.sort_by(|a, b| {
+        a.user_id
+            .is_none()
+            .cmp(&b.user_id.is_none())
+            .then(b.translations.cmp(&a.translations))
+            .then(b.proofreads.cmp(&a.proofreads))
+            .then(a.name.cmp(&b.name))
+    });
+
+    let typesetters = members
+        .iter()
+        .flatten()
+        .filter(|m| m.is_typesetter)
+        .map(|m| m.username.clone())
+        .collect();
+
+    Ok(ContributionsReport {
+        project_id: project_id.to_string(),
+        target_id: target_id.to_string(),
+        file_count: files.len(),
+        source_count,
+        contributors,
+        typesetters,
+        roles_known: members.is_some(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_project_contributions(
+    payload: GetProjectContributionsReq,
+) -> Result<ContributionsReport, String> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        "moetran.project.contributions.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.project.contributions");
+
+    let report = build_contributions_report(&payload.project_id, &payload.target_id).await?;
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        sources = report.source_count,
+        contributors = report.contributors.len(),
+        "moetran.project.contributions.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FormatProjectCreditsReq {
+    pub project_id: String,
+    pub target_id: String,
+    pub template: String,
+}
+
+// 统计并按模板渲染名单文本
+#[tauri::command]
+pub async fn format_project_credits(payload: FormatProjectCreditsReq) -> Result<String, String> {
+    tracing::info!(project_id = %payload.project_id, "moetran.project.credits.start");
+
+    let mut defer = WarnDefer::new("moetran.project.credits");
+
+    let report = build_contributions_report(&payload.project_id, &payload.target_id).await?;
+
+    let credits = report.format_credits(&payload.template);
+
+    tracing::info!(project_id = %payload.project_id, "moetran.project.credits.ok");
+
+    defer.success();
+
+    Ok(credits)
+}
@@ -0,0 +1,248 @@
+// 数据目录（SQLite 与图片缓存所在）的解析与切换。按优先级：环境变量 APP_DIR（含 .env）>
+// 用户通过 set_data_dir 选择的目录 > 旧版本在工作目录下留下的 ./data > 平台应用数据目录。
+// 选择记录在平台配置目录下的单独文件中（数据库本身就在数据目录里，无法用设置表保存），下次启动时生效。
+// 解析不会 panic：候选目录无法创建时依次尝试下一个，最后退回系统临时目录
+use std::{
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{config, ConfigSource},
+    error::AppError,
+};
+
+// 与 tauri.conf.json 的 identifier 一致，目录位置与 Tauri 的 app_data_dir / app_config_dir 相同
+const APP_IDENTIFIER: &str = "com.moetran-native.app";
+
+// 根目录下实际存放数据的子目录
+const DATA_SUBDIR: &str = "data";
+
+// 记录用户所选目录的文件（位于平台配置目录）
+const CHOICE_FILE_NAME: &str = "data_dir";
+
+// 检查目录可写时写入的临时文件
+const WRITE_PROBE_NAME: &str = ".write_probe";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDirSource {
+    Env,
+    Chosen,
+    Legacy,
+    Platform,
+    Fallback,
+}
+
+// 当前进程使用的数据目录及其来源
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedDataDir {
+    pub(crate) path: PathBuf,
+    pub(crate) source: DataDirSource,
+}
+
+fn platform_root() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+fn choice_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_IDENTIFIER).join(CHOICE_FILE_NAME))
+}
+
+fn env_root() -> Option<PathBuf> {
+    let config = config();
+
+    config
+        .entries()
+        .iter()
+        .any(|entry| entry.key == "app_dir" && entry.source == ConfigSource::Env)
+        .then(|| config.app_dir.clone())
+}
+
+// set_data_dir 保存的目录；文件不存在或内容为空时为 None
+fn chosen_root() -> Option<PathBuf> {
+    let raw = std::fs::read_to_string(choice_file()?).ok()?;
+    let raw = raw.trim();
+
+    (!raw.is_empty()).then(|| PathBuf::from(raw))
+}
+
+// 旧版本默认把数据放在工作目录下的 ./data，已有数据库时继续使用，避免升级后数据“消失”
+fn legacy_root() -> Option<PathBuf> {
+    let root = PathBuf::from("./");
+
+    root.join(DATA_SUBDIR)
+        .join("local.db")
+        .exists()
+        .then_some(root)
+}
+
+fn candidates() -> Vec<(PathBuf, DataDirSource)> {
+    [
+        (env_root(), DataDirSource::Env),
+        (chosen_root(), DataDirSource::Chosen),
+        (legacy_root(), DataDirSource::Legacy),
+        (platform_root(), DataDirSource::Platform),
+    ]
+    .into_iter()
+    .filter_map(|(root, source)| root.map(|root| (root, source)))
+    .collect()
+}
+
+static RESOLVED: LazyLock<ResolvedDataDir> = LazyLock::new(resolve);
+
+// 当前进程使用的数据目录（首次调用时解析，之后不变；见 lib.rs 的 DATA_DIR）
+pub(crate) fn resolved() -> &'static ResolvedDataDir {
+    &RESOLVED
+}
+
+// 按优先级选出第一个能创建的数据目录
+fn resolve() -> ResolvedDataDir {
+    for (root, source) in candidates() {
+        let path = root.join(DATA_SUBDIR);
+
+        match std::fs::create_dir_all(&path) {
+            Ok(_) => {
+                tracing::info!(path = %path.display(), ?source, "data_dir.resolved");
+
+                return ResolvedDataDir { path, source };
+            }
+            Err(err) => {
+                tracing::error!(path = %path.display(), ?source, error = %err, "data_dir.create_failed");
+            }
+        }
+    }
+
+    let path = std::env::temp_dir().join(APP_IDENTIFIER).join(DATA_SUBDIR);
+
+    if let Err(err) = std::fs::create_dir_all(&path) {
+        tracing::error!(path = %path.display(), error = %err, "data_dir.fallback_create_failed");
+    }
+
+    tracing::warn!(path = %path.display(), "data_dir.fallback");
+
+    ResolvedDataDir {
+        path,
+        source: DataDirSource::Fallback,
+    }
+}
+
+// 确认目录存在（不存在时创建）且可写入
+fn ensure_writable(root: &Path) -> Result<(), AppError> {
+    let path = root.join(DATA_SUBDIR);
+
+    std::fs::create_dir_all(&path).map_err(|err| {
+        AppError::InvalidInput(format!("无法创建数据目录 {}: {}", path.display(), err))
+    })?;
+
+    let probe = path.join(WRITE_PROBE_NAME);
+
+    std::fs::write(&probe, b"ok").map_err(|err| {
+        AppError::InvalidInput(format!("数据目录不可写 {}: {}", path.display(), err))
+    })?;
+
+    let _ = std::fs::remove_file(&probe);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirReply {
+    // 当前进程使用的数据目录
+    pub current: String,
+    pub source: DataDirSource,
+    // 已选择、下次启动时使用的根目录（未选择时为 None）
+    pub chosen: Option<String>,
+    // 未选择时使用的平台默认根目录
+    pub platform_default: Option<String>,
+    // 设置了环境变量 APP_DIR，所选目录不会生效
+    pub overridden_by_env: bool,
+    // 下次启动时使用的目录与当前不同，需要重启
+    pub restart_required: bool,
+}
+
+fn data_dir_reply() -> DataDirReply {
+    let current = resolved();
+    let chosen = chosen_root();
+
+    // 下次启动时按同样的优先级解析出的目录
+    let next = candidates()
+        .into_iter()
+        .next()
+        .map(|(root, _)| root.join(DATA_SUBDIR));
+
+    DataDirReply {
+        current: current.path.display().to_string(),
+        source: current.source,
+        chosen: chosen.map(|root| root.display().to_string()),
+        platform_default: platform_root().map(|root| root.display().to_string()),
+        overridden_by_env: env_root().is_some(),
+        restart_required: next.is_some_and(|next| next != current.path),
+    }
+}
+
+#[tauri::command]
+pub async fn get_data_dir() -> Result<DataDirReply, AppError> {
+    Ok(data_dir_reply())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetDataDirReq {
+    // 数据根目录（其下的 data 子目录存放数据库与图片缓存）；None 表示恢复默认
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+// 选择数据根目录，下次启动时生效；已有数据不会被移动
+#[tauri::command]
+pub async fn set_data_dir(payload: SetDataDirReq) -> Result<DataDirReply, AppError> {
+    tracing::info!(path = ?payload.path, "data_dir.set.start");
+
+    if env_root().is_some() {
+        return Err(AppError::InvalidInput(
+            "已通过环境变量 APP_DIR 指定数据目录，无法修改".to_string(),
+        ));
+    }
+
+    let choice_file =
+        choice_file().ok_or_else(|| AppError::Storage("无法确定平台配置目录".to_string()))?;
+
+    match payload.path.as_deref().map(str::trim) {
+        Some(raw) if !raw.is_empty() => {
+            let root = PathBuf::from(raw);
+
+            if !root.is_absolute() {
+                return Err(AppError::InvalidInput(format!(
+                    "数据目录必须是绝对路径: {}",
+                    raw
+                )));
+            }
+
+            ensure_writable(&root)?;
+
+            if let Some(parent) = choice_file.parent() {
+                std::fs::create_dir_all(parent).map_err(|err| {
+                    AppError::Storage(format!("无法创建配置目录 {}: {}", parent.display(), err))
+                })?;
+            }
+
+            std::fs::write(&choice_file, root.display().to_string())
+                .map_err(|err| AppError::Storage(format!("保存数据目录失败: {}", err)))?;
+        }
+        _ => match std::fs::remove_file(&choice_file) {
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(AppError::Storage(format!("清除数据目录设置失败: {}", err)));
+            }
+        },
+    }
+
+    let reply = data_dir_reply();
+
+    tracing::info!(chosen = ?reply.chosen, restart_required = reply.restart_required, "data_dir.set.ok");
+
+    Ok(reply)
+}
@@ -0,0 +1,191 @@
+// 项目各阶段的截止时间：PopRaKo 暂无对应字段，记录在本地 project_deadlines 表。
+// 项目列表中附带 next_deadline：未完成阶段中最早的截止时间（已完成阶段的截止时间忽略）
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::unix_now,
+    defer::WarnDefer,
+    project::{cached_proj_snapshot, ResProjectEnriched},
+    publish::{ProjStage, STAGE_STATUS_COMPLETED},
+    storage::{deadlines, LOCAL_STORAGE},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageDeadline {
+    pub stage: ProjStage,
+    // Unix timestamp
+    pub due_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NextDeadline {
+    pub stage: ProjStage,
+    pub due_at: i64,
+    pub overdue: bool,
+}
+
+// enriched 项目中各阶段的状态；没有 PopRaKo 信息时为 None
+fn stage_status(proj: &ResProjectEnriched, stage: ProjStage) -> Option<i32> {
+    match stage {
+        ProjStage::Translating => proj.translating_status,
+        ProjStage::Proofreading => proj.proofreading_status,
+        ProjStage::Typesetting => proj.typesetting_status,
+        ProjStage::Reviewing => proj.reviewing_status,
+    }
+}
+
+// 未完成阶段中最早的截止时间；状态未知的阶段视为未完成。
+// 全部完成或没有截止时间时返回 None
+pub(crate) fn next_deadline(
+    deadlines: &[StageDeadline],
+    status_of: impl Fn(ProjStage) -> Option<i32>,
+    now: i64,
+) -> Option<NextDeadline> {
+    deadlines
+        .iter()
+        .filter(|deadline| status_of(deadline.stage) != Some(STAGE_STATUS_COMPLETED))
+        .min_by_key(|deadline| (deadline.due_at, deadline.stage as u8))
+        .map(|deadline| NextDeadline {
+            stage: deadline.stage,
+            due_at: deadline.due_at,
+            overdue: deadline.due_at < now,
+        })
+}
+
+fn parse_rows(rows: impl IntoIterator<Item = (String, i64)>) -> Vec<StageDeadline> {
+    rows.into_iter()
+        .filter_map(|(stage, due_at)| {
+            Some(StageDeadline {
+                stage: ProjStage::parse(&stage)?,
+                due_at,
+            })
+        })
+        .collect()
+}
+
+// 为项目列表填充 next_deadline；读取失败时只记录日志
+pub(crate) async fn attach_next_deadlines(list: &mut [ResProjectEnriched]) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let rows = match deadlines::list_all_deadlines(storage.pool()).await {
+        Ok(rows) => rows,
+        Err(err) => {
+            tracing::warn!(error = %err, "deadline.list.failed");
+            return;
+        }
+    };
+
+    if rows.is_empty() {
+        return;
+    }
+
+    let mut by_proj: HashMap<String, Vec<(String, i64)>> = HashMap::new();
+
+    for (proj_id, stage, due_at) in rows {
+        by_proj.entry(proj_id).or_default().push((stage, due_at));
+    }
+
+    let now = unix_now();
+
+    for proj in list.iter_mut() {
+        let Some(rows) = by_proj.remove(proj.id.as_str()) else {
+            continue;
+        };
+
+        let stage_deadlines = parse_rows(rows);
+
+        proj.next_deadline =
+            next_deadline(&stage_deadlines, |stage| stage_status(proj, stage), now);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetProjectDeadlineReq {
+    pub proj_id: String,
+    pub stage: ProjStage,
+    // None 表示清除
+    #[serde(default)]
+    pub due_at: Option<i64>,
+}
+
+#[tauri::command]
+pub async fn set_project_deadline(
+    payload: SetProjectDeadlineReq,
+) -> Result<ProjectDeadlines, String> {
+    tracing::info!(
+        proj_id = %payload.proj_id,
+        stage = payload.stage.as_str(),
+        due_at = ?payload.due_at,
+        "deadline.set.start"
+    );
+
+    let mut defer = WarnDefer::new("deadline.set");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    deadlines::set_deadline(
+        storage.pool(),
+        &payload.proj_id,
+        payload.stage.as_str(),
+        payload.due_at,
+    )
+    .await?;
+
+    let reply = load_project_deadlines(&payload.proj_id).await?;
+
+    tracing::info!(proj_id = %payload.proj_id, "deadline.set.ok");
+
+    defer.success();
+
+    Ok(reply)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectDeadlines {
+    pub proj_id: String,
+    // 按截止时间排序
+    pub deadlines: Vec<StageDeadline>,
+    // 按最近一次获取的项目状态计算；项目不在缓存中时不考虑阶段状态
+    pub next: Option<NextDeadline>,
+}
+
+async fn load_project_deadlines(proj_id: &str) -> Result<ProjectDeadlines, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let stage_deadlines =
+        parse_rows(deadlines::get_project_deadlines(storage.pool(), proj_id).await?);
+
+    let snapshot = cached_proj_snapshot(proj_id);
+
+    let next = next_deadline(
+        &stage_deadlines,
+        |stage| snapshot.as_ref().and_then(|proj| stage_status(proj, stage)),
+        unix_now(),
+    );
+
+    Ok(ProjectDeadlines {
+        proj_id: proj_id.to_string(),
+        deadlines: stage_deadlines,
+        next,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetProjectDeadlinesReq {
+    pub proj_id: String,
+}
+
+#[tauri::command]
+pub async fn get_project_deadlines(
+    payload: GetProjectDeadlinesReq,
+) -> Result<ProjectDeadlines, String> {
+    load_project_deadlines(&payload.proj_id).await
+}
@@ -0,0 +1,1333 @@
+// 演示模式：新成员无需真实账号即可熟悉应用，也便于截图。
+// 开启后 moetran_* / poprako_* 请求由进程内的 DemoProvider 应答：数据按固定规则生成（每次开启都相同），
+// 写操作只修改内存中的状态，关闭演示模式即丢弃；token 为假值且不落盘。
+// 其余访问真实后端的路径（图片代理、文件上传等）由 http::ensure_online 拦截
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use base64::{engine::general_purpose, Engine as _};
+use image::{ImageFormat, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::{
+    clock::unix_now,
+    config::{config, set_runtime_value},
+    connectivity::Backend,
+    defer::WarnDefer,
+    error::AppError,
+    events::{emit_event, DemoModeChanged},
+    http::{set_provider, ApiProvider, ProviderRequest, RawBody},
+    publish::STAGE_STATUS_COMPLETED,
+    token::use_demo_tokens,
+};
+
+const DEMO_MOETRAN_TOKEN: &str = "demo-moetran-token";
+const DEMO_USER_ID: &str = "demo-user";
+const DEMO_USER_NAME: &str = "演示用户";
+const FILE_URL_PREFIX: &str = "https://demo.invalid/files/";
+
+// PopRaKo token 需能被 session 模块解码出用户 id，否则身份检查会报告无法识别
+static DEMO_POPRAKO_TOKEN: LazyLock<String> = LazyLock::new(|| {
+    let claims =
+        general_purpose::URL_SAFE_NO_PAD.encode(json!({ "user_id": DEMO_USER_ID }).to_string());
+
+    format!("demo.{}.demo", claims)
+});
+
+const TEAM_NAMES: &[&str] = &["星光汉化组", "月下嵌字社"];
+const PROJSET_NAMES: &[&str] = &["短篇合集", "长篇连载"];
+const MEMBER_NAMES: &[&str] = &["小译", "阿校", "嵌字君", "修图师"];
+const SAMPLE_LINES: &[&str] = &[
+    "早上好！",
+    "今天也要加油哦。",
+    "……这是怎么回事？",
+    "等等我！",
+    "原来如此。",
+    "谢谢你。",
+];
+
+const STATUS_PENDING: i32 = 0;
+const STATUS_IN_PROGRESS: i32 = 1;
+
+const PROJECTS_PER_PROJSET: usize = 3;
+const FILES_PER_PROJECT: usize = 3;
+const SOURCES_PER_FILE: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Roles {
+    translator: bool,
+    proofreader: bool,
+    typesetter: bool,
+    redrawer: bool,
+}
+
+impl Roles {
+    fn any(&self) -> bool {
+        self.translator || self.proofreader || self.typesetter || self.redrawer
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DemoTeam {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+struct DemoMember {
+    team_id: String,
+    member_id: String,
+    user_id: String,
+    username: String,
+    is_admin: bool,
+    is_principal: bool,
+    roles: Roles,
+}
+
+#[derive(Debug, Clone)]
+struct DemoProjSet {
+    id: String,
+    team_id: String,
+    name: String,
+    description: String,
+    serial: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Assignment {
+    member_id: String,
+    roles: Roles,
+    updated_at: i64,
+}
+
+#[derive(Debug, Clone)]
+struct DemoProject {
+    id: String,
+    name: String,
+    description: String,
+    team_id: String,
+    projset_id: String,
+    projset_index: u32,
+    // 翻译 / 校对 / 嵌字 / 审核
+    status: [i32; 4],
+    is_published: bool,
+    assignments: Vec<Assignment>,
+}
+
+#[derive(Debug, Clone)]
+struct DemoFile {
+    id: String,
+    project_id: String,
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+struct DemoTranslation {
+    id: String,
+    user_id: String,
+    content: String,
+    proofread_content: Option<String>,
+    selected: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DemoSource {
+    id: String,
+    file_id: String,
+    x: f64,
+    y: f64,
+    position_type: i32,
+    translations: Vec<DemoTranslation>,
+}
+
+#[derive(Debug, Default)]
+struct DemoState {
+    next_id: u64,
+    teams: Vec<DemoTeam>,
+    members: Vec<DemoMember>,
+    projsets: Vec<DemoProjSet>,
+    projects: Vec<DemoProject>,
+    files: Vec<DemoFile>,
+    sources: Vec<DemoSource>,
+}
+
+// 按固定规则生成：每个汉化组 2 个项目集，每个项目集 3 个项目（已发布 / 校对中 / 翻译中），
+// 每个项目 3 页、每页 3 个标记
+fn seed() -> DemoState {
+    let mut state = DemoState::default();
+    let seeded_at = unix_now();
+
+    for (t, team_name) in TEAM_NAMES.iter().enumerate() {
+        let team_id = format!("demo-team-{}", t + 1);
+
+        state.teams.push(DemoTeam {
+            id: team_id.clone(),
+            name: team_name.to_string(),
+        });
+
+        state.members.push(DemoMember {
+            team_id: team_id.clone(),
+            member_id: format!("demo-member-{}-0", t + 1),
+            user_id: DEMO_USER_ID.to_string(),
+            username: DEMO_USER_NAME.to_string(),
+            is_admin: true,
+            is_principal: true,
+            roles: Roles {
+                translator: true,
+                proofreader: true,
+                ..Roles::default()
+            },
+        });
+
+        for (m, name) in MEMBER_NAMES.iter().enumerate() {
+            state.members.push(DemoMember {
+                team_id: team_id.clone(),
+                member_id: format!("demo-member-{}-{}", t + 1, m + 1),
+                user_id: format!("demo-user-{}-{}", t + 1, m + 1),
+                username: name.to_string(),
+                is_admin: false,
+                is_principal: false,
+                roles: Roles {
+                    translator: m == 0,
+                    proofreader: m == 1,
+                    typesetter: m == 2,
+                    redrawer: m == 3,
+                },
+            });
+        }
+
+        for (s, projset_name) in PROJSET_NAMES.iter().enumerate() {
+            let projset_id = format!("demo-projset-{}-{}", t + 1, s + 1);
+
+            state.projsets.push(DemoProjSet {
+                id: projset_id.clone(),
+                team_id: team_id.clone(),
+                name: projset_name.to_string(),
+                description: format!("{}的演示项目集", team_name),
+                serial: s as u32 + 1,
+            });
+
+            for p in 0..PROJECTS_PER_PROJSET {
+                let project_id = format!("demo-proj-{}-{}-{}", t + 1, s + 1, p + 1);
+
+                let (status, is_published) = match p {
+                    0 => ([STAGE_STATUS_COMPLETED; 4], true),
+                    1 => (
+                        [
+                            STAGE_STATUS_COMPLETED,
+                            STATUS_IN_PROGRESS,
+                            STATUS_PENDING,
+                            STATUS_PENDING,
+                        ],
+                        false,
+                    ),
+                    _ => (
+                        [
+                            STATUS_IN_PROGRESS,
+                            STATUS_PENDING,
+                            STATUS_PENDING,
+                            STATUS_PENDING,
+                        ],
+                        false,
+                    ),
+                };
+
+                let assignments = vec![
+                    Assignment {
+                        member_id: format!("demo-member-{}-0", t + 1),
+                        roles: Roles {
+                            translator: true,
+                            ..Roles::default()
+                        },
+                        updated_at: seeded_at,
+                    },
+                    Assignment {
+                        member_id: format!("demo-member-{}-2", t + 1),
+                        roles: Roles {
+                            proofreader: true,
+                            ..Roles::default()
+                        },
+                        updated_at: seeded_at,
+                    },
+                ];
+
+                state.projects.push(DemoProject {
+                    id: project_id.clone(),
+                    name: format!("{} 第{}话", projset_name, p + 1),
+                    description: String::new(),
+                    team_id: team_id.clone(),
+                    projset_id: projset_id.clone(),
+                    projset_index: p as u32 + 1,
+                    status,
+                    is_published,
+                    assignments,
+                });
+
+                // 已翻译的页数：已发布的项目全部翻译完成，其余依次递减
+                let translated_files = FILES_PER_PROJECT.saturating_sub(p);
+
+                for f in 0..FILES_PER_PROJECT {
+                    let file_id = format!("{}-file-{}", project_id, f + 1);
+
+                    state.files.push(DemoFile {
+                        id: file_id.clone(),
+                        project_id: project_id.clone(),
+                        name: format!("{:03}.png", f + 1),
+                    });
+
+                    for n in 0..SOURCES_PER_FILE {
+                        let line = SAMPLE_LINES[(f * SOURCES_PER_FILE + n) % SAMPLE_LINES.len()];
+
+                        let translations = if f < translated_files {
+                            vec![DemoTranslation {
+                                id: format!("{}-src-{}-tr", file_id, n + 1),
+                                user_id: DEMO_USER_ID.to_string(),
+                                content: line.to_string(),
+                                proofread_content: (p == 0).then(|| line.to_string()),
+                                selected: p == 0,
+                            }]
+                        } else {
+                            vec![]
+                        };
+
+                        state.sources.push(DemoSource {
+                            id: format!("{}-src-{}", file_id, n + 1),
+                            file_id: file_id.clone(),
+                            x: 0.2 + 0.3 * n as f64,
+                            y: 0.25 + 0.2 * n as f64,
+                            position_type: if n % 2 == 0 { 1 } else { 2 },
+                            translations,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    state
+}
+
+// ================== JSON 形态（与真实接口的响应一致） ==================
+
+fn user_brief(state: &DemoState, user_id: &str) -> Value {
+    let name = state
+        .members
+        .iter()
+        .find(|member| member.user_id == user_id)
+        .map(|member| member.username.clone())
+        .unwrap_or_default();
+
+    json!({ "id": user_id, "name": name })
+}
+
+fn team_json(team: &DemoTeam) -> Value {
+    json!({ "id": team.id, "name": team.name, "avatar": "", "has_avatar": false })
+}
+
+fn translation_json(state: &DemoState, translation: &DemoTranslation) -> Value {
+    json!({
+        "id": translation.id,
+        "content": translation.content,
+        "proofread_content": translation.proofread_content,
+        "selected": translation.selected,
+        "user": user_brief(state, &translation.user_id),
+        "proofreader": translation
+            .proofread_content
+            .as_ref()
+            .map(|_| user_brief(state, DEMO_USER_ID)),
+    })
+}
+
+fn source_json(state: &DemoState, source: &DemoSource) -> Value {
+    let mine = source
+        .translations
+        .iter()
+        .find(|translation| translation.user_id == DEMO_USER_ID)
+        .map(|translation| translation_json(state, translation));
+
+    json!({
+        "id": source.id,
+        "x": source.x,
+        "y": source.y,
+        "position_type": source.position_type,
+        "my_translation": mine,
+        "translations": source
+            .translations
+            .iter()
+            .map(|translation| translation_json(state, translation))
+            .collect::<Vec<_>>(),
+    })
+}
+
+impl DemoState {
+    fn alloc_id(&mut self, kind: &str) -> String {
+        self.next_id += 1;
+        format!("demo-{}-new-{}", kind, self.next_id)
+    }
+
+    fn team(&self, team_id: &str) -> Option<&DemoTeam> {
+        self.teams.iter().find(|team| team.id == team_id)
+    }
+
+    fn project(&self, project_id: &str) -> Result<&DemoProject, AppError> {
+        self.projects
+            .iter()
+            .find(|proj| proj.id == project_id)
+            .ok_or_else(|| not_found("project", project_id))
+    }
+
+    fn project_mut(&mut self, project_id: &str) -> Result<&mut DemoProject, AppError> {
+        self.projects
+            .iter_mut()
+            .find(|proj| proj.id == project_id)
+            .ok_or_else(|| not_found("project", project_id))
+    }
+
+    fn projset(&self, projset_id: &str) -> Option<&DemoProjSet> {
+        self.projsets.iter().find(|set| set.id == projset_id)
+    }
+
+    fn project_sources(&self, project_id: &str) -> impl Iterator<Item = &DemoSource> {
+        let file_ids: Vec<&str> = self
+            .files
+            .iter()
+            .filter(|file| file.project_id == project_id)
+            .map(|file| file.id.as_str())
+            .collect();
+
+        self.sources
+            .iter()
+            .filter(move |source| file_ids.contains(&source.file_id.as_str()))
+    }
+
+    fn project_counts(&self, project_id: &str) -> (u64, u64, u64) {
+        self.project_sources(project_id)
+            .fold((0, 0, 0), |(total, translated, checked), source| {
+                let has_translation = !source.translations.is_empty();
+                let has_check = source.translations.iter().any(|translation| {
+                    translation.selected || translation.proofread_content.is_some()
+                });
+
+                (
+                    total + 1,
+                    translated + has_translation as u64,
+                    checked + has_check as u64,
+                )
+            })
+    }
+
+    fn moetran_project_json(&self, proj: &DemoProject) -> Value {
+        let (sources, translated, checked) = self.project_counts(&proj.id);
+
+        let team = self
+            .team(&proj.team_id)
+            .map(team_json)
+            .unwrap_or(Value::Null);
+
+        let projset_name = self
+            .projset(&proj.projset_id)
+            .map(|set| set.name.clone())
+            .unwrap_or_default();
+
+        json!({
+            "id": proj.id,
+            "name": proj.name,
+            "source_count": sources,
+            "translated_source_count": translated,
+            "checked_source_count": checked,
+            "team": team,
+            "project_set": { "id": proj.projset_id, "name": projset_name },
+            "role": null,
+        })
+    }
+
+    fn member(&self, member_id: &str) -> Option<&DemoMember> {
+        self.members
+            .iter()
+            .find(|member| member.member_id == member_id)
+    }
+
+    fn project_members_json(&self, proj: &DemoProject) -> Vec<Value> {
+        proj.assignments
+            .iter()
+            .filter_map(|assignment| {
+                let member = self.member(&assignment.member_id)?;
+
+                Some(json!({
+                    "user_id": member.user_id,
+                    "member_id": member.member_id,
+                    "username": member.username,
+                    "is_admin": member.is_admin,
+                    "is_translator": assignment.roles.translator,
+                    "is_proofreader": assignment.roles.proofreader,
+                    "is_typesetter": assignment.roles.typesetter,
+                    "is_redrawer": assignment.roles.redrawer,
+                    "is_principal": member.is_principal,
+                }))
+            })
+            .collect()
+    }
+
+    fn proj_info_json(&self, proj: &DemoProject) -> Value {
+        json!({
+            "proj_id": proj.id,
+            "proj_name": proj.name,
+            "projset_index": proj.projset_index,
+            "translating_status": proj.status[0],
+            "proofreading_status": proj.status[1],
+            "typesetting_status": proj.status[2],
+            "reviewing_status": proj.status[3],
+            "is_published": proj.is_published,
+            "members": self.project_members_json(proj),
+        })
+    }
+
+    fn team_proj_item_json(&self, proj: &DemoProject) -> Value {
+        let mut item = self.proj_info_json(proj);
+
+        item["description"] = json!(proj.description);
+        item["projset_id"] = json!(proj.projset_id);
+        item["projset_serial"] = json!(self.projset(&proj.projset_id).map(|set| set.serial));
+
+        item
+    }
+
+    fn member_json(member: &DemoMember) -> Value {
+        json!({
+            "member_id": member.member_id,
+            "user_id": member.user_id,
+            "username": member.username,
+            "is_admin": member.is_admin,
+            "is_translator": member.roles.translator,
+            "is_proofreader": member.roles.proofreader,
+            "is_typesetter": member.roles.typesetter,
+            "is_redrawer": member.roles.redrawer,
+            "is_principal": member.is_principal,
+            "last_active": null,
+        })
+    }
+
+    fn source_mut(&mut self, source_id: &str) -> Result<&mut DemoSource, AppError> {
+        self.sources
+            .iter_mut()
+            .find(|source| source.id == source_id)
+            .ok_or_else(|| not_found("source", source_id))
+    }
+}
+
+// ================== 路由 ==================
+
+fn not_found(kind: &str, id: &str) -> AppError {
+    AppError::Other(format!(
+        "Remote returned status 404 Not Found: demo {} {} not found",
+        kind, id
+    ))
+}
+
+fn ok_envelope(data: Value) -> Value {
+    json!({ "code": 200, "data": data, "message": null })
+}
+
+fn list_envelope(items: Vec<Value>, total: usize) -> Value {
+    json!({ "code": 200, "data": items, "message": null, "total": total })
+}
+
+fn str_field<'a>(body: &'a Value, key: &str) -> Option<&'a str> {
+    body.get(key).and_then(Value::as_str)
+}
+
+fn bool_field(body: &Value, key: &str) -> bool {
+    body.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn u32_field(body: &Value, key: &str) -> Option<u32> {
+    body.get(key)
+        .and_then(Value::as_u64)
+        .map(|value| value as u32)
+}
+
+// page 从 1 开始；未提供 limit 时返回全部
+fn paginate(items: Vec<Value>, page: Option<u32>, limit: Option<u32>) -> Vec<Value> {
+    let Some(limit) = limit.filter(|limit| *limit > 0) else {
+        return items;
+    };
+
+    let skip = page.unwrap_or(1).saturating_sub(1) as usize * limit as usize;
+
+    items.into_iter().skip(skip).take(limit as usize).collect()
+}
+
+// 路径中内联的查询串（如 "user/teams?page=1&limit=10"）与 query 参数合并
+fn split_path<'a>(
+    path: &'a str,
+    query: Option<&HashMap<&str, String>>,
+) -> (&'a str, HashMap<String, String>) {
+    let (path, inline) = path.split_once('?').unwrap_or((path, ""));
+
+    let mut merged: HashMap<String, String> = inline
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    if let Some(query) = query {
+        merged.extend(
+            query
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone())),
+        );
+    }
+
+    (path, merged)
+}
+
+fn query_u32(query: &HashMap<String, String>, key: &str) -> Option<u32> {
+    query.get(key).and_then(|value| value.parse().ok())
+}
+
+impl DemoState {
+    fn moetran(
+        &mut self,
+        method: &str,
+        segments: &[&str],
+        query: &HashMap<String, String>,
+        body: &Value,
+    ) -> Result<Value, AppError> {
+        let page = query_u32(query, "page");
+        let limit = query_u32(query, "limit");
+
+        let reply = match (method, segments) {
+            ("POST", ["captchas"]) => json!({
+                "image": format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(placeholder_png(120, 40, 0))),
+                "info": "demo-captcha",
+            }),
+            ("POST", ["user", "token"]) => json!({ "token": DEMO_MOETRAN_TOKEN }),
+            ("GET", ["user", "info"]) => {
+                json!({ "id": DEMO_USER_ID, "name": DEMO_USER_NAME, "has_avatar": false, "avatar": "" })
+            }
+            ("GET", ["user", "teams"]) => {
+                let teams = self.teams.iter().map(team_json).collect();
+                json!(paginate(teams, page, limit))
+            }
+            ("GET", ["user", "projects"]) => {
+                let projects = self
+                    .projects
+                    .iter()
+                    .map(|proj| self.moetran_project_json(proj))
+                    .collect();
+                json!(paginate(projects, page, limit))
+            }
+            ("GET", ["teams", team_id, "projects"]) => {
+                let word = query.get("word").map(String::as_str).unwrap_or("");
+
+                let projects = self
+                    .projects
+                    .iter()
+                    .filter(|proj| proj.team_id == *team_id && proj.name.contains(word))
+                    .map(|proj| self.moetran_project_json(proj))
+                    .collect();
+                json!(paginate(projects, page, limit))
+            }
+            ("GET", ["teams", team_id, "users"]) => {
+                let users = self
+                    .members
+                    .iter()
+                    .filter(|member| member.team_id == *team_id)
+                    .map(|member| json!({ "id": member.user_id, "name": member.username }))
+                    .collect();
+                json!(paginate(users, page, limit))
+            }
+            ("GET", ["projects", project_id]) => {
+                self.moetran_project_json(self.project(project_id)?)
+            }
+            // 演示数据只保留进行中的项目，完结即移出列表
+            ("PUT", ["projects", project_id]) => {
+                let project = self.moetran_project_json(self.project(project_id)?);
+
+                if body.get("status").and_then(Value::as_i64).unwrap_or(0) != 0 {
+                    self.projects.retain(|proj| proj.id != *project_id);
+                }
+
+                project
+            }
+            ("GET", ["projects", project_id, "targets"]) => {
+                let (_, translated, checked) =
+                    self.project_counts(self.project(project_id)?.id.as_str());
+
+                json!([{
+                    "id": format!("{}-target", project_id),
+                    "translated_source_count": translated,
+                    "checked_source_count": checked,
+                }])
+            }
+            ("GET", ["projects", project_id, "files"]) => {
+                let files: Vec<Value> = self
+                    .files
+                    .iter()
+                    .filter(|file| file.project_id == *project_id)
+                    .map(|file| {
+                        let url = format!("{}{}.png", FILE_URL_PREFIX, file.id);
+                        let sources = self
+                            .sources
+                            .iter()
+                            .filter(|source| source.file_id == file.id)
+                            .count();
+
+                        json!({
+                            "id": file.id,
+                            "name": file.name,
+                            "source_count": sources,
+                            "url": url,
+                            "cover_url": url,
+                            "width": PAGE_WIDTH,
+                            "height": PAGE_HEIGHT,
+                        })
+                    })
+                    .collect();
+                json!(files)
+            }
+            ("GET", ["files", file_id, "sources"]) => {
+                let sources: Vec<Value> = self
+                    .sources
+                    .iter()
+                    .filter(|source| source.file_id == *file_id)
+                    .map(|source| source_json(self, source))
+                    .collect();
+                json!(sources)
+            }
+            ("POST", ["files", file_id, "sources"]) => {
+                if !self.files.iter().any(|file| file.id == *file_id) {
+                    return Err(not_found("file", file_id));
+                }
+
+                let source = DemoSource {
+                    id: self.alloc_id("src"),
+                    file_id: file_id.to_string(),
+                    x: body.get("x").and_then(Value::as_f64).unwrap_or(0.5),
+                    y: body.get("y").and_then(Value::as_f64).unwrap_or(0.5),
+                    position_type: body
+                        .get("position_type")
+                        .and_then(Value::as_i64)
+                        .unwrap_or(1) as i32,
+                    translations: vec![],
+                };
+
+                let reply = source_json(self, &source);
+                self.sources.push(source);
+                reply
+            }
+            ("PUT", ["sources", source_id]) => {
+                let source = self.source_mut(source_id)?;
+
+                if let Some(x) = body.get("x").and_then(Value::as_f64) {
+                    source.x = x;
+                }
+                if let Some(y) = body.get("y").and_then(Value::as_f64) {
+                    source.y = y;
+                }
+                if let Some(pt) = body.get("position_type").and_then(Value::as_i64) {
+                    source.position_type = pt as i32;
+                }
+
+                let source = source.clone();
+                source_json(self, &source)
+            }
+            ("DELETE", ["sources", source_id]) => {
+                self.source_mut(source_id)?;
+                self.sources.retain(|source| source.id != *source_id);
+                json!({})
+            }
+            ("POST", ["sources", source_id, "translations"]) => {
+                let content = str_field(body, "content").unwrap_or_default().to_string();
+                let new_id = self.alloc_id("tr");
+                let source = self.source_mut(source_id)?;
+
+                // 同一用户在同一标记下只有一条翻译，重复提交即覆盖
+                let translation = match source
+                    .translations
+                    .iter_mut()
+                    .find(|translation| translation.user_id == DEMO_USER_ID)
+                {
+                    Some(existing) => {
+                        existing.content = content;
+                        existing.clone()
+                    }
+                    None => {
+                        let translation = DemoTranslation {
+                            id: new_id,
+                            user_id: DEMO_USER_ID.to_string(),
+                            content,
+                            proofread_content: None,
+                            selected: false,
+                        };
+                        source.translations.push(translation.clone());
+                        translation
+                    }
+                };
+
+                translation_json(self, &translation)
+            }
+            ("PUT", ["translations", translation_id]) => {
+                let translation = self
+                    .sources
+                    .iter_mut()
+                    .flat_map(|source| source.translations.iter_mut())
+                    .find(|translation| translation.id == *translation_id)
+                    .ok_or_else(|| not_found("translation", translation_id))?;
+
+                if let Some(content) = str_field(body, "content") {
+                    translation.content = content.to_string();
+                }
+                if let Some(proofread) = body.get("proofread_content") {
+                    translation.proofread_content = proofread.as_str().map(str::to_string);
+                }
+                if let Some(selected) = body.get("selected").and_then(Value::as_bool) {
+                    translation.selected = selected;
+                }
+
+                let translation = translation.clone();
+                translation_json(self, &translation)
+            }
+            _ => return Err(unsupported(method, segments)),
+        };
+
+        Ok(reply)
+    }
+
+    fn search_projects(&self, body: &Value) -> Value {
+        let ids: Option<Vec<&str>> = body
+            .get("proj_ids")
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(Value::as_str).collect());
+        let projset_ids: Option<Vec<&str>> = body
+            .get("projset_ids")
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(Value::as_str).collect());
+        let member_ids: Option<Vec<&str>> = body
+            .get("member_ids")
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(Value::as_str).collect());
+        let name = str_field(body, "fuzzy_proj_name").unwrap_or("");
+        let published = body.get("is_published").and_then(Value::as_bool);
+
+        let status_filters: Vec<(usize, i64)> = [
+            "translating_status",
+            "proofreading_status",
+            "typesetting_status",
+            "reviewing_status",
+        ]
+        .iter()
+        .enumerate()
+        .filter_map(|(i, key)| Some((i, body.get(*key)?.as_i64()?)))
+        .collect();
+
+        let matched: Vec<Value> = self
+            .projects
+            .iter()
+            .filter(|proj| {
+                ids.as_ref()
+                    .map(|ids| ids.contains(&proj.id.as_str()))
+                    .unwrap_or(true)
+            })
+            .filter(|proj| {
+                projset_ids
+                    .as_ref()
+                    .map(|ids| ids.contains(&proj.projset_id.as_str()))
+                    .unwrap_or(true)
+            })
+            .filter(|proj| {
+                member_ids
+                    .as_ref()
+                    .map(|ids| {
+                        proj.assignments
+                            .iter()
+                            .any(|assignment| ids.contains(&assignment.member_id.as_str()))
+                    })
+                    .unwrap_or(true)
+            })
+            .filter(|proj| proj.name.contains(name))
+            .filter(|proj| published.map(|p| p == proj.is_published).unwrap_or(true))
+            .filter(|proj| {
+                status_filters
+                    .iter()
+                    .all(|(i, status)| proj.status[*i] as i64 == *status)
+            })
+            .map(|proj| self.proj_info_json(proj))
+            .collect();
+
+        let total = matched.len();
+
+        list_envelope(
+            paginate(matched, u32_field(body, "page"), u32_field(body, "limit")),
+            total,
+        )
+    }
+
+    fn poprako(
+        &mut self,
+        method: &str,
+        segments: &[&str],
+        query: &HashMap<String, String>,
+        body: &Value,
+    ) -> Result<Value, AppError> {
+        let team_id = query.get("team_id").cloned().unwrap_or_default();
+
+        let reply = match (method, segments) {
+            ("POST", ["sync"]) => ok_envelope(json!({ "token": DEMO_POPRAKO_TOKEN.as_str() })),
+            ("GET", ["notify", "update"]) => json!({ "data": { "has_update": false } }),
+            ("GET", ["health"]) => json!({ "status": "ok", "version": "demo" }),
+            ("POST", ["projs", "search"]) => self.search_projects(body),
+            ("GET", ["projs"]) => {
+                let items = self
+                    .projects
+                    .iter()
+                    .filter(|proj| proj.team_id == team_id)
+                    .map(|proj| self.team_proj_item_json(proj))
+                    .collect();
+                ok_envelope(json!(paginate(
+                    items,
+                    query_u32(query, "page"),
+                    query_u32(query, "limit")
+                )))
+            }
+            ("POST", ["projs"]) => self.create_project(body),
+            ("POST", ["projs", proj_id, "assign"]) => {
+                let member_id = str_field(body, "member_id").unwrap_or_default().to_string();
+                let roles = Roles {
+                    translator: bool_field(body, "is_translator"),
+                    proofreader: bool_field(body, "is_proofreader"),
+                    typesetter: bool_field(body, "is_typesetter"),
+                    redrawer: bool_field(body, "is_redrawer"),
+                };
+                let proj = self.project_mut(proj_id)?;
+
+                proj.assignments
+                    .retain(|assignment| assignment.member_id != member_id);
+
+                if roles.any() {
+                    proj.assignments.push(Assignment {
+                        member_id,
+                        roles,
+                        updated_at: unix_now(),
+                    });
+                }
+
+                Value::Null
+            }
+            ("PUT", ["projs", proj_id, "status"]) => {
+                let stage = match str_field(body, "status_type") {
+                    Some("translating") => 0,
+                    Some("proofreading") => 1,
+                    Some("typesetting") => 2,
+                    Some("reviewing") => 3,
+                    other => {
+                        return Err(AppError::Other(format!(
+                            "Remote returned status 400 Bad Request: unknown status_type {:?}",
+                            other
+                        )))
+                    }
+                };
+                let status = body.get("new_status").and_then(Value::as_i64).unwrap_or(0) as i32;
+
+                self.project_mut(proj_id)?.status[stage] = status;
+                Value::Null
+            }
+            ("DELETE", ["projs", proj_id]) => {
+                let (total, _, checked) = self.project_counts(self.project(proj_id)?.id.as_str());
+
+                if checked < total {
+                    return Ok(
+                        json!({ "code": 409, "data": null, "message": "项目中仍有未校对的原文" }),
+                    );
+                }
+
+                self.projects.retain(|proj| proj.id != *proj_id);
+                Value::Null
+            }
+            ("PUT", ["projs", proj_id, "publish"]) => {
+                self.project_mut(proj_id)?.is_published = true;
+                Value::Null
+            }
+            ("GET", ["projsets"]) => {
+                let projsets: Vec<Value> = self
+                    .projsets
+                    .iter()
+                    .filter(|set| set.team_id == team_id)
+                    .map(|set| {
+                        json!({
+                            "projset_id": set.id,
+                            "projset_name": set.name,
+                            "projset_description": set.description,
+                            "projset_serial": set.serial,
+                            "team_id": set.team_id,
+                        })
+                    })
+                    .collect();
+                ok_envelope(json!({ "projsets": projsets }))
+            }
+            ("POST", ["projsets"]) => {
+                let team_id = str_field(body, "team_id").unwrap_or_default().to_string();
+                let serial = self
+                    .projsets
+                    .iter()
+                    .filter(|set| set.team_id == team_id)
+                    .map(|set| set.serial)
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+
+                let projset = DemoProjSet {
+                    id: self.alloc_id("projset"),
+                    team_id,
+                    name: str_field(body, "projset_name")
+                        .unwrap_or_default()
+                        .to_string(),
+                    description: str_field(body, "projset_description")
+                        .unwrap_or_default()
+                        .to_string(),
+                    serial,
+                };
+
+                self.projsets.push(projset);
+                ok_envelope(json!({ "projset_serial": serial }))
+            }
+            ("PUT", ["projsets", projset_id]) => {
+                let Some(projset) = self.projsets.iter_mut().find(|set| set.id == *projset_id)
+                else {
+                    return Err(not_found("projset", projset_id));
+                };
+
+                if let Some(name) = str_field(body, "projset_name") {
+                    projset.name = name.to_string();
+                }
+
+                if let Some(description) = str_field(body, "projset_description") {
+                    projset.description = description.to_string();
+                }
+
+                Value::Null
+            }
+            ("DELETE", ["projsets", projset_id]) => {
+                if self.projset(projset_id).is_none() {
+                    return Err(not_found("projset", projset_id));
+                }
+
+                if self
+                    .projects
+                    .iter()
+                    .any(|proj| proj.projset_id == *projset_id)
+                {
+                    return Ok(json!({ "code": 409, "data": null, "message": "项目集不为空" }));
+                }
+
+                self.projsets.retain(|set| set.id != *projset_id);
+                Value::Null
+            }
+            ("GET", ["assigns"]) => {
+                let since = query
+                    .get("time_start")
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0);
+
+                let assigns: Vec<Value> = self
+                    .projects
+                    .iter()
+                    .flat_map(|proj| proj.assignments.iter().map(move |assignment| (proj, assignment)))
+                    .filter(|(_, assignment)| assignment.updated_at >= since)
+                    .filter_map(|(proj, assignment)| {
+                        let member = self.member(&assignment.member_id)?;
+
+                        Some(json!({
+                            "proj_id": proj.id,
+                            "proj_name": proj.name,
+                            "projset_serial": self.projset(&proj.projset_id).map(|set| set.serial).unwrap_or(0),
+                            "projset_index": proj.projset_index,
+                            "member_id": member.member_id,
+                            "username": member.username,
+                            "is_translator": assignment.roles.translator,
+                            "is_proofreader": assignment.roles.proofreader,
+                            "is_typesetter": assignment.roles.typesetter,
+                            "is_redrawer": assignment.roles.redrawer,
+                            "is_principal": member.is_principal,
+                            "updated_at": assignment.updated_at,
+                        }))
+                    })
+                    .collect();
+                ok_envelope(json!(assigns))
+            }
+            ("POST", ["members", "search"]) => {
+                let team_id = str_field(body, "team_id").unwrap_or_default();
+                let name = str_field(body, "fuzzy_name").unwrap_or("");
+                let position = str_field(body, "position");
+
+                let members: Vec<Value> = self
+                    .members
+                    .iter()
+                    .filter(|member| member.team_id == team_id && member.username.contains(name))
+                    .filter(|member| match position {
+                        Some("translator") => member.roles.translator,
+                        Some("proofreader") => member.roles.proofreader,
+                        Some("typesetter") => member.roles.typesetter,
+                        Some("redrawer") => member.roles.redrawer,
+                        Some("principal") => member.is_principal,
+                        _ => true,
+                    })
+                    .map(DemoState::member_json)
+                    .collect();
+                let total = members.len();
+
+                list_envelope(
+                    paginate(members, u32_field(body, "page"), u32_field(body, "limit")),
+                    total,
+                )
+            }
+            ("GET", ["members", "info"]) => {
+                let member = self
+                    .members
+                    .iter()
+                    .find(|member| member.team_id == team_id && member.user_id == DEMO_USER_ID)
+                    .ok_or_else(|| not_found("member of team", &team_id))?;
+
+                ok_envelope(json!({
+                    "member_id": member.member_id,
+                    "is_admin": member.is_admin,
+                    "is_translator": member.roles.translator,
+                    "is_proofreader": member.roles.proofreader,
+                    "is_typesetter": member.roles.typesetter,
+                    "is_principal": member.is_principal,
+                }))
+            }
+            ("GET", ["members", "active"]) => {
+                let members = self
+                    .members
+                    .iter()
+                    .filter(|member| member.team_id == team_id)
+                    .map(DemoState::member_json)
+                    .collect();
+                ok_envelope(json!(paginate(
+                    members,
+                    query_u32(query, "page"),
+                    query_u32(query, "limit")
+                )))
+            }
+            _ => return Err(unsupported(method, segments)),
+        };
+
+        Ok(reply)
+    }
+
+    // 创建项目：同时出现在 Moetran 与 PopRaKo 两侧（真实环境中由 PopRaKo 代为创建 Moetran 项目）
+    fn create_project(&mut self, body: &Value) -> Value {
+        let projset_id = str_field(body, "projset_id")
+            .unwrap_or_default()
+            .to_string();
+
+        let Some(projset) = self.projset(&projset_id).cloned() else {
+            return json!({ "code": 404, "data": null, "message": "项目集不存在" });
+        };
+
+        let taken: Vec<u32> = self
+            .projects
+            .iter()
+            .filter(|proj| proj.projset_id == projset_id)
+            .map(|proj| proj.projset_index)
+            .collect();
+
+        let requested = body
+            .get("workset_index")
+            .and_then(Value::as_i64)
+            .unwrap_or(-1);
+
+        let index = if requested < 0 {
+            taken.iter().max().copied().unwrap_or(0) + 1
+        } else {
+            requested as u32
+        };
+
+        if taken.contains(&index) {
+            return json!({ "code": 409, "data": null, "message": "项目集中已存在该序号" });
+        }
+
+        let proj_id = self.alloc_id("proj");
+
+        self.projects.push(DemoProject {
+            id: proj_id.clone(),
+            name: str_field(body, "proj_name").unwrap_or_default().to_string(),
+            description: str_field(body, "proj_description")
+                .unwrap_or_default()
+                .to_string(),
+            team_id: projset.team_id.clone(),
+            projset_id,
+            projset_index: index,
+            status: [STATUS_PENDING; 4],
+            is_published: false,
+            assignments: vec![],
+        });
+
+        ok_envelope(json!({
+            "proj_id": proj_id,
+            "proj_serial": projset.serial,
+            "projset_index": index,
+        }))
+    }
+}
+
+fn unsupported(method: &str, segments: &[&str]) -> AppError {
+    AppError::Other(format!(
+        "demo_mode: 演示模式不支持该接口: {} {}",
+        method,
+        segments.join("/")
+    ))
+}
+
+// ================== 占位图片 ==================
+
+const PAGE_WIDTH: u32 = 600;
+const PAGE_HEIGHT: u32 = 850;
+
+// 浅色纯色图片，颜色随 seed 变化，便于区分不同页面
+fn placeholder_png(width: u32, height: u32, seed: u32) -> Vec<u8> {
+    let shade = |offset: u32| 200 + ((seed.wrapping_mul(37) + offset) % 48) as u8;
+    let image = RgbImage::from_pixel(width, height, Rgb([shade(0), shade(16), shade(32)]));
+
+    let mut bytes = Cursor::new(Vec::new());
+
+    // 写入内存缓冲区不会失败
+    let _ = image.write_to(&mut bytes, ImageFormat::Png);
+
+    bytes.into_inner()
+}
+
+// 演示页面的图片；不是演示 url 时返回 None
+fn placeholder_page(url: &str) -> Option<Vec<u8>> {
+    let name = url.strip_prefix(FILE_URL_PREFIX)?;
+    let seed = name
+        .bytes()
+        .fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
+
+    Some(placeholder_png(PAGE_WIDTH, PAGE_HEIGHT, seed))
+}
+
+// 图片代理在演示模式下的应答（PNG）；未开启演示模式时返回 None
+pub(crate) fn proxied_image(url: &str) -> Option<Result<Vec<u8>, String>> {
+    if !config().demo_mode {
+        return None;
+    }
+
+    Some(placeholder_page(url).ok_or_else(|| format!("demo_mode: 演示模式下无法获取图片 {}", url)))
+}
+
+// ================== 提供者与开关 ==================
+
+struct DemoProvider {
+    state: Mutex<DemoState>,
+}
+
+impl ApiProvider for DemoProvider {
+    fn respond(&self, request: ProviderRequest<'_>) -> Result<Value, AppError> {
+        let (path, query) = split_path(request.path, request.query);
+        let segments: Vec<&str> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let body = request.body.unwrap_or(Value::Null);
+
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| AppError::Other("demo_mode: 演示数据不可用".to_string()))?;
+
+        match request.backend {
+            Backend::Moetran => state.moetran(request.method, &segments, &query, &body),
+            Backend::Poprako => state.poprako(request.method, &segments, &query, &body),
+        }
+    }
+
+    fn respond_raw(&self, url: &str) -> Result<RawBody, String> {
+        let bytes = placeholder_page(url)
+            .ok_or_else(|| format!("demo_mode: 演示模式下无法获取 {}", url))?;
+
+        Ok(RawBody {
+            bytes,
+            content_type: Some("image/png".to_string()),
+            etag: None,
+            last_modified: None,
+            content_length: None,
+        })
+    }
+}
+
+// 按当前配置安装（或移除）演示数据提供者并替换 token；每次开启都重新生成数据
+fn apply(enabled: bool) {
+    if enabled {
+        set_provider(Some(Arc::new(DemoProvider {
+            state: Mutex::new(seed()),
+        })));
+        use_demo_tokens(Some((DEMO_MOETRAN_TOKEN, DEMO_POPRAKO_TOKEN.as_str())));
+    } else {
+        set_provider(None);
+        use_demo_tokens(None);
+    }
+}
+
+// 启动时配置（设置表或环境变量）已开启演示模式
+pub(crate) fn apply_on_startup() {
+    if config().demo_mode {
+        tracing::info!("demo.enabled_on_startup");
+        apply(true);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetDemoModeReq {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DemoModeReply {
+    pub enabled: bool,
+}
+
+#[tauri::command]
+pub async fn get_demo_mode() -> Result<DemoModeReply, String> {
+    Ok(DemoModeReply {
+        enabled: config().demo_mode,
+    })
+}
+
+// 切换演示模式，无需重启；切换后发送 "demo-mode-changed" 事件，前端据此显示横幅并刷新各视图
+#[tauri::command]
+pub async fn set_demo_mode(
+    app: AppHandle,
+    payload: SetDemoModeReq,
+) -> Result<DemoModeReply, String> {
+    tracing::info!(enabled = payload.enabled, "demo.set.start");
+
+    let mut defer = WarnDefer::new("demo.set");
+
+    let value = if payload.enabled { "true" } else { "false" };
+
+    let changed = set_runtime_value("demo_mode", Some(value)).await?;
+
+    if changed {
+        apply(payload.enabled);
+
+        emit_event(
+            &app,
+            DemoModeChanged {
+                enabled: payload.enabled,
+            },
+        );
+    }
+
+    tracing::info!(enabled = payload.enabled, changed, "demo.set.ok");
+
+    defer.success();
+
+    get_demo_mode().await
+}
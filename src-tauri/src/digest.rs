@@ -0,0 +1,676 @@
+// 汉化组“上次查看以来的变化”摘要：以设置表中保存的基线（上次标记已读时各项目的阶段状态、成员与发布状态，
+// 键为 "digest_baseline.<team_id>"）为准，与本次看到的项目状态比较，得到新项目、新分配、状态变化、
+// 阶段完成与发布几类条目；发布另外合并本机的发布记录。
+// 能联网时拉取一次 enriched 列表作为当前状态；离线或拉取失败时只用最近打开项目的本地快照（network_refresh = false）
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::unix_now,
+    defer::WarnDefer,
+    project::{get_team_projects_enriched, GetTeamProjectsEnrichedReq, ResProjectEnriched},
+    project_history::{diff_snapshots, Change, SnapshotState},
+    publish::{ProjStage, STAGE_STATUS_COMPLETED},
+    storage::{publish_records, recent_projects, settings, LOCAL_STORAGE},
+};
+
+// 设置表中的键前缀，完整键为 "digest_baseline.<team_id>"
+const BASELINE_KEY_PREFIX: &str = "digest_baseline.";
+
+// 拉取的项目数与整体时限；摘要只需第一页
+const FETCH_LIMIT: u32 = 100;
+const FETCH_DEADLINE_MS: u64 = 10_000;
+
+// 从未标记过已读时，发布记录只回溯这么多天
+const FIRST_VISIT_DAYS: i64 = 7;
+
+// 一个项目在某一时刻的状态；None 表示当时不知道（如没有 PopRaKo 信息），比较时跳过
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ProjState {
+    name: String,
+    // 按 ProjStage::ALL 的顺序
+    statuses: [Option<i32>; 4],
+    // user_id -> username
+    members: Option<BTreeMap<String, String>>,
+    is_published: Option<bool>,
+}
+
+impl ProjState {
+    // 摘要只比较阶段状态与发布状态，计数按 0 处理（两侧相同，不产生变化）；成员另行比较以得到具体成员
+    fn snapshot_state(&self) -> SnapshotState {
+        SnapshotState {
+            name: self.name.clone(),
+            statuses: self.statuses,
+            source_count: 0,
+            translated_source_count: 0,
+            checked_source_count: 0,
+            is_published: self.is_published,
+            member_hash: None,
+            member_count: None,
+        }
+    }
+
+    fn from_enriched(proj: &ResProjectEnriched) -> Self {
+        Self {
+            name: proj.name.clone(),
+            statuses: [
+                proj.translating_status,
+                proj.proofreading_status,
+                proj.typesetting_status,
+                proj.reviewing_status,
+            ],
+            members: proj.members.as_ref().map(|members| {
+                members
+                    .iter()
+                    .map(|member| (member.user_id.to_string(), member.username.clone()))
+                    .collect()
+            }),
+            is_published: proj.is_published,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Baseline {
+    seen_at: i64,
+    projects: HashMap<String, ProjState>,
+}
+
+// 当前状态：项目 id -> (状态, 观察到的时间)
+type Observed = HashMap<String, (ProjState, i64)>;
+
+// 最近一次 get_team_digest 看到的状态，mark_digest_seen 时写入基线
+static LAST_OBSERVED: LazyLock<Mutex<HashMap<String, HashMap<String, ProjState>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestKind {
+    NewProject,
+    NewAssignment,
+    StatusChange,
+    // 阶段变为已完成；不再同时出现在 status_change 中
+    Completed,
+    Published,
+}
+
+impl DigestKind {
+    const ALL: [DigestKind; 5] = [
+        DigestKind::NewProject,
+        DigestKind::NewAssignment,
+        DigestKind::StatusChange,
+        DigestKind::Completed,
+        DigestKind::Published,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestEntry {
+    pub proj_id: String,
+    pub proj_name: String,
+    // 发布记录为实际发布时间；其余为观察到变化的时间（拉取时间或本地快照的更新时间）
+    pub at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<ProjStage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+impl DigestEntry {
+    fn new(proj_id: &str, proj_name: &str, at: i64) -> Self {
+        Self {
+            proj_id: proj_id.to_string(),
+            proj_name: proj_name.to_string(),
+            at,
+            stage: None,
+            from_status: None,
+            to_status: None,
+            user_id: None,
+            username: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestCategory {
+    pub kind: DigestKind,
+    // 按时间从新到旧；没有条目时为空数组
+    pub entries: Vec<DigestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamDigest {
+    pub team_id: String,
+    pub since: i64,
+    pub generated_at: i64,
+    // 是否拉取到了最新列表；false 时只比较了本地快照，可能遗漏变化
+    pub network_refresh: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_error: Option<String>,
+    // 从未标记过已读：没有基线可比较，只包含发布记录
+    pub first_visit: bool,
+    // 固定按 DigestKind::ALL 的顺序，每类一项
+    pub categories: Vec<DigestCategory>,
+    pub total: usize,
+}
+
+fn baseline_key(team_id: &str) -> String {
+    format!("{}{}", BASELINE_KEY_PREFIX, team_id)
+}
+
+async fn load_baseline(team_id: &str) -> Result<Option<Baseline>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let Some(raw) = settings::get_setting(storage.pool(), &baseline_key(team_id)).await? else {
+        return Ok(None);
+    };
+
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|err| format!("摘要基线格式错误: {}", err))
+}
+
+async fn save_baseline(team_id: &str, baseline: &Baseline) -> Result<(), String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let raw =
+        serde_json::to_string(baseline).map_err(|err| format!("序列化摘要基线失败: {}", err))?;
+
+    settings::save_setting(storage.pool(), &baseline_key(team_id), &raw).await
+}
+
+// 最近打开项目的本地快照；时间取快照的更新时间
+async fn local_observed(team_id: &str) -> Result<Observed, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = recent_projects::list_recent_projects(storage.pool(), team_id).await?;
+
+    let mut observed = Observed::new();
+
+    for row in rows {
+        let Some(raw) = row.snapshot else {
+            continue;
+        };
+
+        match serde_json::from_str::<ResProjectEnriched>(&raw) {
+            Ok(proj) => {
+                observed.insert(
+                    row.project_id,
+                    (ProjState::from_enriched(&proj), row.last_opened_at),
+                );
+            }
+            Err(err) => {
+                tracing::debug!(project_id = %row.project_id, error = %err, "digest.snapshot.skip");
+            }
+        }
+    }
+
+    Ok(observed)
+}
+
+// 拉取一次 enriched 列表；缺少 PopRaKo 信息或返回的是缓存数据时视为失败，避免把“不知道”当作变化
+async fn fetch_observed(team_id: &str) -> Result<Observed, String> {
+    let reply = get_team_projects_enriched(GetTeamProjectsEnrichedReq {
+        team_id: team_id.into(),
+        page: 1,
+        limit: FETCH_LIMIT,
+        deadline_ms: Some(FETCH_DEADLINE_MS),
+    })
+    .await?;
+
+    if let Some(err) = reply.enrichment_error {
+        return Err(err);
+    }
+
+    if reply.deadline_exceeded || reply.items.iter().any(|proj| proj.stale == Some(true)) {
+        return Err("只取得了缓存数据".to_string());
+    }
+
+    let at = unix_now();
+
+    Ok(reply
+        .items
+        .iter()
+        .map(|proj| (proj.id.to_string(), (ProjState::from_enriched(proj), at)))
+        .collect())
+}
+
+// 基线与当前状态比较；发布状态的变化与发布记录按项目合并，以发布记录的时间为准
+fn compose(
+    baseline: Option<&Baseline>,
+    observed: &Observed,
+    publishes: &[(String, Option<String>, i64)],
+    since: i64,
+) -> Vec<DigestCategory> {
+    let mut entries: HashMap<DigestKind, Vec<DigestEntry>> = HashMap::new();
+    let mut published: HashMap<String, DigestEntry> = HashMap::new();
+
+    if let Some(baseline) = baseline {
+        for (proj_id, (current, at)) in observed {
+            let at = *at;
+            let Some(previous) = baseline.projects.get(proj_id) else {
+                entries
+                    .entry(DigestKind::NewProject)
+                    .or_default()
+                    .push(DigestEntry::new(proj_id, &current.name, at));
+                continue;
+            };
+
+            let changes = diff_snapshots(&previous.snapshot_state(), &current.snapshot_state());
+
+            for change in changes {
+                let (kind, stage, from, to) = match change {
+                    Change::StatusChanged { stage, from, to } => {
+                        (DigestKind::StatusChange, stage, from, to)
+                    }
+                    Change::StageReopened { stage, to } => {
+                        (DigestKind::StatusChange, stage, STAGE_STATUS_COMPLETED, to)
+                    }
+                    Change::StageCompleted { stage, from } => {
+                        (DigestKind::Completed, stage, from, STAGE_STATUS_COMPLETED)
+                    }
+                    Change::Published => {
+                        published.insert(
+                            proj_id.clone(),
+                            DigestEntry::new(proj_id, &current.name, at),
+                        );
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                entries.entry(kind).or_default().push(DigestEntry {
+                    stage: Some(stage),
+                    from_status: Some(from),
+                    to_status: Some(to),
+                    ..DigestEntry::new(proj_id, &current.name, at)
+                });
+            }
+
+            if let (Some(before), Some(after)) = (&previous.members, &current.members) {
+                for (user_id, username) in after {
+                    if before.contains_key(user_id) {
+                        continue;
+                    }
+
+                    entries
+                        .entry(DigestKind::NewAssignment)
+                        .or_default()
+                        .push(DigestEntry {
+                            user_id: Some(user_id.clone()),
+                            username: Some(username.clone()),
+                            ..DigestEntry::new(proj_id, &current.name, at)
+                        });
+                }
+            }
+        }
+    }
+
+    // 发布记录不带汉化组，只保留基线或当前状态中属于该组的项目
+    let team_projects: HashSet<&str> = observed
+        .keys()
+        .chain(
+            baseline
+                .into_iter()
+                .flat_map(|baseline| baseline.projects.keys()),
+        )
+        .map(String::as_str)
+        .collect();
+
+    // 记录按时间从新到旧，同一项目只取最近一次；与状态变化重复时以记录的时间为准
+    let mut recorded = HashSet::new();
+
+    for (proj_id, proj_name, published_at) in publishes {
+        if !team_projects.contains(proj_id.as_str()) || !recorded.insert(proj_id.as_str()) {
+            continue;
+        }
+
+        match published.get_mut(proj_id) {
+            Some(entry) => entry.at = *published_at,
+            None => {
+                let name = proj_name
+                    .clone()
+                    .or_else(|| observed.get(proj_id).map(|(state, _)| state.name.clone()))
+                    .unwrap_or_default();
+
+                published.insert(
+                    proj_id.clone(),
+                    DigestEntry::new(proj_id, &name, *published_at),
+                );
+            }
+        }
+    }
+
+    entries
+        .entry(DigestKind::Published)
+        .or_default()
+        .extend(published.into_values());
+
+    DigestKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let mut list: Vec<DigestEntry> = entries
+                .remove(&kind)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|entry| entry.at >= since)
+                .collect();
+
+            list.sort_by(|a, b| {
+                b.at.cmp(&a.at)
+                    .then_with(|| a.proj_name.cmp(&b.proj_name))
+                    .then_with(|| {
+                        let stage_index = |entry: &DigestEntry| {
+                            entry
+                                .stage
+                                .and_then(|stage| ProjStage::ALL.iter().position(|s| *s == stage))
+                        };
+                        stage_index(a).cmp(&stage_index(b))
+                    })
+                    .then_with(|| a.username.cmp(&b.username))
+            });
+
+            DigestCategory {
+                kind,
+                entries: list,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetTeamDigestReq {
+    pub team_id: String,
+    // 只包含此时间（Unix 秒）之后的条目；不提供时为上次标记已读的时间
+    #[serde(default)]
+    pub since: Option<i64>,
+}
+
+#[tauri::command]
+pub async fn get_team_digest(payload: GetTeamDigestReq) -> Result<TeamDigest, String> {
+    tracing::info!(team_id = %payload.team_id, since = ?payload.since, "digest.get.start");
+
+    let mut defer = WarnDefer::new("digest.get");
+
+    let team_id = payload.team_id;
+    let generated_at = unix_now();
+
+    let baseline = load_baseline(&team_id).await?;
+
+    let since = payload
+        .since
+        .or(baseline.as_ref().map(|baseline| baseline.seen_at))
+        .unwrap_or(generated_at - FIRST_VISIT_DAYS * 86_400);
+
+    let (observed, refresh_error) = match fetch_observed(&team_id).await {
+        Ok(observed) => (observed, None),
+        Err(err) => {
+            tracing::warn!(team_id = %team_id, error = %err, "digest.refresh.failed");
+            (local_observed(&team_id).await?, Some(err))
+        }
+    };
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let publishes = publish_records::list_publishes_since(storage.pool(), since).await?;
+
+    let categories = compose(baseline.as_ref(), &observed, &publishes, since);
+    let total = categories
+        .iter()
+        .map(|category| category.entries.len())
+        .sum();
+
+    if let Ok(mut last) = LAST_OBSERVED.lock() {
+        last.insert(
+            team_id.clone(),
+            observed
+                .into_iter()
+                .map(|(proj_id, (state, _))| (proj_id, state))
+                .collect(),
+        );
+    }
+
+    tracing::info!(
+        team_id = %team_id,
+        total,
+        network_refresh = refresh_error.is_none(),
+        "digest.get.ok"
+    );
+
+    defer.success();
+
+    Ok(TeamDigest {
+        team_id,
+        since,
+        generated_at,
+        network_refresh: refresh_error.is_none(),
+        refresh_error,
+        first_visit: baseline.is_none(),
+        categories,
+        total,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkDigestSeenReq {
+    pub team_id: String,
+}
+
+// 把最近一次 get_team_digest 看到的状态并入基线，并把已读时间设为现在；
+// 本次会话还没有获取过摘要时使用本地快照
+#[tauri::command]
+pub async fn mark_digest_seen(payload: MarkDigestSeenReq) -> Result<i64, String> {
+    tracing::info!(team_id = %payload.team_id, "digest.mark_seen.start");
+
+    let team_id = payload.team_id;
+
+    let observed = LAST_OBSERVED
+        .lock()
+        .ok()
+        .and_then(|mut last| last.remove(&team_id));
+
+    let observed = match observed {
+        Some(observed) => observed,
+        None => local_observed(&team_id)
+            .await?
+            .into_iter()
+            .map(|(proj_id, (state, _))| (proj_id, state))
+            .collect(),
+    };
+
+    // 基线中有而本次没看到的项目（不在第一页或没有本地快照）保留原状态
+    let mut projects = load_baseline(&team_id)
+        .await?
+        .map(|baseline| baseline.projects)
+        .unwrap_or_default();
+    projects.extend(observed);
+
+    let seen_at = unix_now();
+
+    save_baseline(&team_id, &Baseline { seen_at, projects }).await?;
+
+    tracing::info!(team_id = %team_id, seen_at, "digest.mark_seen.ok");
+
+    Ok(seen_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{publish::STAGE_STATUS_WIP, test_support::local_storage};
+
+    fn state(name: &str, statuses: [i32; 4], members: &[&str], published: bool) -> ProjState {
+        ProjState {
+            name: name.to_string(),
+            statuses: statuses.map(Some),
+            members: Some(
+                members
+                    .iter()
+                    .map(|user| (user.to_string(), user.to_uppercase()))
+                    .collect(),
+            ),
+            is_published: Some(published),
+        }
+    }
+
+    fn baseline(projects: &[(&str, ProjState)]) -> Baseline {
+        Baseline {
+            seen_at: 100,
+            projects: projects
+                .iter()
+                .map(|(id, state)| (id.to_string(), state.clone()))
+                .collect(),
+        }
+    }
+
+    fn category(categories: &[DigestCategory], kind: DigestKind) -> &[DigestEntry] {
+        &categories
+            .iter()
+            .find(|category| category.kind == kind)
+            .unwrap()
+            .entries
+    }
+
+    const WIP: i32 = STAGE_STATUS_WIP;
+    const DONE: i32 = STAGE_STATUS_COMPLETED;
+
+    #[test]
+    fn changes_since_the_baseline_are_categorised() {
+        let before = baseline(&[("p1", state("一", [WIP, 0, 0, 0], &["u1"], false))]);
+
+        let observed: Observed = [
+            (
+                "p1".to_string(),
+                (state("一", [DONE, WIP, 0, 0], &["u1", "u2"], true), 200),
+            ),
+            (
+                "p2".to_string(),
+                (state("二", [0, 0, 0, 0], &[], false), 150),
+            ),
+        ]
+        .into();
+
+        let publishes = vec![("p1".to_string(), Some("一".to_string()), 180)];
+
+        let categories = compose(Some(&before), &observed, &publishes, 100);
+
+        let kinds: Vec<DigestKind> = categories.iter().map(|category| category.kind).collect();
+        assert_eq!(kinds, DigestKind::ALL);
+
+        let new_projects = category(&categories, DigestKind::NewProject);
+        assert_eq!(new_projects.len(), 1);
+        assert_eq!(
+            (new_projects[0].proj_id.as_str(), new_projects[0].at),
+            ("p2", 150)
+        );
+
+        let assignments = category(&categories, DigestKind::NewAssignment);
+        assert_eq!(assignments.len(), 1);
+        assert_eq!(assignments[0].username.as_deref(), Some("U2"));
+
+        // 完成的阶段只出现在 completed 中
+        let completed = category(&categories, DigestKind::Completed);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].stage, Some(ProjStage::Translating));
+
+        let changed = category(&categories, DigestKind::StatusChange);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(
+            (
+                changed[0].stage,
+                changed[0].from_status,
+                changed[0].to_status
+            ),
+            (Some(ProjStage::Proofreading), Some(0), Some(WIP))
+        );
+
+        // 发布状态变化与发布记录合并，时间取记录的时间
+        let published = category(&categories, DigestKind::Published);
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].at, 180);
+    }
+
+    #[test]
+    fn first_visit_only_lists_this_teams_publishes() {
+        let observed: Observed =
+            [("p1".to_string(), (state("一", [DONE; 4], &[], true), 200))].into();
+
+        let publishes = vec![
+            ("p1".to_string(), None, 190),
+            ("p1".to_string(), None, 120),
+            ("other-team".to_string(), Some("外组".to_string()), 195),
+            ("p1-old".to_string(), None, 50),
+        ];
+
+        let categories = compose(None, &observed, &publishes, 100);
+
+        let total: usize = categories
+            .iter()
+            .map(|category| category.entries.len())
+            .sum();
+        assert_eq!(total, 1);
+
+        let published = category(&categories, DigestKind::Published);
+        assert_eq!(
+            (published[0].proj_name.as_str(), published[0].at),
+            ("一", 190)
+        );
+    }
+
+    #[test]
+    fn unknown_states_are_not_reported_as_changes() {
+        let mut unknown = state("一", [WIP; 4], &["u1"], false);
+        unknown.members = None;
+        unknown.is_published = None;
+        unknown.statuses = [None; 4];
+
+        let before = baseline(&[("p1", state("一", [WIP; 4], &["u1"], false))]);
+        let observed: Observed = [("p1".to_string(), (unknown, 200))].into();
+
+        let categories = compose(Some(&before), &observed, &[], 100);
+        assert!(categories
+            .iter()
+            .all(|category| category.entries.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn marking_seen_keeps_projects_not_observed_this_time() {
+        local_storage().await;
+        let team_id = "digest-mark-team";
+
+        save_baseline(
+            team_id,
+            &baseline(&[("kept", state("保留", [WIP; 4], &[], false))]),
+        )
+        .await
+        .unwrap();
+
+        let seen_at = mark_digest_seen(MarkDigestSeenReq {
+            team_id: team_id.to_string(),
+        })
+        .await
+        .unwrap();
+
+        let saved = load_baseline(team_id).await.unwrap().unwrap();
+        assert_eq!(saved.seen_at, seen_at);
+        assert!(saved.projects.contains_key("kept"));
+    }
+}
@@ -0,0 +1,148 @@
+// 磁盘空间检查：下载前估算所需空间，不足时拒绝；下载中写入因磁盘已满失败时立即中止。
+// 两种情况都返回 AppError::InsufficientDisk，前端据此提示清理缓存
+use std::path::Path;
+
+use crate::{
+    error::AppError,
+    storage::{cache_metadata::get_all_cached_projects, LOCAL_STORAGE},
+    DATA_DIR,
+};
+
+// 没有历史下载可参考时每页的估算大小
+const DEFAULT_BYTES_PER_PAGE: u64 = 2 * 1024 * 1024;
+// 额外保留的空间，避免把磁盘写满影响系统与数据库
+const SAFETY_MARGIN_BYTES: u64 = 512 * 1024 * 1024;
+// 前端“释放空间”入口对应的清理命令
+pub(crate) const CLEANUP_COMMAND: &str = "delete_file_cache";
+
+// 按历史下载（已完成的缓存项目）的平均每页大小估算；usage stats 只按接口汇总流量，无法得到每页大小
+pub(crate) fn estimate_bytes(pages: usize, history: &[(i64, i64)]) -> u64 {
+    let (files, bytes) = history
+        .iter()
+        .filter(|(files, bytes)| *files > 0 && *bytes > 0)
+        .fold((0u64, 0u64), |(files, total), (f, b)| {
+            (files + *f as u64, total + *b as u64)
+        });
+
+    let per_page = bytes.checked_div(files).unwrap_or(DEFAULT_BYTES_PER_PAGE);
+
+    per_page.saturating_mul(pages as u64)
+}
+
+pub(crate) fn insufficient_disk_error(required: u64, available: u64) -> AppError {
+    AppError::InsufficientDisk {
+        required,
+        available,
+    }
+}
+
+// 写入失败是否因为磁盘已满
+pub(crate) fn is_disk_full(err: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    const DISK_FULL_CODES: &[i32] = &[28]; // ENOSPC
+    #[cfg(windows)]
+    const DISK_FULL_CODES: &[i32] = &[39, 112]; // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+    #[cfg(not(any(unix, windows)))]
+    const DISK_FULL_CODES: &[i32] = &[];
+
+    err.raw_os_error()
+        .is_some_and(|code| DISK_FULL_CODES.contains(&code))
+}
+
+// DATA_DIR 所在卷的可用空间
+pub(crate) fn available_space() -> std::io::Result<u64> {
+    fs4::available_space(Path::new(&*DATA_DIR))
+}
+
+async fn download_history() -> Vec<(i64, i64)> {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return vec![];
+    };
+
+    match get_all_cached_projects(storage.pool()).await {
+        Ok(projects) => projects
+            .into_iter()
+            .filter(|p| p.status == "completed")
+            .map(|p| (p.file_count, p.total_size_bytes))
+            .collect(),
+        Err(err) => {
+            tracing::warn!(error = %err, "disk_space.history.failed");
+            vec![]
+        }
+    }
+}
+
+pub(crate) async fn estimate_download_bytes(pages: usize) -> u64 {
+    estimate_bytes(pages, &download_history().await)
+}
+
+// 下载 pages 页之前检查空间；无法获取可用空间时放行
+pub(crate) async fn ensure_disk_space(pages: usize) -> Result<(), AppError> {
+    if pages == 0 {
+        return Ok(());
+    }
+
+    let required = estimate_download_bytes(pages).await;
+
+    let available = match available_space() {
+        Ok(available) => available,
+        Err(err) => {
+            tracing::warn!(error = %err, "disk_space.available.failed");
+            return Ok(());
+        }
+    };
+
+    tracing::debug!(pages, required, available, "disk_space.check");
+
+    if required.saturating_add(SAFETY_MARGIN_BYTES) > available {
+        tracing::warn!(pages, required, available, "disk_space.insufficient");
+        return Err(insufficient_disk_error(required, available));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_uses_average_page_size_of_past_downloads() {
+        assert_eq!(estimate_bytes(3, &[]), 3 * DEFAULT_BYTES_PER_PAGE);
+
+        // 空项目与大小未知的记录不计入平均
+        let history = [(10, 1_000), (0, 500), (30, 3_000), (5, 0)];
+        assert_eq!(estimate_bytes(7, &history), 700);
+
+        assert_eq!(estimate_bytes(0, &history), 0);
+        assert_eq!(estimate_bytes(usize::MAX, &[(1, i64::MAX)]), u64::MAX);
+    }
+
+    #[test]
+    fn insufficient_space_error_names_the_cleanup_command() {
+        let error = insufficient_disk_error(4096, 1024);
+        assert!(error.is_insufficient_disk());
+
+        let body = serde_json::to_value(&error).unwrap();
+        assert_eq!(body["kind"], "InsufficientDisk");
+        assert_eq!(body["required"], 4096);
+        assert_eq!(body["available"], 1024);
+        assert_eq!(body["cleanup_command"], CLEANUP_COMMAND);
+    }
+
+    #[test]
+    fn only_disk_full_errors_are_detected() {
+        #[cfg(unix)]
+        assert!(is_disk_full(&std::io::Error::from_raw_os_error(28)));
+        #[cfg(windows)]
+        assert!(is_disk_full(&std::io::Error::from_raw_os_error(112)));
+
+        assert!(!is_disk_full(&std::io::Error::from_raw_os_error(2)));
+        assert!(!is_disk_full(&std::io::Error::other("disk full")));
+    }
+
+    #[tokio::test]
+    async fn empty_download_needs_no_check() {
+        assert!(ensure_disk_space(0).await.is_ok());
+    }
+}
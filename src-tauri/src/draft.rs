@@ -0,0 +1,304 @@
+// 翻译草稿：编辑器防抖保存，打开页面时恢复；全部为本地操作，不发起网络请求
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    config::config,
+    error::AppError,
+    project::MoetranSource,
+    storage::{translation_drafts, LOCAL_STORAGE},
+};
+
+// 清理超过保留期的草稿（启动时调用）
+pub(crate) async fn prune_expired_drafts() -> Result<(), String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    // 保留天数见配置项 draft_retention_days
+    let cutoff =
+        OffsetDateTime::now_utc().unix_timestamp() - config().draft_retention_days * 24 * 3600;
+
+    let removed = translation_drafts::prune_drafts_before(storage.pool(), cutoff).await?;
+
+    if removed > 0 {
+        tracing::info!(removed, "translation.draft.prune.expired");
+    }
+
+    Ok(())
+}
+
+// 根据刚获取到的 sources 标注 has_draft，并清理该页已不存在的 source 的草稿
+pub(crate) async fn annotate_page_drafts(
+    file_id: &str,
+    target_id: &str,
+    sources: &mut [MoetranSource],
+) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let drafts = match translation_drafts::get_file_drafts(storage.pool(), file_id, target_id).await
+    {
+        Ok(drafts) => drafts,
+        Err(err) => {
+            tracing::warn!(%file_id, %target_id, error = %err, "translation.draft.annotate.failed");
+            return;
+        }
+    };
+
+    if drafts.is_empty() {
+        return;
+    }
+
+    let drafted: HashSet<&str> = drafts.iter().map(|d| d.source_id.as_str()).collect();
+
+    for source in sources.iter_mut() {
+        source.has_draft = drafted.contains(source.id.as_str());
+    }
+
+    let existing: HashSet<&str> = sources.iter().map(|s| s.id.as_str()).collect();
+
+    for draft in drafts
+        .iter()
+        .filter(|d| !existing.contains(d.source_id.as_str()))
+    {
+        tracing::info!(source_id = %draft.source_id, %target_id, "translation.draft.prune.orphan");
+
+        if let Err(err) = translation_drafts::delete_translation_draft(
+            storage.pool(),
+            &draft.source_id,
+            target_id,
+        )
+        .await
+        {
+            tracing::warn!(source_id = %draft.source_id, error = %err, "translation.draft.prune.failed");
+        }
+    }
+}
+
+// 提交 / 更新翻译成功后清除对应草稿；失败只记录日志，不影响提交结果
+pub(crate) async fn clear_draft_after_submit(source_id: &str, target_id: &str) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    if let Err(err) =
+        translation_drafts::delete_translation_draft(storage.pool(), source_id, target_id).await
+    {
+        tracing::warn!(%source_id, %target_id, error = %err, "translation.draft.clear.failed");
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SaveTranslationDraftReq {
+    pub source_id: String,
+    pub target_id: String,
+    // 用于按页恢复草稿
+    pub file_id: String,
+    pub content: String,
+}
+
+#[tauri::command]
+pub async fn save_translation_draft(payload: SaveTranslationDraftReq) -> Result<(), String> {
+    tracing::debug!(
+        source_id = %payload.source_id,
+        target_id = %payload.target_id,
+        content_len = payload.content.len(),
+        "translation.draft.save.start"
+    );
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    translation_drafts::save_translation_draft(
+        storage.pool(),
+        &payload.source_id,
+        &payload.target_id,
+        &payload.file_id,
+        &payload.content,
+    )
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetTranslationDraftsReq {
+    pub file_id: String,
+    pub target_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranslationDraftItem {
+    pub source_id: String,
+    pub target_id: String,
+    pub content: String,
+    pub updated_at: i64,
+}
+
+#[tauri::command]
+pub async fn get_translation_drafts(
+    payload: GetTranslationDraftsReq,
+) -> Result<Vec<TranslationDraftItem>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows =
+        translation_drafts::get_file_drafts(storage.pool(), &payload.file_id, &payload.target_id)
+            .await?;
+
+    tracing::debug!(
+        file_id = %payload.file_id,
+        target_id = %payload.target_id,
+        count = rows.len(),
+        "translation.draft.list.ok"
+    );
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TranslationDraftItem {
+            source_id: row.source_id,
+            target_id: row.target_id,
+            content: row.content,
+            updated_at: row.updated_at,
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClearTranslationDraftReq {
+    pub source_id: String,
+    pub target_id: String,
+}
+
+#[tauri::command]
+pub async fn clear_translation_draft(payload: ClearTranslationDraftReq) -> Result<bool, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    translation_drafts::delete_translation_draft(
+        storage.pool(),
+        &payload.source_id,
+        &payload.target_id,
+    )
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurgeOldDraftsReq {
+    // 删除最后修改早于该天数的草稿，0 表示删除此前保存的全部草稿；
+    // 缺省时使用配置项 draft_retention_days
+    #[serde(default)]
+    pub days: Option<i64>,
+}
+
+const SECS_PER_DAY: i64 = 24 * 3600;
+
+// now 之前 days 天的时间戳；天数为负或大到溢出时报错
+fn purge_cutoff(now: i64, days: i64) -> Result<i64, AppError> {
+    if days < 0 {
+        return Err(AppError::InvalidInput(format!("天数不能为负数: {}", days)));
+    }
+
+    days.checked_mul(SECS_PER_DAY)
+        .and_then(|secs| now.checked_sub(secs))
+        .ok_or_else(|| AppError::InvalidInput(format!("天数过大: {}", days)))
+}
+
+// 手动清理旧草稿，返回删除条数
+#[tauri::command]
+pub async fn purge_old_drafts(payload: PurgeOldDraftsReq) -> Result<u64, AppError> {
+    let days = payload
+        .days
+        .unwrap_or_else(|| config().draft_retention_days);
+
+    let cutoff = purge_cutoff(OffsetDateTime::now_utc().unix_timestamp(), days)?;
+
+    tracing::info!(days, "translation.draft.purge.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or_else(|| AppError::Storage("LOCAL_STORAGE not initialized".to_string()))?;
+
+    let removed = translation_drafts::prune_drafts_before(storage.pool(), cutoff)
+        .await
+        .map_err(AppError::Storage)?;
+
+    tracing::info!(days, removed, "translation.draft.purge.ok");
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::local_storage;
+
+    const NOW: i64 = 1_760_000_000;
+
+    #[test]
+    fn cutoff_counts_back_whole_days() {
+        assert_eq!(purge_cutoff(NOW, 0).unwrap(), NOW);
+        assert_eq!(purge_cutoff(NOW, 30).unwrap(), NOW - 30 * SECS_PER_DAY);
+    }
+
+    #[test]
+    fn cutoff_rejects_negative_and_overflowing_days() {
+        assert!(matches!(
+            purge_cutoff(NOW, -1),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            purge_cutoff(NOW, i64::MAX),
+            Err(AppError::InvalidInput(_))
+        ));
+        // 刚好不溢出时得到很早的时间戳（不会删除任何草稿）
+        assert!(purge_cutoff(NOW, i64::MAX / SECS_PER_DAY).unwrap() < 0);
+    }
+
+    #[tokio::test]
+    async fn purge_removes_only_drafts_older_than_cutoff() {
+        let pool = local_storage().await.pool();
+
+        for source_id in ["s-purge-old", "s-purge-new"] {
+            translation_drafts::save_translation_draft(
+                pool, source_id, "t-purge", "f-purge", "草稿",
+            )
+            .await
+            .unwrap();
+        }
+
+        sqlx::query(
+            "UPDATE translation_drafts SET updated_at = updated_at - ? WHERE source_id = ?",
+        )
+        .bind(10 * SECS_PER_DAY)
+        .bind("s-purge-old")
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let removed = purge_old_drafts(PurgeOldDraftsReq { days: Some(7) })
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+
+        let left: Vec<String> = translation_drafts::get_file_drafts(pool, "f-purge", "t-purge")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|draft| draft.source_id)
+            .collect();
+        assert_eq!(left, ["s-purge-new"]);
+
+        let err = purge_old_drafts(PurgeOldDraftsReq {
+            days: Some(i64::MAX),
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), "InvalidInput");
+    }
+}
@@ -0,0 +1,381 @@
+// 列表响应的 DTO 校验：列表接口按元素宽松解析（filter_map），字段改名时会静默丢行。
+// 校验模式下（调试构建默认开启，或设置 strict_dto_validation）额外按严格 DTO 反序列化每个元素，
+// 失败时记录精确的 JSON 路径并累计计数，供诊断页展示；宽松解析的结果不受影响
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::config::config;
+
+// 每类列表保留的最近失败条目数
+const RECENT_FAILURE_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DtoFailure {
+    // 元素在列表中的下标
+    pub index: usize,
+    // 出错位置的 JSON 路径，如 "cover_url"、"target.id"；缺少字段时为所在对象的路径（顶层为 "."）
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+struct DtoCounter {
+    checked: u64,
+    failed: u64,
+    lenient_dropped: u64,
+    recent: VecDeque<DtoFailure>,
+}
+
+// key: 列表名称（如 "项目文件"）
+static DTO_COUNTERS: LazyLock<Mutex<BTreeMap<&'static str, DtoCounter>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+fn record(label: &'static str, checked: u64, dropped: u64, failures: Vec<DtoFailure>) {
+    if let Ok(mut guard) = DTO_COUNTERS.lock() {
+        let counter = guard.entry(label).or_default();
+        counter.checked += checked;
+        counter.failed += failures.len() as u64;
+        counter.lenient_dropped += dropped;
+
+        for failure in failures {
+            if counter.recent.len() == RECENT_FAILURE_LIMIT {
+                counter.recent.pop_front();
+            }
+            counter.recent.push_back(failure);
+        }
+    }
+}
+
+// 宽松解析列表：每个元素交给 lenient 转换，返回 None 的元素丢弃（与原 filter_map 行为一致）。
+// 校验模式下同时尝试按 S 严格反序列化，失败的元素记录路径与原因
+pub(crate) fn parse_list_lenient_with_report<S, T>(
+    label: &'static str,
+    raw: Vec<Value>,
+    mut lenient: impl FnMut(Value) -> Option<T>,
+) -> Vec<T>
+where
+    S: DeserializeOwned,
+{
+    let strict = config().strict_dto_validation;
+
+    let total = raw.len();
+    let mut failures = Vec::new();
+    let mut result = Vec::with_capacity(total);
+
+    for (index, value) in raw.into_iter().enumerate() {
+        if strict {
+            if let Err(err) = serde_path_to_error::deserialize::<_, S>(&value) {
+                let failure = DtoFailure {
+                    index,
+                    path: err.path().to_string(),
+                    message: err.inner().to_string(),
+                };
+
+                tracing::warn!(
+                    label,
+                    index,
+                    path = %failure.path,
+                    error = %failure.message,
+                    "dto.strict.failed"
+                );
+
+                failures.push(failure);
+            }
+        }
+
+        if let Some(item) = lenient(value) {
+            result.push(item);
+        }
+    }
+
+    let dropped = (total - result.len()) as u64;
+
+    if dropped > 0 {
+        tracing::warn!(label, dropped, total, "dto.lenient.dropped");
+    }
+
+    if strict {
+        record(label, total as u64, dropped, failures);
+    } else if dropped > 0 {
+        record(label, 0, dropped, Vec::new());
+    }
+
+    result
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DtoValidationStat {
+    pub label: &'static str,
+    // 参与严格校验的元素数
+    pub checked: u64,
+    // 严格校验失败的元素数
+    pub failed: u64,
+    // 宽松解析中被丢弃的元素数（不论是否开启校验都会统计）
+    pub lenient_dropped: u64,
+    pub summary: String,
+    pub recent_failures: Vec<DtoFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DtoValidationReport {
+    pub enabled: bool,
+    pub lists: Vec<DtoValidationStat>,
+}
+
+// 本次会话的校验统计，供诊断页展示
+#[tauri::command]
+pub async fn get_dto_validation_report() -> Result<DtoValidationReport, String> {
+    let guard = DTO_COUNTERS
+        .lock()
+        .map_err(|_| "dto counters lock poisoned".to_string())?;
+
+    let lists = guard
+        .iter()
+        .map(|(label, counter)| DtoValidationStat {
+            label,
+            checked: counter.checked,
+            failed: counter.failed,
+            lenient_dropped: counter.lenient_dropped,
+            summary: format!(
+                "本次会话中有 {} 个{}未通过严格解析（共校验 {} 个，宽松解析丢弃 {} 个）",
+                counter.failed, label, counter.checked, counter.lenient_dropped
+            ),
+            recent_failures: counter.recent.iter().cloned().collect(),
+        })
+        .collect();
+
+    Ok(DtoValidationReport {
+        enabled: config().strict_dto_validation,
+        lists,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        config,
+        project::{
+            get_project_files, get_project_targets, GetProjectFilesReq, GetProjectTargetsReq,
+        },
+        test_support::MockBackends,
+    };
+
+    // Moetran 列表响应的样例（当前格式的一项，加上字段改名 / 类型变化的项）
+    const PROJECT_FILES_FIXTURE: &str =
+        include_str!("../tests/fixtures/moetran/project_files.json");
+    const PROJECT_TARGETS_FIXTURE: &str =
+        include_str!("../tests/fixtures/moetran/project_targets.json");
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct StrictItem {
+        id: String,
+        count: u64,
+    }
+
+    fn fixture(raw: &str) -> Vec<Value> {
+        serde_json::from_str(raw).unwrap()
+    }
+
+    // 缺少字段时路径为所在对象（顶层为 "."），字段名在 message 中
+    fn counter(label: &str) -> (u64, u64, u64, Vec<(usize, String)>) {
+        let guard = DTO_COUNTERS.lock().unwrap();
+
+        guard
+            .get(label)
+            .map(|counter| {
+                (
+                    counter.checked,
+                    counter.failed,
+                    counter.lenient_dropped,
+                    counter
+                        .recent
+                        .iter()
+                        .map(
+                            |failure| match failure.message.strip_prefix("missing field ") {
+                                Some(field) => {
+                                    (failure.index, format!("{} {}", failure.path, field))
+                                }
+                                None => (failure.index, failure.path.clone()),
+                            },
+                        )
+                        .collect(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn set_strict(strict: bool) {
+        config::update_for_test(|config| config.strict_dto_validation = strict);
+    }
+
+    #[tokio::test]
+    async fn strict_mode_reports_paths_without_changing_lenient_result() {
+        let _backends = MockBackends::start().await;
+        set_strict(true);
+
+        let raw = vec![
+            json!({ "id": "a", "count": 1 }),
+            json!({ "id": "b", "count": "2" }),
+            json!({ "count": 3 }),
+        ];
+
+        let ids = parse_list_lenient_with_report::<StrictItem, _>("测试列表", raw, |v| {
+            v.get("id")?.as_str().map(str::to_string)
+        });
+
+        set_strict(false);
+
+        assert_eq!(ids, ["a", "b"]);
+
+        let (checked, failed, dropped, recent) = counter("测试列表");
+
+        assert_eq!((checked, failed, dropped), (3, 2, 1));
+        assert_eq!(
+            recent,
+            [(1, "count".to_string()), (2, ". `id`".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn lenient_mode_only_counts_dropped_items() {
+        let _backends = MockBackends::start().await;
+        set_strict(false);
+
+        let raw = vec![json!({ "id": "a", "count": "x" }), json!({})];
+
+        let ids = parse_list_lenient_with_report::<StrictItem, _>("宽松列表", raw, |v| {
+            v.get("id")?.as_str().map(str::to_string)
+        });
+
+        assert_eq!(ids, ["a"]);
+        assert_eq!(counter("宽松列表"), (0, 0, 1, Vec::new()));
+    }
+
+    #[tokio::test]
+    async fn recent_failures_are_capped() {
+        let _backends = MockBackends::start().await;
+        set_strict(true);
+
+        let raw = (0..RECENT_FAILURE_LIMIT + 5)
+            .map(|n| json!({ "id": n.to_string() }))
+            .collect();
+
+        parse_list_lenient_with_report::<StrictItem, _>("上限列表", raw, Some);
+
+        set_strict(false);
+
+        let (checked, failed, _, recent) = counter("上限列表");
+
+        assert_eq!(checked, RECENT_FAILURE_LIMIT as u64 + 5);
+        assert_eq!(failed, RECENT_FAILURE_LIMIT as u64 + 5);
+        assert_eq!(recent.len(), RECENT_FAILURE_LIMIT);
+        // 保留最新的失败
+        assert_eq!(recent[0].0, 5);
+    }
+
+    #[tokio::test]
+    async fn project_files_fixture_drift_is_reported() {
+        let backends = MockBackends::start().await;
+        set_strict(true);
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/dto-proj/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture(PROJECT_FILES_FIXTURE)))
+            .mount(&backends.moetran)
+            .await;
+
+        let (checked_before, failed_before, dropped_before, _) = counter("项目文件");
+
+        let files = get_project_files(GetProjectFilesReq {
+            project_id: "dto-proj".into(),
+            target_id: None,
+            sort: None,
+        })
+        .await
+        .unwrap();
+
+        set_strict(false);
+
+        // 宽松解析：改名的项被丢弃，缺少 url 的项标记为 broken
+        let parsed: Vec<(&str, bool)> = files
+            .iter()
+            .map(|file| (file.id.as_str(), file.broken))
+            .collect();
+        assert_eq!(parsed, [("f-current", false), ("f-no-url", true)]);
+        assert_eq!(files[0].width, Some(1200));
+
+        let (checked, failed, dropped, recent) = counter("项目文件");
+
+        assert_eq!(checked - checked_before, 3);
+        assert_eq!(failed - failed_before, 2);
+        assert_eq!(dropped - dropped_before, 1);
+        assert_eq!(
+            recent[recent.len() - 2..],
+            [(1, ". `name`".to_string()), (2, "url".to_string())]
+        );
+
+        let report = get_dto_validation_report().await.unwrap();
+        let stat = report
+            .lists
+            .iter()
+            .find(|stat| stat.label == "项目文件")
+            .unwrap();
+
+        assert!(stat.summary.contains(&format!("有 {} 个项目文件", failed)));
+    }
+
+    #[tokio::test]
+    async fn project_targets_fixture_drift_is_reported() {
+        let backends = MockBackends::start().await;
+        set_strict(true);
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/dto-proj/targets"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(fixture(PROJECT_TARGETS_FIXTURE)),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        let (_, failed_before, _, _) = counter("项目 target");
+
+        let targets = get_project_targets(GetProjectTargetsReq {
+            project_id: "dto-proj".into(),
+        })
+        .await
+        .unwrap();
+
+        set_strict(false);
+
+        let parsed: Vec<(&str, u64)> = targets
+            .iter()
+            .map(|target| (target.id.as_str(), target.translated_source_count))
+            .collect();
+        assert_eq!(parsed, [("t-current", 40), ("t-string-count", 0)]);
+
+        let (_, failed, _, recent) = counter("项目 target");
+
+        assert_eq!(failed - failed_before, 2);
+        assert_eq!(
+            recent[recent.len() - 2..],
+            [
+                (1, "translated_source_count".to_string()),
+                (2, ". `id`".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+// 空白译文检查：误按回车提交的空译文会被计入“已翻译”，掩盖真正未翻译的气泡。
+// 扫描项目某 target 下全部翻译，列出内容（或非空的校对内容）为空白的条目，判定与提交时的拦截一致；
+// 附带文件、source 与作者信息，前端可据此用 update_translation 补写，或删除对应的 source
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    normalize::is_blank,
+    project::{MoetranSource, MoetranTranslation, MoetranUserBrief},
+    text_stats::load_project_sources,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyField {
+    Content,
+    ProofreadContent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmptyTranslation {
+    pub file_id: String,
+    pub file_name: String,
+    // 文件在项目中的顺序（从 0 开始）
+    pub file_index: usize,
+    pub source_id: String,
+    pub translation_id: String,
+    pub field: EmptyField,
+    pub selected: bool,
+    pub user: Option<MoetranUserBrief>,
+    // 同一 source 上是否还有非空白的翻译（有则通常直接删除这条即可）
+    pub has_other_content: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmptyTranslationsReport {
+    pub project_id: String,
+    pub target_id: String,
+    pub file_count: usize,
+    pub source_count: usize,
+    pub translations: Vec<EmptyTranslation>,
+    // 拉取失败、改用本地快照的文件（结果可能不是最新的）
+    pub stale_files: Vec<String>,
+}
+
+// my_translation 通常也出现在 translations 中，按 id 去重
+fn source_translations(source: &MoetranSource) -> Vec<&MoetranTranslation> {
+    let mut seen = HashSet::new();
+
+    source
+        .translations
+        .iter()
+        .chain(source.my_translation.as_ref())
+        .filter(|translation| seen.insert(translation.id.as_str()))
+        .collect()
+}
+
+// 空字符串的校对内容表示未校对，不算空白
+fn empty_field(translation: &MoetranTranslation) -> Option<EmptyField> {
+    if is_blank(&translation.content) {
+        return Some(EmptyField::Content);
+    }
+
+    translation
+        .proofread_content
+        .as_deref()
+        .filter(|proof| !proof.is_empty() && is_blank(proof))
+        .map(|_| EmptyField::ProofreadContent)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FindEmptyTranslationsReq {
+    pub project_id: String,
+    pub target_id: String,
+}
+
+#[tauri::command]
+pub async fn find_empty_translations(
+    payload: FindEmptyTranslationsReq,
+) -> Result<EmptyTranslationsReport, String> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        "moetran.project.empty_translations.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.project.empty_translations");
+
+    let files = load_project_sources(&payload.project_id, &payload.target_id).await?;
+
+    let mut report = EmptyTranslationsReport {
+        project_id: payload.project_id.clone(),
+        target_id: payload.target_id.clone(),
+        file_count: files.len(),
+        source_count: 0,
+        translations: Vec::new(),
+        stale_files: Vec::new(),
+    };
+
+    for (file_index, file) in files.iter().enumerate() {
+        report.source_count += file.sources.len();
+
+        if file.stale {
+            report.stale_files.push(file.file_id.clone());
+        }
+
+        for source in &file.sources {
+            let translations = source_translations(source);
+
+            let non_blank = translations
+                .iter()
+                .filter(|translation| !is_blank(&translation.content))
+                .count();
+
+            for translation in translations {
+                let Some(field) = empty_field(translation) else {
+                    continue;
+                };
+
+                let has_other_content = match field {
+                    EmptyField::Content => non_blank > 0,
+                    EmptyField::ProofreadContent => true,
+                };
+
+                report.translations.push(EmptyTranslation {
+                    file_id: file.file_id.clone(),
+                    file_name: file.file_name.clone(),
+                    file_index,
+                    source_id: source.id.to_string(),
+                    translation_id: translation.id.to_string(),
+                    field,
+                    selected: translation.selected,
+                    user: translation.user.clone(),
+                    has_other_content,
+                });
+            }
+        }
+    }
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        found = report.translations.len(),
+        stale_files = report.stale_files.len(),
+        "moetran.project.empty_translations.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    fn translation(id: &str, content: &str, proofread: Value) -> Value {
+        json!({
+            "id": id,
+            "content": content,
+            "proofread_content": proofread,
+            "selected": false,
+            "user": { "id": "u1", "name": "译者" },
+        })
+    }
+
+    fn parse(value: Value) -> MoetranTranslation {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn blank_content_or_proofread_is_detected() {
+        let field = |content, proofread| empty_field(&parse(translation("t", content, proofread)));
+
+        assert_eq!(field(" \u{200B}\n", Value::Null), Some(EmptyField::Content));
+        assert_eq!(field("", json!("校对")), Some(EmptyField::Content));
+        assert_eq!(
+            field("译文", json!("\u{3000}")),
+            Some(EmptyField::ProofreadContent)
+        );
+        // 空字符串的校对内容表示未校对
+        assert_eq!(field("译文", json!("")), None);
+        assert_eq!(field("译文", Value::Null), None);
+    }
+
+    #[test]
+    fn own_translation_is_not_listed_twice() {
+        let own = translation("t1", "", Value::Null);
+        let source: MoetranSource = serde_json::from_value(json!({
+            "id": "s1", "x": 0.5, "y": 0.5, "position_type": 1,
+            "my_translation": own,
+            "translations": [own, translation("t2", "有内容", Value::Null)],
+        }))
+        .unwrap();
+
+        let ids: Vec<&str> = source_translations(&source)
+            .iter()
+            .map(|translation| translation.id.as_str())
+            .collect();
+        assert_eq!(ids, ["t1", "t2"]);
+    }
+
+    #[tokio::test]
+    async fn report_lists_blank_entries_with_their_context() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/empty-proj/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": "ef1", "name": "1.png", "source_count": 2, "url": "", "cover_url": "" },
+            ])))
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/files/ef1/sources"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "id": "s1", "x": 0.1, "y": 0.1, "position_type": 1, "my_translation": null,
+                    "translations": [
+                        translation("t1", "  ", Value::Null),
+                        translation("t2", "正常", json!(" ")),
+                    ],
+                },
+                {
+                    "id": "s2", "x": 0.2, "y": 0.2, "position_type": 1, "my_translation": null,
+                    "translations": [translation("t3", "\n", Value::Null)],
+                },
+            ])))
+            .mount(&backends.moetran)
+            .await;
+
+        let report = find_empty_translations(FindEmptyTranslationsReq {
+            project_id: "empty-proj".to_string(),
+            target_id: "empty-target".to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!((report.file_count, report.source_count), (1, 2));
+        assert!(report.stale_files.is_empty());
+
+        let found: Vec<(&str, &str, EmptyField, bool)> = report
+            .translations
+            .iter()
+            .map(|entry| {
+                (
+                    entry.source_id.as_str(),
+                    entry.translation_id.as_str(),
+                    entry.field,
+                    entry.has_other_content,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            found,
+            [
+                ("s1", "t1", EmptyField::Content, true),
+                ("s1", "t2", EmptyField::ProofreadContent, true),
+                ("s2", "t3", EmptyField::Content, false),
+            ]
+        );
+        assert_eq!(report.translations[0].file_name, "1.png");
+    }
+}
@@ -0,0 +1,325 @@
+// 命令与 HTTP 层共用的错误类型。以 { kind, message, ... } 对象传给前端，前端可按 kind 区分
+// 网络故障、登录过期、PopRaKo 业务错误等；message 与原先的字符串错误相同，按字符串识别错误的旧代码不受影响。
+// 尚未迁移的命令仍返回 String（经 Display 转换）
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::{
+    connectivity::Backend,
+    disk_space::CLEANUP_COMMAND,
+    image_cache::DownloadProgress,
+    impact_check::DeleteImpact,
+    name_guard::{NameMatch, NamedEntityKind},
+    validation::ValidationErrors,
+};
+
+// 离线模式错误的固定前缀，前端据此显示“当前处于离线模式”
+const OFFLINE_ERROR_CODE: &str = "offline_mode";
+const DEADLINE_EXCEEDED_CODE: &str = "deadline_exceeded";
+const DELETE_BLOCKED_MESSAGE: &str = "该操作会影响关联数据，请确认后使用 force 重试";
+
+#[derive(Debug, Clone)]
+pub enum AppError {
+    // 请求未能送达（连接失败、超时等）
+    Network(String),
+    // 401：token 无效或已过期，需要重新登录
+    AuthExpired {
+        backend: Backend,
+        body: String,
+    },
+    // PopRaKo 返回成功状态码，但响应中的 code 表示失败
+    PoprakoBusiness {
+        code: u16,
+        message: String,
+    },
+    // Moetran 返回的其余非 2xx 响应
+    MoetranHttp {
+        status: u16,
+        body: String,
+    },
+    // PopRaKo 返回的其余非 2xx 响应
+    PoprakoHttp {
+        status: u16,
+        body: String,
+    },
+    // 服务端返回维护页 / 网关错误页等非 JSON 内容，视为后端暂不可用
+    ServiceUnavailable {
+        status: u16,
+        retry_after: Option<u64>,
+        excerpt: String,
+    },
+    // 已开启离线模式，请求未发出
+    Offline,
+    // 组合命令的整体时限已用尽（见 request_budget），请求未发出或被中止
+    DeadlineExceeded,
+    // 422 且响应体可解析为字段错误（见 validation）
+    Validation(ValidationErrors),
+    // 本地数据库读写失败
+    Storage(String),
+    // 命令参数未通过检查
+    InvalidInput(String),
+    // 请求的资源不存在（如 Moetran 对项目返回 404）
+    NotFound(String),
+    // 后端以 409 拒绝操作（如项目中仍有未校对的原文），需用户处理后再试
+    Conflict(String),
+    // 删除 / 移除会孤立关联数据且未确认（force），附带影响摘要供前端渲染确认对话框
+    DeleteBlocked(Box<DeleteImpact>),
+    // 创建项目集 / 项目时已存在同名实体且未允许重名，附带已有实体供前端提供“打开已有的”
+    DuplicateName(Box<NameMatch>),
+    // 磁盘空间不足（下载前估算不足或写入时磁盘已满），附带所需与可用字节数；前端据此提示清理缓存
+    InsufficientDisk {
+        required: u64,
+        available: u64,
+    },
+    // 同一项目的下载任务已在进行中（见 image_cache），附带该任务的当前进度
+    AlreadyInProgress {
+        project_id: String,
+        progress: DownloadProgress,
+    },
+    // 写操作因连接类错误失败，已登记重试（见 retry）；前端可凭 retry_token 调用 retry_command 重新执行。
+    // message 与原错误相同，cause 为原错误的类别
+    Retryable {
+        command: String,
+        retry_token: String,
+        source: Box<AppError>,
+    },
+    // 其余错误（JSON 解析失败、演示模式拒绝等）
+    Other(String),
+    // 附加了上下文说明的错误；kind 取内部错误的类别
+    Context {
+        context: String,
+        source: Box<AppError>,
+    },
+}
+
+impl AppError {
+    // 在错误信息前加上说明，保留错误类别
+    pub fn context(self, context: impl Into<String>) -> Self {
+        AppError::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    // 去掉上下文说明后的原始错误
+    pub fn root(&self) -> &AppError {
+        match self {
+            AppError::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self.root() {
+            AppError::Network(_) => "Network",
+            AppError::AuthExpired { .. } => "AuthExpired",
+            AppError::PoprakoBusiness { .. } => "PoprakoBusiness",
+            AppError::MoetranHttp { .. } => "MoetranHttp",
+            AppError::PoprakoHttp { .. } => "PoprakoHttp",
+            AppError::ServiceUnavailable { .. } => "ServiceUnavailable",
+            AppError::Offline => "Offline",
+            AppError::DeadlineExceeded => "DeadlineExceeded",
+            AppError::Validation(_) => "Validation",
+            AppError::Storage(_) => "Storage",
+            AppError::InvalidInput(_) => "InvalidInput",
+            AppError::NotFound(_) => "NotFound",
+            AppError::Conflict(_) => "Conflict",
+            AppError::DeleteBlocked(_) => "DeleteBlocked",
+            AppError::DuplicateName(_) => "DuplicateName",
+            AppError::InsufficientDisk { .. } => "InsufficientDisk",
+            AppError::AlreadyInProgress { .. } => "AlreadyInProgress",
+            AppError::Retryable { .. } => "Retryable",
+            AppError::Other(_) | AppError::Context { .. } => "Other",
+        }
+    }
+
+    pub fn is_network(&self) -> bool {
+        matches!(self.root(), AppError::Network(_))
+    }
+
+    pub fn is_auth_expired(&self) -> bool {
+        matches!(self.root(), AppError::AuthExpired { .. })
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self.root(), AppError::NotFound(_))
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        matches!(self.root(), AppError::Conflict(_))
+    }
+
+    pub fn is_delete_blocked(&self) -> bool {
+        matches!(self.root(), AppError::DeleteBlocked(_))
+    }
+
+    pub fn is_duplicate_name(&self) -> bool {
+        matches!(self.root(), AppError::DuplicateName(_))
+    }
+
+    pub fn is_insufficient_disk(&self) -> bool {
+        matches!(self.root(), AppError::InsufficientDisk { .. })
+    }
+
+    pub fn is_already_in_progress(&self) -> bool {
+        matches!(self.root(), AppError::AlreadyInProgress { .. })
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.root(), AppError::Retryable { .. })
+    }
+
+    pub fn is_service_unavailable(&self) -> bool {
+        matches!(self.root(), AppError::ServiceUnavailable { .. })
+    }
+
+    pub fn is_offline(&self) -> bool {
+        matches!(self.root(), AppError::Offline)
+    }
+
+    pub fn is_deadline_exceeded(&self) -> bool {
+        matches!(self.root(), AppError::DeadlineExceeded)
+    }
+}
+
+// 与 reqwest::StatusCode 的 Display 一致（如 "404 Not Found"）
+fn status_text(status: u16) -> String {
+    reqwest::StatusCode::from_u16(status)
+        .map(|status| status.to_string())
+        .unwrap_or_else(|_| status.to_string())
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Network(msg)
+            | AppError::Storage(msg)
+            | AppError::InvalidInput(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::Other(msg) => write!(f, "{}", msg),
+            AppError::AuthExpired { body, .. } => {
+                write!(f, "http error: status {} body: {}", status_text(401), body)
+            }
+            AppError::MoetranHttp { status, body } | AppError::PoprakoHttp { status, body } => {
+                write!(
+                    f,
+                    "http error: status {} body: {}",
+                    status_text(*status),
+                    body
+                )
+            }
+            AppError::PoprakoBusiness { message, .. } => write!(f, "{}", message),
+            AppError::DeleteBlocked(_) => write!(f, "{}", DELETE_BLOCKED_MESSAGE),
+            AppError::DuplicateName(existing) => match existing.kind {
+                NamedEntityKind::Projset => write!(f, "团队中已存在同名项目集"),
+                NamedEntityKind::Proj => write!(f, "已存在同名项目"),
+            },
+            AppError::InsufficientDisk { .. } => {
+                write!(f, "磁盘空间不足，请清理缓存或释放磁盘空间后重试")
+            }
+            AppError::AlreadyInProgress { .. } => write!(f, "该项目正在下载中"),
+            AppError::Offline => {
+                write!(f, "{}: 已开启离线模式，未发送网络请求", OFFLINE_ERROR_CODE)
+            }
+            AppError::DeadlineExceeded => {
+                write!(f, "{}: 已超出本次操作的时限", DEADLINE_EXCEEDED_CODE)
+            }
+            AppError::Validation(errors) => write!(f, "{}", errors),
+            AppError::ServiceUnavailable {
+                status,
+                retry_after,
+                excerpt,
+            } => {
+                write!(f, "service unavailable: status {}", status)?;
+
+                if let Some(secs) = retry_after {
+                    write!(f, ", retry after {}s", secs)?;
+                }
+
+                if !excerpt.is_empty() {
+                    write!(f, ": {}", excerpt)?;
+                }
+
+                Ok(())
+            }
+            AppError::Retryable { source, .. } => write!(f, "{}", source),
+            AppError::Context { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+// { kind, message } 加上各类别的结构化字段
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+
+        match self.root() {
+            AppError::AuthExpired { backend, .. } => {
+                let backend = match backend {
+                    Backend::Moetran => "moetran",
+                    Backend::Poprako => "poprako",
+                };
+                map.serialize_entry("backend", backend)?;
+            }
+            AppError::PoprakoBusiness { code, .. } => map.serialize_entry("code", code)?,
+            AppError::MoetranHttp { status, body } | AppError::PoprakoHttp { status, body } => {
+                map.serialize_entry("status", status)?;
+                map.serialize_entry("body", body)?;
+            }
+            AppError::ServiceUnavailable {
+                status,
+                retry_after,
+                ..
+            } => {
+                map.serialize_entry("status", status)?;
+                map.serialize_entry("retry_after", retry_after)?;
+            }
+            AppError::Validation(errors) => map.serialize_entry("errors", errors)?,
+            AppError::DeleteBlocked(impact) => map.serialize_entry("impact", impact)?,
+            AppError::DuplicateName(existing) => map.serialize_entry("existing", existing)?,
+            AppError::InsufficientDisk {
+                required,
+                available,
+            } => {
+                map.serialize_entry("required", required)?;
+                map.serialize_entry("available", available)?;
+                map.serialize_entry("cleanup_command", CLEANUP_COMMAND)?;
+            }
+            AppError::AlreadyInProgress {
+                project_id,
+                progress,
+            } => {
+                map.serialize_entry("project_id", project_id)?;
+                map.serialize_entry("progress", progress)?;
+            }
+            AppError::Retryable {
+                command,
+                retry_token,
+                source,
+            } => {
+                map.serialize_entry("retry_token", retry_token)?;
+                map.serialize_entry("command", command)?;
+                map.serialize_entry("cause", source.kind())?;
+            }
+            _ => {}
+        }
+
+        map.end()
+    }
+}
+
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+// 尚未分类的字符串错误（本地检查、其他模块返回的 String 等）
+impl From<String> for AppError {
+    fn from(err: String) -> Self {
+        AppError::Other(err)
+    }
+}
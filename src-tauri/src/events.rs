@@ -0,0 +1,885 @@
+// 前端事件约定：每个事件是一个带常量名的类型，统一经 emit_event 发出。
+// 前端的 src/ipc/events.gen.ts 由这里的定义生成（UPDATE_EVENT_BINDINGS=1 cargo test events:: 写出）；
+// 测试会序列化每个事件的示例 payload 与 TS 类型逐字段比对，避免两端事件名 / 结构不一致
+#[cfg(test)]
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    config::ConfigEntry, image_cache::DownloadReport, poprako_health::PoprakoHealth,
+    project::MoetranSource, publish::BulkPublishSummary, session::SessionIdentity,
+    translation_verify::LostTranslation, write_queue::PendingWriteEvent,
+};
+
+pub(crate) trait AppEvent: Serialize + Clone {
+    // 事件名
+    const NAME: &'static str;
+    // 生成 TS 绑定时使用的类型名（与 Rust 类型同名）；绑定只在测试中生成 / 校验
+    #[cfg_attr(not(test), allow(dead_code))]
+    const TS_NAME: &'static str;
+    // payload 的 TS 类型（可引用 TS_SHARED_TYPES 中的类型）
+    #[cfg_attr(not(test), allow(dead_code))]
+    const TS_PAYLOAD: &'static str;
+}
+
+// 序列化并发出事件；失败只记录日志
+pub(crate) fn emit_event<E: AppEvent>(app: &AppHandle, payload: E) {
+    if let Err(err) = app.emit(E::NAME, payload) {
+        tracing::warn!(event = E::NAME, error = %err, "events.emit.failed");
+    }
+}
+
+// 事件的发出方：命令中为 AppHandle。需要在测试中完整运行的流程（如图片下载）以泛型接收，
+// 测试时传入只记录事件名的实现
+pub(crate) trait EventSink: Clone + Send + Sync + 'static {
+    fn send_event<E: AppEvent>(&self, payload: E);
+}
+
+impl EventSink for AppHandle {
+    fn send_event<E: AppEvent>(&self, payload: E) {
+        emit_event(self, payload);
+    }
+}
+
+// ========== 事件定义 ==========
+
+// 后台刷新得到与快照不同的 sources
+#[derive(Debug, Clone, Serialize)]
+pub struct SourcesUpdated {
+    pub file_id: String,
+    pub target_id: String,
+    pub sources: Vec<MoetranSource>,
+}
+
+impl AppEvent for SourcesUpdated {
+    const NAME: &'static str = "sources-updated";
+    const TS_NAME: &'static str = "SourcesUpdated";
+    const TS_PAYLOAD: &'static str =
+        "{ file_id: string; target_id: string; sources: MoetranSource[] }";
+}
+
+// 批量发布完成（dry run 不发送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct BulkPublished(pub BulkPublishSummary);
+
+impl AppEvent for BulkPublished {
+    const NAME: &'static str = "publish://bulk-completed";
+    const TS_NAME: &'static str = "BulkPublished";
+    const TS_PAYLOAD: &'static str = "BulkPublishSummary";
+}
+
+// 队列中的 PopRaKo 写操作已执行
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct WriteFlushed(pub PendingWriteEvent);
+
+impl AppEvent for WriteFlushed {
+    const NAME: &'static str = "poprako-write-flushed";
+    const TS_NAME: &'static str = "WriteFlushed";
+    const TS_PAYLOAD: &'static str = "PendingWriteEvent";
+}
+
+// 队列中的 PopRaKo 写操作执行失败（不再重试）
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct WriteFailed(pub PendingWriteEvent);
+
+impl AppEvent for WriteFailed {
+    const NAME: &'static str = "poprako-write-failed";
+    const TS_NAME: &'static str = "WriteFailed";
+    const TS_PAYLOAD: &'static str = "PendingWriteEvent";
+}
+
+// 队列中的 PopRaKo 写操作与服务端当前状态冲突，已丢弃
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct WriteConflict(pub PendingWriteEvent);
+
+impl AppEvent for WriteConflict {
+    const NAME: &'static str = "poprako-write-conflict";
+    const TS_NAME: &'static str = "WriteConflict";
+    const TS_PAYLOAD: &'static str = "PendingWriteEvent";
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OfflineModeChanged {
+    pub enabled: bool,
+}
+
+impl AppEvent for OfflineModeChanged {
+    const NAME: &'static str = "offline-mode-changed";
+    const TS_NAME: &'static str = "OfflineModeChanged";
+    const TS_PAYLOAD: &'static str = "{ enabled: boolean }";
+}
+
+// 演示模式开关变化（前端据此显示 / 隐藏演示横幅）
+#[derive(Debug, Clone, Serialize)]
+pub struct DemoModeChanged {
+    pub enabled: bool,
+}
+
+impl AppEvent for DemoModeChanged {
+    const NAME: &'static str = "demo-mode-changed";
+    const TS_NAME: &'static str = "DemoModeChanged";
+    const TS_PAYLOAD: &'static str = "{ enabled: boolean }";
+}
+
+// 运行时配置修改后的全部配置项
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct ConfigChanged(pub Vec<ConfigEntry>);
+
+impl AppEvent for ConfigChanged {
+    const NAME: &'static str = "config-changed";
+    const TS_NAME: &'static str = "ConfigChanged";
+    const TS_PAYLOAD: &'static str = "ConfigEntry[]";
+}
+
+// Moetran 与 PopRaKo 登录的不是同一个人
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct IdentityMismatch(pub SessionIdentity);
+
+impl AppEvent for IdentityMismatch {
+    const NAME: &'static str = "session://identity-mismatch";
+    const TS_NAME: &'static str = "IdentityMismatch";
+    const TS_PAYLOAD: &'static str = "SessionIdentity";
+}
+
+// PopRaKo 服务端自报的健康状态变化（up / degraded / down 等之间切换）
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct PoprakoHealthChanged(pub PoprakoHealth);
+
+impl AppEvent for PoprakoHealthChanged {
+    const NAME: &'static str = "poprako-health-changed";
+    const TS_NAME: &'static str = "PoprakoHealthChanged";
+    const TS_PAYLOAD: &'static str = "PoprakoHealth";
+}
+
+// 提交后的校验发现翻译丢失或被改动
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct TranslationLost(pub LostTranslation);
+
+impl AppEvent for TranslationLost {
+    const NAME: &'static str = "translation-lost";
+    const TS_NAME: &'static str = "TranslationLost";
+    const TS_PAYLOAD: &'static str = "LostTranslation";
+}
+
+// Moetran 返回 401：登录已过期，需要重新登录（重新登录前只发送一次）
+#[derive(Debug, Clone, Serialize)]
+pub struct MoetranAuthExpired {}
+
+impl AppEvent for MoetranAuthExpired {
+    const NAME: &'static str = "auth://moetran-expired";
+    const TS_NAME: &'static str = "MoetranAuthExpired";
+    const TS_PAYLOAD: &'static str = "Record<string, never>";
+}
+
+// PopRaKo 返回 401：登录已过期，需要重新登录（重新登录前只发送一次）
+#[derive(Debug, Clone, Serialize)]
+pub struct PoprakoAuthExpired {}
+
+impl AppEvent for PoprakoAuthExpired {
+    const NAME: &'static str = "auth://poprako-expired";
+    const TS_NAME: &'static str = "PoprakoAuthExpired";
+    const TS_PAYLOAD: &'static str = "Record<string, never>";
+}
+
+// 项目图片缓存下载中，一个文件下载结束（成功或失败）
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgressed {
+    pub project_id: String,
+    pub completed: usize,
+    pub total: usize,
+    pub failed: usize,
+    // 刚结束的文件（传入的 files 中的下标）
+    pub current_file_index: usize,
+}
+
+impl AppEvent for DownloadProgressed {
+    const NAME: &'static str = "image-cache://progress";
+    const TS_NAME: &'static str = "DownloadProgressed";
+    const TS_PAYLOAD: &'static str = "{ project_id: string; completed: number; total: number; failed: number; current_file_index: number }";
+}
+
+// 项目图片缓存下载结束；status 为 error / cancelled 时 report 为空、message 为错误信息
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadFinished {
+    pub project_id: String,
+    pub status: String,
+    pub report: Option<DownloadReport>,
+    pub message: Option<String>,
+}
+
+impl AppEvent for DownloadFinished {
+    const NAME: &'static str = "image-cache://done";
+    const TS_NAME: &'static str = "DownloadFinished";
+    const TS_PAYLOAD: &'static str = "{ project_id: string; status: 'completed' | 'failed' | 'cancelled' | 'error'; report: DownloadReport | null; message: string | null }";
+}
+
+// 多文件上传中，一个文件上传结束（成功或失败）；bytes_sent 为已上传成功的文件的总字节数
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgressed {
+    pub project_id: String,
+    pub completed: usize,
+    pub total: usize,
+    pub failed: usize,
+    // 刚结束的文件名
+    pub current_file_name: String,
+    pub bytes_sent: u64,
+    pub bytes_total: u64,
+}
+
+impl AppEvent for UploadProgressed {
+    const NAME: &'static str = "upload://progress";
+    const TS_NAME: &'static str = "UploadProgressed";
+    const TS_PAYLOAD: &'static str = "{ project_id: string; completed: number; total: number; failed: number; current_file_name: string; bytes_sent: number; bytes_total: number }";
+}
+
+// 图片缓存超出容量上限，按最近读取时间淘汰了整个项目的缓存
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheEvicted {
+    pub project_ids: Vec<String>,
+    pub used_bytes: u64,
+    pub limit_bytes: u64,
+}
+
+impl AppEvent for CacheEvicted {
+    const NAME: &'static str = "image-cache://evicted";
+    const TS_NAME: &'static str = "CacheEvicted";
+    const TS_PAYLOAD: &'static str =
+        "{ project_ids: string[]; used_bytes: number; limit_bytes: number }";
+}
+
+// 保存的项目备注中提及了当前用户（前端据此弹出通知）
+#[derive(Debug, Clone, Serialize)]
+pub struct MentionReceived {
+    pub note_id: i64,
+    pub team_id: String,
+    pub project_id: String,
+    pub project_name: Option<String>,
+    pub body: String,
+}
+
+impl AppEvent for MentionReceived {
+    const NAME: &'static str = "project-notes://mentioned";
+    const TS_NAME: &'static str = "MentionReceived";
+    const TS_PAYLOAD: &'static str = "{ note_id: number; team_id: string; project_id: string; project_name: string | null; body: string }";
+}
+
+// ========== TS 绑定生成（仅测试） ==========
+
+#[cfg(test)]
+// payload 中引用的共享类型
+const TS_SHARED_TYPES: &str = r#"export interface MoetranUserBrief {
+  id: string;
+  name: string;
+}
+
+export interface MoetranTranslation {
+  id: string;
+  content: string;
+  proofread_content: string | null;
+  selected: boolean;
+  user: MoetranUserBrief | null;
+  proofreader: MoetranUserBrief | null;
+}
+
+export interface MoetranSource {
+  id: string;
+  x: number;
+  y: number;
+  position_type: number;
+  my_translation: MoetranTranslation | null;
+  translations: MoetranTranslation[];
+  has_draft: boolean;
+  stale?: boolean;
+}
+
+export interface BulkPublishSummary {
+  dry_run: boolean;
+  ready: number;
+  published: number;
+  skipped: number;
+  failed: number;
+}
+
+export interface PendingWriteEvent {
+  pending_id: number;
+  proj_id: string;
+  kind: string;
+  message?: string;
+}
+
+export interface ConfigEntry {
+  key: string;
+  value: string;
+  source: 'env' | 'db' | 'default';
+  runtime_tunable: boolean;
+}
+
+export interface SessionIdentity {
+  moetran_user_id: string | null;
+  moetran_user_name: string | null;
+  poprako_user_id: string | null;
+  status: 'unknown' | 'match' | 'mismatch';
+  reason: string | null;
+  confirmed: boolean;
+  checked_at: number | null;
+}
+
+export interface PoprakoHealth {
+  state: 'unknown' | 'up' | 'degraded' | 'down' | 'unreachable';
+  supported: boolean;
+  version: string | null;
+  uptime_seconds: number | null;
+  components: Record<string, string>;
+  metrics: Record<string, number>;
+  reason?: string;
+  checked_at: number;
+}
+
+export interface DownloadReport {
+  downloaded: number;
+  skipped: number;
+  failed: number[];
+}
+
+export interface LostTranslation {
+  queue_id: number;
+  source_id: string;
+  target_id: string;
+  translation_id: string;
+  file_id: string;
+  reason: 'missing' | 'altered';
+  content: string;
+  current_content?: string;
+  submitted_at: number;
+}
+"#;
+
+#[cfg(test)]
+// (TS 类型名, 事件名, payload 类型)
+struct EventBinding {
+    ts_name: &'static str,
+    name: &'static str,
+    payload: &'static str,
+}
+
+#[cfg(test)]
+fn binding<E: AppEvent>() -> EventBinding {
+    EventBinding {
+        ts_name: E::TS_NAME,
+        name: E::NAME,
+        payload: E::TS_PAYLOAD,
+    }
+}
+
+#[cfg(test)]
+// 新增事件时在此登记
+fn all_events() -> Vec<EventBinding> {
+    vec![
+        binding::<SourcesUpdated>(),
+        binding::<BulkPublished>(),
+        binding::<WriteFlushed>(),
+        binding::<WriteFailed>(),
+        binding::<WriteConflict>(),
+        binding::<OfflineModeChanged>(),
+        binding::<DemoModeChanged>(),
+        binding::<ConfigChanged>(),
+        binding::<IdentityMismatch>(),
+        binding::<PoprakoHealthChanged>(),
+        binding::<TranslationLost>(),
+        binding::<MoetranAuthExpired>(),
+        binding::<PoprakoAuthExpired>(),
+        binding::<DownloadProgressed>(),
+        binding::<DownloadFinished>(),
+        binding::<CacheEvicted>(),
+        binding::<UploadProgressed>(),
+        binding::<MentionReceived>(),
+    ]
+}
+
+#[cfg(test)]
+// 重复的事件名（正常应为空）
+fn duplicate_names(events: &[EventBinding]) -> Vec<&'static str> {
+    let mut seen = HashSet::new();
+
+    events
+        .iter()
+        .filter(|event| !seen.insert(event.name))
+        .map(|event| event.name)
+        .collect()
+}
+
+#[cfg(test)]
+fn typescript_bindings() -> String {
+    let events = all_events();
+
+    let mut out = String::from(
+        "// 由 src-tauri/src/events.rs 生成（UPDATE_EVENT_BINDINGS=1 cargo test events:: 重新生成），请勿手动修改\n\n",
+    );
+
+    out.push_str(TS_SHARED_TYPES);
+
+    out.push_str("\nexport const EVENT_NAMES = {\n");
+    for event in &events {
+        out.push_str(&format!("  {}: '{}',\n", event.ts_name, event.name));
+    }
+    out.push_str("} as const;\n\n");
+
+    out.push_str("export interface EventPayloads {\n");
+    for event in &events {
+        out.push_str(&format!("  '{}': {};\n", event.name, event.payload));
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("export type AppEventName = keyof EventPayloads;\n");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        config::ConfigSource, poprako_health::HealthState, session::IdentityStatus,
+        translation_verify::LostReason,
+    };
+
+    const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/ipc/events.gen.ts");
+
+    #[test]
+    fn event_names_are_unique() {
+        assert!(duplicate_names(&all_events()).is_empty());
+
+        let twice = vec![binding::<SourcesUpdated>(), binding::<SourcesUpdated>()];
+        assert_eq!(duplicate_names(&twice), ["sources-updated"]);
+    }
+
+    #[test]
+    fn checked_in_bindings_are_up_to_date() {
+        let generated = typescript_bindings();
+
+        // 修改事件定义后以 UPDATE_EVENT_BINDINGS=1 运行本测试重新生成
+        if std::env::var_os("UPDATE_EVENT_BINDINGS").is_some() {
+            std::fs::write(BINDINGS_PATH, &generated).unwrap();
+            return;
+        }
+
+        let checked_in = std::fs::read_to_string(BINDINGS_PATH)
+            .unwrap()
+            .replace("\r\n", "\n");
+
+        assert!(
+            checked_in == generated,
+            "events.gen.ts 已过期，请运行 UPDATE_EVENT_BINDINGS=1 cargo test events:: 重新生成"
+        );
+    }
+
+    #[test]
+    fn every_event_is_listed_in_payloads() {
+        let bindings = typescript_bindings();
+
+        for event in all_events() {
+            assert!(bindings.contains(&format!("  {}: '{}',\n", event.ts_name, event.name)));
+            assert!(bindings.contains(&format!("  '{}': {};\n", event.name, event.payload)));
+        }
+    }
+
+    // ========== payload 与 TS 类型比对 ==========
+
+    // 按顶层分隔符切分（忽略 {} <> [] 内部）
+    fn split_top(src: &str, sep: char) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut depth = 0i32;
+        let mut start = 0;
+
+        for (index, c) in src.char_indices() {
+            match c {
+                '{' | '<' | '[' => depth += 1,
+                '}' | '>' | ']' => depth -= 1,
+                c if c == sep && depth == 0 => {
+                    parts.push(&src[start..index]);
+                    start = index + c.len_utf8();
+                }
+                _ => {}
+            }
+        }
+
+        parts.push(&src[start..]);
+        parts
+    }
+
+    // TS_SHARED_TYPES 中 interface 的字段部分
+    fn interface_body(name: &str) -> Option<&'static str> {
+        let header = format!("export interface {} {{", name);
+        let start = TS_SHARED_TYPES.find(&header)? + header.len();
+        let end = start + TS_SHARED_TYPES[start..].find("\n}")?;
+
+        Some(&TS_SHARED_TYPES[start..end])
+    }
+
+    // 字段须与声明一致：必填字段都在、没有未声明的字段、各字段取值符合类型
+    fn check_object(fields: &str, value: &Value, at: &str) -> Result<(), String> {
+        let Value::Object(map) = value else {
+            return Err(format!("{}: {} 不是对象", at, value));
+        };
+
+        let mut declared = HashSet::new();
+
+        for field in split_top(fields, ';') {
+            let field = field.trim();
+
+            if field.is_empty() {
+                continue;
+            }
+
+            let (name, ts) = field
+                .split_once(':')
+                .ok_or_else(|| format!("{}: 无法解析字段 {}", at, field))?;
+
+            let (name, optional) = match name.trim().strip_suffix('?') {
+                Some(name) => (name, true),
+                None => (name.trim(), false),
+            };
+
+            declared.insert(name);
+
+            match map.get(name) {
+                Some(field_value) => {
+                    check_value(ts.trim(), field_value, &format!("{}.{}", at, name))?
+                }
+                None if optional => {}
+                None => return Err(format!("{}.{}: 缺少 TS 中声明的字段", at, name)),
+            }
+        }
+
+        match map.keys().find(|key| !declared.contains(key.as_str())) {
+            Some(key) => Err(format!("{}.{}: TS 中没有声明该字段", at, key)),
+            None => Ok(()),
+        }
+    }
+
+    fn check_value(ts: &str, value: &Value, at: &str) -> Result<(), String> {
+        let alternatives = split_top(ts, '|');
+
+        if alternatives.len() > 1 {
+            let errors: Vec<String> = alternatives
+                .iter()
+                .filter_map(|alternative| check_value(alternative.trim(), value, at).err())
+                .collect();
+
+            return if errors.len() < alternatives.len() {
+                Ok(())
+            } else {
+                Err(errors.join("; "))
+            };
+        }
+
+        let matches = match ts {
+            "null" => value.is_null(),
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "never" => false,
+            _ if ts.starts_with('\'') => value.as_str() == Some(ts.trim_matches('\'')),
+            _ if ts.ends_with("[]") => {
+                let Value::Array(items) = value else {
+                    return Err(format!("{}: {} 不是数组", at, value));
+                };
+
+                let item_ts = &ts[..ts.len() - 2];
+
+                return items.iter().enumerate().try_for_each(|(index, item)| {
+                    check_value(item_ts, item, &format!("{}[{}]", at, index))
+                });
+            }
+            _ if ts.starts_with("Record<string, ") => {
+                let Value::Object(map) = value else {
+                    return Err(format!("{}: {} 不是对象", at, value));
+                };
+
+                let item_ts = &ts["Record<string, ".len()..ts.len() - 1];
+
+                return map.iter().try_for_each(|(key, item)| {
+                    check_value(item_ts, item, &format!("{}.{}", at, key))
+                });
+            }
+            _ if ts.starts_with('{') => return check_object(&ts[1..ts.len() - 1], value, at),
+            _ => {
+                return match interface_body(ts) {
+                    Some(body) => check_object(body, value, at),
+                    None => Err(format!("{}: 未知的 TS 类型 {}", at, ts)),
+                }
+            }
+        };
+
+        if matches {
+            Ok(())
+        } else {
+            Err(format!("{}: {} 与 TS 类型 {} 不符", at, value, ts))
+        }
+    }
+
+    // 事件名与其示例 payload 的序列化结果
+    fn samples<E: AppEvent>(payloads: Vec<E>) -> (&'static str, Vec<Value>) {
+        let values = payloads
+            .iter()
+            .map(|payload| serde_json::to_value(payload).unwrap())
+            .collect();
+
+        (E::NAME, values)
+    }
+
+    fn source_samples() -> Vec<MoetranSource> {
+        let translation = json!({
+            "id": "t1",
+            "content": "译文",
+            "proofread_content": "校对稿",
+            "selected": true,
+            "user": { "id": "u1", "name": "akira" },
+            "proofreader": { "id": "u2", "name": "bob" },
+        });
+
+        let mut full: MoetranSource = serde_json::from_value(json!({
+            "id": "s1",
+            "x": 0.5,
+            "y": 0.25,
+            "position_type": 2,
+            "my_translation": translation,
+            "translations": [translation, { "id": "t2", "content": "", "proofread_content": null, "selected": false }],
+        }))
+        .unwrap();
+        full.has_draft = true;
+        full.stale = Some(true);
+
+        let bare = serde_json::from_value(json!({
+            "id": "s2",
+            "x": 0.1,
+            "y": 0.9,
+            "position_type": 1,
+            "my_translation": null,
+        }))
+        .unwrap();
+
+        vec![full, bare]
+    }
+
+    fn health_sample(reason: Option<&str>) -> PoprakoHealth {
+        PoprakoHealth {
+            state: HealthState::Degraded,
+            supported: true,
+            version: reason.map(|_| "1.2.0".to_string()),
+            uptime_seconds: reason.map(|_| 60),
+            components: BTreeMap::from([("db".to_string(), "ok".to_string())]),
+            metrics: BTreeMap::from([("queue_depth".to_string(), 3)]),
+            reason: reason.map(str::to_string),
+            checked_at: 1_700_000_000,
+        }
+    }
+
+    fn identity_sample(known: bool) -> SessionIdentity {
+        let value = |v: &str| known.then(|| v.to_string());
+
+        SessionIdentity {
+            moetran_user_id: value("u1"),
+            moetran_user_name: value("akira"),
+            poprako_user_id: value("u2"),
+            status: if known {
+                IdentityStatus::Mismatch
+            } else {
+                IdentityStatus::Unknown
+            },
+            reason: value("token 已过期"),
+            confirmed: known,
+            checked_at: known.then_some(1_700_000_000),
+        }
+    }
+
+    fn lost_sample(reason: LostReason, current_content: Option<&str>) -> LostTranslation {
+        LostTranslation {
+            queue_id: 1,
+            source_id: "s1".to_string(),
+            target_id: "tg1".to_string(),
+            translation_id: "t1".to_string(),
+            file_id: "f1".to_string(),
+            reason,
+            content: "译文".to_string(),
+            current_content: current_content.map(str::to_string),
+            submitted_at: 1_700_000_000,
+        }
+    }
+
+    fn pending_write(message: Option<&str>) -> PendingWriteEvent {
+        PendingWriteEvent {
+            pending_id: 7,
+            proj_id: "p1".to_string(),
+            kind: "proj_status".to_string(),
+            message: message.map(str::to_string),
+        }
+    }
+
+    fn all_samples() -> Vec<(&'static str, Vec<Value>)> {
+        vec![
+            samples(vec![SourcesUpdated {
+                file_id: "f1".to_string(),
+                target_id: "tg1".to_string(),
+                sources: source_samples(),
+            }]),
+            samples(vec![BulkPublished(BulkPublishSummary {
+                dry_run: false,
+                ready: 3,
+                published: 2,
+                skipped: 1,
+                failed: 0,
+            })]),
+            samples(vec![
+                WriteFlushed(pending_write(None)),
+                WriteFlushed(pending_write(Some("已执行"))),
+            ]),
+            samples(vec![WriteFailed(pending_write(Some("403 Forbidden")))]),
+            samples(vec![WriteConflict(pending_write(Some("状态已被修改")))]),
+            samples(vec![OfflineModeChanged { enabled: true }]),
+            samples(vec![DemoModeChanged { enabled: false }]),
+            samples(vec![ConfigChanged(
+                [ConfigSource::Env, ConfigSource::Db, ConfigSource::Default]
+                    .into_iter()
+                    .map(|source| ConfigEntry {
+                        key: "offline_mode",
+                        value: "false".to_string(),
+                        source,
+                        runtime_tunable: true,
+                    })
+                    .collect(),
+            )]),
+            samples(vec![
+                IdentityMismatch(identity_sample(true)),
+                IdentityMismatch(identity_sample(false)),
+            ]),
+            samples(vec![
+                PoprakoHealthChanged(health_sample(Some("queue backlog"))),
+                PoprakoHealthChanged(health_sample(None)),
+            ]),
+            samples(vec![
+                TranslationLost(lost_sample(LostReason::Missing, None)),
+                TranslationLost(lost_sample(LostReason::Altered, Some("改动后"))),
+            ]),
+            samples(vec![MoetranAuthExpired {}]),
+            samples(vec![PoprakoAuthExpired {}]),
+            samples(vec![DownloadProgressed {
+                project_id: "p1".to_string(),
+                completed: 2,
+                total: 5,
+                failed: 1,
+                current_file_index: 1,
+            }]),
+            samples(
+                ["completed", "failed", "cancelled", "error"]
+                    .into_iter()
+                    .map(|status| DownloadFinished {
+                        project_id: "p1".to_string(),
+                        status: status.to_string(),
+                        report: (status != "error").then(|| DownloadReport {
+                            downloaded: 3,
+                            skipped: 1,
+                            failed: vec![2],
+                        }),
+                        message: (status == "error").then(|| "磁盘已满".to_string()),
+                    })
+                    .collect(),
+            ),
+            samples(vec![CacheEvicted {
+                project_ids: vec!["p1".to_string()],
+                used_bytes: 1024,
+                limit_bytes: 512,
+            }]),
+            samples(vec![UploadProgressed {
+                project_id: "p1".to_string(),
+                completed: 1,
+                total: 2,
+                failed: 0,
+                current_file_name: "001.png".to_string(),
+                bytes_sent: 100,
+                bytes_total: 200,
+            }]),
+            samples(vec![
+                MentionReceived {
+                    note_id: 1,
+                    team_id: "team".to_string(),
+                    project_id: "p1".to_string(),
+                    project_name: Some("项目".to_string()),
+                    body: "@akira 修图".to_string(),
+                },
+                MentionReceived {
+                    note_id: 2,
+                    team_id: "team".to_string(),
+                    project_id: "p2".to_string(),
+                    project_name: None,
+                    body: "@akira".to_string(),
+                },
+            ]),
+        ]
+    }
+
+    #[test]
+    fn serialized_payloads_match_their_typescript_types() {
+        let samples = all_samples();
+
+        // 新增事件时须同时提供示例 payload
+        let mut sampled: Vec<&str> = samples.iter().map(|(name, _)| *name).collect();
+        let mut registered: Vec<&str> = all_events().iter().map(|event| event.name).collect();
+        sampled.sort_unstable();
+        registered.sort_unstable();
+        assert_eq!(sampled, registered);
+
+        for event in all_events() {
+            let (_, values) = samples
+                .iter()
+                .find(|(name, _)| *name == event.name)
+                .unwrap();
+
+            for value in values {
+                if let Err(err) = check_value(event.payload, value, event.name) {
+                    panic!("{}", err);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shape_check_detects_drift() {
+        let ts = "{ id: string; count: number; note?: string; owner: MoetranUserBrief | null }";
+
+        let ok = json!({ "id": "a", "count": 1, "owner": null });
+        assert!(check_value(ts, &ok, "e").is_ok());
+
+        let cases = [
+            (json!({ "id": "a", "owner": null }), "e.count"),
+            (
+                json!({ "id": "a", "count": 1, "owner": null, "extra": 1 }),
+                "e.extra",
+            ),
+            (json!({ "id": "a", "count": "1", "owner": null }), "e.count"),
+            (
+                json!({ "id": "a", "count": 1, "owner": { "id": "u1" } }),
+                "e.owner.name",
+            ),
+        ];
+
+        for (value, at) in cases {
+            let err = check_value(ts, &value, "e").unwrap_err();
+            assert!(err.contains(at), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// 给排版用的 LabelPlus 翻译稿导出：按文件名自然排序逐页写出全部 source 的坐标（比例）、
+// 框内 / 框外分组与选定译文（有校对稿时用校对稿）。实际写出由 translation_export 完成，
+// 某页拉取失败时已写完的页保留，报告中给出失败的页与错误信息
+use serde::{Deserialize, Serialize};
+
+use crate::translation_export::{
+    export_project_translations, ExportFormat, ExportProjectTranslationsReq, ExportReport,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportLabelplusReq {
+    pub project_id: String,
+    pub target_id: String,
+    pub output_path: String,
+}
+
+// 导出 LabelPlus 翻译稿（总是重新导出；需要继续中断的导出时用 export_project_translations）
+#[tauri::command]
+pub async fn export_labelplus(payload: ExportLabelplusReq) -> Result<ExportReport, String> {
+    export_project_translations(ExportProjectTranslationsReq {
+        project_id: payload.project_id,
+        target_id: payload.target_id,
+        output_path: payload.output_path,
+        format: ExportFormat::Labelplus,
+        resume: false,
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        labelplus_import::{import_labelplus, ImportLabelplusReq},
+        test_support::MockBackends,
+    };
+
+    const PAGES: [(&str, &str); 3] = [
+        ("lp-f10", "page_10.png"),
+        ("lp-f2", "page_2.png"),
+        ("lp-f1", "page_1.png"),
+    ];
+
+    fn sources_json(page: &str) -> Value {
+        json!([
+            {
+                "id": format!("{}-s1", page),
+                "x": 0.1234,
+                "y": 0.5,
+                "position_type": 1,
+                "my_translation": null,
+                "translations": [{
+                    "id": format!("{}-t1", page),
+                    "content": "初稿",
+                    "proofread_content": format!("{} 的校对稿\r\n第二行", page),
+                    "selected": true,
+                }],
+            },
+            {
+                "id": format!("{}-s2", page),
+                "x": 0.75,
+                "y": 0.875,
+                "position_type": 2,
+                "my_translation": null,
+                "translations": [
+                    { "id": format!("{}-t2", page), "content": "未选定", "proofread_content": null, "selected": false },
+                    { "id": format!("{}-t3", page), "content": "选定的译文", "proofread_content": "  ", "selected": true },
+                ],
+            },
+            {
+                "id": format!("{}-s3", page),
+                "x": 0.5,
+                "y": 0.25,
+                "position_type": 1,
+                "my_translation": null,
+                "translations": [],
+            },
+        ])
+    }
+
+    async fn mount_project(backends: &MockBackends, failing_file: Option<&str>) {
+        let files: Vec<Value> = PAGES
+            .iter()
+            .map(|(id, name)| {
+                json!({ "id": id, "name": name, "source_count": 3, "url": "https://img/x", "cover_url": "" })
+            })
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/lp-proj/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(files))
+            .mount(&backends.moetran)
+            .await;
+
+        for (id, name) in PAGES {
+            let response = if failing_file == Some(id) {
+                // 4xx 不会被自动重试
+                ResponseTemplate::new(403).set_body_string("forbidden")
+            } else {
+                ResponseTemplate::new(200).set_body_json(sources_json(name))
+            };
+
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/files/{}/sources", id)))
+                .respond_with(response)
+                .mount(&backends.moetran)
+                .await;
+        }
+    }
+
+    fn export_req(output: &std::path::Path) -> ExportLabelplusReq {
+        ExportLabelplusReq {
+            project_id: "lp-proj".to_string(),
+            target_id: "lp-target".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn export_is_read_back_by_the_importer_unchanged() {
+        let backends = MockBackends::start().await;
+        mount_project(&backends, None).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("lp.txt");
+
+        let report = export_labelplus(export_req(&output)).await.unwrap();
+
+        assert!(!report.partial);
+        let pages: Vec<(&str, usize)> = report
+            .pages
+            .iter()
+            .map(|page| (page.name.as_str(), page.source_count))
+            .collect();
+        assert_eq!(
+            pages,
+            [("page_1.png", 3), ("page_2.png", 3), ("page_10.png", 3)]
+        );
+
+        let text = std::fs::read_to_string(&output).unwrap();
+        assert!(text.contains(
+            "----------------[1]----------------[0.123,0.500,1]\npage_1.png 的校对稿\n第二行\n"
+        ));
+        assert!(text.contains("----------------[2]----------------[0.750,0.875,2]\n选定的译文\n"));
+
+        // 导入同一份文件：每个标记都对应到原来的 source，译文无变化
+        let import = import_labelplus(ImportLabelplusReq {
+            project_id: "lp-proj".into(),
+            target_id: "lp-target".into(),
+            file_path: output.to_string_lossy().to_string(),
+            dry_run: true,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(import.pages_matched, 3);
+        assert!(import.unmatched_pages.is_empty());
+        assert_eq!(import.labels_to_create, 0);
+        assert_eq!(import.translations_to_submit, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_page_is_reported_and_earlier_pages_kept() {
+        let backends = MockBackends::start().await;
+        mount_project(&backends, Some("lp-f2")).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("lp.txt");
+
+        let report = export_labelplus(export_req(&output)).await.unwrap();
+
+        assert!(report.partial);
+        assert_eq!(report.pages_written, 1);
+        assert_eq!(report.failed_page.as_deref(), Some("page_2.png"));
+        assert!(report.error.is_some());
+        assert_eq!(report.missing, ["page_2.png", "page_10.png"]);
+
+        let text = std::fs::read_to_string(&output).unwrap();
+        assert!(text.contains(">>>>>>>>[page_1.png]<<<<<<<<"));
+        assert!(!text.contains("page_2.png"));
+    }
+}
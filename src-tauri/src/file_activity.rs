@@ -0,0 +1,375 @@
+// 页面最近活动：提交 / 更新翻译成功，或编辑器打开页面（mark_viewed）时在本地记录，
+// 供“我最近在做哪一页”与文件列表的 recent_activity 排序使用。记录在后台写入，不阻塞提交流程
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::unix_now,
+    natsort::compare_file_names,
+    project::MoetranProjectFile,
+    source_snapshot::snapshot_file_of,
+    storage::{file_activity, LOCAL_STORAGE},
+};
+
+// 编辑后这段时间内的查看不覆盖 last_action（编辑后刷新页面仍算作编辑）
+const EDIT_STICKY_SECS: i64 = 10 * 60;
+
+const DEFAULT_RECENT_FILES_LIMIT: i64 = 10;
+
+// file id -> project id，拉取文件列表时记录；翻译写操作只带 source / translation id，据此找到项目
+static FILE_PROJECTS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const FILE_PROJECTS_MAX: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    Submit,
+    Update,
+    View,
+}
+
+impl FileAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            FileAction::Submit => "submit",
+            FileAction::Update => "update",
+            FileAction::View => "view",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "submit" => FileAction::Submit,
+            "update" => FileAction::Update,
+            _ => FileAction::View,
+        }
+    }
+}
+
+// 文件列表的排序方式；不指定时保持 Moetran 返回的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSort {
+    // 有活动记录的文件按最近活动在前，其余按文件名自然顺序排在后面
+    RecentActivity,
+}
+
+pub(crate) fn remember_file_projects(project_id: &str, files: &[MoetranProjectFile]) {
+    if let Ok(mut guard) = FILE_PROJECTS.lock() {
+        if guard.len() >= FILE_PROJECTS_MAX {
+            guard.clear();
+        }
+
+        for file in files {
+            guard.insert(file.id.to_string(), project_id.to_string());
+        }
+    }
+}
+
+fn project_of_file(file_id: &str) -> Option<String> {
+    FILE_PROJECTS
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(file_id).cloned())
+}
+
+// 在后台记录一次页面活动；未提供 project_id 时按文件列表推断，推断不到则不记录
+pub(crate) fn record_file_activity(
+    project_id: Option<String>,
+    file_id: String,
+    target_id: String,
+    action: FileAction,
+) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let Some(project_id) = project_id.or_else(|| project_of_file(&file_id)) else {
+        tracing::debug!(%file_id, "file_activity.project_unknown");
+        return;
+    };
+
+    let at = unix_now();
+
+    let row = file_activity::FileActivityRow {
+        project_id,
+        file_id,
+        target_id,
+        last_action: action.as_str().to_string(),
+        at,
+        edited_at: (action != FileAction::View).then_some(at),
+    };
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) =
+            file_activity::record_file_activity(storage.pool(), &row, EDIT_STICKY_SECS).await
+        {
+            tracing::warn!(file_id = %row.file_id, error = %err, "file_activity.record_failed");
+        }
+    });
+}
+
+// 按 source id / translation id 记录所在页面的编辑（页面未加载过时无法定位，不记录）
+pub(crate) fn record_translation_activity(id: &str, target_id: &str, action: FileAction) {
+    let Some(file_id) = snapshot_file_of(id) else {
+        return;
+    };
+
+    record_file_activity(None, file_id, target_id.to_string(), action);
+}
+
+// 有活动的文件按最近活动在前，其余文件按名称自然顺序接在后面；activity 需已按时间倒序
+pub(crate) fn sort_by_recent_activity(
+    files: Vec<MoetranProjectFile>,
+    activity: &[file_activity::FileActivityRow],
+) -> Vec<MoetranProjectFile> {
+    let mut by_id: HashMap<String, MoetranProjectFile> = files
+        .into_iter()
+        .map(|file| (file.id.to_string(), file))
+        .collect();
+
+    let mut sorted = Vec::with_capacity(by_id.len());
+    let mut seen = HashSet::new();
+
+    // 同一文件在多个 target 下都有记录时只取最近的一条
+    for row in activity {
+        if !seen.insert(row.file_id.as_str()) {
+            continue;
+        }
+
+        if let Some(file) = by_id.remove(&row.file_id) {
+            sorted.push(file);
+        }
+    }
+
+    let mut rest: Vec<MoetranProjectFile> = by_id.into_values().collect();
+    rest.sort_by(|a, b| compare_file_names(&a.name, &b.name));
+
+    sorted.extend(rest);
+
+    sorted
+}
+
+// 项目的全部活动（时间倒序），供排序使用；读取失败时视为没有活动
+pub(crate) async fn project_activity(
+    project_id: &str,
+    target_id: Option<&str>,
+) -> Vec<file_activity::FileActivityRow> {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return Vec::new();
+    };
+
+    file_activity::list_file_activity(
+        storage.pool(),
+        project_id,
+        target_id,
+        file_activity::FILE_ACTIVITY_PER_PROJECT,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        tracing::warn!(%project_id, error = %err, "file_activity.list_failed");
+        Vec::new()
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetRecentFilesReq {
+    pub project_id: String,
+    pub target_id: String,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentFile {
+    pub file_id: String,
+    pub target_id: String,
+    pub last_action: FileAction,
+    pub at: i64,
+    // 最近一次提交 / 更新的时间；只查看过时为 None
+    pub edited_at: Option<i64>,
+}
+
+#[tauri::command]
+pub async fn get_recent_files(payload: GetRecentFilesReq) -> Result<Vec<RecentFile>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let limit = payload
+        .limit
+        .unwrap_or(DEFAULT_RECENT_FILES_LIMIT)
+        .clamp(1, file_activity::FILE_ACTIVITY_PER_PROJECT);
+
+    let rows = file_activity::list_file_activity(
+        storage.pool(),
+        &payload.project_id,
+        Some(&payload.target_id),
+        limit,
+    )
+    .await?;
+
+    tracing::debug!(
+        project_id = %payload.project_id,
+        count = rows.len(),
+        "file_activity.recent.ok"
+    );
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RecentFile {
+            last_action: FileAction::parse(&row.last_action),
+            file_id: row.file_id,
+            target_id: row.target_id,
+            at: row.at,
+            edited_at: row.edited_at,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::local_storage;
+
+    fn project_file(id: &str, name: &str) -> MoetranProjectFile {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": name,
+            "source_count": 0,
+            "url": null,
+            "cover_url": "",
+            "width": null,
+            "height": null,
+            "size_bytes": null,
+            "safe_status": null,
+            "broken": false,
+        }))
+        .unwrap()
+    }
+
+    fn activity(
+        file_id: &str,
+        target_id: &str,
+        action: FileAction,
+        at: i64,
+    ) -> file_activity::FileActivityRow {
+        file_activity::FileActivityRow {
+            project_id: "activity-proj".to_string(),
+            file_id: file_id.to_string(),
+            target_id: target_id.to_string(),
+            last_action: action.as_str().to_string(),
+            at,
+            edited_at: (action != FileAction::View).then_some(at),
+        }
+    }
+
+    #[test]
+    fn recent_files_come_first_then_natural_name_order() {
+        let files = vec![
+            project_file("f10", "10.png"),
+            project_file("f2", "2.png"),
+            project_file("f1", "1.png"),
+            project_file("f3", "3.png"),
+        ];
+
+        let rows = [
+            activity("f3", "t1", FileAction::Submit, 30),
+            activity("f10", "t1", FileAction::View, 20),
+            activity("f3", "t2", FileAction::View, 10),
+            activity("deleted", "t1", FileAction::View, 5),
+        ];
+
+        let ids: Vec<String> = sort_by_recent_activity(files, &rows)
+            .into_iter()
+            .map(|file| file.id.to_string())
+            .collect();
+        assert_eq!(ids, ["f3", "f10", "f1", "f2"]);
+    }
+
+    #[test]
+    fn actions_round_trip_through_storage_strings() {
+        for action in [FileAction::Submit, FileAction::Update, FileAction::View] {
+            assert_eq!(FileAction::parse(action.as_str()), action);
+        }
+
+        assert_eq!(FileAction::parse("unknown"), FileAction::View);
+    }
+
+    #[tokio::test]
+    async fn viewing_soon_after_an_edit_keeps_the_edit() {
+        let storage = local_storage().await;
+        let record = |row| async move {
+            file_activity::record_file_activity(storage.pool(), &row, EDIT_STICKY_SECS)
+                .await
+                .unwrap()
+        };
+
+        record(activity("sticky-f1", "t1", FileAction::Update, 1_000)).await;
+        record(activity(
+            "sticky-f1",
+            "t1",
+            FileAction::View,
+            1_000 + EDIT_STICKY_SECS - 1,
+        ))
+        .await;
+
+        record(activity("sticky-f2", "t1", FileAction::Submit, 1_000)).await;
+        record(activity(
+            "sticky-f2",
+            "t1",
+            FileAction::View,
+            1_000 + EDIT_STICKY_SECS,
+        ))
+        .await;
+
+        // 其他 target 的记录不返回
+        record(activity("sticky-f3", "t2", FileAction::Submit, 5_000)).await;
+
+        let recent = get_recent_files(GetRecentFilesReq {
+            project_id: "activity-proj".to_string(),
+            target_id: "t1".to_string(),
+            limit: None,
+        })
+        .await
+        .unwrap();
+
+        let summary: Vec<(&str, FileAction, i64, Option<i64>)> = recent
+            .iter()
+            .map(|file| {
+                (
+                    file.file_id.as_str(),
+                    file.last_action,
+                    file.at,
+                    file.edited_at,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                (
+                    "sticky-f2",
+                    FileAction::View,
+                    1_000 + EDIT_STICKY_SECS,
+                    Some(1_000)
+                ),
+                (
+                    "sticky-f1",
+                    FileAction::Update,
+                    1_000 + EDIT_STICKY_SECS - 1,
+                    Some(1_000)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// PopRaKo 列表响应的宽松解析：不同版本的接口 data 可能是裸数组，也可能是 { <字段>: [...] } 包裹。
+// FlexibleList 同时接受：裸数组、含约定字段名（由 ListField 指定）的对象、只有一个数组字段的对象。
+// 每个接口每次会话只记录一次实际遇到的形态，便于发现服务端的变化
+use std::{
+    collections::HashSet,
+    fmt,
+    marker::PhantomData,
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::Value;
+
+// 约定的列表字段名
+pub(crate) trait ListField {
+    const FIELD: &'static str;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ListShape {
+    // data 直接是数组
+    Bare,
+    // data 是对象，列表位于约定字段中
+    Named,
+    // data 是对象，约定字段不存在，但只有一个数组字段
+    SingleField(String),
+}
+
+impl fmt::Display for ListShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListShape::Bare => write!(f, "bare"),
+            ListShape::Named => write!(f, "named"),
+            ListShape::SingleField(field) => write!(f, "single_field:{}", field),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FlexibleList<T, F> {
+    items: Vec<T>,
+    shape: ListShape,
+    _field: PhantomData<F>,
+}
+
+impl<T, F> FlexibleList<T, F> {
+    // 取出列表；endpoint 用于按接口记录一次形态
+    pub(crate) fn into_items(self, endpoint: &'static str) -> Vec<T> {
+        log_shape_once(endpoint, &self.shape);
+        self.items
+    }
+}
+
+// (接口, 形态)：每种组合每次会话只记录一次
+static SEEN_SHAPES: LazyLock<Mutex<HashSet<(&'static str, ListShape)>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn log_shape_once(endpoint: &'static str, shape: &ListShape) {
+    let first = SEEN_SHAPES
+        .lock()
+        .map(|mut seen| seen.insert((endpoint, shape.clone())))
+        .unwrap_or(false);
+
+    if first {
+        tracing::info!(endpoint, shape = %shape, "poprako.list_shape.seen");
+    }
+}
+
+// 从 data 中找出列表所在的值与形态
+fn locate_list(value: Value, field: &str) -> Result<(Value, ListShape), String> {
+    let mut object = match value {
+        Value::Array(_) => return Ok((value, ListShape::Bare)),
+        Value::Object(object) => object,
+        other => return Err(format!("期望列表或对象，实际为 {}", value_kind(&other))),
+    };
+
+    if let Some(list) = object.remove(field) {
+        return Ok((list, ListShape::Named));
+    }
+
+    let mut arrays = object.into_iter().filter(|(_, value)| value.is_array());
+
+    match (arrays.next(), arrays.next()) {
+        (Some((name, list)), None) => Ok((list, ListShape::SingleField(name))),
+        (None, _) => Err(format!("对象中没有 {} 字段或任何数组字段", field)),
+        (Some(_), Some(_)) => Err(format!("对象中没有 {} 字段且存在多个数组字段", field)),
+    }
+}
+
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl<'de, T, F> Deserialize<'de> for FlexibleList<T, F>
+where
+    T: DeserializeOwned,
+    F: ListField,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+
+        let (list, shape) = locate_list(value, F::FIELD).map_err(serde::de::Error::custom)?;
+
+        let items = serde_json::from_value(list).map_err(serde::de::Error::custom)?;
+
+        Ok(FlexibleList {
+            items,
+            shape,
+            _field: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Items;
+
+    impl ListField for Items {
+        const FIELD: &'static str = "items";
+    }
+
+    fn parse(value: Value) -> Result<FlexibleList<u32, Items>, serde_json::Error> {
+        serde_json::from_value(value)
+    }
+
+    #[test]
+    fn accepts_every_supported_shape() {
+        let bare = parse(json!([1, 2])).unwrap();
+        assert_eq!(
+            (bare.shape.clone(), bare.items),
+            (ListShape::Bare, vec![1, 2])
+        );
+
+        let named = parse(json!({ "items": [3], "others": [4], "total": 1 })).unwrap();
+        assert_eq!(
+            (named.shape.clone(), named.items),
+            (ListShape::Named, vec![3])
+        );
+
+        let single = parse(json!({ "members": [5, 6], "total": 2 })).unwrap();
+        assert_eq!(single.shape, ListShape::SingleField("members".to_string()));
+        assert_eq!(single.into_items("test.flexible_list"), [5, 6]);
+    }
+
+    #[test]
+    fn ambiguous_or_missing_lists_are_errors() {
+        let error = parse(json!({ "a": [1], "b": [2] })).unwrap_err();
+        assert!(error.to_string().contains("多个数组字段"));
+
+        let error = parse(json!({ "total": 0 })).unwrap_err();
+        assert!(error.to_string().contains("没有 items 字段或任何数组字段"));
+
+        let error = parse(json!("oops")).unwrap_err();
+        assert!(error.to_string().contains("实际为 string"));
+
+        // 约定字段存在但不是数组时不回退到其他字段
+        assert!(parse(json!({ "items": null, "list": [1] })).is_err());
+    }
+
+    #[test]
+    fn shape_names_are_stable_for_logging() {
+        assert_eq!(ListShape::Bare.to_string(), "bare");
+        assert_eq!(ListShape::Named.to_string(), "named");
+        assert_eq!(
+            ListShape::SingleField("list".to_string()).to_string(),
+            "single_field:list"
+        );
+    }
+}
@@ -1,10 +1,439 @@
-use std::{cell::LazyCell, collections::HashMap, ops::Deref as _, time::Duration};
-
-use reqwest::header::{self, HeaderName, HeaderValue};
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::{BuildHasher as _, Hasher as _},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 
 use tracing::{debug, warn};
 
+use crate::{
+    auth_expiry,
+    config::config,
+    connectivity::{self, Backend},
+    error::AppError,
+    pagination::Page,
+    request_budget, usage,
+    validation::parse_validation_body,
+};
+
+// 离线模式下直接拒绝，不触碰网络（也不必等待超时）；
+// 已设置请求提供者（演示模式）时同样拒绝，保证不会访问真实后端
+pub(crate) fn ensure_online() -> Result<(), AppError> {
+    if config().offline_mode {
+        return Err(AppError::Offline);
+    }
+
+    if provider_active() {
+        return Err(AppError::Other(format!(
+            "{}: 演示模式下不访问真实后端",
+            DEMO_ERROR_CODE
+        )));
+    }
+
+    Ok(())
+}
+
+// ================== 请求提供者 ==================
+
+// 演示模式错误的固定前缀
+const DEMO_ERROR_CODE: &str = "demo_mode";
+
+// 交给提供者应答的请求；body 已序列化为 JSON
+pub(crate) struct ProviderRequest<'a> {
+    pub backend: Backend,
+    pub method: &'static str,
+    // 相对 base URL 的路径，可能带有内联的查询串
+    pub path: &'a str,
+    pub query: Option<&'a HashMap<&'a str, String>>,
+    pub body: Option<Value>,
+}
+
+// 设置后 moetran_* / poprako_* 请求不再经过网络，由提供者在进程内应答（演示模式）。
+// 应答为 JSON，再按调用方期望的类型反序列化；无内容的响应返回 Value::Null
+pub(crate) trait ApiProvider: Send + Sync {
+    fn respond(&self, request: ProviderRequest<'_>) -> Result<Value, AppError>;
+
+    // moetran_get_raw 的应答（图片等二进制内容）
+    fn respond_raw(&self, url: &str) -> Result<RawBody, String>;
+}
+
+static PROVIDER: RwLock<Option<Arc<dyn ApiProvider>>> = RwLock::new(None);
+
+// None 表示恢复访问真实后端
+pub(crate) fn set_provider(provider: Option<Arc<dyn ApiProvider>>) {
+    if let Ok(mut guard) = PROVIDER.write() {
+        *guard = provider;
+    }
+}
+
+fn current_provider() -> Option<Arc<dyn ApiProvider>> {
+    PROVIDER.read().ok().and_then(|guard| guard.clone())
+}
+
+pub(crate) fn provider_active() -> bool {
+    current_provider().is_some()
+}
+
+// 已设置提供者时由其应答；返回 None 表示照常发送网络请求
+fn provided<B, R>(
+    backend: Backend,
+    method: &'static str,
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    body: Option<B>,
+) -> Option<Result<R, AppError>>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    let provider = current_provider()?;
+
+    let body = match body.map(serde_json::to_value).transpose() {
+        Ok(body) => body,
+        Err(err) => {
+            return Some(Err(AppError::Other(format!(
+                "Failed to serialize request body: {}",
+                err
+            ))))
+        }
+    };
+
+    let reply = provider.respond(ProviderRequest {
+        backend,
+        method,
+        path,
+        query,
+        body,
+    });
+
+    debug!(
+        ?backend,
+        method,
+        path,
+        ok = reply.is_ok(),
+        "http.provider.respond"
+    );
+
+    Some(reply.and_then(|value| {
+        serde_json::from_value(value)
+            .map_err(|err| AppError::Other(format!("Failed to parse JSON: {}", err)))
+    }))
+}
+
+const PAGE_EXCERPT_MAX_CHARS: usize = 80;
+
+// 通过 Content-Type 与首字符嗅探判断响应体是否为 HTML 等非 JSON 页面
+fn looks_like_html(content_type: Option<&str>, body: &str) -> bool {
+    let trimmed = body.trim_start();
+
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return false;
+    }
+
+    trimmed.starts_with('<')
+        || content_type
+            .map(|ct| ct.to_ascii_lowercase().contains("text/html"))
+            .unwrap_or(false)
+}
+
+// 提取页面摘要：优先取 <title>，否则取去掉标签后的前若干字符
+fn page_excerpt(body: &str) -> String {
+    let lower = body.to_ascii_lowercase();
+
+    if let Some(start) = lower.find("<title") {
+        if let Some(open_end) = lower[start..].find('>') {
+            let content_start = start + open_end + 1;
+
+            if let Some(len) = lower[content_start..].find("</title>") {
+                let title = body[content_start..content_start + len].trim();
+
+                if !title.is_empty() {
+                    return title.chars().take(PAGE_EXCERPT_MAX_CHARS).collect();
+                }
+            }
+        }
+    }
+
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for ch in body.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(PAGE_EXCERPT_MAX_CHARS)
+        .collect()
+}
+
+// 通用响应读取：状态检查 -> 识别维护页 / 网关错误页 -> 解析 JSON
+async fn read_json_response<R>(
+    backend: Backend,
+    method: &str,
+    resp: reqwest::Response,
+) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    let status = resp.status();
+    let url = resp.url().clone();
+
+    let content_type = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    // 仅支持秒数形式的 Retry-After，HTTP-date 形式忽略
+    let retry_after = resp
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+
+    // 如果返回非 2xx，尝试读取响应体并返回更详细的错误信息
+    if !status.is_success() {
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|_| "<body read error>".to_string());
+
+        usage::record_request(method, &url, body.len() as u64);
+
+        let gateway_down = matches!(status.as_u16(), 502..=504);
+
+        if gateway_down
+            || (status.is_server_error() && looks_like_html(content_type.as_deref(), &body))
+        {
+            return Err(AppError::ServiceUnavailable {
+                status: status.as_u16(),
+                retry_after,
+                excerpt: page_excerpt(&body),
+            });
+        }
+
+        // 字段校验错误单独返回，便于表单定位到具体输入框；无法识别时按普通错误处理
+        if status == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+            if let Some(errors) = parse_validation_body(&body) {
+                return Err(AppError::Validation(errors));
+            }
+        }
+
+        let status = status.as_u16();
+
+        // 只有 401 视为登录过期。403 不做同样处理：两个后端都用 403 表示“已登录但无权限”
+        // （如非管理员修改项目、访问其他汉化组的资源），此时 token 仍然有效。若 403 也清除 token
+        // 并弹出重新登录，用户每次碰到权限边界都会被登出，重新登录后仍然是 403。
+        // 403 按普通 HTTP 错误（MoetranHttp / PoprakoHttp）返回，前端按 status 提示无权限。
+        // （创建前的 token 探测只请求 user/info，那里的 403 只能是 token 被拒，见 token_probe）
+        if status == 401 {
+            auth_expiry::handle_auth_expired(backend);
+        }
+
+        return Err(match (backend, status) {
+            (_, 401) => AppError::AuthExpired { backend, body },
+            (Backend::Moetran, _) => AppError::MoetranHttp { status, body },
+            (Backend::Poprako, _) => AppError::PoprakoHttp { status, body },
+        });
+    }
+
+    // 读取为文本后再解析，这样可以优雅处理空响应体或 204 No Content 的情况
+    let text = resp
+        .text()
+        .await
+        .map_err(|err| AppError::Network(format!("response body read error: {}", err)))?;
+
+    usage::record_request(method, &url, text.len() as u64);
+
+    if text.trim().is_empty() {
+        // 当响应体为空时，尝试将 JSON "null" 解析为目标类型（对 `()` / `Option` 等友好）
+        let parsed = serde_json::from_str::<R>("null")
+            .map_err(|err| AppError::Other(format!("json parse error: {}", err)))?;
+        return Ok(parsed);
+    }
+
+    // 维护期间 Moetran 可能以 200 返回 HTML 页面，不应报告为 JSON 解析错误
+    if looks_like_html(content_type.as_deref(), &text) {
+        return Err(AppError::ServiceUnavailable {
+            status: status.as_u16(),
+            retry_after,
+            excerpt: page_excerpt(&text),
+        });
+    }
+
+    let parsed = serde_json::from_str::<R>(&text)
+        .map_err(|err| AppError::Other(format!("json parse error: {}", err)))?;
+
+    Ok(parsed)
+}
+
+// 以 normal 作为本次请求的超时，并按当前作用域的时限缩短；时限已用尽时不再发出请求。
+// 所有对外请求（JSON 接口、图片下载与探测、文件上传）都经过这里
+pub(crate) fn apply_budget(
+    req: reqwest::RequestBuilder,
+    normal: Duration,
+) -> Result<reqwest::RequestBuilder, AppError> {
+    match request_budget::current() {
+        Some(budget) if budget.expired() => Err(AppError::DeadlineExceeded),
+        Some(budget) => Ok(req.timeout(budget.clamp(normal))),
+        None => Ok(req.timeout(normal)),
+    }
+}
+
+// 因时限用尽而超时的请求不视为网络故障
+pub(crate) fn send_error(err: reqwest::Error) -> AppError {
+    if err.is_timeout() && request_budget::exceeded() {
+        return AppError::DeadlineExceeded;
+    }
+
+    AppError::Network(format!("request send error: {}", err))
+}
+
+// ================== 自动重试 ==================
+
+// 单次等待的上限；服务端要求等待更久（Retry-After）时不再重试
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(4);
+
+// 请求失败后的自动重试策略。GET 默认按 IDEMPOTENT 重试；POST / PUT / DELETE 不重试
+// （重复提交可能产生重复数据），个别调用可另行指定
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    // 总尝试次数（含第一次），1 表示不重试
+    pub max_attempts: u32,
+    // 第 n 次重试前等待约 base_delay * 2^(n-1)（含随机抖动，不超过 MAX_RETRY_DELAY）
+    pub base_delay: Duration,
+    // 哪些错误值得重试
+    pub retry_on: fn(&AppError) -> bool,
+}
+
+impl RetryPolicy {
+    pub const NONE: Self = Self {
+        max_attempts: 1,
+        base_delay: Duration::ZERO,
+        retry_on: is_transient_error,
+    };
+
+    pub const IDEMPOTENT: Self = Self {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(300),
+        retry_on: is_transient_error,
+    };
+
+    // 图片代理：图床偶发超时较多，重复请求没有副作用
+    pub const IMAGE: Self = Self {
+        max_attempts: 5,
+        base_delay: Duration::from_millis(500),
+        retry_on: is_transient_error,
+    };
+
+    // 第 attempt 次尝试失败后的等待时间；None 表示不再重试
+    fn delay_after(&self, attempt: u32, err: &AppError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !(self.retry_on)(err) {
+            return None;
+        }
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(MAX_RETRY_DELAY);
+
+        // 在 [backoff/2, backoff] 之间随机，避免多个请求同时重试
+        let mut delay = backoff / 2 + backoff.mul_f64(jitter() / 2.0);
+
+        if let AppError::ServiceUnavailable {
+            retry_after: Some(secs),
+            ..
+        } = err.root()
+        {
+            let wait = Duration::from_secs(*secs);
+
+            if wait > MAX_RETRY_DELAY {
+                return None;
+            }
+
+            delay = delay.max(wait);
+        }
+
+        // 剩余时限不够等待时直接返回本次错误
+        if request_budget::current().is_some_and(|budget| budget.remaining() <= delay) {
+            return None;
+        }
+
+        Some(delay)
+    }
+}
+
+// 超时、连接失败、5xx 与维护页
+pub(crate) fn is_transient_error(err: &AppError) -> bool {
+    match err.root() {
+        AppError::Network(_) | AppError::ServiceUnavailable { .. } => true,
+        AppError::MoetranHttp { status, .. } | AppError::PoprakoHttp { status, .. } => {
+            (500..=599).contains(status)
+        }
+        _ => false,
+    }
+}
+
+// [0, 1) 内的随机数（RandomState 每次使用不同的随机种子）
+fn jitter() -> f64 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u64(0);
+
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// 按策略执行请求，失败且可重试时退避后再次执行；label 仅用于日志
+pub(crate) async fn with_retry<T, F, Fut>(
+    policy: RetryPolicy,
+    label: &str,
+    mut attempt: F,
+) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let mut tries = 1;
+
+    loop {
+        let err = match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        let Some(delay) = policy.delay_after(tries, &err) else {
+            return Err(err);
+        };
+
+        tracing::info!(
+            label,
+            attempt = tries,
+            delay_ms = delay.as_millis() as u64,
+            error = %err,
+            "http.retry"
+        );
+
+        tokio::time::sleep(delay).await;
+        tries += 1;
+    }
+}
+
 // ================== API Client 封装结构 ==================
 
 struct ApiClient {
@@ -15,6 +444,10 @@ struct ApiClient {
 impl ApiClient {
     const TIMEOUT_SECS: u64 = 5;
 
+    fn timeout() -> Duration {
+        Duration::from_secs(Self::TIMEOUT_SECS)
+    }
+
     // new：仅供模块内部懒初始化使用，不对外暴露
     fn new(base_url: reqwest::Url, default_headers: Vec<(HeaderName, HeaderValue)>) -> Self {
         let mut default_header_map = reqwest::header::HeaderMap::new();
@@ -38,88 +471,62 @@ impl ApiClient {
         Self { client, base_url }
     }
 
-    // 通用 GET：执行请求 -> 状态检查 -> 解析 JSON
-    pub async fn http_get<R>(
+    // 通用 GET：执行请求 -> 状态检查 -> 解析 JSON，失败时按 policy 重试；同时返回响应头（如分页总数）
+    pub async fn http_get_with_headers<R>(
+        backend: Backend,
         client: &reqwest::Client,
         url: reqwest::Url,
         headers: Vec<(HeaderName, HeaderValue)>,
-    ) -> Result<R, String>
+        policy: RetryPolicy,
+    ) -> Result<(R, HeaderMap), AppError>
     where
         R: DeserializeOwned,
     {
         tracing::debug!(%url, "ApiClient.http_get called");
 
-        let mut req = client.get(url);
-
-        if !headers.is_empty() {
-            let mut headers_map = reqwest::header::HeaderMap::new();
+        ensure_online()?;
 
-            headers.into_iter().for_each(|(key, value)| {
-                if let Some(prev) = headers_map.insert(key, value) {
-                    warn!(?prev, "Header key duplicated when building headers for GET");
-                }
-            });
+        let mut headers_map = reqwest::header::HeaderMap::new();
 
-            req = req.headers(headers_map);
-        }
+        headers.into_iter().for_each(|(key, value)| {
+            if let Some(prev) = headers_map.insert(key, value) {
+                warn!(?prev, "Header key duplicated when building headers for GET");
+            }
+        });
 
-        let resp = req
-            .send()
-            .await
-            .map_err(|err| format!("request send error: {}", err))?;
+        with_retry(policy, url.as_str(), || async {
+            let req = client.get(url.clone()).headers(headers_map.clone());
 
-        // 如果返回非 2xx，尝试读取响应体并返回更详细的错误信息
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp
-                .text()
+            let resp = apply_budget(req, ApiClient::timeout())?
+                .send()
                 .await
-                .unwrap_or_else(|_| "<body read error>".to_string());
-            return Err(format!("http error: status {} body: {}", status, body));
-        }
-
-        // 读取为文本后再解析，这样可以优雅处理空响应体或 204 No Content 的情况
-        let text = resp
-            .text()
-            .await
-            .map_err(|err| format!("response body read error: {}", err))?;
-
-        if text.trim().is_empty() {
-            // 当响应体为空时，尝试将 JSON "null" 解析为目标类型（对 `()` / `Option` 等友好）
-            let parsed = serde_json::from_str::<R>("null")
-                .map_err(|err| format!("json parse error: {}", err))?;
-            return Ok(parsed);
-        }
-
-        let parsed =
-            serde_json::from_str::<R>(&text).map_err(|err| format!("json parse error: {}", err))?;
+                .map_err(send_error)?;
+            let resp_headers = resp.headers().clone();
 
-        Ok(parsed)
+            read_json_response(backend, "GET", resp)
+                .await
+                .map(|reply| (reply, resp_headers))
+        })
+        .await
     }
 
-    // 通用 POST：构造请求（必要时空 body） -> 附加头 -> 状态检查 -> 解析 JSON
+    // 通用 POST：构造请求（必要时空 body） -> 附加头 -> 状态检查 -> 解析 JSON；
+    // 只有接口幂等时才应传入重试策略
     pub async fn http_post<B, R>(
+        backend: Backend,
         client: &reqwest::Client,
         url: reqwest::Url,
         headers: Vec<(HeaderName, HeaderValue)>,
         body: Option<B>,
-    ) -> Result<R, String>
+        policy: RetryPolicy,
+    ) -> Result<R, AppError>
     where
         B: Serialize,
         R: DeserializeOwned,
     {
         tracing::debug!(%url, "ApiClient.http_post called");
 
-        let mut req = client.post(url);
-
-        match body {
-            Some(b) => {
-                req = req.json(&b);
-            }
-            None => {
-                req = req.body("");
-            }
-        }
+        ensure_online()?;
 
         let mut headers_map = reqwest::header::HeaderMap::new();
 
@@ -132,52 +539,44 @@ impl ApiClient {
             }
         });
 
-        let resp = req
-            .headers(headers_map)
-            .send()
-            .await
-            .map_err(|err| format!("request send error: {}", err))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp
-                .text()
-                .await
-                .unwrap_or_else(|_| "<body read error>".to_string());
-            return Err(format!("http error: status {} body: {}", status, body));
-        }
-
-        // 读取为文本后再解析，这样可以优雅处理空响应体或 204 No Content 的情况
-        let text = resp
-            .text()
-            .await
-            .map_err(|err| format!("response body read error: {}", err))?;
+        with_retry(policy, url.as_str(), || async {
+            let mut req = client.post(url.clone());
 
-        if text.trim().is_empty() {
-            let parsed = serde_json::from_str::<R>("null")
-                .map_err(|err| format!("json parse error: {}", err))?;
-            return Ok(parsed);
-        }
+            match &body {
+                Some(b) => {
+                    req = req.json(b);
+                }
+                None => {
+                    req = req.body("");
+                }
+            }
 
-        let parsed =
-            serde_json::from_str::<R>(&text).map_err(|err| format!("json parse error: {}", err))?;
+            let resp = apply_budget(req.headers(headers_map.clone()), ApiClient::timeout())?
+                .send()
+                .await
+                .map_err(send_error)?;
 
-        Ok(parsed)
+            read_json_response(backend, "POST", resp).await
+        })
+        .await
     }
 
     // 通用 PUT：构造请求（必要时空 body） -> 附加头 -> 状态检查 -> 解析 JSON
     pub async fn http_put<B, R>(
+        backend: Backend,
         client: &reqwest::Client,
         url: reqwest::Url,
         headers: Vec<(HeaderName, HeaderValue)>,
         body: Option<B>,
-    ) -> Result<R, String>
+    ) -> Result<R, AppError>
     where
         B: Serialize,
         R: DeserializeOwned,
     {
         tracing::debug!(%url, "ApiClient.http_put called");
 
+        ensure_online()?;
+
         let mut req = client.put(url);
 
         match body {
@@ -197,52 +596,37 @@ impl ApiClient {
             }
         });
 
-        let resp = req
-            .headers(headers_map)
+        let resp = apply_budget(req.headers(headers_map), ApiClient::timeout())?
             .send()
             .await
-            .map_err(|err| format!("request send error: {}", err))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp
-                .text()
-                .await
-                .unwrap_or_else(|_| "<body read error>".to_string());
-            return Err(format!("http error: status {} body: {}", status, body));
-        }
-
-        // 读取为文本后再解析，这样可以优雅处理空响应体或 204 No Content 的情况
-        let text = resp
-            .text()
-            .await
-            .map_err(|err| format!("response body read error: {}", err))?;
-
-        if text.trim().is_empty() {
-            let parsed = serde_json::from_str::<R>("null")
-                .map_err(|err| format!("json parse error: {}", err))?;
-            return Ok(parsed);
-        }
+            .map_err(send_error)?;
 
-        let parsed =
-            serde_json::from_str::<R>(&text).map_err(|err| format!("json parse error: {}", err))?;
-
-        Ok(parsed)
+        read_json_response(backend, "PUT", resp).await
     }
 
-    // 通用 DELETE：执行请求 -> 状态检查 -> 解析 JSON（多数情况返回空 body）
-    pub async fn http_delete<R>(
+    // 通用 DELETE：执行请求 -> 状态检查 -> 解析 JSON（多数情况返回空 body）；
+    // 个别接口（如 PopRaKo 取消指派）需要在请求体中携带参数
+    pub async fn http_delete<B, R>(
+        backend: Backend,
         client: &reqwest::Client,
         url: reqwest::Url,
         headers: Vec<(HeaderName, HeaderValue)>,
-    ) -> Result<R, String>
+        body: Option<B>,
+    ) -> Result<R, AppError>
     where
+        B: Serialize,
         R: DeserializeOwned,
     {
         tracing::debug!(%url, "ApiClient.http_delete called");
 
+        ensure_online()?;
+
         let mut req = client.delete(url);
 
+        if let Some(b) = body {
+            req = req.json(&b);
+        }
+
         if !headers.is_empty() {
             let mut headers_map = reqwest::header::HeaderMap::new();
 
@@ -258,161 +642,304 @@ impl ApiClient {
             req = req.headers(headers_map);
         }
 
-        let resp = req
+        let resp = apply_budget(req, ApiClient::timeout())?
             .send()
             .await
-            .map_err(|err| format!("request send error: {}", err))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp
-                .text()
-                .await
-                .unwrap_or_else(|_| "<body read error>".to_string());
-            return Err(format!("http error: status {} body: {}", status, body));
-        }
-
-        let text = resp
-            .text()
-            .await
-            .map_err(|err| format!("response body read error: {}", err))?;
+            .map_err(send_error)?;
 
-        if text.trim().is_empty() {
-            let parsed = serde_json::from_str::<R>("null")
-                .map_err(|err| format!("json parse error: {}", err))?;
-            return Ok(parsed);
-        }
-
-        let parsed =
-            serde_json::from_str::<R>(&text).map_err(|err| format!("json parse error: {}", err))?;
-
-        Ok(parsed)
+        read_json_response(backend, "DELETE", resp).await
     }
 }
 
-thread_local! {
-    pub static MOETRAN_API_BASE: reqwest::Url = "https://api.moetran.com/v1/".parse().expect("invalid MOETRAN_API_BASE URL");
+// 两个后端的客户端。base URL 由 config 模块解析（环境变量 > 设置表 > 默认值），已规范化为以 '/' 结尾；
+// 运行时修改地址后，下一次请求发现与缓存的客户端不一致时按新地址重建
+static MOETRAN_API_CLIENT: RwLock<Option<Arc<ApiClient>>> = RwLock::new(None);
+
+static POPRAKO_API_CLIENT: RwLock<Option<Arc<ApiClient>>> = RwLock::new(None);
+
+fn moetran_default_headers() -> Vec<(HeaderName, HeaderValue)> {
+    vec![
+        // Origin/Referer are sometimes validated; include as defaults here for API calls originating from the app
+        (header::ACCEPT, HeaderValue::from_static("application/json, text/plain, */*")),
+        (header::USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/142.0.0.0 Safari/537.36")),
+        (header::ACCEPT_LANGUAGE, HeaderValue::from_static("zh-CN")),
+        (header::ORIGIN, HeaderValue::from_static("https://moetran.com")),
+        (header::REFERER, HeaderValue::from_static("https://moetran.com/")),
+    ]
+}
 
-    static MOETRAN_API_CLIENT: LazyCell<ApiClient> = LazyCell::new(|| {
-        let base = MOETRAN_API_BASE.with(|b| b.clone());
+fn poprako_default_headers() -> Vec<(HeaderName, HeaderValue)> {
+    vec![
+        (
+            HeaderName::from_static("accept"),
+            HeaderValue::from_static("application/json, text/plain, */*"),
+        ),
+        (
+            HeaderName::from_static("user-agent"),
+            HeaderValue::from_static("moetran-native-client/1.0"),
+        ),
+    ]
+}
 
-        let default_headers = vec![
-            // Origin/Referer are sometimes validated; include as defaults here for API calls originating from the app
-            (header::ACCEPT, HeaderValue::from_static("application/json, text/plain, */*")),
-            (header::USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/142.0.0.0 Safari/537.36")),
-            (header::ACCEPT_LANGUAGE, HeaderValue::from_static("zh-CN")),
-            (header::ORIGIN, HeaderValue::from_static("https://moetran.com")),
-            (header::REFERER, HeaderValue::from_static("https://moetran.com/")),
-        ];
+// 取出与当前配置的 base URL 一致的客户端；首次使用或地址已修改时重建
+fn current_client(
+    slot: &RwLock<Option<Arc<ApiClient>>>,
+    base: &str,
+    default_headers: fn() -> Vec<(HeaderName, HeaderValue)>,
+) -> Result<Arc<ApiClient>, AppError> {
+    let base_url = reqwest::Url::parse(base)
+        .map_err(|err| AppError::Other(format!("Invalid API base URL {}: {}", base, err)))?;
+
+    if let Ok(guard) = slot.read() {
+        if let Some(api) = guard.as_ref().filter(|api| api.base_url == base_url) {
+            return Ok(api.clone());
+        }
+    }
 
-        ApiClient::new(base, default_headers)
-    });
+    let api = Arc::new(ApiClient::new(base_url, default_headers()));
 
-    pub static POPRAKO_API_BASE: reqwest::Url = {
-        dotenvy::dotenv().expect("Failed to load .end file");
+    if let Ok(mut guard) = slot.write() {
+        *guard = Some(api.clone());
+    }
 
-        let use_local = match std::env::var("RUST_LOG") {
-            Ok(v) => v.to_lowercase().contains("debug"),
-            Err(_) => false,
-        };
+    Ok(api)
+}
 
-        let url_str = if use_local {
-            tracing::info!("Using local Poprako API endpoint for debugging");
+fn moetran_api() -> Result<Arc<ApiClient>, AppError> {
+    current_client(
+        &MOETRAN_API_CLIENT,
+        &config().moetran_api_base,
+        moetran_default_headers,
+    )
+}
 
-            "http://127.0.0.1:8080/api/v1/"
-        } else {
-            tracing::info!("Using production Poprako API endpoint");
+fn poprako_api() -> Result<Arc<ApiClient>, AppError> {
+    current_client(
+        &POPRAKO_API_CLIENT,
+        &config().poprako_api_base,
+        poprako_default_headers,
+    )
+}
 
-            "https://hatsu1ki-lb-site.com/api/v1/"
-        };
+// 当前生效的 Moetran API 地址（已规范化为以 '/' 结尾）
+fn moetran_api_base() -> Result<reqwest::Url, AppError> {
+    moetran_api().map(|api| api.base_url.clone())
+}
 
-        url_str.parse().expect("invalid POPRAKO_API_BASE URL")
-    };
+// ================== 后端请求封装 ==================
 
-    static POPRAKO_API_CLIENT: LazyCell<ApiClient> = LazyCell::new(|| {
-        let base = POPRAKO_API_BASE.with(|b| b.clone());
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
 
-        let default_headers = vec![
-            (HeaderName::from_static("accept"), HeaderValue::from_static("application/json, text/plain, */*")),
-            (HeaderName::from_static("user-agent"), HeaderValue::from_static("moetran-native-client/1.0")),
-        ];
+impl Method {
+    fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        }
+    }
+}
 
-        ApiClient::new(base, default_headers)
-    });
+// Authorization 的附加方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Auth {
+    // Moetran：有缓存的 token 时附加；PopRaKo：除 sync 外必须附加
+    Token,
+    // 公开接口（健康检查等），不附加
+    Public,
 }
 
-pub async fn moetran_post_opt<B, R>(path: &str, body: Option<B>) -> Result<R, String>
-where
-    B: Serialize,
-    R: DeserializeOwned,
-{
-    if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for moetran_post_opt: {}", path));
+// 一次 moetran_* / poprako_* 请求；name 为对外函数名，用于日志与错误信息
+struct ApiCall<'a, B> {
+    name: &'static str,
+    backend: Backend,
+    method: Method,
+    path: &'a str,
+    query: Option<&'a HashMap<&'a str, String>>,
+    body: Option<B>,
+    // 仅对 GET / POST 生效；PUT / DELETE 不重试
+    policy: RetryPolicy,
+    auth: Auth,
+}
+
+impl<'a, B> ApiCall<'a, B> {
+    fn new(name: &'static str, backend: Backend, method: Method, path: &'a str) -> Self {
+        Self {
+            name,
+            backend,
+            method,
+            path,
+            query: None,
+            body: None,
+            policy: RetryPolicy::NONE,
+            auth: Auth::Token,
+        }
     }
 
-    let (client, base) = MOETRAN_API_CLIENT.with(|lazy| {
-        let api = lazy.deref();
-        (api.client.clone(), api.base_url.clone())
-    });
+    fn query(mut self, query: Option<&'a HashMap<&'a str, String>>) -> Self {
+        self.query = query;
+        self
+    }
 
-    let url = base
-        .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
+    fn body(mut self, body: Option<B>) -> Self {
+        self.body = body;
+        self
+    }
 
-    let mut headers = Vec::new();
+    fn policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
 
-    if let Some(token) = crate::token::cached_moetran_token() {
-        match HeaderValue::from_str(&format!("Bearer {}", token)) {
-            Ok(header_value) => {
-                headers.push((header::AUTHORIZATION, header_value));
-                debug!("Authorization header added for moetran_post_opt");
-            }
-            Err(err) => {
-                warn!("Invalid token header value: {}", err);
-            }
+    fn public(mut self) -> Self {
+        self.auth = Auth::Public;
+        self
+    }
+}
+
+// Moetran 的 token 可选：没有或无效时照常发送（由服务端决定是否拒绝）
+fn moetran_auth_headers(name: &str) -> Vec<(HeaderName, HeaderValue)> {
+    match crate::token::moetran_auth_header() {
+        Some(Ok(header_value)) => {
+            debug!("Authorization header added for {}", name);
+            vec![(header::AUTHORIZATION, header_value)]
+        }
+        Some(Err(err)) => {
+            warn!("Invalid token header value: {}", err);
+            Vec::new()
+        }
+        None => {
+            warn!("No cached Moetran token available");
+            Vec::new()
         }
-    } else {
-        warn!("No cached Moetran token available");
     }
+}
 
-    ApiClient::http_post(&client, url, headers, body).await
+// PopRaKo 除 sync 外都要求登录：没有 token 时直接失败，避免发出未认证的请求
+fn poprako_auth_headers(path: &str) -> Result<Vec<(HeaderName, HeaderValue)>, AppError> {
+    match crate::token::poprako_auth_header() {
+        Some(header_value) => header_value
+            .map(|value| vec![(header::AUTHORIZATION, value)])
+            .map_err(|err| AppError::Other(format!("Invalid token header value: {}", err))),
+        None if path == "sync" => Ok(Vec::new()),
+        None => Err(AppError::Other(
+            "Missing Poprako token: Authorization header required for this endpoint".to_string(),
+        )),
+    }
 }
 
-pub async fn moetran_put_opt<B, R>(path: &str, body: Option<B>) -> Result<R, String>
+// 全部 moetran_* / poprako_* 请求的公共流程：校验路径 -> 请求提供者（演示模式）-> PopRaKo 写操作的会话检查
+// -> 拼接 URL 与查询参数 -> 附加 Authorization -> 发送并记录后端连通状态。
+// 只有 GET 返回响应头；由请求提供者应答时也没有响应头
+async fn send_api<B, R>(call: ApiCall<'_, B>) -> Result<(R, Option<HeaderMap>), AppError>
 where
     B: Serialize,
     R: DeserializeOwned,
 {
+    let ApiCall {
+        name,
+        backend,
+        method,
+        path,
+        query,
+        body,
+        policy,
+        auth,
+    } = call;
+
     if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for moetran_put_opt: {}", path));
+        return Err(AppError::Other(format!(
+            "Invalid path for {}: {}",
+            name, path
+        )));
     }
 
-    let (client, base) = MOETRAN_API_CLIENT.with(|lazy| {
-        let api = lazy.deref();
-        (api.client.clone(), api.base_url.clone())
-    });
+    if let Some(reply) = provided(backend, method.as_str(), path, query, body.as_ref()) {
+        return reply.map(|reply| (reply, None));
+    }
+
+    // 会话身份不一致且未确认时拒绝 PopRaKo 写操作（sync 与搜索类 POST 只读，不受限制）
+    if matches!(backend, Backend::Poprako)
+        && method != Method::Get
+        && path != "sync"
+        && !path.ends_with("/search")
+    {
+        crate::session::ensure_poprako_writable().map_err(AppError::Other)?;
+    }
+
+    let api = match backend {
+        Backend::Moetran => moetran_api()?,
+        Backend::Poprako => poprako_api()?,
+    };
+    let (client, base) = (api.client.clone(), api.base_url.clone());
 
-    let url = base
+    let mut url = base
         .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
+        .map_err(|err| AppError::Other(format!("Failed to build URL for {}: {}", path, err)))?;
 
-    let mut headers = Vec::new();
+    if let Some(q) = query {
+        let mut pairs = url.query_pairs_mut();
 
-    if let Some(token) = crate::token::cached_moetran_token() {
-        match HeaderValue::from_str(&format!("Bearer {}", token)) {
-            Ok(header_value) => {
-                headers.push((header::AUTHORIZATION, header_value));
-                debug!("Authorization header added for moetran_put_opt");
-            }
-            Err(err) => {
-                warn!("Invalid token header value: {}", err);
-            }
+        for (key, value) in q.iter() {
+            pairs.append_pair(key, value);
         }
-    } else {
-        warn!("No cached Moetran token available");
     }
 
-    ApiClient::http_put(&client, url, headers, body).await
+    let headers = match (auth, backend) {
+        (Auth::Public, _) => Vec::new(),
+        (Auth::Token, Backend::Moetran) => moetran_auth_headers(name),
+        (Auth::Token, Backend::Poprako) => poprako_auth_headers(path)?,
+    };
+
+    let result = match method {
+        Method::Get => ApiClient::http_get_with_headers(backend, &client, url, headers, policy)
+            .await
+            .map(|(reply, headers)| (reply, Some(headers))),
+        Method::Post => ApiClient::http_post(backend, &client, url, headers, body, policy)
+            .await
+            .map(|reply| (reply, None)),
+        Method::Put => ApiClient::http_put(backend, &client, url, headers, body)
+            .await
+            .map(|reply| (reply, None)),
+        Method::Delete => ApiClient::http_delete(backend, &client, url, headers, body)
+            .await
+            .map(|reply| (reply, None)),
+    };
+
+    connectivity::observe(backend, result)
+}
+
+// 只需要响应体时使用
+async fn send_json<B, R>(call: ApiCall<'_, B>) -> Result<R, AppError>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    send_api(call).await.map(|(reply, _)| reply)
+}
+
+pub async fn moetran_post_opt<B, R>(path: &str, body: Option<B>) -> Result<R, AppError>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    send_json(ApiCall::new("moetran_post_opt", Backend::Moetran, Method::Post, path).body(body))
+        .await
+}
+
+pub async fn moetran_put_opt<B, R>(path: &str, body: Option<B>) -> Result<R, AppError>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    send_json(ApiCall::new("moetran_put_opt", Backend::Moetran, Method::Put, path).body(body)).await
 }
 
 // 通用 DELETE：构造请求 -> 附加头 -> 状态检查
@@ -455,275 +982,1162 @@ where
     Ok(parsed)
 }
 
-pub async fn moetran_delete<R>(path: &str) -> Result<R, String>
+pub async fn moetran_delete<R>(path: &str) -> Result<R, AppError>
 where
     R: DeserializeOwned,
 {
-    if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for moetran_delete: {}", path));
-    }
+    send_json(ApiCall::<()>::new(
+        "moetran_delete",
+        Backend::Moetran,
+        Method::Delete,
+        path,
+    ))
+    .await
+}
 
-    let (client, base) = MOETRAN_API_CLIENT.with(|lazy| {
-        let api = lazy.deref();
-        (api.client.clone(), api.base_url.clone())
-    });
+// 失败时按 RetryPolicy::IDEMPOTENT 重试
+pub async fn moetran_get<R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    moetran_get_with_retry(path, query, RetryPolicy::IDEMPOTENT).await
+}
 
-    let url = base
-        .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
+pub async fn moetran_get_with_retry<R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    policy: RetryPolicy,
+) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    moetran_get_with_headers(path, query, policy)
+        .await
+        .map(|(reply, _)| reply)
+}
 
-    let mut headers = Vec::new();
+// Moetran 列表接口在该响应头中给出总条数
+const PAGINATION_COUNT_HEADER: &str = "x-pagination-count";
+
+// 分页 GET：page / limit 作为查询参数附加，总数取自 X-Pagination-Count
+// （演示模式或响应头缺失时由 Page::new 按本页是否满页推断）
+pub async fn moetran_get_page<T>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    page: u32,
+    limit: u32,
+) -> Result<Page<T>, AppError>
+where
+    T: DeserializeOwned,
+{
+    let mut paged = query.cloned().unwrap_or_default();
+    paged.insert("page", page.to_string());
+    paged.insert("limit", limit.to_string());
 
-    if let Some(token) = crate::token::cached_moetran_token() {
-        match HeaderValue::from_str(&format!("Bearer {}", token)) {
-            Ok(header_value) => {
-                headers.push((header::AUTHORIZATION, header_value));
-                debug!("Authorization header added for moetran_delete");
-            }
-            Err(err) => {
-                warn!("Invalid token header value: {}", err);
-            }
-        }
-    } else {
-        warn!("No cached Moetran token available");
-    }
+    let (items, headers) =
+        moetran_get_with_headers::<Vec<T>>(path, Some(&paged), RetryPolicy::IDEMPOTENT).await?;
+
+    let total = headers
+        .as_ref()
+        .and_then(|headers| headers.get(PAGINATION_COUNT_HEADER))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
 
-    ApiClient::http_delete(&client, url, headers).await
+    Ok(Page::new(items, total, page, limit))
 }
 
-pub async fn moetran_get<R>(path: &str, query: Option<&HashMap<&str, String>>) -> Result<R, String>
+// 由请求提供者应答时没有响应头，返回 None
+async fn moetran_get_with_headers<R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    policy: RetryPolicy,
+) -> Result<(R, Option<HeaderMap>), AppError>
 where
     R: DeserializeOwned,
 {
-    if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for moetran_get: {}", path));
-    }
+    send_api(
+        ApiCall::<()>::new("moetran_get", Backend::Moetran, Method::Get, path)
+            .query(query)
+            .policy(policy),
+    )
+    .await
+}
 
-    let (client, base) = MOETRAN_API_CLIENT.with(|lazy| {
-        let api_client = lazy.deref();
-        (api_client.client.clone(), api_client.base_url.clone())
-    });
+// 原始响应体及其 Content-Type（用于图片下载时确定文件格式）
+pub struct RawBody {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+    // 缓存校验头，供之后检查远端文件是否变化
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    // 响应头中的 Content-Length；没有该响应头（如分块传输）时为 None
+    pub content_length: Option<u64>,
+}
 
-    let mut url = base
-        .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
+// 条件请求的结果：未变化（304），或已变化 / 无法判断时返回的响应头
+pub enum RawProbe {
+    NotModified,
+    Fetched {
+        etag: Option<String>,
+        last_modified: Option<String>,
+        content_length: Option<u64>,
+    },
+}
 
-    if let Some(q) = query {
-        {
-            let mut pairs = url.query_pairs_mut();
+// 图片等二进制内容的大小上限
+pub(crate) const MAX_RAW_BODY_BYTES: u64 = 32 * 1024 * 1024;
 
-            for (key, value) in q.iter() {
-                pairs.append_pair(key, value);
-            }
-        }
-    }
+// 下载图片的超时（API 请求的 TIMEOUT_SECS 对大图不够）
+const RAW_TIMEOUT_SECS: u64 = 30;
+
+// 图床校验 Referer / UA，与网页版浏览器请求保持一致（Referer、UA 见 Moetran 客户端的默认头）
+fn raw_request_headers(caller: &str, url: &reqwest::Url) -> reqwest::header::HeaderMap {
+    let mut headers_map = reqwest::header::HeaderMap::new();
 
-    let mut headers = Vec::new();
+    headers_map.insert(
+        header::ACCEPT,
+        HeaderValue::from_static(
+            "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8",
+        ),
+    );
+    headers_map.insert(
+        HeaderName::from_static("sec-ch-ua"),
+        HeaderValue::from_static(
+            "\"Chromium\";v=\"142\", \"Microsoft Edge\";v=\"142\", \"Not_A Brand\";v=\"99\"",
+        ),
+    );
+
+    // token 只发给 Moetran API，不发给图床
+    let is_api = moetran_api_base().is_ok_and(|base| base.host_str() == url.host_str());
+
+    if !is_api {
+        return headers_map;
+    }
 
-    if let Some(token) = crate::token::cached_moetran_token() {
-        match HeaderValue::from_str(&format!("Bearer {}", token)) {
+    if let Some(auth_header) = crate::token::moetran_auth_header() {
+        match auth_header {
             Ok(header_value) => {
-                headers.push((header::AUTHORIZATION, header_value));
-                debug!("Authorization header added for moetran_get");
+                headers_map.insert(header::AUTHORIZATION, header_value);
+                debug!("Authorization header added for {}", caller);
             }
             Err(err) => {
                 warn!("Invalid token header value: {}", err);
             }
         }
-    } else {
-        warn!("No cached Moetran token available");
     }
 
-    ApiClient::http_get(&client, url, headers).await
+    headers_map
 }
 
-pub async fn moetran_get_raw(url: &str) -> Result<Vec<u8>, String> {
-    let client = MOETRAN_API_CLIENT.with(|lazy| {
-        let api_client = lazy.deref();
-        api_client.client.clone()
-    });
+fn header_string(headers: &header::HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
 
-    let mut headers_map = reqwest::header::HeaderMap::new();
+// 绝对 URL（图床上的图片）原样使用；相对路径按 Moetran API 地址解析
+fn resolve_raw_url(url_or_path: &str) -> Result<reqwest::Url, AppError> {
+    if url_or_path.starts_with("http://") || url_or_path.starts_with("https://") {
+        return reqwest::Url::parse(url_or_path).map_err(|err| {
+            AppError::InvalidInput(format!("Invalid URL {}: {}", url_or_path, err))
+        });
+    }
 
-    if let Some(token) = crate::token::cached_moetran_token() {
-        match HeaderValue::from_str(&format!("Bearer {}", token)) {
-            Ok(header_value) => {
-                headers_map.insert(header::AUTHORIZATION, header_value);
-                debug!("Authorization header added for moetran_get_raw");
-            }
-            Err(err) => {
-                warn!("Invalid token header value: {}", err);
-            }
-        }
+    if url_or_path.is_empty() || url_or_path.starts_with('/') {
+        return Err(AppError::Other(format!(
+            "Invalid path for moetran_get_raw: {}",
+            url_or_path
+        )));
     }
 
-    let resp = client
-        .get(url)
-        .headers(headers_map)
-        .send()
+    moetran_api_base()?
+        .join(url_or_path)
+        .map_err(|err| AppError::Other(format!("Failed to build URL for {}: {}", url_or_path, err)))
+}
+
+// 下载二进制内容（图片等）：共用 Moetran 客户端及其 Referer / UA，逐块读取响应体，
+// 超过 MAX_RAW_BODY_BYTES 时立即中止，不会先把整个响应读入内存
+pub async fn moetran_get_raw(url_or_path: &str) -> Result<RawBody, AppError> {
+    if let Some(provider) = current_provider() {
+        return provider.respond_raw(url_or_path).map_err(AppError::Other);
+    }
+
+    ensure_online()?;
+
+    let url = resolve_raw_url(url_or_path)?;
+
+    let client = moetran_api()?.client.clone();
+
+    let headers = raw_request_headers("moetran_get_raw", &url);
+
+    let mut resp = apply_budget(
+        client.get(url).headers(headers),
+        Duration::from_secs(RAW_TIMEOUT_SECS),
+    )?
+    .send()
+    .await
+    .map_err(send_error)?;
+
+    let status = resp.status();
+
+    if !status.is_success() {
+        return Err(AppError::MoetranHttp {
+            status: status.as_u16(),
+            body: String::new(),
+        });
+    }
+
+    if resp
+        .content_length()
+        .is_some_and(|len| len > MAX_RAW_BODY_BYTES)
+    {
+        return Err(raw_too_large());
+    }
+
+    let content_type = header_string(resp.headers(), header::CONTENT_TYPE);
+    let etag = header_string(resp.headers(), header::ETAG);
+    let last_modified = header_string(resp.headers(), header::LAST_MODIFIED);
+    let content_length = resp.content_length();
+
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or(0) as usize);
+
+    while let Some(chunk) = resp
+        .chunk()
         .await
-        .map_err(|err| format!("request send error: {}", err))?;
+        .map_err(|err| AppError::Network(format!("response body read error: {}", err)))?
+    {
+        if (bytes.len() + chunk.len()) as u64 > MAX_RAW_BODY_BYTES {
+            return Err(raw_too_large());
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(RawBody {
+        bytes,
+        content_type,
+        etag,
+        last_modified,
+        content_length,
+    })
+}
+
+fn raw_too_large() -> AppError {
+    AppError::Other(format!(
+        "Remote file too large (over {} MB)",
+        MAX_RAW_BODY_BYTES / 1024 / 1024
+    ))
+}
+
+// 以条件 GET 检查远端文件是否变化：带上下载时记录的 ETag / Last-Modified，
+// 返回 304 即未变化；否则只读取响应头（不读取响应体）。
+// 使用 GET 而非 HEAD：签名 URL 通常只对 GET 有效
+pub async fn moetran_probe_raw(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<RawProbe, String> {
+    // 提供者的内容不会变化
+    if provider_active() {
+        return Ok(RawProbe::NotModified);
+    }
+
+    ensure_online()?;
+
+    let client = moetran_api()?.client.clone();
+
+    let url = resolve_raw_url(url).map_err(String::from)?;
+
+    let mut headers_map = raw_request_headers("moetran_probe_raw", &url);
+
+    if let Some(value) = etag.and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers_map.insert(header::IF_NONE_MATCH, value);
+    }
+
+    if let Some(value) = last_modified.and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers_map.insert(header::IF_MODIFIED_SINCE, value);
+    }
+
+    let resp = apply_budget(
+        client.get(url).headers(headers_map),
+        Duration::from_secs(RAW_TIMEOUT_SECS),
+    )
+    .map_err(String::from)?
+    .send()
+    .await
+    .map_err(|err| String::from(send_error(err)))?;
+
+    let status = resp.status();
+
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(RawProbe::NotModified);
+    }
 
-    if !resp.status().is_success() {
-        let status = resp.status();
+    if !status.is_success() {
         return Err(format!("http error: status {}", status));
     }
 
-    let bytes = resp
-        .bytes()
-        .await
-        .map_err(|err| format!("read response bytes error: {}", err))?;
+    Ok(RawProbe::Fetched {
+        etag: header_string(resp.headers(), header::ETAG),
+        last_modified: header_string(resp.headers(), header::LAST_MODIFIED),
+        content_length: resp.content_length(),
+    })
+}
 
-    Ok(bytes.to_vec())
+pub async fn poprako_post_opt<B, R>(path: &str, body: Option<B>) -> Result<R, AppError>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    poprako_post_with_query(path, None, body).await
 }
 
-pub async fn poprako_post_opt<B, R>(path: &str, body: Option<B>) -> Result<R, String>
+// 同 poprako_post_opt，附带查询参数
+pub async fn poprako_post_with_query<B, R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    body: Option<B>,
+) -> Result<R, AppError>
 where
     B: Serialize,
     R: DeserializeOwned,
 {
-    if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for poprako_post_opt: {}", path));
-    }
+    send_json(
+        ApiCall::new("poprako_post_opt", Backend::Poprako, Method::Post, path)
+            .query(query)
+            .body(body),
+    )
+    .await
+}
 
-    let (client, base) = POPRAKO_API_CLIENT.with(|lazy| {
-        let api = lazy.deref();
-        (api.client.clone(), api.base_url.clone())
-    });
+// 失败时按 RetryPolicy::IDEMPOTENT 重试
+pub async fn poprako_get<R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    poprako_get_with_retry(path, query, RetryPolicy::IDEMPOTENT).await
+}
 
-    let url = base
-        .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
-
-    // For Poprako endpoints other than 'sync', an Authorization header is required.
-    // If no token is cached, fail early to avoid sending unauthenticated requests.
-    let mut headers = Vec::new();
-
-    if path != "sync" {
-        let token = crate::token::cached_poprako_token();
-        if token.is_none() {
-            return Err(
-                "Missing Poprako token: Authorization header required for this endpoint"
-                    .to_string(),
-            );
-        }
-        headers.push((
-            header::AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", token.unwrap()))
-                .map_err(|err| format!("Invalid token header value: {}", err))?,
-        ));
-    } else {
-        // sync endpoint may be called without Authorization header
-        if let Some(token) = crate::token::cached_poprako_token() {
-            headers.push((
-                header::AUTHORIZATION,
-                HeaderValue::from_str(&format!("Bearer {}", token))
-                    .map_err(|err| format!("Invalid token header value: {}", err))?,
-            ));
-        }
-    }
+pub async fn poprako_get_with_retry<R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    policy: RetryPolicy,
+) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    send_json(
+        ApiCall::<()>::new("poprako_get", Backend::Poprako, Method::Get, path)
+            .query(query)
+            .policy(policy),
+    )
+    .await
+}
 
-    ApiClient::http_post(&client, url, headers, body).await
+// 不带 Authorization 的 PopRaKo GET（健康检查等公开接口，未登录时也可调用）；
+// 不重试，健康检查需要如实反映单次请求的结果
+pub async fn poprako_get_public<R>(path: &str) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    send_json(
+        ApiCall::<()>::new("poprako_get_public", Backend::Poprako, Method::Get, path).public(),
+    )
+    .await
 }
 
-pub async fn poprako_get<R>(path: &str, query: Option<&HashMap<&str, String>>) -> Result<R, String>
+pub async fn poprako_put_opt<B, R>(path: &str, body: Option<B>) -> Result<R, AppError>
 where
+    B: Serialize,
     R: DeserializeOwned,
 {
-    if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for poprako_get: {}", path));
+    poprako_put_with_query(path, None, body).await
+}
+
+// 同 poprako_put_opt，附带查询参数
+pub async fn poprako_put_with_query<B, R>(
+    path: &str,
+    query: Option<&HashMap<&str, String>>,
+    body: Option<B>,
+) -> Result<R, AppError>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    send_json(
+        ApiCall::new("poprako_put_opt", Backend::Poprako, Method::Put, path)
+            .query(query)
+            .body(body),
+    )
+    .await
+}
+
+pub async fn poprako_delete<R>(path: &str) -> Result<R, AppError>
+where
+    R: DeserializeOwned,
+{
+    poprako_delete_opt(path, None::<()>).await
+}
+
+// 同 poprako_delete，个别接口需要在请求体中携带参数
+pub async fn poprako_delete_opt<B, R>(path: &str, body: Option<B>) -> Result<R, AppError>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    send_json(ApiCall::new("poprako_delete", Backend::Poprako, Method::Delete, path).body(body))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{header, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        project::PoprakoEnvelope,
+        request_budget::{with_budget, RequestBudget},
+        test_support::{MockBackends, TEST_MOETRAN_TOKEN, TEST_POPRAKO_TOKEN},
+    };
+
+    const NGINX_502: &str = "<html>\r\n<head><title>502 Bad Gateway</title></head>\r\n\
+                             <body>\r\n<center><h1>502 Bad Gateway</h1></center>\r\n\
+                             <hr><center>nginx/1.24.0</center>\r\n</body>\r\n</html>\r\n";
+
+    #[test]
+    fn html_is_detected_by_content_type_or_leading_tag() {
+        assert!(looks_like_html(Some("text/html"), NGINX_502));
+        assert!(looks_like_html(None, NGINX_502));
+        assert!(looks_like_html(
+            Some("text/html; charset=utf-8"),
+            "Service Unavailable"
+        ));
+        assert!(looks_like_html(
+            Some("application/json"),
+            "  <!DOCTYPE html><html></html>"
+        ));
+
+        // JSON 即使被错标为 text/html 也按 JSON 处理
+        assert!(!looks_like_html(Some("text/html"), r#"{"code":500}"#));
+        assert!(!looks_like_html(Some("text/html"), "  [1, 2]"));
+        assert!(!looks_like_html(Some("text/plain"), "upstream timed out"));
+        assert!(!looks_like_html(None, ""));
     }
 
-    let (client, base) = POPRAKO_API_CLIENT.with(|lazy| {
-        let api_client = lazy.deref();
-        (api_client.client.clone(), api_client.base_url.clone())
-    });
+    #[test]
+    fn excerpt_prefers_title_then_stripped_text() {
+        assert_eq!(page_excerpt(NGINX_502), "502 Bad Gateway");
+        assert_eq!(
+            page_excerpt("<HTML><TITLE> Maintenance </TITLE></HTML>"),
+            "Maintenance"
+        );
+
+        // 空标题时退回正文文本，并合并空白
+        assert_eq!(
+            page_excerpt(
+                "<html><title></title><body><h1>维护中</h1>\n  <p>稍后再试</p></body></html>"
+            ),
+            "维护中 稍后再试"
+        );
+
+        let long = format!("<p>{}</p>", "字".repeat(200));
+        assert_eq!(page_excerpt(&long).chars().count(), PAGE_EXCERPT_MAX_CHARS);
+    }
 
-    let mut url = base
-        .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
+    #[tokio::test]
+    async fn poprako_envelope_is_decoded_with_token() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projsets"))
+            .and(query_param("team_id", "team-1"))
+            .and(header(
+                "authorization",
+                format!("Bearer {}", TEST_POPRAKO_TOKEN).as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": [{ "projset_id": "set-1" }],
+                "message": null,
+            })))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        let query = HashMap::from([("team_id", "team-1".to_string())]);
+        let reply: PoprakoEnvelope<Vec<Value>> =
+            poprako_get("projsets", Some(&query)).await.unwrap();
+
+        assert_eq!(reply.code, 200);
+        assert_eq!(reply.data.unwrap()[0]["projset_id"], "set-1");
+        assert_eq!(reply.message, None);
+    }
 
-    if let Some(q) = query {
-        {
-            let mut pairs = url.query_pairs_mut();
+    #[tokio::test]
+    async fn empty_body_decodes_as_unit() {
+        let backends = MockBackends::start().await;
 
-            for (key, value) in q.iter() {
-                pairs.append_pair(key, value);
-            }
-        }
+        Mock::given(method("PUT"))
+            .and(path("/v1/projs/p1/publish"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&backends.poprako)
+            .await;
+
+        poprako_put_opt::<(), ()>("projs/p1/publish", None)
+            .await
+            .unwrap();
     }
 
-    // Require Authorization for all Poprako endpoints except 'sync'
-    let mut headers = Vec::new();
-    if path != "sync" {
-        let token = crate::token::cached_poprako_token();
-        if token.is_none() {
-            return Err(
-                "Missing Poprako token: Authorization header required for this endpoint"
-                    .to_string(),
-            );
-        }
-        match HeaderValue::from_str(&format!("Bearer {}", token.unwrap())) {
-            Ok(header_value) => {
-                headers.push((header::AUTHORIZATION, header_value));
-            }
-            Err(err) => {
-                warn!("Invalid token header value: {}", err);
-            }
-        }
-    } else {
-        if let Some(token) = crate::token::cached_poprako_token() {
-            if let Ok(hv) = HeaderValue::from_str(&format!("Bearer {}", token)) {
-                headers.push((header::AUTHORIZATION, hv));
-            }
-        }
+    #[tokio::test]
+    async fn moetran_page_reads_pagination_header() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/teams"))
+            .and(query_param("page", "2"))
+            .and(query_param("limit", "2"))
+            .and(header(
+                "authorization",
+                format!("Bearer {}", TEST_MOETRAN_TOKEN).as_str(),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Pagination-Count", "5")
+                    .set_body_json(json!([{ "id": "t3" }, { "id": "t4" }])),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        let page = moetran_get_page::<Value>("user/teams", None, 2, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.total, Some(5));
+        assert!(page.has_more);
     }
 
-    ApiClient::http_get(&client, url, headers).await
-}
+    #[tokio::test]
+    async fn error_statuses_map_to_error_kinds() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string(r#"{"message":"not found"}"#))
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs"))
+            .respond_with(ResponseTemplate::new(409).set_body_string("conflict"))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projsets"))
+            .respond_with(ResponseTemplate::new(422).set_body_json(json!({
+                "code": 422,
+                "data": null,
+                "message": "invalid",
+                "errors": [{ "field": "projset_name", "message": "too long" }],
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("expired"))
+            .mount(&backends.moetran)
+            .await;
+
+        let err = moetran_get_with_retry::<Value>("projects/missing", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::MoetranHttp { status: 404, .. }));
 
-pub async fn poprako_put_opt<B, R>(path: &str, body: Option<B>) -> Result<R, String>
-where
-    B: Serialize,
-    R: DeserializeOwned,
-{
-    if path.is_empty() || path.starts_with('/') {
-        return Err(format!("Invalid path for poprako_put_opt: {}", path));
+        let err = poprako_post_opt::<Value, Value>("projs", Some(json!({})))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, AppError::PoprakoHttp { status: 409, ref body } if body == "conflict")
+        );
+
+        let err = poprako_post_opt::<Value, Value>("projsets", Some(json!({})))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "Validation", "{}", err);
+
+        let err = moetran_get_with_retry::<Value>("user/info", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+        assert!(err.is_auth_expired());
+        // 401 后清除内存中的 token
+        assert_eq!(crate::token::cached_moetran_token(), None);
     }
 
-    let (client, base) = POPRAKO_API_CLIENT.with(|lazy| {
-        let api = lazy.deref();
-        (api.client.clone(), api.base_url.clone())
-    });
+    #[tokio::test]
+    async fn auth_statuses_map_per_backend() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/teams"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("forbidden"))
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/remove"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("not admin"))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("expired"))
+            .mount(&backends.poprako)
+            .await;
+
+        // 403 只是无权限：按普通 HTTP 错误返回，token 保留
+        let err = moetran_get_with_retry::<Value>("teams", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::MoetranHttp { status: 403, .. }));
+        assert!(!err.is_auth_expired());
+        assert_eq!(
+            crate::token::cached_moetran_token().as_deref(),
+            Some(TEST_MOETRAN_TOKEN)
+        );
+
+        let err = poprako_post_opt::<Value, Value>("members/remove", Some(json!({})))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::PoprakoHttp { status: 403, .. }));
+        assert_eq!(
+            crate::token::cached_poprako_token().as_deref(),
+            Some(TEST_POPRAKO_TOKEN)
+        );
+
+        // PopRaKo 的 401 只清除 PopRaKo 的 token
+        let err = poprako_post_opt::<Value, Value>("projs", Some(json!({})))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::AuthExpired { backend: Backend::Poprako, ref body } if body == "expired"
+        ));
+        assert_eq!(crate::token::cached_poprako_token(), None);
+        assert_eq!(
+            crate::token::cached_moetran_token().as_deref(),
+            Some(TEST_MOETRAN_TOKEN)
+        );
+    }
 
-    let url = base
-        .join(path)
-        .map_err(|err| format!("Failed to build URL for {}: {}", path, err))?;
-
-    // Require Authorization header for non-sync endpoints
-    let mut headers = Vec::new();
-    if path != "sync" {
-        let token = crate::token::cached_poprako_token();
-        if token.is_none() {
-            return Err(
-                "Missing Poprako token: Authorization header required for this endpoint"
-                    .to_string(),
-            );
-        }
-        headers.push((
-            header::AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", token.unwrap()))
-                .map_err(|err| format!("Invalid token header value: {}", err))?,
+    #[tokio::test]
+    async fn requests_rejoin_against_a_changed_base() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "old" })))
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v2/user/info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "new" })))
+            .mount(&backends.poprako)
+            .await;
+
+        let reply: Value = moetran_get("user/info", None).await.unwrap();
+        assert_eq!(reply["id"], "old");
+        let old_client = moetran_api().unwrap();
+
+        // 带子路径的新地址：客户端按新地址重建，相对路径拼接在子路径之后
+        crate::config::update_for_test(|config| {
+            config.moetran_api_base = format!("{}/api/v2/", backends.poprako.uri());
+        });
+
+        let reply: Value = moetran_get("user/info", None).await.unwrap();
+        assert_eq!(reply["id"], "new");
+
+        let new_client = moetran_api().unwrap();
+        assert!(!Arc::ptr_eq(&old_client, &new_client));
+        assert_eq!(new_client.base_url.path(), "/api/v2/");
+
+        // 地址不变时复用同一客户端
+        assert!(Arc::ptr_eq(&new_client, &moetran_api().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn html_error_page_is_service_unavailable() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/projects"))
+            .respond_with(
+                ResponseTemplate::new(502)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string(NGINX_502),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/maintenance"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .insert_header("content-type", "text/html; charset=utf-8")
+                    .insert_header("retry-after", "120")
+                    .set_body_string("<!DOCTYPE html><html><body><p>系统维护中</p></body></html>"),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        // 维护期间以 200 返回的 HTML 页面
+        Mock::given(method("GET"))
+            .and(path("/v1/user/teams"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string("<html><title>Under maintenance</title></html>"),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        let err = moetran_get_with_retry::<Value>("user/projects", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::ServiceUnavailable { status: 502, retry_after: None, ref excerpt }
+                if excerpt == "502 Bad Gateway"
+        ));
+
+        let err = moetran_get_with_retry::<Value>("maintenance", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::ServiceUnavailable { status: 503, retry_after: Some(120), ref excerpt }
+                if excerpt == "系统维护中"
         ));
-    } else {
-        if let Some(token) = crate::token::cached_poprako_token() {
-            headers.push((
-                header::AUTHORIZATION,
-                HeaderValue::from_str(&format!("Bearer {}", token))
-                    .map_err(|err| format!("Invalid token header value: {}", err))?,
-            ));
+
+        let err = moetran_get_with_retry::<Value>("user/teams", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::ServiceUnavailable { status: 200, ref excerpt, .. }
+                if excerpt == "Under maintenance"
+        ));
+    }
+
+    #[tokio::test]
+    async fn idempotent_get_retries_transient_failures() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("{}"))
+            .up_to_n_times(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "u1" })))
+            .mount(&backends.moetran)
+            .await;
+
+        let reply: Value = moetran_get("user/info", None).await.unwrap();
+        assert_eq!(reply["id"], "u1");
+    }
+
+    // 测试用：退避时间缩短到毫秒级
+    const FAST_RETRY: RetryPolicy = RetryPolicy {
+        base_delay: Duration::from_millis(1),
+        ..RetryPolicy::IDEMPOTENT
+    };
+
+    #[tokio::test]
+    async fn get_succeeds_after_failing_twice() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/projects"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("{}"))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/projects"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{ "id": "p1" }])))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projs"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("{}"))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": [],
+                "message": null,
+            })))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        let reply: Value = moetran_get_with_retry("user/projects", None, FAST_RETRY)
+            .await
+            .unwrap();
+        assert_eq!(reply[0]["id"], "p1");
+
+        let reply: PoprakoEnvelope<Value> = poprako_get_with_retry("projs", None, FAST_RETRY)
+            .await
+            .unwrap();
+        assert_eq!(reply.code, 200);
+
+        backends.moetran.verify().await;
+        backends.poprako.verify().await;
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_max_attempts_and_skip_client_errors() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/projects"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("{}"))
+            .expect(3)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/gone"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("{}"))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        let err = moetran_get_with_retry::<Value>("user/projects", None, FAST_RETRY)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::MoetranHttp { status: 500, .. }));
+
+        let err = moetran_get_with_retry::<Value>("projects/gone", None, FAST_RETRY)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::MoetranHttp { status: 404, .. }));
+
+        backends.moetran.verify().await;
+    }
+
+    #[tokio::test]
+    async fn posts_and_none_policy_are_sent_once() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("{}"))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("{}"))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        let err = poprako_post_opt::<Value, Value>("projs", Some(json!({})))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::PoprakoHttp { status: 500, .. }));
+
+        moetran_get_with_retry::<Value>("user/info", None, RetryPolicy::NONE)
+            .await
+            .unwrap_err();
+
+        backends.moetran.verify().await;
+        backends.poprako.verify().await;
+    }
+
+    #[tokio::test]
+    async fn write_helpers_send_method_path_query_body_and_token() {
+        use wiremock::matchers::body_json;
+
+        let backends = MockBackends::start().await;
+        let moetran_auth = format!("Bearer {}", TEST_MOETRAN_TOKEN);
+        let poprako_auth = format!("Bearer {}", TEST_POPRAKO_TOKEN);
+        let ok = |data: Value| {
+            ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": data,
+                "message": null,
+            }))
+        };
+
+        Mock::given(method("PUT"))
+            .and(path("/v1/translations/t1"))
+            .and(header("authorization", moetran_auth.as_str()))
+            .and(body_json(json!({ "content": "译文" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "t1" })))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v1/sources/s1"))
+            .and(header("authorization", moetran_auth.as_str()))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v1/projsets/ps1"))
+            .and(header("authorization", poprako_auth.as_str()))
+            .respond_with(ok(json!(null)))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v1/assignments"))
+            .and(header("authorization", poprako_auth.as_str()))
+            .and(body_json(json!({ "member_id": "m1" })))
+            .respond_with(ok(json!({ "removed": 1 })))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("PUT"))
+            .and(path("/v1/projs/p1"))
+            .and(query_param("force", "true"))
+            .and(header("authorization", poprako_auth.as_str()))
+            .and(body_json(json!({ "status": 2 })))
+            .respond_with(ok(json!({ "proj_id": "p1" })))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/p1/assign"))
+            .and(query_param("notify", "false"))
+            .and(header("authorization", poprako_auth.as_str()))
+            .and(body_json(json!({ "member_id": "m1" })))
+            .respond_with(ok(json!(null)))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        let reply: Value = moetran_put_opt("translations/t1", Some(json!({ "content": "译文" })))
+            .await
+            .unwrap();
+        assert_eq!(reply["id"], "t1");
+
+        // 204 无响应体
+        moetran_delete::<()>("sources/s1").await.unwrap();
+
+        let reply: PoprakoEnvelope<Value> = poprako_delete("projsets/ps1").await.unwrap();
+        assert_eq!(reply.code, 200);
+        assert_eq!(reply.data, None);
+
+        let reply: PoprakoEnvelope<Value> =
+            poprako_delete_opt("assignments", Some(json!({ "member_id": "m1" })))
+                .await
+                .unwrap();
+        assert_eq!(reply.data.unwrap()["removed"], 1);
+
+        let force = HashMap::from([("force", "true".to_string())]);
+        let reply: PoprakoEnvelope<Value> =
+            poprako_put_with_query("projs/p1", Some(&force), Some(json!({ "status": 2 })))
+                .await
+                .unwrap();
+        assert_eq!(reply.data.unwrap()["proj_id"], "p1");
+
+        let notify = HashMap::from([("notify", "false".to_string())]);
+        poprako_post_with_query::<Value, PoprakoEnvelope<Value>>(
+            "projs/p1/assign",
+            Some(&notify),
+            Some(json!({ "member_id": "m1" })),
+        )
+        .await
+        .unwrap();
+
+        backends.moetran.verify().await;
+        backends.poprako.verify().await;
+    }
+
+    #[tokio::test]
+    async fn put_and_delete_are_never_retried() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path("/v1/translations/t1"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("{}"))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/v1/projsets/ps1"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("{}"))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        let err = moetran_put_opt::<Value, Value>("translations/t1", Some(json!({})))
+            .await
+            .unwrap_err();
+        assert!(err.is_service_unavailable());
+
+        let err = poprako_delete::<Value>("projsets/ps1").await.unwrap_err();
+        assert!(matches!(err, AppError::PoprakoHttp { status: 500, .. }));
+
+        // 以 "/" 开头的路径在发出请求之前被拒绝
+        let err = poprako_delete::<Value>("/projsets/ps1").await.unwrap_err();
+        assert!(err.to_string().contains("Invalid path for poprako_delete"));
+        let err = moetran_put_opt::<Value, Value>("", None).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid path for moetran_put_opt"));
+
+        backends.moetran.verify().await;
+        backends.poprako.verify().await;
+    }
+
+    #[test]
+    fn backoff_doubles_with_jitter_and_honours_retry_after() {
+        let transient = AppError::Network("timeout".to_string());
+        let policy = RetryPolicy::IDEMPOTENT;
+
+        for (attempt, full) in [(1, 300), (2, 600)] {
+            let delay = policy.delay_after(attempt, &transient).unwrap();
+            let full = Duration::from_millis(full);
+
+            assert!(delay >= full / 2 && delay <= full, "{:?}", delay);
         }
+
+        assert_eq!(policy.delay_after(3, &transient), None);
+        assert_eq!(RetryPolicy::NONE.delay_after(1, &transient), None);
+        assert!(RetryPolicy::IMAGE.delay_after(4, &transient).is_some());
+
+        // 退避不超过 MAX_RETRY_DELAY
+        let slow = RetryPolicy {
+            max_attempts: 20,
+            base_delay: Duration::from_secs(1),
+            retry_on: is_transient_error,
+        };
+        assert!(slow.delay_after(10, &transient).unwrap() <= MAX_RETRY_DELAY);
+
+        let unavailable = |retry_after| AppError::ServiceUnavailable {
+            status: 503,
+            retry_after,
+            excerpt: String::new(),
+        };
+        assert_eq!(
+            policy.delay_after(1, &unavailable(Some(2))),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(policy.delay_after(1, &unavailable(Some(60))), None);
+
+        let client_error = AppError::MoetranHttp {
+            status: 400,
+            body: String::new(),
+        };
+        assert_eq!(policy.delay_after(1, &client_error), None);
+    }
+
+    #[tokio::test]
+    async fn raw_download_is_not_sent_after_the_budget_is_spent() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/files/spent.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 4]))
+            .expect(0)
+            .mount(&backends.moetran)
+            .await;
+
+        let err = with_budget(
+            RequestBudget::from_ms(Some(0)),
+            moetran_get_raw("files/spent.png"),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert!(matches!(err, AppError::DeadlineExceeded));
     }
 
-    ApiClient::http_put(&client, url, headers, body).await
+    #[tokio::test]
+    async fn raw_download_timeout_is_clamped_to_the_budget() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/files/slow.png"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(vec![0u8; 4])
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&backends.moetran)
+            .await;
+
+        let started = tokio::time::Instant::now();
+
+        let err = with_budget(
+            RequestBudget::from_ms(Some(200)),
+            moetran_get_raw("files/slow.png"),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert!(matches!(err, AppError::DeadlineExceeded));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn raw_probe_respects_the_budget() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/files/probe.png"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&backends.moetran)
+            .await;
+
+        let started = tokio::time::Instant::now();
+
+        let result = with_budget(
+            RequestBudget::from_ms(Some(200)),
+            moetran_probe_raw("files/probe.png", None, None),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
 }
@@ -0,0 +1,210 @@
+// 各类标识符的 newtype：Moetran / PopRaKo 的 id 都是字符串，混用（如把 member_id 当作 user_id、
+// 把 Moetran 项目 id 填进 PopRaKo 的 projset 字段）编译器无法发现。序列化为原样字符串（serde transparent），
+// 前端与后端接口不受影响；与 String 之间需显式转换，不同种类的 id 之间不能直接赋值
+use std::{borrow::Borrow, fmt, ops::Deref};
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! define_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        // 只读访问按 &str 使用（拼接路径、作为查询参数等）
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        // 以 String 为键的表可直接用 id 查找
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(id: &String) -> Self {
+                Self(id.clone())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+
+        impl PartialEq<$name> for String {
+            fn eq(&self, other: &$name) -> bool {
+                self == &other.0
+            }
+        }
+    };
+}
+
+define_id!(
+    /// Moetran 项目 id（PopRaKo 的 proj_id 与之相同）
+    ///
+    /// 序列化为原样字符串：
+    ///
+    /// ```
+    /// use moetran_native_lib::ids::ProjectId;
+    ///
+    /// let id: ProjectId = serde_json::from_str("\"p1\"").unwrap();
+    ///
+    /// assert_eq!(id, "p1");
+    /// assert_eq!(serde_json::to_string(&id).unwrap(), "\"p1\"");
+    /// ```
+    ///
+    /// 不同种类的 id 不能互相赋值：
+    ///
+    /// ```compile_fail,E0308
+    /// use moetran_native_lib::ids::{ProjectId, ProjsetId};
+    ///
+    /// let projset_id: ProjsetId = ProjectId::new("p1");
+    /// ```
+    ///
+    /// ```compile_fail,E0308
+    /// use moetran_native_lib::ids::{ProjectId, TeamId};
+    ///
+    /// fn team_projects(team_id: &TeamId) {}
+    ///
+    /// team_projects(&ProjectId::new("p1"));
+    /// ```
+    ProjectId
+);
+
+// PopRaKo 项目集 id
+define_id!(ProjsetId);
+
+// 汉化组 id（Moetran 与 PopRaKo 共用）
+define_id!(TeamId);
+
+// Moetran 文件（页面）id
+define_id!(FileId);
+
+// Moetran 翻译目标语言 id
+define_id!(TargetId);
+
+// Moetran source（标记）id
+define_id!(SourceId);
+
+// Moetran 翻译 id
+define_id!(TranslationId);
+
+define_id!(
+    /// PopRaKo 成员 id（汉化组内的成员记录，不是用户 id）
+    ///
+    /// ```compile_fail,E0308
+    /// use moetran_native_lib::ids::{MemberId, UserId};
+    ///
+    /// let member_id: MemberId = UserId::new("u1");
+    /// ```
+    MemberId
+);
+
+// 用户 id
+define_id!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct AssignDto {
+        proj_id: ProjectId,
+        member_id: MemberId,
+        user_id: Option<UserId>,
+    }
+
+    #[test]
+    fn ids_are_plain_strings_on_the_wire() {
+        let raw = json!({ "proj_id": "p1", "member_id": "m1", "user_id": null });
+
+        let dto: AssignDto = serde_json::from_value(raw.clone()).unwrap();
+
+        assert_eq!(dto.proj_id, "p1");
+        assert_eq!(dto.member_id.as_str(), "m1");
+        assert_eq!(serde_json::to_value(&dto).unwrap(), raw);
+    }
+
+    #[test]
+    fn ids_convert_explicitly_and_look_up_by_str() {
+        let id = FileId::from("f1");
+
+        assert_eq!(id.to_string(), "f1");
+        assert_eq!(String::from(id.clone()), "f1");
+
+        let pages = HashMap::from([(id, 3)]);
+
+        assert_eq!(pages.get("f1"), Some(&3));
+    }
+}
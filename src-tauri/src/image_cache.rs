@@ -1,23 +1,50 @@
 // 图片缓存管理模块
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
-use crate::http::moetran_get_raw;
+use crate::clock::unix_now;
+use crate::config::{config, set_runtime_value};
+use crate::disk_space::{
+    available_space, ensure_disk_space, estimate_download_bytes, insufficient_disk_error,
+    is_disk_full,
+};
+use crate::error::AppError;
+use crate::events::{
+    emit_event, CacheEvicted, ConfigChanged, DownloadFinished, DownloadProgressed, EventSink,
+};
+use crate::http::{moetran_get, moetran_get_raw, moetran_probe_raw, RawProbe};
+use crate::ids::{FileId, ProjectId, TeamId};
+use crate::instance_lock;
+use crate::project::{get_project_files, GetProjectFilesReq, MoetranProjectFile, ResProject};
 use crate::storage::cache_metadata::{
-    delete_cached_project_metadata, get_all_cached_projects, get_cached_project_metadata,
-    upsert_cached_project, CachedProjectMetadata,
+    cached_projects_usage, delete_cached_project_metadata, get_all_cached_projects,
+    get_cached_project_metadata, list_least_recently_used, touch_cached_project,
+    update_remote_summary, upsert_cached_project, CachedProjectMetadata,
+};
+use crate::storage::cached_project_files::{
+    delete_project_files, list_project_files, replace_project_files, CachedProjectFileRow,
 };
 use crate::storage::LOCAL_STORAGE;
+use crate::url_refresh::{is_expired_url_error, replacement_url};
 use crate::DATA_DIR;
 
 const MAX_RETRIES: usize = 2;
 const CONCURRENT_DOWNLOADS: usize = 5;
+// 新鲜度检查只读响应头，但仍限制并发，避免短时间内大量请求
+const CONCURRENT_FRESHNESS_PROBES: usize = 4;
+// 按文件 id 命名缓存时写入的清单（页面顺序 -> 文件 id）
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
 
 /// 检查项目的图片缓存是否存在
 #[tauri::command]
 #[tracing::instrument]
-pub async fn check_file_cache(project_id: String) -> Result<bool, String> {
+pub async fn check_file_cache(project_id: ProjectId) -> Result<bool, String> {
     tracing::info!("image_cache.check_file_cache.start");
 
     let cache_dir = get_cache_dir(&project_id);
@@ -29,19 +56,323 @@ pub async fn check_file_cache(project_id: String) -> Result<bool, String> {
     Ok(exists)
 }
 
-/// 下载整个项目的所有图片到本地缓存
+// ========== 项目下载任务登记 ==========
+
+// 同一项目同时只允许一个下载任务：重复触发（连点、定时任务与手动触发重叠）时
+// 按 wait 参数等待已有任务的结果，或返回 AlreadyInProgress 错误
+
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct DownloadProgress {
+    // 需要下载的文件数（检查已缓存文件之前为 0）
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+// 下载结果：部分文件失败时不再整体报错，前端可只重试 failed 中的文件
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DownloadReport {
+    // 本次下载成功的文件数
+    pub downloaded: usize,
+    // 已缓存、无需下载的文件数
+    pub skipped: usize,
+    // 下载失败的文件（传入的 files 中的下标）
+    pub failed: Vec<usize>,
+}
+
+struct DownloadJob {
+    cancel: AtomicBool,
+    total: AtomicUsize,
+    completed: AtomicUsize,
+    failed: AtomicUsize,
+    // 任务结束时写入结果，等待中的重复调用据此返回
+    result: tokio::sync::watch::Sender<Option<Result<DownloadReport, AppError>>>,
+}
+
+impl DownloadJob {
+    fn new() -> Self {
+        Self {
+            cancel: AtomicBool::new(false),
+            total: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            result: tokio::sync::watch::Sender::new(None),
+        }
+    }
+
+    fn progress(&self) -> DownloadProgress {
+        DownloadProgress {
+            total: self.total.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn wait(&self) -> Result<DownloadReport, AppError> {
+        let mut rx = self.result.subscribe();
+
+        let result = rx
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| AppError::Other(DOWNLOAD_INTERRUPTED.to_string()))?;
+
+        result
+            .clone()
+            .unwrap_or_else(|| Err(AppError::Other(DOWNLOAD_INTERRUPTED.to_string())))
+    }
+}
+
+static DOWNLOAD_JOBS: LazyLock<Mutex<HashMap<String, Arc<DownloadJob>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 持有期间占用该项目的下载登记；无论正常结束、出错、panic 还是任务被丢弃，
+// drop 时都会注销并唤醒等待者（未写入结果时视为中断）
+struct DownloadJobGuard {
+    project_id: String,
+    job: Arc<DownloadJob>,
+}
+
+impl DownloadJobGuard {
+    fn finish(&self, result: &Result<DownloadReport, AppError>) {
+        self.job.result.send_replace(Some(result.clone()));
+    }
+}
+
+impl Drop for DownloadJobGuard {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = DOWNLOAD_JOBS.lock() {
+            if jobs
+                .get(&self.project_id)
+                .is_some_and(|job| Arc::ptr_eq(job, &self.job))
+            {
+                jobs.remove(&self.project_id);
+            }
+        }
+
+        self.job.result.send_if_modified(|result| {
+            if result.is_some() {
+                return false;
+            }
+
+            *result = Some(Err(AppError::Other(DOWNLOAD_INTERRUPTED.to_string())));
+            true
+        });
+    }
+}
+
+enum DownloadClaim {
+    Acquired(DownloadJobGuard),
+    Running(Arc<DownloadJob>),
+}
+
+fn claim_download(project_id: &str) -> Result<DownloadClaim, String> {
+    let mut jobs = DOWNLOAD_JOBS
+        .lock()
+        .map_err(|_| "download jobs lock poisoned".to_string())?;
+
+    if let Some(job) = jobs.get(project_id) {
+        return Ok(DownloadClaim::Running(job.clone()));
+    }
+
+    let job = Arc::new(DownloadJob::new());
+    jobs.insert(project_id.to_string(), job.clone());
+
+    Ok(DownloadClaim::Acquired(DownloadJobGuard {
+        project_id: project_id.to_string(),
+        job,
+    }))
+}
+
+fn download_in_progress_error(project_id: &str, progress: DownloadProgress) -> AppError {
+    AppError::AlreadyInProgress {
+        project_id: project_id.to_string(),
+        progress,
+    }
+}
+
+// 下载结束时的状态（image-cache://done 事件与缓存元数据共用）
+fn finished_status(result: &Result<DownloadReport, AppError>) -> &'static str {
+    match result {
+        Ok(report) if report.failed.is_empty() => "completed",
+        Ok(_) => "failed",
+        Err(AppError::Other(message)) if message == DOWNLOAD_CANCELLED => "cancelled",
+        Err(_) => "error",
+    }
+}
+
+const DOWNLOAD_CANCELLED: &str = "下载已取消";
+const DOWNLOAD_INTERRUPTED: &str = "下载任务已中断";
+
+/// 下载整个项目的所有图片到本地缓存。每个文件结束后发出 image-cache://progress，
+/// 全部结束后发出 image-cache://done；部分文件失败时返回的 DownloadReport 中列出失败的文件
 #[tauri::command]
-#[tracing::instrument]
+#[tracing::instrument(skip(app))]
 pub async fn download_project_files(
+    app: AppHandle,
+    project_id: ProjectId,
+    project_name: String,
+    files: Vec<FileDownloadInfo>,
+    // 为 true 时同时检查已缓存文件是否在远端被替换，只重新下载变化的文件
+    verify_freshness: Option<bool>,
+    // 该项目已在下载时：true 等待其结束并返回其结果，否则立即返回 AlreadyInProgress 错误
+    wait: Option<bool>,
+) -> Result<DownloadReport, AppError> {
+    download_project(
+        &app,
+        project_id.to_string(),
+        project_name,
+        files,
+        verify_freshness,
+        wait.unwrap_or(false),
+    )
+    .await
+}
+
+// download_project_files 的实现；事件经 EventSink 发出，测试中可直接调用
+async fn download_project(
+    app: &impl EventSink,
     project_id: String,
     project_name: String,
     files: Vec<FileDownloadInfo>,
-) -> Result<(), String> {
+    verify_freshness: Option<bool>,
+    wait: bool,
+) -> Result<DownloadReport, AppError> {
+    let guard = match claim_download(&project_id)? {
+        DownloadClaim::Acquired(guard) => guard,
+        DownloadClaim::Running(job) => {
+            let progress = job.progress();
+
+            if wait {
+                tracing::info!(?progress, "image_cache.download_project_files.join");
+                return job.wait().await;
+            }
+
+            tracing::info!(
+                ?progress,
+                "image_cache.download_project_files.already_running"
+            );
+
+            return Err(download_in_progress_error(&project_id, progress));
+        }
+    };
+
+    let result = run_download(
+        app,
+        &guard.job,
+        project_id,
+        project_name,
+        files,
+        verify_freshness,
+    )
+    .await;
+
+    finish_download(app, &guard, &result);
+
+    evict_after_download(app).await;
+
+    result
+}
+
+/// 只重新下载上次下载中缺失、为空或被截断（大小与下载时的 Content-Length 不一致）的文件。
+/// 文件列表取自下载时保存的 cached_project_files，无需重新传入；同样发出 progress / done 事件
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn retry_failed_downloads(
+    app: AppHandle,
+    project_id: ProjectId,
+) -> Result<DownloadReport, AppError> {
+    let guard = match claim_download(&project_id)? {
+        DownloadClaim::Acquired(guard) => guard,
+        DownloadClaim::Running(job) => {
+            let progress = job.progress();
+
+            tracing::info!(
+                ?progress,
+                "image_cache.retry_failed_downloads.already_running"
+            );
+
+            return Err(download_in_progress_error(&project_id, progress));
+        }
+    };
+
+    let result = run_retry(&app, &guard.job, project_id.to_string()).await;
+
+    finish_download(&app, &guard, &result);
+
+    evict_after_download(&app).await;
+
+    result
+}
+
+// 下载任务结束：写入结果唤醒等待者，并发出 image-cache://done
+fn finish_download(
+    app: &impl EventSink,
+    guard: &DownloadJobGuard,
+    result: &Result<DownloadReport, AppError>,
+) {
+    // 无论成功与否，部分文件可能已被替换
+    invalidate_memory_cache(&guard.project_id);
+
+    guard.finish(result);
+
+    app.send_event(DownloadFinished {
+        project_id: guard.project_id.clone(),
+        status: finished_status(result).to_string(),
+        report: result.as_ref().ok().cloned(),
+        message: result.as_ref().err().map(ToString::to_string),
+    });
+}
+
+/// 取消项目进行中的下载（尚未开始的文件不再下载），返回是否存在该任务
+#[tauri::command]
+#[tracing::instrument]
+pub async fn cancel_project_download(project_id: ProjectId) -> Result<bool, String> {
+    let jobs = DOWNLOAD_JOBS
+        .lock()
+        .map_err(|_| "download jobs lock poisoned".to_string())?;
+
+    match jobs.get(project_id.as_str()) {
+        Some(job) => {
+            job.cancel.store(true, Ordering::Relaxed);
+            tracing::info!(progress = ?job.progress(), "image_cache.cancel_project_download.ok");
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// 查询项目进行中的下载进度；没有下载任务时返回 None
+#[tauri::command]
+pub async fn get_project_download_progress(
+    project_id: ProjectId,
+) -> Result<Option<DownloadProgress>, String> {
+    let jobs = DOWNLOAD_JOBS
+        .lock()
+        .map_err(|_| "download jobs lock poisoned".to_string())?;
+
+    Ok(jobs.get(project_id.as_str()).map(|job| job.progress()))
+}
+
+async fn run_download(
+    app: &impl EventSink,
+    job: &Arc<DownloadJob>,
+    project_id: String,
+    project_name: String,
+    files: Vec<FileDownloadInfo>,
+    verify_freshness: Option<bool>,
+) -> Result<DownloadReport, AppError> {
     tracing::info!(
         file_count = files.len(),
         "image_cache.download_project_files.start"
     );
 
+    // 另一个实例持有数据目录锁时不写缓存，避免两边的下载互相覆盖
+    if !instance_lock::owns_lock() {
+        return Err(AppError::Other(
+            "数据目录正被另一个应用实例使用，已停止下载".to_string(),
+        ));
+    }
+
     let cache_dir = get_cache_dir(&project_id);
 
     // 创建缓存目录
@@ -49,11 +380,50 @@ pub async fn download_project_files(
         .await
         .map_err(|e| format!("创建缓存目录失败: {}", e))?;
 
-    // 检查已存在的文件，跳过下载
+    // 已缓存的文件按 stem（文件 id 或页面索引）识别，扩展名在下载后才能确定
+    let existing = scan_cached_files(&cache_dir).await?;
+    let previous = read_manifest(&cache_dir).await;
+
+    let stems: Vec<String> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| cache_stem(index, file))
+        .collect();
+
+    let outdated: HashSet<usize> = if verify_freshness.unwrap_or(false) {
+        let probes = files
+            .iter()
+            .enumerate()
+            .filter_map(|(index, file)| {
+                let file_name = existing.get(&stems[index])?;
+
+                Some(freshness_probe(
+                    &cache_dir,
+                    index,
+                    file.id.clone().map(String::from),
+                    file.url.clone(),
+                    file_name,
+                    previous
+                        .as_ref()
+                        .and_then(|m| m.find_by_file_name(file_name)),
+                ))
+            })
+            .collect();
+
+        check_freshness(probes)
+            .await
+            .into_iter()
+            .filter(|result| result.state == FreshnessState::Outdated)
+            .map(|result| result.index)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    // 检查已存在的文件，跳过下载（远端已变化的除外）
     let mut files_to_download = Vec::new();
     for (index, file) in files.iter().enumerate() {
-        let file_path = cache_dir.join(format!("{}.{}", index, get_extension(&file.url)));
-        if !file_path.exists() {
+        if !existing.contains_key(&stems[index]) || outdated.contains(&index) {
             files_to_download.push((index, file));
         } else {
             tracing::debug!(index = index, "file already cached, skip");
@@ -62,253 +432,2731 @@ pub async fn download_project_files(
 
     tracing::info!(
         total = files.len(),
+        outdated = outdated.len(),
         to_download = files_to_download.len(),
         "image_cache.download_project_files.files_checked"
     );
 
-    let mut download_failed = false;
+    // 空间明显不够时直接拒绝，避免下载到一半写满磁盘
+    ensure_disk_space(files_to_download.len()).await?;
 
-    if !files_to_download.is_empty() {
-        // 使用 semaphore 控制并发度
-        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(CONCURRENT_DOWNLOADS));
-        let mut tasks = Vec::new();
+    job.total.store(files_to_download.len(), Ordering::Relaxed);
 
-        for (index, file) in files_to_download {
-            let sem = semaphore.clone();
-            let url = file.url.clone();
-            let cache_dir = cache_dir.clone();
+    let mut report = DownloadReport {
+        skipped: files.len() - files_to_download.len(),
+        ..DownloadReport::default()
+    };
 
-            let task = tokio::spawn(async move {
-                let _permit = sem.acquire().await.unwrap();
+    // 写入时磁盘已满：尚未开始的下载不再进行
+    let disk_full = Arc::new(AtomicBool::new(false));
 
-                download_file_with_retry(&url, &cache_dir, index).await
-            });
+    let batch = files_to_download
+        .into_iter()
+        .map(|(index, file)| (index, file, stems[index].clone()))
+        .collect();
 
-            tasks.push(task);
-        }
+    let BatchOutcome {
+        mut downloaded,
+        failed,
+        cancelled,
+    } = download_batch(app, job, &project_id, &cache_dir, batch, &disk_full).await;
 
-        // 等待所有下载任务完成
-        for task in tasks {
-            match task.await {
-                Ok(Ok(_)) => {}
-                Ok(Err(e)) => {
-                    tracing::error!(error = %e, "download task failed");
-                    download_failed = true;
-                }
-                Err(e) => {
-                    tracing::error!(error = %e, "task join failed");
-                    download_failed = true;
-                }
+    let download_failed = cancelled || !failed.is_empty();
+    report.failed = failed;
+
+    report.downloaded = downloaded.len();
+
+    // 重新下载的文件：扩展名变化时删除旧文件，并清理基于旧图片生成的分块
+    for index in outdated
+        .iter()
+        .filter(|index| downloaded.contains_key(index))
+    {
+        let stem = &stems[*index];
+
+        if let (Some(old), Some(new)) = (existing.get(stem), downloaded[index].file_name.as_ref()) {
+            if old != new {
+                let _ = fs::remove_file(cache_dir.join(old)).await;
             }
         }
+
+        let _ = fs::remove_dir_all(cache_dir.join(stem)).await;
     }
 
-    // 计算缓存文件大小
-    let mut total_size_bytes = 0i64;
-    let mut file_count = 0i64;
-    for i in 0..files.len() {
-        let file_path = cache_dir.join(format!("{}.{}", i, get_extension(&files[i].url)));
-        if file_path.exists() {
-            if let Ok(metadata) = fs::metadata(&file_path).await {
-                total_size_bytes += metadata.len() as i64;
-                file_count += 1;
-            }
-        }
+    // 按页面顺序写入清单：记录实际文件名与内容类型，读取时不再根据文件名猜测
+    let mut entries = Vec::with_capacity(files.len());
+
+    for (index, file) in files.iter().enumerate() {
+        let entry = match downloaded.remove(&index) {
+            Some(entry) => entry,
+            None => match existing.get(&stems[index]) {
+                Some(file_name) => {
+                    let previous_entry = previous
+                        .as_ref()
+                        .and_then(|m| m.find_by_file_name(file_name));
+
+                    let content_type =
+                        match previous_entry.and_then(|entry| entry.content_type.clone()) {
+                            Some(content_type) => content_type,
+                            None => sniff_cached_content_type(&cache_dir.join(file_name)).await,
+                        };
+
+                    ManifestEntry {
+                        id: file.id.clone().map(String::from),
+                        file_name: Some(file_name.clone()),
+                        content_type: Some(content_type),
+                        etag: previous_entry.and_then(|entry| entry.etag.clone()),
+                        last_modified: previous_entry.and_then(|entry| entry.last_modified.clone()),
+                        url: Some(file.url.clone()),
+                        expected_size: previous_entry.and_then(|entry| entry.expected_size),
+                    }
+                }
+                None => ManifestEntry {
+                    id: file.id.clone().map(String::from),
+                    file_name: None,
+                    content_type: None,
+                    etag: None,
+                    last_modified: None,
+                    url: Some(file.url.clone()),
+                    expected_size: None,
+                },
+            },
+        };
+
+        entries.push(entry);
     }
 
-    // 写入元数据到 SQLite
     let status = if download_failed {
         "failed"
     } else {
         "completed"
     };
-    let cached_at = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
 
-    let metadata = CachedProjectMetadata {
-        project_id: project_id.clone(),
+    let metadata = save_download_state(
+        &project_id,
         project_name,
-        status: status.to_string(),
-        file_count,
-        total_size_bytes,
-        cached_at,
-    };
+        &cache_dir,
+        &files,
+        &entries,
+        status,
+        disk_full.load(Ordering::Relaxed),
+    )
+    .await?;
 
     if let Some(storage) = LOCAL_STORAGE.get() {
-        upsert_cached_project(storage.pool(), &metadata).await?;
-    } else {
-        tracing::warn!("LOCAL_STORAGE not initialized, skip metadata save");
+        // 下载时拿到的就是完整的远端文件列表，顺带更新比对结果
+        let missing = entries.iter().filter(|e| e.file_name.is_none()).count();
+
+        update_remote_summary(
+            storage.pool(),
+            &project_id,
+            Some(files.len() as i64),
+            Some(missing as i64),
+            false,
+            metadata.cached_at,
+        )
+        .await?;
     }
 
     tracing::info!(
         status = status,
-        file_count = file_count,
-        total_size_bytes = total_size_bytes,
+        file_count = metadata.file_count,
+        total_size_bytes = metadata.total_size_bytes,
         "image_cache.download_project_files.ok"
     );
 
-    if download_failed {
-        return Err("部分文件下载失败".to_string());
+    if cancelled {
+        return Err(AppError::Other(DOWNLOAD_CANCELLED.to_string()));
     }
 
-    Ok(())
+    if !report.failed.is_empty() {
+        tracing::warn!(
+            failed = report.failed.len(),
+            "image_cache.download_project_files.partial"
+        );
+    }
+
+    Ok(report)
 }
 
-/// 删除项目的图片缓存
-#[tauri::command]
-#[tracing::instrument]
-pub async fn delete_file_cache(project_id: String) -> Result<(), String> {
-    tracing::info!("image_cache.delete_file_cache.start");
+struct BatchOutcome {
+    // 下载成功的文件（页面下标 -> 清单记录）
+    downloaded: HashMap<usize, ManifestEntry>,
+    failed: Vec<usize>,
+    cancelled: bool,
+}
 
-    let cache_dir = get_cache_dir(&project_id);
+// 并发下载一批文件（页面下标、文件、缓存 stem），每个文件结束后发出进度事件；
+// 写入时磁盘已满会置位 disk_full，尚未开始的文件不再下载
+async fn download_batch(
+    app: &impl EventSink,
+    job: &Arc<DownloadJob>,
+    project_id: &str,
+    cache_dir: &Path,
+    batch: Vec<(usize, &FileDownloadInfo, String)>,
+    disk_full: &Arc<AtomicBool>,
+) -> BatchOutcome {
+    let mut outcome = BatchOutcome {
+        downloaded: HashMap::new(),
+        failed: Vec::new(),
+        cancelled: false,
+    };
 
-    if cache_dir.exists() {
-        fs::remove_dir_all(&cache_dir)
-            .await
-            .map_err(|e| format!("删除缓存目录失败: {}", e))?;
+    if batch.is_empty() {
+        return outcome;
     }
 
-    // 删除元数据
-    if let Some(storage) = LOCAL_STORAGE.get() {
-        delete_cached_project_metadata(storage.pool(), &project_id).await?;
-    } else {
-        tracing::warn!("LOCAL_STORAGE not initialized, skip metadata delete");
-    }
+    // 使用 semaphore 控制并发度
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(CONCURRENT_DOWNLOADS));
+    let mut tasks = Vec::new();
 
-    tracing::info!("image_cache.delete_file_cache.ok");
+    for (index, file, stem) in batch {
+        let sem = semaphore.clone();
+        let url = file.url.clone();
+        let cache_dir = cache_dir.to_path_buf();
+        let id = file.id.clone();
+        let project_id = project_id.to_string();
+        let disk_full = disk_full.clone();
+        let job = job.clone();
+        let app = app.clone();
 
-    Ok(())
-}
+        let task = tokio::spawn(async move {
+            let _permit = sem.acquire().await.unwrap();
 
-/// 获取所有缓存项目列表
-#[tauri::command]
-#[tracing::instrument]
-pub async fn get_all_cached_projects_list() -> Result<Vec<CachedProjectMetadata>, String> {
-    tracing::info!("image_cache.get_all_cached_projects_list.start");
+            if disk_full.load(Ordering::Relaxed) {
+                return Err(DownloadFailure::DiskFull);
+            }
 
-    if let Some(storage) = LOCAL_STORAGE.get() {
-        let projects = get_all_cached_projects(storage.pool()).await?;
+            if job.cancel.load(Ordering::Relaxed) {
+                return Err(DownloadFailure::Cancelled);
+            }
 
-        tracing::info!(
-            count = projects.len(),
-            "image_cache.get_all_cached_projects_list.ok"
-        );
+            download_with_url_refresh(&project_id, id.as_deref(), &url, &cache_dir, &stem, index)
+                .await
+                .map(|(saved, url)| {
+                    (
+                        index,
+                        ManifestEntry {
+                            id: id.map(String::from),
+                            file_name: Some(saved.file_name),
+                            content_type: Some(saved.content_type),
+                            etag: saved.etag,
+                            last_modified: saved.last_modified,
+                            url: Some(url),
+                            expected_size: Some(saved.expected_size),
+                        },
+                    )
+                })
+                .inspect(|_| {
+                    job.completed.fetch_add(1, Ordering::Relaxed);
+                    emit_progress(&app, &project_id, &job, index);
+                })
+                .inspect_err(|err| {
+                    if matches!(err, DownloadFailure::DiskFull) {
+                        disk_full.store(true, Ordering::Relaxed);
+                    }
 
-        Ok(projects)
-    } else {
-        Err("LOCAL_STORAGE not initialized".to_string())
+                    job.failed.fetch_add(1, Ordering::Relaxed);
+                    emit_progress(&app, &project_id, &job, index);
+                })
+        });
+
+        tasks.push((index, task));
+    }
+
+    // 等待所有下载任务完成
+    for (index, task) in tasks {
+        match task.await {
+            Ok(Ok((index, entry))) => {
+                outcome.downloaded.insert(index, entry);
+            }
+            Ok(Err(DownloadFailure::Cancelled)) => {
+                outcome.cancelled = true;
+            }
+            Ok(Err(DownloadFailure::DiskFull)) => {
+                outcome.failed.push(index);
+            }
+            Ok(Err(DownloadFailure::Failed(e) | DownloadFailure::UrlExpired(e))) => {
+                tracing::error!(error = %e, "download task failed");
+                outcome.failed.push(index);
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "task join failed");
+                outcome.failed.push(index);
+            }
+        }
     }
+
+    outcome
 }
 
-/// 获取单个项目的缓存元数据
-#[tauri::command]
-#[tracing::instrument]
-pub async fn get_cached_project_info(
-    project_id: String,
-) -> Result<Option<CachedProjectMetadata>, String> {
-    tracing::debug!("image_cache.get_cached_project_info.start");
+// 写入清单、文件列表与缓存元数据，返回写入的元数据；磁盘已满时以空间不足的错误返回
+async fn save_download_state(
+    project_id: &str,
+    project_name: String,
+    cache_dir: &Path,
+    files: &[FileDownloadInfo],
+    entries: &[ManifestEntry],
+    status: &str,
+    disk_full: bool,
+) -> Result<CachedProjectMetadata, AppError> {
+    // 磁盘已满时清单可能也写不进去，仍以空间不足的错误返回
+    if let Err(err) = write_manifest(cache_dir, entries).await {
+        if !disk_full {
+            return Err(err.into());
+        }
+    }
 
-    if let Some(storage) = LOCAL_STORAGE.get() {
-        let metadata = get_cached_project_metadata(storage.pool(), &project_id).await?;
+    if disk_full {
+        let remaining = files.len() - entries.iter().filter(|e| e.file_name.is_some()).count();
+        let required = estimate_download_bytes(remaining).await;
 
-        tracing::debug!(
-            found = metadata.is_some(),
-            "image_cache.get_cached_project_info.ok"
+        tracing::error!(
+            remaining,
+            required,
+            "image_cache.download_project_files.disk_full"
         );
 
-        Ok(metadata)
-    } else {
-        Err("LOCAL_STORAGE not initialized".to_string())
+        return Err(insufficient_disk_error(
+            required,
+            available_space().unwrap_or(0),
+        ));
     }
-}
 
-/// 从本地缓存读取图片（base64 编码）
-#[tauri::command]
-#[tracing::instrument]
-pub async fn load_cached_file(
-    project_id: String,
-    file_index: usize,
-) -> Result<CachedFileData, String> {
-    tracing::debug!("image_cache.load_cached_file.start");
+    // 计算缓存文件大小
+    let mut total_size_bytes = 0i64;
+    let mut file_count = 0i64;
+    for file_name in entries.iter().filter_map(|e| e.file_name.as_ref()) {
+        if let Ok(metadata) = fs::metadata(cache_dir.join(file_name)).await {
+            total_size_bytes += metadata.len() as i64;
+            file_count += 1;
+        }
+    }
 
-    let cache_dir = get_cache_dir(&project_id);
+    let cached_at = unix_now();
 
-    // 检查缓存目录是否存在
-    if !cache_dir.exists() {
-        return Err(format!("缓存目录不存在: {}", cache_dir.display()));
-    }
+    let metadata = CachedProjectMetadata {
+        project_id: project_id.to_string(),
+        project_name,
+        status: status.to_string(),
+        file_count,
+        total_size_bytes,
+        cached_at,
+        remote_file_count: None,
+        missing_count: None,
+        remote_checked_at: None,
+        remote_deleted: false,
+    };
 
-    // 查找对应索引的文件（不确定扩展名）
-    let entries = fs::read_dir(&cache_dir)
-        .await
-        .map_err(|e| format!("读取缓存目录失败: {}", e))?;
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        tracing::warn!("LOCAL_STORAGE not initialized, skip metadata save");
+        return Ok(metadata);
+    };
 
-    let mut entries = entries;
-    while let Some(entry) = entries
-        .next_entry()
-        .await
-        .map_err(|e| format!("遍历缓存目录失败: {}", e))?
-    {
-        let file_name = entry.file_name();
-        let file_name_str = file_name.to_string_lossy();
+    // 写入元数据到 SQLite
+    upsert_cached_project(storage.pool(), &metadata).await?;
+
+    // 保存完整的文件列表，供 retry_failed_downloads 使用
+    let rows: Vec<CachedProjectFileRow> = files
+        .iter()
+        .zip(entries)
+        .enumerate()
+        .map(|(index, (file, entry))| CachedProjectFileRow {
+            project_id: project_id.to_string(),
+            file_index: index as i64,
+            file_id: entry.id.clone(),
+            url: entry.url.clone().unwrap_or_else(|| file.url.clone()),
+            file_name: entry.file_name.clone(),
+            expected_size: entry.expected_size.map(|size| size as i64),
+        })
+        .collect();
 
-        // 检查文件名是否匹配索引（格式：{index}.{ext}）
-        if let Some(dot_pos) = file_name_str.rfind('.') {
-            let name_part = &file_name_str[..dot_pos];
-            if name_part == file_index.to_string() {
-                let file_path = entry.path();
-                let ext = &file_name_str[dot_pos + 1..];
-                let content_type = get_content_type(ext);
+    replace_project_files(storage.pool(), project_id, &rows).await?;
 
-                let data = fs::read(&file_path)
-                    .await
-                    .map_err(|e| format!("读取缓存文件失败: {}", e))?;
+    Ok(metadata)
+}
 
-                let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data);
+// 缓存文件需要重新下载的原因；完好（或下载时未记录大小而无法判断）时为 None
+async fn broken_file_reason(
+    cache_dir: &Path,
+    file_name: Option<&str>,
+    expected_size: Option<i64>,
+) -> Option<&'static str> {
+    let Some(file_name) = file_name else {
+        return Some("missing");
+    };
 
-                tracing::debug!("image_cache.load_cached_file.ok");
+    let Ok(metadata) = fs::metadata(cache_dir.join(file_name)).await else {
+        return Some("missing");
+    };
 
-                return Ok(CachedFileData { b64, content_type });
-            }
-        }
+    if metadata.len() == 0 {
+        return Some("empty");
+    }
+
+    if expected_size.is_some_and(|size| size as u64 != metadata.len()) {
+        return Some("truncated");
     }
 
-    Err(format!("缓存文件不存在: index {}", file_index))
+    None
 }
 
-// ========== 内部辅助函数 ==========
+async fn run_retry(
+    app: &impl EventSink,
+    job: &Arc<DownloadJob>,
+    project_id: String,
+) -> Result<DownloadReport, AppError> {
+    tracing::info!("image_cache.retry_failed_downloads.start");
+
+    if !instance_lock::owns_lock() {
+        return Err(AppError::Other(
+            "数据目录正被另一个应用实例使用，已停止下载".to_string(),
+        ));
+    }
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or_else(|| "LOCAL_STORAGE not initialized".to_string())?;
+
+    let previous = get_cached_project_metadata(storage.pool(), &project_id)
+        .await?
+        .ok_or_else(|| "该项目没有缓存记录，请先下载项目".to_string())?;
+
+    let rows = list_project_files(storage.pool(), &project_id).await?;
+
+    // 旧版本下载的缓存没有保存文件列表
+    if rows.is_empty() {
+        return Err(AppError::Other(
+            "没有该项目的文件列表，请重新下载整个项目".to_string(),
+        ));
+    }
+
+    let cache_dir = get_cache_dir(&project_id);
+
+    fs::create_dir_all(&cache_dir)
+        .await
+        .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+
+    let manifest = read_manifest(&cache_dir).await;
+
+    let files: Vec<FileDownloadInfo> = rows
+        .iter()
+        .map(|row| FileDownloadInfo {
+            url: row.url.clone(),
+            id: row.file_id.clone().map(FileId::from),
+        })
+        .collect();
+
+    let stems: Vec<String> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| cache_stem(index, file))
+        .collect();
+
+    // 以文件列表为准重建清单，内容类型与校验头沿用旧清单
+    let mut entries = Vec::with_capacity(rows.len());
+    let mut broken = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        let previous_entry = row
+            .file_name
+            .as_deref()
+            .and_then(|file_name| manifest.as_ref()?.find_by_file_name(file_name));
+
+        entries.push(ManifestEntry {
+            id: row.file_id.clone(),
+            file_name: row.file_name.clone(),
+            content_type: previous_entry.and_then(|entry| entry.content_type.clone()),
+            etag: previous_entry.and_then(|entry| entry.etag.clone()),
+            last_modified: previous_entry.and_then(|entry| entry.last_modified.clone()),
+            url: Some(row.url.clone()),
+            expected_size: row.expected_size.map(|size| size as u64),
+        });
+
+        if let Some(reason) =
+            broken_file_reason(&cache_dir, row.file_name.as_deref(), row.expected_size).await
+        {
+            tracing::debug!(index, reason, "image_cache.retry_failed_downloads.broken");
+            broken.push(index);
+        }
+    }
+
+    tracing::info!(
+        total = rows.len(),
+        to_download = broken.len(),
+        "image_cache.retry_failed_downloads.files_checked"
+    );
+
+    ensure_disk_space(broken.len()).await?;
+
+    job.total.store(broken.len(), Ordering::Relaxed);
+
+    let disk_full = Arc::new(AtomicBool::new(false));
+
+    let batch = broken
+        .iter()
+        .map(|&index| (index, &files[index], stems[index].clone()))
+        .collect();
+
+    let BatchOutcome {
+        downloaded,
+        failed,
+        cancelled,
+    } = download_batch(app, job, &project_id, &cache_dir, batch, &disk_full).await;
+
+    let report = DownloadReport {
+        downloaded: downloaded.len(),
+        skipped: rows.len() - broken.len(),
+        failed,
+    };
+
+    // 重新下载的文件：扩展名变化时删除旧文件，并清理基于损坏图片生成的分块
+    for (index, entry) in downloaded {
+        if let (Some(old), Some(new)) = (&entries[index].file_name, &entry.file_name) {
+            if old != new {
+                let _ = fs::remove_file(cache_dir.join(old)).await;
+            }
+        }
+
+        let _ = fs::remove_dir_all(cache_dir.join(&stems[index])).await;
+
+        entries[index] = entry;
+    }
+
+    // 仍缺失或损坏的页数（失败的与取消后未下载的）
+    let missing = broken.len() - report.downloaded;
+
+    let status = if missing == 0 { "completed" } else { "failed" };
+
+    let metadata = save_download_state(
+        &project_id,
+        previous.project_name,
+        &cache_dir,
+        &files,
+        &entries,
+        status,
+        disk_full.load(Ordering::Relaxed),
+    )
+    .await?;
+
+    // 文件列表不是刚从远端拉取的：保留上次比对的远端页数与时间，只更新缺失数
+    // （远端在上次比对时多出的页不在文件列表中，仍计入缺失）
+    let remote_extra = previous
+        .remote_file_count
+        .map_or(0, |count| (count - rows.len() as i64).max(0));
+
+    update_remote_summary(
+        storage.pool(),
+        &project_id,
+        previous.remote_file_count.or(Some(rows.len() as i64)),
+        Some(missing as i64 + remote_extra),
+        previous.remote_deleted,
+        previous.remote_checked_at.unwrap_or(metadata.cached_at),
+    )
+    .await?;
+
+    tracing::info!(
+        status = status,
+        downloaded = report.downloaded,
+        missing,
+        file_count = metadata.file_count,
+        "image_cache.retry_failed_downloads.ok"
+    );
+
+    if cancelled {
+        return Err(AppError::Other(DOWNLOAD_CANCELLED.to_string()));
+    }
+
+    Ok(report)
+}
+
+// 单个文件下载结束（成功或失败）后的进度事件
+fn emit_progress(app: &impl EventSink, project_id: &str, job: &DownloadJob, index: usize) {
+    let progress = job.progress();
+
+    app.send_event(DownloadProgressed {
+        project_id: project_id.to_string(),
+        completed: progress.completed,
+        total: progress.total,
+        failed: progress.failed,
+        current_file_index: index,
+    });
+}
+
+/// 删除项目的图片缓存
+#[tauri::command]
+#[tracing::instrument]
+pub async fn delete_file_cache(project_id: ProjectId) -> Result<(), String> {
+    tracing::info!("image_cache.delete_file_cache.start");
+
+    remove_project_cache(&project_id).await?;
+
+    tracing::info!("image_cache.delete_file_cache.ok");
+
+    Ok(())
+}
+
+// 删除项目的缓存目录、元数据与文件列表
+async fn remove_project_cache(project_id: &str) -> Result<(), String> {
+    invalidate_memory_cache(project_id);
+
+    let cache_dir = get_cache_dir(project_id);
+
+    if cache_dir.exists() {
+        fs::remove_dir_all(&cache_dir)
+            .await
+            .map_err(|e| format!("删除缓存目录失败: {}", e))?;
+    }
+
+    // 删除元数据
+    if let Some(storage) = LOCAL_STORAGE.get() {
+        delete_cached_project_metadata(storage.pool(), project_id).await?;
+        delete_project_files(storage.pool(), project_id).await?;
+    } else {
+        tracing::warn!("LOCAL_STORAGE not initialized, skip metadata delete");
+    }
+
+    Ok(())
+}
+
+/// 获取所有缓存项目列表
+#[tauri::command]
+#[tracing::instrument]
+pub async fn get_all_cached_projects_list() -> Result<Vec<CachedProjectMetadata>, String> {
+    tracing::info!("image_cache.get_all_cached_projects_list.start");
+
+    if let Some(storage) = LOCAL_STORAGE.get() {
+        let projects = get_all_cached_projects(storage.pool()).await?;
+
+        tracing::info!(
+            count = projects.len(),
+            "image_cache.get_all_cached_projects_list.ok"
+        );
+
+        Ok(projects)
+    } else {
+        Err("LOCAL_STORAGE not initialized".to_string())
+    }
+}
+
+/// 获取单个项目的缓存元数据
+#[tauri::command]
+#[tracing::instrument]
+pub async fn get_cached_project_info(
+    project_id: ProjectId,
+) -> Result<Option<CachedProjectMetadata>, String> {
+    tracing::debug!("image_cache.get_cached_project_info.start");
+
+    if let Some(storage) = LOCAL_STORAGE.get() {
+        let metadata = get_cached_project_metadata(storage.pool(), &project_id).await?;
+
+        tracing::debug!(
+            found = metadata.is_some(),
+            "image_cache.get_cached_project_info.ok"
+        );
+
+        Ok(metadata)
+    } else {
+        Err("LOCAL_STORAGE not initialized".to_string())
+    }
+}
+
+// ========== 磁盘缓存容量 ==========
+
+// 缓存总大小（按元数据中的 total_size_bytes 计）超过 image_cache_max_bytes 时，
+// 从最久未读取的项目开始整个删除；删除前先占用该项目的下载登记，正在下载的项目不会被淘汰。
+// 读取时间由 load_cached_file / get_thumbnail / get_tile 记录，同一项目每 ACCESS_TOUCH_INTERVAL 最多写一次库
+
+const ACCESS_TOUCH_INTERVAL: Duration = Duration::from_secs(60);
+
+static LAST_TOUCHED: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 两个下载同时结束时只让一个执行淘汰
+static EVICTION_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+// 记录项目缓存被读取；写库在后台进行，不拖慢翻页
+fn touch_project(project_id: &str) {
+    let now = Instant::now();
+
+    {
+        let Ok(mut touched) = LAST_TOUCHED.lock() else {
+            return;
+        };
+
+        if touched
+            .get(project_id)
+            .is_some_and(|at| now.duration_since(*at) < ACCESS_TOUCH_INTERVAL)
+        {
+            return;
+        }
+
+        touched.insert(project_id.to_string(), now);
+    }
+
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let project_id = project_id.to_string();
+
+    tokio::spawn(async move {
+        let accessed_at = unix_now();
+
+        if let Err(err) = touch_cached_project(storage.pool(), &project_id, accessed_at).await {
+            tracing::debug!(%project_id, error = %err, "image_cache.touch_failed");
+        }
+    });
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CacheUsage {
+    pub used_bytes: u64,
+    pub limit_bytes: u64,
+    pub project_count: usize,
+}
+
+async fn cache_usage() -> Result<CacheUsage, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or_else(|| "LOCAL_STORAGE not initialized".to_string())?;
+
+    let (used_bytes, project_count) = cached_projects_usage(storage.pool()).await?;
+
+    Ok(CacheUsage {
+        used_bytes: used_bytes.max(0) as u64,
+        limit_bytes: config().image_cache_max_bytes,
+        project_count: project_count as usize,
+    })
+}
+
+// 超出上限时淘汰最久未读取的项目，直到回到上限以内；返回被淘汰的项目
+async fn evict_over_limit(app: &impl EventSink) -> Result<Vec<String>, String> {
+    let _eviction = EVICTION_LOCK.lock().await;
+
+    // 另一个实例持有数据目录锁时不删除缓存
+    if !instance_lock::owns_lock() {
+        return Ok(Vec::new());
+    }
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or_else(|| "LOCAL_STORAGE not initialized".to_string())?;
+
+    let limit = config().image_cache_max_bytes;
+    let (used, _) = cached_projects_usage(storage.pool()).await?;
+    let mut used = used.max(0) as u64;
+
+    if used <= limit {
+        return Ok(Vec::new());
+    }
+
+    tracing::info!(used, limit, "image_cache.evict.start");
+
+    let mut evicted = Vec::new();
+
+    for (project_id, size) in list_least_recently_used(storage.pool()).await? {
+        if used <= limit {
+            break;
+        }
+
+        // 占用下载登记期间该项目无法开始下载；已在下载的项目（包括刚结束、尚未注销的）跳过
+        let _guard = match claim_download(&project_id)? {
+            DownloadClaim::Acquired(guard) => guard,
+            DownloadClaim::Running(_) => {
+                tracing::debug!(%project_id, "image_cache.evict.skip_downloading");
+                continue;
+            }
+        };
+
+        if let Err(err) = remove_project_cache(&project_id).await {
+            tracing::warn!(%project_id, error = %err, "image_cache.evict.remove_failed");
+            continue;
+        }
+
+        used = used.saturating_sub(size.max(0) as u64);
+        evicted.push(project_id);
+    }
+
+    tracing::info!(evicted = evicted.len(), used, limit, "image_cache.evict.ok");
+
+    if !evicted.is_empty() {
+        app.send_event(CacheEvicted {
+            project_ids: evicted.clone(),
+            used_bytes: used,
+            limit_bytes: limit,
+        });
+    }
+
+    Ok(evicted)
+}
+
+// 下载结束后检查容量；调用时当前项目仍登记为下载中，不会被淘汰
+async fn evict_after_download(app: &impl EventSink) {
+    if let Err(err) = evict_over_limit(app).await {
+        tracing::warn!(error = %err, "image_cache.evict.failed");
+    }
+}
+
+/// 查询图片磁盘缓存的占用与上限
+#[tauri::command]
+pub async fn get_cache_usage() -> Result<CacheUsage, String> {
+    cache_usage().await
+}
+
+/// 修改图片磁盘缓存上限（字节，None 恢复默认 2 GB），并立即按新上限淘汰
+#[tauri::command]
+#[tracing::instrument(skip(app))]
+pub async fn set_cache_limit(app: AppHandle, bytes: Option<u64>) -> Result<CacheUsage, String> {
+    tracing::info!("image_cache.set_cache_limit.start");
+
+    let value = bytes.map(|bytes| bytes.to_string());
+
+    let changed = set_runtime_value("image_cache_max_bytes", value.as_deref()).await?;
+
+    if changed {
+        emit_event(&app, ConfigChanged(config().entries().to_vec()));
+    }
+
+    let evicted = evict_over_limit(&app).await?;
+    let usage = cache_usage().await?;
+
+    tracing::info!(
+        limit = usage.limit_bytes,
+        used = usage.used_bytes,
+        evicted = evicted.len(),
+        "image_cache.set_cache_limit.ok"
+    );
+
+    Ok(usage)
+}
+
+// ========== 内存中的图片缓存 ==========
+
+// 快速翻页时会反复读盘并重新编码同一文件（网络盘上尤其明显）：编码后的整页、缩略图与分块按字节数上限做 LRU，
+// 上限由 image_memory_cache_mb 配置；单个超过上限的条目直接不缓存，而不是清空其他条目。
+// 项目缓存被删除、重新下载、迁移或重新分块时按项目失效
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum MemoryVariant {
+    Full,
+    // 长边上限
+    Thumb(u32),
+    Tile(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct MemoryKey {
+    project_id: String,
+    file_index: usize,
+    file_id: Option<String>,
+    variant: MemoryVariant,
+}
+
+#[derive(Clone)]
+enum MemoryValue {
+    File(CachedFileData),
+    Thumb(CachedThumbnailData),
+    Tile(CachedTileData),
+}
+
+impl MemoryValue {
+    // 按实际占用的字符串字节数计
+    fn bytes(&self) -> usize {
+        match self {
+            MemoryValue::File(data) => data.b64.len() + data.content_type.len(),
+            MemoryValue::Thumb(data) => data.b64.len() + data.content_type.len(),
+            MemoryValue::Tile(data) => data.b64.len() + data.content_type.len(),
+        }
+    }
+}
+
+struct MemoryEntry {
+    value: MemoryValue,
+    bytes: usize,
+    tick: u64,
+}
+
+#[derive(Default)]
+struct MemoryCache {
+    entries: HashMap<MemoryKey, MemoryEntry>,
+    // 使用顺序：tick 最小的最久未使用
+    order: BTreeMap<u64, MemoryKey>,
+    bytes: usize,
+    tick: u64,
+    // 每次失效加一；读盘前后不一致时不写入，避免把失效前读到的旧内容放回缓存
+    generation: u64,
+}
+
+impl MemoryCache {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, key: &MemoryKey) -> Option<MemoryValue> {
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(key)?;
+
+        self.order.remove(&entry.tick);
+        self.order.insert(tick, key.clone());
+        entry.tick = tick;
+
+        Some(entry.value.clone())
+    }
+
+    fn remove(&mut self, key: &MemoryKey) -> bool {
+        let Some(entry) = self.entries.remove(key) else {
+            return false;
+        };
+
+        self.order.remove(&entry.tick);
+        self.bytes -= entry.bytes;
+
+        true
+    }
+
+    // 返回淘汰的条目数
+    fn insert(&mut self, key: MemoryKey, value: MemoryValue, limit: usize) -> usize {
+        self.remove(&key);
+
+        let bytes = value.bytes();
+
+        if bytes > limit {
+            return 0;
+        }
+
+        let mut evicted = 0;
+
+        while self.bytes + bytes > limit {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.bytes -= entry.bytes;
+                evicted += 1;
+            }
+        }
+
+        let tick = self.next_tick();
+
+        self.order.insert(tick, key.clone());
+        self.entries.insert(key, MemoryEntry { value, bytes, tick });
+        self.bytes += bytes;
+
+        evicted
+    }
+
+    fn remove_where(&mut self, pred: impl Fn(&MemoryKey) -> bool) -> usize {
+        let keys: Vec<MemoryKey> = self
+            .entries
+            .keys()
+            .filter(|key| pred(key))
+            .cloned()
+            .collect();
+
+        for key in &keys {
+            self.remove(key);
+        }
+
+        self.generation += 1;
+
+        keys.len()
+    }
+}
+
+static MEMORY_CACHE: LazyLock<Mutex<MemoryCache>> =
+    LazyLock::new(|| Mutex::new(MemoryCache::default()));
+
+static MEMORY_HITS: AtomicU64 = AtomicU64::new(0);
+static MEMORY_MISSES: AtomicU64 = AtomicU64::new(0);
+static MEMORY_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+fn memory_limit_bytes() -> usize {
+    config().image_memory_cache_mb.saturating_mul(1024 * 1024)
+}
+
+// 命中时返回缓存值；未命中时返回当前 generation，供读盘后写入
+fn memory_get(key: &MemoryKey) -> Result<MemoryValue, u64> {
+    let Ok(mut cache) = MEMORY_CACHE.lock() else {
+        return Err(u64::MAX);
+    };
+
+    match cache.get(key) {
+        Some(value) => {
+            MEMORY_HITS.fetch_add(1, Ordering::Relaxed);
+            Ok(value)
+        }
+        None => {
+            MEMORY_MISSES.fetch_add(1, Ordering::Relaxed);
+            Err(cache.generation)
+        }
+    }
+}
+
+fn memory_put(key: MemoryKey, value: MemoryValue, generation: u64) {
+    let limit = memory_limit_bytes();
+
+    let Ok(mut cache) = MEMORY_CACHE.lock() else {
+        return;
+    };
+
+    if cache.generation != generation {
+        return;
+    }
+
+    let evicted = cache.insert(key, value, limit);
+
+    MEMORY_EVICTIONS.fetch_add(evicted as u64, Ordering::Relaxed);
+}
+
+// 丢弃项目的全部内存缓存（磁盘上的缓存文件被删除或替换后调用）
+pub(crate) fn invalidate_memory_cache(project_id: &str) {
+    if let Ok(mut cache) = MEMORY_CACHE.lock() {
+        let removed = cache.remove_where(|key| key.project_id == project_id);
+
+        if removed > 0 {
+            tracing::debug!(project_id, removed, "image_cache.memory.invalidated");
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImageMemoryCacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub limit_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+pub(crate) fn memory_cache_stats() -> ImageMemoryCacheStats {
+    let (entries, bytes) = MEMORY_CACHE
+        .lock()
+        .map(|cache| (cache.entries.len(), cache.bytes))
+        .unwrap_or_default();
+
+    ImageMemoryCacheStats {
+        entries,
+        bytes,
+        limit_bytes: memory_limit_bytes(),
+        hits: MEMORY_HITS.load(Ordering::Relaxed),
+        misses: MEMORY_MISSES.load(Ordering::Relaxed),
+        evictions: MEMORY_EVICTIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// 清空内存中的图片缓存，返回清除的条目数
+#[tauri::command]
+#[tracing::instrument]
+pub async fn clear_image_memory_cache() -> Result<usize, String> {
+    let removed = MEMORY_CACHE
+        .lock()
+        .map_err(|_| "image memory cache lock poisoned".to_string())?
+        .remove_where(|_| true);
+
+    tracing::info!(removed, "image_cache.clear_image_memory_cache.ok");
+
+    Ok(removed)
+}
+
+/// 从本地缓存读取图片（base64 编码）。慢路径：整张图片经 base64 编码后通过 IPC 传输，
+/// 大图时内存占用翻倍；新代码应通过 moeimg:// 协议直接加载（见 image_protocol），此命令仅为兼容保留
+#[tauri::command]
+#[tracing::instrument]
+pub async fn load_cached_file(
+    project_id: ProjectId,
+    file_index: usize,
+    file_id: Option<FileId>,
+) -> Result<CachedFileData, String> {
+    tracing::debug!("image_cache.load_cached_file.start");
+
+    touch_project(&project_id);
+
+    let memory_key = MemoryKey {
+        project_id: project_id.to_string(),
+        file_index,
+        file_id: file_id.clone().map(String::from),
+        variant: MemoryVariant::Full,
+    };
+
+    let generation = match memory_get(&memory_key) {
+        Ok(MemoryValue::File(data)) => {
+            tracing::debug!("image_cache.load_cached_file.memory_hit");
+            return Ok(data);
+        }
+        Ok(_) => u64::MAX,
+        Err(generation) => generation,
+    };
+
+    let cache_dir = get_cache_dir(&project_id);
+
+    let cached = locate_cached_file(&cache_dir, file_index, file_id.map(String::from)).await?;
+
+    let data = fs::read(&cached.path)
+        .await
+        .map_err(|e| format!("读取缓存文件失败: {}", e))?;
+
+    // 旧缓存没有记录内容类型：按文件头判断，扩展名不符时顺带修正
+    let (file_path, content_type) = match cached.content_type {
+        Some(content_type) => (cached.path, content_type),
+        None => repair_legacy_file(&cache_dir, cached.path, &data).await,
+    };
+
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data);
+
+    // 只读取文件头获取尺寸，失败时按普通图片处理
+    let (width, height) = match image::image_dimensions(&file_path) {
+        Ok((w, h)) => (Some(w), Some(h)),
+        Err(err) => {
+            tracing::debug!(error = %err, "image_cache.load_cached_file.dimensions_failed");
+            (None, None)
+        }
+    };
+
+    let is_tall =
+        matches!((width, height), (Some(w), Some(h)) if w > 0 && h >= w * TALL_ASPECT_RATIO);
+
+    let tiles_ready = read_tile_index(&cache_dir.join(&cached.stem))
+        .await
+        .is_some_and(|index| index.complete);
+
+    tracing::debug!("image_cache.load_cached_file.ok");
+
+    let data = CachedFileData {
+        b64,
+        content_type,
+        width,
+        height,
+        is_tall,
+        tiles_ready,
+    };
+
+    memory_put(memory_key, MemoryValue::File(data.clone()), generation);
+
+    Ok(data)
+}
+
+// moeimg:// 协议读取缓存图片：返回文件路径与内容类型（旧缓存按文件头判断），并记录项目被读取
+pub(crate) async fn cached_file_for_protocol(
+    project_id: &str,
+    file_index: usize,
+    file_id: Option<String>,
+) -> Result<(PathBuf, String), String> {
+    touch_project(project_id);
+
+    let cached = locate_cached_file(&get_cache_dir(project_id), file_index, file_id).await?;
+
+    let content_type = match cached.content_type {
+        Some(content_type) => content_type,
+        None => sniff_cached_content_type(&cached.path).await,
+    };
+
+    Ok((cached.path, content_type))
+}
+
+// 已缓存的文件路径（供导出等需要直接读取原图的功能使用）；未缓存时为 None
+pub(crate) async fn cached_file_path(project_id: &str, file_id: &str) -> Option<PathBuf> {
+    let cached = locate_cached_file(&get_cache_dir(project_id), 0, Some(file_id.to_string()))
+        .await
+        .ok()?;
+
+    cached.path.exists().then_some(cached.path)
+}
+
+struct CachedFileRef {
+    stem: String,
+    path: PathBuf,
+    // 清单中记录的内容类型；旧缓存为 None
+    content_type: Option<String>,
+}
+
+// 存在清单时按文件 id（或页面索引）查找；旧格式清单只记录了 id，需要按 stem 在目录中查找
+async fn locate_cached_file(
+    cache_dir: &Path,
+    file_index: usize,
+    file_id: Option<String>,
+) -> Result<CachedFileRef, String> {
+    // 检查缓存目录是否存在
+    if !cache_dir.exists() {
+        return Err(format!("缓存目录不存在: {}", cache_dir.display()));
+    }
+
+    let stem = match read_manifest(cache_dir).await {
+        Some(Manifest::Entries(entries)) => {
+            let entry = match &file_id {
+                Some(id) => entries.iter().find(|e| e.id.as_deref() == Some(id)),
+                None => entries.get(file_index),
+            }
+            .ok_or_else(|| format!("缓存文件不存在: index {}", file_index))?;
+
+            if let Some(file_name) = &entry.file_name {
+                let stem = file_name
+                    .rsplit_once('.')
+                    .map(|(stem, _)| stem)
+                    .unwrap_or(file_name);
+
+                return Ok(CachedFileRef {
+                    stem: stem.to_string(),
+                    path: cache_dir.join(file_name),
+                    content_type: entry.content_type.clone(),
+                });
+            }
+
+            return Err(format!("缓存文件不存在: index {}", file_index));
+        }
+        Some(Manifest::LegacyIds(ids)) => match file_id {
+            Some(id) => id,
+            None => ids
+                .get(file_index)
+                .cloned()
+                .ok_or_else(|| format!("缓存文件不存在: index {}", file_index))?,
+        },
+        None => file_index.to_string(),
+    };
+
+    let existing = scan_cached_files(cache_dir).await?;
+
+    let file_name = existing
+        .get(&stem)
+        .ok_or_else(|| format!("缓存文件不存在: {}", stem))?;
+
+    Ok(CachedFileRef {
+        path: cache_dir.join(file_name),
+        stem,
+        content_type: None,
+    })
+}
+
+// 列出缓存目录中的图片文件，key 为 stem（{stem}.{ext}），跳过清单、临时文件与分块目录
+async fn scan_cached_files(cache_dir: &Path) -> Result<HashMap<String, String>, String> {
+    let mut entries = fs::read_dir(cache_dir)
+        .await
+        .map_err(|e| format!("读取缓存目录失败: {}", e))?;
+
+    let mut files = HashMap::new();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("遍历缓存目录失败: {}", e))?
+    {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name == MANIFEST_FILE || file_name.ends_with(".tmp") {
+            continue;
+        }
+
+        if let Some((stem, _)) = file_name.rsplit_once('.') {
+            files.insert(stem.to_string(), file_name.clone());
+        }
+    }
+
+    Ok(files)
+}
+
+// 旧缓存（按 URL 猜测扩展名）：按文件头判断真实类型，扩展名不符时改名并更新清单
+async fn repair_legacy_file(cache_dir: &Path, path: PathBuf, data: &[u8]) -> (PathBuf, String) {
+    let current_ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let Some(sniffed) = sniff_extension(data) else {
+        return (path, get_content_type(&current_ext));
+    };
+
+    let content_type = get_content_type(sniffed);
+
+    if get_content_type(&current_ext) == content_type {
+        return (path, content_type);
+    }
+
+    let fixed = path.with_extension(sniffed);
+
+    if let Err(err) = fs::rename(&path, &fixed).await {
+        tracing::warn!(error = %err, "image_cache.repair.rename_failed");
+        return (path, content_type);
+    }
+
+    tracing::info!(from = %path.display(), to = %fixed.display(), "image_cache.repair.ok");
+
+    // 清单中的旧文件名同步更新（旧格式清单只记录 id，无需修改）
+    if let Some(Manifest::Entries(mut entries)) = read_manifest(cache_dir).await {
+        let old_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+        let new_name = fixed.file_name().map(|n| n.to_string_lossy().to_string());
+
+        for entry in entries.iter_mut().filter(|e| e.file_name == old_name) {
+            entry.file_name = new_name.clone();
+            entry.content_type = Some(content_type.clone());
+        }
+
+        if let Err(err) = write_manifest(cache_dir, &entries).await {
+            tracing::warn!(error = %err, "image_cache.repair.manifest_failed");
+        }
+    }
+
+    (fixed, content_type)
+}
+
+// ========== 缩略图 ==========
+
+const DEFAULT_THUMBNAIL_EDGE: u32 = 256;
+const MIN_THUMBNAIL_EDGE: u32 = 64;
+const MAX_THUMBNAIL_EDGE: u32 = 1024;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CachedThumbnailData {
+    pub b64: String,
+    pub content_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+// 按比例缩小到长边不超过 max_edge（不放大，短边至少 1 像素）
+fn thumbnail_size(width: u32, height: u32, max_edge: u32) -> (u32, u32) {
+    let long = width.max(height);
+
+    if long <= max_edge {
+        return (width, height);
+    }
+
+    let scale =
+        |side: u32| ((side as u64 * max_edge as u64 + long as u64 / 2) / long as u64).max(1) as u32;
+
+    (scale(width), scale(height))
+}
+
+// 在阻塞线程中执行：解码、缩小后编码为 JPEG
+fn render_thumbnail(source: &Path, max_edge: u32) -> Result<CachedThumbnailData, String> {
+    let img = image::open(source).map_err(|e| format!("解码图片失败: {}", e))?;
+
+    let (width, height) = thumbnail_size(img.width(), img.height(), max_edge);
+
+    let img = if (width, height) != (img.width(), img.height()) {
+        img.resize_exact(width, height, image::imageops::FilterType::Triangle)
+    } else {
+        img
+    };
+
+    let mut jpeg = Vec::new();
+
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, THUMBNAIL_JPEG_QUALITY)
+        .encode_image(&img.to_rgb8())
+        .map_err(|e| format!("编码缩略图失败: {}", e))?;
+
+    Ok(CachedThumbnailData {
+        b64: base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &jpeg),
+        content_type: get_content_type("jpg"),
+        width,
+        height,
+    })
+}
+
+/// 读取缩略图（base64 编码的 JPEG），长边不超过 max_edge（默认 256）；只保存在内存缓存中
+#[tauri::command]
+#[tracing::instrument]
+pub async fn get_thumbnail(
+    project_id: ProjectId,
+    file_index: usize,
+    file_id: Option<FileId>,
+    max_edge: Option<u32>,
+) -> Result<CachedThumbnailData, String> {
+    tracing::debug!("image_cache.get_thumbnail.start");
+
+    touch_project(&project_id);
+
+    let max_edge = max_edge
+        .unwrap_or(DEFAULT_THUMBNAIL_EDGE)
+        .clamp(MIN_THUMBNAIL_EDGE, MAX_THUMBNAIL_EDGE);
+
+    let memory_key = MemoryKey {
+        project_id: project_id.to_string(),
+        file_index,
+        file_id: file_id.clone().map(String::from),
+        variant: MemoryVariant::Thumb(max_edge),
+    };
+
+    let generation = match memory_get(&memory_key) {
+        Ok(MemoryValue::Thumb(data)) => {
+            tracing::debug!("image_cache.get_thumbnail.memory_hit");
+            return Ok(data);
+        }
+        Ok(_) => u64::MAX,
+        Err(generation) => generation,
+    };
+
+    let cache_dir = get_cache_dir(&project_id);
+
+    let source = locate_cached_file(&cache_dir, file_index, file_id.map(String::from))
+        .await?
+        .path;
+
+    let data = tokio::task::spawn_blocking(move || render_thumbnail(&source, max_edge))
+        .await
+        .map_err(|e| format!("缩略图任务失败: {}", e))??;
+
+    tracing::debug!(
+        width = data.width,
+        height = data.height,
+        "image_cache.get_thumbnail.ok"
+    );
+
+    memory_put(memory_key, MemoryValue::Thumb(data.clone()), generation);
+
+    Ok(data)
+}
+
+// ========== 长条图分块（webtoon） ==========
+
+// 高度达到宽度的该倍数时视为长条图，前端应改用分块渲染
+const TALL_ASPECT_RATIO: u32 = 3;
+const DEFAULT_TILE_HEIGHT: u32 = 2048;
+const MIN_TILE_HEIGHT: u32 = 256;
+// 分块目录（{stem}/）下的索引文件，记录各分块的纵向偏移
+const TILE_INDEX_FILE: &str = "tiles.json";
+
+// 进行中的分块任务的取消标记，key: (project_id, stem)
+type TileJobs = HashMap<(String, String), Arc<AtomicBool>>;
+
+static TILE_JOBS: LazyLock<Mutex<TileJobs>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TileInfo {
+    pub n: usize,
+    pub offset_y: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TileIndex {
+    pub source_width: u32,
+    pub source_height: u32,
+    pub tile_height: u32,
+    pub tiles: Vec<TileInfo>,
+    // 全部分块写入完成后才为 true；中途取消时保留已生成的分块，下次跳过
+    pub complete: bool,
+}
+
+// 按固定高度切分，最后一块取剩余高度；相邻分块首尾相接，无重叠无缝隙
+fn plan_tiles(source_height: u32, tile_height: u32) -> Vec<TileInfo> {
+    (0..source_height)
+        .step_by(tile_height as usize)
+        .enumerate()
+        .map(|(n, offset_y)| TileInfo {
+            n,
+            offset_y,
+            height: tile_height.min(source_height - offset_y),
+        })
+        .collect()
+}
+
+fn tile_path(tile_dir: &Path, n: usize) -> PathBuf {
+    tile_dir.join(format!("tile_{}.webp", n))
+}
+
+async fn read_tile_index(tile_dir: &Path) -> Option<TileIndex> {
+    let data = fs::read(tile_dir.join(TILE_INDEX_FILE)).await.ok()?;
+
+    serde_json::from_slice(&data).ok()
+}
+
+fn write_tile_index(tile_dir: &Path, index: &TileIndex) -> Result<(), String> {
+    let data = serde_json::to_vec(index).map_err(|e| format!("序列化分块索引失败: {}", e))?;
+
+    std::fs::write(tile_dir.join(TILE_INDEX_FILE), data)
+        .map_err(|e| format!("写入分块索引失败: {}", e))
+}
+
+// 在阻塞线程中执行：解码整张图并逐块编码为 webp，每块之间检查取消标记
+fn slice_tiles(
+    source: &Path,
+    tile_dir: &Path,
+    tile_height: u32,
+    cancel: &AtomicBool,
+) -> Result<TileIndex, String> {
+    let (width, height) =
+        image::image_dimensions(source).map_err(|e| format!("读取图片尺寸失败: {}", e))?;
+
+    let existing = std::fs::read(tile_dir.join(TILE_INDEX_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice::<TileIndex>(&data).ok());
+
+    let same_params = |index: &TileIndex| {
+        index.tile_height == tile_height
+            && index.source_width == width
+            && index.source_height == height
+    };
+
+    match existing {
+        Some(index)
+            if same_params(&index)
+                && index.complete
+                && index
+                    .tiles
+                    .iter()
+                    .all(|t| tile_path(tile_dir, t.n).exists()) =>
+        {
+            return Ok(index);
+        }
+        // 参数一致但未完成：在已有分块基础上继续
+        Some(index) if same_params(&index) => {}
+        // 分块高度或源图变化，旧分块作废
+        Some(_) => {
+            std::fs::remove_dir_all(tile_dir).map_err(|e| format!("清理旧分块失败: {}", e))?;
+        }
+        None => {}
+    }
+
+    std::fs::create_dir_all(tile_dir).map_err(|e| format!("创建分块目录失败: {}", e))?;
+
+    let mut index = TileIndex {
+        source_width: width,
+        source_height: height,
+        tile_height,
+        tiles: plan_tiles(height, tile_height),
+        complete: false,
+    };
+
+    write_tile_index(tile_dir, &index)?;
+
+    let img = image::open(source).map_err(|e| format!("解码图片失败: {}", e))?;
+
+    for tile in &index.tiles {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("分块生成已取消".to_string());
+        }
+
+        let path = tile_path(tile_dir, tile.n);
+
+        if path.exists() {
+            continue;
+        }
+
+        // 先写临时文件再改名，避免中断时留下不完整的分块被当作已生成
+        let tmp_path = path.with_extension("webp.tmp");
+
+        image::DynamicImage::from(
+            img.crop_imm(0, tile.offset_y, width, tile.height)
+                .to_rgba8(),
+        )
+        .save_with_format(&tmp_path, image::ImageFormat::WebP)
+        .map_err(|e| format!("编码分块 {} 失败: {}", tile.n, e))?;
+
+        std::fs::rename(&tmp_path, &path)
+            .map_err(|e| format!("写入分块 {} 失败: {}", tile.n, e))?;
+    }
+
+    index.complete = true;
+    write_tile_index(tile_dir, &index)?;
+
+    Ok(index)
+}
+
+/// 将缓存的长条图切分为固定高度的分块（幂等：已生成的分块会跳过）
+#[tauri::command]
+#[tracing::instrument]
+pub async fn generate_tiles(
+    project_id: ProjectId,
+    file_index: usize,
+    file_id: Option<FileId>,
+    tile_height: Option<u32>,
+) -> Result<TileIndex, String> {
+    tracing::info!("image_cache.generate_tiles.start");
+
+    let cache_dir = get_cache_dir(&project_id);
+
+    let cached = locate_cached_file(&cache_dir, file_index, file_id.map(String::from)).await?;
+    let source = cached.path;
+    let stem = cached.stem;
+    let tile_dir = cache_dir.join(&stem);
+    let tile_height = tile_height
+        .unwrap_or(DEFAULT_TILE_HEIGHT)
+        .max(MIN_TILE_HEIGHT);
+
+    let key = (project_id.into_inner(), stem);
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    {
+        let mut jobs = TILE_JOBS
+            .lock()
+            .map_err(|_| "tile jobs lock poisoned".to_string())?;
+
+        if jobs.contains_key(&key) {
+            return Err("该文件正在生成分块".to_string());
+        }
+
+        jobs.insert(key.clone(), cancel.clone());
+    }
+
+    let result =
+        tokio::task::spawn_blocking(move || slice_tiles(&source, &tile_dir, tile_height, &cancel))
+            .await;
+
+    if let Ok(mut jobs) = TILE_JOBS.lock() {
+        jobs.remove(&key);
+    }
+
+    // 分块可能被重新生成，整页数据中的 tiles_ready 也随之变化
+    invalidate_memory_cache(&key.0);
+
+    let index = result.map_err(|e| format!("分块任务异常: {}", e))??;
+
+    tracing::info!(tiles = index.tiles.len(), "image_cache.generate_tiles.ok");
+
+    Ok(index)
+}
+
+/// 取消进行中的分块生成，返回是否存在该任务
+#[tauri::command]
+#[tracing::instrument]
+pub async fn cancel_tile_generation(
+    project_id: ProjectId,
+    file_index: usize,
+    file_id: Option<FileId>,
+) -> Result<bool, String> {
+    let cache_dir = get_cache_dir(&project_id);
+
+    let stem = locate_cached_file(&cache_dir, file_index, file_id.map(String::from))
+        .await?
+        .stem;
+
+    let jobs = TILE_JOBS
+        .lock()
+        .map_err(|_| "tile jobs lock poisoned".to_string())?;
+
+    match jobs.get(&(project_id.into_inner(), stem)) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            tracing::info!("image_cache.cancel_tile_generation.ok");
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct CachedTileData {
+    pub b64: String,
+    pub content_type: String,
+    pub offset_y: u32,
+    pub height: u32,
+}
+
+/// 读取单个分块（base64 编码）
+#[tauri::command]
+#[tracing::instrument]
+pub async fn get_tile(
+    project_id: ProjectId,
+    file_index: usize,
+    file_id: Option<FileId>,
+    tile_n: usize,
+) -> Result<CachedTileData, String> {
+    tracing::debug!("image_cache.get_tile.start");
+
+    touch_project(&project_id);
+
+    let memory_key = MemoryKey {
+        project_id: project_id.to_string(),
+        file_index,
+        file_id: file_id.clone().map(String::from),
+        variant: MemoryVariant::Tile(tile_n),
+    };
+
+    let generation = match memory_get(&memory_key) {
+        Ok(MemoryValue::Tile(data)) => {
+            tracing::debug!("image_cache.get_tile.memory_hit");
+            return Ok(data);
+        }
+        Ok(_) => u64::MAX,
+        Err(generation) => generation,
+    };
+
+    let cache_dir = get_cache_dir(&project_id);
+
+    let stem = locate_cached_file(&cache_dir, file_index, file_id.map(String::from))
+        .await?
+        .stem;
+    let tile_dir = cache_dir.join(&stem);
+
+    let index = read_tile_index(&tile_dir)
+        .await
+        .ok_or_else(|| "分块尚未生成".to_string())?;
+
+    let tile = index
+        .tiles
+        .get(tile_n)
+        .ok_or_else(|| format!("分块不存在: {}", tile_n))?;
+
+    let data = fs::read(tile_path(&tile_dir, tile_n))
+        .await
+        .map_err(|e| format!("读取分块失败: {}", e))?;
+
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data);
+
+    tracing::debug!("image_cache.get_tile.ok");
+
+    let data = CachedTileData {
+        b64,
+        content_type: get_content_type("webp"),
+        offset_y: tile.offset_y,
+        height: tile.height,
+    };
+
+    memory_put(memory_key, MemoryValue::Tile(data.clone()), generation);
+
+    Ok(data)
+}
+
+// ========== 项目重建后的缓存迁移 ==========
+
+// 抽样校验的文件数（首、尾及均匀分布的中间文件）
+const RELINK_SAMPLE_SIZE: usize = 5;
+
+#[derive(Debug, serde::Serialize)]
+pub struct RelinkCacheResult {
+    pub relinked: bool,
+    pub cached_file_count: usize,
+    pub remote_file_count: usize,
+    // 文件数不一致等需要用户注意的情况；未使用 force 时不会执行迁移
+    pub warning: Option<String>,
+}
+
+/// 将旧项目 id 下的缓存迁移到新项目 id（项目在 Moetran 上删除重建后使用）
+#[tauri::command]
+#[tracing::instrument]
+pub async fn relink_project_cache(
+    old_project_id: ProjectId,
+    new_project_id: ProjectId,
+    force: Option<bool>,
+) -> Result<RelinkCacheResult, String> {
+    tracing::info!("image_cache.relink_project_cache.start");
+
+    let force = force.unwrap_or(false);
+
+    if old_project_id == new_project_id {
+        return Err("新旧项目 id 相同".to_string());
+    }
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let old_dir = get_cache_dir(&old_project_id);
+    let new_dir = get_cache_dir(&new_project_id);
+
+    if !old_dir.exists() {
+        return Err(format!("旧项目缓存不存在: {}", old_project_id));
+    }
+
+    if new_dir.exists() {
+        return Err(format!("新项目已存在缓存，请先删除: {}", new_project_id));
+    }
+
+    let old_metadata = get_cached_project_metadata(storage.pool(), &old_project_id)
+        .await?
+        .ok_or_else(|| format!("旧项目缓存元数据不存在: {}", old_project_id))?;
+
+    let cached_sizes = collect_cached_sizes(&old_dir).await?;
+
+    let remote_files = get_project_files(GetProjectFilesReq {
+        project_id: new_project_id.clone(),
+        target_id: None,
+        sort: None,
+    })
+    .await?;
+
+    let mut result = RelinkCacheResult {
+        relinked: false,
+        cached_file_count: cached_sizes.len(),
+        remote_file_count: remote_files.len(),
+        warning: None,
+    };
+
+    if cached_sizes.len() != remote_files.len() {
+        let warning = format!(
+            "缓存文件数（{}）与新项目文件数（{}）不一致，迁移后页面可能错位",
+            cached_sizes.len(),
+            remote_files.len()
+        );
+
+        tracing::warn!(
+            cached = cached_sizes.len(),
+            remote = remote_files.len(),
+            force,
+            "image_cache.relink_project_cache.count_mismatch"
+        );
+
+        if !force {
+            result.warning = Some(warning);
+            return Ok(result);
+        }
+
+        result.warning = Some(warning);
+    }
+
+    invalidate_memory_cache(&old_project_id);
+    invalidate_memory_cache(&new_project_id);
+
+    move_cache_dir(&old_dir, &new_dir, &cached_sizes).await?;
+
+    // 迁移后抽样校验文件大小，不一致则回滚
+    if let Err(err) = verify_sample(&new_dir, &cached_sizes).await {
+        tracing::error!(error = %err, "image_cache.relink_project_cache.verify_failed");
+        rollback_move(&new_dir, &old_dir).await;
+        return Err(err);
+    }
+
+    let new_metadata = CachedProjectMetadata {
+        project_id: new_project_id.to_string(),
+        ..old_metadata
+    };
+
+    let db_result = async {
+        upsert_cached_project(storage.pool(), &new_metadata).await?;
+        delete_cached_project_metadata(storage.pool(), &old_project_id).await
+    }
+    .await;
+
+    if let Err(err) = db_result {
+        tracing::error!(error = %err, "image_cache.relink_project_cache.metadata_failed");
+
+        // 元数据回滚尽力而为：删除可能已写入的新记录，目录移回原处
+        let _ = delete_cached_project_metadata(storage.pool(), &new_project_id).await;
+        rollback_move(&new_dir, &old_dir).await;
+
+        return Err(err);
+    }
+
+    // 文件列表仍指向旧项目的文件，不能用于新项目的重试；重新下载时会重建
+    if let Err(err) = delete_project_files(storage.pool(), &old_project_id).await {
+        tracing::warn!(error = %err, "image_cache.relink_project_cache.files_cleanup_failed");
+    }
+
+    result.relinked = true;
+
+    tracing::info!(
+        files = cached_sizes.len(),
+        "image_cache.relink_project_cache.ok"
+    );
+
+    Ok(result)
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CacheRelinkSuggestion {
+    pub old_project_id: ProjectId,
+    pub new_project_id: ProjectId,
+    pub project_name: String,
+    pub cached_file_count: i64,
+}
+
+/// 按项目名匹配：缓存中存在、但 id 已不在当前汉化组项目列表中的项目，提示可迁移到同名新项目
+#[tauri::command]
+#[tracing::instrument]
+pub async fn suggest_cache_relinks(team_id: TeamId) -> Result<Vec<CacheRelinkSuggestion>, String> {
+    tracing::info!("image_cache.suggest_cache_relinks.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let cached = get_all_cached_projects(storage.pool()).await?;
+
+    let mut query = std::collections::HashMap::new();
+    query.insert("page", "1".to_string());
+    query.insert("limit", "100".to_string());
+    query.insert("status", "0".to_string());
+
+    let path = format!("teams/{}/projects", team_id);
+
+    let team_projects: Vec<ResProject> = moetran_get(&path, Some(&query))
+        .await
+        .map_err(|err| format!("获取团队项目列表失败: {}", err))?;
+
+    let cached_ids: std::collections::HashSet<&str> =
+        cached.iter().map(|c| c.project_id.as_str()).collect();
+    let team_ids: std::collections::HashSet<&str> =
+        team_projects.iter().map(|p| p.id.as_str()).collect();
+
+    let normalize = |name: &str| name.trim().to_lowercase();
+
+    let suggestions: Vec<CacheRelinkSuggestion> = cached
+        .iter()
+        .filter(|c| !team_ids.contains(c.project_id.as_str()))
+        .filter_map(|c| {
+            let candidate = team_projects.iter().find(|p| {
+                normalize(&p.name) == normalize(&c.project_name)
+                    && !cached_ids.contains(p.id.as_str())
+            })?;
+
+            Some(CacheRelinkSuggestion {
+                old_project_id: c.project_id.clone().into(),
+                new_project_id: candidate.id.clone(),
+                project_name: c.project_name.clone(),
+                cached_file_count: c.file_count,
+            })
+        })
+        .collect();
+
+    tracing::info!(
+        count = suggestions.len(),
+        "image_cache.suggest_cache_relinks.ok"
+    );
+
+    Ok(suggestions)
+}
+
+// 收集缓存目录中的文件及大小（按文件名排序）
+pub(crate) async fn collect_cached_sizes(dir: &Path) -> Result<Vec<(String, u64)>, String> {
+    let mut entries = fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("读取缓存目录失败: {}", e))?;
+
+    let mut sizes = Vec::new();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("遍历缓存目录失败: {}", e))?
+    {
+        let metadata = entry
+            .metadata()
+            .await
+            .map_err(|e| format!("读取缓存文件信息失败: {}", e))?;
+
+        if metadata.is_file() && entry.file_name() != MANIFEST_FILE {
+            sizes.push((
+                entry.file_name().to_string_lossy().to_string(),
+                metadata.len(),
+            ));
+        }
+    }
+
+    sizes.sort();
+
+    Ok(sizes)
+}
+
+// 优先原子 rename；跨文件系统时退化为 复制 -> 校验 -> 删除原目录
+async fn move_cache_dir(from: &Path, to: &Path, sizes: &[(String, u64)]) -> Result<(), String> {
+    if fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+
+    tracing::info!("image_cache.relink.rename_failed_fallback_copy");
+
+    let copy_result = async {
+        fs::create_dir_all(to)
+            .await
+            .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+
+        if from.join(MANIFEST_FILE).exists() {
+            fs::copy(from.join(MANIFEST_FILE), to.join(MANIFEST_FILE))
+                .await
+                .map_err(|e| format!("复制缓存清单失败: {}", e))?;
+        }
+
+        for (name, size) in sizes {
+            let copied = fs::copy(from.join(name), to.join(name))
+                .await
+                .map_err(|e| format!("复制缓存文件 {} 失败: {}", name, e))?;
+
+            if copied != *size {
+                return Err(format!("复制缓存文件 {} 大小不一致", name));
+            }
+        }
+
+        Ok(())
+    }
+    .await;
+
+    if let Err(err) = copy_result {
+        let _ = fs::remove_dir_all(to).await;
+        return Err(err);
+    }
+
+    fs::remove_dir_all(from)
+        .await
+        .map_err(|e| format!("删除旧缓存目录失败: {}", e))
+}
+
+async fn rollback_move(from: &Path, to: &Path) {
+    let sizes = match collect_cached_sizes(from).await {
+        Ok(sizes) => sizes,
+        Err(err) => {
+            tracing::error!(error = %err, "image_cache.relink.rollback_failed");
+            return;
+        }
+    };
+
+    if let Err(err) = move_cache_dir(from, to, &sizes).await {
+        tracing::error!(error = %err, "image_cache.relink.rollback_failed");
+    }
+}
+
+async fn verify_sample(dir: &Path, sizes: &[(String, u64)]) -> Result<(), String> {
+    if sizes.is_empty() {
+        return Ok(());
+    }
+
+    let step = (sizes.len() / RELINK_SAMPLE_SIZE).max(1);
+
+    let mut samples: Vec<&(String, u64)> = sizes.iter().step_by(step).collect();
+
+    if let Some(last) = sizes.last() {
+        samples.push(last);
+    }
+
+    for (name, size) in samples {
+        let metadata = fs::metadata(dir.join(name))
+            .await
+            .map_err(|e| format!("校验缓存文件 {} 失败: {}", name, e))?;
+
+        if metadata.len() != *size {
+            return Err(format!("缓存文件 {} 大小校验失败", name));
+        }
+    }
+
+    Ok(())
+}
+
+// ========== 缓存新鲜度检查 ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshnessState {
+    Fresh,
+    // 远端文件已被替换（同一文件 id 重新上传）
+    Outdated,
+    // 该页尚未缓存
+    NotCached,
+    // 请求失败或远端已无此文件
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshnessConfidence {
+    // 依据 ETag / Last-Modified 判断
+    High,
+    // 服务端未提供校验头，仅比较文件大小
+    Low,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileFreshness {
+    pub index: usize,
+    pub file_id: Option<FileId>,
+    pub state: FreshnessState,
+    pub confidence: FreshnessConfidence,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CacheFreshnessReport {
+    pub project_id: ProjectId,
+    pub checked: usize,
+    pub outdated: usize,
+    pub entries: Vec<FileFreshness>,
+}
+
+// 单个已缓存文件的检查参数
+struct FreshnessProbe {
+    index: usize,
+    file_id: Option<String>,
+    url: String,
+    path: PathBuf,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+fn freshness_probe(
+    cache_dir: &Path,
+    index: usize,
+    file_id: Option<String>,
+    url: String,
+    file_name: &str,
+    entry: Option<&ManifestEntry>,
+) -> FreshnessProbe {
+    FreshnessProbe {
+        index,
+        file_id,
+        url,
+        path: cache_dir.join(file_name),
+        etag: entry.and_then(|e| e.etag.clone()),
+        last_modified: entry.and_then(|e| e.last_modified.clone()),
+    }
+}
+
+async fn probe_freshness(probe: FreshnessProbe) -> FileFreshness {
+    let has_validators = probe.etag.is_some() || probe.last_modified.is_some();
+
+    let result = |state, confidence, reason: Option<String>| FileFreshness {
+        index: probe.index,
+        file_id: probe.file_id.clone().map(FileId::from),
+        state,
+        confidence,
+        reason,
+    };
+
+    let confidence = if has_validators {
+        FreshnessConfidence::High
+    } else {
+        FreshnessConfidence::Low
+    };
+
+    let (etag, last_modified, content_length) = match moetran_probe_raw(
+        &probe.url,
+        probe.etag.as_deref(),
+        probe.last_modified.as_deref(),
+    )
+    .await
+    {
+        Ok(RawProbe::NotModified) => return result(FreshnessState::Fresh, confidence, None),
+        Ok(RawProbe::Fetched {
+            etag,
+            last_modified,
+            content_length,
+        }) => (etag, last_modified, content_length),
+        Err(err) => return result(FreshnessState::Unknown, confidence, Some(err)),
+    };
+
+    if has_validators {
+        // 部分服务端忽略条件请求头，直接比较返回的校验头
+        let unchanged = match (&probe.etag, &etag) {
+            (Some(old), Some(new)) => old == new,
+            _ => probe.last_modified.is_some() && probe.last_modified == last_modified,
+        };
+
+        let state = if unchanged {
+            FreshnessState::Fresh
+        } else {
+            FreshnessState::Outdated
+        };
+
+        return result(state, FreshnessConfidence::High, None);
+    }
+
+    let local_size = fs::metadata(&probe.path).await.ok().map(|m| m.len());
+
+    match (content_length, local_size) {
+        (Some(remote), Some(local)) if remote == local => {
+            result(FreshnessState::Fresh, FreshnessConfidence::Low, None)
+        }
+        (Some(remote), Some(local)) => result(
+            FreshnessState::Outdated,
+            FreshnessConfidence::Low,
+            Some(format!(
+                "文件大小不同: 本地 {} 字节，远端 {} 字节",
+                local, remote
+            )),
+        ),
+        _ => result(
+            FreshnessState::Unknown,
+            FreshnessConfidence::Low,
+            Some("服务端未提供校验头与文件大小".to_string()),
+        ),
+    }
+}
+
+// 并发检查（限制并发数），结果与输入顺序一致
+async fn check_freshness(probes: Vec<FreshnessProbe>) -> Vec<FileFreshness> {
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(CONCURRENT_FRESHNESS_PROBES));
+    let mut tasks = Vec::with_capacity(probes.len());
+
+    for probe in probes {
+        let sem = semaphore.clone();
+        let index = probe.index;
+        let file_id = probe.file_id.clone();
+
+        let task = tokio::spawn(async move {
+            let _permit = sem.acquire().await.unwrap();
+
+            probe_freshness(probe).await
+        });
+
+        tasks.push((index, file_id, task));
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+
+    for (index, file_id, task) in tasks {
+        let result = task.await.unwrap_or_else(|err| FileFreshness {
+            index,
+            file_id: file_id.map(FileId::from),
+            state: FreshnessState::Unknown,
+            confidence: FreshnessConfidence::Low,
+            reason: Some(format!("检查任务失败: {}", err)),
+        });
+
+        results.push(result);
+    }
+
+    results
+}
+
+/// 检查项目缓存中的文件是否在远端被替换（同一文件 id 重新上传）
+#[tauri::command]
+#[tracing::instrument]
+pub async fn check_cache_freshness(project_id: ProjectId) -> Result<CacheFreshnessReport, String> {
+    tracing::info!("image_cache.check_cache_freshness.start");
+
+    let cache_dir = get_cache_dir(&project_id);
+
+    let entries = match read_manifest(&cache_dir).await {
+        Some(Manifest::Entries(entries)) => entries,
+        Some(Manifest::LegacyIds(_)) | None => {
+            return Err("缓存清单缺失或为旧格式，请重新下载缓存后再检查".to_string());
+        }
+    };
+
+    let remote = get_project_files(GetProjectFilesReq {
+        project_id: project_id.clone(),
+        target_id: None,
+        sort: None,
+    })
+    .await?;
+
+    let mut probes = Vec::new();
+    let mut report_entries = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let unchecked = |state, reason: &str| FileFreshness {
+            index,
+            file_id: entry.id.clone().map(FileId::from),
+            state,
+            confidence: FreshnessConfidence::High,
+            reason: Some(reason.to_string()),
+        };
+
+        let Some(file_name) = &entry.file_name else {
+            report_entries.push(unchecked(FreshnessState::NotCached, "该页尚未缓存"));
+            continue;
+        };
+
+        match remote_for_entry(index, entry, &remote).and_then(|file| file.url.clone()) {
+            Some(url) => probes.push(freshness_probe(
+                &cache_dir,
+                index,
+                entry.id.clone(),
+                url,
+                file_name,
+                Some(entry),
+            )),
+            None => report_entries.push(unchecked(FreshnessState::Unknown, "远端已无此文件")),
+        }
+    }
+
+    let checked = probes.len();
+
+    report_entries.extend(check_freshness(probes).await);
+    report_entries.sort_by_key(|entry| entry.index);
+
+    let outdated = report_entries
+        .iter()
+        .filter(|entry| entry.state == FreshnessState::Outdated)
+        .count();
+
+    tracing::info!(checked, outdated, "image_cache.check_cache_freshness.ok");
+
+    Ok(CacheFreshnessReport {
+        project_id,
+        checked,
+        outdated,
+        entries: report_entries,
+    })
+}
+
+// ========== 远端文件数比对 ==========
+
+// 缓存清单与远端文件列表的差异
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ManifestDiff {
+    // 远端新增（清单中没有）的文件 id
+    pub added: Vec<String>,
+    // 清单中有、远端已删除的文件（没有文件 id 的缓存记为 "#<页面索引>"）
+    pub removed: Vec<String>,
+    // 远端 url 与下载时不同（忽略签名参数），通常是重新上传
+    pub url_changed: Vec<String>,
+    // 清单中尚未下载成功的页数
+    pub not_downloaded: usize,
+}
+
+impl ManifestDiff {
+    // 远端有、本地缺少的页数
+    fn missing_count(&self) -> usize {
+        self.added.len() + self.not_downloaded
+    }
+}
+
+// 清单条目对应的远端文件：有文件 id 时按 id 查找，否则按页面索引
+fn remote_for_entry<'a>(
+    index: usize,
+    entry: &ManifestEntry,
+    remote: &'a [MoetranProjectFile],
+) -> Option<&'a MoetranProjectFile> {
+    match &entry.id {
+        Some(id) => remote.iter().find(|file| &file.id == id),
+        None => remote.get(index),
+    }
+}
+
+fn diff_manifest(entries: &[ManifestEntry], remote: &[MoetranProjectFile]) -> ManifestDiff {
+    let mut diff = ManifestDiff::default();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let label = || entry.id.clone().unwrap_or_else(|| format!("#{}", index));
+
+        let Some(file) = remote_for_entry(index, entry, remote) else {
+            diff.removed.push(label());
+            continue;
+        };
+
+        if entry.file_name.is_none() {
+            diff.not_downloaded += 1;
+        }
+
+        if let (Some(old), Some(new)) = (&entry.url, &file.url) {
+            if !same_file_url(old, new) {
+                diff.url_changed.push(label());
+            }
+        }
+    }
+
+    // 没有文件 id 的缓存只能按页数判断新增
+    let by_index = entries.iter().any(|entry| entry.id.is_none());
+    let known: HashSet<&str> = entries
+        .iter()
+        .filter_map(|entry| entry.id.as_deref())
+        .collect();
+
+    for (index, file) in remote.iter().enumerate() {
+        let cached = if by_index {
+            index < entries.len()
+        } else {
+            known.contains(file.id.as_str())
+        };
+
+        if !cached {
+            diff.added.push(file.id.to_string());
+        }
+    }
+
+    diff
+}
+
+// 旧格式清单只有文件 id，视为已全部下载
+fn manifest_entries(manifest: Manifest) -> Vec<ManifestEntry> {
+    match manifest {
+        Manifest::Entries(entries) => entries,
+        Manifest::LegacyIds(ids) => ids
+            .into_iter()
+            .map(|id| ManifestEntry {
+                file_name: Some(id.clone()),
+                id: Some(id),
+                content_type: None,
+                etag: None,
+                last_modified: None,
+                url: None,
+                expected_size: None,
+            })
+            .collect(),
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CachedProjectRemoteSummary {
+    pub project_id: ProjectId,
+    // 远端项目已不存在；此时远端相关字段保留上次比对的结果
+    pub remote_deleted: bool,
+    pub cached_file_count: i64,
+    pub remote_file_count: Option<i64>,
+    pub missing_count: Option<i64>,
+    // 缓存没有清单时为 None，只按文件数比较
+    pub diff: Option<ManifestDiff>,
+    pub checked_at: i64,
+}
+
+fn is_not_found_error(err: &AppError) -> bool {
+    matches!(err.root(), AppError::MoetranHttp { status: 404, .. })
+}
+
+/// 与远端文件列表比对，更新缓存记录中的远端文件数与缺少的页数
+#[tauri::command]
+#[tracing::instrument]
+pub async fn refresh_cached_project_info(
+    project_id: ProjectId,
+) -> Result<CachedProjectRemoteSummary, String> {
+    tracing::info!("image_cache.refresh_cached_project_info.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let metadata = get_cached_project_metadata(storage.pool(), &project_id)
+        .await?
+        .ok_or_else(|| format!("项目没有缓存记录: {}", project_id))?;
+
+    let checked_at = unix_now();
+
+    let mut summary = CachedProjectRemoteSummary {
+        project_id: project_id.clone(),
+        remote_deleted: false,
+        cached_file_count: metadata.file_count,
+        remote_file_count: metadata.remote_file_count,
+        missing_count: metadata.missing_count,
+        diff: None,
+        checked_at,
+    };
+
+    let remote = match get_project_files(GetProjectFilesReq {
+        project_id: project_id.clone(),
+        target_id: None,
+        sort: None,
+    })
+    .await
+    {
+        Ok(remote) => remote,
+        Err(err) if is_not_found_error(&err) => {
+            tracing::info!("image_cache.refresh_cached_project_info.remote_deleted");
+
+            summary.remote_deleted = true;
+
+            update_remote_summary(
+                storage.pool(),
+                &project_id,
+                summary.remote_file_count,
+                summary.missing_count,
+                true,
+                checked_at,
+            )
+            .await?;
+
+            return Ok(summary);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let missing = match read_manifest(&get_cache_dir(&project_id)).await {
+        Some(manifest) => {
+            let diff = diff_manifest(&manifest_entries(manifest), &remote);
+            let missing = diff.missing_count();
+
+            summary.diff = Some(diff);
+
+            missing as i64
+        }
+        None => (remote.len() as i64 - metadata.file_count).max(0),
+    };
+
+    summary.remote_file_count = Some(remote.len() as i64);
+    summary.missing_count = Some(missing);
+
+    update_remote_summary(
+        storage.pool(),
+        &project_id,
+        summary.remote_file_count,
+        summary.missing_count,
+        false,
+        checked_at,
+    )
+    .await?;
+
+    tracing::info!(
+        remote_file_count = remote.len(),
+        missing,
+        "image_cache.refresh_cached_project_info.ok"
+    );
+
+    Ok(summary)
+}
+
+// ========== 旧版本缓存接入 ==========
+
+// 已按当前命名放入缓存目录的文件（按页面顺序）
+pub(crate) struct AdoptedFile {
+    pub id: Option<FileId>,
+    pub file_name: String,
+}
+
+// 为迁移进来的文件写入清单，内容类型按文件头判断
+pub(crate) async fn adopt_cache_files(
+    cache_dir: &Path,
+    files: &[AdoptedFile],
+) -> Result<(), String> {
+    let mut entries = Vec::with_capacity(files.len());
+
+    for file in files {
+        entries.push(ManifestEntry {
+            id: file.id.clone().map(String::from),
+            file_name: Some(file.file_name.clone()),
+            content_type: Some(sniff_cached_content_type(&cache_dir.join(&file.file_name)).await),
+            etag: None,
+            last_modified: None,
+            url: None,
+            expected_size: None,
+        });
+    }
+
+    write_manifest(cache_dir, &entries).await
+}
+
+// ========== 签名 url 刷新 ==========
+
+// 忽略签名参数比较 url
+fn same_file_url(a: &str, b: &str) -> bool {
+    a.split('?').next() == b.split('?').next()
+}
+
+// 按 url 在项目清单中找到对应的文件 id
+pub(crate) async fn manifest_file_id_for_url(project_id: &str, url: &str) -> Option<String> {
+    let Manifest::Entries(entries) = read_manifest(&get_cache_dir(project_id)).await? else {
+        return None;
+    };
+
+    entries
+        .into_iter()
+        .find(|entry| entry.url.as_deref().is_some_and(|u| same_file_url(u, url)))?
+        .id
+}
+
+// 刷新后把新 url 写回清单（项目未缓存或清单中没有该文件时忽略）
+pub(crate) async fn update_manifest_url(project_id: &str, file_id: &str, url: &str) {
+    let cache_dir = get_cache_dir(project_id);
+
+    let Some(Manifest::Entries(mut entries)) = read_manifest(&cache_dir).await else {
+        return;
+    };
+
+    let Some(entry) = entries
+        .iter_mut()
+        .find(|entry| entry.id.as_deref() == Some(file_id))
+    else {
+        return;
+    };
+
+    entry.url = Some(url.to_string());
+
+    if let Err(err) = write_manifest(&cache_dir, &entries).await {
+        tracing::warn!(%project_id, %file_id, error = %err, "image_cache.manifest.url_update_failed");
+    }
+}
+
+// ========== 完整性检查 ==========
+
+// 清单中指向不存在文件的条目；fix 时把这些条目改为未下载，下次下载时补齐
+pub(crate) async fn manifest_missing_files(
+    cache_dir: &Path,
+    fix: bool,
+) -> Result<Vec<String>, String> {
+    let Some(Manifest::Entries(mut entries)) = read_manifest(cache_dir).await else {
+        return Ok(vec![]);
+    };
+
+    let mut missing = Vec::new();
+
+    for entry in entries.iter_mut() {
+        let Some(file_name) = &entry.file_name else {
+            continue;
+        };
+
+        if cache_dir.join(file_name).exists() {
+            continue;
+        }
+
+        missing.push(file_name.clone());
+
+        if fix {
+            entry.file_name = None;
+            entry.content_type = None;
+            entry.etag = None;
+            entry.last_modified = None;
+        }
+    }
+
+    if fix && !missing.is_empty() {
+        write_manifest(cache_dir, &entries).await?;
+    }
+
+    Ok(missing)
+}
+
+// ========== 内部辅助函数 ==========
 
 #[derive(Debug, serde::Deserialize)]
 pub struct FileDownloadInfo {
     pub url: String,
+    // Moetran 文件 id；提供时缓存文件按 id 命名
+    #[serde(default)]
+    pub id: Option<FileId>,
+}
+
+// 缓存文件名（不含扩展名）：有文件 id 时使用 id，避免文件增删导致索引错位
+fn cache_stem(index: usize, file: &FileDownloadInfo) -> String {
+    file.id
+        .as_ref()
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| index.to_string())
+}
+
+// 清单中的单页记录；file_name 为 None 表示该页尚未成功下载
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct ManifestEntry {
+    id: Option<String>,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    // 下载时服务端返回的校验头，用于检查远端文件是否被替换；旧清单没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    // 下载时使用的 url（签名过期刷新后更新为新 url）；旧清单没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    // 下载时的 Content-Length（没有该响应头时为实际字节数），用于发现被截断的文件；旧清单没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expected_size: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum Manifest {
+    Entries(Vec<ManifestEntry>),
+    // 旧格式：只记录页面顺序对应的文件 id
+    LegacyIds(Vec<String>),
+}
+
+impl Manifest {
+    fn find_by_file_name(&self, file_name: &str) -> Option<&ManifestEntry> {
+        match self {
+            Manifest::Entries(entries) => entries
+                .iter()
+                .find(|e| e.file_name.as_deref() == Some(file_name)),
+            Manifest::LegacyIds(_) => None,
+        }
+    }
+}
+
+async fn write_manifest(cache_dir: &Path, entries: &[ManifestEntry]) -> Result<(), String> {
+    let data = serde_json::to_vec(entries).map_err(|e| format!("序列化缓存清单失败: {}", e))?;
+
+    fs::write(cache_dir.join(MANIFEST_FILE), data)
+        .await
+        .map_err(|e| format!("写入缓存清单失败: {}", e))
+}
+
+async fn read_manifest(cache_dir: &Path) -> Option<Manifest> {
+    let data = fs::read(cache_dir.join(MANIFEST_FILE)).await.ok()?;
+
+    serde_json::from_slice(&data).ok()
 }
 
-#[derive(serde::Serialize)]
+#[derive(Clone, serde::Serialize)]
 pub struct CachedFileData {
     pub b64: String,
     pub content_type: String,
+    // 图片尺寸（无法读取文件头时为 None）
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    // 长条图，前端应使用分块渲染
+    pub is_tall: bool,
+    // 分块已全部生成
+    pub tiles_ready: bool,
+}
+
+// 所有项目缓存目录的上级目录
+pub(crate) fn cache_root() -> PathBuf {
+    DATA_DIR.join("images")
+}
+
+pub(crate) fn get_cache_dir(project_id: &str) -> PathBuf {
+    cache_root().join(project_id)
+}
+
+const KNOWN_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+
+// 扩展名依次取自：URL 路径 -> 常见的格式查询参数（签名 CDN URL）-> 响应 Content-Type -> 文件头
+fn resolve_extension(url: &str, content_type: Option<&str>, data: &[u8]) -> String {
+    extension_from_url(url)
+        .or_else(|| content_type.and_then(extension_from_content_type))
+        .or_else(|| sniff_extension(data))
+        .unwrap_or("jpg")
+        .to_string()
+}
+
+pub(crate) fn known_extension(ext: &str) -> Option<&'static str> {
+    let ext = ext.to_ascii_lowercase();
+
+    KNOWN_EXTENSIONS.iter().copied().find(|known| *known == ext)
+}
+
+fn extension_from_url(url: &str) -> Option<&'static str> {
+    let parsed = url::Url::parse(url).ok()?;
+
+    let from_path = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|name| name.rsplit_once('.'))
+        .and_then(|(_, ext)| known_extension(ext));
+
+    from_path.or_else(|| {
+        parsed
+            .query_pairs()
+            .find(|(key, _)| matches!(key.as_ref(), "format" | "fm" | "ext"))
+            .and_then(|(_, value)| known_extension(&value))
+    })
 }
 
-fn get_cache_dir(project_id: &str) -> PathBuf {
-    let mut path = DATA_DIR.clone();
-    path.push("images");
-    path.push(project_id);
-    path
+fn extension_from_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+
+    match mime.as_str() {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/webp" => Some("webp"),
+        "image/gif" => Some("gif"),
+        _ => None,
+    }
 }
 
-fn get_extension(url: &str) -> &str {
-    if url.ends_with(".png") || url.contains(".png?") {
-        "png"
-    } else if url.ends_with(".jpg") || url.contains(".jpg?") {
-        "jpg"
-    } else if url.ends_with(".jpeg") || url.contains(".jpeg?") {
-        "jpeg"
-    } else if url.ends_with(".webp") || url.contains(".webp?") {
-        "webp"
+// 按文件头（magic bytes）判断图片格式
+pub(crate) fn sniff_extension(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("webp")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("gif")
     } else {
-        "jpg" // 默认
+        None
+    }
+}
+
+// 读取已缓存文件的文件头判断内容类型，无法判断时按扩展名
+async fn sniff_cached_content_type(path: &Path) -> String {
+    let mut header = [0u8; 16];
+
+    let read = match fs::File::open(path).await {
+        Ok(mut file) => tokio::io::AsyncReadExt::read(&mut file, &mut header)
+            .await
+            .unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match sniff_extension(&header[..read]) {
+        Some(ext) => get_content_type(ext),
+        None => get_content_type(
+            &path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default(),
+        ),
     }
 }
 
@@ -317,21 +3165,84 @@ fn get_content_type(ext: &str) -> String {
         "png" => "image/png".to_string(),
         "jpg" | "jpeg" => "image/jpeg".to_string(),
         "webp" => "image/webp".to_string(),
+        "gif" => "image/gif".to_string(),
         _ => "image/jpeg".to_string(),
     }
 }
 
-async fn download_file_with_retry(url: &str, cache_dir: &Path, index: usize) -> Result<(), String> {
-    let ext = get_extension(url);
-    let file_path = cache_dir.join(format!("{}.{}", index, ext));
+enum DownloadFailure {
+    // 磁盘已满，重试无意义
+    DiskFull,
+    // 下载任务已被取消，尚未开始的文件不再下载
+    Cancelled,
+    // url 签名已过期，需换新 url 后再试
+    UrlExpired(String),
+    Failed(String),
+}
+
+// 签名过期时刷新 url 后重试一次，仍失败时返回原始错误；成功时同时返回实际使用的 url
+async fn download_with_url_refresh(
+    project_id: &str,
+    file_id: Option<&str>,
+    url: &str,
+    cache_dir: &Path,
+    stem: &str,
+    index: usize,
+) -> Result<(SavedFile, String), DownloadFailure> {
+    let started = Instant::now();
+
+    let err = match download_file_with_retry(url, cache_dir, stem, index).await {
+        Ok(saved) => return Ok((saved, url.to_string())),
+        Err(DownloadFailure::UrlExpired(err)) => err,
+        Err(other) => return Err(other),
+    };
+
+    let fresh = match file_id {
+        Some(file_id) => replacement_url(project_id, file_id, url, started).await,
+        None => None,
+    };
+
+    let Some(fresh) = fresh else {
+        return Err(DownloadFailure::Failed(err));
+    };
+
+    tracing::info!(
+        index = index,
+        "download url expired, retrying with refreshed url"
+    );
 
+    match download_file_with_retry(&fresh, cache_dir, stem, index).await {
+        Ok(saved) => Ok((saved, fresh)),
+        Err(DownloadFailure::DiskFull) => Err(DownloadFailure::DiskFull),
+        Err(_) => Err(DownloadFailure::Failed(err)),
+    }
+}
+
+async fn download_file_with_retry(
+    url: &str,
+    cache_dir: &Path,
+    stem: &str,
+    index: usize,
+) -> Result<SavedFile, DownloadFailure> {
     for attempt in 0..=MAX_RETRIES {
-        match download_file(url, &file_path).await {
-            Ok(_) => {
+        match download_file(url, cache_dir, stem).await {
+            Ok(saved) => {
                 tracing::debug!(index = index, "file downloaded successfully");
-                return Ok(());
+                return Ok(saved);
             }
-            Err(e) => {
+            Err(DownloadFailure::DiskFull) => {
+                tracing::error!(index = index, "download aborted, disk full");
+                return Err(DownloadFailure::DiskFull);
+            }
+            Err(DownloadFailure::Cancelled) => return Err(DownloadFailure::Cancelled),
+            Err(DownloadFailure::UrlExpired(e)) => {
+                tracing::warn!(index = index, error = %e, "download url expired");
+                return Err(DownloadFailure::UrlExpired(format!(
+                    "下载文件 {} 失败（索引 {}）: {}",
+                    url, index, e
+                )));
+            }
+            Err(DownloadFailure::Failed(e)) => {
                 if attempt < MAX_RETRIES {
                     tracing::warn!(
                         index = index,
@@ -346,7 +3257,10 @@ async fn download_file_with_retry(url: &str, cache_dir: &Path, index: usize) ->
                         error = %e,
                         "download failed after all retries"
                     );
-                    return Err(format!("下载文件 {} 失败（索引 {}）: {}", url, index, e));
+                    return Err(DownloadFailure::Failed(format!(
+                        "下载文件 {} 失败（索引 {}）: {}",
+                        url, index, e
+                    )));
                 }
             }
         }
@@ -355,20 +3269,844 @@ async fn download_file_with_retry(url: &str, cache_dir: &Path, index: usize) ->
     unreachable!()
 }
 
-async fn download_file(url: &str, file_path: &Path) -> Result<(), String> {
+struct SavedFile {
+    file_name: String,
+    content_type: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    expected_size: u64,
+}
+
+// 下载并保存为 {stem}.{ext}
+async fn download_file(
+    url: &str,
+    cache_dir: &Path,
+    stem: &str,
+) -> Result<SavedFile, DownloadFailure> {
     // 使用 moetran_get_raw 下载图片二进制数据
-    let data = moetran_get_raw(url)
-        .await
-        .map_err(|e| format!("HTTP 请求失败: {}", e))?;
+    let raw = moetran_get_raw(url).await.map_err(|e| {
+        if is_expired_url_error(&e.to_string()) {
+            DownloadFailure::UrlExpired(format!("HTTP 请求失败: {}", e))
+        } else {
+            DownloadFailure::Failed(format!("HTTP 请求失败: {}", e))
+        }
+    })?;
 
-    // 写入文件
-    let mut file = fs::File::create(file_path)
-        .await
-        .map_err(|e| format!("创建文件失败: {}", e))?;
+    // 连接中断时响应体可能不完整，不写入缓存
+    if let Some(len) = raw.content_length {
+        if len != raw.bytes.len() as u64 {
+            return Err(DownloadFailure::Failed(format!(
+                "响应体不完整: 收到 {} 字节，Content-Length 为 {}",
+                raw.bytes.len(),
+                len
+            )));
+        }
+    }
+
+    let ext = resolve_extension(url, raw.content_type.as_deref(), &raw.bytes);
+    let file_name = format!("{}.{}", stem, ext);
+    let path = cache_dir.join(&file_name);
+
+    // 写入文件；磁盘已满时删除写了一半的文件
+    let written = async {
+        let mut file = fs::File::create(&path).await?;
+        file.write_all(&raw.bytes).await
+    }
+    .await;
+
+    if let Err(e) = written {
+        if is_disk_full(&e) {
+            let _ = fs::remove_file(&path).await;
+            return Err(DownloadFailure::DiskFull);
+        }
+
+        return Err(DownloadFailure::Failed(format!("写入文件失败: {}", e)));
+    }
+
+    Ok(SavedFile {
+        file_name,
+        content_type: get_content_type(&ext),
+        etag: raw.etag,
+        last_modified: raw.last_modified,
+        expected_size: raw.content_length.unwrap_or(raw.bytes.len() as u64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THUMB_PROJECT: &str = "ic-thumb-proj";
+
+    fn key(project_id: &str, file_index: usize, variant: MemoryVariant) -> MemoryKey {
+        MemoryKey {
+            project_id: project_id.to_string(),
+            file_index,
+            file_id: None,
+            variant,
+        }
+    }
+
+    // 占用 bytes 字节的缩略图条目
+    fn thumb(bytes: usize) -> MemoryValue {
+        MemoryValue::Thumb(CachedThumbnailData {
+            b64: "x".repeat(bytes),
+            content_type: String::new(),
+            width: 1,
+            height: 1,
+        })
+    }
+
+    fn assert_accounting(cache: &MemoryCache) {
+        let sum: usize = cache.entries.values().map(|entry| entry.bytes).sum();
+
+        assert_eq!(cache.bytes, sum);
+        assert_eq!(cache.order.len(), cache.entries.len());
+    }
+
+    #[test]
+    fn thumbnail_keeps_aspect_ratio_and_never_upscales() {
+        assert_eq!(thumbnail_size(1200, 1800, 256), (171, 256));
+        assert_eq!(thumbnail_size(1800, 600, 256), (256, 85));
+        assert_eq!(thumbnail_size(200, 100, 256), (200, 100));
+        assert_eq!(thumbnail_size(256, 256, 256), (256, 256));
+        assert_eq!(thumbnail_size(10_000, 3, 256), (256, 1));
+    }
+
+    #[test]
+    fn thumbnail_is_rendered_as_a_smaller_jpeg() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("0.png");
+
+        image::RgbImage::from_pixel(600, 300, image::Rgb([200, 30, 30]))
+            .save(&source)
+            .unwrap();
+
+        let data = render_thumbnail(&source, 256).unwrap();
+
+        assert_eq!((data.width, data.height), (256, 128));
+        assert_eq!(data.content_type, "image/jpeg");
+
+        let jpeg =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &data.b64).unwrap();
+        let decoded = image::load_from_memory(&jpeg).unwrap();
+
+        assert_eq!((decoded.width(), decoded.height()), (256, 128));
+    }
+
+    #[test]
+    fn eviction_is_accounted_in_bytes_not_entries() {
+        let mut cache = MemoryCache::default();
+
+        cache.insert(key("p", 0, MemoryVariant::Full), thumb(600), 1000);
+        cache.insert(key("p", 1, MemoryVariant::Thumb(256)), thumb(100), 1000);
+        cache.insert(key("p", 2, MemoryVariant::Tile(0)), thumb(200), 1000);
+        assert_eq!(cache.bytes, 900);
+
+        // 放入 300 字节只需淘汰最久未使用的那一个大条目
+        let evicted = cache.insert(key("p", 3, MemoryVariant::Thumb(256)), thumb(300), 1000);
+
+        assert_eq!(evicted, 1);
+        assert_eq!(cache.bytes, 600);
+        assert!(!cache
+            .entries
+            .contains_key(&key("p", 0, MemoryVariant::Full)));
+        assert_accounting(&cache);
+
+        // 覆盖同一个 key 时按新大小计
+        cache.insert(key("p", 3, MemoryVariant::Thumb(256)), thumb(50), 1000);
+        assert_eq!(cache.bytes, 350);
+        assert_accounting(&cache);
+    }
+
+    #[test]
+    fn reading_an_entry_protects_it_from_eviction() {
+        let mut cache = MemoryCache::default();
+
+        cache.insert(key("p", 0, MemoryVariant::Thumb(256)), thumb(400), 1000);
+        cache.insert(key("p", 1, MemoryVariant::Thumb(256)), thumb(400), 1000);
+
+        assert!(cache.get(&key("p", 0, MemoryVariant::Thumb(256))).is_some());
+
+        cache.insert(key("p", 2, MemoryVariant::Thumb(256)), thumb(400), 1000);
+
+        assert!(cache
+            .entries
+            .contains_key(&key("p", 0, MemoryVariant::Thumb(256))));
+        assert!(!cache
+            .entries
+            .contains_key(&key("p", 1, MemoryVariant::Thumb(256))));
+    }
+
+    #[test]
+    fn entry_larger_than_the_limit_is_not_cached() {
+        let mut cache = MemoryCache::default();
+
+        cache.insert(key("p", 0, MemoryVariant::Full), thumb(400), 1000);
+        cache.insert(key("p", 1, MemoryVariant::Full), thumb(400), 1000);
+
+        let evicted = cache.insert(key("p", 2, MemoryVariant::Full), thumb(1001), 1000);
+
+        assert_eq!(evicted, 0);
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(cache.bytes, 800);
+        assert!(!cache
+            .entries
+            .contains_key(&key("p", 2, MemoryVariant::Full)));
+    }
+
+    #[test]
+    fn invalidation_drops_every_variant_of_one_project() {
+        let mut cache = MemoryCache::default();
+
+        cache.insert(key("a", 0, MemoryVariant::Full), thumb(10), 1000);
+        cache.insert(key("a", 0, MemoryVariant::Thumb(256)), thumb(10), 1000);
+        cache.insert(key("a", 0, MemoryVariant::Tile(3)), thumb(10), 1000);
+        cache.insert(key("b", 0, MemoryVariant::Thumb(256)), thumb(10), 1000);
+
+        let generation = cache.generation;
+        let removed = cache.remove_where(|key| key.project_id == "a");
+
+        assert_eq!(removed, 3);
+        assert_eq!(cache.generation, generation + 1);
+        assert_eq!(
+            cache.entries.keys().cloned().collect::<Vec<_>>(),
+            [key("b", 0, MemoryVariant::Thumb(256))]
+        );
+        assert_accounting(&cache);
+    }
+
+    #[test]
+    fn concurrent_access_keeps_accounting_consistent() {
+        let cache = Mutex::new(MemoryCache::default());
+
+        std::thread::scope(|scope| {
+            for worker in 0..8 {
+                let cache = &cache;
+
+                scope.spawn(move || {
+                    for n in 0..200 {
+                        let key = key("p", (worker * 7 + n) % 40, MemoryVariant::Thumb(256));
+                        let mut cache = cache.lock().unwrap();
+
+                        if cache.get(&key).is_none() {
+                            cache.insert(key, thumb(50 + n % 90), 2000);
+                        }
+                    }
+                });
+            }
+        });
+
+        let cache = cache.into_inner().unwrap();
+
+        assert!(cache.bytes <= 2000);
+        assert_accounting(&cache);
+    }
+
+    #[tokio::test]
+    async fn thumbnail_is_served_from_memory_until_invalidated() {
+        let cached = CachedThumbnailData {
+            b64: "dGh1bWI=".to_string(),
+            content_type: "image/jpeg".to_string(),
+            width: 171,
+            height: 256,
+        };
+
+        MEMORY_CACHE.lock().unwrap().insert(
+            key(
+                THUMB_PROJECT,
+                4,
+                MemoryVariant::Thumb(DEFAULT_THUMBNAIL_EDGE),
+            ),
+            MemoryValue::Thumb(cached),
+            usize::MAX,
+        );
+
+        // 磁盘上没有这个项目，只能来自内存缓存
+        let hit = get_thumbnail(THUMB_PROJECT.into(), 4, None, None)
+            .await
+            .unwrap();
+        assert_eq!((hit.b64.as_str(), hit.width), ("dGh1bWI=", 171));
+
+        // 不同尺寸是另一个条目
+        assert!(get_thumbnail(THUMB_PROJECT.into(), 4, None, Some(512))
+            .await
+            .is_err());
+
+        invalidate_memory_cache(THUMB_PROJECT);
+
+        assert!(get_thumbnail(THUMB_PROJECT.into(), 4, None, None)
+            .await
+            .is_err());
+    }
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG_HEADER: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+    const WEBP_HEADER: &[u8] = b"RIFF\x24\0\0\0WEBPVP8 ";
+    const GIF_HEADER: &[u8] = b"GIF89a\x01\0\x01\0";
+
+    #[test]
+    fn sniffs_image_types_from_magic_bytes() {
+        assert_eq!(sniff_extension(PNG_HEADER), Some("png"));
+        assert_eq!(sniff_extension(JPEG_HEADER), Some("jpg"));
+        assert_eq!(sniff_extension(WEBP_HEADER), Some("webp"));
+        assert_eq!(sniff_extension(GIF_HEADER), Some("gif"));
+        assert_eq!(sniff_extension(b"GIF87a\x01\0"), Some("gif"));
+
+        for unknown in [
+            &b""[..],
+            b"\x89PN",
+            b"RIFF\x24\0\0\0WAVEfmt ",
+            b"RIFF\0\0\0\0WEB",
+            b"BM\x36\0\0\0",
+            b"<html></html>",
+        ] {
+            assert_eq!(sniff_extension(unknown), None, "{:?}", unknown);
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_content_type_prefers_file_header_over_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let png_as_jpg = dir.path().join("0.jpg");
+        std::fs::write(&png_as_jpg, PNG_HEADER).unwrap();
+        assert_eq!(sniff_cached_content_type(&png_as_jpg).await, "image/png");
+
+        let unknown_webp = dir.path().join("1.WEBP");
+        std::fs::write(&unknown_webp, b"????").unwrap();
+        assert_eq!(sniff_cached_content_type(&unknown_webp).await, "image/webp");
 
-    file.write_all(&data)
+        let missing = dir.path().join("2.gif");
+        assert_eq!(sniff_cached_content_type(&missing).await, "image/gif");
+    }
+
+    #[test]
+    fn tiles_cover_the_strip_without_gaps() {
+        let tiles = plan_tiles(5000, 2048);
+        let heights: Vec<(u32, u32)> = tiles.iter().map(|t| (t.offset_y, t.height)).collect();
+        assert_eq!(heights, [(0, 2048), (2048, 2048), (4096, 904)]);
+
+        assert_eq!(plan_tiles(4096, 2048).len(), 2);
+        assert_eq!(
+            plan_tiles(100, 2048),
+            [TileInfo {
+                n: 0,
+                offset_y: 0,
+                height: 100
+            }]
+        );
+    }
+
+    #[test]
+    fn slicing_resumes_from_existing_tiles_and_can_be_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("strip.png");
+        let tile_dir = dir.path().join("strip");
+
+        image::RgbImage::from_pixel(64, 600, image::Rgb([10, 120, 200]))
+            .save(&source)
+            .unwrap();
+
+        // 开始前即取消：只留下未完成的索引
+        let cancelled = AtomicBool::new(true);
+        assert!(slice_tiles(&source, &tile_dir, 256, &cancelled).is_err());
+        let partial: TileIndex =
+            serde_json::from_slice(&std::fs::read(tile_dir.join(TILE_INDEX_FILE)).unwrap())
+                .unwrap();
+        assert!(!partial.complete);
+        assert!(!tile_path(&tile_dir, 0).exists());
+
+        let go = AtomicBool::new(false);
+        let index = slice_tiles(&source, &tile_dir, 256, &go).unwrap();
+        assert!(index.complete);
+        assert_eq!(index.tiles.len(), 3);
+        assert_eq!(index.tiles[2].height, 88);
+
+        let last = image::open(tile_path(&tile_dir, 2)).unwrap();
+        assert_eq!((last.width(), last.height()), (64, 88));
+
+        // 丢失的分块会补齐，已有的保持不动
+        let kept = std::fs::metadata(tile_path(&tile_dir, 0))
+            .unwrap()
+            .modified()
+            .unwrap();
+        std::fs::remove_file(tile_path(&tile_dir, 1)).unwrap();
+        slice_tiles(&source, &tile_dir, 256, &go).unwrap();
+        assert!(tile_path(&tile_dir, 1).exists());
+        assert_eq!(
+            std::fs::metadata(tile_path(&tile_dir, 0))
+                .unwrap()
+                .modified()
+                .unwrap(),
+            kept
+        );
+
+        // 分块高度变化时旧分块作废
+        let regrown = slice_tiles(&source, &tile_dir, 512, &go).unwrap();
+        assert_eq!(regrown.tiles.len(), 2);
+        assert!(!tile_path(&tile_dir, 2).exists());
+    }
+
+    #[test]
+    fn extension_comes_from_url_then_content_type_then_header() {
+        let png = b"\x89PNG\r\n\x1a\n0000";
+
+        assert_eq!(
+            resolve_extension("https://cdn/a/page.WEBP?sig=1", Some("image/png"), png),
+            "webp"
+        );
+        assert_eq!(
+            resolve_extension("https://cdn/a/page?fm=gif&sig=1", Some("image/png"), png),
+            "gif"
+        );
+        assert_eq!(
+            resolve_extension(
+                "https://cdn/a/page",
+                Some("image/JPEG; charset=binary"),
+                png
+            ),
+            "jpg"
+        );
+        assert_eq!(
+            resolve_extension(
+                "https://cdn/a/page.bin",
+                Some("application/octet-stream"),
+                png
+            ),
+            "png"
+        );
+        assert_eq!(resolve_extension("not a url", None, b"????"), "jpg");
+    }
+
+    fn manifest_entry(id: &str, file_name: &str, content_type: &str) -> ManifestEntry {
+        ManifestEntry {
+            id: Some(id.to_string()),
+            file_name: Some(file_name.to_string()),
+            content_type: Some(content_type.to_string()),
+            etag: None,
+            last_modified: None,
+            url: None,
+            expected_size: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn legacy_file_with_wrong_extension_is_renamed_in_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = b"\x89PNG\r\n\x1a\n0000";
+
+        std::fs::write(dir.path().join("f1.jpg"), png).unwrap();
+        write_manifest(dir.path(), &[manifest_entry("f1", "f1.jpg", "image/jpeg")])
+            .await
+            .unwrap();
+
+        let (path, content_type) =
+            repair_legacy_file(dir.path(), dir.path().join("f1.jpg"), png).await;
+
+        assert_eq!(path, dir.path().join("f1.png"));
+        assert_eq!(content_type, "image/png");
+        assert!(path.exists());
+
+        let located = locate_cached_file(dir.path(), 0, Some("f1".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(located.path, path);
+        assert_eq!(located.content_type.as_deref(), Some("image/png"));
+
+        // 扩展名已正确时不改名
+        let (same, _) = repair_legacy_file(dir.path(), path.clone(), png).await;
+        assert_eq!(same, path);
+    }
+
+    #[tokio::test]
+    async fn legacy_id_manifest_is_located_by_stem() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::write(dir.path().join(MANIFEST_FILE), r#"["id-a", "id-b"]"#).unwrap();
+        std::fs::write(dir.path().join("id-b.webp"), b"data").unwrap();
+        std::fs::write(dir.path().join("id-a.jpg.tmp"), b"partial").unwrap();
+
+        let files = scan_cached_files(dir.path()).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files.get("id-b").map(String::as_str), Some("id-b.webp"));
+
+        let located = locate_cached_file(dir.path(), 1, None).await.unwrap();
+        assert_eq!(located.stem, "id-b");
+        assert_eq!(located.path, dir.path().join("id-b.webp"));
+        assert_eq!(located.content_type, None);
+
+        // 未完成的下载不算已缓存
+        assert!(locate_cached_file(dir.path(), 0, None).await.is_err());
+        assert!(locate_cached_file(dir.path(), 2, None).await.is_err());
+    }
+
+    fn acquire(project_id: &str) -> DownloadJobGuard {
+        match claim_download(project_id).unwrap() {
+            DownloadClaim::Acquired(guard) => guard,
+            DownloadClaim::Running(_) => panic!("{} is already downloading", project_id),
+        }
+    }
+
+    fn running(project_id: &str) -> Arc<DownloadJob> {
+        match claim_download(project_id).unwrap() {
+            DownloadClaim::Running(job) => job,
+            DownloadClaim::Acquired(_) => panic!("{} is not downloading", project_id),
+        }
+    }
+
+    #[tokio::test]
+    async fn second_download_joins_the_running_job() {
+        let project_id = "ic-job-join";
+        let guard = acquire(project_id);
+        guard.job.total.store(4, Ordering::Relaxed);
+        guard.job.completed.store(1, Ordering::Relaxed);
+
+        let job = running(project_id);
+        assert!(Arc::ptr_eq(&job, &guard.job));
+
+        let progress = get_project_download_progress(project_id.to_string().into())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((progress.total, progress.completed), (4, 1));
+
+        let error =
+            serde_json::to_value(download_in_progress_error(project_id, job.progress())).unwrap();
+        assert_eq!(error["kind"], "AlreadyInProgress");
+        assert_eq!(error["project_id"], project_id);
+        assert_eq!(error["progress"]["total"], 4);
+
+        assert!(cancel_project_download(project_id.to_string().into())
+            .await
+            .unwrap());
+        assert!(guard.job.cancel.load(Ordering::Relaxed));
+
+        let waiter = tokio::spawn(async move { job.wait().await });
+
+        let report = DownloadReport {
+            downloaded: 3,
+            skipped: 1,
+            failed: vec![],
+        };
+        guard.finish(&Ok(report));
+        drop(guard);
+
+        let joined = waiter.await.unwrap().unwrap();
+        assert_eq!((joined.downloaded, joined.skipped), (3, 1));
+
+        // 结束后登记被注销，可以重新开始
+        assert!(get_project_download_progress(project_id.to_string().into())
+            .await
+            .unwrap()
+            .is_none());
+        drop(acquire(project_id));
+    }
+
+    #[tokio::test]
+    async fn dropped_job_wakes_waiters_and_frees_the_project() {
+        let project_id = "ic-job-dropped";
+        let guard = acquire(project_id);
+        let job = running(project_id);
+
+        let waiter = tokio::spawn(async move { job.wait().await });
+        drop(guard);
+
+        assert_eq!(
+            waiter.await.unwrap().unwrap_err().to_string(),
+            DOWNLOAD_INTERRUPTED
+        );
+        assert!(!cancel_project_download(project_id.to_string().into())
+            .await
+            .unwrap());
+
+        // 旧的 guard 不会注销之后登记的新任务
+        let stale = DownloadJobGuard {
+            project_id: project_id.to_string(),
+            job: Arc::new(DownloadJob::new()),
+        };
+        let current = acquire(project_id);
+        drop(stale);
+        assert!(Arc::ptr_eq(&running(project_id), &current.job));
+    }
+
+    #[test]
+    fn finished_status_distinguishes_failures_and_cancellation() {
+        let report = |failed: Vec<usize>| DownloadReport {
+            downloaded: 0,
+            skipped: 0,
+            failed,
+        };
+
+        assert_eq!(finished_status(&Ok(report(vec![]))), "completed");
+        assert_eq!(finished_status(&Ok(report(vec![2]))), "failed");
+        assert_eq!(
+            finished_status(&Err(AppError::Other(DOWNLOAD_CANCELLED.to_string()))),
+            "cancelled"
+        );
+        assert_eq!(
+            finished_status(&Err(AppError::Other("boom".to_string()))),
+            "error"
+        );
+        assert_eq!(
+            finished_status(&Err(insufficient_disk_error(2, 1))),
+            "error"
+        );
+    }
+
+    fn remote_file(id: &str, url: &str) -> MoetranProjectFile {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": format!("{}.png", id),
+            "source_count": 0,
+            "url": url,
+            "cover_url": "",
+            "width": null,
+            "height": null,
+            "size_bytes": null,
+            "safe_status": null,
+            "broken": false,
+        }))
+        .unwrap()
+    }
+
+    fn downloaded(id: Option<&str>, url: &str, file_name: Option<&str>) -> ManifestEntry {
+        ManifestEntry {
+            id: id.map(str::to_string),
+            file_name: file_name.map(str::to_string),
+            content_type: None,
+            etag: None,
+            last_modified: None,
+            url: Some(url.to_string()),
+            expected_size: None,
+        }
+    }
+
+    #[test]
+    fn manifest_diff_pairs_entries_by_file_id() {
+        let entries = [
+            downloaded(Some("a"), "https://cdn/a.png?sig=1", Some("a.png")),
+            downloaded(Some("b"), "https://cdn/b.png", None),
+            downloaded(Some("gone"), "https://cdn/gone.png", Some("gone.png")),
+        ];
+        let remote = [
+            remote_file("new", "https://cdn/new.png"),
+            remote_file("b", "https://cdn/b-v2.png"),
+            remote_file("a", "https://cdn/a.png?sig=2"),
+        ];
+
+        let diff = diff_manifest(&entries, &remote);
+
+        assert_eq!(diff.added, ["new"]);
+        assert_eq!(diff.removed, ["gone"]);
+        // 只有签名参数不同的 url 不算变化
+        assert_eq!(diff.url_changed, ["b"]);
+        assert_eq!(diff.not_downloaded, 1);
+        assert_eq!(diff.missing_count(), 2);
+    }
+
+    #[test]
+    fn manifest_without_ids_is_compared_by_page_index() {
+        let entries = [
+            downloaded(None, "https://cdn/1.png", Some("0.png")),
+            downloaded(None, "https://cdn/2.png", Some("1.png")),
+        ];
+
+        let diff = diff_manifest(&entries, &[remote_file("f1", "https://cdn/1.png")]);
+        assert_eq!(diff.removed, ["#1"]);
+        assert!(diff.added.is_empty());
+
+        let remote = [
+            remote_file("f1", "https://cdn/1.png"),
+            remote_file("f2", "https://cdn/2.png"),
+            remote_file("f3", "https://cdn/3.png"),
+        ];
+        let diff = diff_manifest(&entries, &remote);
+        assert_eq!(diff.added, ["f3"]);
+        assert!(diff.removed.is_empty() && diff.url_changed.is_empty());
+
+        // 旧格式清单视为已全部下载
+        let legacy = manifest_entries(Manifest::LegacyIds(vec!["f1".to_string()]));
+        assert_eq!(diff_manifest(&legacy, &remote).missing_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn remote_summary_is_stored_and_kept_when_the_project_is_deleted() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, ResponseTemplate,
+        };
+
+        use crate::test_support::{local_storage, MockBackends};
+
+        let backends = MockBackends::start().await;
+        let storage = local_storage().await;
+        let project_id = "ic-remote-proj";
+
+        upsert_cached_project(
+            storage.pool(),
+            &CachedProjectMetadata {
+                project_id: project_id.to_string(),
+                project_name: "远端比对".to_string(),
+                status: "completed".to_string(),
+                file_count: 2,
+                total_size_bytes: 0,
+                cached_at: 1,
+                remote_file_count: None,
+                missing_count: None,
+                remote_checked_at: None,
+                remote_deleted: false,
+            },
+        )
         .await
-        .map_err(|e| format!("写入文件失败: {}", e))?;
+        .unwrap();
 
-    Ok(())
+        let files: Vec<serde_json::Value> = (1..=3)
+            .map(|n| serde_json::json!({ "id": format!("r{}", n), "name": "", "source_count": 0, "url": "", "cover_url": "" }))
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/projects/{}/files", project_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(files))
+            .up_to_n_times(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/projects/{}/files", project_id)))
+            .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+            .mount(&backends.moetran)
+            .await;
+
+        // 没有清单时只按文件数比较
+        let summary = refresh_cached_project_info(project_id.to_string().into())
+            .await
+            .unwrap();
+        assert!(!summary.remote_deleted && summary.diff.is_none());
+        assert_eq!(
+            (summary.remote_file_count, summary.missing_count),
+            (Some(3), Some(1))
+        );
+
+        let summary = refresh_cached_project_info(project_id.to_string().into())
+            .await
+            .unwrap();
+        assert!(summary.remote_deleted);
+
+        let stored = get_cached_project_metadata(storage.pool(), project_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.remote_deleted);
+        assert_eq!(
+            (stored.remote_file_count, stored.missing_count),
+            (Some(3), Some(1))
+        );
+        assert_eq!(stored.remote_checked_at, Some(summary.checked_at));
+
+        delete_cached_project_metadata(storage.pool(), project_id)
+            .await
+            .unwrap();
+    }
+
+    // 只记录事件名的 EventSink
+    #[derive(Clone, Default)]
+    struct RecordedEvents(Arc<Mutex<Vec<&'static str>>>);
+
+    impl EventSink for RecordedEvents {
+        fn send_event<E: crate::events::AppEvent>(&self, _payload: E) {
+            self.0.lock().unwrap().push(E::NAME);
+        }
+    }
+
+    impl RecordedEvents {
+        fn count(&self, name: &str) -> usize {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|n| **n == name)
+                .count()
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_downloads_of_one_project_fetch_each_file_once() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, ResponseTemplate,
+        };
+
+        use crate::{
+            events::AppEvent,
+            test_support::{local_storage, MockBackends},
+        };
+
+        let backends = MockBackends::start().await;
+        local_storage().await;
+        let project_id = "ic-concurrent-proj";
+        remove_project_cache(project_id).await.unwrap();
+
+        let png = b"\x89PNG\r\n\x1a\n0000";
+        let mut urls = Vec::new();
+
+        for n in 0..3 {
+            let route = format!("/img/{}.png", n);
+
+            // 延迟响应，保证后两次调用发生在第一次下载进行中
+            Mock::given(method("GET"))
+                .and(path(route.clone()))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_bytes(png.to_vec())
+                        .insert_header("content-type", "image/png")
+                        .set_delay(Duration::from_millis(200)),
+                )
+                .expect(1)
+                .mount(&backends.moetran)
+                .await;
+
+            urls.push(format!("{}{}", backends.moetran.uri(), route));
+        }
+
+        let events = RecordedEvents::default();
+        let download = |wait| {
+            download_project(
+                &events,
+                project_id.to_string(),
+                "并发下载".to_string(),
+                urls.iter()
+                    .map(|url| FileDownloadInfo {
+                        url: url.clone(),
+                        id: None,
+                    })
+                    .collect(),
+                None,
+                wait,
+            )
+        };
+
+        let (first, joined, rejected) =
+            tokio::join!(download(false), download(true), download(false));
+
+        let first = first.unwrap();
+        assert_eq!((first.downloaded, first.skipped), (3, 0));
+        assert!(first.failed.is_empty());
+
+        // 等待的调用拿到同一次下载的结果，而不是再下载一遍
+        let joined = joined.unwrap();
+        assert_eq!((joined.downloaded, joined.skipped), (3, 0));
+
+        let rejected = rejected.unwrap_err();
+        assert_eq!(rejected.kind(), "AlreadyInProgress");
+        let rejected = serde_json::to_value(&rejected).unwrap();
+        assert_eq!(rejected["project_id"], project_id);
+
+        assert_eq!(events.count(DownloadFinished::NAME), 1);
+        assert_eq!(events.count(DownloadProgressed::NAME), 3);
+
+        backends.moetran.verify().await;
+
+        remove_project_cache(project_id).await.unwrap();
+    }
 }
@@ -0,0 +1,313 @@
+// 自定义协议 moeimg://：前端直接以 URL 加载缓存图片（<img src>、fetch），不再把整张图片 base64 后经 IPC 传输。
+// 路径为 {project_id}/{file_index}[/{file_id}]，查找逻辑与 load_cached_file 相同，支持单段 Range 请求。
+// 各平台的实际 URL 不同（Windows / Android 为 http://moeimg.localhost/...），前端应通过 convertFileSrc 生成
+use std::io::SeekFrom;
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::image_cache::cached_file_for_protocol;
+
+pub(crate) const SCHEME: &str = "moeimg";
+
+// id 长度上限（Moetran 的 id 为 24 位十六进制）
+const MAX_ID_LEN: usize = 64;
+
+struct ImageTarget {
+    project_id: String,
+    file_index: usize,
+    file_id: Option<String>,
+}
+
+// 只允许字母、数字、'-' 与 '_'：".."、路径分隔符等无法拼出缓存目录之外的路径
+fn is_safe_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn parse_target(path: &str) -> Option<ImageTarget> {
+    // convertFileSrc 会把整个路径编码为一段（'/' 变为 %2F），先解码再拆分
+    let decoded = urlencoding::decode(path).ok()?;
+    let mut segments = decoded.trim_matches('/').split('/');
+
+    let project_id = segments.next().filter(|id| is_safe_id(id))?;
+    let file_index = segments.next()?.parse().ok()?;
+
+    let file_id = match segments.next() {
+        Some(id) if is_safe_id(id) => Some(id.to_string()),
+        Some(_) => return None,
+        None => None,
+    };
+
+    if segments.next().is_some() {
+        return None;
+    }
+
+    Some(ImageTarget {
+        project_id: project_id.to_string(),
+        file_index,
+        file_id,
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Full,
+    // 闭区间 [start, end]
+    Partial { start: u64, end: u64 },
+    Unsatisfiable,
+}
+
+// 只支持单段范围（bytes=a-b、bytes=a-、bytes=-n）；多段或无法解析时按规范忽略，返回整个文件
+fn parse_range(value: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = value.and_then(|value| value.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let last = len.saturating_sub(1);
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return ByteRange::Full,
+        // 最后 n 个字节
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(n) => (len.saturating_sub(n), last),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+
+            let end = match end {
+                "" => last,
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end.min(last),
+                    _ => return ByteRange::Full,
+                },
+            };
+
+            (start, end)
+        }
+    };
+
+    if len == 0 || start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+
+    ByteRange::Partial { start, end }
+}
+
+type ProtocolError = (StatusCode, String);
+
+fn io_error(err: std::io::Error) -> ProtocolError {
+    let status = if err.kind() == std::io::ErrorKind::NotFound {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    (status, format!("读取缓存文件失败: {}", err))
+}
+
+async fn serve(request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>, ProtocolError> {
+    let target = parse_target(request.uri().path())
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "无效的图片路径".to_string()))?;
+
+    let (path, content_type) =
+        cached_file_for_protocol(&target.project_id, target.file_index, target.file_id)
+            .await
+            .map_err(|err| (StatusCode::NOT_FOUND, err))?;
+
+    let mut file = tokio::fs::File::open(&path).await.map_err(io_error)?;
+    let len = file.metadata().await.map_err(io_error)?.len();
+
+    let range = parse_range(
+        request
+            .headers()
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok()),
+        len,
+    );
+
+    // 重新下载后同一 URL 的内容可能变化，不让 webview 长期缓存
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+    let response = match range {
+        ByteRange::Full => {
+            let mut body = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut body).await.map_err(io_error)?;
+
+            builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(body)
+        }
+        ByteRange::Partial { start, end } => {
+            let mut body = vec![0; (end - start + 1) as usize];
+
+            file.seek(SeekFrom::Start(start)).await.map_err(io_error)?;
+            file.read_exact(&mut body).await.map_err(io_error)?;
+
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", start, end, len),
+                )
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(body)
+        }
+        ByteRange::Unsatisfiable => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new()),
+    };
+
+    response.map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+// 处理一次 moeimg:// 请求；出错时返回对应状态码与纯文本错误信息
+pub(crate) async fn respond(request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    match serve(&request).await {
+        Ok(response) => response,
+        Err((status, message)) => {
+            tracing::debug!(uri = %request.uri(), %status, error = %message, "image_protocol.request_failed");
+
+            let mut response = Response::new(message.into_bytes());
+            *response.status_mut() = status;
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("text/plain; charset=utf-8"),
+            );
+            response.headers_mut().insert(
+                header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                header::HeaderValue::from_static("*"),
+            );
+
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(path: &str) -> Option<(String, usize, Option<String>)> {
+        parse_target(path).map(|t| (t.project_id, t.file_index, t.file_id))
+    }
+
+    #[test]
+    fn ids_are_limited_to_a_safe_alphabet() {
+        assert!(is_safe_id("65f0c2a9e4b0a1b2c3d4e5f6"));
+        assert!(is_safe_id("demo-proj_1"));
+        assert!(is_safe_id(&"a".repeat(MAX_ID_LEN)));
+
+        for id in ["", "..", ".", "a/b", "a\\b", "a%2Fb", "a.b", "项目", " a"] {
+            assert!(!is_safe_id(id), "{:?}", id);
+        }
+        assert!(!is_safe_id(&"a".repeat(MAX_ID_LEN + 1)));
+    }
+
+    #[test]
+    fn targets_are_parsed_from_plain_and_encoded_paths() {
+        let expected = Some(("p1".to_string(), 3, Some("f1".to_string())));
+
+        assert_eq!(target("p1/3/f1"), expected);
+        assert_eq!(target("/p1/3/f1/"), expected);
+        // convertFileSrc 把整个路径编码为一段
+        assert_eq!(target("p1%2F3%2Ff1"), expected);
+        assert_eq!(target("p1%2f3%2ff1"), expected);
+
+        assert_eq!(target("p1/0"), Some(("p1".to_string(), 0, None)));
+    }
+
+    #[test]
+    fn traversal_and_malformed_targets_are_rejected() {
+        for path in [
+            "",
+            "p1",
+            "../3",
+            "p1/../3",
+            "p1/3/..",
+            "p1/3/../../etc",
+            // 编码后的 ".." 与分隔符解码后同样被拒绝
+            "%2E%2E%2F3",
+            "p1%2F3%2F..%2F..%2Fsecret",
+            "p1%5C..%5C/3",
+            // 只解码一次：双重编码的 ".." 含有 '%'
+            "%252E%252E/3",
+            "p1/3/f1/extra",
+            "p1/-1",
+            "p1/x",
+            "p1/3/f.png",
+            // 解码结果不是 UTF-8
+            "%FF/3",
+        ] {
+            assert!(target(path).is_none(), "{:?}", path);
+        }
+    }
+
+    #[test]
+    fn single_ranges_are_clamped_to_the_file() {
+        let partial = |start, end| ByteRange::Partial { start, end };
+
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), partial(0, 9));
+        assert_eq!(parse_range(Some(" bytes=10-19 "), 100), partial(10, 19));
+        assert_eq!(parse_range(Some("bytes=90-"), 100), partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-10"), 100), partial(90, 99));
+        // 超出文件末尾的部分截断
+        assert_eq!(parse_range(Some("bytes=50-500"), 100), partial(50, 99));
+        assert_eq!(parse_range(Some("bytes=-500"), 100), partial(0, 99));
+    }
+
+    #[test]
+    fn out_of_bounds_ranges_are_unsatisfiable() {
+        for spec in ["bytes=100-", "bytes=150-200", "bytes=-0"] {
+            assert_eq!(
+                parse_range(Some(spec), 100),
+                ByteRange::Unsatisfiable,
+                "{}",
+                spec
+            );
+        }
+
+        assert_eq!(parse_range(Some("bytes=0-"), 0), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=-5"), 0), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn multi_range_and_malformed_headers_serve_the_whole_file() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+
+        for spec in [
+            "bytes=0-1,5-6",
+            "bytes=0-1, 200-300",
+            "bytes=9-0",
+            "bytes=-",
+            "bytes=abc",
+            "bytes=1-x",
+            "bytes=--5",
+            "items=0-9",
+            "0-9",
+        ] {
+            assert_eq!(parse_range(Some(spec), 100), ByteRange::Full, "{}", spec);
+        }
+    }
+}
@@ -0,0 +1,559 @@
+// 删除 / 移除前的影响检查：统计会被孤立的关联数据，未确认（force）时拒绝执行
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    defer::WarnDefer,
+    error::AppError,
+    http::{moetran_get, poprako_post_opt},
+    project::{
+        cached_source_translation_count, get_project_targets, GetProjectTargetsReq,
+        PoprakoEnvelope, PoprakoProjFilterReq, PoprakoProjInfo,
+    },
+};
+
+// 同时进行的检查数上限，避免批量删除时瞬间打满两个后端
+const IMPACT_CHECK_CONCURRENCY: usize = 4;
+// 单项检查超时：后端不可达时报告“影响未知”，而不是一直阻塞删除流程
+const IMPACT_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImpactEntity {
+    Source {
+        source_id: String,
+    },
+    File {
+        file_id: String,
+        target_id: String,
+    },
+    Target {
+        project_id: String,
+        target_id: String,
+    },
+    Member {
+        member_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ImpactCount {
+    Known { count: u64 },
+    Unknown { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactItem {
+    // "translations" / "sources" / "active_projects" / "principal_projects"
+    pub label: &'static str,
+    pub count: ImpactCount,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteImpact {
+    pub entity: ImpactEntity,
+    pub items: Vec<ImpactItem>,
+}
+
+impl DeleteImpact {
+    // 所有关联数据均确认为 0 时才可直接删除
+    pub fn is_empty(&self) -> bool {
+        self.items
+            .iter()
+            .all(|item| matches!(item.count, ImpactCount::Known { count: 0 }))
+    }
+}
+
+fn known(label: &'static str, count: u64) -> ImpactItem {
+    ImpactItem {
+        label,
+        count: ImpactCount::Known { count },
+    }
+}
+
+fn unknown(label: &'static str, reason: impl Into<String>) -> ImpactItem {
+    ImpactItem {
+        label,
+        count: ImpactCount::Unknown {
+            reason: reason.into(),
+        },
+    }
+}
+
+async fn with_timeout<F>(label: &'static str, fut: F) -> ImpactItem
+where
+    F: std::future::Future<Output = Result<u64, String>>,
+{
+    match tokio::time::timeout(IMPACT_CHECK_TIMEOUT, fut).await {
+        Ok(Ok(count)) => known(label, count),
+        Ok(Err(err)) => unknown(label, err),
+        Err(_) => unknown(label, "检查超时"),
+    }
+}
+
+// source 上的翻译数：仅来自最近一次 get_page_sources 的结果
+fn source_impact(source_id: &str) -> Vec<ImpactItem> {
+    let item = match cached_source_translation_count(source_id) {
+        Some(count) => known("translations", count as u64),
+        None => unknown("translations", "该 source 未在本地加载过"),
+    };
+
+    vec![item]
+}
+
+async fn file_impact(file_id: &str, target_id: &str) -> Vec<ImpactItem> {
+    let endpoint = format!("files/{}/sources", file_id);
+
+    let mut query = std::collections::HashMap::new();
+    query.insert("target_id", target_id.to_string());
+    query.insert("paging", "false".to_string());
+
+    let fut = async {
+        moetran_get::<Vec<serde_json::Value>>(&endpoint, Some(&query))
+            .await
+            .map(|list| list.len() as u64)
+            .map_err(|err| err.to_string())
+    };
+
+    vec![with_timeout("sources", fut).await]
+}
+
+async fn target_impact(project_id: &str, target_id: &str) -> Vec<ImpactItem> {
+    let fut = async {
+        let targets = get_project_targets(GetProjectTargetsReq {
+            project_id: project_id.into(),
+        })
+        .await?;
+
+        targets
+            .into_iter()
+            .find(|target| target.id == target_id)
+            .map(|target| target.translated_source_count)
+            .ok_or_else(|| "未找到该 target".to_string())
+    };
+
+    vec![with_timeout("translations", fut).await]
+}
+
+// 成员参与的未发布项目（及其中作为负责人的项目）
+async fn member_impact(member_id: &str) -> Vec<ImpactItem> {
+    let filter = PoprakoProjFilterReq {
+        is_published: Some(false),
+        member_ids: Some(vec![member_id.into()]),
+        ..Default::default()
+    };
+
+    let fut = async {
+        let reply =
+            poprako_post_opt::<PoprakoProjFilterReq, PoprakoEnvelope<Vec<PoprakoProjInfo>>>(
+                "projs/search",
+                Some(filter),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+
+        if reply.code != 200 {
+            return Err(reply
+                .message
+                .unwrap_or_else(|| "PopRaKo 项目搜索失败".to_string()));
+        }
+
+        Ok(reply.data.unwrap_or_default())
+    };
+
+    match tokio::time::timeout(IMPACT_CHECK_TIMEOUT, fut).await {
+        Ok(Ok(projs)) => {
+            let principal = projs
+                .iter()
+                .filter(|proj| {
+                    proj.members
+                        .iter()
+                        .flatten()
+                        .any(|member| member.member_id == member_id && member.is_principal)
+                })
+                .count();
+
+            vec![
+                known("active_projects", projs.len() as u64),
+                known("principal_projects", principal as u64),
+            ]
+        }
+        Ok(Err(err)) => vec![
+            unknown("active_projects", err.clone()),
+            unknown("principal_projects", err),
+        ],
+        Err(_) => vec![
+            unknown("active_projects", "检查超时"),
+            unknown("principal_projects", "检查超时"),
+        ],
+    }
+}
+
+pub async fn check_delete_impact(entity: &ImpactEntity) -> DeleteImpact {
+    let items = match entity {
+        ImpactEntity::Source { source_id } => source_impact(source_id),
+        ImpactEntity::File { file_id, target_id } => file_impact(file_id, target_id).await,
+        ImpactEntity::Target {
+            project_id,
+            target_id,
+        } => target_impact(project_id, target_id).await,
+        ImpactEntity::Member { member_id } => member_impact(member_id).await,
+    };
+
+    DeleteImpact {
+        entity: entity.clone(),
+        items,
+    }
+}
+
+// 删除命令的前置检查：force=true 直接放行；否则存在（或无法确认）关联数据时拒绝，
+// 返回携带影响摘要的 DeleteBlocked 错误，供前端渲染确认对话框
+pub async fn ensure_delete_allowed(entity: ImpactEntity, force: bool) -> Result<(), AppError> {
+    if force {
+        tracing::info!(?entity, "impact_check.forced");
+        return Ok(());
+    }
+
+    let impact = check_delete_impact(&entity).await;
+
+    if impact.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(?impact, "impact_check.blocked");
+
+    Err(AppError::DeleteBlocked(Box::new(impact)))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckDeleteImpactReq {
+    pub entities: Vec<ImpactEntity>,
+}
+
+// 批量预览删除影响（有并发上限；单项失败或超时记为 unknown，不影响其余项）
+#[tauri::command]
+pub async fn check_delete_impacts(
+    payload: CheckDeleteImpactReq,
+) -> Result<Vec<DeleteImpact>, String> {
+    tracing::info!(count = payload.entities.len(), "impact_check.batch.start");
+
+    let mut defer = WarnDefer::new("impact_check.batch");
+
+    let semaphore = Arc::new(Semaphore::new(IMPACT_CHECK_CONCURRENCY));
+    let mut set = JoinSet::new();
+
+    for (index, entity) in payload.entities.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (index, check_delete_impact(&entity).await)
+        });
+    }
+
+    let mut results = Vec::with_capacity(set.len());
+
+    while let Some(joined) = set.join_next().await {
+        let item = joined.map_err(|err| format!("影响检查任务失败: {}", err))?;
+        results.push(item);
+    }
+
+    // 按请求顺序返回
+    results.sort_by_key(|(index, _)| *index);
+
+    tracing::info!(count = results.len(), "impact_check.batch.ok");
+
+    defer.success();
+
+    Ok(results.into_iter().map(|(_, impact)| impact).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        member::{remove_team_member, RemoveTeamMemberReq},
+        project::{create_source, CreateSourceReq},
+        test_support::MockBackends,
+    };
+
+    fn counts(impact: &DeleteImpact) -> Vec<(&'static str, Option<u64>)> {
+        impact
+            .items
+            .iter()
+            .map(|item| match item.count {
+                ImpactCount::Known { count } => (item.label, Some(count)),
+                ImpactCount::Unknown { .. } => (item.label, None),
+            })
+            .collect()
+    }
+
+    fn member_entity(member_id: &str) -> ImpactEntity {
+        ImpactEntity::Member {
+            member_id: member_id.to_string(),
+        }
+    }
+
+    fn proj_json(proj_id: &str, member_id: &str, is_principal: bool) -> serde_json::Value {
+        json!({
+            "proj_id": proj_id,
+            "proj_name": "项目",
+            "projset_index": 1,
+            "translating_status": 1,
+            "proofreading_status": 0,
+            "typesetting_status": 0,
+            "reviewing_status": 0,
+            "is_published": false,
+            "members": [{
+                "user_id": "u1",
+                "member_id": member_id,
+                "username": "成员",
+                "is_admin": false,
+                "is_translator": true,
+                "is_proofreader": false,
+                "is_typesetter": false,
+                "is_principal": is_principal,
+            }],
+        })
+    }
+
+    async fn mount_member_projs(backends: &MockBackends, projs: Vec<serde_json::Value>) {
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": projs,
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn unloaded_source_has_unknown_impact() {
+        let impact = check_delete_impact(&ImpactEntity::Source {
+            source_id: "never-loaded".to_string(),
+        })
+        .await;
+
+        assert_eq!(counts(&impact), [("translations", None)]);
+        assert!(!impact.is_empty());
+    }
+
+    #[tokio::test]
+    async fn created_source_is_known_empty() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/files/f-new/sources"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "s-created",
+                "x": 0.5,
+                "y": 0.5,
+                "position_type": 1,
+                "my_translation": null,
+                "translations": [],
+            })))
+            .mount(&backends.moetran)
+            .await;
+
+        let req: CreateSourceReq = serde_json::from_value(json!({
+            "file_id": "f-new",
+            "x": 0.5,
+            "y": 0.5,
+        }))
+        .unwrap();
+        create_source(req).await.unwrap();
+
+        let impact = check_delete_impact(&ImpactEntity::Source {
+            source_id: "s-created".to_string(),
+        })
+        .await;
+
+        assert_eq!(counts(&impact), [("translations", Some(0))]);
+        assert!(impact.is_empty());
+    }
+
+    #[tokio::test]
+    async fn file_impact_counts_sources() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/files/f1/sources"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": "s1" },
+                { "id": "s2" },
+                { "id": "s3" },
+            ])))
+            .mount(&backends.moetran)
+            .await;
+
+        let impact = check_delete_impact(&ImpactEntity::File {
+            file_id: "f1".to_string(),
+            target_id: "t1".to_string(),
+        })
+        .await;
+
+        assert_eq!(counts(&impact), [("sources", Some(3))]);
+    }
+
+    #[tokio::test]
+    async fn target_impact_counts_translated_sources() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/p1/targets"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": "t-other", "translated_source_count": 9, "checked_source_count": 0 },
+                { "id": "t1", "translated_source_count": 4, "checked_source_count": 1 },
+            ])))
+            .mount(&backends.moetran)
+            .await;
+
+        let target = |target_id: &str| ImpactEntity::Target {
+            project_id: "p1".to_string(),
+            target_id: target_id.to_string(),
+        };
+
+        let impact = check_delete_impact(&target("t1")).await;
+        assert_eq!(counts(&impact), [("translations", Some(4))]);
+
+        let impact = check_delete_impact(&target("t-missing")).await;
+        assert_eq!(counts(&impact), [("translations", None)]);
+    }
+
+    #[tokio::test]
+    async fn member_impact_counts_active_and_principal_projects() {
+        let backends = MockBackends::start().await;
+
+        mount_member_projs(
+            &backends,
+            vec![proj_json("p1", "m1", true), proj_json("p2", "m1", false)],
+        )
+        .await;
+
+        let impact = check_delete_impact(&member_entity("m1")).await;
+
+        assert_eq!(
+            counts(&impact),
+            [
+                ("active_projects", Some(2)),
+                ("principal_projects", Some(1))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_backend_reports_unknown_impact() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&backends.poprako)
+            .await;
+
+        let impact = check_delete_impact(&member_entity("m1")).await;
+
+        assert_eq!(
+            counts(&impact),
+            [("active_projects", None), ("principal_projects", None)]
+        );
+        assert!(!impact.is_empty());
+    }
+
+    #[tokio::test]
+    async fn blocked_delete_returns_typed_error_with_impact() {
+        let backends = MockBackends::start().await;
+
+        mount_member_projs(&backends, vec![proj_json("p1", "m1", false)]).await;
+
+        let err = ensure_delete_allowed(member_entity("m1"), false)
+            .await
+            .unwrap_err();
+
+        assert!(err.is_delete_blocked());
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["kind"], "DeleteBlocked");
+        assert_eq!(value["impact"]["entity"]["kind"], "member");
+        assert_eq!(value["impact"]["items"][0]["label"], "active_projects");
+        assert_eq!(
+            value["impact"]["items"][0]["count"],
+            json!({ "state": "known", "count": 1 })
+        );
+
+        // force 时不做检查
+        ensure_delete_allowed(member_entity("m1"), true)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn member_without_active_projects_is_allowed() {
+        let backends = MockBackends::start().await;
+
+        mount_member_projs(&backends, vec![]).await;
+
+        ensure_delete_allowed(member_entity("m-idle"), false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn remove_team_member_checks_member_impact() {
+        let backends = MockBackends::start().await;
+
+        mount_member_projs(&backends, vec![proj_json("p1", "m1", true)]).await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/remove"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&backends.poprako)
+            .await;
+
+        let err = remove_team_member(RemoveTeamMemberReq {
+            team_id: "team-1".into(),
+            member_id: "m1".into(),
+            force: false,
+        })
+        .await
+        .unwrap_err();
+
+        assert!(err.is_delete_blocked());
+    }
+
+    #[tokio::test]
+    async fn batch_check_keeps_request_order() {
+        let backends = MockBackends::start().await;
+
+        mount_member_projs(&backends, vec![]).await;
+
+        let entities: Vec<ImpactEntity> = (0..6)
+            .map(|index| member_entity(&format!("m{}", index)))
+            .collect();
+
+        let impacts = check_delete_impacts(CheckDeleteImpactReq { entities })
+            .await
+            .unwrap();
+
+        let ids: Vec<String> = impacts
+            .iter()
+            .map(|impact| match &impact.entity {
+                ImpactEntity::Member { member_id } => member_id.clone(),
+                other => panic!("unexpected entity {:?}", other),
+            })
+            .collect();
+        assert_eq!(ids, ["m0", "m1", "m2", "m3", "m4", "m5"]);
+    }
+}
@@ -0,0 +1,371 @@
+// 单实例保护：两个实例同时打开 local.db 与图片缓存时，元数据写入会交错、下载会互相覆盖。
+// 启动时对数据目录下的 instance.lock 加排他锁（系统文件锁，进程退出或崩溃后自动释放），
+// 并在 instance.json 中写入 PID、启动时间与本机聚焦端口；已被占用时向已有实例发送聚焦请求后退出。
+// 后台任务在大量写入前用 owns_lock 确认锁仍属于本进程（数据目录被删除重建后可能出现第二个持锁者）
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::clock::unix_now;
+
+const LOCK_FILE_NAME: &str = "instance.lock";
+const INFO_FILE_NAME: &str = "instance.json";
+
+// 聚焦握手：第二个实例发送 "<协议> <pid>\n"，已有实例回复 "ok\n"
+const FOCUS_PROTOCOL: &str = "moetran-poprako/focus/1";
+const FOCUS_REPLY_OK: &str = "ok";
+const FOCUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+const MAIN_WINDOW_LABEL: &str = "main";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstanceInfo {
+    pid: u32,
+    started_at: i64,
+    // 0 表示未能监听，无法转发聚焦请求
+    focus_port: u16,
+}
+
+struct HeldLock {
+    // 持有文件句柄即持有锁，进程结束时由系统释放
+    _file: File,
+    info_path: PathBuf,
+    listener: Mutex<Option<TcpListener>>,
+}
+
+static HELD: OnceLock<HeldLock> = OnceLock::new();
+
+pub(crate) enum Acquire {
+    Acquired,
+    // 已有实例在运行；focused 表示已有实例确认了聚焦请求
+    AlreadyRunning { pid: Option<u32>, focused: bool },
+}
+
+fn read_info(path: &Path) -> Option<InstanceInfo> {
+    let raw = fs::read_to_string(path).ok()?;
+
+    serde_json::from_str(&raw).ok()
+}
+
+fn focus_request(pid: u32) -> String {
+    format!("{} {}\n", FOCUS_PROTOCOL, pid)
+}
+
+// 返回发送方 pid；协议不符时为 None
+fn parse_focus_request(line: &str) -> Option<u32> {
+    let (protocol, pid) = line.trim().split_once(' ')?;
+
+    if protocol != FOCUS_PROTOCOL {
+        return None;
+    }
+
+    pid.parse().ok()
+}
+
+// 向已有实例转发聚焦请求，返回对方是否确认
+fn forward_focus(port: u16) -> Result<(), String> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+    let mut stream = TcpStream::connect_timeout(&addr, FOCUS_TIMEOUT)
+        .map_err(|err| format!("connect failed: {}", err))?;
+
+    stream
+        .set_read_timeout(Some(FOCUS_TIMEOUT))
+        .map_err(|err| format!("set timeout failed: {}", err))?;
+
+    stream
+        .write_all(focus_request(std::process::id()).as_bytes())
+        .map_err(|err| format!("send failed: {}", err))?;
+
+    let mut reply = String::new();
+
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|err| format!("read failed: {}", err))?;
+
+    if reply.trim() != FOCUS_REPLY_OK {
+        return Err(format!("unexpected reply: {:?}", reply.trim()));
+    }
+
+    Ok(())
+}
+
+// 启动时调用（早于打开数据库）。锁文件无法打开等 I/O 错误时不阻止启动，只记录日志
+pub(crate) fn acquire(data_dir: &Path) -> Acquire {
+    let lock_path = data_dir.join(LOCK_FILE_NAME);
+    let info_path = data_dir.join(INFO_FILE_NAME);
+
+    let file = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&lock_path)
+    {
+        Ok(file) => file,
+        Err(err) => {
+            tracing::warn!(error = %err, path = ?lock_path, "instance_lock.open_failed");
+            return Acquire::Acquired;
+        }
+    };
+
+    match file.try_lock_exclusive() {
+        Ok(true) => {}
+        Ok(false) => {
+            let info = read_info(&info_path);
+            let pid = info.as_ref().map(|info| info.pid);
+
+            let focused = match info.filter(|info| info.focus_port != 0) {
+                Some(info) => match forward_focus(info.focus_port) {
+                    Ok(()) => true,
+                    Err(err) => {
+                        tracing::warn!(error = %err, ?pid, "instance_lock.focus_forward_failed");
+                        false
+                    }
+                },
+                None => false,
+            };
+
+            return Acquire::AlreadyRunning { pid, focused };
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, path = ?lock_path, "instance_lock.lock_failed");
+            return Acquire::Acquired;
+        }
+    }
+
+    // 系统锁已随上一个进程退出释放，但 instance.json 仍是它写下的（崩溃或被强制结束）
+    if let Some(previous) = read_info(&info_path) {
+        tracing::info!(
+            previous_pid = previous.pid,
+            previous_started_at = previous.started_at,
+            "instance_lock.stale_broken"
+        );
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .inspect_err(|err| tracing::warn!(error = %err, "instance_lock.focus_listen_failed"))
+        .ok();
+
+    let info = InstanceInfo {
+        pid: std::process::id(),
+        started_at: unix_now(),
+        focus_port: listener
+            .as_ref()
+            .and_then(|listener| listener.local_addr().ok())
+            .map(|addr| addr.port())
+            .unwrap_or(0),
+    };
+
+    let written = serde_json::to_string(&info)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| fs::write(&info_path, raw).map_err(|err| err.to_string()));
+
+    if let Err(err) = written {
+        tracing::warn!(error = %err, path = ?info_path, "instance_lock.info_write_failed");
+    }
+
+    tracing::info!(
+        pid = info.pid,
+        focus_port = info.focus_port,
+        "instance_lock.acquired"
+    );
+
+    let _ = HELD.set(HeldLock {
+        _file: file,
+        info_path,
+        listener: Mutex::new(listener),
+    });
+
+    Acquire::Acquired
+}
+
+// 锁是否仍属于本进程；未能加锁（I/O 错误）时不做限制，返回 true
+pub(crate) fn owns_lock() -> bool {
+    let Some(held) = HELD.get() else {
+        return true;
+    };
+
+    read_info(&held.info_path).is_some_and(|info| info.pid == std::process::id())
+}
+
+fn focus_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+
+    let _ = window.unminimize();
+    let _ = window.show();
+
+    if let Err(err) = window.set_focus() {
+        tracing::warn!(error = %err, "instance_lock.focus_failed");
+    }
+}
+
+fn handle_focus_stream(app: &AppHandle, stream: TcpStream) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(FOCUS_TIMEOUT))
+        .map_err(|err| err.to_string())?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+
+    reader.read_line(&mut line).map_err(|err| err.to_string())?;
+
+    let Some(pid) = parse_focus_request(&line) else {
+        return Err(format!("unexpected request: {:?}", line.trim()));
+    };
+
+    tracing::info!(from_pid = pid, "instance_lock.focus_requested");
+
+    focus_main_window(app);
+
+    reader
+        .get_mut()
+        .write_all(format!("{}\n", FOCUS_REPLY_OK).as_bytes())
+        .map_err(|err| err.to_string())
+}
+
+// 在后台线程上接收其他实例的聚焦请求（应用 setup 时调用一次）
+pub(crate) fn serve_focus(app: AppHandle) {
+    let listener = HELD
+        .get()
+        .and_then(|held| held.listener.lock().ok()?.take());
+
+    let Some(listener) = listener else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result = stream
+                .map_err(|err| err.to_string())
+                .and_then(|stream| handle_focus_stream(&app, stream));
+
+            if let Err(err) = result {
+                tracing::warn!(error = %err, "instance_lock.focus_request_failed");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 只测试“已有实例”一侧：成功加锁会设置进程级的 HELD，影响其他测试中的 owns_lock
+    fn hold_lock(dir: &Path) -> File {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(dir.join(LOCK_FILE_NAME))
+            .unwrap();
+
+        assert!(file.try_lock_exclusive().unwrap());
+        file
+    }
+
+    fn write_info(dir: &Path, pid: u32, focus_port: u16) {
+        let info = InstanceInfo {
+            pid,
+            started_at: 0,
+            focus_port,
+        };
+
+        fs::write(
+            dir.join(INFO_FILE_NAME),
+            serde_json::to_string(&info).unwrap(),
+        )
+        .unwrap();
+    }
+
+    // 模拟已有实例的聚焦服务：读取一行请求并按 reply 回复
+    fn serve_once(reply: &'static str) -> (u16, std::thread::JoinHandle<Option<u32>>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            reader.get_mut().write_all(reply.as_bytes()).unwrap();
+            parse_focus_request(&line)
+        });
+
+        (port, handle)
+    }
+
+    #[test]
+    fn focus_request_round_trips() {
+        assert_eq!(parse_focus_request(&focus_request(42)), Some(42));
+        assert_eq!(parse_focus_request("other/focus/1 42\n"), None);
+        assert_eq!(
+            parse_focus_request(&format!("{} abc", FOCUS_PROTOCOL)),
+            None
+        );
+        assert_eq!(parse_focus_request(""), None);
+    }
+
+    #[test]
+    fn second_instance_forwards_focus_to_the_running_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let _held = hold_lock(dir.path());
+
+        let (port, server) = serve_once("ok\n");
+        write_info(dir.path(), 4242, port);
+
+        let Acquire::AlreadyRunning { pid, focused } = acquire(dir.path()) else {
+            panic!("lock should be held by the other handle");
+        };
+        assert_eq!(pid, Some(4242));
+        assert!(focused);
+        assert_eq!(server.join().unwrap(), Some(std::process::id()));
+    }
+
+    #[test]
+    fn focus_is_not_confirmed_without_a_valid_reply() {
+        let dir = tempfile::tempdir().unwrap();
+        let _held = hold_lock(dir.path());
+
+        let (port, server) = serve_once("busy\n");
+        write_info(dir.path(), 4242, port);
+
+        let Acquire::AlreadyRunning { focused, .. } = acquire(dir.path()) else {
+            panic!("lock should be held by the other handle");
+        };
+        assert!(!focused);
+        server.join().unwrap();
+
+        // 已有实例未能监听，或 instance.json 缺失
+        write_info(dir.path(), 4242, 0);
+        assert!(matches!(
+            acquire(dir.path()),
+            Acquire::AlreadyRunning {
+                pid: Some(4242),
+                focused: false
+            }
+        ));
+
+        fs::remove_file(dir.path().join(INFO_FILE_NAME)).unwrap();
+        assert!(matches!(
+            acquire(dir.path()),
+            Acquire::AlreadyRunning {
+                pid: None,
+                focused: false
+            }
+        ));
+    }
+}
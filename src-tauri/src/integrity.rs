@@ -0,0 +1,742 @@
+// 本地数据完整性检查：崩溃或中断后各存储之间可能出现小的不一致（孤立的缓存目录、清单中丢失的文件、
+// 指向已删除项目的待执行写操作、对应 source 已删除的草稿等）。每项检查是 CHECKS 中的一个函数，
+// 返回按严重程度分级的问题；fix 为 true 时执行可安全自动修复的部分（幂等，逐条记录日志）
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio::fs;
+
+use crate::{
+    clock::unix_now,
+    config::config,
+    defer::WarnDefer,
+    image_cache::{cache_root, collect_cached_sizes, manifest_missing_files},
+    project::{cached_proj_snapshot, fetch_poprako_proj, ResProjectEnriched},
+    source_snapshot::decode,
+    storage::{
+        cache_metadata::{
+            delete_cached_project_metadata, get_all_cached_projects, upsert_cached_project,
+            CachedProjectMetadata,
+        },
+        pending_writes, publish_records, recent_projects, source_snapshots, translation_drafts,
+        LOCAL_STORAGE,
+    },
+    write_queue::is_connectivity_error,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    // 涉及的对象（项目 id、文件名等）
+    pub subject: String,
+    pub message: String,
+    // 是否有可自动执行的修复
+    pub fixable: bool,
+    pub fixed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_error: Option<String>,
+}
+
+impl Finding {
+    fn new(
+        check: &'static str,
+        severity: Severity,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            check,
+            severity,
+            subject: subject.into(),
+            message: message.into(),
+            fixable: false,
+            fixed: false,
+            fix_error: None,
+        }
+    }
+
+    // 标记为可修复，并记录修复结果（未执行修复时传 None）
+    fn with_fix(mut self, result: Option<Result<(), String>>) -> Self {
+        self.fixable = true;
+
+        match result {
+            Some(Ok(())) => {
+                tracing::info!(check = self.check, subject = %self.subject, "integrity.fix.applied");
+                self.fixed = true;
+            }
+            Some(Err(err)) => {
+                tracing::warn!(check = self.check, subject = %self.subject, error = %err, "integrity.fix.failed");
+                self.fix_error = Some(err);
+            }
+            None => {}
+        }
+
+        self
+    }
+}
+
+struct CheckContext {
+    pool: SqlitePool,
+    fix: bool,
+}
+
+type CheckFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Finding>, String>> + Send + 'a>>;
+
+struct IntegrityCheck {
+    name: &'static str,
+    // 只读本地数据、开销小，可在启动时运行
+    lightweight: bool,
+    run: fn(&CheckContext) -> CheckFuture<'_>,
+}
+
+// 新增检查时在此登记
+const CHECKS: &[IntegrityCheck] = &[
+    IntegrityCheck {
+        name: "cache_metadata",
+        lightweight: true,
+        run: check_cache_metadata,
+    },
+    IntegrityCheck {
+        name: "cache_manifest",
+        lightweight: true,
+        run: check_cache_manifest,
+    },
+    IntegrityCheck {
+        name: "source_snapshots",
+        lightweight: true,
+        run: check_source_snapshots,
+    },
+    IntegrityCheck {
+        name: "draft_sources",
+        lightweight: false,
+        run: check_draft_sources,
+    },
+    IntegrityCheck {
+        name: "publish_records",
+        lightweight: true,
+        run: check_publish_records,
+    },
+    IntegrityCheck {
+        name: "pending_write_targets",
+        lightweight: false,
+        run: check_pending_write_targets,
+    },
+];
+
+// ========== 各项检查 ==========
+
+async fn cache_dir_names() -> Result<Vec<String>, String> {
+    let root = cache_root();
+
+    if !root.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries = fs::read_dir(&root)
+        .await
+        .map_err(|err| format!("读取缓存目录失败: {}", err))?;
+
+    let mut names = Vec::new();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| format!("遍历缓存目录失败: {}", err))?
+    {
+        if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+
+    names.sort();
+
+    Ok(names)
+}
+
+// 缓存元数据与磁盘：有记录无目录的删除记录；有目录无记录的按目录内容补建记录
+fn check_cache_metadata(ctx: &CheckContext) -> CheckFuture<'_> {
+    Box::pin(async move {
+        const CHECK: &str = "cache_metadata";
+
+        let rows = get_all_cached_projects(&ctx.pool).await?;
+        let dirs: HashSet<String> = cache_dir_names().await?.into_iter().collect();
+        let recorded: HashSet<&str> = rows.iter().map(|r| r.project_id.as_str()).collect();
+
+        let mut findings = Vec::new();
+
+        for row in &rows {
+            if dirs.contains(&row.project_id) {
+                continue;
+            }
+
+            let fix = if ctx.fix {
+                Some(delete_cached_project_metadata(&ctx.pool, &row.project_id).await)
+            } else {
+                None
+            };
+
+            findings.push(
+                Finding::new(
+                    CHECK,
+                    Severity::Warning,
+                    &row.project_id,
+                    format!("缓存记录「{}」对应的目录不存在", row.project_name),
+                )
+                .with_fix(fix),
+            );
+        }
+
+        for dir in dirs.iter().filter(|dir| !recorded.contains(dir.as_str())) {
+            let fix = if ctx.fix {
+                let result = async {
+                    let sizes = collect_cached_sizes(&cache_root().join(dir)).await?;
+
+                    upsert_cached_project(
+                        &ctx.pool,
+                        &CachedProjectMetadata {
+                            project_id: dir.clone(),
+                            project_name: dir.clone(),
+                            status: "completed".to_string(),
+                            file_count: sizes.len() as i64,
+                            total_size_bytes: sizes.iter().map(|(_, size)| *size as i64).sum(),
+                            cached_at: unix_now(),
+                            remote_file_count: None,
+                            missing_count: None,
+                            remote_checked_at: None,
+                            remote_deleted: false,
+                        },
+                    )
+                    .await
+                }
+                .await;
+
+                Some(result)
+            } else {
+                None
+            };
+
+            findings.push(
+                Finding::new(CHECK, Severity::Info, dir, "缓存目录没有对应的缓存记录")
+                    .with_fix(fix),
+            );
+        }
+
+        Ok(findings)
+    })
+}
+
+// 清单与文件：清单中记录的文件不存在时改为未下载
+fn check_cache_manifest(ctx: &CheckContext) -> CheckFuture<'_> {
+    Box::pin(async move {
+        const CHECK: &str = "cache_manifest";
+
+        let mut findings = Vec::new();
+
+        for dir in cache_dir_names().await? {
+            let cache_dir = cache_root().join(&dir);
+            let missing = manifest_missing_files(&cache_dir, false).await?;
+
+            if missing.is_empty() {
+                continue;
+            }
+
+            let fix = if ctx.fix {
+                Some(manifest_missing_files(&cache_dir, true).await.map(|_| ()))
+            } else {
+                None
+            };
+
+            findings.push(
+                Finding::new(
+                    CHECK,
+                    Severity::Warning,
+                    &dir,
+                    format!(
+                        "清单中有 {} 个文件已丢失：{}",
+                        missing.len(),
+                        missing.join(", ")
+                    ),
+                )
+                .with_fix(fix),
+            );
+        }
+
+        Ok(findings)
+    })
+}
+
+// source 快照：无法解码的快照删除（下次打开页面时重新拉取）
+fn check_source_snapshots(ctx: &CheckContext) -> CheckFuture<'_> {
+    Box::pin(async move {
+        const CHECK: &str = "source_snapshots";
+
+        let mut findings = Vec::new();
+
+        for (file_id, target_id, _) in
+            source_snapshots::list_source_snapshot_keys(&ctx.pool).await?
+        {
+            let Some(row) =
+                source_snapshots::load_source_snapshot(&ctx.pool, &file_id, &target_id).await?
+            else {
+                continue;
+            };
+
+            let Err(err) = decode(&row.payload) else {
+                continue;
+            };
+
+            let fix = if ctx.fix {
+                Some(
+                    source_snapshots::delete_source_snapshot(&ctx.pool, &file_id, &target_id)
+                        .await
+                        .map(|_| ()),
+                )
+            } else {
+                None
+            };
+
+            findings.push(
+                Finding::new(
+                    CHECK,
+                    Severity::Warning,
+                    format!("{}/{}", file_id, target_id),
+                    err,
+                )
+                .with_fix(fix),
+            );
+        }
+
+        Ok(findings)
+    })
+}
+
+// (拉取时间, 快照中的 source id)
+type SnapshotSources = (i64, HashSet<String>);
+
+// 草稿与快照：快照晚于草稿保存、且快照中已没有该 source 时，说明 source 已被删除
+fn check_draft_sources(ctx: &CheckContext) -> CheckFuture<'_> {
+    Box::pin(async move {
+        const CHECK: &str = "draft_sources";
+
+        let drafts = translation_drafts::list_all_drafts(&ctx.pool).await?;
+
+        // None 表示没有可用快照
+        let mut pages: HashMap<(String, String), Option<SnapshotSources>> = HashMap::new();
+
+        let mut findings = Vec::new();
+
+        for draft in drafts {
+            let key = (draft.file_id.clone(), draft.target_id.clone());
+
+            if !pages.contains_key(&key) {
+                let page = source_snapshots::load_source_snapshot(&ctx.pool, &key.0, &key.1)
+                    .await?
+                    .and_then(|row| {
+                        let sources = decode(&row.payload).ok()?;
+                        Some((
+                            row.fetched_at,
+                            sources.into_iter().map(|s| s.id.into()).collect(),
+                        ))
+                    });
+
+                pages.insert(key.clone(), page);
+            }
+
+            let Some((fetched_at, source_ids)) = &pages[&key] else {
+                continue;
+            };
+
+            // 快照早于草稿时 source 可能是之后新建的，无法判断
+            if *fetched_at < draft.updated_at || source_ids.contains(&draft.source_id) {
+                continue;
+            }
+
+            let fix = if ctx.fix {
+                Some(
+                    translation_drafts::delete_translation_draft(
+                        &ctx.pool,
+                        &draft.source_id,
+                        &draft.target_id,
+                    )
+                    .await
+                    .map(|_| ()),
+                )
+            } else {
+                None
+            };
+
+            findings.push(
+                Finding::new(
+                    CHECK,
+                    Severity::Warning,
+                    &draft.source_id,
+                    format!("草稿对应的 source 已不存在（文件 {}）", draft.file_id),
+                )
+                .with_fix(fix),
+            );
+        }
+
+        Ok(findings)
+    })
+}
+
+// 发布记录与 enriched 数据：本机发布过但项目显示未发布（可能被他人撤回），只报告
+fn check_publish_records(ctx: &CheckContext) -> CheckFuture<'_> {
+    Box::pin(async move {
+        const CHECK: &str = "publish_records";
+
+        let stored: HashMap<String, ResProjectEnriched> =
+            recent_projects::list_all_recent_snapshots(&ctx.pool)
+                .await?
+                .into_iter()
+                .filter_map(|(id, snapshot)| Some((id, serde_json::from_str(&snapshot).ok()?)))
+                .collect();
+
+        let mut findings = Vec::new();
+
+        for (proj_id, proj_name) in publish_records::list_published_proj_ids(&ctx.pool).await? {
+            let project = cached_proj_snapshot(&proj_id).or_else(|| stored.get(&proj_id).cloned());
+
+            let Some(project) = project else {
+                continue;
+            };
+
+            if project.is_published == Some(false) {
+                findings.push(Finding::new(
+                    CHECK,
+                    Severity::Warning,
+                    &proj_id,
+                    format!(
+                        "项目「{}」有本机发布记录，但当前显示为未发布",
+                        proj_name.unwrap_or(project.name)
+                    ),
+                ));
+            }
+        }
+
+        Ok(findings)
+    })
+}
+
+// 待执行写操作：目标项目在 PopRaKo 中已不存在的删除；离线或不可达时跳过
+fn check_pending_write_targets(ctx: &CheckContext) -> CheckFuture<'_> {
+    Box::pin(async move {
+        const CHECK: &str = "pending_write_targets";
+
+        let rows = pending_writes::list_pending_writes(&ctx.pool).await?;
+
+        let proj_ids: Vec<String> = rows
+            .iter()
+            .map(|row| row.proj_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        if proj_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        if config().offline_mode {
+            return Ok(vec![Finding::new(
+                CHECK,
+                Severity::Info,
+                "",
+                "离线模式下未检查待执行写操作",
+            )]);
+        }
+
+        let mut findings = Vec::new();
+
+        for proj_id in proj_ids {
+            match fetch_poprako_proj(&proj_id).await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    let fix = if ctx.fix {
+                        Some(
+                            pending_writes::delete_pending_writes_for_proj(&ctx.pool, &proj_id)
+                                .await
+                                .map(|_| ()),
+                        )
+                    } else {
+                        None
+                    };
+
+                    findings.push(
+                        Finding::new(
+                            CHECK,
+                            Severity::Error,
+                            &proj_id,
+                            "待执行写操作的目标项目已不存在",
+                        )
+                        .with_fix(fix),
+                    );
+                }
+                Err(err) if is_connectivity_error(&err) => {
+                    findings.push(Finding::new(
+                        CHECK,
+                        Severity::Info,
+                        "",
+                        format!("PopRaKo 不可达，未完成检查: {}", err),
+                    ));
+                    break;
+                }
+                Err(err) => {
+                    tracing::warn!(%proj_id, error = %err, "integrity.pending_write.lookup_failed");
+                }
+            }
+        }
+
+        Ok(findings)
+    })
+}
+
+// ========== 执行与报告 ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckSummary {
+    pub name: &'static str,
+    pub findings: usize,
+    // 检查本身执行失败
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntegrityReport {
+    pub fix: bool,
+    pub checks: Vec<CheckSummary>,
+    pub errors: Vec<Finding>,
+    pub warnings: Vec<Finding>,
+    pub infos: Vec<Finding>,
+}
+
+async fn run_checks(fix: bool, lightweight_only: bool) -> Result<IntegrityReport, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let ctx = CheckContext {
+        pool: storage.pool().clone(),
+        fix,
+    };
+
+    let mut report = IntegrityReport {
+        fix,
+        ..Default::default()
+    };
+
+    for check in CHECKS.iter().filter(|c| c.lightweight || !lightweight_only) {
+        let (count, error) = match (check.run)(&ctx).await {
+            Ok(findings) => {
+                let count = findings.len();
+
+                for finding in findings {
+                    match finding.severity {
+                        Severity::Error => report.errors.push(finding),
+                        Severity::Warning => report.warnings.push(finding),
+                        Severity::Info => report.infos.push(finding),
+                    }
+                }
+
+                (count, None)
+            }
+            Err(err) => {
+                tracing::warn!(check = check.name, error = %err, "integrity.check.failed");
+                (0, Some(err))
+            }
+        };
+
+        report.checks.push(CheckSummary {
+            name: check.name,
+            findings: count,
+            error,
+        });
+    }
+
+    Ok(report)
+}
+
+// 启动时的轻量检查（由 integrity_check_on_startup 开启），只记录日志
+pub(crate) async fn run_startup_checks() {
+    if !config().integrity_check_on_startup {
+        return;
+    }
+
+    match run_checks(false, true).await {
+        Ok(report) => tracing::info!(
+            errors = report.errors.len(),
+            warnings = report.warnings.len(),
+            infos = report.infos.len(),
+            "integrity.startup.ok"
+        ),
+        Err(err) => tracing::warn!(%err, "integrity.startup.failed"),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunIntegrityCheckReq {
+    #[serde(default)]
+    pub fix: bool,
+}
+
+#[tauri::command]
+pub async fn run_integrity_check(payload: RunIntegrityCheckReq) -> Result<IntegrityReport, String> {
+    tracing::info!(fix = payload.fix, "integrity.check.start");
+
+    let mut defer = WarnDefer::new("integrity.check");
+
+    let report = run_checks(payload.fix, false).await?;
+
+    tracing::info!(
+        fix = payload.fix,
+        errors = report.errors.len(),
+        warnings = report.warnings.len(),
+        infos = report.infos.len(),
+        "integrity.check.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::project::MoetranSource;
+
+    // 每个测试使用独立的内存库（单连接，否则每个连接各有一个内存库）
+    async fn context(fix: bool) -> CheckContext {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        source_snapshots::migrate_source_snapshots_table(&mut conn)
+            .await
+            .unwrap();
+        translation_drafts::migrate_translation_drafts_table(&mut conn)
+            .await
+            .unwrap();
+        drop(conn);
+
+        CheckContext { pool, fix }
+    }
+
+    fn snapshot(source_ids: &[&str]) -> Vec<u8> {
+        let sources: Vec<MoetranSource> = source_ids
+            .iter()
+            .map(|id| {
+                serde_json::from_value(json!({
+                    "id": id,
+                    "x": 0.5,
+                    "y": 0.5,
+                    "position_type": 1,
+                    "my_translation": null,
+                }))
+                .unwrap()
+            })
+            .collect();
+
+        rmp_serde::to_vec_named(&sources).unwrap()
+    }
+
+    #[test]
+    fn fix_outcome_is_recorded_on_the_finding() {
+        let finding = |fix| Finding::new("c", Severity::Info, "s", "m").with_fix(fix);
+
+        let reported = finding(None);
+        assert!(reported.fixable && !reported.fixed);
+
+        assert!(finding(Some(Ok(()))).fixed);
+
+        let failed = finding(Some(Err("locked".to_string())));
+        assert!(!failed.fixed);
+        assert_eq!(failed.fix_error.as_deref(), Some("locked"));
+    }
+
+    #[tokio::test]
+    async fn undecodable_snapshots_are_removed_only_when_fixing() {
+        let ctx = context(false).await;
+
+        source_snapshots::save_source_snapshot(&ctx.pool, "f1", "t1", &snapshot(&["s1"]), 1)
+            .await
+            .unwrap();
+        source_snapshots::save_source_snapshot(&ctx.pool, "f2", "t1", b"\xc1broken", 2)
+            .await
+            .unwrap();
+
+        let findings = check_source_snapshots(&ctx).await.unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].subject, "f2/t1");
+        assert!(!findings[0].fixed);
+
+        let fixing = CheckContext {
+            pool: ctx.pool.clone(),
+            fix: true,
+        };
+        assert!(check_source_snapshots(&fixing).await.unwrap()[0].fixed);
+
+        // 修复后再次检查没有问题
+        assert!(check_source_snapshots(&fixing).await.unwrap().is_empty());
+        assert!(
+            source_snapshots::load_source_snapshot(&ctx.pool, "f1", "t1")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn drafts_for_deleted_sources_are_found_from_newer_snapshots() {
+        let ctx = context(true).await;
+
+        for source_id in ["kept", "deleted", "newer"] {
+            translation_drafts::save_translation_draft(&ctx.pool, source_id, "t1", "f1", "草稿")
+                .await
+                .unwrap();
+        }
+
+        // 晚于快照保存的草稿可能对应之后新建的 source
+        sqlx::query("UPDATE translation_drafts SET updated_at = updated_at + 3600 WHERE source_id = 'newer'")
+            .execute(&ctx.pool)
+            .await
+            .unwrap();
+
+        source_snapshots::save_source_snapshot(&ctx.pool, "f1", "t1", &snapshot(&["kept"]), 1)
+            .await
+            .unwrap();
+
+        let findings = check_draft_sources(&ctx).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].subject, "deleted");
+        assert!(findings[0].fixed);
+
+        let remaining: Vec<String> = translation_drafts::list_all_drafts(&ctx.pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|draft| draft.source_id)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(!remaining.contains(&"deleted".to_string()));
+    }
+}
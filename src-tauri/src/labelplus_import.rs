@@ -0,0 +1,688 @@
+// 从 LabelPlus 翻译稿导入（translation_export 的反向）：按页名匹配 Moetran 文件，
+// 坐标与已有 source 相差不超过 COORD_EPSILON 时视为同一个标记，否则创建新的 source，再逐条提交译文。
+// 格式错误时整体拒绝并给出行号与列号；dry_run 时只返回计划，不调用任何写接口
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    contributions::fetch_file_sources,
+    defer::WarnDefer,
+    error::AppError,
+    ids::{FileId, ProjectId, SourceId, TargetId},
+    position_type::PositionType,
+    project::{
+        create_source, get_project_files, submit_translation, CreateSourceReq, GetProjectFilesReq,
+        MoetranSource, SubmitTranslationReq,
+    },
+    translation_export::export_text,
+};
+
+// 坐标为相对于图片宽高的比例（0 ~ 1），导出时保留 3 位小数
+const COORD_EPSILON: f64 = 0.002;
+
+const PAGE_PREFIX: &str = ">>>>>>>>[";
+const PAGE_SUFFIX: &str = "]<<<<<<<<";
+const LABEL_SEPARATOR: &str = "----------------[";
+
+#[derive(Debug, Clone)]
+struct ParsedLabel {
+    x: f64,
+    y: f64,
+    position_type: PositionType,
+    text: String,
+}
+
+#[derive(Debug, Clone)]
+struct ParsedPage {
+    name: String,
+    labels: Vec<ParsedLabel>,
+}
+
+#[derive(Debug)]
+struct ParseError {
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+impl From<ParseError> for AppError {
+    fn from(err: ParseError) -> Self {
+        AppError::InvalidInput(format!(
+            "LabelPlus 文件格式错误（第 {} 行第 {} 列）: {}",
+            err.line, err.column, err.message
+        ))
+    }
+}
+
+fn parse_error(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+    ParseError {
+        line,
+        column,
+        message: message.into(),
+    }
+}
+
+// 按字符计的列号（从 1 开始）
+fn column_of(line: &str, byte_offset: usize) -> usize {
+    line[..byte_offset].chars().count() + 1
+}
+
+// 分组名优先按名称对应位置类型，其余按组号（与导出一致：1 = 框内，2 = 框外）
+fn group_position(groups: &[String], group: usize) -> PositionType {
+    let outside = PositionType::Outside.labelplus_group();
+    let inside = PositionType::Inside.labelplus_group();
+
+    match groups.get(group - 1).map(String::as_str) {
+        name if name == outside => PositionType::Outside,
+        name if name == inside => PositionType::Inside,
+        _ if group == 2 => PositionType::Outside,
+        _ => PositionType::Inside,
+    }
+}
+
+// 解析 "----------------[序号]----------------[x,y,组号]"，返回坐标与组号
+fn parse_label_header(
+    line: &str,
+    line_no: usize,
+    group_count: usize,
+) -> Result<(f64, f64, usize), ParseError> {
+    let rest = &line[LABEL_SEPARATOR.len()..];
+    let offset = LABEL_SEPARATOR.len();
+
+    let Some(close) = rest.find(']') else {
+        return Err(parse_error(
+            line_no,
+            column_of(line, line.len()),
+            "标记序号缺少 ']'",
+        ));
+    };
+
+    if rest[..close].trim().parse::<usize>().is_err() {
+        return Err(parse_error(
+            line_no,
+            column_of(line, offset),
+            "标记序号不是整数",
+        ));
+    }
+
+    let offset = offset + close + 1;
+    let Some(meta) = line[offset..].strip_prefix(LABEL_SEPARATOR) else {
+        return Err(parse_error(
+            line_no,
+            column_of(line, offset),
+            "标记序号之后应为 \"----------------[x,y,组号]\"",
+        ));
+    };
+
+    let offset = offset + LABEL_SEPARATOR.len();
+    let Some(meta) = meta.strip_suffix(']') else {
+        return Err(parse_error(
+            line_no,
+            column_of(line, line.len()),
+            "坐标缺少 ']'",
+        ));
+    };
+
+    let parts: Vec<&str> = meta.split(',').collect();
+    if parts.len() != 3 {
+        return Err(parse_error(
+            line_no,
+            column_of(line, offset),
+            "坐标应为 \"x,y,组号\" 三项",
+        ));
+    }
+
+    let mut part_offset = offset;
+    let mut coords = [0.0; 2];
+
+    for (axis, part) in parts[..2].iter().enumerate() {
+        match part.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() && (0.0..=1.0).contains(&value) => coords[axis] = value,
+            _ => {
+                return Err(parse_error(
+                    line_no,
+                    column_of(line, part_offset),
+                    format!("坐标 \"{}\" 应为 0 到 1 之间的数", part.trim()),
+                ))
+            }
+        }
+        part_offset += part.len() + 1;
+    }
+
+    let group = match parts[2].trim().parse::<usize>() {
+        Ok(group) if (1..=group_count).contains(&group) => group,
+        _ => {
+            return Err(parse_error(
+                line_no,
+                column_of(line, part_offset),
+                format!(
+                    "组号 \"{}\" 应为 1 到 {} 之间的整数",
+                    parts[2].trim(),
+                    group_count
+                ),
+            ))
+        }
+    };
+
+    Ok((coords[0], coords[1], group))
+}
+
+// 去掉标记译文末尾的空行（导出时每个标记后有一个空行）
+fn finish_label(label: &mut ParsedLabel, lines: &mut Vec<&str>) {
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    label.text = lines.join("\n");
+    lines.clear();
+}
+
+fn parse_labelplus(content: &str) -> Result<Vec<ParsedPage>, ParseError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut lines = content
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    // 文件头：版本行 "a,b"，然后是以 "-" 包围的分组名，其后直到第一个页头之前为备注
+    let (line_no, version) = lines.next().unwrap_or((1, ""));
+    let valid_version = version.split_once(',').is_some_and(|(major, minor)| {
+        major.trim().parse::<u32>().is_ok() && minor.trim().parse::<u32>().is_ok()
+    });
+    if !valid_version {
+        return Err(parse_error(line_no, 1, "第一行应为版本号（如 \"1,0\"）"));
+    }
+
+    let mut last_line = line_no;
+
+    match lines.next() {
+        Some((_, "-")) => last_line += 1,
+        Some((line_no, _)) => return Err(parse_error(line_no, 1, "分组列表应以 \"-\" 开始")),
+        None => return Err(parse_error(last_line + 1, 1, "缺少分组列表")),
+    }
+
+    let mut groups = Vec::new();
+    loop {
+        match lines.next() {
+            Some((line_no, "-")) => {
+                last_line = line_no;
+                break;
+            }
+            Some((line_no, group)) => {
+                last_line = line_no;
+                if !group.trim().is_empty() {
+                    groups.push(group.trim().to_string());
+                }
+            }
+            None => return Err(parse_error(last_line + 1, 1, "分组列表缺少结尾的 \"-\"")),
+        }
+    }
+
+    if groups.is_empty() {
+        return Err(parse_error(last_line, 1, "分组列表为空"));
+    }
+
+    let mut pages: Vec<ParsedPage> = Vec::new();
+    let mut page_lines: HashMap<String, usize> = HashMap::new();
+    let mut label: Option<ParsedLabel> = None;
+    let mut text: Vec<&str> = Vec::new();
+
+    for (line_no, line) in lines {
+        if let Some(rest) = line.strip_prefix(PAGE_PREFIX) {
+            let Some(name) = rest.trim_end().strip_suffix(PAGE_SUFFIX) else {
+                return Err(parse_error(
+                    line_no,
+                    column_of(line, line.len()),
+                    "页头应以 \"]<<<<<<<<\" 结尾",
+                ));
+            };
+
+            if name.trim().is_empty() {
+                return Err(parse_error(
+                    line_no,
+                    column_of(line, PAGE_PREFIX.len()),
+                    "页名为空",
+                ));
+            }
+
+            if let Some(first) = page_lines.insert(name.to_string(), line_no) {
+                return Err(parse_error(
+                    line_no,
+                    column_of(line, PAGE_PREFIX.len()),
+                    format!("页 \"{}\" 与第 {} 行重复", name, first),
+                ));
+            }
+
+            if let (Some(mut done), Some(page)) = (label.take(), pages.last_mut()) {
+                finish_label(&mut done, &mut text);
+                page.labels.push(done);
+            }
+
+            pages.push(ParsedPage {
+                name: name.to_string(),
+                labels: Vec::new(),
+            });
+            continue;
+        }
+
+        if line.starts_with(LABEL_SEPARATOR) {
+            let Some(page) = pages.last_mut() else {
+                return Err(parse_error(line_no, 1, "标记出现在第一个页头之前"));
+            };
+
+            let (x, y, group) = parse_label_header(line.trim_end(), line_no, groups.len())?;
+
+            if let Some(mut done) = label.take() {
+                finish_label(&mut done, &mut text);
+                page.labels.push(done);
+            }
+
+            label = Some(ParsedLabel {
+                x,
+                y,
+                position_type: group_position(&groups, group),
+                text: String::new(),
+            });
+            continue;
+        }
+
+        // 页头与第一个标记之间的空行、文件头备注忽略；标记之后的行都是译文
+        if label.is_some() {
+            text.push(line);
+        } else if !pages.is_empty() && !line.trim().is_empty() {
+            return Err(parse_error(line_no, 1, "页头之后应为标记行"));
+        }
+    }
+
+    if let (Some(mut done), Some(page)) = (label.take(), pages.last_mut()) {
+        finish_label(&mut done, &mut text);
+        page.labels.push(done);
+    }
+
+    Ok(pages)
+}
+
+// 页名先按原样匹配，其次忽略大小写与扩展名（Moetran 可能转换了图片格式）且唯一时匹配
+fn match_file<'a>(name: &str, files: &'a [(FileId, String)]) -> Option<&'a FileId> {
+    if let Some((id, _)) = files.iter().find(|(_, file)| file == name) {
+        return Some(id);
+    }
+
+    let stem = |name: &str| {
+        let name = name.trim();
+        name.rsplit_once('.')
+            .map_or(name, |(stem, _)| stem)
+            .to_lowercase()
+    };
+
+    let wanted = stem(name);
+    let mut matches = files.iter().filter(|(_, file)| stem(file) == wanted);
+
+    match (matches.next(), matches.next()) {
+        (Some((id, _)), None) => Some(id),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportLabelplusReq {
+    pub project_id: ProjectId,
+    pub target_id: TargetId,
+    pub file_path: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LabelImportFailure {
+    pub page: String,
+    // 标记在页内的序号（从 1 开始）
+    pub label_index: usize,
+    pub error: AppError,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LabelplusImportReport {
+    pub dry_run: bool,
+    pub pages_matched: usize,
+    // 需要新建的 source 数（坐标与已有 source 都不接近的标记）
+    pub labels_to_create: usize,
+    // 需要提交的译文数（非空且与已有译文不同）
+    pub translations_to_submit: usize,
+    // 没有同名文件的页（页名）
+    pub unmatched_pages: Vec<String>,
+    pub sources_created: usize,
+    pub translations_submitted: usize,
+    pub failed: Vec<LabelImportFailure>,
+    // 登录过期或进入离线模式后中止，其余标记未处理
+    pub aborted: bool,
+}
+
+enum LabelAction {
+    // 已有同位置的 source；None 表示译文相同，无需提交
+    Existing(SourceId, Option<String>),
+    Create(String),
+}
+
+struct PagePlan {
+    name: String,
+    file_id: FileId,
+    labels: Vec<(usize, ParsedLabel, LabelAction)>,
+}
+
+// 每个已有 source 最多对应一个标记
+fn plan_page(page: ParsedPage, file_id: FileId, existing: &[MoetranSource]) -> PagePlan {
+    let mut taken = vec![false; existing.len()];
+    let mut labels = Vec::with_capacity(page.labels.len());
+
+    for (index, label) in page.labels.into_iter().enumerate() {
+        let found = existing.iter().enumerate().position(|(i, source)| {
+            !taken[i]
+                && (source.x - label.x).abs() <= COORD_EPSILON
+                && (source.y - label.y).abs() <= COORD_EPSILON
+        });
+
+        let text = (!label.text.trim().is_empty()).then(|| label.text.clone());
+
+        let action = match found {
+            Some(i) => {
+                taken[i] = true;
+                let source = &existing[i];
+                let text = text.filter(|text| export_text(source).replace("\r\n", "\n") != *text);
+                LabelAction::Existing(source.id.clone(), text)
+            }
+            None => LabelAction::Create(text.unwrap_or_default()),
+        };
+
+        labels.push((index + 1, label, action));
+    }
+
+    PagePlan {
+        name: page.name,
+        file_id,
+        labels,
+    }
+}
+
+async fn submit_label_text(
+    source_id: SourceId,
+    target_id: &TargetId,
+    content: String,
+) -> Result<(), AppError> {
+    submit_translation(SubmitTranslationReq {
+        source_id,
+        target_id: target_id.clone(),
+        content,
+        raw: false,
+        team_id: None,
+        allow_empty: false,
+    })
+    .await
+    .map(|_| ())
+}
+
+// 逐条执行（不并发），出错的标记记入 failed 后继续；登录过期 / 离线时中止
+async fn apply_plans(
+    plans: Vec<PagePlan>,
+    target_id: &TargetId,
+    report: &mut LabelplusImportReport,
+) {
+    for plan in plans {
+        for (label_index, label, action) in plan.labels {
+            let result = match action {
+                LabelAction::Existing(_, None) => continue,
+                LabelAction::Existing(source_id, Some(text)) => {
+                    submit_label_text(source_id, target_id, text)
+                        .await
+                        .map(|()| report.translations_submitted += 1)
+                }
+                LabelAction::Create(text) => {
+                    let created = create_source(CreateSourceReq {
+                        file_id: plan.file_id.clone(),
+                        x: label.x,
+                        y: label.y,
+                        position_type: label.position_type,
+                        width: None,
+                        height: None,
+                    })
+                    .await;
+
+                    match created {
+                        Ok(_) if text.is_empty() => {
+                            report.sources_created += 1;
+                            Ok(())
+                        }
+                        Ok(source) => {
+                            report.sources_created += 1;
+                            submit_label_text(source.id, target_id, text)
+                                .await
+                                .map(|()| report.translations_submitted += 1)
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+            };
+
+            if let Err(err) = result {
+                tracing::warn!(page = %plan.name, label_index, error = %err, "labelplus.import.label_failed");
+
+                let halt = err.is_auth_expired() || err.is_offline();
+
+                report.failed.push(LabelImportFailure {
+                    page: plan.name.clone(),
+                    label_index,
+                    error: err,
+                });
+
+                if halt {
+                    report.aborted = true;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// 导入 LabelPlus 翻译稿；dry_run 时只读取 Moetran 上的文件与 source，返回计划
+#[tauri::command]
+pub async fn import_labelplus(
+    payload: ImportLabelplusReq,
+) -> Result<LabelplusImportReport, AppError> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        dry_run = payload.dry_run,
+        "labelplus.import.start"
+    );
+
+    let mut defer = WarnDefer::new("labelplus.import");
+
+    let content = tokio::fs::read_to_string(&payload.file_path)
+        .await
+        .map_err(|err| AppError::InvalidInput(format!("读取 LabelPlus 文件失败: {}", err)))?;
+
+    let pages = parse_labelplus(&content)?;
+
+    let files: Vec<(FileId, String)> = get_project_files(GetProjectFilesReq {
+        project_id: payload.project_id.clone(),
+        target_id: Some(payload.target_id.clone()),
+        sort: None,
+    })
+    .await?
+    .into_iter()
+    .map(|file| (file.id, file.name))
+    .collect();
+
+    let mut report = LabelplusImportReport {
+        dry_run: payload.dry_run,
+        ..Default::default()
+    };
+    let mut plans = Vec::new();
+
+    for page in pages {
+        let Some(file_id) = match_file(&page.name, &files).cloned() else {
+            report.unmatched_pages.push(page.name);
+            continue;
+        };
+
+        let existing = fetch_file_sources(file_id.to_string(), payload.target_id.to_string())
+            .await
+            .map_err(|err| AppError::from(err).context(format!("读取页 \"{}\" 失败", page.name)))?;
+
+        let plan = plan_page(page, file_id, &existing);
+
+        for (_, _, action) in &plan.labels {
+            match action {
+                LabelAction::Existing(_, text) => {
+                    report.translations_to_submit += usize::from(text.is_some());
+                }
+                LabelAction::Create(text) => {
+                    report.labels_to_create += 1;
+                    report.translations_to_submit += usize::from(!text.is_empty());
+                }
+            }
+        }
+
+        report.pages_matched += 1;
+        plans.push(plan);
+    }
+
+    if !payload.dry_run {
+        apply_plans(plans, &payload.target_id, &mut report).await;
+    }
+
+    tracing::info!(
+        pages_matched = report.pages_matched,
+        unmatched = report.unmatched_pages.len(),
+        labels_to_create = report.labels_to_create,
+        translations_to_submit = report.translations_to_submit,
+        sources_created = report.sources_created,
+        translations_submitted = report.translations_submitted,
+        failed = report.failed.len(),
+        aborted = report.aborted,
+        "labelplus.import.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 版本行、两个分组，页头从第 7 行开始
+    const HEADER: &str = "1,0\n-\n框内\n框外\n-\n\n";
+
+    fn error_at(content: &str) -> (usize, usize, String) {
+        match parse_labelplus(content) {
+            Ok(pages) => panic!("expected a parse error, got {:?}", pages),
+            Err(err) => (err.line, err.column, err.message),
+        }
+    }
+
+    #[test]
+    fn well_formed_file_is_parsed() {
+        let content = format!(
+            "{}>>>>>>>>[001.png]<<<<<<<<\r\n\
+             ----------------[1]----------------[0.123,0.456,1]\r\n\
+             第一行\r\n第二行\r\n\r\n\
+             ----------------[2]----------------[0.5,0.5,2]\r\n\r\n",
+            HEADER
+        );
+
+        let pages = parse_labelplus(&content).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].name, "001.png");
+
+        let labels = &pages[0].labels;
+        assert_eq!(labels.len(), 2);
+        assert_eq!((labels[0].x, labels[0].y), (0.123, 0.456));
+        assert_eq!(labels[0].text, "第一行\n第二行");
+        assert!(matches!(labels[0].position_type, PositionType::Inside));
+        assert!(matches!(labels[1].position_type, PositionType::Outside));
+        assert_eq!(labels[1].text, "");
+    }
+
+    #[test]
+    fn malformed_headers_report_line_and_column() {
+        let cases = [
+            ("", 1, 1, "版本号"),
+            ("1,0\n框内\n", 2, 1, "应以 \"-\" 开始"),
+            ("1,0", 2, 1, "缺少分组列表"),
+            ("1,0\n-\n框内", 4, 1, "缺少结尾"),
+            ("1,0\n-\n-\n", 3, 1, "分组列表为空"),
+        ];
+
+        for (content, line, column, message) in cases {
+            let (got_line, got_column, got_message) = error_at(content);
+            assert_eq!((got_line, got_column), (line, column), "{:?}", content);
+            assert!(got_message.contains(message), "{}", got_message);
+        }
+    }
+
+    #[test]
+    fn malformed_pages_and_labels_report_line_and_column() {
+        let page = ">>>>>>>>[001.png]<<<<<<<<";
+        let label = |meta: &str| format!("{}\n----------------{}", page, meta);
+
+        let cases = [
+            // 页头
+            (">>>>>>>>[001.png]<<<<".to_string(), 7, 22, "页头应以"),
+            // 列号按字符计，不按字节
+            (">>>>>>>>[第一页.png]".to_string(), 7, 18, "页头应以"),
+            (">>>>>>>>[ ]<<<<<<<<".to_string(), 7, 10, "页名为空"),
+            (format!("{}\n{}", page, page), 8, 10, "与第 7 行重复"),
+            (
+                "----------------[1]----------------[0.1,0.2,1]".to_string(),
+                7,
+                1,
+                "第一个页头之前",
+            ),
+            (format!("{}\n\n多余的行", page), 9, 1, "应为标记行"),
+            // 标记行
+            (label("[1"), 8, 19, "序号缺少 ']'"),
+            (
+                label("[x]----------------[0.1,0.2,1]"),
+                8,
+                18,
+                "序号不是整数",
+            ),
+            (label("[1]xx"), 8, 20, "标记序号之后"),
+            (
+                label("[1]----------------[0.1,0.2,1"),
+                8,
+                46,
+                "坐标缺少 ']'",
+            ),
+            (label("[1]----------------[0.1,0.2]"), 8, 37, "三项"),
+            (label("[1]----------------[abc,0.2,1]"), 8, 37, "\"abc\""),
+            (label("[1]----------------[0.1,1.5,1]"), 8, 41, "\"1.5\""),
+            (label("[1]----------------[0.1,0.2,3]"), 8, 45, "1 到 2"),
+        ];
+
+        for (body, line, column, message) in cases {
+            let content = format!("{}{}\n", HEADER, body);
+
+            let (got_line, got_column, got_message) = error_at(&content);
+            assert_eq!((got_line, got_column), (line, column), "{:?}", body);
+            assert!(got_message.contains(message), "{}", got_message);
+
+            // CRLF 与 BOM 不影响行列号
+            let windows = format!("\u{feff}{}", content.replace('\n', "\r\n"));
+            let (crlf_line, crlf_column, _) = error_at(&windows);
+            assert_eq!((crlf_line, crlf_column), (line, column), "{:?}", body);
+        }
+    }
+
+    #[test]
+    fn parse_errors_become_invalid_input_with_position() {
+        let err = AppError::from(parse_labelplus("1,0\n-\n框内").err().unwrap());
+
+        assert_eq!(err.kind(), "InvalidInput");
+        assert!(err.to_string().contains("第 4 行第 1 列"), "{}", err);
+    }
+}
@@ -0,0 +1,400 @@
+// 旧版本磁盘缓存迁移：早期版本把图片存放在 data/cache/{project}/page_{n}.{ext}，
+// 元数据写在同目录的 metadata.json 中。启动时一次性把它们迁入当前的 images/ 目录与 cached_projects 表，
+// 无法识别或损坏的条目只跳过并报告，绝不删除
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::{
+    clock::unix_now,
+    defer::WarnDefer,
+    ids::FileId,
+    image_cache::{adopt_cache_files, get_cache_dir, known_extension, AdoptedFile},
+    storage::{
+        cache_metadata::{upsert_cached_project, CachedProjectMetadata},
+        settings::{get_setting, save_setting},
+        LOCAL_STORAGE,
+    },
+    DATA_DIR,
+};
+
+const LEGACY_CACHE_DIR: &str = "cache";
+const LEGACY_METADATA_FILE: &str = "metadata.json";
+const LEGACY_PAGE_PREFIX: &str = "page_";
+// 设置表中的迁移标记，值为完成时的时间戳
+const MIGRATED_MARKER_KEY: &str = "legacy_cache_migrated_at";
+
+// 旧版 metadata.json：字段随版本变化，全部按可选处理
+#[derive(Debug, Default, Deserialize)]
+struct LegacyProjectMeta {
+    #[serde(default, alias = "name")]
+    project_name: Option<String>,
+    #[serde(default)]
+    cached_at: Option<i64>,
+    #[serde(default)]
+    files: Vec<LegacyFileMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyFileMeta {
+    #[serde(alias = "index")]
+    page: u32,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LegacyProjectStatus {
+    Migrated,
+    // dry run：将会迁移
+    WouldMigrate,
+    Skipped { reason: String },
+    Failed { message: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyProjectOutcome {
+    pub project_id: String,
+    #[serde(flatten)]
+    pub status: LegacyProjectStatus,
+    pub file_count: usize,
+    pub total_size_bytes: u64,
+    // 无法识别、留在旧目录中的文件
+    pub unrecognized: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyMigrationReport {
+    pub legacy_dir: String,
+    pub dry_run: bool,
+    // 之前已完成迁移（本次未扫描）
+    pub already_migrated: bool,
+    pub projects: Vec<LegacyProjectOutcome>,
+}
+
+// 一个旧项目目录的扫描结果
+struct LegacyProject {
+    project_id: String,
+    dir: PathBuf,
+    meta: LegacyProjectMeta,
+    // (页码, 文件名, 扩展名, 大小)，按页码排序
+    pages: Vec<(u32, String, &'static str, u64)>,
+    unrecognized: Vec<String>,
+}
+
+fn legacy_root() -> PathBuf {
+    DATA_DIR.join(LEGACY_CACHE_DIR)
+}
+
+// page_{n}.{ext} -> (n, ext)
+fn parse_page_name(file_name: &str) -> Option<(u32, &'static str)> {
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    let page = stem.strip_prefix(LEGACY_PAGE_PREFIX)?.parse().ok()?;
+
+    Some((page, known_extension(ext)?))
+}
+
+async fn scan_project(project_id: String, dir: PathBuf) -> Result<LegacyProject, String> {
+    let meta = match fs::read(dir.join(LEGACY_METADATA_FILE)).await {
+        Ok(data) => serde_json::from_slice::<LegacyProjectMeta>(&data)
+            .map_err(|err| format!("metadata.json 无法解析: {}", err))?,
+        // 没有元数据时仍可按文件名迁移
+        Err(_) => LegacyProjectMeta::default(),
+    };
+
+    let mut entries = fs::read_dir(&dir)
+        .await
+        .map_err(|err| format!("读取旧缓存目录失败: {}", err))?;
+
+    let mut pages = Vec::new();
+    let mut unrecognized = Vec::new();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| format!("遍历旧缓存目录失败: {}", err))?
+    {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name == LEGACY_METADATA_FILE {
+            continue;
+        }
+
+        let size = match entry.metadata().await {
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => {
+                unrecognized.push(file_name);
+                continue;
+            }
+        };
+
+        match parse_page_name(&file_name) {
+            Some((page, ext)) if size > 0 => pages.push((page, file_name, ext, size)),
+            _ => unrecognized.push(file_name),
+        }
+    }
+
+    pages.sort_by_key(|(page, ..)| *page);
+    unrecognized.sort();
+
+    Ok(LegacyProject {
+        project_id,
+        dir,
+        meta,
+        pages,
+        unrecognized,
+    })
+}
+
+// 把页面文件改名移入新目录；中途失败时把已移动的文件移回
+async fn move_pages(project: &LegacyProject, target: &Path) -> Result<Vec<AdoptedFile>, String> {
+    let ids: HashMap<u32, &str> = project
+        .meta
+        .files
+        .iter()
+        .filter_map(|file| file.id.as_deref().map(|id| (file.page, id)))
+        .collect();
+
+    fs::create_dir_all(target)
+        .await
+        .map_err(|err| format!("创建缓存目录失败: {}", err))?;
+
+    let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut adopted = Vec::new();
+
+    for (index, (page, file_name, ext, _)) in project.pages.iter().enumerate() {
+        // 与当前下载的命名一致：有文件 id 时按 id，否则按页面顺序
+        let id = ids.get(page).map(|id| id.to_string());
+        let stem = id.clone().unwrap_or_else(|| index.to_string());
+        let new_name = format!("{}.{}", stem, ext);
+
+        let from = project.dir.join(file_name);
+        let to = target.join(&new_name);
+
+        if let Err(err) = fs::rename(&from, &to).await {
+            for (from, to) in moved.iter().rev() {
+                let _ = fs::rename(to, from).await;
+            }
+            let _ = fs::remove_dir(target).await;
+
+            return Err(format!("移动 {} 失败: {}", file_name, err));
+        }
+
+        moved.push((from, to));
+        adopted.push(AdoptedFile {
+            id: id.map(FileId::from),
+            file_name: new_name,
+        });
+    }
+
+    Ok(adopted)
+}
+
+async fn migrate_project(project: &LegacyProject, dry_run: bool) -> LegacyProjectStatus {
+    if project.pages.is_empty() {
+        return LegacyProjectStatus::Skipped {
+            reason: "没有可识别的图片".to_string(),
+        };
+    }
+
+    let target = get_cache_dir(&project.project_id);
+
+    if target.exists() {
+        return LegacyProjectStatus::Skipped {
+            reason: "已存在新格式缓存".to_string(),
+        };
+    }
+
+    if dry_run {
+        return LegacyProjectStatus::WouldMigrate;
+    }
+
+    let adopted = match move_pages(project, &target).await {
+        Ok(adopted) => adopted,
+        Err(message) => return LegacyProjectStatus::Failed { message },
+    };
+
+    if let Err(message) = adopt_cache_files(&target, &adopted).await {
+        return LegacyProjectStatus::Failed { message };
+    }
+
+    let cached_at = project.meta.cached_at.unwrap_or_else(unix_now);
+
+    let metadata = CachedProjectMetadata {
+        project_id: project.project_id.clone(),
+        project_name: project
+            .meta
+            .project_name
+            .clone()
+            .unwrap_or_else(|| project.project_id.clone()),
+        status: "completed".to_string(),
+        file_count: adopted.len() as i64,
+        total_size_bytes: project.pages.iter().map(|(.., size)| *size as i64).sum(),
+        cached_at,
+        remote_file_count: None,
+        missing_count: None,
+        remote_checked_at: None,
+        remote_deleted: false,
+    };
+
+    if let Some(storage) = LOCAL_STORAGE.get() {
+        if let Err(message) = upsert_cached_project(storage.pool(), &metadata).await {
+            return LegacyProjectStatus::Failed { message };
+        }
+    }
+
+    // 只剩元数据或无法识别的文件时保留旧目录
+    let _ = fs::remove_dir(&project.dir).await;
+
+    LegacyProjectStatus::Migrated
+}
+
+// 扫描旧缓存目录并迁移（dry_run 时只报告）
+async fn run_migration(dry_run: bool) -> Result<Vec<LegacyProjectOutcome>, String> {
+    let root = legacy_root();
+
+    if !root.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries = fs::read_dir(&root)
+        .await
+        .map_err(|err| format!("读取旧缓存目录失败: {}", err))?;
+
+    let mut dirs = Vec::new();
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| format!("遍历旧缓存目录失败: {}", err))?
+    {
+        if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+            dirs.push((
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            ));
+        }
+    }
+
+    dirs.sort();
+
+    let total = dirs.len();
+    let mut outcomes = Vec::with_capacity(total);
+
+    for (n, (project_id, dir)) in dirs.into_iter().enumerate() {
+        let outcome = match scan_project(project_id.clone(), dir).await {
+            Ok(project) => LegacyProjectOutcome {
+                status: migrate_project(&project, dry_run).await,
+                file_count: project.pages.len(),
+                total_size_bytes: project.pages.iter().map(|(.., size)| *size).sum(),
+                unrecognized: project.unrecognized,
+                project_id,
+            },
+            Err(reason) => LegacyProjectOutcome {
+                project_id,
+                status: LegacyProjectStatus::Skipped { reason },
+                file_count: 0,
+                total_size_bytes: 0,
+                unrecognized: vec![],
+            },
+        };
+
+        tracing::info!(
+            progress = format!("{}/{}", n + 1, total),
+            project_id = %outcome.project_id,
+            status = ?outcome.status,
+            files = outcome.file_count,
+            unrecognized = outcome.unrecognized.len(),
+            dry_run,
+            "legacy_cache.project"
+        );
+
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+async fn is_migrated() -> Result<bool, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    Ok(get_setting(storage.pool(), MIGRATED_MARKER_KEY)
+        .await?
+        .is_some())
+}
+
+async fn mark_migrated() -> Result<(), String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    save_setting(storage.pool(), MIGRATED_MARKER_KEY, &unix_now().to_string()).await
+}
+
+async fn migrate(dry_run: bool) -> Result<LegacyMigrationReport, String> {
+    let legacy_dir = legacy_root().to_string_lossy().to_string();
+
+    if is_migrated().await? {
+        return Ok(LegacyMigrationReport {
+            legacy_dir,
+            dry_run,
+            already_migrated: true,
+            projects: vec![],
+        });
+    }
+
+    let projects = run_migration(dry_run).await?;
+
+    // 失败的项目保持原样，下次启动时重试
+    let failed = projects
+        .iter()
+        .any(|p| matches!(p.status, LegacyProjectStatus::Failed { .. }));
+
+    if !dry_run && !failed {
+        mark_migrated().await?;
+    }
+
+    Ok(LegacyMigrationReport {
+        legacy_dir,
+        dry_run,
+        already_migrated: false,
+        projects,
+    })
+}
+
+// 启动时调用（存储初始化之后）
+pub(crate) async fn migrate_on_startup() {
+    match migrate(false).await {
+        Ok(report) if !report.already_migrated => {
+            tracing::info!(projects = report.projects.len(), "legacy_cache.migrate.ok")
+        }
+        Ok(_) => {}
+        Err(err) => tracing::warn!(%err, "legacy_cache.migrate.failed"),
+    }
+}
+
+// 手动迁移旧版缓存；dry_run 为 true 时只报告将会执行的操作
+#[tauri::command]
+pub async fn migrate_legacy_cache(dry_run: bool) -> Result<LegacyMigrationReport, String> {
+    tracing::info!(dry_run, "legacy_cache.migrate.start");
+
+    let mut defer = WarnDefer::new("legacy_cache.migrate");
+
+    let report = migrate(dry_run).await?;
+
+    tracing::info!(
+        dry_run,
+        already_migrated = report.already_migrated,
+        projects = report.projects.len(),
+        "legacy_cache.migrate.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
@@ -1,42 +1,99 @@
+mod actions; // 命令面板的操作目录与分派
 pub mod auth;
+mod auth_expiry; // 登录过期（401）时清除 token 并通知前端重新登录
+mod bootstrap; // 一次完成登录、PopRaKo 同步与汉化组拉取
+mod cache_transfer; // 离线活动用的图片缓存打包导出与导入
+mod clock; // 当前时间（Unix 秒）
+mod config; // 应用配置解析（环境变量 > 设置表 > 默认值）
+mod connectivity; // 后端连通性状态
+mod contributions; // 项目贡献统计与汉化名单
+mod data_dir; // 数据目录的解析与切换（下次启动生效）
+mod deadline; // 项目各阶段的截止时间
 mod defer;
+mod demo; // 演示模式：进程内的模拟数据，不访问真实后端
+mod digest; // 汉化组“上次查看以来的变化”摘要
+mod disk_space; // 下载前的磁盘空间检查
+mod draft; // 翻译草稿自动保存与恢复
+mod dto_check; // 列表响应的严格 DTO 校验（调试用）
+mod empty_translations; // 项目中空白译文的扫描
+mod error; // 命令与 HTTP 层共用的结构化错误类型
+mod events; // 前端事件定义与发送（含 TS 绑定生成）
+mod export; // 给排版用的 LabelPlus 翻译稿导出
+mod file_activity; // 我在各页面上的最近活动（最近编辑的页面、文件列表排序）
+mod flexible_list; // PopRaKo 列表响应的宽松解析（裸数组或对象包裹）
 mod http;
+pub mod ids; // 各类标识符的 newtype（项目、文件、成员等 id 不能混用）
 mod image_cache; // 图片缓存管理
+mod image_protocol; // moeimg:// 协议：以 URL 直接加载缓存图片
+mod impact_check; // 删除前的关联数据影响检查
+mod instance_lock; // 单实例保护（数据目录锁与聚焦转发）
+mod integrity; // 本地数据完整性检查与修复
+mod labelplus_import; // LabelPlus 翻译稿导入（按页名匹配、按坐标去重）
+mod legacy_cache; // 旧版本磁盘缓存迁移
 mod member; // 成员搜索等相关
+mod member_audit; // 项目成员与实际贡献者的核对
+mod mention; // 文本中 @成员 提及的解析
+mod mutation; // 变更类命令的前后值返回包装
+mod name_guard; // 创建项目集 / 项目前的重名检查
+mod name_match; // 按名称富化时的候选打分（规范化、匹配等级与自动匹配阈值）
+mod natsort; // 文件名自然排序（上传 / 缓存 / 导出共用）
+mod normalize; // 译文内容的清理与标点规范化
 mod notify; // 更新检查相关
+mod ordering; // 阅读方向与 source 排序
+mod page_approval; // 校对“整页通过”批量操作
+mod pagination; // PopRaKo 列表分页
+mod poprako_health; // PopRaKo 服务端健康状态（状态徽章与变化事件）
+mod position_type; // source 位置类型（框内 / 框外）
+mod preferences_profile; // 编辑器偏好配置文件的导入导出
 mod project; // 项目与项目集相关
+mod project_cache; // 项目列表的离线缓存
+mod project_history; // 项目状态历史快照与变化比较
+mod project_notes; // 项目备注（本地记录，含 @成员 提及）
+mod projset_index; // 项目集内序号的缺号 / 重复检查与自动编号
+mod publish; // 带完成度检查的批量发布
+mod rate_limit; // 批量 / 后台请求按后端共用的限速器
+mod reading_view; // 给组外试读者的阅读版 HTML 导出（图片叠加译文、可加水印）
+mod recent; // 最近打开的项目
+mod redraw_estimate; // 按框外 source 的数量与聚集程度估算修图工作量
+mod request_budget; // 组合命令的整体时限（子请求超时不超过剩余时限）
 mod result_ex;
+mod retry; // 失败写操作的一键重试
+mod session; // 会话身份（Moetran / PopRaKo 账号）一致性检查
+mod source_batch; // 页面 source 批量删除
+mod source_overlay; // 页面 source 的几何信息与按 target 的翻译覆盖层
+mod source_snapshot; // 页面 source 快照（冷启动先显示上次数据）
+mod status_labels; // 汉化组自定义的阶段状态标签
 mod storage; // 本地存储与数据目录管理
 mod team; // 汉化组相关
+#[cfg(test)]
+mod test_support; // 测试用的 mock 后端（本地 HTTP 服务）
+mod text_stats; // 项目文字量统计（按字数结算稿费）
 mod token; // Token 缓存与存取
+mod token_probe; // PopRaKo 创建请求前的 Moetran token 有效性检查
+mod translation_export; // 项目翻译导出（LabelPlus，可取消、可从中断处继续）
+mod translation_verify; // 提交后在后台确认翻译确实保存（丢失时提示重新提交）
+mod ui_session; // 界面会话状态（上次的汉化组与页面）
+mod url_refresh; // Moetran 签名 url 过期后的刷新
+mod usage; // API 使用量统计
 mod user; // 用户与登录相关
+mod validation; // PopRaKo 422 字段错误与命令预校验的统一结构
+mod volume_manifest; // 按卷清单批量创建项目集与项目（可续跑）
+mod write_queue; // PopRaKo 写操作离线重试队列
 
-use std::{path::PathBuf, str::FromStr, sync::LazyLock};
+use std::{path::PathBuf, sync::LazyLock};
 
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
 // 直接导入模块便于 generate_handler 使用路径调用，不强制要求 pub 暴露全部
 
-const DATA_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
-    dotenvy::dotenv().expect("Failed to load .env file");
-
-    let app_dir = std::env::var("APP_DIR").unwrap_or_else(|_| "./".to_string());
-
-    let mut path = PathBuf::from_str(&app_dir).expect("Invalid APP_DIR path");
-
-    path.push("data");
-
-    if !path.exists() {
-        std::fs::create_dir_all(&path).expect("Failed to create data directory");
-    }
-
-    path
-});
+// 数据目录（SQLite 与图片缓存），解析顺序见 data_dir
+static DATA_DIR: LazyLock<PathBuf> = LazyLock::new(|| data_dir::resolved().path.clone());
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    dotenvy::dotenv().expect("Failed to load .env file");
+    // 先加载（可选的）.env 并解析配置，使其中的 RUST_LOG 对日志初始化生效
+    config::init();
 
     // 初始化 tracing（一次性），添加 EnvFilter 方便用户通过环境变量调整日志等级：
     // 示例：RUST_LOG=debug,reqwest=warn
@@ -49,17 +106,63 @@ pub fn run() {
         .try_init()
         .expect("Error when initializing tracing log");
 
+    // 已有实例在运行时把窗口交给它，本实例不再打开数据库与缓存
+    if let instance_lock::Acquire::AlreadyRunning { pid, focused } =
+        instance_lock::acquire(&DATA_DIR)
+    {
+        info!(
+            ?pid,
+            focused, "Another instance is already running, exiting"
+        );
+
+        return;
+    }
+
     tauri::Builder::default()
-        .setup(|_app| {
+        .setup(|app| {
+            let handle = app.handle().clone();
+
+            instance_lock::serve_focus(handle.clone());
+
+            // http 层收到 401 时经此发出登录过期事件
+            auth_expiry::init(handle.clone());
+
             // 异步初始化本地存储，避免使用 block_on 阻塞主事件循环导致 winit 顺序警告
-            tauri::async_runtime::spawn(async {
+            tauri::async_runtime::spawn(async move {
                 match storage::LocalStorage::init(&DATA_DIR.join("local.db").to_string_lossy())
                     .await
                 {
-                    Ok(_) => info!(
-                        "Local storage initialized at {:?}",
-                        DATA_DIR.join("local.db")
-                    ),
+                    Ok(_) => {
+                        info!(
+                            "Local storage initialized at {:?}",
+                            DATA_DIR.join("local.db")
+                        );
+
+                        // 合并设置表中的配置
+                        if let Err(err) = config::reload_from_db().await {
+                            tracing::warn!(%err, "Failed to load settings from database");
+                        }
+
+                        // 设置中已开启演示模式时，在任何请求发出前换上演示数据
+                        demo::apply_on_startup();
+
+                        if let Err(err) = draft::prune_expired_drafts().await {
+                            tracing::warn!(%err, "Failed to prune expired translation drafts");
+                        }
+
+                        // 旧版本的图片缓存只迁移一次（完成后写入标记）
+                        legacy_cache::migrate_on_startup().await;
+                        integrity::run_startup_checks().await;
+
+                        // 启动时检查一次会话身份（不一致时会暂停写操作重试）
+                        session::refresh_identity(&handle).await;
+
+                        // 存储就绪后再启动 PopRaKo 写操作重试任务
+                        connectivity::spawn_connectivity_monitor(handle.clone());
+                        translation_verify::spawn_verifier(handle.clone());
+                        write_queue::spawn_flusher(handle);
+                        usage::spawn_usage_flusher();
+                    }
                     Err(err) => tracing::error!(%err, "Local storage init failed"),
                 }
             });
@@ -67,10 +170,20 @@ pub fn run() {
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
+        // 缓存图片经 moeimg:// 直接以原始字节返回，不走 IPC；文件读取在异步任务中进行
+        .register_asynchronous_uri_scheme_protocol(
+            image_protocol::SCHEME,
+            |_ctx, request, responder| {
+                tauri::async_runtime::spawn(async move {
+                    responder.respond(image_protocol::respond(request).await);
+                });
+            },
+        )
         .invoke_handler(tauri::generate_handler![
             // auth
             crate::auth::get_captcha,
             crate::auth::aquire_token,
+            crate::bootstrap::bootstrap_session,
             // token cache operations
             crate::token::get_moetran_token,
             crate::token::save_moetran_token,
@@ -84,44 +197,178 @@ pub fn run() {
             crate::user::get_user_info,
             // user teams
             crate::team::get_user_teams,
+            crate::team::get_user_teams_page,
             // projects (enriched only)
             crate::project::get_user_projects_enriched,
             crate::project::get_project_targets,
             crate::project::get_project_files,
             crate::project::get_page_sources,
+            crate::project::get_files_sources_batch,
+            crate::source_overlay::get_page_geometry,
+            crate::source_overlay::get_page_translations,
             crate::project::create_source,
             crate::project::update_source,
             crate::project::delete_source,
+            crate::project::delete_project_file,
+            crate::project::delete_project_target,
+            crate::source_batch::delete_sources_batch,
+            crate::source_batch::delete_sources_in_region,
+            crate::source_snapshot::clear_sources_snapshots,
+            crate::draft::save_translation_draft,
+            crate::draft::get_translation_drafts,
+            crate::draft::clear_translation_draft,
+            crate::draft::purge_old_drafts,
+            crate::recent::mark_project_opened,
+            crate::recent::get_recent_projects,
+            crate::impact_check::check_delete_impacts,
             crate::project::submit_translation,
+            crate::project::submit_translations_batch,
             crate::project::update_translation,
+            crate::retry::retry_command,
+            crate::retry::list_retryable_failures,
+            crate::normalize::normalize_text,
+            crate::normalize::get_punctuation_ruleset,
+            crate::normalize::set_punctuation_ruleset,
+            crate::page_approval::approve_page,
             crate::project::proxy_image,
             crate::project::create_projset,
+            crate::projset_index::get_next_projset_index,
             crate::project::create_proj,
             crate::project::get_team_poprako_projsets,
+            crate::project::update_poprako_projset,
+            crate::project::delete_poprako_projset,
             crate::project::list_team_shown_projects,
             crate::project::assign_member_to_proj,
+            crate::project::update_proj_member_roles,
+            crate::project::unassign_member_from_proj,
+            crate::project::delete_proj,
+            crate::project::archive_proj,
             crate::project::search_user_projects_enriched,
             crate::project::search_team_projects_enriched,
+            crate::project::get_project_enriched,
             crate::project::get_team_projects_enriched,
+            crate::project_cache::get_cached_projects_enriched,
             crate::project::update_proj_status,
             crate::project::publish_proj,
+            crate::publish::publish_projs_bulk,
             crate::project::upload_project_file,
+            crate::project::upload_project_files_from_paths,
+            crate::natsort::sort_file_names_natural,
+            crate::position_type::get_position_types,
             crate::project::create_poprako_projset,
             crate::project::get_assignments,
+            crate::deadline::set_project_deadline,
+            crate::deadline::get_project_deadlines,
+            crate::status_labels::get_status_labels,
+            crate::status_labels::set_status_labels,
+            crate::project_history::get_project_snapshot_history,
+            crate::file_activity::get_recent_files,
+            crate::digest::get_team_digest,
+            crate::digest::mark_digest_seen,
+            crate::labelplus_import::import_labelplus,
+            crate::translation_export::export_project_translations,
+            crate::translation_export::cancel_project_export,
+            crate::translation_export::get_project_export_progress,
+            crate::export::export_labelplus,
+            crate::reading_view::export_reading_view,
+            crate::translation_verify::list_lost_translations,
+            crate::translation_verify::resubmit_lost_translation,
+            crate::poprako_health::get_poprako_health,
+            crate::volume_manifest::create_projects_from_manifest,
+            crate::project::get_reading_direction,
+            crate::project::set_reading_direction,
+            // session identity
+            crate::session::check_session_identity,
+            crate::session::get_session_identity,
+            crate::session::confirm_session_identity,
+            // contributions
+            crate::contributions::get_project_contributions,
+            crate::contributions::format_project_credits,
+            crate::text_stats::get_project_text_stats,
+            crate::empty_translations::find_empty_translations,
+            crate::redraw_estimate::estimate_redraw_workload,
+            crate::redraw_estimate::seed_redraw_tasks_from_estimate,
+            crate::redraw_estimate::list_redraw_tasks,
+            // poprako write queue
+            crate::write_queue::list_pending_poprako_writes,
+            crate::write_queue::discard_pending_write,
             // member search
             crate::member::get_members,
+            crate::member::get_all_members,
             crate::member::get_member_info,
+            crate::member::invalidate_member_info_cache,
             crate::member::get_active_members,
+            crate::member::add_team_member,
+            crate::member::remove_team_member,
+            crate::member_audit::audit_project_members,
+            crate::mention::resolve_mentions_in_text,
+            crate::project_notes::add_project_note,
+            crate::project_notes::list_project_notes,
+            crate::project_notes::get_my_mentions,
+            crate::project_notes::delete_project_note,
             // image cache
             crate::image_cache::check_file_cache,
             crate::image_cache::download_project_files,
+            crate::image_cache::retry_failed_downloads,
+            crate::image_cache::cancel_project_download,
+            crate::image_cache::get_project_download_progress,
+            crate::image_cache::check_cache_freshness,
+            crate::image_cache::refresh_cached_project_info,
             crate::image_cache::delete_file_cache,
             crate::image_cache::load_cached_file,
+            crate::image_cache::get_thumbnail,
+            crate::image_cache::generate_tiles,
+            crate::image_cache::cancel_tile_generation,
+            crate::image_cache::get_tile,
+            crate::image_cache::clear_image_memory_cache,
             crate::image_cache::get_all_cached_projects_list,
             crate::image_cache::get_cached_project_info,
+            crate::image_cache::get_cache_usage,
+            crate::image_cache::set_cache_limit,
+            crate::image_cache::relink_project_cache,
+            crate::image_cache::suggest_cache_relinks,
+            crate::cache_transfer::export_caches,
+            crate::cache_transfer::import_caches,
+            crate::cache_transfer::cancel_cache_transfer,
+            crate::cache_transfer::get_cache_transfer_progress,
+            crate::legacy_cache::migrate_legacy_cache,
             // notify
             crate::notify::update,
+            // connectivity
+            crate::connectivity::get_connectivity_status,
+            crate::demo::get_demo_mode,
+            crate::demo::set_demo_mode,
+            crate::connectivity::set_offline_mode,
+            // config
+            crate::config::get_effective_config,
+            crate::dto_check::get_dto_validation_report,
+            crate::config::set_config_value,
+            crate::config::get_api_endpoints,
+            crate::config::set_api_endpoints,
+            crate::data_dir::get_data_dir,
+            crate::data_dir::set_data_dir,
+            crate::storage::get_storage_diagnostics,
+            crate::integrity::run_integrity_check,
+            // command palette
+            crate::actions::list_actions,
+            crate::actions::invoke_action,
+            crate::preferences_profile::export_preferences_profile,
+            crate::preferences_profile::import_preferences_profile,
+            // ui session
+            crate::ui_session::save_ui_session,
+            crate::ui_session::get_ui_session,
+            // usage stats
+            crate::usage::get_usage_stats,
+            crate::usage::export_usage_stats_csv,
         ])
-        .run(tauri::generate_context!())
-        .expect("Error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("Error while building tauri application")
+        .run(|_app, event| {
+            // 退出前把内存中的使用量统计落盘
+            if let tauri::RunEvent::Exit = event {
+                if let Err(err) = tauri::async_runtime::block_on(usage::flush_usage()) {
+                    tracing::warn!(%err, "Failed to flush usage stats on exit");
+                }
+            }
+        });
 }
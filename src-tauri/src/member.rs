@@ -1,11 +1,28 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+    time::{Duration, Instant},
+};
+
 use serde::{Deserialize, Serialize};
 
 use time::OffsetDateTime;
 use tracing::info;
 
 use crate::{
+    clock::unix_now,
+    config::config,
     defer::WarnDefer,
+    error::AppError,
+    flexible_list::{FlexibleList, ListField},
     http::{poprako_get, poprako_post_opt},
+    ids::{MemberId, TeamId, UserId},
+    impact_check::{ensure_delete_allowed, ImpactEntity},
+    pagination::{drain_pages, drain_pages_with_total, Page, PoprakoListEnvelope},
+    project::MemberRoles,
+    storage::{member_directory, LOCAL_STORAGE},
+    token::get_moetran_token,
+    validation::{poprako_error, ValidationErrors},
 };
 
 #[derive(Debug, Deserialize)]
@@ -17,8 +34,8 @@ pub struct PoprakoEnvelope<T> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PoprakoMemberSearchRaw {
-    pub member_id: String,
-    pub user_id: String,
+    pub member_id: MemberId,
+    pub user_id: UserId,
     pub username: String,
     pub is_admin: Option<bool>,
     pub is_translator: Option<bool>,
@@ -29,10 +46,10 @@ pub struct PoprakoMemberSearchRaw {
     pub last_active: Option<OffsetDateTime>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PoprakoMemberSearchItem {
-    pub member_id: String,
-    pub user_id: String,
+    pub member_id: MemberId,
+    pub user_id: UserId,
     pub username: String,
     pub is_admin: Option<bool>,
     pub is_translator: Option<bool>,
@@ -44,9 +61,9 @@ pub struct PoprakoMemberSearchItem {
 }
 
 // 当前登录用户在指定 team 中的成员信息（用于判断是否为管理员等）
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoprakoMemberInfo {
-    pub member_id: String,
+    pub member_id: MemberId,
     pub is_admin: bool,
     pub is_translator: bool,
     pub is_proofreader: bool,
@@ -57,7 +74,7 @@ pub struct PoprakoMemberInfo {
 // 与 PopRaKo 文档中的 PickMemberPayload 对应
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReqMembers {
-    pub team_id: String,
+    pub team_id: TeamId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -69,36 +86,42 @@ pub struct ReqMembers {
 }
 
 // IPC 返回结构：包一层，避免直接使用 Vec 作为 IpcResponse
-#[derive(Debug, Serialize)]
-pub struct MembersReply {
-    pub items: Vec<PoprakoMemberSearchItem>,
-}
+pub type MembersReply = Page<PoprakoMemberSearchItem>;
 
-#[tauri::command]
-pub async fn get_members(payload: ReqMembers) -> Result<MembersReply, String> {
-    info!(
-        team_id = %payload.team_id,
-        position = ?payload.position,
-        fuzzy_name = ?payload.fuzzy_name,
-        page = ?payload.page,
-        limit = ?payload.limit,
-        "poprako.members.request",
-    );
+// PopRaKo 未指定时的默认分页
+const DEFAULT_MEMBERS_PAGE: u32 = 1;
+const DEFAULT_MEMBERS_LIMIT: u32 = 10;
 
-    let mut defer = WarnDefer::new("poprako.members.request");
+// 按名称搜索时关键字的长度上限（用户名不会更长）
+const MEMBER_FUZZY_NAME_MAX_CHARS: usize = 64;
 
-    let reply: PoprakoEnvelope<Vec<PoprakoMemberSearchRaw>> =
-        poprako_post_opt("members/search", Some(&payload))
-            .await
-            .map_err(|err| format!("Failed to fetch members: {}", err))?;
+// 拉取全部成员时的单页大小与总量上限
+const DRAIN_MEMBERS_LIMIT: u32 = 100;
+const DRAIN_MEMBERS_MAX: usize = 2000;
 
-    if reply.code != 200 {
-        return Err(reply.message.unwrap_or_else(|| "Unknown error".to_string()));
-    }
+// 查询单页成员（get_members 与 fetch_all_members 共用）
+async fn search_members_page(
+    payload: &ReqMembers,
+    page: u32,
+    limit: u32,
+) -> Result<Page<PoprakoMemberSearchItem>, String> {
+    let body = ReqMembers {
+        team_id: payload.team_id.clone(),
+        position: payload.position.clone(),
+        fuzzy_name: payload.fuzzy_name.clone(),
+        page: Some(page),
+        limit: Some(limit),
+    };
 
-    let items = reply.data.unwrap_or_default();
+    let reply: PoprakoListEnvelope<PoprakoMemberSearchRaw> =
+        poprako_post_opt("members/search", Some(&body))
+            .await
+            .map_err(|err| poprako_error("Failed to fetch members", err))?;
 
-    let converted: Vec<PoprakoMemberSearchItem> = items
+    let page = reply.into_page(page, limit)?;
+
+    let converted: Vec<PoprakoMemberSearchItem> = page
+        .items
         .into_iter()
         .map(|m| PoprakoMemberSearchItem {
             member_id: m.member_id,
@@ -114,23 +137,233 @@ pub async fn get_members(payload: ReqMembers) -> Result<MembersReply, String> {
         })
         .collect();
 
+    Ok(Page {
+        items: converted,
+        total: page.total,
+        page: page.page,
+        limit: page.limit,
+        has_more: page.has_more,
+    })
+}
+
+#[tauri::command]
+pub async fn get_members(payload: ReqMembers) -> Result<MembersReply, AppError> {
+    info!(
+        team_id = %payload.team_id,
+        position = ?payload.position,
+        fuzzy_name = ?payload.fuzzy_name,
+        page = ?payload.page,
+        limit = ?payload.limit,
+        "poprako.members.request",
+    );
+
+    let mut errors = ValidationErrors::new();
+    errors.require("team_id", &payload.team_id);
+    errors.max_chars(
+        "fuzzy_name",
+        payload.fuzzy_name.as_deref().unwrap_or_default(),
+        MEMBER_FUZZY_NAME_MAX_CHARS,
+    );
+    errors.check()?;
+
+    let mut defer = WarnDefer::new("poprako.members.request");
+
+    let page = payload.page.unwrap_or(DEFAULT_MEMBERS_PAGE).max(1);
+    let limit = payload.limit.unwrap_or(DEFAULT_MEMBERS_LIMIT).max(1);
+
+    let reply = search_members_page(&payload, page, limit).await?;
+
+    info!(
+        count = reply.items.len(),
+        total = ?reply.total,
+        has_more = reply.has_more,
+        "poprako.members.request.ok"
+    );
+
     defer.success();
 
-    Ok(MembersReply { items: converted })
+    Ok(reply)
+}
+
+// 拉取全部成员时的查询条件（不过滤职位与名字）
+fn all_members_query(team_id: &str) -> ReqMembers {
+    ReqMembers {
+        team_id: team_id.into(),
+        position: None,
+        fuzzy_name: None,
+        page: None,
+        limit: None,
+    }
+}
+
+// 保存完整拉取到的成员目录，离线时使用；失败只记录日志
+async fn remember_directory(team_id: &str, items: &[PoprakoMemberSearchItem]) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let result = match serde_json::to_string(items) {
+        Ok(data) => {
+            member_directory::save_member_directory(storage.pool(), team_id, &data, unix_now())
+                .await
+        }
+        Err(err) => Err(err.to_string()),
+    };
+
+    if let Err(err) = result {
+        tracing::warn!(team_id, error = %err, "poprako.members.directory.save_failed");
+    }
+}
+
+// 最近一次保存的成员目录；没有记录或无法解析时返回 None
+async fn cached_directory(team_id: &str) -> Option<Vec<PoprakoMemberSearchItem>> {
+    let storage = LOCAL_STORAGE.get()?;
+
+    let (data, fetched_at) = member_directory::load_member_directory(storage.pool(), team_id)
+        .await
+        .inspect_err(|err| {
+            tracing::warn!(team_id, error = %err, "poprako.members.directory.load_failed");
+        })
+        .ok()??;
+
+    let items: Vec<PoprakoMemberSearchItem> = serde_json::from_str(&data)
+        .inspect_err(|err| {
+            tracing::debug!(team_id, error = %err, "poprako.members.directory.skip");
+        })
+        .ok()?;
+
+    info!(
+        team_id,
+        fetched_at,
+        count = items.len(),
+        "poprako.members.directory.cached"
+    );
+
+    Some(items)
+}
+
+// 拉取团队全部成员（带总量上限），供需要完整成员列表的功能复用；
+// 离线模式下返回最近一次保存的目录，没有时返回离线错误
+pub(crate) async fn fetch_all_members(
+    team_id: &str,
+) -> Result<Vec<PoprakoMemberSearchItem>, String> {
+    if config().offline_mode {
+        return cached_directory(team_id)
+            .await
+            .ok_or_else(|| AppError::Offline.to_string());
+    }
+
+    let payload = all_members_query(team_id);
+
+    let items = drain_pages(DRAIN_MEMBERS_LIMIT, DRAIN_MEMBERS_MAX, |page, limit| {
+        search_members_page(&payload, page, limit)
+    })
+    .await?;
+
+    remember_directory(team_id, &items).await;
+
+    Ok(items)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetAllMembersReq {
+    pub team_id: TeamId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AllMembersReply {
+    pub items: Vec<PoprakoMemberSearchItem>,
+    // 服务端给出的成员总数（未给出时为实际取到的数量）
+    pub total: u64,
+    // 成员数超过 DRAIN_MEMBERS_MAX，items 只含前面一部分
+    pub truncated: bool,
+    // 离线模式下返回的本地目录（最近一次完整拉取的结果）
+    pub stale: bool,
+}
+
+// 一次性获取团队全部成员（成员选择器“加载全部”）
+#[tauri::command]
+pub async fn get_all_members(payload: GetAllMembersReq) -> Result<AllMembersReply, AppError> {
+    info!(team_id = %payload.team_id, "poprako.members.all.request");
+
+    let mut defer = WarnDefer::new("poprako.members.all.request");
+
+    if config().offline_mode {
+        let items = cached_directory(&payload.team_id)
+            .await
+            .ok_or(AppError::Offline)?;
+
+        defer.success();
+
+        return Ok(AllMembersReply {
+            total: items.len() as u64,
+            items,
+            truncated: false,
+            stale: true,
+        });
+    }
+
+    let query = all_members_query(&payload.team_id);
+
+    let drained = drain_pages_with_total(DRAIN_MEMBERS_LIMIT, DRAIN_MEMBERS_MAX, |page, limit| {
+        search_members_page(&query, page, limit)
+    })
+    .await?;
+
+    info!(
+        count = drained.items.len(),
+        total = ?drained.total,
+        truncated = drained.truncated,
+        "poprako.members.all.request.ok"
+    );
+
+    remember_directory(&payload.team_id, &drained.items).await;
+
+    defer.success();
+
+    Ok(AllMembersReply {
+        total: drained.total.unwrap_or(drained.items.len() as u64),
+        items: drained.items,
+        truncated: drained.truncated,
+        stale: false,
+    })
 }
 
 // 获取当前登录用户在指定 team 中的成员信息（含 is_admin 标记）
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetMemberInfoReq {
-    pub team_id: String,
+    pub team_id: TeamId,
+    // 忽略缓存，重新向 PopRaKo 查询
+    #[serde(default)]
+    pub force_refresh: bool,
 }
 
-#[tauri::command]
-pub async fn get_member_info(payload: GetMemberInfoReq) -> Result<PoprakoMemberInfo, String> {
-    info!(team_id = %payload.team_id, "Calling PopRaKo /api/v1/member/info via IPC");
+// members/info 的进程内缓存（按 team_id），有效期见 member_info_ttl_secs；
+// 登录用户变化时整体清空，调整角色后由前端调用 invalidate_member_info_cache
+static MEMBER_INFO_CACHE: LazyLock<RwLock<HashMap<TeamId, (PoprakoMemberInfo, Instant)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
 
-    let mut defer = WarnDefer::new("poprako.member.info.request");
+fn cached_member_info(team_id: &TeamId, ttl: Duration) -> Option<PoprakoMemberInfo> {
+    let guard = MEMBER_INFO_CACHE.read().ok()?;
+    let (info, fetched_at) = guard.get(team_id)?;
 
+    (fetched_at.elapsed() < ttl).then(|| info.clone())
+}
+
+fn remember_member_info(team_id: &TeamId, info: &PoprakoMemberInfo) {
+    if let Ok(mut guard) = MEMBER_INFO_CACHE.write() {
+        guard.insert(team_id.clone(), (info.clone(), Instant::now()));
+    }
+}
+
+// 登录用户变化（保存 / 删除 PopRaKo token）后清空全部缓存
+pub(crate) fn forget_all_member_info() {
+    if let Ok(mut guard) = MEMBER_INFO_CACHE.write() {
+        guard.clear();
+    }
+}
+
+async fn fetch_member_info(team_id: &TeamId) -> Result<PoprakoMemberInfo, AppError> {
     #[derive(Debug, Deserialize)]
     struct Envelope<T> {
         code: u16,
@@ -138,36 +371,83 @@ pub async fn get_member_info(payload: GetMemberInfoReq) -> Result<PoprakoMemberI
         message: Option<String>,
     }
 
-    use std::collections::HashMap;
-
     let mut q = HashMap::new();
-    q.insert("team_id", payload.team_id.clone());
+    q.insert("team_id", team_id.to_string());
 
     let reply: Envelope<PoprakoMemberInfo> = poprako_get("members/info", Some(&q))
         .await
-        .map_err(|err| format!("Failed to fetch member info: {}", err))?;
+        .map_err(|err| err.context("Failed to fetch member info"))?;
 
     if reply.code != 200 {
         let msg = reply.message.unwrap_or_else(|| "Unknown error".to_string());
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let info = reply
         .data
         .ok_or_else(|| "PopRaKo member info response missing data".to_string())?;
 
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn get_member_info(payload: GetMemberInfoReq) -> Result<PoprakoMemberInfo, AppError> {
+    info!(
+        team_id = %payload.team_id,
+        force_refresh = payload.force_refresh,
+        "Calling PopRaKo /api/v1/member/info via IPC"
+    );
+
+    let ttl = Duration::from_secs(config().member_info_ttl_secs);
+
+    if !payload.force_refresh {
+        if let Some(info) = cached_member_info(&payload.team_id, ttl) {
+            info!(team_id = %payload.team_id, "poprako.member.info.cache_hit");
+            return Ok(info);
+        }
+    }
+
+    let mut defer = WarnDefer::new("poprako.member.info.request");
+
+    let info = fetch_member_info(&payload.team_id).await?;
+
+    remember_member_info(&payload.team_id, &info);
+
     defer.success();
 
     Ok(info)
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvalidateMemberInfoCacheReq {
+    pub team_id: TeamId,
+}
+
+// 清除某个 team 的成员信息缓存（调整成员角色后调用）；返回是否存在缓存
+#[tauri::command]
+pub async fn invalidate_member_info_cache(
+    payload: InvalidateMemberInfoCacheReq,
+) -> Result<bool, AppError> {
+    let removed = MEMBER_INFO_CACHE
+        .write()
+        .map(|mut guard| guard.remove(&payload.team_id).is_some())
+        .unwrap_or(false);
+
+    info!(team_id = %payload.team_id, removed, "poprako.member.info.cache_invalidated");
+
+    Ok(removed)
+}
+
 // 获取团队活跃成员列表（包含 last_active）
 // We deserialize PopRaKo's `last_active` into `time::OffsetDateTime` and
 // convert it to a unix timestamp (seconds) before returning to the frontend.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PoprakoActiveMemberRaw {
-    pub member_id: String,
-    pub user_id: String,
+    pub member_id: MemberId,
+    pub user_id: UserId,
     pub username: String,
     pub is_admin: Option<bool>,
     pub is_translator: Option<bool>,
@@ -179,10 +459,17 @@ pub struct PoprakoActiveMemberRaw {
     pub last_active: Option<OffsetDateTime>,
 }
 
+// members/active 的 data 包裹字段
+pub(crate) struct MembersField;
+
+impl ListField for MembersField {
+    const FIELD: &'static str = "members";
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PoprakoActiveMember {
-    pub member_id: String,
-    pub user_id: String,
+    pub member_id: MemberId,
+    pub user_id: UserId,
     pub username: String,
     pub is_admin: Option<bool>,
     pub is_translator: Option<bool>,
@@ -196,7 +483,7 @@ pub struct PoprakoActiveMember {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetActiveMembersReq {
-    pub team_id: String,
+    pub team_id: TeamId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -206,15 +493,13 @@ pub struct GetActiveMembersReq {
 #[tauri::command]
 pub async fn get_active_members(
     payload: GetActiveMembersReq,
-) -> Result<Vec<PoprakoActiveMember>, String> {
+) -> Result<Vec<PoprakoActiveMember>, AppError> {
     info!(team_id=%payload.team_id, page=?payload.page, limit=?payload.limit, "poprako.members.active.request");
 
     let mut defer = WarnDefer::new("poprako.members.active.request");
 
-    use std::collections::HashMap;
-
     let mut q = HashMap::new();
-    q.insert("team_id", payload.team_id.clone());
+    q.insert("team_id", payload.team_id.to_string());
     if let Some(p) = payload.page {
         q.insert("page", p.to_string());
     }
@@ -222,17 +507,24 @@ pub async fn get_active_members(
         q.insert("limit", l.to_string());
     }
 
-    // PopRaKo returns an envelope with code/data/message for this endpoint
-    let reply: PoprakoEnvelope<Vec<PoprakoActiveMemberRaw>> =
+    // PopRaKo returns an envelope with code/data/message for this endpoint;
+    // data may be a bare list or wrapped as { members: [...] }
+    let reply: PoprakoEnvelope<FlexibleList<PoprakoActiveMemberRaw, MembersField>> =
         poprako_get("members/active", Some(&q))
             .await
-            .map_err(|err| format!("Failed to fetch active members: {}", err))?;
+            .map_err(|err| err.context("Failed to fetch active members"))?;
 
     if reply.code != 200 {
-        return Err(reply.message.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: reply.message.unwrap_or_else(|| "Unknown error".to_string()),
+        });
     }
 
-    let items = reply.data.unwrap_or_default();
+    let items = reply
+        .data
+        .map(|list| list.into_items("members/active"))
+        .unwrap_or_default();
 
     // Convert OffsetDateTime -> unix timestamp (seconds)
     let converted: Vec<PoprakoActiveMember> = items
@@ -255,3 +547,522 @@ pub async fn get_active_members(
 
     Ok(converted)
 }
+
+// ========== 团队成员管理（添加 / 移除） ==========
+
+// PopRaKo 对成员管理返回的业务错误码（HTTP 状态码与 code 一致）
+const MEMBER_FORBIDDEN_CODE: u16 = 403;
+const MEMBER_NOT_FOUND_CODE: u16 = 404;
+const MEMBER_EXISTS_CODE: u16 = 409;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddTeamMemberReq {
+    pub team_id: TeamId,
+    pub user_id: UserId,
+    pub roles: MemberRoles,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PoprakoAddMemberReq {
+    team_id: TeamId,
+    user_id: UserId,
+    mtr_auth: String,
+    is_translator: bool,
+    is_proofreader: bool,
+    is_typesetter: bool,
+    is_redrawer: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoprakoAddMemberData {
+    member_id: MemberId,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoveTeamMemberReq {
+    pub team_id: TeamId,
+    pub member_id: MemberId,
+    // 已在前端确认影响（仍参与的未发布项目）后传 true，跳过影响检查
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PoprakoRemoveMemberReq {
+    team_id: TeamId,
+    member_id: MemberId,
+    mtr_auth: String,
+}
+
+// 把常见的业务错误码换成可直接展示的说明；其余错误原样保留（附上下文）
+fn team_member_error(context: &str, err: AppError) -> AppError {
+    let code = match err.root() {
+        AppError::PoprakoBusiness { code, .. } => *code,
+        AppError::PoprakoHttp { status, .. } => *status,
+        _ => return poprako_error(context, err),
+    };
+
+    let message = match code {
+        MEMBER_FORBIDDEN_CODE => "权限不足：只有团队管理员可以管理成员",
+        MEMBER_EXISTS_CODE => "该用户已是团队成员",
+        MEMBER_NOT_FOUND_CODE => "团队或成员不存在",
+        _ => return poprako_error(context, err),
+    };
+
+    AppError::PoprakoBusiness {
+        code,
+        message: message.to_string(),
+    }
+}
+
+// 校验 envelope 的 code；code 不是 2xx 时按业务错误返回
+fn check_envelope<T>(reply: PoprakoEnvelope<T>) -> Result<Option<T>, AppError> {
+    if !(200..300).contains(&reply.code) {
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: reply.message.unwrap_or_else(|| "Unknown error".to_string()),
+        });
+    }
+
+    Ok(reply.data)
+}
+
+async fn moetran_auth() -> Result<String, AppError> {
+    get_moetran_token()
+        .await
+        .map_err(AppError::Other)?
+        .ok_or_else(|| AppError::Other("无法获取 Moetran Token".to_string()))
+}
+
+// 把 Moetran 用户加入 PopRaKo 团队并设置角色，返回新成员的 member_id
+#[tauri::command]
+pub async fn add_team_member(payload: AddTeamMemberReq) -> Result<MemberId, AppError> {
+    info!(
+        team_id = %payload.team_id,
+        user_id = %payload.user_id,
+        roles = ?payload.roles,
+        "poprako.team.member.add.start"
+    );
+
+    let mut errors = ValidationErrors::new();
+    errors.require("team_id", &payload.team_id);
+    errors.require("user_id", &payload.user_id);
+    errors.check()?;
+
+    let mut defer = WarnDefer::new("poprako.team.member.add");
+
+    let body = PoprakoAddMemberReq {
+        team_id: payload.team_id.clone(),
+        user_id: payload.user_id.clone(),
+        mtr_auth: moetran_auth().await?,
+        is_translator: payload.roles.is_translator,
+        is_proofreader: payload.roles.is_proofreader,
+        is_typesetter: payload.roles.is_typesetter,
+        is_redrawer: payload.roles.is_redrawer,
+    };
+
+    let context = "添加团队成员失败";
+
+    let reply: PoprakoEnvelope<PoprakoAddMemberData> = poprako_post_opt("members/add", Some(body))
+        .await
+        .map_err(|err| team_member_error(context, err))?;
+
+    let member_id = check_envelope(reply)
+        .map_err(|err| team_member_error(context, err))?
+        .map(|data| data.member_id)
+        .ok_or_else(|| AppError::Other(format!("{}: 响应缺少 member_id", context)))?;
+
+    info!(member_id = %member_id, "poprako.team.member.add.ok");
+
+    defer.success();
+
+    Ok(member_id)
+}
+
+// 把成员移出 PopRaKo 团队
+#[tauri::command]
+pub async fn remove_team_member(payload: RemoveTeamMemberReq) -> Result<(), AppError> {
+    info!(
+        team_id = %payload.team_id,
+        member_id = %payload.member_id,
+        "poprako.team.member.remove.start"
+    );
+
+    let mut errors = ValidationErrors::new();
+    errors.require("team_id", &payload.team_id);
+    errors.require("member_id", &payload.member_id);
+    errors.check()?;
+
+    let mut defer = WarnDefer::new("poprako.team.member.remove");
+
+    let entity = ImpactEntity::Member {
+        member_id: payload.member_id.to_string(),
+    };
+
+    ensure_delete_allowed(entity, payload.force).await?;
+
+    let body = PoprakoRemoveMemberReq {
+        team_id: payload.team_id.clone(),
+        member_id: payload.member_id.clone(),
+        mtr_auth: moetran_auth().await?,
+    };
+
+    let context = "移除团队成员失败";
+
+    let reply: Option<PoprakoEnvelope<serde_json::Value>> =
+        poprako_post_opt("members/remove", Some(body))
+            .await
+            .map_err(|err| team_member_error(context, err))?;
+
+    // 204 时响应体可能为空
+    if let Some(reply) = reply {
+        check_envelope(reply).map_err(|err| team_member_error(context, err))?;
+    }
+
+    // 被移除的可能是当前用户自己
+    if let Ok(mut guard) = MEMBER_INFO_CACHE.write() {
+        guard.remove(&payload.team_id);
+    }
+
+    info!("poprako.team.member.remove.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use wiremock::{
+        matchers::{body_partial_json, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    fn member_json(index: u64) -> Value {
+        json!({
+            "member_id": format!("m{}", index),
+            "user_id": format!("u{}", index),
+            "username": format!("成员{}", index),
+            "is_admin": false,
+            "is_translator": true,
+            "is_proofreader": false,
+            "is_typesetter": false,
+            "is_principal": false,
+        })
+    }
+
+    async fn mount_members_page(
+        backends: &MockBackends,
+        page: u32,
+        range: std::ops::Range<u64>,
+        total: u64,
+    ) {
+        Mock::given(method("POST"))
+            .and(path("/v1/members/search"))
+            .and(body_partial_json(
+                json!({ "team_id": "team-all", "page": page }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": range.map(member_json).collect::<Vec<_>>(),
+                "message": null,
+                "total": total,
+            })))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn all_members_drains_three_pages() {
+        let backends = MockBackends::start().await;
+
+        let limit = DRAIN_MEMBERS_LIMIT as u64;
+        mount_members_page(&backends, 1, 0..limit, 2 * limit + 5).await;
+        mount_members_page(&backends, 2, limit..2 * limit, 2 * limit + 5).await;
+        mount_members_page(&backends, 3, 2 * limit..2 * limit + 5, 2 * limit + 5).await;
+
+        let reply = get_all_members(GetAllMembersReq {
+            team_id: "team-all".into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(reply.items.len() as u64, 2 * limit + 5);
+        assert_eq!(reply.total, 2 * limit + 5);
+        assert!(!reply.truncated);
+        assert_eq!(reply.items[0].member_id, "m0");
+        assert_eq!(
+            reply.items.last().unwrap().member_id.to_string(),
+            format!("m{}", 2 * limit + 4)
+        );
+    }
+
+    #[tokio::test]
+    async fn all_members_reports_server_total_when_capped() {
+        let backends = MockBackends::start().await;
+
+        // 服务端声称成员远多于上限：每页都返回满页
+        let total = DRAIN_MEMBERS_MAX as u64 + 500;
+        Mock::given(method("POST"))
+            .and(path("/v1/members/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": (0..DRAIN_MEMBERS_LIMIT as u64).map(member_json).collect::<Vec<_>>(),
+                "message": null,
+                "total": total,
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        let reply = get_all_members(GetAllMembersReq {
+            team_id: "team-capped".into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(reply.items.len(), DRAIN_MEMBERS_MAX);
+        assert_eq!(reply.total, total);
+        assert!(reply.truncated);
+    }
+
+    fn member_info(member_id: &str) -> PoprakoMemberInfo {
+        PoprakoMemberInfo {
+            member_id: member_id.into(),
+            is_admin: true,
+            is_translator: false,
+            is_proofreader: false,
+            is_typesetter: false,
+            is_principal: false,
+        }
+    }
+
+    #[test]
+    fn cached_member_info_expires_after_ttl() {
+        let team_id = TeamId::from("team-ttl");
+
+        MEMBER_INFO_CACHE.write().unwrap().insert(
+            team_id.clone(),
+            (
+                member_info("m-old"),
+                Instant::now() - Duration::from_secs(10),
+            ),
+        );
+
+        assert!(cached_member_info(&team_id, Duration::from_secs(5)).is_none());
+        assert_eq!(
+            cached_member_info(&team_id, Duration::from_secs(60))
+                .unwrap()
+                .member_id,
+            "m-old"
+        );
+
+        remember_member_info(&team_id, &member_info("m-new"));
+        assert_eq!(
+            cached_member_info(&team_id, Duration::from_secs(5))
+                .unwrap()
+                .member_id,
+            "m-new"
+        );
+    }
+
+    #[tokio::test]
+    async fn member_info_is_cached_until_forced_or_invalidated() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/members/info"))
+            .and(query_param("team_id", "team-cache"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": member_info("m-cache"),
+                "message": null,
+            })))
+            .expect(3)
+            .mount(&backends.poprako)
+            .await;
+
+        let req = |force_refresh| GetMemberInfoReq {
+            team_id: "team-cache".into(),
+            force_refresh,
+        };
+
+        // 第二次命中缓存
+        assert_eq!(
+            get_member_info(req(false)).await.unwrap().member_id,
+            "m-cache"
+        );
+        assert_eq!(
+            get_member_info(req(false)).await.unwrap().member_id,
+            "m-cache"
+        );
+
+        // force_refresh 绕过缓存
+        get_member_info(req(true)).await.unwrap();
+
+        // 失效后重新请求，再次失效时已无缓存
+        assert!(invalidate_member_info_cache(InvalidateMemberInfoCacheReq {
+            team_id: "team-cache".into(),
+        })
+        .await
+        .unwrap());
+        get_member_info(req(false)).await.unwrap();
+
+        backends.poprako.verify().await;
+    }
+
+    fn add_req(team_id: &str, user_id: &str) -> AddTeamMemberReq {
+        AddTeamMemberReq {
+            team_id: team_id.into(),
+            user_id: user_id.into(),
+            roles: MemberRoles {
+                is_translator: true,
+                is_proofreader: false,
+                is_typesetter: true,
+                is_redrawer: false,
+            },
+        }
+    }
+
+    fn remove_req(member_id: &str) -> RemoveTeamMemberReq {
+        RemoveTeamMemberReq {
+            team_id: "team-rm".into(),
+            member_id: member_id.into(),
+            force: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn add_team_member_decodes_envelope() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/add"))
+            .and(body_partial_json(json!({
+                "team_id": "team-add",
+                "user_id": "u-new",
+                "mtr_auth": crate::test_support::TEST_MOETRAN_TOKEN,
+                "is_translator": true,
+                "is_typesetter": true,
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": { "member_id": "m-new" },
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/add"))
+            .and(body_partial_json(json!({ "user_id": "u-exists" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 409,
+                "data": null,
+                "message": "member exists",
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/add"))
+            .and(body_partial_json(json!({ "user_id": "u-nodata" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": null,
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/add"))
+            .and(body_partial_json(json!({ "user_id": "u-forbidden" })))
+            .respond_with(ResponseTemplate::new(403).set_body_string("forbidden"))
+            .mount(&backends.poprako)
+            .await;
+
+        let member_id = add_team_member(add_req("team-add", "u-new")).await.unwrap();
+        assert_eq!(member_id, "m-new");
+
+        let err = add_team_member(add_req("team-add", "u-exists"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AppError::PoprakoBusiness { code: 409, message } if message == "该用户已是团队成员"
+        ));
+
+        let err = add_team_member(add_req("team-add", "u-nodata"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("响应缺少 member_id"), "{}", err);
+
+        // HTTP 层的 403 同样映射为权限不足
+        let err = add_team_member(add_req("team-add", "u-forbidden"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AppError::PoprakoBusiness { code: 403, message } if message.starts_with("权限不足")
+        ));
+
+        // 本地校验失败时不发请求
+        let err = add_team_member(add_req("", "u-new")).await.unwrap_err();
+        assert!(err.to_string().contains("team_id"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn remove_team_member_accepts_envelope_or_empty_body() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/remove"))
+            .and(body_partial_json(json!({ "member_id": "m-ok" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": null,
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/remove"))
+            .and(body_partial_json(json!({ "member_id": "m-empty" })))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/remove"))
+            .and(body_partial_json(json!({ "member_id": "m-missing" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 404,
+                "data": null,
+                "message": "no such member",
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        // 移除成功后清除该团队的成员信息缓存
+        remember_member_info(&TeamId::from("team-rm"), &member_info("m-ok"));
+
+        remove_team_member(remove_req("m-ok")).await.unwrap();
+        assert!(cached_member_info(&TeamId::from("team-rm"), Duration::from_secs(60)).is_none());
+
+        remove_team_member(remove_req("m-empty")).await.unwrap();
+
+        let err = remove_team_member(remove_req("m-missing"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AppError::PoprakoBusiness { code: 404, message } if message == "团队或成员不存在"
+        ));
+    }
+}
@@ -0,0 +1,454 @@
+// 项目成员核对：对照实际提交翻译 / 校对的人（Moetran）、PopRaKo 中指派的成员与 Moetran 团队成员，
+// 找出未指派的贡献者、没有任何贡献的指派成员、已不在团队中的指派成员，以及缺少管理 / 负责人标记的负责人
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    contributions::{build_contributions_report, ContributorStat},
+    defer::WarnDefer,
+    http::moetran_get,
+    member::{fetch_all_members, PoprakoMemberSearchItem},
+    project::{fetch_poprako_proj, MemberRoles, PoprakoMember, ResProject},
+};
+
+const TEAM_USERS_PAGE_LIMIT: u32 = 100;
+// 防止服务端分页异常导致无限翻页
+const TEAM_USERS_MAX_PAGES: u32 = 50;
+
+// Moetran 团队成员（只取核对需要的字段）
+#[derive(Debug, Deserialize)]
+struct MoetranTeamUser {
+    id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditContributor {
+    pub user_id: String,
+    pub name: String,
+    pub translations: u64,
+    pub proofreads: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditAssignee {
+    pub user_id: String,
+    pub member_id: String,
+    pub name: String,
+    pub roles: MemberRoles,
+    pub is_principal: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemberAuditFindings {
+    // 有翻译 / 校对但未被指派到项目
+    pub contributors_not_assigned: Vec<AuditContributor>,
+    // 指派为翻译或校对，但没有任何翻译 / 校对
+    pub assignees_without_contributions: Vec<AuditAssignee>,
+    // 指派成员已不在 Moetran 团队中
+    pub assignees_not_in_team: Vec<AuditAssignee>,
+    // 项目负责人在团队中既不是管理员也没有负责人标记
+    pub principals_without_flags: Vec<AuditAssignee>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberAuditReport {
+    pub project_id: String,
+    pub target_id: String,
+    // 以下来源获取失败时对应的检查项为空，并在此标记
+    pub assignees_known: bool,
+    pub team_known: bool,
+    pub team_flags_known: bool,
+    #[serde(flatten)]
+    pub findings: MemberAuditFindings,
+}
+
+fn to_assignee(member: &PoprakoMember) -> AuditAssignee {
+    AuditAssignee {
+        user_id: member.user_id.to_string(),
+        member_id: member.member_id.to_string(),
+        name: member.username.clone(),
+        roles: MemberRoles {
+            is_translator: member.is_translator,
+            is_proofreader: member.is_proofreader,
+            is_typesetter: member.is_typesetter,
+            is_redrawer: member.is_redrawer,
+        },
+        is_principal: member.is_principal,
+    }
+}
+
+// 核对逻辑（不含网络请求）：team_user_ids / team_flags 为 None 时跳过对应检查
+pub(crate) fn audit_members(
+    contributors: &[ContributorStat],
+    assignees: &[PoprakoMember],
+    team_user_ids: Option<&HashSet<String>>,
+    team_flags: Option<&HashMap<String, PoprakoMemberSearchItem>>,
+) -> MemberAuditFindings {
+    let assigned: HashSet<&str> = assignees.iter().map(|m| m.user_id.as_str()).collect();
+
+    let contributions: HashMap<&str, &ContributorStat> = contributors
+        .iter()
+        .filter_map(|c| c.user_id.as_deref().map(|id| (id, c)))
+        .collect();
+
+    let mut findings = MemberAuditFindings::default();
+
+    for (user_id, stat) in &contributions {
+        if !assigned.contains(user_id) {
+            findings.contributors_not_assigned.push(AuditContributor {
+                user_id: user_id.to_string(),
+                name: stat.name.clone(),
+                translations: stat.translations,
+                proofreads: stat.proofreads,
+            });
+        }
+    }
+
+    for member in assignees {
+        let contributed = contributions
+            .get(member.user_id.as_str())
+            .is_some_and(|stat| stat.translations > 0 || stat.proofreads > 0);
+
+        // 嵌字 / 修图在 Moetran 上没有记录，只检查翻译与校对
+        if (member.is_translator || member.is_proofreader) && !contributed {
+            findings
+                .assignees_without_contributions
+                .push(to_assignee(member));
+        }
+
+        if let Some(team) = team_user_ids {
+            if !team.contains(member.user_id.as_str()) {
+                findings.assignees_not_in_team.push(to_assignee(member));
+            }
+        }
+
+        if let Some(flags) = team_flags {
+            let flagged = flags
+                .get(member.user_id.as_str())
+                .is_some_and(|m| m.is_admin.unwrap_or(false) || m.is_principal.unwrap_or(false));
+
+            if member.is_principal && !flagged {
+                findings.principals_without_flags.push(to_assignee(member));
+            }
+        }
+    }
+
+    findings.contributors_not_assigned.sort_by(|a, b| {
+        b.translations
+            .cmp(&a.translations)
+            .then(a.name.cmp(&b.name))
+    });
+
+    findings
+}
+
+// 逐页拉取 Moetran 团队成员 id
+async fn fetch_team_user_ids(team_id: &str) -> Result<HashSet<String>, String> {
+    let path = format!("teams/{}/users", team_id);
+    let mut ids = HashSet::new();
+
+    for page in 1..=TEAM_USERS_MAX_PAGES {
+        let mut query = HashMap::new();
+        query.insert("page", page.to_string());
+        query.insert("limit", TEAM_USERS_PAGE_LIMIT.to_string());
+
+        let users: Vec<MoetranTeamUser> = moetran_get(&path, Some(&query))
+            .await
+            .map_err(|err| format!("获取团队成员失败: {}", err))?;
+
+        let fetched = users.len();
+
+        ids.extend(users.into_iter().map(|user| user.id));
+
+        if fetched < TEAM_USERS_PAGE_LIMIT as usize {
+            break;
+        }
+    }
+
+    Ok(ids)
+}
+
+fn markdown_name(name: &str, user_id: &str) -> String {
+    format!("{}（{}）", name, user_id)
+}
+
+// 渲染为便于分享的 Markdown
+pub(crate) fn render_markdown(report: &MemberAuditReport) -> String {
+    let findings = &report.findings;
+    let mut md = format!(
+        "# 项目成员核对\n\n项目：{}，target：{}\n",
+        report.project_id, report.target_id
+    );
+
+    let mut section = |title: &str, known: bool, lines: Vec<String>| {
+        md.push_str(&format!("\n## {}\n\n", title));
+
+        if !known {
+            md.push_str("（数据获取失败，未检查）\n");
+        } else if lines.is_empty() {
+            md.push_str("无\n");
+        } else {
+            for line in lines {
+                md.push_str(&format!("- {}\n", line));
+            }
+        }
+    };
+
+    section(
+        "未指派的贡献者",
+        report.assignees_known,
+        findings
+            .contributors_not_assigned
+            .iter()
+            .map(|c| {
+                format!(
+                    "{}：翻译 {}，校对 {}",
+                    markdown_name(&c.name, &c.user_id),
+                    c.translations,
+                    c.proofreads
+                )
+            })
+            .collect(),
+    );
+
+    let assignee_lines = |list: &[AuditAssignee]| {
+        list.iter()
+            .map(|m| markdown_name(&m.name, &m.user_id))
+            .collect::<Vec<_>>()
+    };
+
+    section(
+        "没有贡献的指派成员",
+        report.assignees_known,
+        assignee_lines(&findings.assignees_without_contributions),
+    );
+    section(
+        "已不在团队中的指派成员",
+        report.assignees_known && report.team_known,
+        assignee_lines(&findings.assignees_not_in_team),
+    );
+    section(
+        "缺少管理员 / 负责人标记的负责人",
+        report.assignees_known && report.team_flags_known,
+        assignee_lines(&findings.principals_without_flags),
+    );
+
+    md
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditProjectMembersReq {
+    pub project_id: String,
+    pub target_id: String,
+    // 指定时同时写出 Markdown 报告
+    #[serde(default)]
+    pub markdown_path: Option<String>,
+}
+
+#[tauri::command]
+pub async fn audit_project_members(
+    payload: AuditProjectMembersReq,
+) -> Result<MemberAuditReport, String> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        "member_audit.start"
+    );
+
+    let mut defer = WarnDefer::new("member_audit");
+
+    let contributions = build_contributions_report(&payload.project_id, &payload.target_id).await?;
+
+    let project = moetran_get::<ResProject>(&format!("projects/{}", payload.project_id), None)
+        .await
+        .map_err(|err| format!("获取 Moetran 项目信息失败: {}", err))?;
+
+    let team_id = project.team.id;
+
+    // PopRaKo / 团队成员任一来源失败时仍返回其余检查结果
+    let assignees = match fetch_poprako_proj(&payload.project_id).await {
+        Ok(proj) => proj.and_then(|proj| proj.members),
+        Err(err) => {
+            tracing::warn!(error = %err, "member_audit.assignees.fetch.failed");
+            None
+        }
+    };
+
+    let team_user_ids = match fetch_team_user_ids(&team_id).await {
+        Ok(ids) => Some(ids),
+        Err(err) => {
+            tracing::warn!(%team_id, error = %err, "member_audit.team.fetch.failed");
+            None
+        }
+    };
+
+    let team_flags = match fetch_all_members(&team_id).await {
+        Ok(members) => Some(
+            members
+                .into_iter()
+                .map(|m| (m.user_id.to_string(), m))
+                .collect::<HashMap<_, _>>(),
+        ),
+        Err(err) => {
+            tracing::warn!(%team_id, error = %err, "member_audit.team_flags.fetch.failed");
+            None
+        }
+    };
+
+    // 不知道指派了谁时所有检查都无从谈起
+    let findings = match &assignees {
+        Some(assignees) => audit_members(
+            &contributions.contributors,
+            assignees,
+            team_user_ids.as_ref(),
+            team_flags.as_ref(),
+        ),
+        None => MemberAuditFindings::default(),
+    };
+
+    let report = MemberAuditReport {
+        project_id: payload.project_id.clone(),
+        target_id: payload.target_id.clone(),
+        assignees_known: assignees.is_some(),
+        team_known: team_user_ids.is_some(),
+        team_flags_known: team_flags.is_some(),
+        findings,
+    };
+
+    if let Some(path) = &payload.markdown_path {
+        tokio::fs::write(path, render_markdown(&report))
+            .await
+            .map_err(|err| format!("写入 Markdown 失败: {}", err))?;
+    }
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        not_assigned = report.findings.contributors_not_assigned.len(),
+        idle = report.findings.assignees_without_contributions.len(),
+        not_in_team = report.findings.assignees_not_in_team.len(),
+        principals = report.findings.principals_without_flags.len(),
+        "member_audit.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn contributor(
+        user_id: Option<&str>,
+        name: &str,
+        translations: u64,
+        proofreads: u64,
+    ) -> ContributorStat {
+        ContributorStat {
+            user_id: user_id.map(str::to_string),
+            name: name.to_string(),
+            translations,
+            selected_translations: 0,
+            proofreads,
+            roles: None,
+            drive_by: false,
+        }
+    }
+
+    fn assignee(user_id: &str, roles: &[&str], is_principal: bool) -> PoprakoMember {
+        serde_json::from_value(json!({
+            "user_id": user_id,
+            "member_id": format!("m-{}", user_id),
+            "username": format!("name-{}", user_id),
+            "is_admin": false,
+            "is_translator": roles.contains(&"translator"),
+            "is_proofreader": roles.contains(&"proofreader"),
+            "is_typesetter": roles.contains(&"typesetter"),
+            "is_principal": is_principal,
+        }))
+        .unwrap()
+    }
+
+    fn user_ids<T>(items: &[T], id: impl Fn(&T) -> &str) -> Vec<String> {
+        items.iter().map(|item| id(item).to_string()).collect()
+    }
+
+    fn fixture() -> (Vec<ContributorStat>, Vec<PoprakoMember>) {
+        let contributors = vec![
+            contributor(Some("u1"), "alice", 10, 0),
+            contributor(Some("u2"), "bob", 0, 3),
+            contributor(Some("u3"), "carol", 5, 0),
+            contributor(Some("u4"), "dave", 20, 1),
+            // 作者缺失的汇总桶不参与核对
+            contributor(None, "匿名", 7, 0),
+        ];
+
+        let assignees = vec![
+            assignee("u1", &["translator"], true),
+            assignee("u2", &["proofreader"], false),
+            assignee("u5", &["translator"], false),
+            // 嵌字在 Moetran 上没有记录
+            assignee("u6", &["typesetter"], false),
+        ];
+
+        (contributors, assignees)
+    }
+
+    #[test]
+    fn findings_cover_every_source() {
+        let (contributors, assignees) = fixture();
+
+        let team: HashSet<String> = ["u1", "u2", "u6"].map(String::from).into();
+        let flags: HashMap<String, PoprakoMemberSearchItem> = [(
+            "u1".to_string(),
+            PoprakoMemberSearchItem {
+                member_id: "m-u1".into(),
+                user_id: "u1".into(),
+                username: "alice".to_string(),
+                is_admin: Some(false),
+                is_translator: Some(true),
+                is_proofreader: None,
+                is_typesetter: None,
+                is_redrawer: None,
+                is_principal: None,
+                last_active: None,
+            },
+        )]
+        .into();
+
+        let findings = audit_members(&contributors, &assignees, Some(&team), Some(&flags));
+
+        assert_eq!(
+            user_ids(&findings.contributors_not_assigned, |c| &c.user_id),
+            ["u4", "u3"]
+        );
+        assert_eq!(
+            user_ids(&findings.assignees_without_contributions, |a| &a.user_id),
+            ["u5"]
+        );
+        assert_eq!(
+            user_ids(&findings.assignees_not_in_team, |a| &a.user_id),
+            ["u5"]
+        );
+        assert_eq!(
+            user_ids(&findings.principals_without_flags, |a| &a.user_id),
+            ["u1"]
+        );
+    }
+
+    #[test]
+    fn unknown_sources_skip_their_checks() {
+        let (contributors, assignees) = fixture();
+
+        let findings = audit_members(&contributors, &assignees, None, None);
+
+        assert_eq!(findings.contributors_not_assigned.len(), 2);
+        assert_eq!(findings.assignees_without_contributions.len(), 1);
+        assert!(findings.assignees_not_in_team.is_empty());
+        assert!(findings.principals_without_flags.is_empty());
+    }
+}
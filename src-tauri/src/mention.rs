@@ -0,0 +1,447 @@
+// 文本中的 @成员 提及：解析 @username / @"带空格的名字"，并对照汉化组成员目录解析为 member_id。
+// 只接受唯一的完整匹配；仅前缀匹配或大小写不同的多个同名成员都作为歧义报告，不做猜测。
+// 保存时应记录 member_id 而不是名字，读取时再按当前名字渲染，成员改名后仍能对应
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    member::{fetch_all_members, PoprakoMemberSearchItem},
+};
+
+// 名字末尾不计入的标点（“@akira, please……”）
+const TRAILING_PUNCTUATION: &[char] = &[',', '.', ';', ':', '!', '?', ')'];
+
+// 全角标点直接结束名字（中文里提及后通常不加空格：“@akira，请……”）
+const NAME_TERMINATORS: &[char] = &['，', '。', '；', '：', '！', '？', '）', '、'];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MentionToken {
+    // 原文中的写法（含 @ 与引号）
+    pub raw: String,
+    pub name: String,
+    // 在原文中的字节区间
+    pub start: usize,
+    pub end: usize,
+}
+
+// 提取文本中的提及；@ 前必须是开头、空白或标点，避免把邮箱地址当作提及
+pub(crate) fn parse_mentions(text: &str) -> Vec<MentionToken> {
+    let mut tokens = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut iter = text.char_indices().peekable();
+
+    while let Some((start, c)) = iter.next() {
+        let boundary = is_boundary(prev);
+        prev = Some(c);
+
+        if c != '@' || !boundary {
+            continue;
+        }
+
+        let rest = &text[start + 1..];
+
+        // @"name with spaces"
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let Some(close) = quoted.find('"') else {
+                continue;
+            };
+
+            let name = quoted[..close].trim();
+            let end = start + 1 + 1 + close + 1;
+
+            if !name.is_empty() {
+                tokens.push(MentionToken {
+                    raw: text[start..end].to_string(),
+                    name: name.to_string(),
+                    start,
+                    end,
+                });
+            }
+
+            skip_to(&mut iter, end);
+            prev = Some('"');
+            continue;
+        }
+
+        let len = rest
+            .find(|ch: char| ch.is_whitespace() || ch == '@' || NAME_TERMINATORS.contains(&ch))
+            .unwrap_or(rest.len());
+        let name = rest[..len].trim_end_matches(TRAILING_PUNCTUATION);
+
+        if name.is_empty() {
+            continue;
+        }
+
+        let end = start + 1 + name.len();
+
+        tokens.push(MentionToken {
+            raw: text[start..end].to_string(),
+            name: name.to_string(),
+            start,
+            end,
+        });
+
+        skip_to(&mut iter, end);
+        prev = name.chars().next_back();
+    }
+
+    tokens
+}
+
+fn skip_to(iter: &mut std::iter::Peekable<std::str::CharIndices>, end: usize) {
+    while iter.peek().is_some_and(|(index, _)| *index < end) {
+        iter.next();
+    }
+}
+
+fn is_boundary(prev: Option<char>) -> bool {
+    match prev {
+        None => true,
+        // 中文标点（“。@bob”）也算边界；字母数字之后的 @ 视为邮箱等普通文本
+        Some(c) => !c.is_alphanumeric() && c != '@' && c != '_',
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedMention {
+    pub raw: String,
+    pub member_id: String,
+    pub user_id: String,
+    // 当前名字（成员改名后以此为准）
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AmbiguousMention {
+    pub raw: String,
+    // 可能指代的成员名
+    pub candidates: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MentionResolution {
+    pub resolved: Vec<ResolvedMention>,
+    pub ambiguous: Vec<AmbiguousMention>,
+    // 没有任何成员匹配
+    pub unknown: Vec<String>,
+    // 去重后的被提及成员（应随内容一起保存）
+    pub member_ids: Vec<String>,
+}
+
+// 按成员目录解析：完全一致（区分大小写）优先；否则忽略大小写的唯一完全匹配；其余情况报告为歧义或未知
+pub(crate) fn resolve_mentions(
+    tokens: &[MentionToken],
+    members: &[PoprakoMemberSearchItem],
+) -> MentionResolution {
+    let mut resolution = MentionResolution::default();
+    let mut seen = HashSet::new();
+
+    for token in tokens {
+        let exact: Vec<&PoprakoMemberSearchItem> = members
+            .iter()
+            .filter(|m| m.username == token.name)
+            .collect();
+
+        let matched = if exact.len() == 1 {
+            Some(exact[0])
+        } else {
+            let folded = token.name.to_lowercase();
+            let insensitive: Vec<&PoprakoMemberSearchItem> = members
+                .iter()
+                .filter(|m| m.username.to_lowercase() == folded)
+                .collect();
+
+            match insensitive.as_slice() {
+                [only] => Some(*only),
+                [] => {
+                    let prefixed: Vec<String> = members
+                        .iter()
+                        .filter(|m| m.username.to_lowercase().starts_with(&folded))
+                        .map(|m| m.username.clone())
+                        .collect();
+
+                    if prefixed.is_empty() {
+                        resolution.unknown.push(token.raw.clone());
+                    } else {
+                        resolution.ambiguous.push(AmbiguousMention {
+                            raw: token.raw.clone(),
+                            candidates: prefixed,
+                        });
+                    }
+
+                    None
+                }
+                many => {
+                    resolution.ambiguous.push(AmbiguousMention {
+                        raw: token.raw.clone(),
+                        candidates: many.iter().map(|m| m.username.clone()).collect(),
+                    });
+
+                    None
+                }
+            }
+        };
+
+        if let Some(member) = matched {
+            if seen.insert(member.member_id.clone()) {
+                resolution.member_ids.push(member.member_id.to_string());
+            }
+
+            resolution.resolved.push(ResolvedMention {
+                raw: token.raw.clone(),
+                member_id: member.member_id.to_string(),
+                user_id: member.user_id.to_string(),
+                username: member.username.clone(),
+            });
+        }
+    }
+
+    resolution
+}
+
+// 提及的写法：名字含空白或 @ 时使用引号形式
+pub(crate) fn mention_text(name: &str) -> String {
+    if name.contains(|c: char| c.is_whitespace() || c == '@') {
+        format!("@\"{}\"", name)
+    } else {
+        format!("@{}", name)
+    }
+}
+
+// 读取时按当前名字重写文本中的提及；renames 为 (保存时的名字, 当前名字)，忽略大小写匹配，
+// 其余提及保持原样
+pub(crate) fn render_mentions(text: &str, renames: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for token in parse_mentions(text) {
+        let folded = token.name.to_lowercase();
+        let Some((_, current)) = renames
+            .iter()
+            .find(|(saved, _)| saved.to_lowercase() == folded)
+        else {
+            continue;
+        };
+
+        out.push_str(&text[last..token.start]);
+        out.push_str(&mention_text(current));
+        last = token.end;
+    }
+
+    out.push_str(&text[last..]);
+
+    out
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResolveMentionsReq {
+    pub team_id: String,
+    pub text: String,
+}
+
+// 解析文本中的提及（供编辑时提示歧义 / 未知成员，以及保存前取得 member_id）
+#[tauri::command]
+pub async fn resolve_mentions_in_text(
+    payload: ResolveMentionsReq,
+) -> Result<MentionResolution, String> {
+    let tokens = parse_mentions(&payload.text);
+
+    if tokens.is_empty() {
+        return Ok(MentionResolution::default());
+    }
+
+    tracing::info!(
+        team_id = %payload.team_id,
+        tokens = tokens.len(),
+        "mention.resolve.start"
+    );
+
+    let mut defer = WarnDefer::new("mention.resolve");
+
+    let members = fetch_all_members(&payload.team_id).await?;
+
+    let resolution = resolve_mentions(&tokens, &members);
+
+    tracing::info!(
+        team_id = %payload.team_id,
+        resolved = resolution.resolved.len(),
+        ambiguous = resolution.ambiguous.len(),
+        unknown = resolution.unknown.len(),
+        "mention.resolve.ok"
+    );
+
+    defer.success();
+
+    Ok(resolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(id: &str, username: &str) -> PoprakoMemberSearchItem {
+        PoprakoMemberSearchItem {
+            member_id: id.into(),
+            user_id: format!("u-{}", id).into(),
+            username: username.to_string(),
+            is_admin: None,
+            is_translator: None,
+            is_proofreader: None,
+            is_typesetter: None,
+            is_redrawer: None,
+            is_principal: None,
+            last_active: None,
+        }
+    }
+
+    fn names(text: &str) -> Vec<String> {
+        parse_mentions(text).into_iter().map(|t| t.name).collect()
+    }
+
+    fn resolve(text: &str, members: &[PoprakoMemberSearchItem]) -> MentionResolution {
+        resolve_mentions(&parse_mentions(text), members)
+    }
+
+    #[test]
+    fn parses_plain_mentions_with_spans() {
+        let text = "@akira please redraw p.14";
+        let tokens = parse_mentions(text);
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].raw, "@akira");
+        assert_eq!(tokens[0].name, "akira");
+        assert_eq!(&text[tokens[0].start..tokens[0].end], "@akira");
+    }
+
+    #[test]
+    fn quoted_form_allows_spaces() {
+        let text = "请 @\"Akira Tanaka\" 和 @\" 小林 \" 看一下";
+        let tokens = parse_mentions(text);
+
+        assert_eq!(names(text), ["Akira Tanaka", "小林"]);
+        assert_eq!(tokens[0].raw, "@\"Akira Tanaka\"");
+        assert_eq!(&text[tokens[1].start..tokens[1].end], "@\" 小林 \"");
+    }
+
+    #[test]
+    fn unterminated_or_empty_quotes_are_not_mentions() {
+        assert!(names("@\"Akira Tanaka").is_empty());
+        assert!(names("@\"\" 空的").is_empty());
+        assert!(names("@ 单独的 @").is_empty());
+    }
+
+    #[test]
+    fn trailing_punctuation_is_not_part_of_the_name() {
+        assert_eq!(
+            names("@akira，请修图。@bob! (@carol) @dave、@eve."),
+            ["akira", "bob", "carol", "dave", "eve"]
+        );
+    }
+
+    #[test]
+    fn email_addresses_are_not_mentions() {
+        assert!(names("联系 akira@example.com").is_empty());
+        assert_eq!(names("a@b @c"), ["c"]);
+    }
+
+    #[test]
+    fn adjacent_mentions_are_split_at_at_sign() {
+        assert_eq!(names("@akira@bob"), ["akira"]);
+        assert_eq!(names("@akira @bob\n@carol"), ["akira", "bob", "carol"]);
+    }
+
+    #[test]
+    fn unique_exact_match_resolves() {
+        let members = [member("m1", "akira"), member("m2", "bob")];
+        let resolution = resolve("@akira please redraw p.14", &members);
+
+        assert_eq!(resolution.member_ids, ["m1"]);
+        assert_eq!(resolution.resolved[0].username, "akira");
+        assert_eq!(resolution.resolved[0].user_id, "u-m1");
+        assert!(resolution.ambiguous.is_empty());
+        assert!(resolution.unknown.is_empty());
+    }
+
+    #[test]
+    fn case_insensitive_match_is_used_only_when_unique() {
+        let members = [member("m1", "Akira")];
+        assert_eq!(resolve("@akira", &members).member_ids, ["m1"]);
+
+        let members = [member("m1", "Akira"), member("m2", "AKIRA")];
+        let resolution = resolve("@akira", &members);
+
+        assert!(resolution.member_ids.is_empty());
+        assert_eq!(resolution.ambiguous[0].candidates, ["Akira", "AKIRA"]);
+
+        // 区分大小写完全一致的优先
+        assert_eq!(resolve("@AKIRA", &members).member_ids, ["m2"]);
+    }
+
+    #[test]
+    fn prefixes_are_reported_not_guessed() {
+        let members = [member("m1", "akira"), member("m2", "akihiro")];
+        let resolution = resolve("@aki", &members);
+
+        assert!(resolution.member_ids.is_empty());
+        assert_eq!(resolution.ambiguous.len(), 1);
+        assert_eq!(resolution.ambiguous[0].raw, "@aki");
+        assert_eq!(resolution.ambiguous[0].candidates, ["akira", "akihiro"]);
+
+        // 即使只有一个成员以此开头也不猜测
+        let resolution = resolve("@akir", &members);
+        assert!(resolution.member_ids.is_empty());
+        assert_eq!(resolution.ambiguous[0].candidates, ["akira"]);
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let members = [member("m1", "akira")];
+        let resolution = resolve("@zed 和 @\"No One\"", &members);
+
+        assert_eq!(resolution.unknown, ["@zed", "@\"No One\""]);
+        assert!(resolution.resolved.is_empty());
+    }
+
+    #[test]
+    fn names_with_spaces_need_the_quoted_form() {
+        let members = [member("m1", "Akira Tanaka")];
+
+        assert_eq!(resolve("@\"Akira Tanaka\"", &members).member_ids, ["m1"]);
+
+        // 未加引号时只取到第一个词，作为前缀报告
+        let resolution = resolve("@Akira Tanaka", &members);
+        assert!(resolution.member_ids.is_empty());
+        assert_eq!(resolution.ambiguous[0].candidates, ["Akira Tanaka"]);
+    }
+
+    #[test]
+    fn repeated_mentions_are_recorded_once() {
+        let members = [member("m1", "akira")];
+        let resolution = resolve("@akira 先看 p.3，@Akira 再看 p.4", &members);
+
+        assert_eq!(resolution.resolved.len(), 2);
+        assert_eq!(resolution.member_ids, ["m1"]);
+    }
+
+    #[test]
+    fn mention_text_quotes_names_with_spaces() {
+        assert_eq!(mention_text("akira"), "@akira");
+        assert_eq!(mention_text("Akira Tanaka"), "@\"Akira Tanaka\"");
+    }
+
+    #[test]
+    fn render_uses_the_current_name() {
+        let renames = [
+            ("akira".to_string(), "Akira Tanaka".to_string()),
+            ("bob".to_string(), "bob".to_string()),
+        ];
+
+        assert_eq!(
+            render_mentions("@Akira，请修 p.14；@bob 校对，@zed 不变", &renames),
+            "@\"Akira Tanaka\"，请修 p.14；@bob 校对，@zed 不变"
+        );
+        assert_eq!(render_mentions("没有提及", &renames), "没有提及");
+    }
+}
@@ -0,0 +1,169 @@
+// 变更类命令的返回包装：携带变更前的值，供前端乐观更新失败时回滚
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::write_queue::WriteOutcome;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MutationResult<T> {
+    pub new_value: T,
+    // 仅从内存 / SQLite 缓存读取，拿不到时为 null（不会为此额外发起网络请求）
+    pub previous_value: Option<T>,
+    pub mutated_at: i64, // Unix timestamp
+    // PopRaKo 写操作可能进入离线重试队列
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<WriteOutcome>,
+}
+
+impl<T> MutationResult<T> {
+    pub fn new(new_value: T, previous_value: Option<T>) -> Self {
+        Self {
+            new_value,
+            previous_value,
+            mutated_at: OffsetDateTime::now_utc().unix_timestamp(),
+            outcome: None,
+        }
+    }
+
+    pub fn with_outcome(mut self, outcome: WriteOutcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+}
+
+// verbose=false 时保持旧的返回结构（兼容一个版本后移除）
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum MutationReply<P, T> {
+    Plain(P),
+    Verbose(MutationResult<T>),
+}
+
+impl<P, T> MutationReply<P, T> {
+    pub fn build(verbose: bool, plain: P, result: MutationResult<T>) -> Self {
+        if verbose {
+            MutationReply::Verbose(result)
+        } else {
+            MutationReply::Plain(plain)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        normalize::{Transformation, WithNormalization},
+        project::MoetranTranslation,
+    };
+
+    fn translation(content: &str) -> MoetranTranslation {
+        MoetranTranslation {
+            id: "t1".into(),
+            content: content.to_string(),
+            proofread_content: None,
+            selected: false,
+            user: None,
+            proofreader: None,
+        }
+    }
+
+    #[test]
+    fn plain_write_outcome_keeps_tagged_shape() {
+        let applied = MutationResult::new(1, Some(0)).with_outcome(WriteOutcome::Applied);
+        let reply = MutationReply::build(false, WriteOutcome::Applied, applied);
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            json!({ "outcome": "applied" })
+        );
+
+        let queued = WriteOutcome::Queued { pending_id: 7 };
+        let reply = MutationReply::build(false, queued.clone(), MutationResult::new(1, None));
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            json!({ "outcome": "queued", "pending_id": 7 })
+        );
+    }
+
+    #[test]
+    fn verbose_reply_carries_previous_value_and_outcome() {
+        let result =
+            MutationResult::new(2, Some(1)).with_outcome(WriteOutcome::Queued { pending_id: 3 });
+        let reply = MutationReply::build(true, WriteOutcome::Applied, result);
+        let value = serde_json::to_value(&reply).unwrap();
+
+        assert_eq!(value["new_value"], 2);
+        assert_eq!(value["previous_value"], 1);
+        assert!(value["mutated_at"].is_i64());
+        assert_eq!(
+            value["outcome"],
+            json!({ "outcome": "queued", "pending_id": 3 })
+        );
+
+        // 未经写队列的变更不输出 outcome，缓存未命中时 previous_value 为 null
+        let value = serde_json::to_value(MutationReply::<(), _>::build(
+            true,
+            (),
+            MutationResult::new(2, None),
+        ))
+        .unwrap();
+        assert_eq!(value["previous_value"], json!(null));
+        assert!(value.get("outcome").is_none());
+    }
+
+    #[test]
+    fn plain_translation_reply_stays_flat() {
+        let reply = WithNormalization {
+            value: MutationReply::build(
+                false,
+                translation("好"),
+                MutationResult::new(translation("好"), None),
+            ),
+            normalized: Vec::new(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            json!({
+                "id": "t1",
+                "content": "好",
+                "proofread_content": null,
+                "selected": false,
+                "user": null,
+                "proofreader": null,
+            })
+        );
+
+        let reply = WithNormalization {
+            value: MutationReply::build(
+                false,
+                translation("好"),
+                MutationResult::new(translation("好"), None),
+            ),
+            normalized: vec![Transformation::TrimmedTrailingWhitespace],
+        };
+        let value = serde_json::to_value(&reply).unwrap();
+
+        assert_eq!(value["content"], "好");
+        assert_eq!(value["normalized"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn verbose_translation_reply_flattens_mutation_result() {
+        let reply = WithNormalization {
+            value: MutationReply::build(
+                true,
+                translation("新"),
+                MutationResult::new(translation("新"), Some(translation("旧"))),
+            ),
+            normalized: Vec::new(),
+        };
+        let value = serde_json::to_value(&reply).unwrap();
+
+        assert_eq!(value["new_value"]["content"], "新");
+        assert_eq!(value["previous_value"]["content"], "旧");
+        assert!(value.get("normalized").is_none());
+    }
+}
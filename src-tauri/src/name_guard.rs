@@ -0,0 +1,357 @@
+// 创建项目集 / 项目前的重名检查：PopRaKo 不限制同名，而按名称富化的搜索遇到同名会结果不确定
+// 完全相同（忽略大小写）时拒绝创建（除非 allow_duplicate）；仅空白或全半角不同时作为警告返回
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    http::{moetran_get, poprako_post_opt},
+    project::{
+        get_team_poprako_projsets, GetTeamPoprakoProjsetsReq, PoprakoEnvelope,
+        PoprakoProjFilterReq, PoprakoProjInfo, ResProject,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamedEntityKind {
+    Projset,
+    Proj,
+}
+
+// 已存在的同名（或近似同名）实体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameMatch {
+    pub kind: NamedEntityKind,
+    pub id: String,
+    pub name: String,
+    // 项目集为 projset_serial；项目为所在项目集内的 projset_index（仅 PopRaKo 返回时已知）
+    pub serial: Option<u32>,
+}
+
+// 创建成功的返回：原有字段平铺，附带近似重名警告
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateWithNameCheck<T> {
+    #[serde(flatten)]
+    pub data: T,
+    pub near_matches: Vec<NameMatch>,
+}
+
+// 全角 ASCII（U+FF01..U+FF5E）与全角空格（U+3000）折叠为半角
+pub(crate) fn to_half_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+// 完全匹配的比较键：去掉首尾空白后忽略大小写
+fn exact_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+// 近似匹配的比较键：全半角折叠、去除所有空白、忽略大小写
+fn loose_key(name: &str) -> String {
+    name.chars()
+        .map(to_half_width)
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct NameCheck {
+    duplicate: Option<NameMatch>,
+    near_matches: Vec<NameMatch>,
+}
+
+fn classify(name: &str, candidates: Vec<NameMatch>) -> NameCheck {
+    let exact = exact_key(name);
+    let loose = loose_key(name);
+
+    let mut check = NameCheck::default();
+
+    for candidate in candidates {
+        if exact_key(&candidate.name) == exact {
+            check.duplicate.get_or_insert(candidate);
+        } else if loose_key(&candidate.name) == loose {
+            check.near_matches.push(candidate);
+        }
+    }
+
+    check
+}
+
+// 实际发给服务端模糊搜索的关键词：原名及其半角形式（两者不同时都查一次）
+fn search_words(name: &str) -> Vec<String> {
+    let raw = name.trim().to_string();
+    let half: String = raw.chars().map(to_half_width).collect();
+
+    if half == raw {
+        vec![raw]
+    } else {
+        vec![raw, half]
+    }
+}
+
+async fn projset_candidates(team_id: &str) -> Result<Vec<NameMatch>, String> {
+    let projsets = get_team_poprako_projsets(GetTeamPoprakoProjsetsReq {
+        team_id: team_id.into(),
+    })
+    .await?;
+
+    Ok(projsets
+        .into_iter()
+        .map(|projset| NameMatch {
+            kind: NamedEntityKind::Projset,
+            id: projset.projset_id.into(),
+            name: projset.projset_name,
+            serial: Some(projset.projset_serial),
+        })
+        .collect())
+}
+
+// 项目候选：PopRaKo 在目标项目集内按名称模糊搜索 + Moetran 团队项目按关键词搜索，按 id 合并
+async fn proj_candidates(
+    team_id: &str,
+    projset_id: &str,
+    name: &str,
+) -> Result<Vec<NameMatch>, String> {
+    let mut merged: HashMap<String, NameMatch> = HashMap::new();
+
+    for word in search_words(name) {
+        let filter = PoprakoProjFilterReq {
+            fuzzy_proj_name: Some(word.clone()),
+            projset_ids: Some(vec![projset_id.into()]),
+            ..Default::default()
+        };
+
+        let reply =
+            poprako_post_opt::<PoprakoProjFilterReq, PoprakoEnvelope<Vec<PoprakoProjInfo>>>(
+                "projs/search",
+                Some(filter),
+            )
+            .await
+            .map_err(|err| format!("PopRaKo 项目搜索失败: {}", err))?;
+
+        if reply.code != 200 {
+            return Err(reply
+                .message
+                .unwrap_or_else(|| "PopRaKo 项目搜索失败".to_string()));
+        }
+
+        for proj in reply.data.unwrap_or_default() {
+            merged.insert(
+                proj.proj_id.to_string(),
+                NameMatch {
+                    kind: NamedEntityKind::Proj,
+                    id: proj.proj_id.into(),
+                    name: proj.proj_name,
+                    serial: Some(proj.projset_index),
+                },
+            );
+        }
+
+        let mut query = HashMap::new();
+        query.insert("word", word);
+        query.insert("status", "0".to_string());
+
+        let path = format!("teams/{}/projects", team_id);
+
+        let list: Vec<ResProject> = moetran_get(&path, Some(&query))
+            .await
+            .map_err(|err| format!("获取团队项目列表失败: {}", err))?;
+
+        for proj in list {
+            merged.entry(proj.id.to_string()).or_insert(NameMatch {
+                kind: NamedEntityKind::Proj,
+                id: proj.id.into(),
+                name: proj.name,
+                serial: None,
+            });
+        }
+    }
+
+    Ok(merged.into_values().collect())
+}
+
+// 根据候选列表决定是否放行：重名且未允许时返回 AppError::DuplicateName，否则返回近似重名警告。
+// 候选获取失败时不阻塞创建，仅记录日志
+async fn guard_name<F>(
+    kind: NamedEntityKind,
+    name: &str,
+    allow_duplicate: bool,
+    candidates: F,
+) -> Result<Vec<NameMatch>, AppError>
+where
+    F: std::future::Future<Output = Result<Vec<NameMatch>, String>>,
+{
+    let candidates = match candidates.await {
+        Ok(list) => list,
+        Err(err) => {
+            tracing::warn!(?kind, %name, error = %err, "name_guard.lookup.failed");
+            return Ok(vec![]);
+        }
+    };
+
+    let check = classify(name, candidates);
+
+    match check.duplicate {
+        Some(existing) if !allow_duplicate => {
+            tracing::info!(?kind, %name, existing_id = %existing.id, "name_guard.duplicate");
+
+            Err(AppError::DuplicateName(Box::new(existing)))
+        }
+        Some(existing) => {
+            tracing::info!(?kind, %name, existing_id = %existing.id, "name_guard.duplicate_allowed");
+
+            Ok(check.near_matches)
+        }
+        None => Ok(check.near_matches),
+    }
+}
+
+pub async fn guard_projset_name(
+    team_id: &str,
+    name: &str,
+    allow_duplicate: bool,
+) -> Result<Vec<NameMatch>, AppError> {
+    guard_name(
+        NamedEntityKind::Projset,
+        name,
+        allow_duplicate,
+        projset_candidates(team_id),
+    )
+    .await
+}
+
+pub async fn guard_proj_name(
+    team_id: &str,
+    projset_id: &str,
+    name: &str,
+    allow_duplicate: bool,
+) -> Result<Vec<NameMatch>, AppError> {
+    guard_name(
+        NamedEntityKind::Proj,
+        name,
+        allow_duplicate,
+        proj_candidates(team_id, projset_id, name),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projset(id: &str, name: &str) -> NameMatch {
+        NameMatch {
+            kind: NamedEntityKind::Projset,
+            id: id.to_string(),
+            name: name.to_string(),
+            serial: Some(3),
+        }
+    }
+
+    fn ids(matches: &[NameMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn full_width_forms_fold_to_half_width() {
+        let folded: String = "Ｖｏｌ．３\u{3000}（上）"
+            .chars()
+            .map(to_half_width)
+            .collect();
+        assert_eq!(folded, "Vol.3 (上)");
+
+        // 中日文字本身不受影响
+        assert_eq!(to_half_width('卷'), '卷');
+        assert_eq!(to_half_width('ー'), 'ー');
+    }
+
+    #[test]
+    fn exact_match_ignores_case_and_surrounding_space() {
+        let check = classify(
+            " vol.3 ",
+            vec![projset("a", "Vol.3"), projset("b", "Vol.4")],
+        );
+
+        assert_eq!(check.duplicate.map(|m| m.id), Some("a".to_string()));
+        assert!(check.near_matches.is_empty());
+    }
+
+    #[test]
+    fn width_and_inner_space_differences_are_near_matches() {
+        let check = classify(
+            "Vol.3",
+            vec![
+                projset("full", "Ｖｏｌ．３"),
+                projset("spaced", "Vol. 3"),
+                projset("ideographic", "Vol.\u{3000}3"),
+                projset("other", "Vol.30"),
+            ],
+        );
+
+        assert!(check.duplicate.is_none());
+        assert_eq!(ids(&check.near_matches), ["full", "spaced", "ideographic"]);
+    }
+
+    #[test]
+    fn cjk_names_match_across_full_width_punctuation() {
+        let check = classify(
+            "第３卷！",
+            vec![projset("half", "第3卷!"), projset("same", "第３卷！")],
+        );
+
+        assert_eq!(check.duplicate.map(|m| m.id), Some("same".to_string()));
+        assert_eq!(ids(&check.near_matches), ["half"]);
+    }
+
+    #[test]
+    fn search_words_include_half_width_form() {
+        assert_eq!(search_words(" Vol.3 "), ["Vol.3"]);
+        assert_eq!(search_words("Ｖｏｌ．３"), ["Ｖｏｌ．３", "Vol.3"]);
+    }
+
+    #[tokio::test]
+    async fn duplicate_is_rejected_with_existing_entity() {
+        let err = guard_name(NamedEntityKind::Projset, "vol.3", false, async {
+            Ok(vec![projset("a", "Vol.3")])
+        })
+        .await
+        .unwrap_err();
+
+        assert!(err.is_duplicate_name());
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["kind"], "DuplicateName");
+        assert_eq!(value["message"], "团队中已存在同名项目集");
+        assert_eq!(value["existing"]["id"], "a");
+        assert_eq!(value["existing"]["serial"], 3);
+    }
+
+    #[tokio::test]
+    async fn allowed_duplicate_returns_near_matches() {
+        let near = guard_name(NamedEntityKind::Projset, "Vol.3", true, async {
+            Ok(vec![projset("a", "Vol.3"), projset("b", "Ｖｏｌ．３")])
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ids(&near), ["b"]);
+    }
+
+    #[tokio::test]
+    async fn lookup_failure_does_not_block_creation() {
+        let near = guard_name(NamedEntityKind::Proj, "Vol.3", false, async {
+            Err("PopRaKo 项目搜索失败".to_string())
+        })
+        .await
+        .unwrap();
+
+        assert!(near.is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+// 按名称富化时的候选打分：PopRaKo 的 proj_name 在 Moetran 上按关键词搜索，可能返回多个相近的项目。
+// 依次比较 完全相同 > 规范化后相同（大小写 / 全半角 / 空白 / 结尾的完结标记）> 词集合相同 > 互相包含，
+// 只有唯一的最高分达到阈值时才自动匹配，否则把候选列表交给前端让用户确认
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::name_guard::to_half_width;
+
+// 自动匹配所需的最低分（规范化后相同及以上）
+pub(crate) const AUTO_MATCH_CONFIDENCE: f64 = 0.9;
+
+// 返回给前端的候选数量上限
+const MAX_CANDIDATES: usize = 5;
+
+// 结尾的完结 / 连载标记（括号内的内容，比较时忽略大小写）
+const TRAILING_MARKERS: &[&str] = &[
+    "完",
+    "完结",
+    "完結",
+    "全",
+    "全本",
+    "连载中",
+    "連載中",
+    "end",
+    "fin",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchLevel {
+    None,
+    // 一方是另一方的子串
+    Substring,
+    // 拆词后集合相同（词序、标点不同）
+    TokenSet,
+    // 规范化后相同
+    Normalized,
+    // 去掉首尾空白后完全相同
+    Exact,
+}
+
+impl MatchLevel {
+    pub fn confidence(self) -> f64 {
+        match self {
+            MatchLevel::Exact => 1.0,
+            MatchLevel::Normalized => 0.9,
+            MatchLevel::TokenSet => 0.75,
+            MatchLevel::Substring => 0.5,
+            MatchLevel::None => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NameCandidate {
+    pub id: String,
+    pub name: String,
+    pub confidence: f64,
+}
+
+// 打分结果：matched 为自动匹配的候选下标；未能自动匹配时 candidates 按分数从高到低列出
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchDecision {
+    pub matched: Option<usize>,
+    pub best: Option<usize>,
+    pub confidence: f64,
+    pub candidates: Vec<NameCandidate>,
+}
+
+fn is_open_bracket(c: char) -> bool {
+    matches!(c, '(' | '[' | '【' | '〔' | '「')
+}
+
+fn is_close_bracket(c: char) -> bool {
+    matches!(c, ')' | ']' | '】' | '〕' | '」')
+}
+
+// 去掉结尾的一个括号标记（如 "(完)"），结尾不是已知标记时返回 None
+fn strip_trailing_marker(name: &str) -> Option<&str> {
+    let trimmed = name.trim_end();
+    let close = trimmed
+        .chars()
+        .next_back()
+        .filter(|c| is_close_bracket(*c))?;
+    let body = &trimmed[..trimmed.len() - close.len_utf8()];
+    let open_at = body.rfind(is_open_bracket)?;
+    let marker = body[open_at..].chars().skip(1).collect::<String>();
+
+    TRAILING_MARKERS
+        .iter()
+        .any(|known| marker.trim().eq_ignore_ascii_case(known))
+        .then(|| &body[..open_at])
+}
+
+// 规范化：全半角折叠、小写、空白合并为单个空格、去掉结尾的完结标记（可叠加）
+pub(crate) fn normalize_name(name: &str) -> String {
+    let folded: String = name
+        .chars()
+        .map(to_half_width)
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let mut rest = folded.as_str();
+
+    while let Some(stripped) = strip_trailing_marker(rest) {
+        // 只有标记本身时保留（避免把名称清空）
+        if stripped.trim().is_empty() {
+            break;
+        }
+        rest = stripped;
+    }
+
+    rest.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// 规范化后的比较键（去除空白）
+fn compact_key(normalized: &str) -> String {
+    normalized.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+// 按空白与标点拆词
+fn token_set(normalized: &str) -> BTreeSet<&str> {
+    normalized
+        .split(|c: char| {
+            c.is_whitespace() || (c.is_ascii_punctuation() && c != '\'') || is_cjk_punctuation(c)
+        })
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(c, '\u{3000}'..='\u{303F}' | '・' | '·' | '…' | '—' | '～')
+}
+
+// 两个名称的匹配等级
+pub(crate) fn match_level(wanted: &str, candidate: &str) -> MatchLevel {
+    if wanted.trim() == candidate.trim() {
+        return MatchLevel::Exact;
+    }
+
+    let wanted = normalize_name(wanted);
+    let candidate = normalize_name(candidate);
+
+    let wanted_key = compact_key(&wanted);
+    let candidate_key = compact_key(&candidate);
+
+    if wanted_key.is_empty() || candidate_key.is_empty() {
+        return MatchLevel::None;
+    }
+
+    if wanted_key == candidate_key {
+        return MatchLevel::Normalized;
+    }
+
+    let wanted_tokens = token_set(&wanted);
+
+    if !wanted_tokens.is_empty() && wanted_tokens == token_set(&candidate) {
+        return MatchLevel::TokenSet;
+    }
+
+    if wanted_key.contains(&candidate_key) || candidate_key.contains(&wanted_key) {
+        return MatchLevel::Substring;
+    }
+
+    MatchLevel::None
+}
+
+// 为 wanted 在候选 (id, name) 中选择匹配项；最高分有多个不同项目并列时不自动匹配
+pub(crate) fn choose_candidate<'a>(
+    wanted: &str,
+    candidates: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> MatchDecision {
+    let mut scored: Vec<(usize, &str, &str, MatchLevel)> = candidates
+        .into_iter()
+        .enumerate()
+        .map(|(index, (id, name))| (index, id, name, match_level(wanted, name)))
+        .collect();
+
+    // 分数相同时保持服务端返回的顺序
+    scored.sort_by(|a, b| b.3.cmp(&a.3).then(a.0.cmp(&b.0)));
+
+    let Some(&(best_index, best_id, _, best_level)) = scored.first() else {
+        return MatchDecision {
+            matched: None,
+            best: None,
+            confidence: 0.0,
+            candidates: Vec::new(),
+        };
+    };
+
+    let confidence = best_level.confidence();
+
+    let tied = scored
+        .iter()
+        .skip(1)
+        .any(|(_, id, _, level)| *level == best_level && *id != best_id);
+
+    if confidence >= AUTO_MATCH_CONFIDENCE && !tied {
+        return MatchDecision {
+            matched: Some(best_index),
+            best: Some(best_index),
+            confidence,
+            candidates: Vec::new(),
+        };
+    }
+
+    MatchDecision {
+        matched: None,
+        best: Some(best_index),
+        confidence,
+        candidates: scored
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|(_, id, name, level)| NameCandidate {
+                id: id.to_string(),
+                name: name.to_string(),
+                confidence: level.confidence(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalization_folds_width_case_space_and_end_markers() {
+        assert_eq!(normalize_name("ＯＮＥ　Piece  (完)"), "one piece");
+        assert_eq!(normalize_name("名字 [END]【完結】"), "名字");
+        assert_eq!(normalize_name("名字（番外）"), "名字(番外)");
+        // 只有标记本身时保留
+        assert_eq!(normalize_name("(完)"), "(完)");
+    }
+
+    #[test]
+    fn match_levels_are_ordered_by_strictness() {
+        assert_eq!(match_level(" 海贼王 ", "海贼王"), MatchLevel::Exact);
+        assert_eq!(
+            match_level("One Piece", "one  piece (完)"),
+            MatchLevel::Normalized
+        );
+        assert_eq!(
+            match_level("Spy x Family", "Family, Spy x"),
+            MatchLevel::TokenSet
+        );
+        assert_eq!(match_level("海贼王", "海贼王 番外"), MatchLevel::Substring);
+        assert_eq!(match_level("海贼王", "火影忍者"), MatchLevel::None);
+        assert_eq!(match_level("", "海贼王"), MatchLevel::None);
+
+        assert!(MatchLevel::Normalized.confidence() >= AUTO_MATCH_CONFIDENCE);
+        assert!(MatchLevel::TokenSet.confidence() < AUTO_MATCH_CONFIDENCE);
+    }
+
+    #[test]
+    fn unique_confident_match_is_chosen_automatically() {
+        let decision = choose_candidate(
+            "One Piece",
+            [
+                ("p1", "One Piece 番外"),
+                ("p2", "ONE PIECE (完)"),
+                ("p3", "Naruto"),
+            ],
+        );
+
+        assert_eq!(decision.matched, Some(1));
+        assert_eq!(decision.confidence, 0.9);
+        assert!(decision.candidates.is_empty());
+
+        // 同一项目重复出现不算并列
+        let decision = choose_candidate("海贼王", [("p1", "海贼王"), ("p1", "海贼王")]);
+        assert_eq!(decision.matched, Some(0));
+    }
+
+    #[test]
+    fn ties_and_weak_matches_are_left_to_the_user() {
+        let decision = choose_candidate(
+            "海贼王",
+            [("p1", "海贼王 番外"), ("p2", "海贼王"), ("p3", "海贼王")],
+        );
+        assert_eq!(decision.matched, None);
+        assert_eq!(decision.best, Some(1));
+
+        let ids: Vec<&str> = decision
+            .candidates
+            .iter()
+            .map(|candidate| candidate.id.as_str())
+            .collect();
+        assert_eq!(ids, ["p2", "p3", "p1"]);
+
+        let weak = choose_candidate("海贼王", [("p1", "其他"), ("p2", "海贼王 番外")]);
+        assert_eq!(
+            (weak.matched, weak.best, weak.confidence),
+            (None, Some(1), 0.5)
+        );
+
+        let many: Vec<(String, String)> = (0..8)
+            .map(|index| (format!("p{}", index), format!("海贼王 {}", index)))
+            .collect();
+        let decision = choose_candidate(
+            "海贼王",
+            many.iter().map(|(id, name)| (id.as_str(), name.as_str())),
+        );
+        assert_eq!(decision.candidates.len(), MAX_CANDIDATES);
+
+        let empty = choose_candidate("海贼王", []);
+        assert_eq!(
+            (empty.matched, empty.best, empty.confidence),
+            (None, None, 0.0)
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// 文件名自然排序：上传、缓存与导出共用同一套规则，保证页序一致
+// 连续数字按数值比较（"img2" < "img10"），全角数字 / 字母视同半角，忽略大小写，不依赖系统区域设置
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::defer::WarnDefer;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Chunk {
+    // 去掉前导零后的数字串，以及前导零个数
+    Num {
+        digits: String,
+        leading_zeros: usize,
+    },
+    Text(String),
+}
+
+// 全角 ASCII（U+FF01..U+FF5E）与全角空格折叠为半角，再转小写
+fn fold_char(c: char) -> impl Iterator<Item = char> {
+    let half = match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    };
+
+    half.to_lowercase()
+}
+
+fn chunks(name: &str) -> Vec<Chunk> {
+    let mut result = Vec::new();
+    let mut text = String::new();
+    let mut digits = String::new();
+
+    let flush_digits = |digits: &mut String, result: &mut Vec<Chunk>| {
+        if digits.is_empty() {
+            return;
+        }
+
+        let trimmed = digits.trim_start_matches('0');
+        result.push(Chunk::Num {
+            leading_zeros: digits.len() - trimmed.len(),
+            digits: trimmed.to_string(),
+        });
+        digits.clear();
+    };
+
+    for c in name.chars().flat_map(fold_char) {
+        if c.is_ascii_digit() {
+            if !text.is_empty() {
+                result.push(Chunk::Text(std::mem::take(&mut text)));
+            }
+            digits.push(c);
+        } else {
+            flush_digits(&mut digits, &mut result);
+            text.push(c);
+        }
+    }
+
+    flush_digits(&mut digits, &mut result);
+
+    if !text.is_empty() {
+        result.push(Chunk::Text(text));
+    }
+
+    result
+}
+
+fn compare_chunk(a: &Chunk, b: &Chunk) -> Ordering {
+    match (a, b) {
+        (
+            Chunk::Num {
+                digits: da,
+                leading_zeros: za,
+            },
+            Chunk::Num {
+                digits: db,
+                leading_zeros: zb,
+            },
+        ) => da
+            .len()
+            .cmp(&db.len())
+            .then_with(|| da.cmp(db))
+            // 数值相同时前导零多的排后（"1" < "01"）
+            .then_with(|| za.cmp(zb)),
+        // 数字排在文本之前
+        (Chunk::Num { .. }, Chunk::Text(_)) => Ordering::Less,
+        (Chunk::Text(_), Chunk::Num { .. }) => Ordering::Greater,
+        (Chunk::Text(ta), Chunk::Text(tb)) => ta.cmp(tb),
+    }
+}
+
+// 自然排序比较器；折叠后完全相同的名称视为相等（排序时保持原有先后）
+pub fn compare_file_names(a: &str, b: &str) -> Ordering {
+    let ka = chunks(a);
+    let kb = chunks(b);
+
+    ka.iter()
+        .zip(kb.iter())
+        .map(|(x, y)| compare_chunk(x, y))
+        .find(|ord| ord.is_ne())
+        .unwrap_or_else(|| ka.len().cmp(&kb.len()))
+}
+
+// 按自然顺序排序（稳定排序）
+pub fn sort_file_names(mut names: Vec<String>) -> Vec<String> {
+    names.sort_by(|a, b| compare_file_names(a, b));
+    names
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SortFileNamesReq {
+    pub names: Vec<String>,
+}
+
+// 供前端多文件上传等场景使用，保证与后端的页序规则一致
+#[tauri::command]
+pub async fn sort_file_names_natural(payload: SortFileNamesReq) -> Result<Vec<String>, String> {
+    tracing::info!(count = payload.names.len(), "natsort.sort.start");
+
+    let mut defer = WarnDefer::new("natsort.sort");
+
+    let sorted = sort_file_names(payload.names);
+
+    tracing::info!(count = sorted.len(), "natsort.sort.ok");
+
+    defer.success();
+
+    Ok(sorted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(names: &[&str]) -> Vec<String> {
+        sort_file_names(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn digit_runs_compare_by_value() {
+        assert_eq!(
+            sorted(&["img10.png", "img2.png", "img1.png", "img100.png"]),
+            ["img1.png", "img2.png", "img10.png", "img100.png"]
+        );
+        assert_eq!(
+            sorted(&["ch2_p10", "ch10_p1", "ch2_p9"]),
+            ["ch2_p9", "ch2_p10", "ch10_p1"]
+        );
+        // 超出整数范围的长数字串也按数值比较
+        assert_eq!(
+            compare_file_names("p99999999999999999999999", "p100000000000000000000000"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn leading_zeros_break_ties_after_the_value() {
+        assert_eq!(sorted(&["01", "1", "001", "2"]), ["1", "01", "001", "2"]);
+        assert_eq!(compare_file_names("007", "8"), Ordering::Less);
+    }
+
+    #[test]
+    fn full_width_and_case_are_folded() {
+        assert_eq!(compare_file_names("ＩＭＧ１０", "img10"), Ordering::Equal);
+        assert_eq!(compare_file_names("Page2", "page10"), Ordering::Less);
+        assert_eq!(compare_file_names("第２话", "第10话"), Ordering::Less);
+    }
+
+    #[test]
+    fn numbers_sort_before_text_and_prefixes_first() {
+        assert_eq!(sorted(&["cover", "1", "a1"]), ["1", "a1", "cover"]);
+        assert_eq!(compare_file_names("page", "page1"), Ordering::Less);
+        assert_eq!(compare_file_names("", "a"), Ordering::Less);
+    }
+
+    #[test]
+    fn equal_names_keep_their_original_order() {
+        assert_eq!(
+            sorted(&["B.png", "a.png", "b.png", "Ａ.png"]),
+            ["a.png", "Ａ.png", "B.png", "b.png"]
+        );
+    }
+}
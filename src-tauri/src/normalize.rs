@@ -0,0 +1,429 @@
+// 译文内容规范化：从 Word / 网页粘贴的文本常带零宽字符、BOM、\r\n 与行尾空白，会破坏嵌字脚本。
+// 提交 / 更新翻译前统一清理，并可按汉化组在设置中保存的规则把半角标点换成全角；
+// 返回实际做过的变换，编辑器据此提示“内容已规范化”
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::storage::{settings, LOCAL_STORAGE};
+
+// 设置表中汉化组标点规则的键前缀，完整键为 "punctuation_ruleset.<team_id>"
+const RULESET_KEY_PREFIX: &str = "punctuation_ruleset.";
+
+const EMPTY_CONTENT_CODE: &str = "empty_content";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transformation {
+    // \r\n、\r 统一为 \n
+    NormalizedLineEndings,
+    // 删除零宽字符、BOM 与除换行外的控制字符
+    StrippedInvisible,
+    // 删除每行末尾的空白
+    TrimmedTrailingWhitespace,
+    // 按规则替换了标点
+    MappedPunctuation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PunctuationRule {
+    pub from: String,
+    pub to: String,
+    // false（默认）时只替换紧跟在中日文字符之后的出现，避免改动英文与数字（如 "v1.2"、"OK, fine"）
+    #[serde(default)]
+    pub anywhere: bool,
+}
+
+// 按顺序匹配，同一位置取第一条命中的规则（较长的 from 应排在前面）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PunctuationRuleset {
+    pub rules: Vec<PunctuationRule>,
+}
+
+impl PunctuationRuleset {
+    // 默认的中文全角标点规则；弯引号统一为直角引号
+    pub fn default_cjk() -> Self {
+        let rule = |from: &str, to: &str, anywhere: bool| PunctuationRule {
+            from: from.to_string(),
+            to: to.to_string(),
+            anywhere,
+        };
+
+        Self {
+            rules: vec![
+                rule("...", "……", false),
+                rule(",", "，", false),
+                rule(".", "。", false),
+                rule("!", "！", false),
+                rule("?", "？", false),
+                rule(":", "：", false),
+                rule(";", "；", false),
+                rule("(", "（", false),
+                rule(")", "）", false),
+                rule("~", "～", false),
+                rule("\u{201C}", "「", true),
+                rule("\u{201D}", "」", true),
+                rule("\u{2018}", "『", true),
+                rule("\u{2019}", "』", true),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Normalized {
+    pub text: String,
+    // 按 Transformation 的顺序排列，不重复
+    pub applied: Vec<Transformation>,
+}
+
+// 中日文字符与全角标点（作为标点替换的上下文）
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF00}'..='\u{FFEF}'
+        | '\u{2026}')
+}
+
+// 零宽字符、BOM、软连字符，以及换行以外的控制字符
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'
+    ) || (c.is_control() && c != '\n')
+}
+
+// 规范化后为空：只含空白（含 NBSP、全角空格等 Unicode 空白）与不可见字符
+pub(crate) fn is_blank(content: &str) -> bool {
+    content
+        .chars()
+        .all(|c| c.is_whitespace() || is_invisible(c))
+}
+
+// 提交空白译文时的错误；field 为被拒绝的字段（content / proofread_content）
+pub(crate) fn empty_content_error(field: &str) -> AppError {
+    AppError::InvalidInput(
+        serde_json::json!({
+            "code": EMPTY_CONTENT_CODE,
+            "message": "内容为空或只包含空白字符",
+            "field": field,
+        })
+        .to_string(),
+    )
+}
+
+fn map_punctuation(text: &str, ruleset: &PunctuationRuleset) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let after_cjk = prev.is_some_and(is_cjk);
+
+        let hit = ruleset
+            .rules
+            .iter()
+            .filter(|rule| !rule.from.is_empty())
+            .find(|rule| (rule.anywhere || after_cjk) && rest.starts_with(rule.from.as_str()));
+
+        match hit {
+            Some(rule) => {
+                out.push_str(&rule.to);
+                rest = &rest[rule.from.len()..];
+                prev = rule.to.chars().last().or(prev);
+            }
+            None => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+                prev = Some(c);
+            }
+        }
+    }
+
+    out
+}
+
+// 依次：统一换行 -> 删除不可见字符 -> 去掉行尾空白 -> （可选）替换标点
+pub(crate) fn normalize(content: &str, ruleset: Option<&PunctuationRuleset>) -> Normalized {
+    let mut applied = Vec::new();
+
+    let text = content.replace("\r\n", "\n").replace('\r', "\n");
+    if text != content {
+        applied.push(Transformation::NormalizedLineEndings);
+    }
+
+    let stripped: String = text.chars().filter(|c| !is_invisible(*c)).collect();
+    if stripped != text {
+        applied.push(Transformation::StrippedInvisible);
+    }
+
+    let trimmed = stripped
+        .split('\n')
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if trimmed != stripped {
+        applied.push(Transformation::TrimmedTrailingWhitespace);
+    }
+
+    let text = match ruleset {
+        Some(ruleset) => {
+            let mapped = map_punctuation(&trimmed, ruleset);
+            if mapped != trimmed {
+                applied.push(Transformation::MappedPunctuation);
+            }
+            mapped
+        }
+        None => trimmed,
+    };
+
+    Normalized { text, applied }
+}
+
+fn ruleset_key(team_id: &str) -> String {
+    format!("{}{}", RULESET_KEY_PREFIX, team_id)
+}
+
+// 汉化组保存的标点规则；未设置时为 None（不替换标点）
+pub(crate) async fn team_ruleset(team_id: &str) -> Result<Option<PunctuationRuleset>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let Some(raw) = settings::get_setting(storage.pool(), &ruleset_key(team_id)).await? else {
+        return Ok(None);
+    };
+
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|err| format!("标点规则格式错误: {}", err))
+}
+
+// 提交前规范化；读取汉化组规则失败时只记录日志，仍做其余清理
+pub(crate) async fn normalize_for_submit(content: &str, team_id: Option<&str>) -> Normalized {
+    let ruleset = match team_id {
+        Some(team_id) => team_ruleset(team_id).await.unwrap_or_else(|err| {
+            tracing::warn!(team_id, error = %err, "normalize.ruleset.load_failed");
+            None
+        }),
+        None => None,
+    };
+
+    normalize(content, ruleset.as_ref())
+}
+
+// 合并多个字段的变换记录（保持顺序、去重）
+pub(crate) fn merge_applied(
+    lists: impl IntoIterator<Item = Vec<Transformation>>,
+) -> Vec<Transformation> {
+    let mut merged: Vec<Transformation> = lists.into_iter().flatten().collect();
+    merged.sort();
+    merged.dedup();
+    merged
+}
+
+// 翻译提交 / 更新的返回：在原结构上附加 normalized（未做任何变换时省略）
+#[derive(Debug, Clone, Serialize)]
+pub struct WithNormalization<T> {
+    #[serde(flatten)]
+    pub value: T,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub normalized: Vec<Transformation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NormalizeTextReq {
+    pub content: String,
+    // 不提供时不替换标点
+    #[serde(default)]
+    pub ruleset: Option<PunctuationRuleset>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NormalizeTextReply {
+    pub content: String,
+    pub applied: Vec<Transformation>,
+}
+
+// 供编辑器粘贴时预先规范化（与提交时使用同一套规则）
+#[tauri::command]
+pub async fn normalize_text(payload: NormalizeTextReq) -> Result<NormalizeTextReply, String> {
+    let normalized = normalize(&payload.content, payload.ruleset.as_ref());
+
+    Ok(NormalizeTextReply {
+        content: normalized.text,
+        applied: normalized.applied,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetPunctuationRulesetReq {
+    pub team_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PunctuationRulesetReply {
+    pub team_id: String,
+    // 未设置时为 null，提交时不替换标点
+    pub ruleset: Option<PunctuationRuleset>,
+    // 内置的默认规则，供设置界面作为起点
+    pub default_ruleset: PunctuationRuleset,
+}
+
+#[tauri::command]
+pub async fn get_punctuation_ruleset(
+    payload: GetPunctuationRulesetReq,
+) -> Result<PunctuationRulesetReply, String> {
+    let ruleset = team_ruleset(&payload.team_id).await?;
+
+    Ok(PunctuationRulesetReply {
+        team_id: payload.team_id,
+        ruleset,
+        default_ruleset: PunctuationRuleset::default_cjk(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetPunctuationRulesetReq {
+    pub team_id: String,
+    // None 表示删除，之后提交时不再替换标点
+    #[serde(default)]
+    pub ruleset: Option<PunctuationRuleset>,
+}
+
+#[tauri::command]
+pub async fn set_punctuation_ruleset(
+    payload: SetPunctuationRulesetReq,
+) -> Result<PunctuationRulesetReply, String> {
+    tracing::info!(
+        team_id = %payload.team_id,
+        rules = payload.ruleset.as_ref().map(|ruleset| ruleset.rules.len()),
+        "normalize.ruleset.set.start"
+    );
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let key = ruleset_key(&payload.team_id);
+
+    match &payload.ruleset {
+        Some(ruleset) => {
+            let raw = serde_json::to_string(ruleset)
+                .map_err(|err| format!("序列化标点规则失败: {}", err))?;
+
+            settings::save_setting(storage.pool(), &key, &raw).await?;
+        }
+        None => settings::delete_setting(storage.pool(), &key).await?,
+    }
+
+    tracing::info!(team_id = %payload.team_id, "normalize.ruleset.set.ok");
+
+    Ok(PunctuationRulesetReply {
+        team_id: payload.team_id,
+        ruleset: payload.ruleset,
+        default_ruleset: PunctuationRuleset::default_cjk(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::local_storage;
+
+    #[test]
+    fn pasted_text_is_cleaned_in_order() {
+        let normalized = normalize("\u{FEFF}第一行  \r\n第\u{200B}二行\t\r第三行", None);
+
+        assert_eq!(normalized.text, "第一行\n第二行\n第三行");
+        assert_eq!(
+            normalized.applied,
+            [
+                Transformation::NormalizedLineEndings,
+                Transformation::StrippedInvisible,
+                Transformation::TrimmedTrailingWhitespace,
+            ]
+        );
+
+        let clean = normalize("已经干净的文本", Some(&PunctuationRuleset::default_cjk()));
+        assert!(clean.applied.is_empty());
+    }
+
+    #[test]
+    fn punctuation_is_mapped_only_after_cjk() {
+        let ruleset = PunctuationRuleset::default_cjk();
+
+        let normalized = normalize(
+            "什么...真的?OK, fine v1.2 \u{201C}嗯\u{201D}",
+            Some(&ruleset),
+        );
+        assert_eq!(normalized.text, "什么……真的？OK, fine v1.2 「嗯」");
+        assert_eq!(normalized.applied, [Transformation::MappedPunctuation]);
+
+        // 替换结果作为下一个字符的上下文
+        assert_eq!(normalize("好!?", Some(&ruleset)).text, "好！？");
+    }
+
+    #[test]
+    fn blank_content_includes_invisible_and_unicode_spaces() {
+        assert!(is_blank(""));
+        assert!(is_blank(" \u{3000}\u{00A0}\u{200B}\u{FEFF}\r\n"));
+        assert!(!is_blank(" 。 "));
+
+        let AppError::InvalidInput(raw) = empty_content_error("proofread_content") else {
+            panic!("unexpected error kind");
+        };
+        let body: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(body["code"], EMPTY_CONTENT_CODE);
+        assert_eq!(body["field"], "proofread_content");
+    }
+
+    #[test]
+    fn merged_transformations_are_ordered_and_unique() {
+        let merged = merge_applied([
+            vec![
+                Transformation::MappedPunctuation,
+                Transformation::StrippedInvisible,
+            ],
+            vec![Transformation::StrippedInvisible],
+        ]);
+
+        assert_eq!(
+            merged,
+            [
+                Transformation::StrippedInvisible,
+                Transformation::MappedPunctuation
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn team_ruleset_is_saved_and_removed() {
+        local_storage().await;
+        let team_id = "normalize-team";
+
+        let saved = set_punctuation_ruleset(SetPunctuationRulesetReq {
+            team_id: team_id.to_string(),
+            ruleset: Some(PunctuationRuleset::default_cjk()),
+        })
+        .await
+        .unwrap();
+        assert_eq!(saved.ruleset, Some(PunctuationRuleset::default_cjk()));
+
+        let normalized = normalize_for_submit("好,", Some(team_id)).await;
+        assert_eq!(normalized.text, "好，");
+
+        set_punctuation_ruleset(SetPunctuationRulesetReq {
+            team_id: team_id.to_string(),
+            ruleset: None,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(team_ruleset(team_id).await.unwrap(), None);
+        assert_eq!(normalize_for_submit("好,", Some(team_id)).await.text, "好,");
+    }
+}
@@ -1,7 +1,7 @@
 use serde::Deserialize;
 use tracing::warn;
 
-use crate::http::poprako_get;
+use crate::{error::AppError, http::poprako_get};
 
 #[derive(Deserialize)]
 struct UpdateResponse {
@@ -15,7 +15,7 @@ struct UpdateData {
 
 #[tauri::command]
 pub async fn update() -> bool {
-    let result: Result<UpdateResponse, String> = poprako_get("notify/update", None).await;
+    let result: Result<UpdateResponse, AppError> = poprako_get("notify/update", None).await;
 
     match result {
         Ok(resp) => resp.data.has_update,
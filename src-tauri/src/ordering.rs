@@ -0,0 +1,242 @@
+// 阅读方向与 source 阅读顺序（导出、未翻译跳转、统计等共用同一套排序）
+use serde::{Deserialize, Serialize};
+
+use crate::project::MoetranSource;
+
+// 同一行判定的纵向容差（Moetran 坐标为 0~1 的相对值）
+const ROW_TOLERANCE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingDirection {
+    // 日漫：从上到下、从右到左
+    Rtl,
+    // 条漫以外的横排作品：从上到下、从左到右
+    Ltr,
+    // 条漫（webtoon）：仅按纵向位置
+    Vertical,
+}
+
+impl ReadingDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadingDirection::Rtl => "rtl",
+            ReadingDirection::Ltr => "ltr",
+            ReadingDirection::Vertical => "vertical",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rtl" => Some(ReadingDirection::Rtl),
+            "ltr" => Some(ReadingDirection::Ltr),
+            "vertical" => Some(ReadingDirection::Vertical),
+            _ => None,
+        }
+    }
+
+    // 根据源语言推断默认阅读方向：ja → rtl，ko、zh 及其他横排语言 → ltr；条漫需手动选择
+    pub fn default_for_language(source_language: Option<&str>) -> Self {
+        let lang = match source_language {
+            Some(lang) => lang.trim().to_ascii_lowercase(),
+            None => return ReadingDirection::Rtl,
+        };
+
+        let primary = lang.split(['-', '_']).next().unwrap_or("");
+
+        match primary {
+            "" | "ja" | "jp" => ReadingDirection::Rtl,
+            _ => ReadingDirection::Ltr,
+        }
+    }
+
+    // 已保存的偏好优先，否则按源语言推断
+    pub fn resolve(stored: Option<Self>, source_language: Option<&str>) -> Self {
+        stored.unwrap_or_else(|| Self::default_for_language(source_language))
+    }
+}
+
+// 可参与阅读顺序排序的条目（坐标为页面内相对位置）
+pub trait Positioned {
+    fn position(&self) -> (f64, f64);
+}
+
+impl Positioned for MoetranSource {
+    fn position(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+}
+
+// 按阅读方向原地排序：先按纵向分行，行内再按横向方向排序；vertical 仅按 y
+pub fn sort_sources_reading_order<T: Positioned>(sources: &mut [T], dir: ReadingDirection) {
+    sources.sort_by(|a, b| {
+        let (ax, ay) = a.position();
+        let (bx, by) = b.position();
+
+        ay.total_cmp(&by).then(ax.total_cmp(&bx))
+    });
+
+    if dir == ReadingDirection::Vertical {
+        return;
+    }
+
+    let mut row_start = 0;
+
+    while row_start < sources.len() {
+        let row_top = sources[row_start].position().1;
+
+        let mut row_end = row_start + 1;
+
+        while row_end < sources.len() && sources[row_end].position().1 - row_top <= ROW_TOLERANCE {
+            row_end += 1;
+        }
+
+        sources[row_start..row_end].sort_by(|a, b| {
+            let (ax, ay) = a.position();
+            let (bx, by) = b.position();
+
+            let horizontal = match dir {
+                ReadingDirection::Rtl => bx.total_cmp(&ax),
+                _ => ax.total_cmp(&bx),
+            };
+
+            horizontal.then(ay.total_cmp(&by))
+        });
+
+        row_start = row_end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    struct Point(&'static str, f64, f64);
+
+    impl Positioned for Point {
+        fn position(&self) -> (f64, f64) {
+            (self.1, self.2)
+        }
+    }
+
+    fn sorted(points: &[Point], dir: ReadingDirection) -> Vec<&'static str> {
+        let mut points = points.to_vec();
+        sort_sources_reading_order(&mut points, dir);
+        points.iter().map(|p| p.0).collect()
+    }
+
+    // 两行：第一行三个气泡（纵坐标略有起伏），第二行两个
+    const PAGE: [Point; 5] = [
+        Point("top-left", 0.1, 0.12),
+        Point("bottom-right", 0.8, 0.6),
+        Point("top-right", 0.9, 0.1),
+        Point("bottom-left", 0.2, 0.62),
+        Point("top-middle", 0.5, 0.14),
+    ];
+
+    #[test]
+    fn rtl_reads_rows_right_to_left() {
+        assert_eq!(
+            sorted(&PAGE, ReadingDirection::Rtl),
+            [
+                "top-right",
+                "top-middle",
+                "top-left",
+                "bottom-right",
+                "bottom-left"
+            ]
+        );
+    }
+
+    #[test]
+    fn ltr_reads_rows_left_to_right() {
+        assert_eq!(
+            sorted(&PAGE, ReadingDirection::Ltr),
+            [
+                "top-left",
+                "top-middle",
+                "top-right",
+                "bottom-left",
+                "bottom-right"
+            ]
+        );
+    }
+
+    #[test]
+    fn vertical_orders_by_y_only() {
+        assert_eq!(
+            sorted(&PAGE, ReadingDirection::Vertical),
+            [
+                "top-right",
+                "top-left",
+                "top-middle",
+                "bottom-right",
+                "bottom-left"
+            ]
+        );
+    }
+
+    #[test]
+    fn row_tolerance_is_inclusive() {
+        let points = [Point("left", 0.1, ROW_TOLERANCE), Point("right", 0.9, 0.0)];
+        assert_eq!(sorted(&points, ReadingDirection::Rtl), ["right", "left"]);
+        assert_eq!(sorted(&points, ReadingDirection::Ltr), ["left", "right"]);
+
+        // 超出容差即换行，阅读方向不再影响两者先后
+        let points = [
+            Point("left", 0.1, ROW_TOLERANCE + 0.001),
+            Point("right", 0.9, 0.0),
+        ];
+        assert_eq!(sorted(&points, ReadingDirection::Ltr), ["right", "left"]);
+    }
+
+    #[test]
+    fn row_tolerance_is_measured_from_row_top() {
+        // 逐个相差不到容差，但第三个距行首已超出，应另起一行
+        let points = [
+            Point("a", 0.9, 0.10),
+            Point("b", 0.5, 0.14),
+            Point("c", 0.1, 0.18),
+        ];
+        assert_eq!(sorted(&points, ReadingDirection::Ltr), ["b", "a", "c"]);
+        assert_eq!(sorted(&points, ReadingDirection::Rtl), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn same_x_in_row_falls_back_to_y() {
+        let points = [Point("lower", 0.5, 0.13), Point("upper", 0.5, 0.1)];
+        assert_eq!(sorted(&points, ReadingDirection::Rtl), ["upper", "lower"]);
+    }
+
+    #[test]
+    fn default_direction_follows_source_language() {
+        use ReadingDirection::*;
+
+        assert_eq!(ReadingDirection::default_for_language(None), Rtl);
+        assert_eq!(ReadingDirection::default_for_language(Some("")), Rtl);
+        assert_eq!(ReadingDirection::default_for_language(Some("ja-JP")), Rtl);
+        assert_eq!(ReadingDirection::default_for_language(Some(" JP ")), Rtl);
+        assert_eq!(ReadingDirection::default_for_language(Some("ko")), Ltr);
+        assert_eq!(ReadingDirection::default_for_language(Some("ko_KR")), Ltr);
+        assert_eq!(ReadingDirection::default_for_language(Some("zh-CN")), Ltr);
+        assert_eq!(ReadingDirection::default_for_language(Some("en")), Ltr);
+
+        assert_eq!(
+            ReadingDirection::resolve(Some(Vertical), Some("ja")),
+            Vertical
+        );
+    }
+
+    #[test]
+    fn direction_round_trips_through_str() {
+        for dir in [
+            ReadingDirection::Rtl,
+            ReadingDirection::Ltr,
+            ReadingDirection::Vertical,
+        ] {
+            assert_eq!(ReadingDirection::parse(dir.as_str()), Some(dir));
+        }
+        assert_eq!(ReadingDirection::parse("ttb"), None);
+    }
+}
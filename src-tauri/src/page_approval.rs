@@ -0,0 +1,408 @@
+// 校对“整页通过”：为页面上每个 source 选出一条翻译，标记为选定并（按团队惯例）在校对内容为空时
+// 复制译文作为校对内容。选择规则显式可配，结果逐个 source 返回
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    defer::WarnDefer,
+    project::{
+        load_page_sources, put_translation, spawn_sources_refresh, GetPageSourcesReq,
+        MoetranSource, MoetranTranslation,
+    },
+    source_snapshot::invalidate_file_snapshots,
+};
+
+// 同时提交的更新数上限
+const APPROVE_CONCURRENCY: usize = 3;
+
+// source 没有选定翻译时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalFallback {
+    // 跳过
+    Skip,
+    // 只有一条翻译时使用它，多条时跳过
+    #[default]
+    SingleOnly,
+    // 使用当前用户自己的翻译（没有则跳过）
+    Mine,
+    // 优先当前用户的翻译，其次唯一的一条
+    MineOrSingle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ApprovePageOptions {
+    // 校对内容为空时复制译文
+    #[serde(default)]
+    pub copy_content_when_empty: bool,
+    // 只处理已选定的翻译（忽略 fallback）
+    #[serde(default)]
+    pub only_selected: bool,
+    // 已有校对内容的翻译也重新写入（仅在 copy_content_when_empty 时有意义）
+    #[serde(default)]
+    pub overwrite: bool,
+    #[serde(default)]
+    pub fallback: ApprovalFallback,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum SourceApproval {
+    Approved {
+        translation_id: String,
+    },
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        translation_id: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceApprovalResult {
+    pub source_id: String,
+    #[serde(flatten)]
+    pub result: SourceApproval,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovePageReport {
+    pub file_id: String,
+    pub target_id: String,
+    pub approved: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    // 按页面顺序
+    pub sources: Vec<SourceApprovalResult>,
+}
+
+// 按规则选出要通过的翻译；返回 Err 为跳过原因
+pub(crate) fn pick_translation<'a>(
+    source: &'a MoetranSource,
+    options: &ApprovePageOptions,
+) -> Result<&'a MoetranTranslation, &'static str> {
+    // my_translation 通常也出现在 translations 中
+    let mut candidates: Vec<&MoetranTranslation> = source.translations.iter().collect();
+    if let Some(mine) = &source.my_translation {
+        if !candidates.iter().any(|t| t.id == mine.id) {
+            candidates.push(mine);
+        }
+    }
+
+    if candidates.is_empty() {
+        return Err("没有翻译");
+    }
+
+    if let Some(selected) = candidates.iter().find(|t| t.selected) {
+        return Ok(selected);
+    }
+
+    if options.only_selected {
+        return Err("没有选定的翻译");
+    }
+
+    let single = || match candidates.as_slice() {
+        [only] => Ok(*only),
+        _ => Err("有多条翻译且未选定"),
+    };
+
+    let mine = || source.my_translation.as_ref().ok_or("没有自己的翻译");
+
+    match options.fallback {
+        ApprovalFallback::Skip => Err("没有选定的翻译"),
+        ApprovalFallback::SingleOnly => single(),
+        ApprovalFallback::Mine => mine(),
+        ApprovalFallback::MineOrSingle => mine().or_else(|_| single()),
+    }
+}
+
+// 需要提交的字段；None 表示无需修改
+pub(crate) fn approval_body(
+    translation: &MoetranTranslation,
+    options: &ApprovePageOptions,
+) -> Option<Map<String, Value>> {
+    let has_proofread = translation
+        .proofread_content
+        .as_deref()
+        .is_some_and(|content| !content.trim().is_empty());
+
+    let mut body = Map::new();
+
+    if !translation.selected {
+        body.insert("selected".to_string(), Value::Bool(true));
+    }
+
+    if options.copy_content_when_empty && (!has_proofread || options.overwrite) {
+        body.insert(
+            "proofread_content".to_string(),
+            Value::String(translation.content.clone()),
+        );
+    }
+
+    (!body.is_empty()).then_some(body)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApprovePageReq {
+    pub file_id: String,
+    pub target_id: String,
+    #[serde(default)]
+    pub options: ApprovePageOptions,
+}
+
+#[tauri::command]
+pub async fn approve_page(
+    app: AppHandle,
+    payload: ApprovePageReq,
+) -> Result<ApprovePageReport, String> {
+    tracing::info!(
+        file_id = %payload.file_id,
+        target_id = %payload.target_id,
+        options = ?payload.options,
+        "moetran.page.approve.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.page.approve");
+
+    let page_req = GetPageSourcesReq {
+        file_id: payload.file_id.clone().into(),
+        target_id: payload.target_id.clone().into(),
+        reading_direction: None,
+        allow_stale: false,
+        mark_viewed: false,
+        project_id: None,
+    };
+
+    let sources = load_page_sources(&page_req).await?;
+
+    let mut results: Vec<Option<SourceApproval>> = vec![None; sources.len()];
+
+    let semaphore = Arc::new(Semaphore::new(APPROVE_CONCURRENCY));
+    let mut set = JoinSet::new();
+
+    for (index, source) in sources.iter().enumerate() {
+        let translation = match pick_translation(source, &payload.options) {
+            Ok(translation) => translation,
+            Err(reason) => {
+                results[index] = Some(SourceApproval::Skipped {
+                    reason: reason.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let Some(body) = approval_body(translation, &payload.options) else {
+            results[index] = Some(SourceApproval::Skipped {
+                reason: "已校对".to_string(),
+            });
+            continue;
+        };
+
+        let semaphore = semaphore.clone();
+        let translation_id = translation.id.to_string();
+
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+
+            let result = match put_translation(&translation_id, body).await {
+                Ok(_) => SourceApproval::Approved { translation_id },
+                Err(err) => SourceApproval::Failed {
+                    translation_id,
+                    message: err.to_string(),
+                },
+            };
+
+            (index, result)
+        });
+    }
+
+    while let Some(joined) = set.join_next().await {
+        let (index, result) = joined.map_err(|err| format!("提交任务异常: {}", err))?;
+        results[index] = Some(result);
+    }
+
+    let sources: Vec<SourceApprovalResult> = sources
+        .iter()
+        .zip(results)
+        .map(|(source, result)| SourceApprovalResult {
+            source_id: source.id.to_string(),
+            result: result.unwrap_or(SourceApproval::Skipped {
+                reason: "未处理".to_string(),
+            }),
+        })
+        .collect();
+
+    let count = |pred: fn(&SourceApproval) -> bool| {
+        sources.iter().filter(|item| pred(&item.result)).count()
+    };
+
+    let report = ApprovePageReport {
+        file_id: payload.file_id.clone(),
+        target_id: payload.target_id.clone(),
+        approved: count(|r| matches!(r, SourceApproval::Approved { .. })),
+        skipped: count(|r| matches!(r, SourceApproval::Skipped { .. })),
+        failed: count(|r| matches!(r, SourceApproval::Failed { .. })),
+        sources,
+    };
+
+    // 有改动时让已打开的编辑器拿到最新数据
+    if report.approved > 0 {
+        invalidate_file_snapshots(&payload.file_id).await;
+        spawn_sources_refresh(app, page_req);
+    }
+
+    tracing::info!(
+        file_id = %payload.file_id,
+        approved = report.approved,
+        skipped = report.skipped,
+        failed = report.failed,
+        "moetran.page.approve.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn translation(id: &str, selected: bool, proofread: Option<&str>) -> Value {
+        json!({
+            "id": id,
+            "content": format!("{} 的译文", id),
+            "proofread_content": proofread,
+            "selected": selected,
+        })
+    }
+
+    fn source(translations: Vec<Value>, mine: Option<Value>) -> MoetranSource {
+        serde_json::from_value(json!({
+            "id": "s1",
+            "x": 0.5,
+            "y": 0.5,
+            "position_type": 1,
+            "my_translation": mine,
+            "translations": translations,
+        }))
+        .unwrap()
+    }
+
+    fn with_fallback(fallback: ApprovalFallback) -> ApprovePageOptions {
+        ApprovePageOptions {
+            fallback,
+            ..Default::default()
+        }
+    }
+
+    fn picked(
+        source: &MoetranSource,
+        options: &ApprovePageOptions,
+    ) -> Result<String, &'static str> {
+        pick_translation(source, options).map(|t| t.id.to_string())
+    }
+
+    #[test]
+    fn selected_translation_always_wins() {
+        let page = source(
+            vec![
+                translation("t1", false, None),
+                translation("t2", true, None),
+            ],
+            Some(translation("t1", false, None)),
+        );
+
+        for fallback in [ApprovalFallback::Skip, ApprovalFallback::Mine] {
+            assert_eq!(picked(&page, &with_fallback(fallback)).unwrap(), "t2");
+        }
+    }
+
+    #[test]
+    fn fallbacks_apply_when_nothing_is_selected() {
+        let single = source(vec![translation("t1", false, None)], None);
+        let several = source(
+            vec![
+                translation("t1", false, None),
+                translation("t2", false, None),
+            ],
+            Some(translation("t2", false, None)),
+        );
+        let none = source(vec![], None);
+
+        assert_eq!(
+            picked(&single, &with_fallback(ApprovalFallback::SingleOnly)).unwrap(),
+            "t1"
+        );
+        assert!(picked(&several, &with_fallback(ApprovalFallback::SingleOnly)).is_err());
+        assert!(picked(&single, &with_fallback(ApprovalFallback::Skip)).is_err());
+        assert!(picked(&single, &with_fallback(ApprovalFallback::Mine)).is_err());
+        assert_eq!(
+            picked(&several, &with_fallback(ApprovalFallback::Mine)).unwrap(),
+            "t2"
+        );
+        assert_eq!(
+            picked(&single, &with_fallback(ApprovalFallback::MineOrSingle)).unwrap(),
+            "t1"
+        );
+        assert_eq!(
+            picked(&none, &ApprovePageOptions::default()),
+            Err("没有翻译")
+        );
+
+        let only_selected = ApprovePageOptions {
+            only_selected: true,
+            fallback: ApprovalFallback::MineOrSingle,
+            ..Default::default()
+        };
+        assert!(picked(&single, &only_selected).is_err());
+    }
+
+    #[test]
+    fn own_translation_missing_from_list_is_a_candidate() {
+        let page = source(vec![], Some(translation("mine", false, None)));
+
+        assert_eq!(
+            picked(&page, &with_fallback(ApprovalFallback::SingleOnly)).unwrap(),
+            "mine"
+        );
+    }
+
+    #[test]
+    fn body_only_contains_needed_changes() {
+        let parse = |value: Value| -> MoetranTranslation { serde_json::from_value(value).unwrap() };
+        let copy = ApprovePageOptions {
+            copy_content_when_empty: true,
+            ..Default::default()
+        };
+        let overwrite = ApprovePageOptions {
+            overwrite: true,
+            ..copy.clone()
+        };
+
+        let unselected = parse(translation("t1", false, Some("  ")));
+        assert_eq!(
+            Value::Object(approval_body(&unselected, &copy).unwrap()),
+            json!({ "selected": true, "proofread_content": "t1 的译文" })
+        );
+
+        let proofread = parse(translation("t2", true, Some("校对稿")));
+        assert_eq!(approval_body(&proofread, &copy), None);
+        assert_eq!(
+            approval_body(&proofread, &ApprovePageOptions::default()),
+            None
+        );
+        assert_eq!(
+            Value::Object(approval_body(&proofread, &overwrite).unwrap()),
+            json!({ "proofread_content": "t2 的译文" })
+        );
+    }
+}
@@ -0,0 +1,253 @@
+// 列表分页（PopRaKo 的 total 字段、Moetran 的 X-Pagination-Count 响应头）：统一的分页返回结构与逐页拉取全部数据的辅助函数
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+// PopRaKo 列表型返回包裹：data 为列表，部分接口会附带 total
+#[derive(Debug, Deserialize)]
+pub struct PoprakoListEnvelope<T> {
+    pub code: u16,
+    pub data: Option<Vec<T>>,
+    pub message: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+}
+
+impl<T> PoprakoListEnvelope<T> {
+    // 校验 code 并转换为分页结构
+    pub fn into_page(self, page: u32, limit: u32) -> Result<Page<T>, String> {
+        if self.code != 200 {
+            return Err(self.message.unwrap_or_else(|| "Unknown error".to_string()));
+        }
+
+        Ok(Page::new(
+            self.data.unwrap_or_default(),
+            self.total,
+            page,
+            limit,
+        ))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    // 服务端未提供且无法推断时为 None
+    pub total: Option<u64>,
+    pub page: u32,
+    pub limit: u32,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: Option<u64>, page: u32, limit: u32) -> Self {
+        let seen = (page.saturating_sub(1) as u64) * limit as u64 + items.len() as u64;
+
+        // 服务端给出 total 时以其为准；否则本页不满即为最后一页，可推断 total
+        let (total, has_more) = match total {
+            Some(total) => (Some(total), seen < total),
+            None if (items.len() as u64) < limit as u64 => (Some(seen), false),
+            None => (None, true),
+        };
+
+        Self {
+            items,
+            total,
+            page,
+            limit,
+            has_more,
+        }
+    }
+
+    // 拆分为列表与分页信息（列表另行加工后再附上分页信息时使用）
+    pub fn into_parts(self) -> (Vec<T>, PageMeta) {
+        let meta = PageMeta {
+            total: self.total,
+            page: self.page,
+            limit: self.limit,
+            has_more: self.has_more,
+        };
+
+        (self.items, meta)
+    }
+}
+
+// Page 去掉列表后的分页信息，含义同 Page 的对应字段
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PageMeta {
+    pub total: Option<u64>,
+    pub page: u32,
+    pub limit: u32,
+    pub has_more: bool,
+}
+
+// drain_pages_with_total 的结果
+#[derive(Debug)]
+pub struct Drained<T> {
+    pub items: Vec<T>,
+    // 最后一次取到的服务端总数（未提供时为 None）
+    pub total: Option<u64>,
+    // 达到 max_items 上限而服务端仍有更多数据
+    pub truncated: bool,
+}
+
+// 逐页拉取直到没有更多数据或达到 max_items 上限（防止服务端异常导致无限翻页）
+pub(crate) async fn drain_pages<T, F, Fut>(
+    limit: u32,
+    max_items: usize,
+    fetch_page: F,
+) -> Result<Vec<T>, String>
+where
+    F: FnMut(u32, u32) -> Fut,
+    Fut: Future<Output = Result<Page<T>, String>>,
+{
+    Ok(drain_pages_with_total(limit, max_items, fetch_page)
+        .await?
+        .items)
+}
+
+// 同 drain_pages，并返回服务端总数与是否被截断
+pub(crate) async fn drain_pages_with_total<T, F, Fut>(
+    limit: u32,
+    max_items: usize,
+    mut fetch_page: F,
+) -> Result<Drained<T>, String>
+where
+    F: FnMut(u32, u32) -> Fut,
+    Fut: Future<Output = Result<Page<T>, String>>,
+{
+    let mut all = Vec::new();
+    let mut total = None;
+    let mut truncated = false;
+    let mut page = 1;
+
+    loop {
+        let current = fetch_page(page, limit).await?;
+
+        let fetched = current.items.len();
+        let has_more = current.has_more;
+
+        total = current.total.or(total);
+        all.extend(current.items);
+
+        if all.len() >= max_items {
+            truncated = all.len() > max_items || (has_more && fetched > 0);
+
+            if truncated {
+                tracing::warn!(max_items, ?total, "pagination.drain.capped");
+            }
+
+            all.truncate(max_items);
+            break;
+        }
+
+        if !has_more || fetched == 0 {
+            break;
+        }
+
+        page += 1;
+    }
+
+    Ok(Drained {
+        items: all,
+        total,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 模拟服务端：共 total 条，按 page/limit 切片
+    async fn serve(
+        total: u32,
+        report_total: bool,
+        page: u32,
+        limit: u32,
+    ) -> Result<Page<u32>, String> {
+        let start = (page - 1) * limit;
+        let items: Vec<u32> = (start..total.min(start + limit)).collect();
+
+        Ok(Page::new(
+            items,
+            report_total.then_some(total as u64),
+            page,
+            limit,
+        ))
+    }
+
+    #[test]
+    fn page_infers_total_from_short_last_page() {
+        let page = Page::new(vec![1, 2, 3], None, 3, 10);
+        assert_eq!(page.total, Some(23));
+        assert!(!page.has_more);
+
+        let page = Page::new(vec![0; 10], None, 1, 10);
+        assert_eq!(page.total, None);
+        assert!(page.has_more);
+
+        let page = Page::new(vec![0; 10], Some(20), 2, 10);
+        assert!(!page.has_more);
+    }
+
+    #[tokio::test]
+    async fn drains_all_pages_in_order() {
+        let mut requested = Vec::new();
+
+        let drained = drain_pages_with_total(10, 100, |page, limit| {
+            requested.push(page);
+            serve(25, true, page, limit)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(requested, [1, 2, 3]);
+        assert_eq!(drained.items, (0..25).collect::<Vec<_>>());
+        assert_eq!(drained.total, Some(25));
+        assert!(!drained.truncated);
+    }
+
+    #[tokio::test]
+    async fn stops_at_cap_and_reports_truncation() {
+        let drained = drain_pages_with_total(10, 15, |page, limit| serve(40, true, page, limit))
+            .await
+            .unwrap();
+
+        assert_eq!(drained.items.len(), 15);
+        assert_eq!(drained.total, Some(40));
+        assert!(drained.truncated);
+
+        // 恰好取完时不算截断
+        let drained = drain_pages_with_total(10, 20, |page, limit| serve(20, true, page, limit))
+            .await
+            .unwrap();
+
+        assert_eq!(drained.items.len(), 20);
+        assert!(!drained.truncated);
+    }
+
+    #[tokio::test]
+    async fn stops_on_empty_page_without_total() {
+        let drained = drain_pages_with_total(10, 100, |page, limit| serve(20, false, page, limit))
+            .await
+            .unwrap();
+
+        assert_eq!(drained.items.len(), 20);
+        assert_eq!(drained.total, Some(20));
+    }
+
+    #[tokio::test]
+    async fn propagates_page_errors() {
+        let result = drain_pages(10, 100, |page, limit| async move {
+            if page == 2 {
+                Err("boom".to_string())
+            } else {
+                serve(30, true, page, limit).await
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err(), "boom");
+    }
+}
@@ -0,0 +1,432 @@
+// PopRaKo 服务端健康状态（自建 PopRaKo 的状态徽章）：无需登录调用 health（或 meta/health），
+// 宽松解析 status / version / uptime_seconds / components，结果短暂缓存；由 connectivity 的后台检查
+// 定时刷新，状态变化时发送 "poprako-health-changed" 事件。旧版服务端没有该接口时返回 unknown（supported = false），
+// 并在一段时间内不再探测
+use std::{
+    collections::BTreeMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::{
+    clock::unix_now,
+    config::config,
+    error::AppError,
+    events::{emit_event, PoprakoHealthChanged},
+    http::poprako_get_public,
+};
+
+// 依次尝试的路径
+const HEALTH_PATHS: [&str; 2] = ["health", "meta/health"];
+
+// 缓存时间；命令在此期间直接返回上次结果
+const HEALTH_CACHE_TTL: Duration = Duration::from_secs(15);
+
+// 服务端不支持时，隔这么久再探测一次（服务端可能已升级）
+const UNSUPPORTED_RECHECK: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    // 尚未检查、服务端不支持或返回内容无法识别
+    Unknown,
+    Up,
+    // 服务端可达，但自报部分组件异常
+    Degraded,
+    // 服务端自报不可用，或返回维护页 / 5xx
+    Down,
+    // 请求未能送达（与服务端自报的异常区分）
+    Unreachable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PoprakoHealth {
+    pub state: HealthState,
+    // 服务端是否提供健康检查接口；false 时 state 为 unknown
+    pub supported: bool,
+    pub version: Option<String>,
+    pub uptime_seconds: Option<u64>,
+    // 组件名 -> 状态（如 db、queue），原样保留服务端的状态文字
+    pub components: BTreeMap<String, String>,
+    // 队列深度等数值指标
+    pub metrics: BTreeMap<String, i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub checked_at: i64,
+}
+
+struct CachedHealth {
+    health: PoprakoHealth,
+    at: Instant,
+}
+
+static HEALTH: RwLock<Option<CachedHealth>> = RwLock::new(None);
+
+fn parse_state(status: &str) -> HealthState {
+    match status.trim().to_ascii_lowercase().as_str() {
+        "ok" | "up" | "healthy" | "pass" | "green" => HealthState::Up,
+        "degraded" | "warn" | "warning" | "partial" | "yellow" => HealthState::Degraded,
+        "down" | "error" | "fail" | "failed" | "unhealthy" | "red" => HealthState::Down,
+        _ => HealthState::Unknown,
+    }
+}
+
+// 组件状态可能是字符串、布尔，或带 status 字段的对象
+fn component_status(value: &Value) -> Option<String> {
+    match value {
+        Value::String(status) => Some(status.clone()),
+        Value::Bool(true) => Some("ok".to_string()),
+        Value::Bool(false) => Some("down".to_string()),
+        Value::Object(map) => map.get("status").and_then(component_status),
+        _ => None,
+    }
+}
+
+fn health_of(state: HealthState, supported: bool, reason: Option<String>) -> PoprakoHealth {
+    PoprakoHealth {
+        state,
+        supported,
+        version: None,
+        uptime_seconds: None,
+        components: BTreeMap::new(),
+        metrics: BTreeMap::new(),
+        reason,
+        checked_at: unix_now(),
+    }
+}
+
+// 宽松解析：允许 { code, data } 包裹；没有总体状态时由组件状态推断
+fn parse_health(body: &Value) -> PoprakoHealth {
+    let body = body
+        .get("data")
+        .filter(|data| data.is_object())
+        .unwrap_or(body);
+
+    let mut health = health_of(HealthState::Unknown, true, None);
+
+    health.version = body
+        .get("version")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    health.uptime_seconds = ["uptime_seconds", "uptime"]
+        .iter()
+        .find_map(|key| body.get(*key)?.as_u64());
+
+    if let Some(components) = body
+        .get("components")
+        .or_else(|| body.get("checks"))
+        .and_then(Value::as_object)
+    {
+        for (name, value) in components {
+            if let Some(status) = component_status(value) {
+                health.components.insert(name.clone(), status);
+            }
+        }
+    }
+
+    if let Some(metrics) = body.get("metrics").and_then(Value::as_object) {
+        for (name, value) in metrics {
+            if let Some(value) = value.as_i64() {
+                health.metrics.insert(name.clone(), value);
+            }
+        }
+    }
+
+    if let Some(depth) = body.get("queue_depth").and_then(Value::as_i64) {
+        health.metrics.insert("queue_depth".to_string(), depth);
+    }
+
+    let overall = body
+        .get("status")
+        .and_then(Value::as_str)
+        .map(parse_state)
+        .unwrap_or(HealthState::Unknown);
+
+    let component_states: Vec<HealthState> = health
+        .components
+        .values()
+        .map(|status| parse_state(status))
+        .collect();
+
+    health.state = match overall {
+        // 总体正常但有组件异常时降级
+        HealthState::Up
+            if component_states
+                .iter()
+                .any(|state| matches!(state, HealthState::Degraded | HealthState::Down)) =>
+        {
+            HealthState::Degraded
+        }
+        HealthState::Unknown if !component_states.is_empty() => {
+            if component_states
+                .iter()
+                .all(|state| *state == HealthState::Up)
+            {
+                HealthState::Up
+            } else if component_states
+                .iter()
+                .all(|state| *state == HealthState::Down)
+            {
+                HealthState::Down
+            } else {
+                HealthState::Degraded
+            }
+        }
+        state => state,
+    };
+
+    health
+}
+
+async fn probe() -> PoprakoHealth {
+    for path in HEALTH_PATHS {
+        let err = match poprako_get_public::<Value>(path).await {
+            Ok(body) => return parse_health(&body),
+            Err(err) => err,
+        };
+
+        match err {
+            AppError::PoprakoHttp {
+                status: 404 | 405, ..
+            } => continue,
+            AppError::PoprakoHttp {
+                status: 500..=599, ..
+            } => {
+                return health_of(HealthState::Down, true, Some(err.to_string()));
+            }
+            AppError::ServiceUnavailable { .. } => {
+                return health_of(HealthState::Down, true, Some(err.to_string()));
+            }
+            AppError::Network(reason) => {
+                return health_of(HealthState::Unreachable, true, Some(reason));
+            }
+            err => return health_of(HealthState::Unknown, true, Some(err.to_string())),
+        }
+    }
+
+    health_of(
+        HealthState::Unknown,
+        false,
+        Some("服务端未提供健康检查接口".to_string()),
+    )
+}
+
+// 缓存仍有效时返回；不支持的服务端按较长的重新检查间隔计
+fn cached() -> Option<PoprakoHealth> {
+    let guard = HEALTH.read().ok()?;
+    let cached = guard.as_ref()?;
+
+    let ttl = if !cached.health.supported {
+        UNSUPPORTED_RECHECK
+    } else {
+        HEALTH_CACHE_TTL
+    };
+
+    (cached.at.elapsed() < ttl).then(|| cached.health.clone())
+}
+
+// 最近一次的健康状态（不发请求）；供连通性查询区分“服务端自报降级”与“不可达”
+pub(crate) fn current_state() -> HealthState {
+    HEALTH
+        .read()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|cached| cached.health.state))
+        .unwrap_or(HealthState::Unknown)
+}
+
+// 探测并更新缓存；状态变化时返回新结果供发送事件
+async fn refresh() -> (PoprakoHealth, bool) {
+    let health = probe().await;
+
+    let mut changed = false;
+
+    if let Ok(mut guard) = HEALTH.write() {
+        let previous = guard.as_ref().map(|cached| cached.health.state);
+        changed = previous.unwrap_or(HealthState::Unknown) != health.state;
+
+        if changed {
+            tracing::info!(?previous, next = ?health.state, "poprako.health.changed");
+        }
+
+        *guard = Some(CachedHealth {
+            health: health.clone(),
+            at: Instant::now(),
+        });
+    }
+
+    (health, changed)
+}
+
+#[tauri::command]
+pub async fn get_poprako_health(app: AppHandle) -> Result<PoprakoHealth, String> {
+    if let Some(health) = cached() {
+        return Ok(health);
+    }
+
+    // 离线模式下不发请求，返回上次的结果
+    if config().offline_mode {
+        return Ok(HEALTH
+            .read()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|cached| cached.health.clone()))
+            .unwrap_or_else(|| health_of(HealthState::Unknown, true, None)));
+    }
+
+    let (health, changed) = refresh().await;
+
+    if changed {
+        emit_event(&app, PoprakoHealthChanged(health.clone()));
+    }
+
+    Ok(health)
+}
+
+// 缓存过期时重新探测（由 connectivity 的后台检查调用）；离线模式下、以及服务端不支持时
+// （在重新检查间隔内）跳过。状态变化时返回新结果供发送事件
+pub(crate) async fn refresh_if_due() -> Option<PoprakoHealth> {
+    if config().offline_mode || cached().is_some() {
+        return None;
+    }
+
+    let (health, changed) = refresh().await;
+
+    changed.then_some(health)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    #[test]
+    fn healthy_payload_is_parsed_leniently() {
+        let health = parse_health(&json!({
+            "code": 200,
+            "data": {
+                "status": "OK",
+                "version": "1.4.2",
+                "uptime": 3600,
+                "components": { "db": "ok", "queue": { "status": "up" }, "cache": true },
+                "metrics": { "jobs": 3, "ratio": 0.5 },
+                "queue_depth": 12,
+            },
+        }));
+
+        assert_eq!(health.state, HealthState::Up);
+        assert!(health.supported);
+        assert_eq!(health.version.as_deref(), Some("1.4.2"));
+        assert_eq!(health.uptime_seconds, Some(3600));
+        assert_eq!(health.components["queue"], "up");
+        assert_eq!(health.components["cache"], "ok");
+        assert_eq!(
+            health.metrics,
+            BTreeMap::from([("jobs".to_string(), 3), ("queue_depth".to_string(), 12)])
+        );
+    }
+
+    #[test]
+    fn degraded_payloads_are_recognised() {
+        // 总体正常但有组件异常
+        let health = parse_health(&json!({
+            "status": "up",
+            "checks": { "db": "ok", "queue": false },
+        }));
+        assert_eq!(health.state, HealthState::Degraded);
+        assert_eq!(health.components["queue"], "down");
+
+        assert_eq!(
+            parse_health(&json!({ "status": "warning" })).state,
+            HealthState::Degraded
+        );
+
+        // 没有总体状态时由组件推断
+        let by_components = |db: &str, queue: &str| {
+            parse_health(&json!({ "components": { "db": db, "queue": queue } })).state
+        };
+        assert_eq!(by_components("ok", "healthy"), HealthState::Up);
+        assert_eq!(by_components("ok", "fail"), HealthState::Degraded);
+        assert_eq!(by_components("down", "red"), HealthState::Down);
+
+        assert_eq!(
+            parse_health(&json!({ "status": "rebooting" })).state,
+            HealthState::Unknown
+        );
+    }
+
+    fn reset() {
+        *HEALTH.write().unwrap() = None;
+    }
+
+    #[tokio::test]
+    async fn missing_endpoint_is_unsupported_not_an_error() {
+        let backends = MockBackends::start().await;
+        reset();
+
+        for health_path in ["/v1/health", "/v1/meta/health"] {
+            Mock::given(method("GET"))
+                .and(path(health_path))
+                .respond_with(ResponseTemplate::new(404))
+                .expect(1)
+                .mount(&backends.poprako)
+                .await;
+        }
+
+        let (health, changed) = refresh().await;
+
+        assert_eq!(health.state, HealthState::Unknown);
+        assert!(!health.supported);
+        assert!(!changed);
+
+        // 在重新检查间隔内不再探测
+        assert_eq!(refresh_if_due().await, None);
+        assert_eq!(current_state(), HealthState::Unknown);
+
+        reset();
+    }
+
+    #[tokio::test]
+    async fn server_errors_and_fallback_path_are_classified() {
+        let backends = MockBackends::start().await;
+        reset();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(405))
+            .mount(&backends.poprako)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/meta/health"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "degraded" })))
+            .mount(&backends.poprako)
+            .await;
+
+        assert_eq!(refresh_if_due().await.unwrap().state, HealthState::Degraded);
+        assert_eq!(current_state(), HealthState::Degraded);
+
+        backends.poprako.reset().await;
+        reset();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/health"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(json!({ "detail": "boom" })))
+            .mount(&backends.poprako)
+            .await;
+
+        let (health, _) = refresh().await;
+        assert_eq!(health.state, HealthState::Down);
+        assert!(health.supported);
+
+        reset();
+    }
+}
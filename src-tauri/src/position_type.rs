@@ -0,0 +1,152 @@
+// source 位置类型（Moetran position_type）：1 = 框内，2 = 框外
+// 读取时兼容未知取值（Unknown），写入时只允许已知取值，避免把 Moetran 网页端无法渲染的值写上去
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionType {
+    #[default]
+    Inside,
+    Outside,
+    // 其他客户端写入的未知取值，原样保留
+    Unknown(i32),
+}
+
+impl PositionType {
+    const KNOWN: [PositionType; 2] = [PositionType::Inside, PositionType::Outside];
+
+    fn key(self) -> &'static str {
+        match self {
+            PositionType::Inside => "inside",
+            PositionType::Outside => "outside",
+            PositionType::Unknown(_) => "unknown",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PositionType::Inside => "框内",
+            PositionType::Outside => "框外",
+            PositionType::Unknown(_) => "未知",
+        }
+    }
+
+    // LabelPlus 翻译稿中对应的分组名（导入 / 导出共用）
+    pub(crate) fn labelplus_group(self) -> Option<&'static str> {
+        match self {
+            PositionType::Inside => Some("框内"),
+            PositionType::Outside => Some("框外"),
+            PositionType::Unknown(_) => None,
+        }
+    }
+
+    // 写入前校验：未知取值返回明确的错误
+    pub fn ensure_writable(self) -> Result<Self, String> {
+        match self {
+            PositionType::Unknown(value) => Err(format!(
+                "不支持的 position_type: {}（仅支持 1=框内、2=框外）",
+                value
+            )),
+            known => Ok(known),
+        }
+    }
+}
+
+impl From<i32> for PositionType {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => PositionType::Inside,
+            2 => PositionType::Outside,
+            other => PositionType::Unknown(other),
+        }
+    }
+}
+
+impl From<PositionType> for i32 {
+    fn from(value: PositionType) -> Self {
+        match value {
+            PositionType::Inside => 1,
+            PositionType::Outside => 2,
+            PositionType::Unknown(other) => other,
+        }
+    }
+}
+
+impl Serialize for PositionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32((*self).into())
+    }
+}
+
+impl<'de> Deserialize<'de> for PositionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i32::deserialize(deserializer).map(PositionType::from)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionTypeInfo {
+    pub value: i32,
+    pub key: &'static str,
+    pub label: &'static str,
+    pub labelplus_group: Option<&'static str>,
+}
+
+// 返回可写入的位置类型及显示名称，前端不再硬编码
+#[tauri::command]
+pub async fn get_position_types() -> Result<Vec<PositionTypeInfo>, String> {
+    Ok(PositionType::KNOWN
+        .into_iter()
+        .map(|position| PositionTypeInfo {
+            value: position.into(),
+            key: position.key(),
+            label: position.label(),
+            labelplus_group: position.labelplus_group(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn unknown_values_round_trip_unchanged() {
+        for value in [1, 2, 0, 7, -1] {
+            let position: PositionType = serde_json::from_value(json!(value)).unwrap();
+            assert_eq!(serde_json::to_value(position).unwrap(), json!(value));
+        }
+
+        assert_eq!(PositionType::from(2), PositionType::Outside);
+        assert_eq!(PositionType::from(7), PositionType::Unknown(7));
+        assert_eq!(PositionType::default(), PositionType::Inside);
+    }
+
+    #[test]
+    fn only_known_values_are_writable() {
+        assert_eq!(
+            PositionType::Outside.ensure_writable(),
+            Ok(PositionType::Outside)
+        );
+
+        let err = PositionType::Unknown(3).ensure_writable().unwrap_err();
+        assert!(err.contains('3'));
+
+        assert_eq!(PositionType::Unknown(3).labelplus_group(), None);
+    }
+
+    #[tokio::test]
+    async fn listed_types_are_the_writable_ones() {
+        let types = get_position_types().await.unwrap();
+
+        let listed: Vec<(i32, &str, Option<&str>)> = types
+            .iter()
+            .map(|info| (info.value, info.key, info.labelplus_group))
+            .collect();
+        assert_eq!(
+            listed,
+            [(1, "inside", Some("框内")), (2, "outside", Some("框外"))]
+        );
+    }
+}
@@ -0,0 +1,472 @@
+// 偏好配置文件：汉化组向新成员分发统一的编辑器偏好。
+// 导出可运行时调整的配置项、界面偏好（会话 extra，键加 "ui." 前缀）与各组自定义的状态标签
+// （键为 "status_labels.<team_id>"）；文件带 HMAC 签名，
+// 签名密钥是应用内常量，只用于发现文件损坏 / 被截断，不提供安全性。
+// 导入时先与当前值比较：overwrite 为 false 只返回差异供确认；为 true 时逐项应用，失败的项单独报告
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+use tauri::AppHandle;
+
+use crate::{
+    clock::unix_now,
+    config::{config, runtime_tunable_keys, set_runtime_value, ConfigSource},
+    defer::WarnDefer,
+    events::{emit_event, ConfigChanged},
+    status_labels::{save_team_overrides, StatusLabel, LABELS_KEY_PREFIX},
+    storage::{settings, LOCAL_STORAGE},
+    ui_session::{load_ui_prefs, merge_ui_prefs},
+};
+
+const PROFILE_FORMAT: &str = "moetran-preferences";
+const PROFILE_VERSION: u32 = 1;
+const SIGNING_KEY: &[u8] = b"moetran-native/preferences-profile";
+const UI_PREFIX: &str = "ui.";
+// 与本机状态相关，不随配置文件分发
+const EXCLUDED_KEYS: &[&str] = &["offline_mode", "demo_mode"];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfileBody {
+    format: String,
+    version: u32,
+    exported_at: i64,
+    entries: BTreeMap<String, Value>,
+}
+
+// 落盘格式：body 以字符串保存，签名针对其原始字节
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfileFile {
+    body: String,
+    signature: String,
+}
+
+fn sign(body: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SIGNING_KEY).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    mac
+}
+
+fn encode_profile(body: &ProfileBody) -> Result<String, String> {
+    let body = serde_json::to_string(body).map_err(|err| format!("序列化偏好失败: {}", err))?;
+    let signature = general_purpose::STANDARD.encode(sign(&body).finalize().into_bytes());
+
+    serde_json::to_string_pretty(&ProfileFile { body, signature })
+        .map_err(|err| format!("序列化偏好失败: {}", err))
+}
+
+fn decode_profile(raw: &str) -> Result<ProfileBody, String> {
+    let file: ProfileFile =
+        serde_json::from_str(raw).map_err(|_| "偏好配置文件格式错误或已损坏".to_string())?;
+
+    let signature = general_purpose::STANDARD
+        .decode(&file.signature)
+        .map_err(|_| "偏好配置文件签名格式错误".to_string())?;
+
+    sign(&file.body)
+        .verify_slice(&signature)
+        .map_err(|_| "偏好配置文件已损坏（签名不匹配）".to_string())?;
+
+    let body: ProfileBody =
+        serde_json::from_str(&file.body).map_err(|_| "偏好配置文件内容格式错误".to_string())?;
+
+    if body.format != PROFILE_FORMAT {
+        return Err("不是偏好配置文件".to_string());
+    }
+
+    Ok(body)
+}
+
+fn config_keys() -> Vec<&'static str> {
+    runtime_tunable_keys()
+        .into_iter()
+        .filter(|key| !EXCLUDED_KEYS.contains(key))
+        .collect()
+}
+
+// 当前可导出的全部偏好
+async fn current_entries() -> Result<BTreeMap<String, Value>, String> {
+    let keys = config_keys();
+    let config = config();
+
+    let mut entries: BTreeMap<String, Value> = config
+        .entries()
+        .iter()
+        .filter(|entry| keys.contains(&entry.key))
+        .map(|entry| (entry.key.to_string(), Value::String(entry.value.clone())))
+        .collect();
+
+    for (key, value) in load_ui_prefs().await? {
+        entries.insert(format!("{}{}", UI_PREFIX, key), value);
+    }
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    for (key, raw) in settings::list_settings(storage.pool()).await? {
+        if !key.starts_with(LABELS_KEY_PREFIX) {
+            continue;
+        }
+
+        match serde_json::from_str::<Value>(&raw) {
+            Ok(value) => {
+                entries.insert(key, value);
+            }
+            Err(err) => tracing::warn!(key, error = %err, "preferences.status_labels.invalid"),
+        }
+    }
+
+    Ok(entries)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportPreferencesProfileReq {
+    pub path: String,
+    // 为空时导出全部偏好
+    #[serde(default)]
+    pub include_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportPreferencesProfileReply {
+    pub path: String,
+    pub exported: Vec<String>,
+    // include_keys 中不可导出或当前没有值的键
+    pub skipped: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn export_preferences_profile(
+    payload: ExportPreferencesProfileReq,
+) -> Result<ExportPreferencesProfileReply, String> {
+    tracing::info!(path = %payload.path, keys = payload.include_keys.len(), "preferences.export.start");
+
+    let mut defer = WarnDefer::new("preferences.export");
+
+    let mut entries = current_entries().await?;
+    let mut skipped = Vec::new();
+
+    if !payload.include_keys.is_empty() {
+        skipped = payload
+            .include_keys
+            .iter()
+            .filter(|key| !entries.contains_key(key.as_str()))
+            .cloned()
+            .collect();
+
+        entries.retain(|key, _| payload.include_keys.contains(key));
+    }
+
+    let exported: Vec<String> = entries.keys().cloned().collect();
+
+    let content = encode_profile(&ProfileBody {
+        format: PROFILE_FORMAT.to_string(),
+        version: PROFILE_VERSION,
+        exported_at: unix_now(),
+        entries,
+    })?;
+
+    tokio::fs::write(&payload.path, content)
+        .await
+        .map_err(|err| format!("写入偏好配置文件失败: {}", err))?;
+
+    tracing::info!(path = %payload.path, ?skipped, "preferences.export.ok");
+
+    defer.success();
+
+    Ok(ExportPreferencesProfileReply {
+        path: payload.path,
+        exported,
+        skipped,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferenceChange {
+    pub key: String,
+    pub current: Option<Value>,
+    pub incoming: Value,
+    // 该配置项由环境变量指定，导入的值不会生效
+    pub overridden_by_env: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferenceFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportPreferencesProfileReply {
+    // false 表示只预览，未修改任何值
+    pub applied: bool,
+    pub profile_version: u32,
+    pub changes: Vec<PreferenceChange>,
+    pub unchanged: usize,
+    // 本版本不认识的键（来自更新版本的配置文件），已跳过
+    pub unknown_keys: Vec<String>,
+    pub failed: Vec<PreferenceFailure>,
+}
+
+// 与当前值比较，返回差异、未变化数量与不认识的键
+async fn diff_profile(
+    body: &ProfileBody,
+) -> Result<(Vec<PreferenceChange>, usize, Vec<String>), String> {
+    let current = current_entries().await?;
+    let keys = config_keys();
+    let config = config();
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    let mut unknown = Vec::new();
+
+    for (key, incoming) in &body.entries {
+        let known = key.starts_with(UI_PREFIX)
+            || key.starts_with(LABELS_KEY_PREFIX)
+            || keys.contains(&key.as_str());
+
+        if !known {
+            unknown.push(key.clone());
+            continue;
+        }
+
+        let existing = current.get(key);
+
+        if existing == Some(incoming) {
+            unchanged += 1;
+            continue;
+        }
+
+        let overridden_by_env = config
+            .entries()
+            .iter()
+            .any(|entry| entry.key == key && entry.source == ConfigSource::Env);
+
+        changes.push(PreferenceChange {
+            key: key.clone(),
+            current: existing.cloned(),
+            incoming: incoming.clone(),
+            overridden_by_env,
+        });
+    }
+
+    Ok((changes, unchanged, unknown))
+}
+
+// 状态标签按与 set_status_labels 相同的规则校验后保存
+async fn import_status_labels(team_id: &str, value: &Value) -> Result<(), String> {
+    let labels: Vec<StatusLabel> = serde_json::from_value(value.clone())
+        .map_err(|err| format!("状态标签格式错误: {}", err))?;
+
+    save_team_overrides(team_id, &labels).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportPreferencesProfileReq {
+    pub path: String,
+    // false 时只返回与当前值的差异
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[tauri::command]
+pub async fn import_preferences_profile(
+    app: AppHandle,
+    payload: ImportPreferencesProfileReq,
+) -> Result<ImportPreferencesProfileReply, String> {
+    tracing::info!(path = %payload.path, overwrite = payload.overwrite, "preferences.import.start");
+
+    let mut defer = WarnDefer::new("preferences.import");
+
+    let raw = tokio::fs::read_to_string(&payload.path)
+        .await
+        .map_err(|err| format!("读取偏好配置文件失败: {}", err))?;
+
+    let body = decode_profile(&raw)?;
+
+    let (changes, unchanged, unknown_keys) = diff_profile(&body).await?;
+
+    if !unknown_keys.is_empty() {
+        tracing::warn!(
+            ?unknown_keys,
+            version = body.version,
+            "preferences.import.unknown_keys"
+        );
+    }
+
+    let mut reply = ImportPreferencesProfileReply {
+        applied: false,
+        profile_version: body.version,
+        changes,
+        unchanged,
+        unknown_keys,
+        failed: vec![],
+    };
+
+    if !payload.overwrite {
+        defer.success();
+        return Ok(reply);
+    }
+
+    let mut config_changed = false;
+    let mut ui_prefs = Map::new();
+
+    for change in &reply.changes {
+        if let Some(ui_key) = change.key.strip_prefix(UI_PREFIX) {
+            ui_prefs.insert(ui_key.to_string(), change.incoming.clone());
+            continue;
+        }
+
+        if let Some(team_id) = change.key.strip_prefix(LABELS_KEY_PREFIX) {
+            if let Err(error) = import_status_labels(team_id, &change.incoming).await {
+                reply.failed.push(PreferenceFailure {
+                    key: change.key.clone(),
+                    error,
+                });
+            }
+            continue;
+        }
+
+        let Some(value) = change.incoming.as_str() else {
+            reply.failed.push(PreferenceFailure {
+                key: change.key.clone(),
+                error: "值必须是字符串".to_string(),
+            });
+            continue;
+        };
+
+        match set_runtime_value(&change.key, Some(value)).await {
+            Ok(changed) => config_changed |= changed,
+            Err(error) => reply.failed.push(PreferenceFailure {
+                key: change.key.clone(),
+                error,
+            }),
+        }
+    }
+
+    if !ui_prefs.is_empty() {
+        let keys: Vec<String> = ui_prefs
+            .keys()
+            .map(|key| format!("{}{}", UI_PREFIX, key))
+            .collect();
+
+        if let Err(error) = merge_ui_prefs(ui_prefs).await {
+            reply
+                .failed
+                .extend(keys.into_iter().map(|key| PreferenceFailure {
+                    key,
+                    error: error.clone(),
+                }));
+        }
+    }
+
+    if config_changed {
+        emit_event(&app, ConfigChanged(config().entries().to_vec()));
+    }
+
+    reply.applied = true;
+
+    tracing::info!(
+        changes = reply.changes.len(),
+        failed = reply.failed.len(),
+        skipped = reply.unknown_keys.len(),
+        "preferences.import.ok"
+    );
+
+    defer.success();
+
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::{local_storage, MockBackends};
+
+    fn body(entries: Value) -> ProfileBody {
+        ProfileBody {
+            format: PROFILE_FORMAT.to_string(),
+            version: PROFILE_VERSION,
+            exported_at: 0,
+            entries: serde_json::from_value(entries).unwrap(),
+        }
+    }
+
+    #[test]
+    fn signed_profile_round_trips() {
+        let raw = encode_profile(&body(json!({ "ui.zoom": 1.5 }))).unwrap();
+
+        let decoded = decode_profile(&raw).unwrap();
+        assert_eq!(decoded.entries["ui.zoom"], json!(1.5));
+    }
+
+    #[test]
+    fn damaged_or_foreign_files_are_rejected() {
+        let raw = encode_profile(&body(json!({ "ui.zoom": 1.5 }))).unwrap();
+
+        let mut file: ProfileFile = serde_json::from_str(&raw).unwrap();
+        file.body = file.body.replace("1.5", "2.5");
+        let tampered = serde_json::to_string(&file).unwrap();
+        assert!(decode_profile(&tampered)
+            .unwrap_err()
+            .contains("签名不匹配"));
+
+        let mut foreign = body(json!({}));
+        foreign.format = "something-else".to_string();
+        assert!(decode_profile(&encode_profile(&foreign).unwrap()).is_err());
+
+        assert!(decode_profile(&raw[..raw.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn machine_local_keys_are_not_exported() {
+        let keys = config_keys();
+
+        assert!(!keys.is_empty());
+        assert!(EXCLUDED_KEYS.iter().all(|key| !keys.contains(key)));
+    }
+
+    #[tokio::test]
+    async fn exported_profile_has_no_differences_on_import() {
+        // 配置与会话是进程级的，持有 MockBackends 避免与其他测试交错
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prefs.json").to_string_lossy().to_string();
+
+        let reply = export_preferences_profile(ExportPreferencesProfileReq {
+            path: path.clone(),
+            include_keys: vec![],
+        })
+        .await
+        .unwrap();
+        assert!(reply.skipped.is_empty());
+
+        let exported = decode_profile(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let (changes, unchanged, unknown) = diff_profile(&exported).await.unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(unchanged, reply.exported.len());
+        assert!(unknown.is_empty());
+
+        let mut incoming = exported;
+        incoming
+            .entries
+            .insert("future_setting".to_string(), json!("x"));
+        incoming
+            .entries
+            .insert("ui.profile_test_key".to_string(), json!(true));
+
+        let (changes, _, unknown) = diff_profile(&incoming).await.unwrap();
+        assert_eq!(unknown, ["future_setting"]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key, "ui.profile_test_key");
+        assert_eq!(changes[0].current, None);
+    }
+}
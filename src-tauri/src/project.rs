@@ -1,16 +1,75 @@
 use crate::{
+    config::config,
+    deadline::{attach_next_deadlines, NextDeadline},
     defer::WarnDefer,
+    demo,
+    draft::{annotate_page_drafts, clear_draft_after_submit},
+    dto_check::parse_list_lenient_with_report,
+    error::AppError,
+    events::{emit_event, SourcesUpdated, UploadProgressed},
+    file_activity::{
+        project_activity, record_file_activity, record_translation_activity,
+        remember_file_projects, sort_by_recent_activity, FileAction, FileSort,
+    },
+    flexible_list::{FlexibleList, ListField},
     http::{
-        moetran_delete, moetran_get, moetran_post_opt, moetran_put_opt, poprako_get,
-        poprako_post_opt, poprako_put_opt,
+        apply_budget, ensure_online, moetran_delete, moetran_get, moetran_get_page,
+        moetran_get_raw, moetran_post_opt, moetran_put_opt, poprako_delete, poprako_delete_opt,
+        poprako_get, poprako_post_opt, poprako_put_opt, send_error, with_retry, RetryPolicy,
+    },
+    ids::{
+        FileId, MemberId, ProjectId, ProjsetId, SourceId, TargetId, TeamId, TranslationId, UserId,
+    },
+    image_cache::{manifest_file_id_for_url, sniff_extension, update_manifest_url},
+    impact_check::{ensure_delete_allowed, ImpactEntity},
+    mutation::{MutationReply, MutationResult},
+    name_guard::{guard_proj_name, guard_projset_name, CreateWithNameCheck},
+    name_match::{choose_candidate, NameCandidate},
+    natsort::compare_file_names,
+    normalize::{
+        empty_content_error, is_blank, merge_applied, normalize_for_submit, WithNormalization,
+    },
+    ordering::{sort_sources_reading_order, ReadingDirection},
+    pagination::PageMeta,
+    position_type::PositionType,
+    project_cache::CachedPage,
+    project_history::record_enriched,
+    projset_index::projset_index_report,
+    rate_limit::{moetran_limiter, poprako_limiter},
+    recent::{forget_recent_project, record_recent_open, sync_recent_with_enriched},
+    request_budget::{self, with_budget, RequestBudget},
+    retry::RetryScope,
+    source_overlay::{
+        cached_geometry, forget_geometry, forget_geometry_of_source, merge_overlay,
+        remember_geometry,
     },
+    source_snapshot::{
+        invalidate_file_snapshots, invalidate_snapshots_for, load_snapshot,
+        remember_snapshot_files, store_snapshot,
+    },
+    status_labels::{labels_for_list, StatusLabel},
+    storage::{project_prefs, publish_records, LOCAL_STORAGE},
     token::get_moetran_token,
+    token_probe::fresh_moetran_token,
+    translation_verify::{enqueue_submitted, sync_updated_content},
+    url_refresh::{is_expired_url_error, replacement_url},
+    validation::{poprako_error, ValidationErrors},
+    write_queue::{
+        is_connectivity_error, queue_write, supersede_pending, PoprakoWrite, WriteOutcome,
+    },
 };
 use base64::{engine::general_purpose, Engine as _};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, REFERER, USER_AGENT};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use std::time::Duration;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::AppHandle;
 use url::Url;
 
 // Moetran 项目集 DTO（仅用于 enriched flows）
@@ -23,7 +82,7 @@ pub struct ResProjectSet {
 // Moetran 项目 DTO（仅用于 enriched flows）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ResProject {
-    pub id: String,
+    pub id: ProjectId,
     pub name: String,
     pub source_count: u64,
     pub translated_source_count: u64,
@@ -39,7 +98,7 @@ pub struct ResProject {
 // PopRaKo 项目搜索返回的精简 DTO（参考 ProjInfoReply）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoProjInfo {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
     pub proj_name: String,
     pub projset_index: u32,
     pub translating_status: i32,
@@ -57,13 +116,15 @@ pub struct PoprakoMember {
     // PopRaKo 返回的用户 id 字段
     // Accept common upstream variants for robustness
     #[serde(alias = "userId", alias = "userid")]
-    pub user_id: String,
-    pub member_id: String,
+    pub user_id: UserId,
+    pub member_id: MemberId,
     pub username: String,
     pub is_admin: bool,
     pub is_translator: bool,
     pub is_proofreader: bool,
     pub is_typesetter: bool,
+    #[serde(default)]
+    pub is_redrawer: bool,
     pub is_principal: bool,
 }
 
@@ -80,7 +141,7 @@ pub struct PoprakoEnvelope<T> {
 pub struct PoprakoProjSetCreateReq {
     pub projset_name: String,
     pub projset_description: String,
-    pub team_id: String,
+    pub team_id: TeamId,
     pub mtr_token: String,
 }
 
@@ -93,28 +154,36 @@ pub struct PoprakoProjSetCreateData {
 // PopRaKo 项目集列表 DTO（对应 GET /projsets 返回的单项）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoProjSetInfo {
-    pub projset_id: String,
+    pub projset_id: ProjsetId,
     pub projset_name: String,
     pub projset_description: Option<String>,
     pub projset_serial: u32,
-    pub team_id: String,
+    pub team_id: TeamId,
 }
 
-// PopRaKo 项目集列表外层 data 包裹
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PoprakoProjSetListData {
-    pub projsets: Vec<PoprakoProjSetInfo>,
+// PopRaKo 项目集列表 data：{ projsets: [...] }，也兼容裸数组
+pub(crate) struct ProjSetsField;
+
+impl ListField for ProjSetsField {
+    const FIELD: &'static str = "projsets";
+}
+
+// PopRaKo 派活列表 data：裸数组，也兼容 { assigns: [...] }
+pub(crate) struct AssignsField;
+
+impl ListField for AssignsField {
+    const FIELD: &'static str = "assigns";
 }
 
 // PopRaKo 团队项目列表 DTO（对应 GET /projs 返回的单项）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoTeamProjListItem {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
     pub proj_name: String,
     #[serde(default)]
     pub description: Option<String>,
     #[serde(default)]
-    pub projset_id: Option<String>,
+    pub projset_id: Option<ProjsetId>,
     #[serde(default)]
     pub projset_serial: Option<u32>,
     #[serde(default)]
@@ -135,7 +204,7 @@ pub struct PoprakoTeamProjListItem {
 // PopRaKo 团队项目列表请求 DTO
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ListTeamShownProjectsReq {
-    pub team_id: String,
+    pub team_id: TeamId,
     #[serde(default)]
     pub page: Option<u32>,
     #[serde(default)]
@@ -145,12 +214,12 @@ pub struct ListTeamShownProjectsReq {
 // 前端纵览表格用到的项目条目
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShownProjectListItem {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
     pub proj_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub projset_id: Option<String>,
+    pub projset_id: Option<ProjsetId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub projset_serial: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -176,8 +245,8 @@ pub struct ShownProjectListItem {
 pub struct PoprakoProjCreateReq {
     pub proj_name: String,
     pub proj_description: String,
-    pub team_id: String,
-    pub projset_id: String,
+    pub team_id: TeamId,
+    pub projset_id: ProjsetId,
     pub mtr_auth: String,
     pub workset_index: i32,
     pub source_language: String,
@@ -190,7 +259,7 @@ pub struct PoprakoProjCreateReq {
 // PopRaKo 创建项目响应 data DTO
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoProjCreateData {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
     pub proj_serial: u32,
     pub projset_index: u32,
 }
@@ -198,8 +267,8 @@ pub struct PoprakoProjCreateData {
 // PopRaKo 指派成员到项目的请求 DTO
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoAssignReq {
-    pub proj_id: String,
-    pub member_id: String,
+    pub proj_id: ProjectId,
+    pub member_id: MemberId,
     pub mtr_auth: String,
     pub is_translator: bool,
     pub is_proofreader: bool,
@@ -210,7 +279,7 @@ pub struct PoprakoAssignReq {
 // enriched 项目 DTO（Moetran + PopRaKo）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ResProjectEnriched {
-    pub id: String,
+    pub id: ProjectId,
     pub name: String,
     pub source_count: u64,
     pub translated_source_count: u64,
@@ -230,46 +299,309 @@ pub struct ResProjectEnriched {
     pub members: Option<Vec<PoprakoMember>>,
     // 从 members 中提取的负责人 user id 列表（可选）
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub principals: Option<Vec<String>>,
+    pub principals: Option<Vec<UserId>>,
     // Passthrough of Moetran `role` for native projects; may be null.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub role: Option<Value>,
+    // 后端不可用时返回的本地缓存数据会标记为 stale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
+    // 未完成阶段中最近的截止时间（本地记录）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_deadline: Option<NextDeadline>,
+    // 按名称搜索富化时未能确定对应的 Moetran 项目：最可能候选的分数与候选列表，需用户确认
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_confidence: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<Vec<NameCandidate>>,
+}
+
+// 由 Moetran 项目与（可选的）PopRaKo 补充信息组装 enriched 项目
+fn build_enriched(base: &ResProject, extra: Option<&PoprakoProjInfo>) -> ResProjectEnriched {
+    ResProjectEnriched {
+        id: base.id.clone(),
+        name: base.name.clone(),
+        source_count: base.source_count,
+        translated_source_count: base.translated_source_count,
+        checked_source_count: base.checked_source_count,
+        team: base.team.clone(),
+        project_set: base.project_set.clone(),
+        has_poprako: extra.is_some(),
+        projset_index: extra.map(|e| e.projset_index),
+        translating_status: extra.map(|e| e.translating_status),
+        proofreading_status: extra.map(|e| e.proofreading_status),
+        typesetting_status: extra.map(|e| e.typesetting_status),
+        reviewing_status: extra.map(|e| e.reviewing_status),
+        is_published: extra.map(|e| e.is_published),
+        members: extra.and_then(|e| e.members.clone()),
+        principals: extra.and_then(|e| {
+            e.members.as_ref().map(|ms| {
+                ms.iter()
+                    .filter(|m| m.is_principal)
+                    .map(|m| m.user_id.clone())
+                    .collect()
+            })
+        }),
+        role: base.role.clone(),
+        stale: None,
+        next_deadline: None,
+        match_confidence: None,
+        candidates: None,
+    }
+}
+
+// 按 proj_name 搜索到的 Moetran 项目中选择对应项；无法确定时以最可能的候选组装，并附上候选列表
+fn enrich_by_name(list: &[ResProject], extra: &PoprakoProjInfo) -> Option<ResProjectEnriched> {
+    let decision = choose_candidate(
+        &extra.proj_name,
+        list.iter()
+            .map(|proj| (proj.id.as_str(), proj.name.as_str())),
+    );
+
+    if let Some(index) = decision.matched {
+        return Some(build_enriched(&list[index], Some(extra)));
+    }
+
+    let best = &list[decision.best?];
+
+    tracing::info!(
+        proj_id = %extra.proj_id,
+        candidates = list.len(),
+        confidence = decision.confidence,
+        "project.enrich_by_name.uncertain"
+    );
+
+    let mut enriched = build_enriched(best, Some(extra));
+    enriched.match_confidence = Some(decision.confidence);
+    enriched.candidates = Some(decision.candidates);
+
+    Some(enriched)
+}
+
+// 最近一次成功获取的 enriched 列表（key: 列表维度 + 分页），后端维护时作为兜底
+static ENRICHED_LIST_CACHE: LazyLock<Mutex<HashMap<String, Vec<ResProjectEnriched>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn remember_enriched_list(key: &str, list: &[ResProjectEnriched]) {
+    if let Ok(mut guard) = ENRICHED_LIST_CACHE.lock() {
+        guard.insert(key.to_string(), list.to_vec());
+    }
+}
+
+// 后端不可用时回退到本地缓存（标记 stale）：先查内存，再查 SQLite 中持久化的同一页；无缓存则返回原错误
+async fn stale_enriched_fallback(
+    page: &CachedPage,
+    err: AppError,
+    context: &str,
+) -> Result<Vec<ResProjectEnriched>, AppError> {
+    let key = page.key();
+
+    let cached = ENRICHED_LIST_CACHE
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(&key).cloned());
+
+    let cached = match cached {
+        Some(list) => Some(list),
+        None => page.load().await,
+    };
+
+    match cached {
+        Some(list) => {
+            tracing::warn!(%key, error = %err, count = list.len(), "projects_enriched.fallback.stale");
+
+            Ok(list
+                .into_iter()
+                .map(|mut item| {
+                    item.stale = Some(true);
+                    item
+                })
+                .collect())
+        }
+        None => Err(err.context(context)),
+    }
+}
+
+// 从 enriched 缓存中查找项目快照（不发起网络请求）
+pub(crate) fn cached_proj_snapshot(proj_id: &str) -> Option<ResProjectEnriched> {
+    let guard = ENRICHED_LIST_CACHE.lock().ok()?;
+
+    guard
+        .values()
+        .flat_map(|list| list.iter())
+        .find(|item| item.id == proj_id)
+        .cloned()
+}
+
+// 就地修改 enriched 缓存中的项目快照，使后续读取到的“变更前值”保持准确
+fn patch_cached_proj(proj_id: &str, patch: impl Fn(&mut ResProjectEnriched)) {
+    if let Ok(mut guard) = ENRICHED_LIST_CACHE.lock() {
+        guard
+            .values_mut()
+            .flat_map(|list| list.iter_mut())
+            .filter(|item| item.id == proj_id)
+            .for_each(&patch);
+    }
+}
+
+// 从 enriched 缓存中移除项目（项目已删除或已完结，不再出现在 status=0 的列表中）
+fn forget_cached_proj(proj_id: &str) {
+    if let Ok(mut guard) = ENRICHED_LIST_CACHE.lock() {
+        guard
+            .values_mut()
+            .for_each(|list| list.retain(|item| item.id != proj_id));
+    }
+}
+
+fn enriched_status_mut<'a>(
+    item: &'a mut ResProjectEnriched,
+    status_type: &str,
+) -> Option<&'a mut Option<i32>> {
+    match status_type {
+        "translating" => Some(&mut item.translating_status),
+        "proofreading" => Some(&mut item.proofreading_status),
+        "typesetting" => Some(&mut item.typesetting_status),
+        "reviewing" => Some(&mut item.reviewing_status),
+        _ => None,
+    }
+}
+
+// 最近从 Moetran 读取到的翻译（key: translation id），用于提供更新前的值
+const TRANSLATION_CACHE_MAX: usize = 5000;
+
+static TRANSLATION_CACHE: LazyLock<Mutex<HashMap<String, MoetranTranslation>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn remember_translations<'a>(translations: impl Iterator<Item = &'a MoetranTranslation>) {
+    if let Ok(mut guard) = TRANSLATION_CACHE.lock() {
+        for translation in translations {
+            // 简单的容量保护：超限时整体清空，缓存只是尽力而为
+            if guard.len() >= TRANSLATION_CACHE_MAX {
+                guard.clear();
+            }
+
+            guard.insert(translation.id.to_string(), translation.clone());
+        }
+    }
+}
+
+// source 上的翻译数量（key: source id），供删除前的影响检查使用
+static SOURCE_TRANSLATION_COUNTS: LazyLock<Mutex<HashMap<String, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn remember_source_translation_counts(sources: &[MoetranSource]) {
+    if let Ok(mut guard) = SOURCE_TRANSLATION_COUNTS.lock() {
+        if guard.len() >= TRANSLATION_CACHE_MAX {
+            guard.clear();
+        }
+
+        for source in sources {
+            // my_translation 通常也包含在 translations 中，取两者中较大者
+            let count = source
+                .translations
+                .len()
+                .max(source.my_translation.is_some() as usize);
+
+            guard.insert(source.id.to_string(), count);
+        }
+    }
+}
+
+pub(crate) fn cached_source_translation_count(source_id: &str) -> Option<usize> {
+    SOURCE_TRANSLATION_COUNTS
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(source_id).copied())
+}
+
+fn cached_translation(translation_id: &str) -> Option<MoetranTranslation> {
+    TRANSLATION_CACHE
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(translation_id).cloned())
 }
 
 // ========== Moetran 项目 target / files DTO（供 ProjectDetail 使用） ==========
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MoetranProjectTarget {
-    pub id: String,
+    pub id: TargetId,
     pub translated_source_count: u64,
     pub checked_source_count: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MoetranProjectFile {
-    pub id: String,
+    pub id: FileId,
     pub name: String,
     pub source_count: u64,
-    pub url: String,
+    // 缺失时为 None 并标记 broken，保留条目以免后续文件的索引整体偏移
+    pub url: Option<String>,
     pub cover_url: String,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub size_bytes: Option<u64>,
+    // Moetran 图片审核状态（原样透传数值）
+    pub safe_status: Option<i64>,
+    pub broken: bool,
+}
+
+// 上面两个 DTO 对应的严格线上格式，仅用于 DTO 校验模式下检测字段变化
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct StrictMoetranTarget {
+    id: String,
+    translated_source_count: u64,
+    checked_source_count: u64,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct StrictMoetranFile {
+    id: String,
+    name: String,
+    source_count: u64,
+    url: String,
+    cover_url: String,
+}
+
+// 按候选字段名依次读取数值，兼容数字与数字字符串
+fn pick_u64(v: &Value, keys: &[&str]) -> Option<u64> {
+    keys.iter().find_map(|key| {
+        let field = v.get(*key)?;
+        field
+            .as_u64()
+            .or_else(|| field.as_str().and_then(|s| s.trim().parse().ok()))
+    })
+}
+
+fn pick_i64(v: &Value, keys: &[&str]) -> Option<i64> {
+    keys.iter().find_map(|key| {
+        let field = v.get(*key)?;
+        field
+            .as_i64()
+            .or_else(|| field.as_str().and_then(|s| s.trim().parse().ok()))
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetProjectTargetsReq {
-    pub project_id: String,
+    pub project_id: ProjectId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetProjectFilesReq {
-    pub project_id: String,
-    pub target_id: Option<String>,
+    pub project_id: ProjectId,
+    pub target_id: Option<TargetId>,
+    #[serde(default)]
+    pub sort: Option<FileSort>,
 }
 
 // PopRaKo 项目搜索请求 DTO（与 PickProjPayload 对齐的子集）
 // 包含 proj_ids 批量查询时也需要的分页字段，避免服务端 422
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoProjSearchReq {
-    pub proj_ids: Vec<String>,
+    pub proj_ids: Vec<ProjectId>,
     pub page: u32,
     pub limit: u32,
 }
@@ -296,10 +628,10 @@ pub struct PoprakoProjFilterReq {
     pub is_published: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub member_ids: Option<Vec<String>>,
+    pub member_ids: Option<Vec<MemberId>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub projset_ids: Option<Vec<String>>,
+    pub projset_ids: Option<Vec<ProjsetId>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_start: Option<i64>,
@@ -314,34 +646,60 @@ pub struct PoprakoProjFilterReq {
 // 单一 payload: 包含 team_id 与 filter（用于 Tauri IPC）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SearchTeamProjectsEnrichedReq {
-    pub team_id: String,
+    pub team_id: TeamId,
     pub filter: PoprakoProjFilterReq,
 }
 
+// PopRaKo 对项目集名称的长度限制
+const PROJSET_NAME_MAX_CHARS: usize = 64;
+
 // 在指定团队下创建项目集（调用 PopRaKo /projset/create）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateProjsetReq {
     pub projset_name: String,
     pub projset_description: String,
-    pub team_id: String,
-    pub mtr_token: String,
+    pub team_id: TeamId,
+    // 为 true 时跳过重名拦截（仍返回近似重名警告）
+    #[serde(default)]
+    pub allow_duplicate: bool,
 }
 
 #[tauri::command]
-pub async fn create_projset(payload: CreateProjsetReq) -> Result<PoprakoProjSetCreateData, String> {
+pub async fn create_projset(
+    payload: CreateProjsetReq,
+) -> Result<CreateWithNameCheck<PoprakoProjSetCreateData>, AppError> {
     tracing::info!(
         team_id = %payload.team_id,
         projset_name = %payload.projset_name,
         "poprako.projset.create.request.start"
     );
 
+    let mut errors = ValidationErrors::new();
+    errors.require("team_id", &payload.team_id);
+    errors.require("projset_name", &payload.projset_name);
+    errors.max_chars(
+        "projset_name",
+        &payload.projset_name,
+        PROJSET_NAME_MAX_CHARS,
+    );
+    errors.check()?;
+
     let mut defer = WarnDefer::new("poprako.projset.create");
 
+    let near_matches = guard_projset_name(
+        &payload.team_id,
+        &payload.projset_name,
+        payload.allow_duplicate,
+    )
+    .await?;
+
+    let mtr_token = fresh_moetran_token().await?;
+
     let body = PoprakoProjSetCreateReq {
         projset_name: payload.projset_name,
         projset_description: payload.projset_description,
         team_id: payload.team_id,
-        mtr_token: payload.mtr_token,
+        mtr_token,
     };
 
     let reply = poprako_post_opt::<
@@ -349,7 +707,7 @@ pub async fn create_projset(payload: CreateProjsetReq) -> Result<PoprakoProjSetC
         PoprakoEnvelope<PoprakoProjSetCreateData>,
     >("projsets", Some(body))
     .await
-    .map_err(|err| format!("创建项目集失败: {}", err))?;
+    .map_err(|err| poprako_error("创建项目集失败", err))?;
 
     if reply.code != 201 {
         let msg = reply
@@ -358,7 +716,10 @@ pub async fn create_projset(payload: CreateProjsetReq) -> Result<PoprakoProjSetC
 
         tracing::info!(message = %msg, code = reply.code, "poprako.projset.create.failed");
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let data = reply
@@ -367,34 +728,172 @@ pub async fn create_projset(payload: CreateProjsetReq) -> Result<PoprakoProjSetC
 
     tracing::info!(
         projset_serial = data.projset_serial,
+        near_matches = near_matches.len(),
         "poprako.projset.create.ok"
     );
 
     defer.success();
 
-    Ok(data)
+    Ok(CreateWithNameCheck { data, near_matches })
+}
+
+// PopRaKo 删除非空项目集时返回的业务码
+const PROJSET_NOT_EMPTY_CODE: u16 = 409;
+
+// PopRaKo 修改项目集请求 DTO
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PoprakoProjSetUpdateReq {
+    pub projset_name: String,
+    pub projset_description: String,
+}
+
+// 修改项目集名称与描述（调用 PopRaKo PUT /projsets/{id}）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdatePoprakoProjsetReq {
+    pub projset_id: ProjsetId,
+    pub projset_name: String,
+    pub projset_description: String,
+}
+
+#[tauri::command]
+pub async fn update_poprako_projset(payload: UpdatePoprakoProjsetReq) -> Result<(), AppError> {
+    tracing::info!(
+        projset_id = %payload.projset_id,
+        projset_name = %payload.projset_name,
+        "poprako.projset.update.request.start"
+    );
+
+    let mut errors = ValidationErrors::new();
+    errors.require("projset_id", &payload.projset_id);
+    errors.require("projset_name", &payload.projset_name);
+    errors.max_chars(
+        "projset_name",
+        &payload.projset_name,
+        PROJSET_NAME_MAX_CHARS,
+    );
+    errors.check()?;
+
+    let mut defer = WarnDefer::new("poprako.projset.update");
+
+    let body = PoprakoProjSetUpdateReq {
+        projset_name: payload.projset_name,
+        projset_description: payload.projset_description,
+    };
+
+    let path = format!("projsets/{}", payload.projset_id);
+
+    let reply = poprako_put_opt::<PoprakoProjSetUpdateReq, Option<PoprakoEnvelope<Value>>>(
+        &path,
+        Some(body),
+    )
+    .await
+    .map_err(|err| poprako_error("修改项目集失败", err))?;
+
+    if let Some(reply) = reply.filter(|reply| !(200..300).contains(&reply.code)) {
+        let msg = reply
+            .message
+            .unwrap_or_else(|| "PopRaKo 修改项目集失败".to_string());
+
+        tracing::info!(message = %msg, code = reply.code, "poprako.projset.update.failed");
+
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
+    }
+
+    tracing::info!("poprako.projset.update.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
+// 删除项目集（调用 PopRaKo DELETE /projsets/{id}）；项目集中仍有项目时返回 code 为 409 的 PoprakoBusiness
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeletePoprakoProjsetReq {
+    pub projset_id: ProjsetId,
+}
+
+#[tauri::command]
+pub async fn delete_poprako_projset(payload: DeletePoprakoProjsetReq) -> Result<(), AppError> {
+    tracing::info!(projset_id = %payload.projset_id, "poprako.projset.delete.request.start");
+
+    let mut defer = WarnDefer::new("poprako.projset.delete");
+
+    let path = format!("projsets/{}", payload.projset_id);
+
+    let not_empty = || AppError::PoprakoBusiness {
+        code: PROJSET_NOT_EMPTY_CODE,
+        message: "项目集中仍有项目，请先移走或删除其中的项目".to_string(),
+    };
+
+    let reply = match poprako_delete::<Option<PoprakoEnvelope<Value>>>(&path).await {
+        Ok(reply) => reply,
+        // HTTP 状态码与 code 一致，非空时请求本身即以 409 失败
+        Err(err)
+            if matches!(
+                err.root(),
+                AppError::PoprakoHttp {
+                    status: PROJSET_NOT_EMPTY_CODE,
+                    ..
+                }
+            ) =>
+        {
+            tracing::info!("poprako.projset.delete.not_empty");
+            return Err(not_empty());
+        }
+        Err(err) => return Err(poprako_error("删除项目集失败", err)),
+    };
+
+    if let Some(reply) = reply.filter(|reply| !(200..300).contains(&reply.code)) {
+        if reply.code == PROJSET_NOT_EMPTY_CODE {
+            tracing::info!("poprako.projset.delete.not_empty");
+            return Err(not_empty());
+        }
+
+        let msg = reply
+            .message
+            .unwrap_or_else(|| "PopRaKo 删除项目集失败".to_string());
+
+        tracing::info!(message = %msg, code = reply.code, "poprako.projset.delete.failed");
+
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
+    }
+
+    tracing::info!("poprako.projset.delete.ok");
+
+    defer.success();
+
+    Ok(())
 }
 
 // 列出 PopRaKo 中指定团队下的项目集（调用 PopRaKo GET /projsets?team_id=）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetTeamPoprakoProjsetsReq {
-    pub team_id: String,
+    pub team_id: TeamId,
 }
 
 #[tauri::command]
 pub async fn get_team_poprako_projsets(
     payload: GetTeamPoprakoProjsetsReq,
-) -> Result<Vec<PoprakoProjSetInfo>, String> {
+) -> Result<Vec<PoprakoProjSetInfo>, AppError> {
     tracing::info!(team_id = %payload.team_id, "poprako.projsets.list.request.start");
 
     let mut defer = WarnDefer::new("poprako.projsets.list");
 
     let mut query = std::collections::HashMap::new();
-    query.insert("team_id", payload.team_id.clone());
+    query.insert("team_id", payload.team_id.to_string());
 
-    let reply = poprako_get::<PoprakoEnvelope<PoprakoProjSetListData>>("projsets", Some(&query))
-        .await
-        .map_err(|err| format!("获取 PopRaKo 项目集列表失败: {}", err))?;
+    let reply = poprako_get::<PoprakoEnvelope<FlexibleList<PoprakoProjSetInfo, ProjSetsField>>>(
+        "projsets",
+        Some(&query),
+    )
+    .await
+    .map_err(|err| err.context("获取 PopRaKo 项目集列表失败"))?;
 
     if reply.code != 200 {
         let msg = reply
@@ -403,25 +902,29 @@ pub async fn get_team_poprako_projsets(
 
         tracing::info!(message = %msg, code = reply.code, "poprako.projsets.list.failed");
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let data = reply
         .data
-        .ok_or_else(|| "PopRaKo 获取项目集列表返回空数据".to_string())?;
+        .ok_or_else(|| "PopRaKo 获取项目集列表返回空数据".to_string())?
+        .into_items("projsets");
 
-    let count = data.projsets.len();
+    let count = data.len();
     tracing::info!(team_id = %payload.team_id, count = count, "poprako.projsets.list.ok");
 
     defer.success();
 
-    Ok(data.projsets)
+    Ok(data)
 }
 
 #[tauri::command]
 pub async fn list_team_shown_projects(
     payload: ListTeamShownProjectsReq,
-) -> Result<Vec<ShownProjectListItem>, String> {
+) -> Result<Vec<ShownProjectListItem>, AppError> {
     let page = payload.page.unwrap_or(1).max(1);
     let limit = payload.limit.unwrap_or(10).clamp(1, 50);
 
@@ -435,13 +938,13 @@ pub async fn list_team_shown_projects(
     let mut defer = WarnDefer::new("poprako.team_projs.overview");
 
     let mut query = std::collections::HashMap::new();
-    query.insert("team_id", payload.team_id.clone());
+    query.insert("team_id", payload.team_id.to_string());
     query.insert("page", page.to_string());
     query.insert("limit", limit.to_string());
 
     let reply = poprako_get::<PoprakoEnvelope<Vec<PoprakoTeamProjListItem>>>("projs", Some(&query))
         .await
-        .map_err(|err| format!("获取 PopRaKo 团队项目失败: {}", err))?;
+        .map_err(|err| err.context("获取 PopRaKo 团队项目失败"))?;
 
     if reply.code != 200 {
         let msg = reply
@@ -455,7 +958,10 @@ pub async fn list_team_shown_projects(
             "poprako.team_projs.overview.failed"
         );
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let raw_items = reply.data.unwrap_or_default();
@@ -531,19 +1037,48 @@ pub async fn list_team_shown_projects(
 pub struct CreateProjReq {
     pub proj_name: String,
     pub proj_description: String,
-    pub team_id: String,
-    pub projset_id: String,
-    pub mtr_auth: String,
+    pub team_id: TeamId,
+    pub projset_id: ProjsetId,
     pub workset_index: i32,
     pub source_language: String,
     pub target_languages: Vec<String>,
     pub allow_apply_type: i32,
     pub application_check_type: i32,
     pub default_role: String,
+    // 为 true 时跳过重名拦截（仍返回近似重名警告）
+    #[serde(default)]
+    pub allow_duplicate: bool,
+    // 为 true（或 workset_index 为负数）时自动使用项目集的下一个序号
+    #[serde(default)]
+    pub auto_index: bool,
+}
+
+// PopRaKo 拒绝重复序号时返回的状态码
+const PROJ_INDEX_CONFLICT_CODE: u16 = 409;
+
+async fn post_create_proj(
+    body: &PoprakoProjCreateReq,
+) -> Result<PoprakoEnvelope<PoprakoProjCreateData>, AppError> {
+    poprako_post_opt::<&PoprakoProjCreateReq, PoprakoEnvelope<PoprakoProjCreateData>>(
+        "projs",
+        Some(body),
+    )
+    .await
+    .map_err(|err| poprako_error("创建项目失败", err))
+}
+
+async fn resolve_next_index(projset_id: &str) -> Result<i32, String> {
+    let report = projset_index_report(projset_id)
+        .await
+        .map_err(|err| format!("获取项目集序号失败: {}", err))?;
+
+    i32::try_from(report.next_index).map_err(|_| "项目集序号超出范围".to_string())
 }
 
 #[tauri::command]
-pub async fn create_proj(payload: CreateProjReq) -> Result<PoprakoProjCreateData, String> {
+pub async fn create_proj(
+    payload: CreateProjReq,
+) -> Result<CreateWithNameCheck<PoprakoProjCreateData>, AppError> {
     tracing::info!(
         team_id = %payload.team_id,
         proj_name = %payload.proj_name,
@@ -551,15 +1086,45 @@ pub async fn create_proj(payload: CreateProjReq) -> Result<PoprakoProjCreateData
         "poprako.proj.create.request.start"
     );
 
+    let mut errors = ValidationErrors::new();
+    errors.require("team_id", &payload.team_id);
+    errors.require("projset_id", &payload.projset_id);
+    errors.require("proj_name", &payload.proj_name);
+    errors.require("source_language", &payload.source_language);
+
+    if payload.target_languages.is_empty() {
+        errors.client("target_languages", "missing", "至少需要一个目标语言");
+    }
+
+    errors.check()?;
+
     let mut defer = WarnDefer::new("poprako.proj.create");
 
-    let body = PoprakoProjCreateReq {
+    let near_matches = guard_proj_name(
+        &payload.team_id,
+        &payload.projset_id,
+        &payload.proj_name,
+        payload.allow_duplicate,
+    )
+    .await?;
+
+    let auto_index = payload.auto_index || payload.workset_index < 0;
+
+    let workset_index = if auto_index {
+        resolve_next_index(&payload.projset_id).await?
+    } else {
+        payload.workset_index
+    };
+
+    let mtr_auth = fresh_moetran_token().await?;
+
+    let mut body = PoprakoProjCreateReq {
         proj_name: payload.proj_name,
         proj_description: payload.proj_description,
         team_id: payload.team_id,
         projset_id: payload.projset_id,
-        mtr_auth: payload.mtr_auth,
-        workset_index: payload.workset_index,
+        mtr_auth,
+        workset_index,
         source_language: payload.source_language,
         target_languages: payload.target_languages,
         allow_apply_type: payload.allow_apply_type,
@@ -567,12 +1132,20 @@ pub async fn create_proj(payload: CreateProjReq) -> Result<PoprakoProjCreateData
         default_role: payload.default_role,
     };
 
-    let reply = poprako_post_opt::<PoprakoProjCreateReq, PoprakoEnvelope<PoprakoProjCreateData>>(
-        "projs",
-        Some(body),
-    )
-    .await
-    .map_err(|err| format!("创建项目失败: {}", err))?;
+    let mut reply = post_create_proj(&body).await?;
+
+    // 自动编号的序号被并发创建的项目占用时，重新计算后重试一次
+    if auto_index && reply.code == PROJ_INDEX_CONFLICT_CODE {
+        tracing::info!(
+            projset_id = %body.projset_id,
+            workset_index = body.workset_index,
+            "poprako.proj.create.index_conflict"
+        );
+
+        body.workset_index = resolve_next_index(&body.projset_id).await?;
+
+        reply = post_create_proj(&body).await?;
+    }
 
     if reply.code != 201 {
         let msg = reply
@@ -581,7 +1154,10 @@ pub async fn create_proj(payload: CreateProjReq) -> Result<PoprakoProjCreateData
 
         tracing::info!(message = %msg, code = reply.code, "poprako.proj.create.failed");
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let data = reply
@@ -592,27 +1168,100 @@ pub async fn create_proj(payload: CreateProjReq) -> Result<PoprakoProjCreateData
         proj_id = %data.proj_id,
         proj_serial = data.proj_serial,
         projset_index = data.projset_index,
+        near_matches = near_matches.len(),
         "poprako.proj.create.ok"
     );
 
     defer.success();
 
-    Ok(data)
+    Ok(CreateWithNameCheck { data, near_matches })
 }
 
 // 为项目指派成员角色（调用 PopRaKo POST /projs/{proj_id}/assign）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AssignMemberReq {
-    pub proj_id: String,
-    pub member_id: String,
+    pub proj_id: ProjectId,
+    pub member_id: MemberId,
+    pub is_translator: bool,
+    pub is_proofreader: bool,
+    pub is_typesetter: bool,
+    pub is_redrawer: bool,
+    // PopRaKo 暂时不可达时是否加入离线重试队列
+    #[serde(default)]
+    pub queue_on_failure: bool,
+    // 为 true 时返回 MutationResult（含变更前的角色）
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+// 成员在项目中的角色组合（用于乐观更新的前后值）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MemberRoles {
     pub is_translator: bool,
     pub is_proofreader: bool,
     pub is_typesetter: bool,
     pub is_redrawer: bool,
 }
 
+// 成员列表中某成员的角色；不在列表中视为无任何角色
+pub(crate) fn member_roles_in(members: &[PoprakoMember], member_id: &str) -> MemberRoles {
+    members
+        .iter()
+        .find(|m| m.member_id == member_id)
+        .map(|m| MemberRoles {
+            is_translator: m.is_translator,
+            is_proofreader: m.is_proofreader,
+            is_typesetter: m.is_typesetter,
+            is_redrawer: m.is_redrawer,
+        })
+        .unwrap_or(MemberRoles {
+            is_translator: false,
+            is_proofreader: false,
+            is_typesetter: false,
+            is_redrawer: false,
+        })
+}
+
+// 从缓存的项目快照中读取成员的当前角色（项目未缓存或成员列表未知时为 None）
+fn cached_member_roles(proj_id: &str, member_id: &str) -> Option<MemberRoles> {
+    let members = cached_proj_snapshot(proj_id)?.members?;
+
+    Some(member_roles_in(&members, member_id))
+}
+
+// 执行 PopRaKo 指派请求（命令与离线重试队列共用）
+pub(crate) async fn post_proj_assign(
+    proj_id: &str,
+    member_id: &str,
+    is_translator: bool,
+    is_proofreader: bool,
+    is_typesetter: bool,
+    is_redrawer: bool,
+) -> Result<(), AppError> {
+    let moetran_token = get_moetran_token()
+        .await
+        .map_err(AppError::Other)?
+        .ok_or_else(|| AppError::Other("无法获取 Moetran Token".to_string()))?;
+
+    let body = PoprakoAssignReq {
+        proj_id: proj_id.into(),
+        member_id: member_id.into(),
+        mtr_auth: moetran_token,
+        is_translator,
+        is_proofreader,
+        is_typesetter,
+        is_redrawer,
+    };
+
+    let path = format!("projs/{}/assign", proj_id);
+
+    poprako_post_opt::<PoprakoAssignReq, ()>(&path, Some(body)).await
+}
+
 #[tauri::command]
-pub async fn assign_member_to_proj(payload: AssignMemberReq) -> Result<(), String> {
+pub async fn assign_member_to_proj(
+    payload: AssignMemberReq,
+) -> Result<MutationReply<WriteOutcome, MemberRoles>, AppError> {
     tracing::info!(
         proj_id = %payload.proj_id,
         member_id = %payload.member_id,
@@ -621,29 +1270,304 @@ pub async fn assign_member_to_proj(payload: AssignMemberReq) -> Result<(), Strin
 
     let mut defer = WarnDefer::new("poprako.proj.assign");
 
-    let moetran_token = get_moetran_token()
-        .await?
-        .ok_or_else(|| "无法获取 Moetran Token".to_string())?;
+    let previous = cached_member_roles(&payload.proj_id, &payload.member_id);
 
-    let body = PoprakoAssignReq {
-        proj_id: payload.proj_id.clone(),
-        member_id: payload.member_id.clone(),
-        mtr_auth: moetran_token,
+    let write = PoprakoWrite::Assign {
+        proj_id: payload.proj_id.to_string(),
+        member_id: payload.member_id.to_string(),
         is_translator: payload.is_translator,
         is_proofreader: payload.is_proofreader,
         is_typesetter: payload.is_typesetter,
         is_redrawer: payload.is_redrawer,
+        observed_roles: previous.clone(),
     };
 
-    let path = format!("projs/{}/assign", payload.proj_id);
+    let result = post_proj_assign(
+        &payload.proj_id,
+        &payload.member_id,
+        payload.is_translator,
+        payload.is_proofreader,
+        payload.is_typesetter,
+        payload.is_redrawer,
+    )
+    .await;
 
-    poprako_post_opt::<PoprakoAssignReq, ()>(&path, Some(body))
-        .await
-        .map_err(|err| format!("指派成员到项目失败: {}", err))?;
+    let outcome = match result {
+        Ok(()) => {
+            supersede_pending(&write).await;
+            WriteOutcome::Applied
+        }
+        Err(err) if payload.queue_on_failure && is_connectivity_error(&err) => {
+            queue_write(&write, &err).await?
+        }
+        Err(err) => return Err(err.context("指派成员到项目失败")),
+    };
 
-    tracing::info!("poprako.proj.assign.ok");
+    let roles = MemberRoles {
+        is_translator: payload.is_translator,
+        is_proofreader: payload.is_proofreader,
+        is_typesetter: payload.is_typesetter,
+        is_redrawer: payload.is_redrawer,
+    };
 
-    defer.success();
+    if matches!(outcome, WriteOutcome::Applied) {
+        patch_cached_proj(&payload.proj_id, |item| {
+            if let Some(member) = item
+                .members
+                .iter_mut()
+                .flatten()
+                .find(|m| m.member_id == payload.member_id)
+            {
+                member.is_translator = roles.is_translator;
+                member.is_proofreader = roles.is_proofreader;
+                member.is_typesetter = roles.is_typesetter;
+                member.is_redrawer = roles.is_redrawer;
+            }
+        });
+    }
+
+    tracing::info!(?outcome, "poprako.proj.assign.ok");
+
+    defer.success();
+
+    let result = MutationResult::new(roles, previous).with_outcome(outcome.clone());
+
+    Ok(MutationReply::build(payload.verbose, outcome, result))
+}
+
+// PopRaKo 取消指派的请求 DTO
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PoprakoUnassignReq {
+    pub proj_id: ProjectId,
+    pub member_id: MemberId,
+    pub mtr_auth: String,
+}
+
+// projs/{id}/assign 的 PUT / DELETE 成功时返回 204（无响应体）或 2xx 的 envelope
+fn check_assign_reply(reply: Option<PoprakoEnvelope<Value>>) -> Result<(), AppError> {
+    match reply {
+        Some(reply) if !(200..300).contains(&reply.code) => Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: reply.message.unwrap_or_else(|| "Unknown error".to_string()),
+        }),
+        _ => Ok(()),
+    }
+}
+
+// 修改成员在项目中的角色（成员须已被指派）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateProjMemberRolesReq {
+    pub proj_id: ProjectId,
+    pub member_id: MemberId,
+    pub roles: MemberRoles,
+}
+
+#[tauri::command]
+pub async fn update_proj_member_roles(
+    payload: UpdateProjMemberRolesReq,
+) -> Result<MutationResult<MemberRoles>, AppError> {
+    tracing::info!(
+        proj_id = %payload.proj_id,
+        member_id = %payload.member_id,
+        roles = ?payload.roles,
+        "poprako.proj.member_roles.update.start"
+    );
+
+    let mut defer = WarnDefer::new("poprako.proj.member_roles.update");
+
+    let previous = cached_member_roles(&payload.proj_id, &payload.member_id);
+
+    let mtr_auth = get_moetran_token()
+        .await
+        .map_err(AppError::Other)?
+        .ok_or_else(|| AppError::Other("无法获取 Moetran Token".to_string()))?;
+
+    let roles = payload.roles;
+
+    let body = PoprakoAssignReq {
+        proj_id: payload.proj_id.clone(),
+        member_id: payload.member_id.clone(),
+        mtr_auth,
+        is_translator: roles.is_translator,
+        is_proofreader: roles.is_proofreader,
+        is_typesetter: roles.is_typesetter,
+        is_redrawer: roles.is_redrawer,
+    };
+
+    let path = format!("projs/{}/assign", payload.proj_id);
+
+    poprako_put_opt::<PoprakoAssignReq, Option<PoprakoEnvelope<Value>>>(&path, Some(body))
+        .await
+        .and_then(check_assign_reply)
+        .map_err(|err| err.context("修改成员角色失败"))?;
+
+    patch_cached_proj(&payload.proj_id, |item| {
+        if let Some(member) = item
+            .members
+            .iter_mut()
+            .flatten()
+            .find(|m| m.member_id == payload.member_id)
+        {
+            member.is_translator = roles.is_translator;
+            member.is_proofreader = roles.is_proofreader;
+            member.is_typesetter = roles.is_typesetter;
+            member.is_redrawer = roles.is_redrawer;
+        }
+    });
+
+    tracing::info!("poprako.proj.member_roles.update.ok");
+
+    defer.success();
+
+    Ok(MutationResult::new(roles, previous))
+}
+
+// 把成员移出项目
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnassignMemberReq {
+    pub proj_id: ProjectId,
+    pub member_id: MemberId,
+}
+
+#[tauri::command]
+pub async fn unassign_member_from_proj(payload: UnassignMemberReq) -> Result<(), AppError> {
+    tracing::info!(
+        proj_id = %payload.proj_id,
+        member_id = %payload.member_id,
+        "poprako.proj.unassign.start"
+    );
+
+    let mut defer = WarnDefer::new("poprako.proj.unassign");
+
+    let mtr_auth = get_moetran_token()
+        .await
+        .map_err(AppError::Other)?
+        .ok_or_else(|| AppError::Other("无法获取 Moetran Token".to_string()))?;
+
+    let body = PoprakoUnassignReq {
+        proj_id: payload.proj_id.clone(),
+        member_id: payload.member_id.clone(),
+        mtr_auth,
+    };
+
+    let path = format!("projs/{}/assign", payload.proj_id);
+
+    poprako_delete_opt::<PoprakoUnassignReq, Option<PoprakoEnvelope<Value>>>(&path, Some(body))
+        .await
+        .and_then(check_assign_reply)
+        .map_err(|err| err.context("将成员移出项目失败"))?;
+
+    patch_cached_proj(&payload.proj_id, |item| {
+        if let Some(members) = item.members.as_mut() {
+            members.retain(|m| m.member_id != payload.member_id);
+        }
+    });
+
+    tracing::info!("poprako.proj.unassign.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
+// 项目中仍有未校对的原文等情况下，PopRaKo / Moetran 以 409 拒绝删除或完结
+const PROJ_CONFLICT_STATUS: u16 = 409;
+
+// 把 409（HTTP 状态码或 envelope code）转换为 Conflict，其余错误附加上下文
+fn proj_conflict_error(context: &str, err: AppError) -> AppError {
+    let detail = match err.root() {
+        AppError::PoprakoHttp { status, body } | AppError::MoetranHttp { status, body }
+            if *status == PROJ_CONFLICT_STATUS =>
+        {
+            // 响应体通常为 {"message": "..."}，无法解析时原样使用
+            serde_json::from_str::<Value>(body)
+                .ok()
+                .and_then(|value| value.get("message")?.as_str().map(str::to_string))
+                .unwrap_or_else(|| body.clone())
+        }
+        AppError::PoprakoBusiness {
+            code: PROJ_CONFLICT_STATUS,
+            message,
+        } => message.clone(),
+        _ => return err.context(context),
+    };
+
+    AppError::Conflict(format!("{}: {}", context, detail))
+}
+
+// PopRaKo 删除项目请求 DTO（附带 mtr_auth，便于后端同时清理 Moetran 侧的项目）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PoprakoProjDeleteReq {
+    pub proj_id: ProjectId,
+    pub mtr_auth: String,
+}
+
+// 删除 PopRaKo 项目；项目中仍有未校对的原文等被拒绝时返回 Conflict
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteProjReq {
+    pub proj_id: ProjectId,
+}
+
+#[tauri::command]
+pub async fn delete_proj(payload: DeleteProjReq) -> Result<(), AppError> {
+    tracing::info!(proj_id = %payload.proj_id, "poprako.proj.delete.start");
+
+    let mut defer = WarnDefer::new("poprako.proj.delete");
+
+    let mtr_auth = get_moetran_token()
+        .await
+        .map_err(AppError::Other)?
+        .ok_or_else(|| AppError::Other("无法获取 Moetran Token".to_string()))?;
+
+    let body = PoprakoProjDeleteReq {
+        proj_id: payload.proj_id.clone(),
+        mtr_auth,
+    };
+
+    let path = format!("projs/{}", payload.proj_id);
+
+    poprako_delete_opt::<PoprakoProjDeleteReq, Option<PoprakoEnvelope<Value>>>(&path, Some(body))
+        .await
+        .and_then(check_assign_reply)
+        .map_err(|err| proj_conflict_error("删除项目失败", err))?;
+
+    forget_cached_proj(&payload.proj_id);
+
+    tracing::info!(proj_id = %payload.proj_id, "poprako.proj.delete.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
+// Moetran 项目状态：0 为进行中（应用只列出该状态的项目），1 为已完结
+const MOETRAN_PROJECT_FINISHED: i32 = 1;
+
+// 把 Moetran 项目标记为已完结，使其不再出现在项目列表中；被拒绝时返回 Conflict
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveProjReq {
+    pub proj_id: ProjectId,
+}
+
+#[tauri::command]
+pub async fn archive_proj(payload: ArchiveProjReq) -> Result<(), AppError> {
+    tracing::info!(proj_id = %payload.proj_id, "moetran.project.archive.start");
+
+    let mut defer = WarnDefer::new("moetran.project.archive");
+
+    let path = format!("projects/{}", payload.proj_id);
+
+    let body = serde_json::json!({ "status": MOETRAN_PROJECT_FINISHED });
+
+    moetran_put_opt::<Value, Value>(&path, Some(body))
+        .await
+        .map_err(|err| proj_conflict_error("完结项目失败", err))?;
+
+    forget_cached_proj(&payload.proj_id);
+
+    tracing::info!(proj_id = %payload.proj_id, "moetran.project.archive.ok");
+
+    defer.success();
 
     Ok(())
 }
@@ -653,7 +1577,7 @@ pub async fn assign_member_to_proj(payload: AssignMemberReq) -> Result<(), Strin
 #[tauri::command]
 pub async fn get_project_targets(
     payload: GetProjectTargetsReq,
-) -> Result<Vec<MoetranProjectTarget>, String> {
+) -> Result<Vec<MoetranProjectTarget>, AppError> {
     tracing::info!(project_id = %payload.project_id, "moetran.project.targets.request.start");
 
     let mut defer = WarnDefer::new("moetran.project.targets");
@@ -673,13 +1597,12 @@ pub async fn get_project_targets(
         Ok(list) => list,
         Err(e) => {
             tracing::error!(project_id = %payload.project_id, %path, ?query, error = %e, "moetran.get_project_targets failed");
-            return Err(format!("获取项目 targets 失败: {}", e));
+            return Err(e.context("获取项目 targets 失败"));
         }
     };
 
-    let result: Vec<MoetranProjectTarget> = raw_list
-        .into_iter()
-        .filter_map(|v| {
+    let result: Vec<MoetranProjectTarget> =
+        parse_list_lenient_with_report::<StrictMoetranTarget, _>("项目 target", raw_list, |v| {
             let id = v.get("id")?.as_str()?.to_string();
             let translated = v
                 .get("translated_source_count")
@@ -691,12 +1614,11 @@ pub async fn get_project_targets(
                 .unwrap_or(0);
 
             Some(MoetranProjectTarget {
-                id,
+                id: id.into(),
                 translated_source_count: translated,
                 checked_source_count: checked,
             })
-        })
-        .collect();
+        });
 
     let count = result.len();
     tracing::info!(project_id = %payload.project_id, count = count, "moetran.project.targets.ok");
@@ -709,7 +1631,7 @@ pub async fn get_project_targets(
 #[tauri::command]
 pub async fn get_project_files(
     payload: GetProjectFilesReq,
-) -> Result<Vec<MoetranProjectFile>, String> {
+) -> Result<Vec<MoetranProjectFile>, AppError> {
     tracing::info!(
         project_id = %payload.project_id,
         target_id = ?payload.target_id,
@@ -723,7 +1645,7 @@ pub async fn get_project_files(
     query.insert("limit", "100000".to_string());
     query.insert("word", "".to_string());
     if let Some(t) = &payload.target_id {
-        query.insert("target", t.clone());
+        query.insert("target", t.to_string());
     }
     // 仅请求尨译项目（status=0）
     query.insert("status", "0".to_string());
@@ -735,32 +1657,50 @@ pub async fn get_project_files(
         Ok(list) => list,
         Err(e) => {
             tracing::error!(project_id = %payload.project_id, target_id = ?payload.target_id, %path, ?query, error = %e, "moetran.get_project_files failed");
-            return Err(format!("获取项目 files 失败: {}", e));
+            return Err(e.context("获取项目 files 失败"));
         }
     };
 
-    let result: Vec<MoetranProjectFile> = raw_list
-        .into_iter()
-        .filter_map(|v| {
+    let mut result: Vec<MoetranProjectFile> =
+        parse_list_lenient_with_report::<StrictMoetranFile, _>("项目文件", raw_list, |v| {
             let id = v.get("id")?.as_str()?.to_string();
             let name = v.get("name")?.as_str()?.to_string();
             let source = v.get("source_count").and_then(|x| x.as_u64()).unwrap_or(0);
-            let url = v.get("url")?.as_str()?.to_string();
+            let url = v
+                .get("url")
+                .and_then(|x| x.as_str())
+                .filter(|x| !x.is_empty())
+                .map(str::to_string);
             let cover = v
                 .get("cover_url")
                 .and_then(|x| x.as_str())
                 .unwrap_or("")
                 .to_string();
 
+            if url.is_none() {
+                tracing::warn!(file_id = %id, "moetran.get_project_files.missing_url");
+            }
+
             Some(MoetranProjectFile {
-                id,
+                id: id.into(),
                 name,
                 source_count: source,
+                broken: url.is_none(),
                 url,
                 cover_url: cover,
+                width: pick_u64(&v, &["width", "image_width"]),
+                height: pick_u64(&v, &["height", "image_height"]),
+                size_bytes: pick_u64(&v, &["file_size", "size"]),
+                safe_status: pick_i64(&v, &["safe_status"]),
             })
-        })
-        .collect();
+        });
+
+    remember_file_projects(&payload.project_id, &result);
+
+    if payload.sort == Some(FileSort::RecentActivity) {
+        let activity = project_activity(&payload.project_id, payload.target_id.as_deref()).await;
+        result = sort_by_recent_activity(result, &activity);
+    }
 
     let count = result.len();
     tracing::info!(
@@ -775,18 +1715,217 @@ pub async fn get_project_files(
     Ok(result)
 }
 
+// 按 id 查询单个 PopRaKo 项目（不存在时返回 None）
+pub(crate) async fn fetch_poprako_proj(proj_id: &str) -> Result<Option<PoprakoProjInfo>, AppError> {
+    let search_body = PoprakoProjSearchReq {
+        proj_ids: vec![proj_id.into()],
+        page: 1,
+        limit: 1,
+    };
+
+    let reply = poprako_post_opt::<PoprakoProjSearchReq, PoprakoEnvelope<Vec<PoprakoProjInfo>>>(
+        "projs/search",
+        Some(search_body),
+    )
+    .await?;
+
+    if reply.code != 200 {
+        return Err(AppError::Other(
+            reply
+                .message
+                .unwrap_or_else(|| "PopRaKo 项目搜索失败".to_string()),
+        ));
+    }
+
+    Ok(reply
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .find(|item| item.proj_id == proj_id))
+}
+
+// enriched 列表的返回：PopRaKo 补充失败时仍返回 Moetran 数据（has_poprako 均为 false），
+// 并通过 enrichment_error 告知前端显示非阻塞提示
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectsEnrichedReply {
+    pub items: Vec<ResProjectEnriched>,
+    pub enrichment_error: Option<String>,
+    // 列表中各汉化组的状态标签，按 team_id 索引
+    pub status_labels: HashMap<String, Vec<StatusLabel>>,
+    // 请求设置的 deadline_ms 已用尽，items 为部分结果（缓存数据或缺少 PopRaKo 信息）
+    pub deadline_exceeded: bool,
+    // 分页信息（总数来自 Moetran 的 X-Pagination-Count）；返回兜底缓存时为 None
+    pub pagination: Option<PageMeta>,
+}
+
+impl ProjectsEnrichedReply {
+    async fn build(items: Vec<ResProjectEnriched>, enrichment_error: Option<String>) -> Self {
+        let status_labels = labels_for_list(&items).await;
+
+        Self {
+            items,
+            enrichment_error,
+            status_labels,
+            deadline_exceeded: false,
+            pagination: None,
+        }
+    }
+
+    async fn complete(items: Vec<ResProjectEnriched>) -> Self {
+        Self::build(items, None).await
+    }
+
+    fn with_deadline_exceeded(mut self, exceeded: bool) -> Self {
+        self.deadline_exceeded = exceeded;
+        self
+    }
+
+    fn with_pagination(mut self, pagination: PageMeta) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+}
+
+// 单批 PopRaKo 项目搜索；超时、非 200、响应无法解析均返回 Err
+async fn search_enrichment_chunk(ids: Vec<ProjectId>) -> Result<Vec<PoprakoProjInfo>, AppError> {
+    // 每批按 id 精确查询，取第一页即可拿到全部结果
+    let search_body = PoprakoProjSearchReq {
+        limit: ids.len() as u32,
+        proj_ids: ids,
+        page: 1,
+    };
+
+    let reply = poprako_post_opt::<PoprakoProjSearchReq, PoprakoEnvelope<Vec<PoprakoProjInfo>>>(
+        "projs/search",
+        Some(search_body),
+    )
+    .await
+    .map_err(|err| err.context("获取 PopRaKo 项目详情失败"))?;
+
+    if reply.code != 200 {
+        let msg = reply
+            .message
+            .unwrap_or_else(|| "PopRaKo 项目搜索失败".to_string());
+
+        tracing::info!(message = %msg, code = reply.code, "poprako.projs.search.failed");
+
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
+    }
+
+    Ok(reply.data.unwrap_or_default())
+}
+
+// 同时进行的分批搜索数
+const ENRICHMENT_CHUNK_CONCURRENCY: usize = 3;
+
+// 按 Moetran 项目 id 批量获取 PopRaKo 补充信息。id 较多时按 enrichment_chunk_size 分批并发请求
+// （PopRaKo 会拒绝过长的 proj_ids），各批经 PopRaKo 的共用限速器发出；部分批次失败时返回其余批次的结果并附带错误说明，
+// 全部失败时返回 Err
+async fn fetch_enrichment(
+    ids: Vec<ProjectId>,
+) -> Result<(HashMap<ProjectId, PoprakoProjInfo>, Option<String>), AppError> {
+    let chunk_size = config().enrichment_chunk_size.max(1);
+    let chunks: Vec<Vec<ProjectId>> = ids.chunks(chunk_size).map(<[ProjectId]>::to_vec).collect();
+    let chunk_count = chunks.len();
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(ENRICHMENT_CHUNK_CONCURRENCY));
+    let budget = request_budget::current();
+    let mut set = tokio::task::JoinSet::new();
+
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+
+        // 子任务不继承 task-local 的整体时限，需重新设置
+        set.spawn(with_budget(budget, async move {
+            let _permit = semaphore.acquire_owned().await;
+
+            let result = async {
+                poprako_limiter().acquire().await?;
+                search_enrichment_chunk(chunk).await
+            }
+            .await;
+
+            (index, result)
+        }));
+    }
+
+    let mut results = Vec::with_capacity(chunk_count);
+
+    while let Some(joined) = set.join_next().await {
+        results.push(
+            joined.map_err(|err| AppError::Other(format!("PopRaKo 项目搜索任务异常: {}", err)))?,
+        );
+    }
+
+    // 按批次顺序合并，同一项目出现在多个批次时以后者为准
+    results.sort_by_key(|(index, _)| *index);
+
+    let mut map = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (index, result) in results {
+        match result {
+            Ok(items) => {
+                for item in items {
+                    let proj_id = item.proj_id.clone();
+
+                    if map.insert(proj_id.clone(), item).is_some() {
+                        tracing::debug!(%proj_id, chunk = index, "poprako.projs.enrichment.duplicate");
+                    }
+                }
+            }
+            Err(err) => {
+                tracing::warn!(chunk = index, chunks = chunk_count, error = %err, "poprako.projs.enrichment.chunk_failed");
+                errors.push(err);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok((map, None));
+    }
+
+    if errors.len() == chunk_count {
+        return Err(errors.swap_remove(0));
+    }
+
+    Ok((
+        map,
+        Some(format!(
+            "部分项目的 PopRaKo 信息获取失败（{}/{} 批）: {}",
+            errors.len(),
+            chunk_count,
+            errors[0]
+        )),
+    ))
+}
+
 // 获取当前用户的 enriched 项目列表（Moetran 列表 + PopRaKo /projs/search 补充）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetUserProjectsEnrichedReq {
     pub page: u32,
     pub limit: u32,
+    // 整体时限（毫秒）；超出时返回部分结果并标记 deadline_exceeded
+    #[serde(default)]
+    pub deadline_ms: Option<u64>,
 }
 
 #[tauri::command]
 #[tracing::instrument]
 pub async fn get_user_projects_enriched(
     payload: GetUserProjectsEnrichedReq,
-) -> Result<Vec<ResProjectEnriched>, String> {
+) -> Result<ProjectsEnrichedReply, AppError> {
+    let budget = RequestBudget::from_ms(payload.deadline_ms);
+
+    with_budget(budget, user_projects_enriched(payload)).await
+}
+
+async fn user_projects_enriched(
+    payload: GetUserProjectsEnrichedReq,
+) -> Result<ProjectsEnrichedReply, AppError> {
     tracing::info!(
         page = payload.page,
         limit = payload.limit,
@@ -795,231 +1934,269 @@ pub async fn get_user_projects_enriched(
 
     let path = "user/projects".to_string();
     let mut query = std::collections::HashMap::new();
-    query.insert("page", payload.page.to_string());
-    query.insert("limit", payload.limit.to_string());
     query.insert("status", "0".to_string());
 
-    let base_list: Vec<ResProject> = moetran_get(&path, Some(&query))
-        .await
-        .map_err(|err| format!("获取用户项目列表失败: {}", err))?;
+    let cache_page = CachedPage::user(payload.page, payload.limit);
+    let cache_key = cache_page.key();
+
+    let (base_list, page_meta) = match moetran_get_page::<ResProject>(
+        &path,
+        Some(&query),
+        payload.page,
+        payload.limit,
+    )
+    .await
+    {
+        Ok(page) => page.into_parts(),
+        Err(err)
+            if err.is_network()
+                || err.is_service_unavailable()
+                || err.is_offline()
+                || err.is_deadline_exceeded() =>
+        {
+            let exceeded = err.is_deadline_exceeded();
+            let items = stale_enriched_fallback(&cache_page, err, "获取用户项目列表失败").await?;
+            return Ok(ProjectsEnrichedReply::complete(items)
+                .await
+                .with_deadline_exceeded(exceeded));
+        }
+        Err(err) => return Err(err.context("获取用户项目列表失败")),
+    };
 
     if base_list.is_empty() {
         tracing::info!("user.projects_enriched.empty");
 
-        return Ok(vec![]);
+        cache_page.persist(&[]).await;
+
+        return Ok(ProjectsEnrichedReply::complete(vec![])
+            .await
+            .with_pagination(page_meta));
     }
 
-    let ids: Vec<String> = base_list.iter().map(|p| p.id.clone()).collect();
+    let ids: Vec<ProjectId> = base_list.iter().map(|p| p.id.clone()).collect();
 
-    let search_body = PoprakoProjSearchReq {
-        proj_ids: ids,
-        page: payload.page,
-        limit: payload.limit,
+    // PopRaKo 补充失败时不影响 Moetran 列表的展示
+    let (map, enrichment_error) = match fetch_enrichment(ids).await {
+        Ok((map, partial)) => (map, partial),
+        Err(err) => {
+            tracing::warn!(error = %err, "poprako.projs.enrichment.failed");
+            (HashMap::new(), Some(err.to_string()))
+        }
     };
 
-    let reply = poprako_post_opt::<PoprakoProjSearchReq, PoprakoEnvelope<Vec<PoprakoProjInfo>>>(
-        "projs/search",
-        Some(search_body),
-    )
-    .await
-    .map_err(|err| format!("获取 PopRaKo 项目详情失败: {}", err))?;
-
-    let mut map = std::collections::HashMap::new();
+    // 补充信息因时限用尽而缺失
+    let deadline_exceeded = enrichment_error.is_some() && request_budget::exceeded();
 
-    if reply.code == 200 {
-        if let Some(items) = reply.data {
-            for item in items {
-                map.insert(item.proj_id.clone(), item);
-            }
-        }
-    } else {
-        let msg = reply
-            .message
-            .unwrap_or_else(|| "PopRaKo 项目搜索失败".to_string());
+    let mut enriched_list: Vec<ResProjectEnriched> = base_list
+        .iter()
+        .map(|item| build_enriched(item, map.get(&item.id)))
+        .collect();
 
-        tracing::info!(message = %msg, code = reply.code, "poprako.projs.search.failed");
-    }
+    attach_next_deadlines(&mut enriched_list).await;
 
-    let mut enriched_list = Vec::with_capacity(base_list.len());
-
-    for item in base_list {
-        if let Some(extra) = map.get(&item.id) {
-            enriched_list.push(ResProjectEnriched {
-                id: item.id,
-                name: item.name,
-                source_count: item.source_count,
-                translated_source_count: item.translated_source_count,
-                checked_source_count: item.checked_source_count,
-                team: item.team.clone(),
-                project_set: item.project_set.clone(),
-                has_poprako: true,
-                projset_index: Some(extra.projset_index),
-                translating_status: Some(extra.translating_status),
-                proofreading_status: Some(extra.proofreading_status),
-                typesetting_status: Some(extra.typesetting_status),
-                reviewing_status: Some(extra.reviewing_status),
-                is_published: Some(extra.is_published),
-                members: extra.members.clone(),
-                principals: extra.members.as_ref().map(|ms| {
-                    ms.iter()
-                        .filter(|m| m.is_principal)
-                        .map(|m| m.user_id.clone())
-                        .collect()
-                }),
-                role: item.role.clone(),
-            });
-        } else {
-            enriched_list.push(ResProjectEnriched {
-                id: item.id,
-                name: item.name,
-                source_count: item.source_count,
-                translated_source_count: item.translated_source_count,
-                checked_source_count: item.checked_source_count,
-                team: item.team.clone(),
-                project_set: item.project_set.clone(),
-                has_poprako: false,
-                projset_index: None,
-                translating_status: None,
-                proofreading_status: None,
-                typesetting_status: None,
-                reviewing_status: None,
-                is_published: None,
-                members: None,
-                principals: None,
-                role: item.role.clone(),
-            });
-        }
+    // 降级结果不写入兜底缓存、状态历史，也不覆盖最近项目快照
+    if enrichment_error.is_none() {
+        remember_enriched_list(&cache_key, &enriched_list);
+        cache_page.persist(&enriched_list).await;
+        record_enriched(&enriched_list).await;
+        sync_recent_with_enriched(&enriched_list).await;
     }
 
     tracing::info!(
         count = enriched_list.len(),
+        degraded = enrichment_error.is_some(),
+        deadline_exceeded,
         "user.projects_enriched.request.ok"
     );
 
-    Ok(enriched_list)
+    Ok(
+        ProjectsEnrichedReply::build(enriched_list, enrichment_error)
+            .await
+            .with_deadline_exceeded(deadline_exceeded)
+            .with_pagination(page_meta),
+    )
 }
 
 // 获取指定汉化组的 enriched 项目列表（Moetran 列表 + PopRaKo /projs/search 补充）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetTeamProjectsEnrichedReq {
-    pub team_id: String,
+    pub team_id: TeamId,
     pub page: u32,
     pub limit: u32,
+    // 整体时限（毫秒）；超出时返回部分结果并标记 deadline_exceeded
+    #[serde(default)]
+    pub deadline_ms: Option<u64>,
 }
 
 #[tauri::command]
 pub async fn get_team_projects_enriched(
     payload: GetTeamProjectsEnrichedReq,
-) -> Result<Vec<ResProjectEnriched>, String> {
+) -> Result<ProjectsEnrichedReply, AppError> {
+    let budget = RequestBudget::from_ms(payload.deadline_ms);
+
+    with_budget(budget, team_projects_enriched(payload)).await
+}
+
+async fn team_projects_enriched(
+    payload: GetTeamProjectsEnrichedReq,
+) -> Result<ProjectsEnrichedReply, AppError> {
     tracing::info!(team_id = %payload.team_id, page = payload.page, limit = payload.limit, "team.projects_enriched.request.start");
 
     let path = format!("teams/{}/projects", payload.team_id);
     let mut query = std::collections::HashMap::new();
-    query.insert("page", payload.page.to_string());
-    query.insert("limit", payload.limit.to_string());
     query.insert("status", "0".to_string());
 
-    let base_list: Vec<ResProject> = moetran_get(&path, Some(&query))
-        .await
-        .map_err(|err| format!("获取团队项目列表失败: {}", err))?;
+    let cache_page = CachedPage::team(&payload.team_id, payload.page, payload.limit);
+    let cache_key = cache_page.key();
 
-    if base_list.is_empty() {
-        tracing::info!(team_id = %payload.team_id, "team.projects_enriched.empty");
-        return Ok(vec![]);
-    }
+    let (base_list, page_meta) = match moetran_get_page::<ResProject>(
+        &path,
+        Some(&query),
+        payload.page,
+        payload.limit,
+    )
+    .await
+    {
+        Ok(page) => page.into_parts(),
+        Err(err)
+            if err.is_network()
+                || err.is_service_unavailable()
+                || err.is_offline()
+                || err.is_deadline_exceeded() =>
+        {
+            let exceeded = err.is_deadline_exceeded();
+            let items = stale_enriched_fallback(&cache_page, err, "获取团队项目列表失败").await?;
+            return Ok(ProjectsEnrichedReply::complete(items)
+                .await
+                .with_deadline_exceeded(exceeded));
+        }
+        Err(err) => return Err(err.context("获取团队项目列表失败")),
+    };
 
-    let ids: Vec<String> = base_list.iter().map(|p| p.id.clone()).collect();
+    if base_list.is_empty() {
+        tracing::info!(team_id = %payload.team_id, "team.projects_enriched.empty");
 
-    let search_body = PoprakoProjSearchReq {
-        proj_ids: ids,
-        page: payload.page,
-        limit: payload.limit,
+        cache_page.persist(&[]).await;
+        return Ok(ProjectsEnrichedReply::complete(vec![])
+            .await
+            .with_pagination(page_meta));
+    }
+
+    let ids: Vec<ProjectId> = base_list.iter().map(|p| p.id.clone()).collect();
+
+    // PopRaKo 补充失败时不影响 Moetran 列表的展示
+    let (map, enrichment_error) = match fetch_enrichment(ids).await {
+        Ok((map, partial)) => (map, partial),
+        Err(err) => {
+            tracing::warn!(error = %err, "poprako.projs.enrichment.failed");
+            (HashMap::new(), Some(err.to_string()))
+        }
     };
 
-    let reply = poprako_post_opt::<PoprakoProjSearchReq, PoprakoEnvelope<Vec<PoprakoProjInfo>>>(
-        "projs/search",
-        Some(search_body),
-    )
-    .await
-    .map_err(|err| format!("获取 PopRaKo 项目详情失败: {}", err))?;
+    // 补充信息因时限用尽而缺失
+    let deadline_exceeded = enrichment_error.is_some() && request_budget::exceeded();
+
+    let mut enriched_list: Vec<ResProjectEnriched> = base_list
+        .iter()
+        .map(|item| build_enriched(item, map.get(&item.id)))
+        .collect();
+
+    attach_next_deadlines(&mut enriched_list).await;
+
+    // 降级结果不写入兜底缓存、状态历史，也不覆盖最近项目快照
+    if enrichment_error.is_none() {
+        remember_enriched_list(&cache_key, &enriched_list);
+        cache_page.persist(&enriched_list).await;
+        record_enriched(&enriched_list).await;
+        sync_recent_with_enriched(&enriched_list).await;
+    }
 
-    let mut map = std::collections::HashMap::new();
+    tracing::info!(
+        team_id = %payload.team_id,
+        count = enriched_list.len(),
+        degraded = enrichment_error.is_some(),
+        deadline_exceeded,
+        "team.projects_enriched.request.ok"
+    );
+
+    Ok(
+        ProjectsEnrichedReply::build(enriched_list, enrichment_error)
+            .await
+            .with_deadline_exceeded(deadline_exceeded)
+            .with_pagination(page_meta),
+    )
+}
 
-    if reply.code == 200 {
-        if let Some(items) = reply.data {
-            for item in items {
-                map.insert(item.proj_id.clone(), item);
+// 搜索结果按名称回查 Moetran 项目时的最大并发数
+const SEARCH_ENRICH_CONCURRENCY: usize = 8;
+
+// 对 PopRaKo 搜索结果逐个以 proj_name 调用 Moetran 的项目列表接口（path 为 user/projects 或
+// teams/:team_id/projects），关键词搜索可能返回多个相近项目，按名称打分选择。请求并发进行，
+// 结果保持 PopRaKo 的原始顺序；单个项目查询失败时记录日志并跳过
+async fn enrich_search_hits(path: String, items: Vec<PoprakoProjInfo>) -> Vec<ResProjectEnriched> {
+    let count = items.len();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(SEARCH_ENRICH_CONCURRENCY));
+    let budget = request_budget::current();
+    let path = Arc::new(path);
+    let mut set = tokio::task::JoinSet::new();
+
+    for (index, extra) in items.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let path = path.clone();
+
+        // 子任务不继承 task-local 的整体时限，需重新设置
+        set.spawn(with_budget(budget, async move {
+            let _permit = semaphore.acquire_owned().await;
+
+            let mut query = HashMap::new();
+            query.insert("word", extra.proj_name.clone());
+            query.insert("status", "0".to_string());
+
+            let result = async {
+                moetran_limiter().acquire().await?;
+                moetran_get::<Vec<ResProject>>(&path, Some(&query)).await
             }
-        }
-    } else {
-        let msg = reply
-            .message
-            .unwrap_or_else(|| "PopRaKo 项目搜索失败".to_string());
+            .await;
 
-        tracing::info!(message = %msg, code = reply.code, "poprako.projs.search.failed");
+            (index, extra, result)
+        }));
     }
 
-    let mut enriched_list = Vec::with_capacity(base_list.len());
-
-    for item in base_list {
-        if let Some(extra) = map.get(&item.id) {
-            enriched_list.push(ResProjectEnriched {
-                id: item.id,
-                name: item.name,
-                source_count: item.source_count,
-                translated_source_count: item.translated_source_count,
-                checked_source_count: item.checked_source_count,
-                team: item.team.clone(),
-                project_set: item.project_set.clone(),
-                has_poprako: true,
-                projset_index: Some(extra.projset_index),
-                translating_status: Some(extra.translating_status),
-                proofreading_status: Some(extra.proofreading_status),
-                typesetting_status: Some(extra.typesetting_status),
-                reviewing_status: Some(extra.reviewing_status),
-                is_published: Some(extra.is_published),
-                members: extra.members.clone(),
-                principals: extra.members.as_ref().map(|ms| {
-                    ms.iter()
-                        .filter(|m| m.is_principal)
-                        .map(|m| m.user_id.clone())
-                        .collect()
-                }),
-                role: item.role.clone(),
-            });
-        } else {
-            enriched_list.push(ResProjectEnriched {
-                id: item.id,
-                name: item.name,
-                source_count: item.source_count,
-                translated_source_count: item.translated_source_count,
-                checked_source_count: item.checked_source_count,
-                team: item.team.clone(),
-                project_set: item.project_set.clone(),
-                has_poprako: false,
-                projset_index: None,
-                translating_status: None,
-                proofreading_status: None,
-                typesetting_status: None,
-                reviewing_status: None,
-                is_published: None,
-                members: None,
-                principals: None,
-                role: item.role.clone(),
-            });
+    let mut results = Vec::with_capacity(count);
+
+    while let Some(joined) = set.join_next().await {
+        match joined {
+            Ok(item) => results.push(item),
+            Err(err) => {
+                tracing::warn!(path = %path, error = %err, "project.search_enrich.task_failed");
+            }
         }
     }
 
-    tracing::info!(team_id = %payload.team_id, count = enriched_list.len(), "team.projects_enriched.request.ok");
+    results.sort_by_key(|(index, _, _)| *index);
 
-    Ok(enriched_list)
+    results
+        .into_iter()
+        .filter_map(|(_, extra, result)| match result {
+            Ok(list) => enrich_by_name(&list, &extra),
+            Err(err) => {
+                tracing::warn!(
+                    path = %path,
+                    proj_id = %extra.proj_id,
+                    error = %err,
+                    "project.search_enrich.lookup_failed"
+                );
+                None
+            }
+        })
+        .collect()
 }
 
 // user 维度：基于 PopRaKo /projs/search + Moetran /user/projects?word= 进行组合搜索
 #[tauri::command]
 pub async fn search_user_projects_enriched(
     filter: PoprakoProjFilterReq,
-) -> Result<Vec<ResProjectEnriched>, String> {
+) -> Result<Vec<ResProjectEnriched>, AppError> {
     tracing::info!("user.projects_enriched.search.start");
 
     let mut defer = WarnDefer::new("user.projects_enriched.search");
@@ -1029,7 +2206,7 @@ pub async fn search_user_projects_enriched(
         Some(filter),
     )
     .await
-    .map_err(|err| format!("PopRaKo 项目搜索失败: {}", err))?;
+    .map_err(|err| err.context("PopRaKo 项目搜索失败"))?;
 
     if reply.code != 200 {
         let msg = reply
@@ -1038,7 +2215,10 @@ pub async fn search_user_projects_enriched(
 
         tracing::info!(message = %msg, code = reply.code, "poprako.projs.search.failed");
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let items = match reply.data {
@@ -1050,45 +2230,9 @@ pub async fn search_user_projects_enriched(
         }
     };
 
-    // 逐个 proj_name 调用 Moetran /user/projects?word=，由于后端保证唯一匹配，直接取第一个
-    let mut enriched_list = Vec::new();
+    let mut enriched_list = enrich_search_hits("user/projects".to_string(), items).await;
 
-    for extra in items {
-        let mut query = std::collections::HashMap::new();
-        query.insert("word", extra.proj_name.clone());
-        query.insert("status", "0".to_string());
-
-        let list: Vec<ResProject> = moetran_get("user/projects", Some(&query))
-            .await
-            .map_err(|err| format!("获取用户项目列表失败: {}", err))?;
-
-        if let Some(base) = list.first() {
-            enriched_list.push(ResProjectEnriched {
-                id: base.id.clone(),
-                name: base.name.clone(),
-                source_count: base.source_count,
-                translated_source_count: base.translated_source_count,
-                checked_source_count: base.checked_source_count,
-                team: base.team.clone(),
-                project_set: base.project_set.clone(),
-                has_poprako: true,
-                projset_index: Some(extra.projset_index),
-                translating_status: Some(extra.translating_status),
-                proofreading_status: Some(extra.proofreading_status),
-                typesetting_status: Some(extra.typesetting_status),
-                reviewing_status: Some(extra.reviewing_status),
-                is_published: Some(extra.is_published),
-                members: extra.members.clone(),
-                principals: extra.members.as_ref().map(|ms| {
-                    ms.iter()
-                        .filter(|m| m.is_principal)
-                        .map(|m| m.user_id.clone())
-                        .collect()
-                }),
-                role: base.role.clone(),
-            });
-        }
-    }
+    attach_next_deadlines(&mut enriched_list).await;
 
     tracing::info!(
         count = enriched_list.len(),
@@ -1104,7 +2248,7 @@ pub async fn search_user_projects_enriched(
 #[tauri::command]
 pub async fn search_team_projects_enriched(
     payload: SearchTeamProjectsEnrichedReq,
-) -> Result<Vec<ResProjectEnriched>, String> {
+) -> Result<Vec<ResProjectEnriched>, AppError> {
     tracing::info!(team_id = %payload.team_id, "team.projects_enriched.search.start");
 
     let mut defer = WarnDefer::new("team.projects_enriched.search");
@@ -1114,7 +2258,7 @@ pub async fn search_team_projects_enriched(
         Some(payload.filter.clone()),
     )
     .await
-    .map_err(|err| format!("PopRaKo 项目搜索失败: {}", err))?;
+    .map_err(|err| err.context("PopRaKo 项目搜索失败"))?;
 
     if reply.code != 200 {
         let msg = reply
@@ -1123,7 +2267,10 @@ pub async fn search_team_projects_enriched(
 
         tracing::info!(message = %msg, code = reply.code, "poprako.projs.search.failed");
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let items = match reply.data {
@@ -1135,46 +2282,10 @@ pub async fn search_team_projects_enriched(
         }
     };
 
-    let mut enriched_list = Vec::new();
-
-    for extra in items {
-        let mut query = std::collections::HashMap::new();
-        query.insert("word", extra.proj_name.clone());
-        query.insert("status", "0".to_string());
-
-        let path = format!("teams/{}/projects", payload.team_id);
+    let path = format!("teams/{}/projects", payload.team_id);
+    let mut enriched_list = enrich_search_hits(path, items).await;
 
-        let list: Vec<ResProject> = moetran_get(&path, Some(&query))
-            .await
-            .map_err(|err| format!("获取团队项目列表失败: {}", err))?;
-
-        if let Some(base) = list.first() {
-            enriched_list.push(ResProjectEnriched {
-                id: base.id.clone(),
-                name: base.name.clone(),
-                source_count: base.source_count,
-                translated_source_count: base.translated_source_count,
-                checked_source_count: base.checked_source_count,
-                team: base.team.clone(),
-                project_set: base.project_set.clone(),
-                has_poprako: true,
-                projset_index: Some(extra.projset_index),
-                translating_status: Some(extra.translating_status),
-                proofreading_status: Some(extra.proofreading_status),
-                typesetting_status: Some(extra.typesetting_status),
-                reviewing_status: Some(extra.reviewing_status),
-                is_published: Some(extra.is_published),
-                members: extra.members.clone(),
-                principals: extra.members.as_ref().map(|ms| {
-                    ms.iter()
-                        .filter(|m| m.is_principal)
-                        .map(|m| m.user_id.clone())
-                        .collect()
-                }),
-                role: base.role.clone(),
-            });
-        }
-    }
+    attach_next_deadlines(&mut enriched_list).await;
 
     tracing::info!(
         team_id = %payload.team_id,
@@ -1187,53 +2298,283 @@ pub async fn search_team_projects_enriched(
     Ok(enriched_list)
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetProjectEnrichedReq {
+    pub project_id: ProjectId,
+}
+
+// 单个项目的 enriched 详情：Moetran /projects/:id 的基础信息 + PopRaKo 按 id 搜索到的补充信息。
+// PopRaKo 中没有该项目时返回 has_poprako=false；Moetran 返回 404 时返回 NotFound
+#[tauri::command]
+pub async fn get_project_enriched(
+    payload: GetProjectEnrichedReq,
+) -> Result<ResProjectEnriched, AppError> {
+    tracing::info!(project_id = %payload.project_id, "project.enriched.request.start");
+
+    let mut defer = WarnDefer::new("project.enriched");
+
+    let path = format!("projects/{}", payload.project_id);
+
+    let base: ResProject = match moetran_get(&path, None).await {
+        Ok(base) => base,
+        Err(err) if matches!(err.root(), AppError::MoetranHttp { status: 404, .. }) => {
+            tracing::info!(project_id = %payload.project_id, "project.enriched.not_found");
+            forget_recent_project(&payload.project_id).await;
+            defer.success();
+            return Err(AppError::NotFound(format!(
+                "项目不存在或已被删除: {}",
+                payload.project_id
+            )));
+        }
+        Err(err) => return Err(err.context("获取项目详情失败")),
+    };
+
+    let extra = search_enrichment_chunk(vec![payload.project_id.clone()])
+        .await?
+        .into_iter()
+        .find(|info| info.proj_id == payload.project_id);
+
+    let mut enriched_list = vec![build_enriched(&base, extra.as_ref())];
+
+    attach_next_deadlines(&mut enriched_list).await;
+
+    // 打开项目详情即记为一次打开
+    record_recent_open(&enriched_list[0]).await;
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        has_poprako = extra.is_some(),
+        "project.enriched.request.ok"
+    );
+
+    defer.success();
+
+    Ok(enriched_list.remove(0))
+}
+
 // ========== 获取文件的 sources（用于 TranslatorView） ==========
 
 // Moetran 单个 translation DTO（精简）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MoetranTranslation {
-    pub id: String,
+    pub id: TranslationId,
     pub content: String,
     pub proofread_content: Option<String>,
     pub selected: bool,
+    // 翻译作者与校对者（匿名或旧数据可能缺失）
+    #[serde(default)]
+    pub user: Option<MoetranUserBrief>,
+    #[serde(default)]
+    pub proofreader: Option<MoetranUserBrief>,
+}
+
+// Moetran 返回的用户简要信息（仅保留 id 与昵称）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoetranUserBrief {
+    pub id: UserId,
+    #[serde(default)]
+    pub name: String,
 }
 
 // Moetran source DTO（精简版，仅包含 TranslatorView 所需字段）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MoetranSource {
-    pub id: String,
+    pub id: SourceId,
     pub x: f64,
     pub y: f64,
-    pub position_type: i32,
+    pub position_type: PositionType,
     pub my_translation: Option<MoetranTranslation>,
     #[serde(default)]
     pub translations: Vec<MoetranTranslation>,
+    // 本地是否存在未提交的草稿（由客户端标注，Moetran 不返回）
+    #[serde(default)]
+    pub has_draft: bool,
+    // 来自本地快照、尚未与 Moetran 同步时为 true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetPageSourcesReq {
-    pub file_id: String,
-    pub target_id: String,
+    pub file_id: FileId,
+    pub target_id: TargetId,
+    // 指定时按阅读顺序返回（供未翻译跳转等按顺序遍历）
+    #[serde(default)]
+    pub reading_direction: Option<ReadingDirection>,
+    // 为 true 时若有本地快照则立即返回（标记 stale），最新数据通过 "sources-updated" 事件推送；
+    // 离线模式下总是优先使用快照
+    #[serde(default)]
+    pub allow_stale: bool,
+    // 编辑器打开页面时为 true，记录为最近查看的页面
+    #[serde(default)]
+    pub mark_viewed: bool,
+    // 记录查看时所属的项目；不提供时按已拉取的文件列表推断
+    #[serde(default)]
+    pub project_id: Option<ProjectId>,
+}
+
+// 从 Moetran 拉取页面 sources，更新本地记录与快照；返回 sources 及内容是否相对快照有变化
+async fn fetch_page_sources(
+    file_id: &str,
+    target_id: &str,
+) -> Result<(Vec<MoetranSource>, bool), String> {
+    let endpoint = format!("files/{}/sources", file_id);
+    let mut query = std::collections::HashMap::new();
+    query.insert("target_id", target_id.to_string());
+    query.insert("paging", "false".to_string());
+
+    let sources = moetran_get::<Vec<MoetranSource>>(&endpoint, Some(&query))
+        .await
+        .map_err(|err| err.context("获取页面源失败"))?;
+
+    remember_source_translation_counts(&sources);
+    remember_translations(sources.iter().flat_map(|source| {
+        source
+            .my_translation
+            .iter()
+            .chain(source.translations.iter())
+    }));
+    remember_snapshot_files(file_id, &sources);
+    remember_geometry(file_id, &sources);
+
+    let changed = store_snapshot(file_id, target_id, &sources).await;
+
+    Ok((sources, changed))
+}
+
+// 按请求排序并标注本地草稿
+async fn prepare_page_sources(payload: &GetPageSourcesReq, sources: &mut [MoetranSource]) {
+    if let Some(dir) = payload.reading_direction {
+        sort_sources_reading_order(sources, dir);
+    }
+
+    annotate_page_drafts(&payload.file_id, &payload.target_id, sources).await;
+}
+
+// 本地快照中的页面 sources（标记 stale，已按请求排序并标注草稿）；没有快照时返回 None
+pub(crate) async fn snapshot_page_sources(
+    payload: &GetPageSourcesReq,
+) -> Option<Vec<MoetranSource>> {
+    let mut sources = load_snapshot(&payload.file_id, &payload.target_id).await?;
+
+    for source in sources.iter_mut() {
+        source.stale = Some(true);
+    }
+
+    prepare_page_sources(payload, &mut sources).await;
+
+    Some(sources)
+}
+
+// 拉取最新的页面 sources（供批量删除等需要准确数据的流程使用）
+pub(crate) async fn load_page_sources(
+    payload: &GetPageSourcesReq,
+) -> Result<Vec<MoetranSource>, String> {
+    let (mut sources, _) = fetch_page_sources(&payload.file_id, &payload.target_id).await?;
+
+    prepare_page_sources(payload, &mut sources).await;
+
+    Ok(sources)
+}
+
+// 后台拉取最新 sources，与快照不同时推送 "sources-updated"
+pub(crate) fn spawn_sources_refresh(app: AppHandle, payload: GetPageSourcesReq) {
+    tauri::async_runtime::spawn(async move {
+        match fetch_page_sources(&payload.file_id, &payload.target_id).await {
+            Ok((mut sources, true)) => {
+                prepare_page_sources(&payload, &mut sources).await;
+
+                let event = SourcesUpdated {
+                    file_id: payload.file_id.to_string(),
+                    target_id: payload.target_id.to_string(),
+                    sources,
+                };
+
+                emit_event(&app, event);
+            }
+            Ok((_, false)) => {
+                tracing::debug!(file_id = %payload.file_id, "moetran.sources.refresh.unchanged");
+            }
+            Err(err) => {
+                tracing::warn!(file_id = %payload.file_id, error = %err, "moetran.sources.refresh.failed");
+            }
+        }
+    });
 }
 
 #[tauri::command]
-pub async fn get_page_sources(payload: GetPageSourcesReq) -> Result<Vec<MoetranSource>, String> {
+pub async fn get_page_sources(
+    app: AppHandle,
+    payload: GetPageSourcesReq,
+) -> Result<Vec<MoetranSource>, AppError> {
     tracing::info!(
         file_id = %payload.file_id,
         target_id = %payload.target_id,
+        allow_stale = payload.allow_stale,
         "moetran.sources.fetch.request.start"
     );
 
     let mut defer = WarnDefer::new("moetran.sources.fetch");
 
-    let endpoint = format!("files/{}/sources", payload.file_id);
-    let mut query = std::collections::HashMap::new();
-    query.insert("target_id", payload.target_id.clone());
-    query.insert("paging", "false".to_string());
+    if payload.mark_viewed {
+        record_file_activity(
+            payload.project_id.clone().map(String::from),
+            payload.file_id.to_string(),
+            payload.target_id.to_string(),
+            FileAction::View,
+        );
+    }
 
-    let sources = moetran_get::<Vec<MoetranSource>>(&endpoint, Some(&query))
-        .await
-        .map_err(|err| format!("获取页面源失败: {}", err))?;
+    let offline = config().offline_mode;
+
+    if payload.allow_stale || offline {
+        if let Some(sources) = snapshot_page_sources(&payload).await {
+            // 离线时后台刷新必然失败，不再发起
+            if !offline {
+                spawn_sources_refresh(app, payload.clone());
+            }
+
+            tracing::info!(
+                file_id = %payload.file_id,
+                count = sources.len(),
+                "moetran.sources.fetch.snapshot"
+            );
+
+            defer.success();
+
+            return Ok(sources);
+        }
+
+        // 该 target 没有快照（如刚切换 target）但已知页面几何信息时，先返回不含翻译的 source，
+        // 翻译随后通过 "sources-updated" 推送
+        if !offline {
+            if let Some(geometry) = cached_geometry(&payload.file_id) {
+                let mut sources = merge_overlay(&geometry, vec![]).sources;
+
+                for source in sources.iter_mut() {
+                    source.stale = Some(true);
+                }
+
+                if let Some(dir) = payload.reading_direction {
+                    sort_sources_reading_order(&mut sources, dir);
+                }
+
+                spawn_sources_refresh(app, payload.clone());
+
+                tracing::info!(
+                    file_id = %payload.file_id,
+                    count = sources.len(),
+                    "moetran.sources.fetch.geometry"
+                );
+
+                defer.success();
+
+                return Ok(sources);
+            }
+        }
+    }
+
+    let sources = load_page_sources(&payload).await?;
 
     let count = sources.len();
     tracing::info!(
@@ -1248,14 +2589,182 @@ pub async fn get_page_sources(payload: GetPageSourcesReq) -> Result<Vec<MoetranS
     Ok(sources)
 }
 
+// 批量拉取时同时进行的请求数
+const SOURCES_BATCH_CONCURRENCY: usize = 6;
+
+// 一次拉取多页的 sources（翻页时预取相邻页面），省去逐页调用的往返
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetFilesSourcesBatchReq {
+    pub file_ids: Vec<FileId>,
+    pub target_id: TargetId,
+    #[serde(default)]
+    pub reading_direction: Option<ReadingDirection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilesSourcesBatchReply {
+    // file_id -> sources，只含拉取成功的页面
+    pub sources: HashMap<FileId, Vec<MoetranSource>>,
+    // file_id -> 错误信息，单页失败不影响其余页面
+    pub failures: HashMap<FileId, String>,
+}
+
+#[tauri::command]
+pub async fn get_files_sources_batch(
+    payload: GetFilesSourcesBatchReq,
+) -> Result<FilesSourcesBatchReply, AppError> {
+    tracing::info!(
+        files = payload.file_ids.len(),
+        target_id = %payload.target_id,
+        "moetran.sources.batch.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.sources.batch");
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(SOURCES_BATCH_CONCURRENCY));
+    let mut set = tokio::task::JoinSet::new();
+    let mut seen = HashSet::new();
+
+    for file_id in payload.file_ids {
+        if !seen.insert(file_id.clone()) {
+            continue;
+        }
+
+        let semaphore = semaphore.clone();
+        let request = GetPageSourcesReq {
+            file_id,
+            target_id: payload.target_id.clone(),
+            reading_direction: payload.reading_direction,
+            allow_stale: false,
+            mark_viewed: false,
+            project_id: None,
+        };
+
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = load_page_sources(&request).await;
+            (request.file_id, result)
+        });
+    }
+
+    let mut reply = FilesSourcesBatchReply {
+        sources: HashMap::new(),
+        failures: HashMap::new(),
+    };
+
+    while let Some(joined) = set.join_next().await {
+        let (file_id, result) =
+            joined.map_err(|err| AppError::Other(format!("批量获取页面源任务异常: {}", err)))?;
+
+        match result {
+            Ok(sources) => {
+                reply.sources.insert(file_id, sources);
+            }
+            Err(err) => {
+                tracing::warn!(%file_id, error = %err, "moetran.sources.batch.file_failed");
+                reply.failures.insert(file_id, err);
+            }
+        }
+    }
+
+    tracing::info!(
+        fetched = reply.sources.len(),
+        failed = reply.failures.len(),
+        "moetran.sources.batch.ok"
+    );
+
+    defer.success();
+
+    Ok(reply)
+}
+
+// ========== 阅读方向（项目本地偏好） ==========
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetReadingDirectionReq {
+    pub project_id: ProjectId,
+    // 用于在未设置偏好时推断默认方向
+    #[serde(default)]
+    pub source_language: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReadingDirectionReply {
+    pub direction: ReadingDirection,
+    // true 表示未保存过偏好，方向由源语言推断
+    pub is_default: bool,
+}
+
+#[tauri::command]
+pub async fn get_reading_direction(
+    payload: GetReadingDirectionReq,
+) -> Result<ReadingDirectionReply, AppError> {
+    tracing::debug!(project_id = %payload.project_id, "project.reading_direction.get.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let stored = project_prefs::get_reading_direction(storage.pool(), &payload.project_id)
+        .await?
+        .and_then(|value| ReadingDirection::parse(&value));
+
+    let direction = ReadingDirection::resolve(stored, payload.source_language.as_deref());
+
+    tracing::debug!(
+        project_id = %payload.project_id,
+        direction = direction.as_str(),
+        "project.reading_direction.get.ok"
+    );
+
+    Ok(ReadingDirectionReply {
+        direction,
+        is_default: stored.is_none(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetReadingDirectionReq {
+    pub project_id: ProjectId,
+    pub direction: ReadingDirection,
+}
+
+#[tauri::command]
+pub async fn set_reading_direction(payload: SetReadingDirectionReq) -> Result<(), AppError> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        direction = payload.direction.as_str(),
+        "project.reading_direction.set.start"
+    );
+
+    let mut defer = WarnDefer::new("project.reading_direction.set");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    project_prefs::save_reading_direction(
+        storage.pool(),
+        &payload.project_id,
+        payload.direction.as_str(),
+    )
+    .await?;
+
+    tracing::info!(project_id = %payload.project_id, "project.reading_direction.set.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
 // 在指定文件上创建一个 source（标记）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateSourceReq {
-    pub file_id: String,
+    pub file_id: FileId,
     pub x: f64,
     pub y: f64,
     #[serde(default)]
-    pub position_type: i32,
+    pub position_type: PositionType,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1263,11 +2772,24 @@ pub struct CreateSourceReq {
 }
 
 #[tauri::command]
-pub async fn create_source(payload: CreateSourceReq) -> Result<MoetranSource, String> {
+pub async fn create_source(payload: CreateSourceReq) -> Result<MoetranSource, AppError> {
+    let retry = RetryScope::begin(
+        "create_source",
+        format!("file:{}", payload.file_id),
+        &payload,
+    )
+    .await;
+
+    retry.finish(source_create(payload).await).await
+}
+
+async fn source_create(payload: CreateSourceReq) -> Result<MoetranSource, AppError> {
     tracing::info!(file_id = %payload.file_id, x = payload.x, y = payload.y, "moetran.source.create.start");
 
     let mut defer = WarnDefer::new("moetran.source.create");
 
+    let position_type = payload.position_type.ensure_writable()?;
+
     let path = format!("files/{}/sources", payload.file_id);
 
     let mut body = serde_json::Map::new();
@@ -1276,7 +2798,7 @@ pub async fn create_source(payload: CreateSourceReq) -> Result<MoetranSource, St
     body.insert("y".to_string(), serde_json::Value::from(payload.y));
     body.insert(
         "position_type".to_string(),
-        serde_json::Value::from(payload.position_type),
+        serde_json::Value::from(i32::from(position_type)),
     );
 
     if let Some(w) = payload.width {
@@ -1292,10 +2814,16 @@ pub async fn create_source(payload: CreateSourceReq) -> Result<MoetranSource, St
         Some(serde_json::Value::Object(body)),
     )
     .await
-    .map_err(|err| format!("创建 source 失败: {}", err))?;
+    .map_err(|err| err.context("创建 source 失败"))?;
 
     tracing::info!(source_id = %reply.id, "moetran.source.create.ok");
 
+    // 新建的 source 还没有翻译，删除前的影响检查无需再加载页面
+    remember_source_translation_counts(std::slice::from_ref(&reply));
+
+    invalidate_file_snapshots(&payload.file_id).await;
+    forget_geometry(&payload.file_id);
+
     defer.success();
 
     Ok(reply)
@@ -1304,14 +2832,25 @@ pub async fn create_source(payload: CreateSourceReq) -> Result<MoetranSource, St
 // 更新 source（框内/框外切换或位置移动）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateSourceReq {
-    pub source_id: String,
-    pub position_type: Option<i32>,
+    pub source_id: SourceId,
+    pub position_type: Option<PositionType>,
     pub x: Option<f64>,
     pub y: Option<f64>,
 }
 
 #[tauri::command]
-pub async fn update_source(payload: UpdateSourceReq) -> Result<MoetranSource, String> {
+pub async fn update_source(payload: UpdateSourceReq) -> Result<MoetranSource, AppError> {
+    let retry = RetryScope::begin(
+        "update_source",
+        format!("source:{}", payload.source_id),
+        &payload,
+    )
+    .await;
+
+    retry.finish(source_update(payload).await).await
+}
+
+async fn source_update(payload: UpdateSourceReq) -> Result<MoetranSource, AppError> {
     tracing::info!(
         source_id = %payload.source_id,
         position_type = ?payload.position_type,
@@ -1322,16 +2861,24 @@ pub async fn update_source(payload: UpdateSourceReq) -> Result<MoetranSource, St
 
     let mut defer = WarnDefer::new("moetran.source.update");
 
+    let position_type = payload
+        .position_type
+        .map(PositionType::ensure_writable)
+        .transpose()?;
+
     let path = format!("sources/{}", payload.source_id);
 
     let mut body = serde_json::Map::new();
     body.insert(
         "id".to_string(),
-        serde_json::Value::String(payload.source_id.clone()),
+        serde_json::Value::String(payload.source_id.to_string()),
     );
 
-    if let Some(pt) = payload.position_type {
-        body.insert("position_type".to_string(), serde_json::Value::from(pt));
+    if let Some(pt) = position_type {
+        body.insert(
+            "position_type".to_string(),
+            serde_json::Value::from(i32::from(pt)),
+        );
     }
 
     if let Some(x) = payload.x {
@@ -1347,16 +2894,18 @@ pub async fn update_source(payload: UpdateSourceReq) -> Result<MoetranSource, St
         Some(serde_json::Value::Object(body)),
     )
     .await
-    .map_err(|err| format!("更新 source 失败: {}", err))?;
+    .map_err(|err| err.context("更新 source 失败"))?;
 
     tracing::info!(
         source_id = %reply.id,
-        position_type = reply.position_type,
+        position_type = ?reply.position_type,
         x = reply.x,
         y = reply.y,
         "moetran.source.update.ok"
     );
 
+    forget_geometry_of_source(&reply.id);
+
     defer.success();
 
     Ok(reply)
@@ -1365,20 +2914,43 @@ pub async fn update_source(payload: UpdateSourceReq) -> Result<MoetranSource, St
 // 删除 source
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DeleteSourceReq {
-    pub source_id: String,
+    pub source_id: SourceId,
+    // 已在前端确认影响后传 true，跳过影响检查
+    #[serde(default)]
+    pub force: bool,
 }
 
-#[tauri::command]
-pub async fn delete_source(payload: DeleteSourceReq) -> Result<(), String> {
+// 执行 Moetran 删除请求并清理本地记录（单个删除与批量删除共用）
+pub(crate) async fn delete_source_remote(source_id: &str) -> Result<(), AppError> {
+    let path = format!("sources/{}", source_id);
+
+    moetran_delete::<serde_json::Value>(&path).await?;
+
+    if let Ok(mut guard) = SOURCE_TRANSLATION_COUNTS.lock() {
+        guard.remove(source_id);
+    }
+
+    invalidate_snapshots_for(source_id).await;
+    forget_geometry_of_source(source_id);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_source(payload: DeleteSourceReq) -> Result<(), AppError> {
     tracing::info!(source_id = %payload.source_id, "moetran.source.delete.start");
 
     let mut defer = WarnDefer::new("moetran.source.delete");
 
-    let path = format!("sources/{}", payload.source_id);
+    let entity = ImpactEntity::Source {
+        source_id: payload.source_id.to_string(),
+    };
 
-    moetran_delete::<serde_json::Value>(&path)
+    ensure_delete_allowed(entity, payload.force).await?;
+
+    delete_source_remote(&payload.source_id)
         .await
-        .map_err(|err| format!("删除 source 失败: {}", err))?;
+        .map_err(|err| err.context("删除 source 失败"))?;
 
     tracing::info!(source_id = %payload.source_id, "moetran.source.delete.ok");
 
@@ -1387,18 +2959,120 @@ pub async fn delete_source(payload: DeleteSourceReq) -> Result<(), String> {
     Ok(())
 }
 
+// 删除项目中的图片文件（其上的 source 与翻译一并删除）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteProjectFileReq {
+    pub file_id: FileId,
+    // 用于统计 source 数量的 target
+    pub target_id: TargetId,
+    // 已在前端确认影响后传 true，跳过影响检查
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[tauri::command]
+pub async fn delete_project_file(payload: DeleteProjectFileReq) -> Result<(), AppError> {
+    tracing::info!(file_id = %payload.file_id, "moetran.file.delete.start");
+
+    let mut defer = WarnDefer::new("moetran.file.delete");
+
+    let entity = ImpactEntity::File {
+        file_id: payload.file_id.to_string(),
+        target_id: payload.target_id.to_string(),
+    };
+
+    ensure_delete_allowed(entity, payload.force).await?;
+
+    let path = format!("files/{}", payload.file_id);
+
+    moetran_delete::<serde_json::Value>(&path)
+        .await
+        .map_err(|err| err.context("删除文件失败"))?;
+
+    invalidate_file_snapshots(&payload.file_id).await;
+    forget_geometry(&payload.file_id);
+
+    tracing::info!(file_id = %payload.file_id, "moetran.file.delete.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
+// 删除项目的翻译目标（target 上的翻译一并删除）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteProjectTargetReq {
+    pub project_id: ProjectId,
+    pub target_id: TargetId,
+    // 已在前端确认影响后传 true，跳过影响检查
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[tauri::command]
+pub async fn delete_project_target(payload: DeleteProjectTargetReq) -> Result<(), AppError> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        "moetran.target.delete.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.target.delete");
+
+    let entity = ImpactEntity::Target {
+        project_id: payload.project_id.to_string(),
+        target_id: payload.target_id.to_string(),
+    };
+
+    ensure_delete_allowed(entity, payload.force).await?;
+
+    let path = format!("targets/{}", payload.target_id);
+
+    moetran_delete::<serde_json::Value>(&path)
+        .await
+        .map_err(|err| err.context("删除翻译目标失败"))?;
+
+    tracing::info!(target_id = %payload.target_id, "moetran.target.delete.ok");
+
+    defer.success();
+
+    Ok(())
+}
+
 // 提交翻译稿
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SubmitTranslationReq {
-    pub source_id: String,
-    pub target_id: String,
+    pub source_id: SourceId,
+    pub target_id: TargetId,
     pub content: String,
+    // 为 true 时跳过内容规范化，原样提交
+    #[serde(default)]
+    pub raw: bool,
+    // 用于读取汉化组的标点规则；不提供时不替换标点
+    #[serde(default)]
+    pub team_id: Option<TeamId>,
+    // 为 true 时允许提交空白译文（默认拒绝，返回 empty_content 错误）
+    #[serde(default)]
+    pub allow_empty: bool,
 }
 
 #[tauri::command]
 pub async fn submit_translation(
     payload: SubmitTranslationReq,
-) -> Result<MoetranTranslation, String> {
+) -> Result<WithNormalization<MoetranTranslation>, AppError> {
+    let retry = RetryScope::begin(
+        "submit_translation",
+        format!("source:{}:{}", payload.source_id, payload.target_id),
+        &payload,
+    )
+    .await;
+
+    retry.finish(translation_submit(payload).await).await
+}
+
+async fn translation_submit(
+    payload: SubmitTranslationReq,
+) -> Result<WithNormalization<MoetranTranslation>, AppError> {
     tracing::info!(
         source_id = %payload.source_id,
         target_id = %payload.target_id,
@@ -1406,52 +3080,243 @@ pub async fn submit_translation(
         "moetran.translation.submit.start"
     );
 
+    if !payload.allow_empty && is_blank(&payload.content) {
+        return Err(empty_content_error("content"));
+    }
+
     let mut defer = WarnDefer::new("moetran.translation.submit");
 
     let path = format!("sources/{}/translations", payload.source_id);
 
+    let (content, normalized) = if payload.raw {
+        (payload.content.clone(), vec![])
+    } else {
+        let normalized = normalize_for_submit(&payload.content, payload.team_id.as_deref()).await;
+        (normalized.text, normalized.applied)
+    };
+
     let body = serde_json::json!({
         "target_id": payload.target_id,
-        "content": payload.content,
+        "content": content,
     });
 
     let reply = moetran_post_opt::<serde_json::Value, MoetranTranslation>(&path, Some(body))
         .await
-        .map_err(|err| format!("提交翻译失败: {}", err))?;
+        .map_err(|err| err.context("提交翻译失败"))?;
 
     tracing::info!(
         translation_id = %reply.id,
         source_id = %payload.source_id,
+        ?normalized,
         "moetran.translation.submit.ok"
     );
 
+    remember_translations(std::iter::once(&reply));
+    enqueue_submitted(&payload.source_id, &payload.target_id, &reply);
+    record_translation_activity(&payload.source_id, &payload.target_id, FileAction::Submit);
+    clear_draft_after_submit(&payload.source_id, &payload.target_id).await;
+    invalidate_snapshots_for(&payload.source_id).await;
+
+    defer.success();
+
+    Ok(WithNormalization {
+        value: reply,
+        normalized,
+    })
+}
+
+// 批量提交时同时进行的请求数
+const SUBMIT_BATCH_CONCURRENCY: usize = 4;
+
+// 一次提交多条翻译（如粘贴整段译文），逐条结果见 BatchSubmitReport
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubmitTranslationsBatchReq {
+    pub items: Vec<SubmitTranslationReq>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranslationSubmitFailure {
+    pub source_id: SourceId,
+    pub error: AppError,
+}
+
+// 各列表均按 items 中的顺序
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchSubmitReport {
+    pub succeeded: Vec<SourceId>,
+    pub failed: Vec<TranslationSubmitFailure>,
+    // 登录过期或进入离线模式后未提交的条目
+    pub not_attempted: Vec<SourceId>,
+}
+
+enum BatchSubmitOutcome {
+    Submitted,
+    Failed(AppError),
+    NotAttempted,
+}
+
+#[tauri::command]
+pub async fn submit_translations_batch(
+    payload: SubmitTranslationsBatchReq,
+) -> Result<BatchSubmitReport, AppError> {
+    tracing::info!(
+        count = payload.items.len(),
+        "moetran.translation.batch_submit.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.translation.batch_submit");
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(SUBMIT_BATCH_CONCURRENCY));
+    // 登录过期（或已切换到离线模式）后其余条目必然失败，不再发出请求
+    let halted = Arc::new(AtomicBool::new(false));
+    let mut set = tokio::task::JoinSet::new();
+
+    for (index, item) in payload.items.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let halted = halted.clone();
+
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let source_id = item.source_id.clone();
+
+            if halted.load(Ordering::SeqCst) {
+                return (index, source_id, BatchSubmitOutcome::NotAttempted);
+            }
+
+            let outcome = match translation_submit(item).await {
+                Ok(_) => BatchSubmitOutcome::Submitted,
+                Err(err) => {
+                    if err.is_auth_expired() || err.is_offline() {
+                        halted.store(true, Ordering::SeqCst);
+                    }
+
+                    BatchSubmitOutcome::Failed(err)
+                }
+            };
+
+            (index, source_id, outcome)
+        });
+    }
+
+    let mut outcomes = Vec::with_capacity(set.len());
+
+    while let Some(joined) = set.join_next().await {
+        outcomes
+            .push(joined.map_err(|err| AppError::Other(format!("批量提交翻译任务异常: {}", err)))?);
+    }
+
+    outcomes.sort_by_key(|(index, ..)| *index);
+
+    let mut report = BatchSubmitReport::default();
+
+    for (_, source_id, outcome) in outcomes {
+        match outcome {
+            BatchSubmitOutcome::Submitted => report.succeeded.push(source_id),
+            BatchSubmitOutcome::Failed(error) => {
+                tracing::warn!(%source_id, %error, "moetran.translation.batch_submit.item_failed");
+                report
+                    .failed
+                    .push(TranslationSubmitFailure { source_id, error });
+            }
+            BatchSubmitOutcome::NotAttempted => report.not_attempted.push(source_id),
+        }
+    }
+
+    tracing::info!(
+        succeeded = report.succeeded.len(),
+        failed = report.failed.len(),
+        not_attempted = report.not_attempted.len(),
+        "moetran.translation.batch_submit.ok"
+    );
+
     defer.success();
 
+    Ok(report)
+}
+
+// 提交翻译更新，并同步本地的翻译记录与页面快照
+pub(crate) async fn put_translation(
+    translation_id: &str,
+    body: Map<String, Value>,
+) -> Result<MoetranTranslation, AppError> {
+    let path = format!("translations/{}", translation_id);
+
+    let reply =
+        moetran_put_opt::<Value, MoetranTranslation>(&path, Some(Value::Object(body))).await?;
+
+    remember_translations(std::iter::once(&reply));
+    invalidate_snapshots_for(translation_id).await;
+
     Ok(reply)
 }
 
 // 更新翻译稿（包括校对状态与校对内容）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateTranslationReq {
-    pub translation_id: String,
+    pub translation_id: TranslationId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub selected: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proofread_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    // 更新译文内容时传入，用于成功后清除对应草稿
+    #[serde(default)]
+    pub source_id: Option<SourceId>,
+    #[serde(default)]
+    pub target_id: Option<TargetId>,
+    // 为 true 时返回 MutationResult（含更新前的翻译）
+    #[serde(default)]
+    pub verbose: bool,
+    // 为 true 时跳过内容规范化，原样提交
+    #[serde(default)]
+    pub raw: bool,
+    // 用于读取汉化组的标点规则；不提供时不替换标点
+    #[serde(default)]
+    pub team_id: Option<TeamId>,
+    // 为 true 时允许把译文改为空白（默认拒绝，返回 empty_content 错误）；
+    // 校对内容为 "" 表示清除校对，不受此限制
+    #[serde(default)]
+    pub allow_empty: bool,
 }
 
 #[tauri::command]
 pub async fn update_translation(
     payload: UpdateTranslationReq,
-) -> Result<MoetranTranslation, String> {
+) -> Result<WithNormalization<MutationReply<MoetranTranslation, MoetranTranslation>>, AppError> {
+    let retry = RetryScope::begin(
+        "update_translation",
+        format!("translation:{}", payload.translation_id),
+        &payload,
+    )
+    .await;
+
+    retry.finish(translation_update(payload).await).await
+}
+
+async fn translation_update(
+    payload: UpdateTranslationReq,
+) -> Result<WithNormalization<MutationReply<MoetranTranslation, MoetranTranslation>>, AppError> {
     let has_selected = payload.selected.is_some();
     let has_proof = payload.proofread_content.is_some();
     let has_content = payload.content.is_some();
 
     if !has_selected && !has_proof && !has_content {
-        return Err("至少需要一个可更新字段".to_string());
+        return Err(AppError::InvalidInput("至少需要一个可更新字段".to_string()));
+    }
+
+    if !payload.allow_empty {
+        if payload.content.as_deref().is_some_and(is_blank) {
+            return Err(empty_content_error("content"));
+        }
+
+        if payload
+            .proofread_content
+            .as_deref()
+            .is_some_and(|proof| !proof.is_empty() && is_blank(proof))
+        {
+            return Err(empty_content_error("proofread_content"));
+        }
     }
 
     tracing::info!(
@@ -1464,26 +3329,35 @@ pub async fn update_translation(
 
     let mut defer = WarnDefer::new("moetran.translation.update");
 
+    let previous = cached_translation(&payload.translation_id);
+
     let mut body = Map::new();
+    let mut applied = Vec::new();
 
     if let Some(selected) = payload.selected {
         body.insert("selected".to_string(), Value::Bool(selected));
     }
 
-    if let Some(proof) = payload.proofread_content {
-        body.insert("proofread_content".to_string(), Value::String(proof));
-    }
+    for (key, value) in [
+        ("proofread_content", payload.proofread_content),
+        ("content", payload.content),
+    ] {
+        let Some(mut value) = value else {
+            continue;
+        };
+
+        if !payload.raw {
+            let normalized = normalize_for_submit(&value, payload.team_id.as_deref()).await;
+            value = normalized.text;
+            applied.push(normalized.applied);
+        }
 
-    if let Some(content) = payload.content {
-        body.insert("content".to_string(), Value::String(content));
+        body.insert(key.to_string(), Value::String(value));
     }
 
-    let path = format!("translations/{}", payload.translation_id);
-
-    let reply =
-        moetran_put_opt::<serde_json::Value, MoetranTranslation>(&path, Some(Value::Object(body)))
-            .await
-            .map_err(|err| format!("更新翻译失败: {}", err))?;
+    let reply = put_translation(&payload.translation_id, body)
+        .await
+        .map_err(|err| err.context("更新翻译失败"))?;
 
     tracing::info!(
         translation_id = %reply.id,
@@ -1491,93 +3365,140 @@ pub async fn update_translation(
         "moetran.translation.update.ok"
     );
 
+    if has_content {
+        sync_updated_content(&reply);
+    }
+
+    if let Some(target_id) = &payload.target_id {
+        record_translation_activity(&payload.translation_id, target_id, FileAction::Update);
+    }
+
+    if let (true, Some(source_id), Some(target_id)) =
+        (has_content, &payload.source_id, &payload.target_id)
+    {
+        clear_draft_after_submit(source_id, target_id).await;
+    }
+
     defer.success();
 
-    Ok(reply)
+    let result = MutationResult::new(reply.clone(), previous);
+
+    Ok(WithNormalization {
+        value: MutationReply::build(payload.verbose, reply, result),
+        normalized: merge_applied(applied),
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProxyImageReply {
     pub b64: String,
     pub content_type: String,
+    // 原 url 签名过期、改用刷新后的 url 取得图片时返回新 url，前端应替换本地记录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refreshed_url: Option<String>,
 }
 
+// project_id / file_id 用于 url 签名过期（403 / 410）时刷新 url；未提供 file_id 时按 url 在缓存清单中查找
 #[tauri::command]
-pub async fn proxy_image(url: String) -> Result<ProxyImageReply, String> {
+pub async fn proxy_image(
+    url: String,
+    project_id: Option<String>,
+    file_id: Option<String>,
+) -> Result<ProxyImageReply, AppError> {
     tracing::info!(%url, "proxy_image.request.start");
 
-    // Basic validation: parse URL and whitelist host
-    let parsed = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
-    let host = parsed
-        .host_str()
-        .ok_or_else(|| "URL missing host".to_string())?;
-
-    // Only allow m-t.pics subdomains for now. Adjust whitelist as needed.
-    if !host.ends_with("m-t.pics") {
-        return Err("Host not allowed".to_string());
+    // 演示模式下返回占位图，不访问真实图床
+    if let Some(result) = demo::proxied_image(&url) {
+        return result
+            .map(|bytes| ProxyImageReply {
+                b64: general_purpose::STANDARD.encode(bytes),
+                content_type: "image/png".to_string(),
+                refreshed_url: None,
+            })
+            .map_err(AppError::Other);
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(15))
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
-
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        ACCEPT,
-        HeaderValue::from_static(
-            "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8",
-        ),
-    );
-    headers.insert(REFERER, HeaderValue::from_static("https://moetran.com/"));
-    headers.insert(
-        USER_AGENT,
-        HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/142.0.0.0 Safari/537.36 Edg/142.0.0.0"),
-    );
-    headers.insert(
-        "Sec-CH-UA",
-        HeaderValue::from_static(
-            "\"Chromium\";v=\"142\", \"Microsoft Edge\";v=\"142\", \"Not_A Brand\";v=\"99\"",
-        ),
-    );
+    ensure_online()?;
 
-    let resp = client
-        .get(url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("Fetch failed: {}", e))?;
+    let started = Instant::now();
 
-    if !resp.status().is_success() {
-        return Err(format!("Remote returned status {}", resp.status()));
-    }
+    let err = match fetch_proxied_image(&url).await {
+        Ok(reply) => return Ok(reply),
+        Err(err) => err,
+    };
+
+    let Some(project_id) = project_id.filter(|_| is_expired_url_error(&err.to_string())) else {
+        return Err(err);
+    };
+
+    let file_id = match file_id {
+        Some(file_id) => Some(file_id),
+        None => manifest_file_id_for_url(&project_id, &url).await,
+    };
+
+    let Some(file_id) = file_id else {
+        return Err(err);
+    };
+
+    let Some(fresh) = replacement_url(&project_id, &file_id, &url, started).await else {
+        return Err(err);
+    };
+
+    tracing::info!(%project_id, %file_id, "proxy_image.url_refreshed");
 
-    // Enforce size limit (32 MB)
-    let content_length = resp.content_length().unwrap_or(0);
-    if content_length > 32 * 1024 * 1024 {
-        return Err("Remote file too large".to_string());
+    match fetch_proxied_image(&fresh).await {
+        Ok(reply) => {
+            update_manifest_url(&project_id, &file_id, &fresh).await;
+
+            Ok(ProxyImageReply {
+                refreshed_url: Some(fresh),
+                ..reply
+            })
+        }
+        Err(retry_err) => {
+            tracing::warn!(%project_id, %file_id, error = %retry_err, "proxy_image.refreshed_url_failed");
+            Err(err)
+        }
     }
+}
 
-    let content_type = resp
-        .headers()
-        .get(CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("application/octet-stream")
-        .to_string();
+// 图床偶发超时：网络错误与 5xx 按 RetryPolicy::IMAGE 重试；403 / 410（签名过期）不重试，由调用方刷新 url
+async fn fetch_proxied_image(url: &str) -> Result<ProxyImageReply, AppError> {
+    with_retry(RetryPolicy::IMAGE, "proxy_image", || {
+        fetch_proxied_image_once(url)
+    })
+    .await
+}
 
-    let bytes = resp
-        .bytes()
-        .await
-        .map_err(|e| format!("Read body failed: {}", e))?;
-    if (bytes.len() as u64) > 32 * 1024 * 1024 {
-        return Err("Remote file too large".to_string());
+async fn fetch_proxied_image_once(url: &str) -> Result<ProxyImageReply, AppError> {
+    // Basic validation: parse URL and whitelist host
+    let parsed =
+        Url::parse(url).map_err(|e| AppError::InvalidInput(format!("Invalid URL: {}", e)))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| AppError::InvalidInput("URL missing host".to_string()))?;
+
+    // Only allow m-t.pics subdomains for now. Adjust whitelist as needed.
+    if !host.ends_with("m-t.pics") {
+        return Err(AppError::InvalidInput("Host not allowed".to_string()));
     }
 
-    let b64 = general_purpose::STANDARD.encode(&bytes);
+    // 与图片缓存下载共用请求头与大小限制
+    let raw = moetran_get_raw(url).await?;
+
+    let content_type = raw
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let b64 = general_purpose::STANDARD.encode(&raw.bytes);
 
-    tracing::info!(size = bytes.len(), "proxy_image.request.ok");
+    tracing::info!(size = raw.bytes.len(), "proxy_image.request.ok");
 
-    Ok(ProxyImageReply { b64, content_type })
+    Ok(ProxyImageReply {
+        b64,
+        content_type,
+        refreshed_url: None,
+    })
 }
 
 // ========== 更新项目状态与发布（PopRaKo API #9, #10） ==========
@@ -1585,13 +3506,53 @@ pub async fn proxy_image(url: String) -> Result<ProxyImageReply, String> {
 // 更新项目流程状态（仅项目负责人可调用）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateProjStatusReq {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
     pub status_type: String, // "translating" / "proofreading" / "typesetting" / "reviewing"
     pub new_status: i32,     // 0=pending, 1=wip, 2=completed
+    // PopRaKo 暂时不可达时是否加入离线重试队列
+    #[serde(default)]
+    pub queue_on_failure: bool,
+    // 为 true 时返回 MutationResult（含变更前的状态）
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+// 执行 PopRaKo 状态更新请求（命令与离线重试队列共用）
+pub(crate) async fn put_proj_status(
+    proj_id: &str,
+    status_type: &str,
+    new_status: i32,
+) -> Result<(), AppError> {
+    let path = format!("projs/{}/status", proj_id);
+
+    let body = serde_json::json!({
+        "proj_id": proj_id,
+        "status_type": status_type,
+        "new_status": new_status,
+    });
+
+    // PopRaKo API returns 204 No Content on success
+    // Use unit `()` as the expected response type so empty body / 204 is handled.
+    poprako_put_opt::<serde_json::Value, ()>(&path, Some(body)).await
 }
 
 #[tauri::command]
-pub async fn update_proj_status(payload: UpdateProjStatusReq) -> Result<(), String> {
+pub async fn update_proj_status(
+    payload: UpdateProjStatusReq,
+) -> Result<MutationReply<WriteOutcome, i32>, AppError> {
+    let retry = RetryScope::begin(
+        "update_proj_status",
+        format!("proj:{}:{}", payload.proj_id, payload.status_type),
+        &payload,
+    )
+    .await;
+
+    retry.finish(proj_status_update(payload).await).await
+}
+
+async fn proj_status_update(
+    payload: UpdateProjStatusReq,
+) -> Result<MutationReply<WriteOutcome, i32>, AppError> {
     tracing::info!(
         proj_id = %payload.proj_id,
         status_type = %payload.status_type,
@@ -1601,40 +3562,73 @@ pub async fn update_proj_status(payload: UpdateProjStatusReq) -> Result<(), Stri
 
     let mut defer = WarnDefer::new("poprako.proj.status.update");
 
-    let path = format!("projs/{}/status", payload.proj_id);
-
-    let body = serde_json::json!({
-        "proj_id": payload.proj_id,
-        "status_type": payload.status_type,
-        "new_status": payload.new_status,
+    let previous = cached_proj_snapshot(&payload.proj_id).and_then(|mut item| {
+        enriched_status_mut(&mut item, &payload.status_type).and_then(|status| *status)
     });
 
-    // PopRaKo API returns 204 No Content on success
-    // Use unit `()` as the expected response type so empty body / 204 is handled.
-    poprako_put_opt::<serde_json::Value, ()>(&path, Some(body))
-        .await
-        .map_err(|err| format!("更新项目状态失败: {}", err))?;
+    let write = PoprakoWrite::ProjStatus {
+        proj_id: payload.proj_id.to_string(),
+        status_type: payload.status_type.clone(),
+        new_status: payload.new_status,
+        observed_status: previous,
+    };
+
+    let result = put_proj_status(&payload.proj_id, &payload.status_type, payload.new_status).await;
+
+    let outcome = match result {
+        Ok(()) => {
+            supersede_pending(&write).await;
+            WriteOutcome::Applied
+        }
+        Err(err) if payload.queue_on_failure && is_connectivity_error(&err) => {
+            queue_write(&write, &err).await?
+        }
+        Err(err) => return Err(err.context("更新项目状态失败")),
+    };
 
     tracing::info!(
         proj_id = %payload.proj_id,
         status_type = %payload.status_type,
         new_status = payload.new_status,
+        ?outcome,
         "poprako.proj.status.update.ok"
     );
 
+    if matches!(outcome, WriteOutcome::Applied) {
+        patch_cached_proj(&payload.proj_id, |item| {
+            if let Some(status) = enriched_status_mut(item, &payload.status_type) {
+                *status = Some(payload.new_status);
+            }
+        });
+    }
+
     defer.success();
 
-    Ok(())
+    let result = MutationResult::new(payload.new_status, previous).with_outcome(outcome.clone());
+
+    Ok(MutationReply::build(payload.verbose, outcome, result))
 }
 
 // 标记项目为已发布（仅项目负责人可调用）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PublishProjReq {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
+    // PopRaKo 暂时不可达时是否加入离线重试队列
+    #[serde(default)]
+    pub queue_on_failure: bool,
+}
+
+// 执行 PopRaKo 发布请求（命令与离线重试队列共用）
+pub(crate) async fn put_proj_publish(proj_id: &str) -> Result<(), AppError> {
+    let path = format!("projs/{}/publish", proj_id);
+
+    // PopRaKo API returns 204 No Content on success (no body)
+    // Use unit `()` as the expected response type so empty body / 204 is handled.
+    poprako_put_opt::<(), ()>(&path, None).await
 }
 
 #[tauri::command]
-pub async fn publish_proj(payload: PublishProjReq) -> Result<(), String> {
+pub async fn publish_proj(payload: PublishProjReq) -> Result<WriteOutcome, AppError> {
     tracing::info!(
         proj_id = %payload.proj_id,
         "poprako.proj.publish.request.start"
@@ -1642,97 +3636,289 @@ pub async fn publish_proj(payload: PublishProjReq) -> Result<(), String> {
 
     let mut defer = WarnDefer::new("poprako.proj.publish");
 
-    let path = format!("projs/{}/publish", payload.proj_id);
+    let write = PoprakoWrite::Publish {
+        proj_id: payload.proj_id.to_string(),
+    };
 
-    // PopRaKo API returns 204 No Content on success (no body)
-    // Use unit `()` as the expected response type so empty body / 204 is handled.
-    poprako_put_opt::<(), ()>(&path, None)
-        .await
-        .map_err(|err| format!("标记项目为已发布失败: {}", err))?;
+    let outcome = match put_proj_publish(&payload.proj_id).await {
+        Ok(()) => {
+            supersede_pending(&write).await;
+            WriteOutcome::Applied
+        }
+        Err(err) if payload.queue_on_failure && is_connectivity_error(&err) => {
+            queue_write(&write, &err).await?
+        }
+        Err(err) => return Err(err.context("标记项目为已发布失败")),
+    };
+
+    if matches!(outcome, WriteOutcome::Applied) {
+        if let Some(storage) = LOCAL_STORAGE.get() {
+            if let Err(err) =
+                publish_records::record_publish(storage.pool(), &payload.proj_id, None, false, None)
+                    .await
+            {
+                tracing::warn!(proj_id = %payload.proj_id, error = %err, "publish.record.failed");
+            }
+        }
+    }
 
     tracing::info!(
         proj_id = %payload.proj_id,
+        ?outcome,
         "poprako.proj.publish.ok"
     );
 
     defer.success();
 
-    Ok(())
+    Ok(outcome)
 }
 
-// 上传漫画页文件到 Moetran 项目
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct UploadProjectFileReq {
-    pub project_id: String,
-    pub file_name: String,
-    pub file_bytes: Vec<u8>,
-}
+// Moetran 接受的漫画页文件类型（gif 用于制作组信息页等）
+const UPLOAD_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "webp", "gif"];
 
-#[tauri::command]
-pub async fn upload_project_file(payload: UploadProjectFileReq) -> Result<(), String> {
-    tracing::info!(
-        project_id = %payload.project_id,
-        file_name = %payload.file_name,
-        file_size = payload.file_bytes.len(),
-        "moetran.project.file.upload.start"
-    );
+// 多文件上传的并发数
+const UPLOAD_CONCURRENCY: usize = 3;
 
-    let mut defer = WarnDefer::new("moetran.project.file.upload");
+fn unsupported_upload_type(ext: &str) -> AppError {
+    AppError::InvalidInput(format!(
+        "Unsupported file type: {}. Only {} are allowed",
+        ext,
+        UPLOAD_EXTENSIONS.join("/")
+    ))
+}
 
-    // 验证文件类型（仅支持 jpg/jpeg/png/bmp）
-    let ext = payload
-        .file_name
-        .rsplit('.')
-        .next()
-        .unwrap_or("")
-        .to_lowercase();
-    if !matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "bmp") {
-        return Err(format!(
-            "Unsupported file type: {}. Only jpg/jpeg/png/bmp are allowed",
-            ext
-        ));
+fn upload_extension(file_name: &str) -> String {
+    file_name.rsplit('.').next().unwrap_or("").to_lowercase()
+}
+
+// 验证文件类型（扩展名须在 UPLOAD_EXTENSIONS 中）
+fn check_upload_extension(file_name: &str) -> Result<(), AppError> {
+    let ext = upload_extension(file_name);
+
+    if !UPLOAD_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(unsupported_upload_type(&ext));
+    }
+
+    Ok(())
+}
+
+// 按文件头判断上传文件的实际格式并给出 MIME；与扩展名不符或无法识别时报错。
+// 图片缓存不处理 BMP，这里单独识别
+fn upload_mime(file_name: &str, data: &[u8]) -> Result<&'static str, AppError> {
+    let ext = match upload_extension(file_name).as_str() {
+        "jpeg" => "jpg".to_string(),
+        ext => ext.to_string(),
+    };
+
+    let Some(detected) = sniff_extension(data).or_else(|| data.starts_with(b"BM").then_some("bmp"))
+    else {
+        return Err(AppError::InvalidInput(format!(
+            "Cannot recognize the content of {}: the file header is not a valid {} image",
+            file_name, ext
+        )));
+    };
+
+    if detected != ext {
+        return Err(AppError::InvalidInput(format!(
+            "File {} has extension .{} but its content is {}",
+            file_name, ext, detected
+        )));
     }
 
-    // 构建 multipart/form-data 请求
+    match detected {
+        "png" => Ok("image/png"),
+        "jpg" => Ok("image/jpeg"),
+        "webp" => Ok("image/webp"),
+        "gif" => Ok("image/gif"),
+        "bmp" => Ok("image/bmp"),
+        other => Err(unsupported_upload_type(other)),
+    }
+}
+
+// 单个文件上传的超时（仍受调用方设置的整体时限约束）
+const UPLOAD_TIMEOUT_SECS: u64 = 120;
+
+// 以 multipart/form-data 上传一个文件到 Moetran 项目（单文件与多文件上传共用）
+async fn post_project_file(
+    project_id: &str,
+    file_name: &str,
+    file_bytes: Vec<u8>,
+    mime: &str,
+) -> Result<(), AppError> {
     let token = match get_moetran_token().await {
         Ok(Some(t)) => t,
-        Ok(None) => return Err("Missing Moetran token: Authorization required".to_string()),
-        Err(e) => return Err(format!("Failed to get Moetran token: {}", e)),
+        Ok(None) => {
+            return Err(AppError::Other(
+                "Missing Moetran token: Authorization required".to_string(),
+            ))
+        }
+        Err(e) => {
+            return Err(AppError::Storage(format!(
+                "Failed to get Moetran token: {}",
+                e
+            )))
+        }
     };
 
     let form = reqwest::multipart::Form::new().part(
         "file",
-        reqwest::multipart::Part::bytes(payload.file_bytes)
-            .file_name(payload.file_name.clone())
-            .mime_str("application/octet-stream")
-            .map_err(|err| format!("Failed to set file mime type: {}", err))?,
+        reqwest::multipart::Part::bytes(file_bytes)
+            .file_name(file_name.to_string())
+            .mime_str(mime)
+            .map_err(|err| AppError::Other(format!("Failed to set file mime type: {}", err)))?,
     );
 
-    let base_url = std::env::var("MOETRAN_URL").unwrap_or("https://api.moetran.com".to_string());
-    let url = format!("{}/v1/projects/{}/files", base_url, payload.project_id);
+    let url = format!("{}projects/{}/files", config().moetran_api_base, project_id);
 
     let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(120))
         .build()
-        .map_err(|err| format!("Failed to create HTTP client: {}", err))?;
+        .map_err(|err| AppError::Other(format!("Failed to create HTTP client: {}", err)))?;
 
-    let resp = client
+    let req = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", token))
-        .multipart(form)
+        .multipart(form);
+
+    let resp = apply_budget(req, Duration::from_secs(UPLOAD_TIMEOUT_SECS))?
         .send()
         .await
-        .map_err(|err| format!("File upload failed: {}", err))?;
+        .map_err(|err| match send_error(err) {
+            AppError::Network(message) => {
+                AppError::Network(format!("File upload failed: {}", message))
+            }
+            other => other,
+        })?;
 
     let status = resp.status();
     if !status.is_success() {
         let body = resp.text().await.unwrap_or_else(|_| "<empty>".to_string());
-        return Err(format!(
+        return Err(AppError::Other(format!(
             "File upload failed with status {}: {}",
             status, body
-        ));
+        )));
+    }
+
+    Ok(())
+}
+
+// 项目中已存在同名文件时的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDuplicate {
+    // 跳过同名文件，在结果中列出
+    #[default]
+    Skip,
+    // 存在同名文件时整体失败，不上传任何文件
+    Error,
+    // 不检查，直接上传
+    UploadAnyway,
+}
+
+// 上传前的预检：项目中已有的文件（按文件名精确匹配）
+struct ExistingFiles {
+    by_name: HashMap<String, MoetranProjectFile>,
+}
+
+impl ExistingFiles {
+    // on_duplicate 为 upload_anyway 时不发起请求，返回 None
+    async fn load(
+        project_id: &ProjectId,
+        on_duplicate: OnDuplicate,
+    ) -> Result<Option<Self>, AppError> {
+        if on_duplicate == OnDuplicate::UploadAnyway {
+            return Ok(None);
+        }
+
+        let files = get_project_files(GetProjectFilesReq {
+            project_id: project_id.clone(),
+            target_id: None,
+            sort: None,
+        })
+        .await
+        .map_err(|err| err.context("检查项目中的同名文件失败"))?;
+
+        Ok(Some(Self {
+            by_name: files
+                .into_iter()
+                .map(|file| (file.name.clone(), file))
+                .collect(),
+        }))
+    }
+
+    // 同名文件；两边都有文件大小时一并给出大小是否相同
+    fn find(&self, file_name: &str, size: Option<u64>) -> Option<FileUploadOutcome> {
+        self.by_name
+            .get(file_name)
+            .map(|file| FileUploadOutcome::SkippedDuplicate {
+                existing_file_id: file.id.to_string(),
+                same_size: size.zip(file.size_bytes).map(|(a, b)| a == b),
+            })
+    }
+}
+
+fn duplicate_error(names: &[&str]) -> AppError {
+    AppError::Conflict(format!("项目中已存在同名文件: {}", names.join(", ")))
+}
+
+// 上传漫画页文件到 Moetran 项目
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadProjectFileReq {
+    pub project_id: ProjectId,
+    pub file_name: String,
+    pub file_bytes: Vec<u8>,
+    #[serde(default)]
+    pub on_duplicate: OnDuplicate,
+}
+
+#[tauri::command]
+pub async fn upload_project_file(
+    payload: UploadProjectFileReq,
+) -> Result<FileUploadOutcome, AppError> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        file_name = %payload.file_name,
+        file_size = payload.file_bytes.len(),
+        "moetran.project.file.upload.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.project.file.upload");
+
+    ensure_online()?;
+
+    check_upload_extension(&payload.file_name)?;
+
+    let size = payload.file_bytes.len() as u64;
+
+    let duplicate = ExistingFiles::load(&payload.project_id, payload.on_duplicate)
+        .await?
+        .and_then(|existing| existing.find(&payload.file_name, Some(size)));
+
+    if let Some(duplicate) = duplicate {
+        if payload.on_duplicate == OnDuplicate::Error {
+            return Err(duplicate_error(&[payload.file_name.as_str()]));
+        }
+
+        tracing::info!(
+            project_id = %payload.project_id,
+            file_name = %payload.file_name,
+            "moetran.project.file.upload.skipped_duplicate"
+        );
+
+        defer.success();
+
+        return Ok(duplicate);
     }
 
+    let mime = upload_mime(&payload.file_name, &payload.file_bytes)?;
+
+    post_project_file(
+        &payload.project_id,
+        &payload.file_name,
+        payload.file_bytes,
+        mime,
+    )
+    .await?;
+
     tracing::info!(
         project_id = %payload.project_id,
         file_name = %payload.file_name,
@@ -1741,7 +3927,258 @@ pub async fn upload_project_file(payload: UploadProjectFileReq) -> Result<(), St
 
     defer.success();
 
-    Ok(())
+    Ok(FileUploadOutcome::Uploaded { bytes: size })
+}
+
+// 从本地路径批量上传漫画页：在 Rust 侧读取文件，避免整张图片经 IPC 传输
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadProjectFilesFromPathsReq {
+    pub project_id: ProjectId,
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub on_duplicate: OnDuplicate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum FileUploadOutcome {
+    Uploaded {
+        bytes: u64,
+    },
+    // 项目中已有同名文件，未上传；same_size 在无法比较大小时为 None
+    SkippedDuplicate {
+        existing_file_id: String,
+        same_size: Option<bool>,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileUploadResult {
+    pub path: String,
+    pub file_name: String,
+    pub result: FileUploadOutcome,
+}
+
+// 多文件上传结果；files 按实际上传顺序（文件名自然排序）排列
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadFilesReport {
+    pub project_id: String,
+    pub uploaded: usize,
+    pub failed: usize,
+    // 因项目中已有同名文件而跳过的文件名
+    pub skipped_duplicates: Vec<String>,
+    pub files: Vec<FileUploadResult>,
+}
+
+fn upload_file_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+#[tauri::command]
+pub async fn upload_project_files_from_paths(
+    app: AppHandle,
+    payload: UploadProjectFilesFromPathsReq,
+) -> Result<UploadFilesReport, AppError> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        count = payload.paths.len(),
+        "moetran.project.files.upload.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.project.files.upload");
+
+    ensure_online()?;
+
+    // 按文件名自然排序，保证 Moetran 中的页序与文件名一致
+    let mut files: Vec<(String, String)> = payload
+        .paths
+        .into_iter()
+        .map(|path| (upload_file_name(&path), path))
+        .collect();
+    files.sort_by(|a, b| compare_file_names(&a.0, &b.0));
+
+    // 开始上传前先检查全部文件的类型，避免只上传了一部分
+    let rejected: Vec<&str> = files
+        .iter()
+        .filter(|(name, _)| check_upload_extension(name).is_err())
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    if !rejected.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported file type: {}. Only {} are allowed",
+            rejected.join(", "),
+            UPLOAD_EXTENSIONS.join("/")
+        )));
+    }
+
+    // 事先取得文件大小，用于同名文件的大小比较，进度事件也据此给出已发送的字节数
+    let mut sizes = Vec::with_capacity(files.len());
+    for (_, path) in &files {
+        sizes.push(tokio::fs::metadata(path).await.map(|meta| meta.len()).ok());
+    }
+
+    let mut results: Vec<Option<FileUploadOutcome>> = vec![None; files.len()];
+
+    if let Some(existing) = ExistingFiles::load(&payload.project_id, payload.on_duplicate).await? {
+        for (index, (file_name, _)) in files.iter().enumerate() {
+            results[index] = existing.find(file_name, sizes[index]);
+        }
+
+        if payload.on_duplicate == OnDuplicate::Error {
+            let duplicates: Vec<&str> = files
+                .iter()
+                .zip(&results)
+                .filter(|(_, result)| result.is_some())
+                .map(|((name, _), _)| name.as_str())
+                .collect();
+
+            if !duplicates.is_empty() {
+                return Err(duplicate_error(&duplicates));
+            }
+        }
+    }
+
+    let pending = || {
+        results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.is_none())
+    };
+
+    let total = pending().count();
+    let bytes_total = pending().map(|(index, _)| sizes[index].unwrap_or(0)).sum();
+
+    let project_id = payload.project_id.to_string();
+    let progress = Arc::new(Mutex::new(UploadProgressed {
+        project_id: project_id.clone(),
+        completed: 0,
+        total,
+        failed: 0,
+        current_file_name: String::new(),
+        bytes_sent: 0,
+        bytes_total,
+    }));
+
+    let pending: Vec<usize> = pending().map(|(index, _)| index).collect();
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(UPLOAD_CONCURRENCY));
+    let mut set = tokio::task::JoinSet::new();
+
+    // 按排序后的顺序依次取得许可，先排的文件先开始上传
+    for index in pending {
+        let (file_name, path) = &files[index];
+
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|err| AppError::Other(format!("上传任务异常: {}", err)))?;
+
+        let app = app.clone();
+        let progress = progress.clone();
+        let project_id = project_id.clone();
+        let file_name = file_name.clone();
+        let path = path.clone();
+
+        set.spawn(async move {
+            let _permit = permit;
+
+            let result = match tokio::fs::read(&path).await {
+                Ok(bytes) => {
+                    let size = bytes.len() as u64;
+
+                    let uploaded = match upload_mime(&file_name, &bytes) {
+                        Ok(mime) => post_project_file(&project_id, &file_name, bytes, mime).await,
+                        Err(err) => Err(err),
+                    };
+
+                    match uploaded {
+                        Ok(()) => FileUploadOutcome::Uploaded { bytes: size },
+                        Err(err) => FileUploadOutcome::Failed {
+                            message: err.to_string(),
+                        },
+                    }
+                }
+                Err(err) => FileUploadOutcome::Failed {
+                    message: format!("读取文件失败: {}", err),
+                },
+            };
+
+            let event = progress.lock().ok().map(|mut progress| {
+                progress.completed += 1;
+                progress.current_file_name = file_name;
+
+                match &result {
+                    FileUploadOutcome::Uploaded { bytes } => progress.bytes_sent += bytes,
+                    FileUploadOutcome::SkippedDuplicate { .. } => {}
+                    FileUploadOutcome::Failed { .. } => progress.failed += 1,
+                }
+
+                progress.clone()
+            });
+
+            if let Some(event) = event {
+                emit_event(&app, event);
+            }
+
+            (index, result)
+        });
+    }
+
+    while let Some(joined) = set.join_next().await {
+        let (index, result) =
+            joined.map_err(|err| AppError::Other(format!("上传任务异常: {}", err)))?;
+        results[index] = Some(result);
+    }
+
+    let files: Vec<FileUploadResult> = files
+        .into_iter()
+        .zip(results)
+        .map(|((file_name, path), result)| FileUploadResult {
+            path,
+            file_name,
+            result: result.unwrap_or(FileUploadOutcome::Failed {
+                message: "未处理".to_string(),
+            }),
+        })
+        .collect();
+
+    let count = |pred: fn(&FileUploadOutcome) -> bool| {
+        files.iter().filter(|file| pred(&file.result)).count()
+    };
+
+    let skipped_duplicates = files
+        .iter()
+        .filter(|file| matches!(file.result, FileUploadOutcome::SkippedDuplicate { .. }))
+        .map(|file| file.file_name.clone())
+        .collect();
+
+    let report = UploadFilesReport {
+        project_id,
+        uploaded: count(|r| matches!(r, FileUploadOutcome::Uploaded { .. })),
+        failed: count(|r| matches!(r, FileUploadOutcome::Failed { .. })),
+        skipped_duplicates,
+        files,
+    };
+
+    tracing::info!(
+        project_id = %report.project_id,
+        uploaded = report.uploaded,
+        failed = report.failed,
+        skipped = report.skipped_duplicates.len(),
+        "moetran.project.files.upload.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
 }
 
 // ==================== Assignment 相关 ====================
@@ -1749,11 +4186,11 @@ pub async fn upload_project_file(payload: UploadProjectFileReq) -> Result<(), St
 // PopRaKo Assignment DTO（对应 API 文档中的 ProjAssignInfo）
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PoprakoAssignment {
-    pub proj_id: String,
+    pub proj_id: ProjectId,
     pub proj_name: String,
     pub projset_serial: u32,
     pub projset_index: u32,
-    pub member_id: String,
+    pub member_id: MemberId,
     pub username: String,
     pub is_translator: bool,
     pub is_proofreader: bool,
@@ -1772,7 +4209,9 @@ pub struct GetAssignmentsReq {
 
 // 获取 assignments 列表（调用 PopRaKo GET /assigns）
 #[tauri::command]
-pub async fn get_assignments(payload: GetAssignmentsReq) -> Result<Vec<PoprakoAssignment>, String> {
+pub async fn get_assignments(
+    payload: GetAssignmentsReq,
+) -> Result<Vec<PoprakoAssignment>, AppError> {
     tracing::info!(
         time_start = payload.time_start,
         "poprako.assigns.list.request.start"
@@ -1783,9 +4222,12 @@ pub async fn get_assignments(payload: GetAssignmentsReq) -> Result<Vec<PoprakoAs
     let mut query = std::collections::HashMap::new();
     query.insert("time_start", payload.time_start.to_string());
 
-    let reply = poprako_get::<PoprakoEnvelope<Vec<PoprakoAssignment>>>("assigns", Some(&query))
-        .await
-        .map_err(|err| format!("获取派活列表失败: {}", err))?;
+    let reply = poprako_get::<PoprakoEnvelope<FlexibleList<PoprakoAssignment, AssignsField>>>(
+        "assigns",
+        Some(&query),
+    )
+    .await
+    .map_err(|err| err.context("获取派活列表失败"))?;
 
     if reply.code != 200 {
         let msg = reply
@@ -1798,12 +4240,16 @@ pub async fn get_assignments(payload: GetAssignmentsReq) -> Result<Vec<PoprakoAs
             "poprako.assigns.list.failed"
         );
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let data = reply
         .data
-        .ok_or_else(|| "PopRaKo 获取派活列表返回空数据".to_string())?;
+        .ok_or_else(|| "PopRaKo 获取派活列表返回空数据".to_string())?
+        .into_items("assigns");
 
     let count = data.len();
     tracing::info!(
@@ -1821,6 +4267,704 @@ pub async fn get_assignments(payload: GetAssignmentsReq) -> Result<Vec<PoprakoAs
 #[tauri::command]
 pub async fn create_poprako_projset(
     payload: CreateProjsetReq,
-) -> Result<PoprakoProjSetCreateData, String> {
+) -> Result<CreateWithNameCheck<PoprakoProjSetCreateData>, AppError> {
     create_projset(payload).await
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_partial_json, method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        config,
+        test_support::{enriched_fixture, MockBackends},
+    };
+
+    fn team_req(team_id: &str) -> GetTeamProjectsEnrichedReq {
+        GetTeamProjectsEnrichedReq {
+            team_id: team_id.into(),
+            page: 1,
+            limit: 20,
+            deadline_ms: None,
+        }
+    }
+
+    async fn mount_gateway_error(backends: &MockBackends, team_id: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/teams/{}/projects", team_id)))
+            .respond_with(
+                ResponseTemplate::new(502)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string(
+                        "<html><head><title>502 Bad Gateway</title></head>\
+                         <body><center>nginx</center></body></html>",
+                    ),
+            )
+            .mount(&backends.moetran)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn gateway_error_falls_back_to_stale_enriched_list() {
+        let backends = MockBackends::start().await;
+        mount_gateway_error(&backends, "team-fallback").await;
+
+        let cached = vec![
+            enriched_fixture("p1", "team-fallback"),
+            enriched_fixture("p2", "team-fallback"),
+        ];
+        remember_enriched_list(&CachedPage::team("team-fallback", 1, 20).key(), &cached);
+
+        let reply = team_projects_enriched(team_req("team-fallback"))
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = reply.items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["p1", "p2"]);
+        assert!(reply.items.iter().all(|item| item.stale == Some(true)));
+        assert!(reply.pagination.is_none());
+        assert!(!reply.deadline_exceeded);
+    }
+
+    #[tokio::test]
+    async fn gateway_error_without_cache_is_returned() {
+        let backends = MockBackends::start().await;
+        mount_gateway_error(&backends, "team-uncached").await;
+
+        let err = team_projects_enriched(team_req("team-uncached"))
+            .await
+            .unwrap_err();
+
+        assert!(err.is_service_unavailable());
+        assert!(err.to_string().starts_with("获取团队项目列表失败"));
+    }
+
+    #[tokio::test]
+    async fn status_update_replies_with_write_outcome() {
+        let backends = MockBackends::start().await;
+        crate::test_support::local_storage().await;
+
+        Mock::given(method("PUT"))
+            .and(path("/v1/projs/p-shape/status"))
+            .respond_with(ResponseTemplate::new(204))
+            .up_to_n_times(1)
+            .mount(&backends.poprako)
+            .await;
+
+        let req = |queue_on_failure| UpdateProjStatusReq {
+            proj_id: "p-shape".into(),
+            status_type: "translating".to_string(),
+            new_status: 1,
+            queue_on_failure,
+            verbose: false,
+        };
+
+        let reply = update_proj_status(req(false)).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            json!({ "outcome": "applied" })
+        );
+
+        // 之后 PopRaKo 进入维护：入队并返回 pending_id
+        Mock::given(method("PUT"))
+            .and(path("/v1/projs/p-shape/status"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&backends.poprako)
+            .await;
+
+        let reply = serde_json::to_value(update_proj_status(req(true)).await.unwrap()).unwrap();
+        assert_eq!(reply["outcome"], "queued");
+        assert!(reply["pending_id"].is_i64());
+
+        crate::write_queue::supersede_pending(&PoprakoWrite::ProjStatus {
+            proj_id: "p-shape".to_string(),
+            status_type: "translating".to_string(),
+            new_status: 1,
+            observed_status: None,
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn other_errors_do_not_fall_back() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/teams/team-forbidden/projects"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("{}"))
+            .mount(&backends.moetran)
+            .await;
+
+        remember_enriched_list(
+            &CachedPage::team("team-forbidden", 1, 20).key(),
+            &[enriched_fixture("p1", "team-forbidden")],
+        );
+
+        let err = team_projects_enriched(team_req("team-forbidden"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.root(),
+            AppError::MoetranHttp { status: 403, .. }
+        ));
+    }
+
+    fn proj_info_json(id: &str) -> serde_json::Value {
+        json!({
+            "proj_id": id,
+            "proj_name": format!("项目 {}", id),
+            "projset_index": 1,
+            "translating_status": 1,
+            "proofreading_status": 0,
+            "typesetting_status": 0,
+            "reviewing_status": 0,
+            "is_published": false,
+        })
+    }
+
+    #[tokio::test]
+    async fn enrichment_is_chunked_and_tolerates_a_failed_chunk() {
+        let backends = MockBackends::start().await;
+        config::update_for_test(|config| config.enrichment_chunk_size = 2);
+
+        for (ids, ok) in [
+            (["c1", "c2"].as_slice(), true),
+            (["c3", "c4"].as_slice(), false),
+            (["c5"].as_slice(), true),
+        ] {
+            let response = if ok {
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "code": 200,
+                    "data": ids.iter().map(|id| proj_info_json(id)).collect::<Vec<_>>(),
+                    "message": null,
+                }))
+            } else {
+                ResponseTemplate::new(422).set_body_json(json!({ "message": "too many ids" }))
+            };
+
+            Mock::given(method("POST"))
+                .and(path("/v1/projs/search"))
+                .and(body_partial_json(json!({ "proj_ids": ids })))
+                .respond_with(response)
+                .expect(1)
+                .mount(&backends.poprako)
+                .await;
+        }
+
+        let ids = ["c1", "c2", "c3", "c4", "c5"].map(ProjectId::from).to_vec();
+        let result = fetch_enrichment(ids).await;
+
+        config::update_for_test(|config| config.enrichment_chunk_size = 50);
+
+        let (map, partial_error) = result.unwrap();
+
+        let mut found: Vec<&str> = map.keys().map(|id| id.as_str()).collect();
+        found.sort();
+        assert_eq!(found, ["c1", "c2", "c5"]);
+        assert!(partial_error.unwrap().contains("1/3"));
+    }
+
+    // PopRaKo 补充失败时返回仅含 Moetran 数据的列表，且不写入兜底缓存
+    async fn assert_moetran_only_and_uncached(
+        backends: &MockBackends,
+        team_id: &str,
+        poprako_response: ResponseTemplate,
+    ) -> String {
+        let moetran_item = |id: &str| {
+            json!({
+                "id": id,
+                "name": format!("项目 {}", id),
+                "source_count": 10,
+                "translated_source_count": 4,
+                "checked_source_count": 1,
+                "team": { "id": team_id, "avatar": "", "has_avatar": false, "name": "汉化组" },
+                "project_set": { "id": "default", "name": "默认项目集" },
+            })
+        };
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/teams/{}/projects", team_id)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([moetran_item("d1"), moetran_item("d2")])),
+            )
+            .up_to_n_times(1)
+            .mount(&backends.moetran)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(poprako_response)
+            .mount(&backends.poprako)
+            .await;
+
+        let reply = team_projects_enriched(team_req(team_id)).await.unwrap();
+
+        let ids: Vec<&str> = reply.items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["d1", "d2"]);
+        assert!(reply
+            .items
+            .iter()
+            .all(|item| !item.has_poprako && item.translating_status.is_none()));
+        assert!(!reply.deadline_exceeded);
+        assert!(reply.pagination.is_some());
+
+        // 降级结果不作为兜底缓存：之后 Moetran 不可用时没有可返回的数据
+        mount_gateway_error(backends, team_id).await;
+
+        let err = team_projects_enriched(team_req(team_id)).await.unwrap_err();
+        assert!(err.is_service_unavailable());
+
+        reply.enrichment_error.unwrap()
+    }
+
+    #[tokio::test]
+    async fn failed_enrichment_returns_moetran_only_list_without_caching_it() {
+        let backends = MockBackends::start().await;
+
+        let error = assert_moetran_only_and_uncached(
+            &backends,
+            "team-degraded",
+            ResponseTemplate::new(200).set_body_json(json!({
+                "code": 500,
+                "data": null,
+                "message": "search unavailable",
+            })),
+        )
+        .await;
+
+        assert!(error.contains("search unavailable"));
+    }
+
+    #[tokio::test]
+    async fn timed_out_enrichment_returns_moetran_only_list_without_caching_it() {
+        let backends = MockBackends::start().await;
+
+        // 超过 ApiClient 的请求超时（5 秒）
+        assert_moetran_only_and_uncached(
+            &backends,
+            "team-slow-poprako",
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "code": 200, "data": [], "message": null }))
+                .set_delay(std::time::Duration::from_millis(5_500)),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn malformed_enrichment_returns_moetran_only_list_without_caching_it() {
+        let backends = MockBackends::start().await;
+
+        assert_moetran_only_and_uncached(
+            &backends,
+            "team-malformed-poprako",
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/json")
+                .set_body_string(r#"{"code": 200, "data": [{"proj_id": "d1", "#),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn slow_enrichment_is_cut_off_at_the_command_deadline() {
+        let backends = MockBackends::start().await;
+        let team_id = "team-deadline";
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/teams/{}/projects", team_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": "dl1",
+                "name": "项目 dl1",
+                "source_count": 10,
+                "translated_source_count": 4,
+                "checked_source_count": 1,
+                "team": { "id": team_id, "avatar": "", "has_avatar": false, "name": "汉化组" },
+                "project_set": { "id": "default", "name": "默认项目集" },
+            }])))
+            .mount(&backends.moetran)
+            .await;
+
+        // 远超时限，但短于 ApiClient 的请求超时（5 秒），只有整体时限能提前结束
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(
+                envelope(200, json!([])).set_delay(std::time::Duration::from_millis(3_000)),
+            )
+            .mount(&backends.poprako)
+            .await;
+
+        let deadline = std::time::Duration::from_millis(300);
+        let started = std::time::Instant::now();
+
+        let reply = get_team_projects_enriched(GetTeamProjectsEnrichedReq {
+            deadline_ms: Some(deadline.as_millis() as u64),
+            ..team_req(team_id)
+        })
+        .await
+        .unwrap();
+
+        let elapsed = started.elapsed();
+        assert!(
+            elapsed < deadline + std::time::Duration::from_millis(500),
+            "returned after {:?}",
+            elapsed
+        );
+
+        // 部分结果：Moetran 列表完整，PopRaKo 信息缺失
+        assert!(reply.deadline_exceeded);
+        assert!(reply.enrichment_error.is_some());
+        let ids: Vec<&str> = reply.items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["dl1"]);
+        assert!(!reply.items[0].has_poprako);
+    }
+
+    #[tokio::test]
+    async fn batch_source_fetch_reports_failed_pages_individually() {
+        let backends = MockBackends::start().await;
+        crate::test_support::local_storage().await;
+
+        let source = json!({
+            "id": "bs1",
+            "x": 0.5,
+            "y": 0.5,
+            "position_type": 1,
+            "my_translation": null,
+            "translations": [],
+        });
+
+        for (file_id, response) in [
+            (
+                "bf-ok1",
+                ResponseTemplate::new(200).set_body_json(json!([source])),
+            ),
+            (
+                "bf-ok2",
+                ResponseTemplate::new(200).set_body_json(json!([])),
+            ),
+            (
+                "bf-gone",
+                ResponseTemplate::new(404).set_body_string("file not found"),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/files/{}/sources", file_id)))
+                .respond_with(response)
+                .expect(1)
+                .mount(&backends.moetran)
+                .await;
+        }
+
+        // 重复的 id 只拉取一次
+        let reply = get_files_sources_batch(GetFilesSourcesBatchReq {
+            file_ids: ["bf-ok1", "bf-gone", "bf-ok2", "bf-ok1"]
+                .into_iter()
+                .map(FileId::from)
+                .collect(),
+            target_id: "bt1".into(),
+            reading_direction: None,
+        })
+        .await
+        .unwrap();
+
+        let mut fetched: Vec<&str> = reply.sources.keys().map(|id| id.as_str()).collect();
+        fetched.sort_unstable();
+        assert_eq!(fetched, ["bf-ok1", "bf-ok2"]);
+        assert_eq!(reply.sources[&FileId::from("bf-ok1")].len(), 1);
+        assert!(reply.sources[&FileId::from("bf-ok2")].is_empty());
+
+        assert_eq!(reply.failures.len(), 1);
+        let failure = &reply.failures[&FileId::from("bf-gone")];
+        assert!(failure.contains("获取页面源失败"), "{}", failure);
+        assert!(failure.contains("404"), "{}", failure);
+
+        backends.moetran.verify().await;
+    }
+
+    fn envelope(code: u16, data: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "code": code,
+            "data": data,
+            "message": format!("business code {}", code),
+        }))
+    }
+
+    async fn mount_once(
+        server: &wiremock::MockServer,
+        http_method: &str,
+        route: &str,
+        response: ResponseTemplate,
+    ) {
+        server.reset().await;
+
+        Mock::given(method(http_method))
+            .and(path(route))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn project_search_handles_business_errors_empty_data_and_expired_login() {
+        let backends = MockBackends::start().await;
+
+        let search_user = || search_user_projects_enriched(PoprakoProjFilterReq::default());
+        let search_team = || {
+            search_team_projects_enriched(SearchTeamProjectsEnrichedReq {
+                team_id: "team-search".into(),
+                filter: PoprakoProjFilterReq::default(),
+            })
+        };
+
+        mount_once(
+            &backends.poprako,
+            "POST",
+            "/v1/projs/search",
+            envelope(500, json!(null)),
+        )
+        .await;
+
+        for err in [
+            search_user().await.unwrap_err(),
+            search_team().await.unwrap_err(),
+        ] {
+            assert!(matches!(
+                err.root(),
+                AppError::PoprakoBusiness { code: 500, message } if message == "business code 500"
+            ));
+        }
+
+        for data in [json!(null), json!([])] {
+            mount_once(
+                &backends.poprako,
+                "POST",
+                "/v1/projs/search",
+                envelope(200, data),
+            )
+            .await;
+
+            assert!(search_user().await.unwrap().is_empty());
+            assert!(search_team().await.unwrap().is_empty());
+        }
+
+        // 没有命中时不访问 Moetran
+        assert!(backends
+            .moetran
+            .received_requests()
+            .await
+            .unwrap()
+            .is_empty());
+
+        mount_once(
+            &backends.poprako,
+            "POST",
+            "/v1/projs/search",
+            ResponseTemplate::new(401).set_body_string("token expired"),
+        )
+        .await;
+
+        let err = search_team().await.unwrap_err();
+        assert!(err.is_auth_expired());
+        assert!(err.to_string().starts_with("PopRaKo 项目搜索失败"));
+    }
+
+    fn submit_req(source_id: &str) -> SubmitTranslationReq {
+        SubmitTranslationReq {
+            source_id: source_id.into(),
+            target_id: "submit-target".into(),
+            content: "译文".to_string(),
+            raw: true,
+            team_id: None,
+            allow_empty: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_translation_rejects_envelopes_empty_bodies_and_expired_login() {
+        let backends = MockBackends::start().await;
+        let route = "/v1/sources/submit-src/translations";
+
+        // Moetran 不使用信封：带 code 的失败信封无法解析为译文
+        mount_once(
+            &backends.moetran,
+            "POST",
+            route,
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "code": 400, "message": "bad target" })),
+        )
+        .await;
+
+        let err = submit_translation(submit_req("submit-src"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "Other");
+        assert!(err.to_string().starts_with("提交翻译失败"));
+
+        for response in [
+            ResponseTemplate::new(200),
+            ResponseTemplate::new(200).set_body_json(json!({})),
+        ] {
+            mount_once(&backends.moetran, "POST", route, response).await;
+
+            let err = submit_translation(submit_req("submit-src"))
+                .await
+                .unwrap_err();
+            assert!(!err.is_retryable());
+            assert!(err.to_string().starts_with("提交翻译失败"));
+        }
+
+        mount_once(
+            &backends.moetran,
+            "POST",
+            route,
+            ResponseTemplate::new(401).set_body_string("token expired"),
+        )
+        .await;
+
+        let err = submit_translation(submit_req("submit-src"))
+            .await
+            .unwrap_err();
+        assert!(err.is_auth_expired());
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn batch_submit_stops_sending_after_an_expired_login() {
+        let backends = MockBackends::start().await;
+        crate::test_support::local_storage().await;
+
+        let ids: Vec<String> = (0..8).map(|n| format!("batch-src-{}", n)).collect();
+
+        // 前 SUBMIT_BATCH_CONCURRENCY 条同时发出：第 2 条立即返回 401，其余几条稍后成功
+        for (index, id) in ids.iter().enumerate() {
+            let mock =
+                Mock::given(method("POST")).and(path(format!("/v1/sources/{}/translations", id)));
+
+            let mock = match index {
+                1 => mock
+                    .respond_with(ResponseTemplate::new(401).set_body_string("token expired"))
+                    .expect(1),
+                _ if index < SUBMIT_BATCH_CONCURRENCY => mock
+                    .respond_with(
+                        ResponseTemplate::new(200)
+                            .set_body_json(json!({
+                                "id": format!("t-{}", id),
+                                "content": "译文",
+                                "proofread_content": null,
+                                "selected": false,
+                            }))
+                            .set_delay(std::time::Duration::from_millis(300)),
+                    )
+                    .expect(1),
+                // 登录过期后才轮到的条目不应再发出请求
+                _ => mock.respond_with(ResponseTemplate::new(500)).expect(0),
+            };
+
+            mock.mount(&backends.moetran).await;
+        }
+
+        let report = submit_translations_batch(SubmitTranslationsBatchReq {
+            items: ids.iter().map(|id| submit_req(id)).collect(),
+        })
+        .await
+        .unwrap();
+
+        let as_str = |ids: &[SourceId]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            as_str(&report.succeeded),
+            [ids[0].clone(), ids[2].clone(), ids[3].clone()]
+        );
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].source_id.as_str(), ids[1]);
+        assert!(report.failed[0].error.is_auth_expired());
+        assert_eq!(as_str(&report.not_attempted), &ids[4..]);
+
+        backends.moetran.verify().await;
+    }
+
+    #[tokio::test]
+    async fn create_projset_handles_business_errors_empty_data_and_expired_login() {
+        let backends = MockBackends::start().await;
+
+        let mount = |response: ResponseTemplate| async {
+            mount_once(&backends.poprako, "POST", "/v1/projsets", response).await;
+
+            // 重名检查与 token 探测
+            Mock::given(method("GET"))
+                .and(path("/v1/projsets"))
+                .respond_with(envelope(200, json!([])))
+                .mount(&backends.poprako)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/v1/user/info"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "u1" })))
+                .mount(&backends.moetran)
+                .await;
+        };
+
+        let create = || {
+            create_projset(CreateProjsetReq {
+                projset_name: "新项目集".to_string(),
+                projset_description: String::new(),
+                team_id: "team-create".into(),
+                allow_duplicate: false,
+            })
+        };
+
+        mount(envelope(400, json!(null))).await;
+        let err = create().await.unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AppError::PoprakoBusiness { code: 400, .. }
+        ));
+
+        mount(envelope(201, json!(null))).await;
+        let err = create().await.unwrap_err();
+        assert!(err.to_string().contains("返回空数据"));
+
+        mount(envelope(201, json!({ "projset_serial": 7 }))).await;
+        let created = create().await.unwrap();
+        assert_eq!(created.data.projset_serial, 7);
+        assert!(created.near_matches.is_empty());
+
+        mount(ResponseTemplate::new(401).set_body_string("token expired")).await;
+        let err = create().await.unwrap_err();
+        assert!(err.is_auth_expired());
+        assert!(err.to_string().starts_with("创建项目集失败"));
+    }
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG_HEADER: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+    const WEBP_HEADER: &[u8] = b"RIFF\x24\0\0\0WEBPVP8 ";
+    const GIF_HEADER: &[u8] = b"GIF89a\x01\0\x01\0";
+
+    #[test]
+    fn upload_mime_follows_magic_bytes() {
+        assert_eq!(upload_mime("p1.png", PNG_HEADER).unwrap(), "image/png");
+        assert_eq!(upload_mime("p1.JPG", JPEG_HEADER).unwrap(), "image/jpeg");
+        assert_eq!(upload_mime("p1.jpeg", JPEG_HEADER).unwrap(), "image/jpeg");
+        assert_eq!(upload_mime("p1.webp", WEBP_HEADER).unwrap(), "image/webp");
+        assert_eq!(upload_mime("p1.gif", GIF_HEADER).unwrap(), "image/gif");
+        assert_eq!(upload_mime("p1.bmp", b"BM\x36\0\0\0").unwrap(), "image/bmp");
+    }
+
+    #[test]
+    fn upload_mime_rejects_mismatched_or_unknown_content() {
+        let err = upload_mime("p1.jpg", PNG_HEADER).unwrap_err();
+        assert!(
+            matches!(err, AppError::InvalidInput(ref msg) if msg.contains("its content is png"))
+        );
+
+        let err = upload_mime("p1.png", b"<html>not an image</html>").unwrap_err();
+        assert!(matches!(err, AppError::InvalidInput(ref msg) if msg.contains("Cannot recognize")));
+
+        assert!(upload_mime("p1.webp", b"RIFF\x24\0\0\0WAVEfmt ").is_err());
+        assert!(upload_mime("p1.gif", b"").is_err());
+    }
+}
@@ -0,0 +1,205 @@
+// 项目列表的离线缓存：每次完整拉取 enriched 列表（PopRaKo 补充成功）后按列表与分页写入 SQLite，
+// 后端不可达且内存中没有兜底（如刚重启）时按同一页回退；get_cached_projects_enriched 返回某个列表
+// 最近一次的完整快照，供离线查看并提示数据的拉取时间
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::unix_now,
+    error::AppError,
+    ids::TeamId,
+    project::ResProjectEnriched,
+    storage::{project_cache, LOCAL_STORAGE},
+};
+
+// 最多保留的项目条数（所有列表合计），超出时删除最早写入的
+const PROJECT_CACHE_MAX_ROWS: i64 = 500;
+
+// 拉取时间超过该时长的快照标记为 stale
+const PROJECT_CACHE_FRESH_SECS: i64 = 5 * 60;
+
+fn user_scope() -> String {
+    "user".to_string()
+}
+
+fn team_scope(team_id: &str) -> String {
+    format!("team:{}", team_id)
+}
+
+// 无法解析的记录（如结构变化前写入的）跳过；返回项目及其中最早的拉取时间
+fn decode_rows(
+    rows: Vec<project_cache::CachedProjectRow>,
+) -> (Vec<ResProjectEnriched>, Option<i64>) {
+    let fetched_at = rows.iter().map(|row| row.fetched_at).min();
+
+    let projects = rows
+        .into_iter()
+        .filter_map(|row| match serde_json::from_str(&row.data) {
+            Ok(proj) => Some(proj),
+            Err(err) => {
+                tracing::debug!(proj_id = %row.proj_id, error = %err, "project_cache.decode_failed");
+                None
+            }
+        })
+        .collect();
+
+    (projects, fetched_at)
+}
+
+fn mark_stale(projects: &mut [ResProjectEnriched]) {
+    for proj in projects.iter_mut() {
+        proj.stale = Some(true);
+    }
+}
+
+// 列表中的一页：用户项目或某个汉化组的项目
+#[derive(Debug, Clone)]
+pub(crate) struct CachedPage {
+    scope: String,
+    page: u32,
+    limit: u32,
+}
+
+impl CachedPage {
+    pub(crate) fn user(page: u32, limit: u32) -> Self {
+        Self {
+            scope: user_scope(),
+            page,
+            limit,
+        }
+    }
+
+    pub(crate) fn team(team_id: &str, page: u32, limit: u32) -> Self {
+        Self {
+            scope: team_scope(team_id),
+            page,
+            limit,
+        }
+    }
+
+    // 内存兜底缓存的 key
+    pub(crate) fn key(&self) -> String {
+        format!("{}:{}:{}", self.scope, self.page, self.limit)
+    }
+
+    // 该页在整个列表中的位置范围 [start, end)
+    fn range(&self) -> (i64, i64) {
+        let start = (self.page.max(1) as i64 - 1) * self.limit as i64;
+
+        (start, start + self.limit as i64)
+    }
+
+    // 写入一页完整结果，替换该页的旧记录；失败只记录日志
+    pub(crate) async fn persist(&self, list: &[ResProjectEnriched]) {
+        let Some(storage) = LOCAL_STORAGE.get() else {
+            return;
+        };
+
+        let fetched_at = unix_now();
+        let (start, end) = self.range();
+
+        let rows: Vec<project_cache::CachedProjectRow> = list
+            .iter()
+            .enumerate()
+            .filter_map(|(index, proj)| {
+                let data = serde_json::to_string(proj).ok()?;
+
+                Some(project_cache::CachedProjectRow {
+                    scope: self.scope.clone(),
+                    position: start + index as i64,
+                    proj_id: proj.id.to_string(),
+                    data,
+                    fetched_at,
+                })
+            })
+            .collect();
+
+        if let Err(err) = project_cache::replace_page(
+            storage.pool(),
+            &self.scope,
+            start,
+            end,
+            &rows,
+            PROJECT_CACHE_MAX_ROWS,
+        )
+        .await
+        {
+            tracing::warn!(scope = %self.scope, count = rows.len(), error = %err, "project_cache.persist_failed");
+        }
+    }
+
+    // 该页的缓存（标记 stale）；没有缓存时返回 None
+    pub(crate) async fn load(&self) -> Option<Vec<ResProjectEnriched>> {
+        let storage = LOCAL_STORAGE.get()?;
+        let (start, end) = self.range();
+
+        let rows = match project_cache::list_range(storage.pool(), &self.scope, start, end).await {
+            Ok(rows) => rows,
+            Err(err) => {
+                tracing::warn!(scope = %self.scope, error = %err, "project_cache.load_failed");
+                return None;
+            }
+        };
+
+        let (mut projects, _) = decode_rows(rows);
+
+        if projects.is_empty() {
+            return None;
+        }
+
+        mark_stale(&mut projects);
+
+        Some(projects)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetCachedProjectsEnrichedReq {
+    // 缺省时为当前用户的项目列表
+    #[serde(default)]
+    pub team_id: Option<TeamId>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedProjectsEnriched {
+    // 按列表顺序（跨分页）
+    pub projects: Vec<ResProjectEnriched>,
+    // 其中最早一页的拉取时间；没有缓存时为 None
+    pub fetched_at: Option<i64>,
+    // 拉取时间已超过 PROJECT_CACHE_FRESH_SECS
+    pub stale: bool,
+}
+
+// 某个列表最近一次成功拉取的结果（不发起网络请求）
+#[tauri::command]
+pub async fn get_cached_projects_enriched(
+    payload: GetCachedProjectsEnrichedReq,
+) -> Result<CachedProjectsEnriched, AppError> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or_else(|| AppError::Storage("LOCAL_STORAGE not initialized".to_string()))?;
+
+    let scope = match &payload.team_id {
+        Some(team_id) => team_scope(team_id),
+        None => user_scope(),
+    };
+
+    let rows = project_cache::list_scope(storage.pool(), &scope)
+        .await
+        .map_err(AppError::Storage)?;
+
+    let (mut projects, fetched_at) = decode_rows(rows);
+
+    let stale = fetched_at.is_some_and(|at| unix_now() - at > PROJECT_CACHE_FRESH_SECS);
+
+    if stale {
+        mark_stale(&mut projects);
+    }
+
+    tracing::info!(%scope, count = projects.len(), ?fetched_at, stale, "project_cache.get.ok");
+
+    Ok(CachedProjectsEnriched {
+        projects,
+        fetched_at,
+        stale,
+    })
+}
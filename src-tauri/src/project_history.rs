@@ -0,0 +1,431 @@
+// 项目状态历史：每次成功拉取 enriched 列表后，把各项目的精简状态（阶段状态、计数、发布状态、成员集合摘要）
+// 记入 project_snapshots，每个项目保留最近 project_snapshot_history 条（内容不变时只刷新时间）。
+// diff_snapshots 给出两次状态之间的类型化变化，摘要等需要“上次已知状态”的功能统一使用
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    clock::unix_now,
+    config::config,
+    project::ResProjectEnriched,
+    publish::{ProjStage, STAGE_STATUS_COMPLETED},
+    storage::{project_snapshots, LOCAL_STORAGE},
+};
+
+// 查询历史时的默认条数
+const DEFAULT_HISTORY_LIMIT: i64 = 10;
+
+// 一个项目在某次拉取时的精简状态；None 表示当时不知道（如没有 PopRaKo 信息），比较时跳过
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotState {
+    pub name: String,
+    // 按 ProjStage::ALL 的顺序
+    pub statuses: [Option<i32>; 4],
+    pub source_count: u64,
+    pub translated_source_count: u64,
+    pub checked_source_count: u64,
+    pub is_published: Option<bool>,
+    // 成员 user id 集合的摘要；成员列表未知时为 None
+    pub member_hash: Option<String>,
+    pub member_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSnapshot {
+    pub proj_id: String,
+    pub fetched_at: i64,
+    #[serde(flatten)]
+    pub state: SnapshotState,
+}
+
+// 与成员顺序无关
+fn member_hash<'a>(user_ids: impl IntoIterator<Item = &'a str>) -> String {
+    let mut ids: Vec<&str> = user_ids.into_iter().collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut hasher = Sha256::new();
+
+    for id in ids {
+        hasher.update(id.as_bytes());
+        hasher.update([0]);
+    }
+
+    general_purpose::STANDARD_NO_PAD.encode(&hasher.finalize()[..12])
+}
+
+impl SnapshotState {
+    pub(crate) fn from_enriched(proj: &ResProjectEnriched) -> Self {
+        Self {
+            name: proj.name.clone(),
+            statuses: [
+                proj.translating_status,
+                proj.proofreading_status,
+                proj.typesetting_status,
+                proj.reviewing_status,
+            ],
+            source_count: proj.source_count,
+            translated_source_count: proj.translated_source_count,
+            checked_source_count: proj.checked_source_count,
+            is_published: proj.is_published,
+            member_hash: proj
+                .members
+                .as_ref()
+                .map(|members| member_hash(members.iter().map(|member| member.user_id.as_str()))),
+            member_count: proj.members.as_ref().map(Vec::len),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Counter {
+    Sources,
+    Translated,
+    Checked,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    Renamed {
+        from: String,
+        to: String,
+    },
+    // 阶段状态变化（不含下面两种）
+    StatusChanged {
+        stage: ProjStage,
+        from: i32,
+        to: i32,
+    },
+    // 阶段变为已完成
+    StageCompleted {
+        stage: ProjStage,
+        from: i32,
+    },
+    // 已完成的阶段被改回未完成
+    StageReopened {
+        stage: ProjStage,
+        to: i32,
+    },
+    CountChanged {
+        counter: Counter,
+        from: u64,
+        to: u64,
+    },
+    Published,
+    Unpublished,
+    // 成员集合变化（只记录摘要，具体成员需另行比较）
+    MembersChanged {
+        from_count: Option<usize>,
+        to_count: Option<usize>,
+    },
+}
+
+// 两次状态之间的变化，按 名称 -> 阶段 -> 计数 -> 发布 -> 成员 的固定顺序；任一侧未知的字段不比较
+pub(crate) fn diff_snapshots(old: &SnapshotState, new: &SnapshotState) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    if old.name != new.name {
+        changes.push(Change::Renamed {
+            from: old.name.clone(),
+            to: new.name.clone(),
+        });
+    }
+
+    for (index, stage) in ProjStage::ALL.into_iter().enumerate() {
+        let (Some(from), Some(to)) = (old.statuses[index], new.statuses[index]) else {
+            continue;
+        };
+
+        if from == to {
+            continue;
+        }
+
+        changes.push(if to == STAGE_STATUS_COMPLETED {
+            Change::StageCompleted { stage, from }
+        } else if from == STAGE_STATUS_COMPLETED {
+            Change::StageReopened { stage, to }
+        } else {
+            Change::StatusChanged { stage, from, to }
+        });
+    }
+
+    for (counter, from, to) in [
+        (Counter::Sources, old.source_count, new.source_count),
+        (
+            Counter::Translated,
+            old.translated_source_count,
+            new.translated_source_count,
+        ),
+        (
+            Counter::Checked,
+            old.checked_source_count,
+            new.checked_source_count,
+        ),
+    ] {
+        if from != to {
+            changes.push(Change::CountChanged { counter, from, to });
+        }
+    }
+
+    match (old.is_published, new.is_published) {
+        (Some(false), Some(true)) => changes.push(Change::Published),
+        (Some(true), Some(false)) => changes.push(Change::Unpublished),
+        _ => {}
+    }
+
+    if let (Some(from), Some(to)) = (&old.member_hash, &new.member_hash) {
+        if from != to {
+            changes.push(Change::MembersChanged {
+                from_count: old.member_count,
+                to_count: new.member_count,
+            });
+        }
+    }
+
+    changes
+}
+
+// 记录一次完整（PopRaKo 补充成功）的 enriched 拉取结果；整批在同一事务中写入，失败只记录日志
+pub(crate) async fn record_enriched(list: &[ResProjectEnriched]) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let fetched_at = unix_now();
+
+    let rows: Vec<project_snapshots::ProjectSnapshotRow> = list
+        .iter()
+        .filter(|proj| proj.stale != Some(true))
+        .filter_map(|proj| {
+            let data = serde_json::to_string(&SnapshotState::from_enriched(proj)).ok()?;
+
+            Some(project_snapshots::ProjectSnapshotRow {
+                proj_id: proj.id.to_string(),
+                fetched_at,
+                data,
+            })
+        })
+        .collect();
+
+    let keep = config().project_snapshot_history as i64;
+
+    if let Err(err) = project_snapshots::record_snapshots(storage.pool(), &rows, keep).await {
+        tracing::warn!(count = rows.len(), error = %err, "project_history.record_failed");
+    }
+}
+
+// 项目最近的快照（新的在前）；无法解析的旧记录跳过
+pub(crate) async fn snapshot_history(
+    proj_id: &str,
+    limit: i64,
+) -> Result<Vec<ProjectSnapshot>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = project_snapshots::list_snapshots(storage.pool(), proj_id, limit).await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let state = serde_json::from_str(&row.data)
+                .inspect_err(|err| {
+                    tracing::debug!(proj_id = %row.proj_id, error = %err, "project_history.row.skip");
+                })
+                .ok()?;
+
+            Some(ProjectSnapshot {
+                proj_id: row.proj_id,
+                fetched_at: row.fetched_at,
+                state,
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotHistoryEntry {
+    #[serde(flatten)]
+    pub snapshot: ProjectSnapshot,
+    // 相对上一条（更早的）快照的变化；最早一条为空
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetProjectSnapshotHistoryReq {
+    pub proj_id: String,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[tauri::command]
+pub async fn get_project_snapshot_history(
+    payload: GetProjectSnapshotHistoryReq,
+) -> Result<Vec<SnapshotHistoryEntry>, String> {
+    let limit = payload.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).max(1);
+
+    let snapshots = snapshot_history(&payload.proj_id, limit).await?;
+
+    let changes: Vec<Vec<Change>> = snapshots
+        .iter()
+        .enumerate()
+        .map(|(index, snapshot)| match snapshots.get(index + 1) {
+            Some(older) => diff_snapshots(&older.state, &snapshot.state),
+            None => Vec::new(),
+        })
+        .collect();
+
+    tracing::debug!(
+        proj_id = %payload.proj_id,
+        count = snapshots.len(),
+        "project_history.get.ok"
+    );
+
+    Ok(snapshots
+        .into_iter()
+        .zip(changes)
+        .map(|(snapshot, changes)| SnapshotHistoryEntry { snapshot, changes })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config,
+        publish::{STAGE_STATUS_PENDING, STAGE_STATUS_WIP},
+        test_support::{enriched_fixture, local_storage, MockBackends},
+    };
+
+    fn state() -> SnapshotState {
+        SnapshotState {
+            name: "项目".to_string(),
+            statuses: [
+                Some(STAGE_STATUS_WIP),
+                Some(STAGE_STATUS_PENDING),
+                None,
+                Some(2),
+            ],
+            source_count: 10,
+            translated_source_count: 5,
+            checked_source_count: 0,
+            is_published: Some(false),
+            member_hash: Some(member_hash(["u1", "u2"])),
+            member_count: Some(2),
+        }
+    }
+
+    #[test]
+    fn member_hash_ignores_order_and_duplicates() {
+        assert_eq!(member_hash(["b", "a"]), member_hash(["a", "b", "a"]));
+        assert_ne!(member_hash(["a", "b"]), member_hash(["ab"]));
+    }
+
+    #[test]
+    fn changes_are_typed_and_in_fixed_order() {
+        let old = state();
+        let mut new = state();
+        new.name = "新名字".to_string();
+        new.statuses = [
+            Some(STAGE_STATUS_COMPLETED),
+            Some(STAGE_STATUS_WIP),
+            Some(STAGE_STATUS_WIP),
+            Some(STAGE_STATUS_WIP),
+        ];
+        new.translated_source_count = 7;
+        new.is_published = Some(true);
+        new.member_hash = Some(member_hash(["u1"]));
+        new.member_count = Some(1);
+
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            [
+                Change::Renamed {
+                    from: "项目".to_string(),
+                    to: "新名字".to_string()
+                },
+                Change::StageCompleted {
+                    stage: ProjStage::Translating,
+                    from: STAGE_STATUS_WIP
+                },
+                Change::StatusChanged {
+                    stage: ProjStage::Proofreading,
+                    from: STAGE_STATUS_PENDING,
+                    to: STAGE_STATUS_WIP
+                },
+                Change::StageReopened {
+                    stage: ProjStage::Reviewing,
+                    to: STAGE_STATUS_WIP
+                },
+                Change::CountChanged {
+                    counter: Counter::Translated,
+                    from: 5,
+                    to: 7
+                },
+                Change::Published,
+                Change::MembersChanged {
+                    from_count: Some(2),
+                    to_count: Some(1)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_not_compared() {
+        let old = state();
+        let mut new = state();
+        new.statuses = [None; 4];
+        new.is_published = None;
+        new.member_hash = None;
+        new.member_count = None;
+
+        assert!(diff_snapshots(&old, &new).is_empty());
+        assert!(diff_snapshots(&new, &old).is_empty());
+    }
+
+    #[tokio::test]
+    async fn history_keeps_distinct_states_up_to_the_limit() {
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+        config::update_for_test(|config| config.project_snapshot_history = 2);
+
+        let proj_id = "history-proj";
+        let mut proj = enriched_fixture(proj_id, "history-team");
+
+        for translated in [1, 1, 2, 3] {
+            proj.translated_source_count = translated;
+            record_enriched(std::slice::from_ref(&proj)).await;
+        }
+
+        // 本地缓存数据不记录
+        proj.translated_source_count = 9;
+        proj.stale = Some(true);
+        record_enriched(std::slice::from_ref(&proj)).await;
+
+        let history = get_project_snapshot_history(GetProjectSnapshotHistoryReq {
+            proj_id: proj_id.to_string(),
+            limit: None,
+        })
+        .await
+        .unwrap();
+
+        let counts: Vec<u64> = history
+            .iter()
+            .map(|entry| entry.snapshot.state.translated_source_count)
+            .collect();
+        assert_eq!(counts, [3, 2]);
+        assert_eq!(
+            history[0].changes,
+            [Change::CountChanged {
+                counter: Counter::Translated,
+                from: 2,
+                to: 3
+            }]
+        );
+        assert!(history[1].changes.is_empty());
+    }
+}
@@ -0,0 +1,665 @@
+// 项目备注：PopRaKo 暂无备注接口，记录在本地 project_notes 表。
+// 保存时解析正文中的 @成员 提及并记录 member_id（歧义 / 未知的提及随结果返回，不做猜测）；
+// 读取时按成员目录中的当前名字渲染，成员改名后仍指向同一人
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{
+    clock::unix_now,
+    defer::WarnDefer,
+    events::{emit_event, MentionReceived},
+    member::{fetch_all_members, get_member_info, GetMemberInfoReq},
+    mention::{parse_mentions, render_mentions, resolve_mentions, MentionResolution},
+    project::cached_proj_snapshot,
+    storage::{
+        project_notes::{self, NoteMentionRow, ProjectNoteRow},
+        LOCAL_STORAGE,
+    },
+};
+
+// 正文长度上限（字符数）
+const NOTE_BODY_MAX_CHARS: usize = 4000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NoteMention {
+    pub member_id: String,
+    // 当前名字；成员已不在目录中（或目录获取失败）时为保存时的名字
+    pub username: String,
+    pub in_directory: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectNote {
+    pub id: i64,
+    pub team_id: String,
+    pub project_id: String,
+    pub author_member_id: Option<String>,
+    pub author_name: Option<String>,
+    // 按当前名字渲染后的正文
+    pub body: String,
+    pub created_at: i64,
+    pub mentions: Vec<NoteMention>,
+}
+
+// member_id -> 当前名字
+type MemberDirectory = HashMap<String, String>;
+
+// 把一条备注与它的提及记录组合起来，按成员目录渲染当前名字；directory 为 None 时沿用保存时的名字
+fn render_note(
+    row: ProjectNoteRow,
+    mention_rows: &[NoteMentionRow],
+    directory: Option<&MemberDirectory>,
+) -> ProjectNote {
+    let mut renames = Vec::new();
+    let mut mentions = Vec::new();
+
+    for mention in mention_rows.iter().filter(|m| m.note_id == row.id) {
+        let current = directory.and_then(|names| names.get(&mention.member_id));
+        let username = current.unwrap_or(&mention.username).clone();
+
+        renames.push((mention.username.clone(), username.clone()));
+        mentions.push(NoteMention {
+            member_id: mention.member_id.clone(),
+            username,
+            in_directory: directory.is_none() || current.is_some(),
+        });
+    }
+
+    let author_name = row
+        .author_member_id
+        .as_ref()
+        .and_then(|author| directory?.get(author).cloned());
+
+    ProjectNote {
+        body: render_mentions(&row.body, &renames),
+        id: row.id,
+        team_id: row.team_id,
+        project_id: row.project_id,
+        author_member_id: row.author_member_id,
+        author_name,
+        created_at: row.created_at,
+        mentions,
+    }
+}
+
+// 读取时使用的成员目录；获取失败（离线等）时返回 None，只记录日志
+async fn member_directory(team_id: &str) -> Option<MemberDirectory> {
+    match fetch_all_members(team_id).await {
+        Ok(members) => Some(
+            members
+                .into_iter()
+                .map(|member| (member.member_id.into_inner(), member.username))
+                .collect(),
+        ),
+        Err(err) => {
+            tracing::warn!(team_id, error = %err, "project_notes.directory.failed");
+            None
+        }
+    }
+}
+
+async fn render_rows(
+    rows: Vec<ProjectNoteRow>,
+    directory: Option<&MemberDirectory>,
+) -> Result<Vec<ProjectNote>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let note_ids: Vec<i64> = rows.iter().map(|row| row.id).collect();
+    let mention_rows = project_notes::list_note_mentions(storage.pool(), &note_ids).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| render_note(row, &mention_rows, directory))
+        .collect())
+}
+
+// 当前用户在汉化组中的 member_id（使用 members/info 缓存）
+async fn my_member_id(team_id: &str) -> Result<String, String> {
+    let info = get_member_info(GetMemberInfoReq {
+        team_id: team_id.into(),
+        force_refresh: false,
+    })
+    .await?;
+
+    Ok(info.member_id.into_inner())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddProjectNoteReq {
+    pub team_id: String,
+    pub project_id: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AddProjectNoteReply {
+    pub note: ProjectNote,
+    // 歧义 / 未知的提及没有记录，前端据此提示改写
+    pub resolution: MentionResolution,
+}
+
+// 保存备注，返回结果以及是否提及了当前用户
+async fn save_project_note(
+    payload: &AddProjectNoteReq,
+) -> Result<(AddProjectNoteReply, bool), String> {
+    let body = payload.body.trim();
+
+    if body.is_empty() {
+        return Err("备注内容不能为空".to_string());
+    }
+
+    if body.chars().count() > NOTE_BODY_MAX_CHARS {
+        return Err(format!("备注内容不能超过 {} 个字符", NOTE_BODY_MAX_CHARS));
+    }
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let tokens = parse_mentions(body);
+
+    let members = if tokens.is_empty() {
+        Vec::new()
+    } else {
+        fetch_all_members(&payload.team_id).await?
+    };
+
+    let resolution = resolve_mentions(&tokens, &members);
+
+    // 同一成员只记录一次（保存时的名字取第一次出现的那个）
+    let mut mentions: Vec<(String, String)> = Vec::new();
+    for resolved in &resolution.resolved {
+        if !mentions.iter().any(|(id, _)| *id == resolved.member_id) {
+            mentions.push((resolved.member_id.clone(), resolved.username.clone()));
+        }
+    }
+
+    let author = match my_member_id(&payload.team_id).await {
+        Ok(member_id) => Some(member_id),
+        Err(err) => {
+            tracing::warn!(team_id = %payload.team_id, error = %err, "project_notes.author.failed");
+            None
+        }
+    };
+
+    let created_at = unix_now();
+
+    let note_id = project_notes::insert_note(
+        storage.pool(),
+        &payload.team_id,
+        &payload.project_id,
+        author.as_deref(),
+        body,
+        created_at,
+        &mentions,
+    )
+    .await?;
+
+    let mentions_me = author
+        .as_ref()
+        .is_some_and(|me| mentions.iter().any(|(id, _)| id == me));
+
+    let row = ProjectNoteRow {
+        id: note_id,
+        team_id: payload.team_id.clone(),
+        project_id: payload.project_id.clone(),
+        author_member_id: author,
+        body: body.to_string(),
+        created_at,
+    };
+    let mention_rows: Vec<NoteMentionRow> = mentions
+        .into_iter()
+        .map(|(member_id, username)| NoteMentionRow {
+            note_id,
+            member_id,
+            username,
+        })
+        .collect();
+
+    // 没有提及时不拉取成员目录，作者名留空
+    let directory: Option<MemberDirectory> = (!members.is_empty()).then(|| {
+        members
+            .into_iter()
+            .map(|member| (member.member_id.into_inner(), member.username))
+            .collect()
+    });
+
+    let note = render_note(row, &mention_rows, directory.as_ref());
+
+    Ok((AddProjectNoteReply { note, resolution }, mentions_me))
+}
+
+// 保存备注并记录其中提及的成员；提及了当前用户时发出 MentionReceived
+#[tauri::command]
+pub async fn add_project_note(
+    app: AppHandle,
+    payload: AddProjectNoteReq,
+) -> Result<AddProjectNoteReply, String> {
+    tracing::info!(
+        team_id = %payload.team_id,
+        project_id = %payload.project_id,
+        "project_notes.add.start"
+    );
+
+    let mut defer = WarnDefer::new("project_notes.add");
+
+    let (reply, mentions_me) = save_project_note(&payload).await?;
+
+    if mentions_me {
+        emit_event(&app, mention_event(&reply.note));
+    }
+
+    tracing::info!(
+        note_id = reply.note.id,
+        mentions = reply.note.mentions.len(),
+        ambiguous = reply.resolution.ambiguous.len(),
+        unknown = reply.resolution.unknown.len(),
+        "project_notes.add.ok"
+    );
+
+    defer.success();
+
+    Ok(reply)
+}
+
+fn mention_event(note: &ProjectNote) -> MentionReceived {
+    MentionReceived {
+        note_id: note.id,
+        team_id: note.team_id.clone(),
+        project_id: note.project_id.clone(),
+        project_name: cached_proj_snapshot(&note.project_id).map(|proj| proj.name),
+        body: note.body.clone(),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListProjectNotesReq {
+    pub team_id: String,
+    pub project_id: String,
+}
+
+// 项目的全部备注（按时间倒序），提及按当前名字渲染
+#[tauri::command]
+pub async fn list_project_notes(payload: ListProjectNotesReq) -> Result<Vec<ProjectNote>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = project_notes::list_project_notes(storage.pool(), &payload.project_id).await?;
+
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let directory = member_directory(&payload.team_id).await;
+
+    render_rows(rows, directory.as_ref()).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetMyMentionsReq {
+    pub team_id: String,
+    // 只返回晚于此时间（Unix timestamp）的备注；None 表示全部
+    #[serde(default)]
+    pub since: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MyMention {
+    pub note: ProjectNote,
+    // 最近一次获取的项目列表中的名字；项目不在缓存中时为 None
+    pub project_name: Option<String>,
+    pub project_set_name: Option<String>,
+}
+
+// 提及当前用户的备注（按时间倒序），附带项目信息
+#[tauri::command]
+pub async fn get_my_mentions(payload: GetMyMentionsReq) -> Result<Vec<MyMention>, String> {
+    tracing::info!(
+        team_id = %payload.team_id,
+        since = ?payload.since,
+        "project_notes.mentions.start"
+    );
+
+    let mut defer = WarnDefer::new("project_notes.mentions");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let me = my_member_id(&payload.team_id).await?;
+
+    let rows = project_notes::list_notes_mentioning(
+        storage.pool(),
+        &payload.team_id,
+        &me,
+        payload.since.unwrap_or(i64::MIN),
+    )
+    .await?;
+
+    let directory = if rows.is_empty() {
+        None
+    } else {
+        member_directory(&payload.team_id).await
+    };
+
+    let mentions: Vec<MyMention> = render_rows(rows, directory.as_ref())
+        .await?
+        .into_iter()
+        .map(|note| {
+            let snapshot = cached_proj_snapshot(&note.project_id);
+
+            MyMention {
+                project_name: snapshot.as_ref().map(|proj| proj.name.clone()),
+                project_set_name: snapshot.map(|proj| proj.project_set.name),
+                note,
+            }
+        })
+        .collect();
+
+    tracing::info!(
+        team_id = %payload.team_id,
+        count = mentions.len(),
+        "project_notes.mentions.ok"
+    );
+
+    defer.success();
+
+    Ok(mentions)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteProjectNoteReq {
+    pub note_id: i64,
+}
+
+// 删除备注；返回是否存在
+#[tauri::command]
+pub async fn delete_project_note(payload: DeleteProjectNoteReq) -> Result<bool, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let deleted = project_notes::delete_note(storage.pool(), payload.note_id).await?;
+
+    tracing::info!(
+        note_id = payload.note_id,
+        deleted,
+        "project_notes.delete.ok"
+    );
+
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::{local_storage, MockBackends};
+
+    fn row(id: i64, body: &str) -> ProjectNoteRow {
+        ProjectNoteRow {
+            id,
+            team_id: "team".to_string(),
+            project_id: "proj".to_string(),
+            author_member_id: Some("m9".to_string()),
+            body: body.to_string(),
+            created_at: 100,
+        }
+    }
+
+    fn mention_row(note_id: i64, member_id: &str, username: &str) -> NoteMentionRow {
+        NoteMentionRow {
+            note_id,
+            member_id: member_id.to_string(),
+            username: username.to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_current_names_for_stored_ids() {
+        let mentions = [
+            mention_row(1, "m1", "akira"),
+            mention_row(1, "m2", "bob"),
+            mention_row(2, "m3", "other note"),
+        ];
+        let directory: MemberDirectory = [
+            ("m1".to_string(), "Akira Tanaka".to_string()),
+            ("m9".to_string(), "me".to_string()),
+        ]
+        .into();
+
+        let note = render_note(
+            row(1, "@akira 修 p.14，@bob 校对"),
+            &mentions,
+            Some(&directory),
+        );
+
+        assert_eq!(note.body, "@\"Akira Tanaka\" 修 p.14，@bob 校对");
+        assert_eq!(note.author_name.as_deref(), Some("me"));
+        assert_eq!(
+            note.mentions,
+            [
+                NoteMention {
+                    member_id: "m1".to_string(),
+                    username: "Akira Tanaka".to_string(),
+                    in_directory: true,
+                },
+                // 已离开汉化组：沿用保存时的名字
+                NoteMention {
+                    member_id: "m2".to_string(),
+                    username: "bob".to_string(),
+                    in_directory: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn without_directory_saved_names_are_kept() {
+        let mentions = [mention_row(1, "m1", "akira")];
+        let note = render_note(row(1, "@Akira 修图"), &mentions, None);
+
+        assert_eq!(note.body, "@akira 修图");
+        assert_eq!(note.author_name, None);
+        assert!(note.mentions[0].in_directory);
+    }
+
+    fn member_json(member_id: &str, username: &str) -> Value {
+        json!({
+            "member_id": member_id,
+            "user_id": format!("u-{}", member_id),
+            "username": username,
+            "is_admin": false,
+            "is_translator": true,
+            "is_proofreader": false,
+            "is_typesetter": false,
+            "is_principal": false,
+        })
+    }
+
+    async fn mount_directory(backends: &MockBackends, members: Vec<Value>) {
+        let total = members.len();
+
+        Mock::given(method("POST"))
+            .and(path("/v1/members/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": members,
+                "message": null,
+                "total": total,
+            })))
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    async fn mount_me(backends: &MockBackends, team_id: &str, member_id: &str) {
+        Mock::given(method("GET"))
+            .and(path("/v1/members/info"))
+            .and(query_param("team_id", team_id))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": {
+                    "member_id": member_id,
+                    "is_admin": false,
+                    "is_translator": true,
+                    "is_proofreader": false,
+                    "is_typesetter": false,
+                    "is_principal": false,
+                },
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    fn note_req(team_id: &str, project_id: &str, body: &str) -> AddProjectNoteReq {
+        AddProjectNoteReq {
+            team_id: team_id.to_string(),
+            project_id: project_id.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn saves_mentions_and_follows_renames() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+
+        mount_directory(
+            &backends,
+            vec![
+                member_json("m1", "akira"),
+                member_json("m2", "akihiro"),
+                member_json("m9", "me"),
+            ],
+        )
+        .await;
+        mount_me(&backends, "team-notes", "m9").await;
+
+        let (reply, mentions_me) = save_project_note(&note_req(
+            "team-notes",
+            "proj-notes",
+            "  @akira please redraw p.14，@aki 也看看 @zed  ",
+        ))
+        .await
+        .unwrap();
+
+        assert!(!mentions_me);
+        assert_eq!(
+            reply.note.body,
+            "@akira please redraw p.14，@aki 也看看 @zed"
+        );
+        assert_eq!(reply.note.author_member_id.as_deref(), Some("m9"));
+        assert_eq!(reply.resolution.member_ids, ["m1"]);
+        assert_eq!(
+            reply.resolution.ambiguous[0].candidates,
+            ["akira", "akihiro"]
+        );
+        assert_eq!(reply.resolution.unknown, ["@zed"]);
+
+        // akira 改名后，读取时按新名字渲染
+        backends.poprako.reset().await;
+        mount_directory(
+            &backends,
+            vec![member_json("m1", "Akira Tanaka"), member_json("m9", "me")],
+        )
+        .await;
+
+        let notes = list_project_notes(ListProjectNotesReq {
+            team_id: "team-notes".to_string(),
+            project_id: "proj-notes".to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(notes.len(), 1);
+        assert_eq!(
+            notes[0].body,
+            "@\"Akira Tanaka\" please redraw p.14，@aki 也看看 @zed"
+        );
+        assert_eq!(notes[0].mentions[0].member_id, "m1");
+        assert_eq!(notes[0].author_name.as_deref(), Some("me"));
+    }
+
+    #[tokio::test]
+    async fn my_mentions_are_newest_first_and_respect_since() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+
+        mount_directory(
+            &backends,
+            vec![member_json("m1", "akira"), member_json("m9", "me")],
+        )
+        .await;
+        mount_me(&backends, "team-mine", "m9").await;
+
+        let (first, mentions_me) =
+            save_project_note(&note_req("team-mine", "proj-a", "@me 请确认 p.3"))
+                .await
+                .unwrap();
+        assert!(mentions_me);
+
+        save_project_note(&note_req("team-mine", "proj-a", "@akira 修图"))
+            .await
+            .unwrap();
+
+        let (second, _) = save_project_note(&note_req("team-mine", "proj-b", "@akira @Me 一起看"))
+            .await
+            .unwrap();
+
+        let mentions = get_my_mentions(GetMyMentionsReq {
+            team_id: "team-mine".to_string(),
+            since: None,
+        })
+        .await
+        .unwrap();
+
+        let ids: Vec<i64> = mentions.iter().map(|m| m.note.id).collect();
+        assert_eq!(ids, [second.note.id, first.note.id]);
+        assert_eq!(mentions[0].note.project_id, "proj-b");
+        assert_eq!(mentions[0].note.body, "@akira @me 一起看");
+
+        let later = get_my_mentions(GetMyMentionsReq {
+            team_id: "team-mine".to_string(),
+            since: Some(second.note.created_at),
+        })
+        .await
+        .unwrap();
+        assert!(later.is_empty());
+
+        // 删除后不再出现
+        assert!(delete_project_note(DeleteProjectNoteReq {
+            note_id: second.note.id,
+        })
+        .await
+        .unwrap());
+
+        let mentions = get_my_mentions(GetMyMentionsReq {
+            team_id: "team-mine".to_string(),
+            since: None,
+        })
+        .await
+        .unwrap();
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].note.id, first.note.id);
+    }
+
+    #[tokio::test]
+    async fn empty_body_is_rejected() {
+        let err = save_project_note(&note_req("team", "proj", "   "))
+            .await
+            .unwrap_err();
+
+        assert!(err.contains("不能为空"));
+    }
+}
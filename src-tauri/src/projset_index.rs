@@ -0,0 +1,190 @@
+// 项目集内的项目序号（projset_index）检查：找出缺号与重复序号，并给出下一个可用序号，
+// 供创建项目时自动编号使用
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    http::poprako_post_opt,
+    pagination::{drain_pages, PoprakoListEnvelope},
+    project::{PoprakoProjFilterReq, PoprakoProjInfo},
+};
+
+const DRAIN_PROJS_LIMIT: u32 = 50;
+const DRAIN_PROJS_MAX: usize = 2000;
+
+// 序号从 1 开始
+const FIRST_INDEX: u32 = 1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateIndex {
+    pub index: u32,
+    pub proj_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjsetIndexReport {
+    pub projset_id: String,
+    pub proj_count: usize,
+    pub max_index: Option<u32>,
+    // 最大序号 + 1（空项目集为 1）
+    pub next_index: u32,
+    // 1..max_index 之间未被使用的序号
+    pub gaps: Vec<u32>,
+    // 被多个项目同时使用的序号
+    pub duplicates: Vec<DuplicateIndex>,
+}
+
+// 根据 (proj_id, projset_index) 列表统计缺号与重复
+pub(crate) fn analyze_indices(projset_id: &str, projs: &[(String, u32)]) -> ProjsetIndexReport {
+    let mut by_index: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+
+    for (proj_id, index) in projs {
+        by_index.entry(*index).or_default().push(proj_id.clone());
+    }
+
+    let max_index = by_index.keys().next_back().copied();
+
+    let gaps = match max_index {
+        Some(max) => (FIRST_INDEX..max)
+            .filter(|index| !by_index.contains_key(index))
+            .collect(),
+        None => vec![],
+    };
+
+    let duplicates = by_index
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(index, proj_ids)| DuplicateIndex { index, proj_ids })
+        .collect();
+
+    ProjsetIndexReport {
+        projset_id: projset_id.to_string(),
+        proj_count: projs.len(),
+        max_index,
+        next_index: max_index.map_or(FIRST_INDEX, |max| max.saturating_add(1)),
+        gaps,
+        duplicates,
+    }
+}
+
+// 通过 PopRaKo 搜索拉取项目集内全部项目
+pub(crate) async fn fetch_projset_projs(projset_id: &str) -> Result<Vec<PoprakoProjInfo>, String> {
+    drain_pages(
+        DRAIN_PROJS_LIMIT,
+        DRAIN_PROJS_MAX,
+        |page, limit| async move {
+            let filter = PoprakoProjFilterReq {
+                projset_ids: Some(vec![projset_id.into()]),
+                page: Some(page),
+                limit: Some(limit),
+                ..Default::default()
+            };
+
+            poprako_post_opt::<PoprakoProjFilterReq, PoprakoListEnvelope<PoprakoProjInfo>>(
+                "projs/search",
+                Some(filter),
+            )
+            .await
+            .map_err(|err| format!("PopRaKo 项目搜索失败: {}", err))?
+            .into_page(page, limit)
+        },
+    )
+    .await
+}
+
+pub(crate) async fn projset_index_report(projset_id: &str) -> Result<ProjsetIndexReport, String> {
+    let projs = fetch_projset_projs(projset_id).await?;
+
+    let indices: Vec<(String, u32)> = projs
+        .into_iter()
+        .map(|proj| (proj.proj_id.into(), proj.projset_index))
+        .collect();
+
+    Ok(analyze_indices(projset_id, &indices))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetNextProjsetIndexReq {
+    pub projset_id: String,
+}
+
+// 获取项目集的下一个序号，以及现有序号中的缺号与重复（供协调者修正编号）
+#[tauri::command]
+pub async fn get_next_projset_index(
+    payload: GetNextProjsetIndexReq,
+) -> Result<ProjsetIndexReport, String> {
+    tracing::info!(projset_id = %payload.projset_id, "poprako.projset.index.start");
+
+    let mut defer = WarnDefer::new("poprako.projset.index");
+
+    let report = projset_index_report(&payload.projset_id).await?;
+
+    tracing::info!(
+        projset_id = %payload.projset_id,
+        next_index = report.next_index,
+        gaps = report.gaps.len(),
+        duplicates = report.duplicates.len(),
+        "poprako.projset.index.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projs(items: &[(&str, u32)]) -> Vec<(String, u32)> {
+        items
+            .iter()
+            .map(|(id, index)| (id.to_string(), *index))
+            .collect()
+    }
+
+    #[test]
+    fn empty_projset_starts_at_one() {
+        let report = analyze_indices("ps", &[]);
+
+        assert_eq!(report.max_index, None);
+        assert_eq!(report.next_index, 1);
+        assert!(report.gaps.is_empty());
+        assert!(report.duplicates.is_empty());
+    }
+
+    #[test]
+    fn gaps_and_duplicates_are_reported_in_index_order() {
+        let report = analyze_indices(
+            "ps",
+            &projs(&[("a", 5), ("b", 2), ("c", 5), ("d", 1), ("e", 2), ("f", 2)]),
+        );
+
+        assert_eq!(report.proj_count, 6);
+        assert_eq!(report.max_index, Some(5));
+        assert_eq!(report.next_index, 6);
+        assert_eq!(report.gaps, [3, 4]);
+
+        let duplicates: Vec<(u32, Vec<String>)> = report
+            .duplicates
+            .into_iter()
+            .map(|dup| (dup.index, dup.proj_ids))
+            .collect();
+        assert_eq!(
+            duplicates,
+            [
+                (2, vec!["b".to_string(), "e".to_string(), "f".to_string()]),
+                (5, vec!["a".to_string(), "c".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn index_zero_is_not_counted_as_a_gap() {
+        let report = analyze_indices("ps", &projs(&[("a", 0), ("b", 2)]));
+        assert_eq!(report.gaps, [1]);
+        assert_eq!(report.next_index, 3);
+    }
+}
@@ -0,0 +1,407 @@
+// 批量发布：发布前逐个检查项目完成度（PopRaKo 四个阶段均已完成，可选 Moetran 原文全部校对），
+// 未通过的项目跳过并说明原因；其余按顺序发布并写入发布记录，结束后只发送一次汇总事件
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{
+    defer::WarnDefer,
+    events::{emit_event, BulkPublished},
+    http::moetran_get,
+    project::{fetch_poprako_proj, put_proj_publish, PoprakoProjInfo, ResProject},
+    storage::{publish_records, LOCAL_STORAGE},
+};
+
+// PopRaKo 阶段状态：0=pending, 1=wip, 2=completed
+pub(crate) const STAGE_STATUS_PENDING: i32 = 0;
+pub(crate) const STAGE_STATUS_WIP: i32 = 1;
+pub(crate) const STAGE_STATUS_COMPLETED: i32 = 2;
+pub(crate) const STAGE_STATUSES: [i32; 3] = [
+    STAGE_STATUS_PENDING,
+    STAGE_STATUS_WIP,
+    STAGE_STATUS_COMPLETED,
+];
+
+// 项目流程阶段，序列化后与 update_proj_status 的 status_type 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjStage {
+    Translating,
+    Proofreading,
+    Typesetting,
+    Reviewing,
+}
+
+impl ProjStage {
+    pub(crate) const ALL: [ProjStage; 4] = [
+        ProjStage::Translating,
+        ProjStage::Proofreading,
+        ProjStage::Typesetting,
+        ProjStage::Reviewing,
+    ];
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ProjStage::Translating => "translating",
+            ProjStage::Proofreading => "proofreading",
+            ProjStage::Typesetting => "typesetting",
+            ProjStage::Reviewing => "reviewing",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.as_str() == value)
+    }
+
+    fn status_of(self, proj: &PoprakoProjInfo) -> i32 {
+        match self {
+            ProjStage::Translating => proj.translating_status,
+            ProjStage::Proofreading => proj.proofreading_status,
+            ProjStage::Typesetting => proj.typesetting_status,
+            ProjStage::Reviewing => proj.reviewing_status,
+        }
+    }
+}
+
+// 未通过的检查项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GateIssue {
+    StageIncomplete { stage: ProjStage, status: i32 },
+    SourcesUnchecked { checked: u64, total: u64 },
+}
+
+// 按 PopRaKo 状态（及可选的 Moetran 计数）评估发布检查，返回全部未通过项
+pub(crate) fn evaluate_gate(
+    proj: &PoprakoProjInfo,
+    moetran: Option<&ResProject>,
+) -> Vec<GateIssue> {
+    let mut issues: Vec<GateIssue> = ProjStage::ALL
+        .into_iter()
+        .filter_map(|stage| {
+            let status = stage.status_of(proj);
+
+            (status != STAGE_STATUS_COMPLETED)
+                .then_some(GateIssue::StageIncomplete { stage, status })
+        })
+        .collect();
+
+    if let Some(project) = moetran {
+        if project.checked_source_count < project.source_count {
+            issues.push(GateIssue::SourcesUnchecked {
+                checked: project.checked_source_count,
+                total: project.source_count,
+            });
+        }
+    }
+
+    issues
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BulkPublishStatus {
+    // dry_run 时检查通过（未实际发布）
+    Ready,
+    Published,
+    // 检查未通过，或项目已发布 / 不存在
+    Skipped { reason: String },
+    Failed { message: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkPublishItem {
+    pub proj_id: String,
+    pub proj_name: Option<String>,
+    pub issues: Vec<GateIssue>,
+    #[serde(flatten)]
+    pub status: BulkPublishStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkPublishSummary {
+    pub dry_run: bool,
+    pub ready: usize,
+    pub published: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkPublishReply {
+    pub summary: BulkPublishSummary,
+    pub items: Vec<BulkPublishItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishProjsBulkReq {
+    pub proj_ids: Vec<String>,
+    // 为 false 时检查未通过的项目也会发布（仍在结果与发布记录中注明）
+    #[serde(default = "default_true")]
+    pub require_complete: bool,
+    // 是否同时要求 Moetran 中所有原文都已校对
+    #[serde(default)]
+    pub check_sources: bool,
+    // 只返回检查结果，不发布
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// 拉取项目信息并评估；项目不存在或已发布时返回 Err(跳过原因)
+async fn preflight(
+    proj_id: &str,
+    check_sources: bool,
+) -> Result<(PoprakoProjInfo, Vec<GateIssue>), String> {
+    let proj = fetch_poprako_proj(proj_id)
+        .await
+        .map_err(|err| format!("获取 PopRaKo 项目信息失败: {}", err))?
+        .ok_or_else(|| "PopRaKo 中不存在该项目".to_string())?;
+
+    if proj.is_published {
+        return Err("项目已发布".to_string());
+    }
+
+    let moetran = if check_sources {
+        let path = format!("projects/{}", proj_id);
+
+        let project = moetran_get::<ResProject>(&path, None)
+            .await
+            .map_err(|err| format!("获取 Moetran 项目信息失败: {}", err))?;
+
+        Some(project)
+    } else {
+        None
+    };
+
+    let issues = evaluate_gate(&proj, moetran.as_ref());
+
+    Ok((proj, issues))
+}
+
+async fn record_publish(proj: &PoprakoProjInfo, issues: &[GateIssue]) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let gate_issues = (!issues.is_empty())
+        .then(|| serde_json::to_string(issues).ok())
+        .flatten();
+
+    if let Err(err) = publish_records::record_publish(
+        storage.pool(),
+        &proj.proj_id,
+        Some(&proj.proj_name),
+        !issues.is_empty(),
+        gate_issues.as_deref(),
+    )
+    .await
+    {
+        tracing::warn!(proj_id = %proj.proj_id, error = %err, "publish.record.failed");
+    }
+}
+
+#[tauri::command]
+pub async fn publish_projs_bulk(
+    app: AppHandle,
+    payload: PublishProjsBulkReq,
+) -> Result<BulkPublishReply, String> {
+    tracing::info!(
+        count = payload.proj_ids.len(),
+        require_complete = payload.require_complete,
+        check_sources = payload.check_sources,
+        dry_run = payload.dry_run,
+        "poprako.proj.publish_bulk.start"
+    );
+
+    let mut defer = WarnDefer::new("poprako.proj.publish_bulk");
+
+    let mut summary = BulkPublishSummary {
+        dry_run: payload.dry_run,
+        ..Default::default()
+    };
+    let mut items = Vec::with_capacity(payload.proj_ids.len());
+
+    for proj_id in &payload.proj_ids {
+        let (proj, issues) = match preflight(proj_id, payload.check_sources).await {
+            Ok(checked) => checked,
+            Err(reason) => {
+                summary.skipped += 1;
+                items.push(BulkPublishItem {
+                    proj_id: proj_id.clone(),
+                    proj_name: None,
+                    issues: vec![],
+                    status: BulkPublishStatus::Skipped { reason },
+                });
+                continue;
+            }
+        };
+
+        let status = if payload.require_complete && !issues.is_empty() {
+            summary.skipped += 1;
+            BulkPublishStatus::Skipped {
+                reason: "未通过发布前检查".to_string(),
+            }
+        } else if payload.dry_run {
+            summary.ready += 1;
+            BulkPublishStatus::Ready
+        } else {
+            match put_proj_publish(proj_id).await {
+                Ok(()) => {
+                    record_publish(&proj, &issues).await;
+                    summary.published += 1;
+                    BulkPublishStatus::Published
+                }
+                Err(err) => {
+                    tracing::warn!(%proj_id, error = %err, "poprako.proj.publish_bulk.item_failed");
+                    summary.failed += 1;
+                    BulkPublishStatus::Failed {
+                        message: err.to_string(),
+                    }
+                }
+            }
+        };
+
+        items.push(BulkPublishItem {
+            proj_id: proj_id.clone(),
+            proj_name: Some(proj.proj_name),
+            issues,
+            status,
+        });
+    }
+
+    tracing::info!(
+        published = summary.published,
+        ready = summary.ready,
+        skipped = summary.skipped,
+        failed = summary.failed,
+        "poprako.proj.publish_bulk.ok"
+    );
+
+    // 只发送一次汇总事件，看板据此统一刷新
+    if !payload.dry_run {
+        emit_event(&app, BulkPublished(summary.clone()));
+    }
+
+    defer.success();
+
+    Ok(BulkPublishReply { summary, items })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn proj(statuses: [i32; 4]) -> PoprakoProjInfo {
+        serde_json::from_value(json!({
+            "proj_id": "pub-proj",
+            "proj_name": "发布测试",
+            "projset_index": 1,
+            "translating_status": statuses[0],
+            "proofreading_status": statuses[1],
+            "typesetting_status": statuses[2],
+            "reviewing_status": statuses[3],
+            "is_published": false,
+        }))
+        .unwrap()
+    }
+
+    fn moetran(checked: u64, total: u64) -> ResProject {
+        serde_json::from_value(json!({
+            "id": "pub-proj",
+            "name": "发布测试",
+            "source_count": total,
+            "translated_source_count": total,
+            "checked_source_count": checked,
+            "team": { "id": "t", "avatar": "", "has_avatar": false, "name": "汉化组" },
+            "project_set": { "id": "default", "name": "默认项目集" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn completed_project_passes_the_gate() {
+        let done = proj([STAGE_STATUS_COMPLETED; 4]);
+
+        assert!(evaluate_gate(&done, None).is_empty());
+        assert!(evaluate_gate(&done, Some(&moetran(12, 12))).is_empty());
+    }
+
+    #[test]
+    fn every_failing_check_is_reported() {
+        let unfinished = proj([
+            STAGE_STATUS_COMPLETED,
+            STAGE_STATUS_WIP,
+            STAGE_STATUS_COMPLETED,
+            STAGE_STATUS_PENDING,
+        ]);
+
+        assert_eq!(
+            evaluate_gate(&unfinished, Some(&moetran(3, 12))),
+            [
+                GateIssue::StageIncomplete {
+                    stage: ProjStage::Proofreading,
+                    status: STAGE_STATUS_WIP,
+                },
+                GateIssue::StageIncomplete {
+                    stage: ProjStage::Reviewing,
+                    status: STAGE_STATUS_PENDING,
+                },
+                GateIssue::SourcesUnchecked {
+                    checked: 3,
+                    total: 12,
+                },
+            ]
+        );
+
+        // 不检查原文时只看阶段状态
+        assert_eq!(evaluate_gate(&unfinished, None).len(), 2);
+    }
+
+    #[test]
+    fn stage_names_round_trip_and_serialize_like_status_types() {
+        for stage in ProjStage::ALL {
+            assert_eq!(ProjStage::parse(stage.as_str()), Some(stage));
+            assert_eq!(serde_json::to_value(stage).unwrap(), json!(stage.as_str()));
+        }
+
+        assert_eq!(ProjStage::parse("published"), None);
+
+        let item = BulkPublishItem {
+            proj_id: "pub-proj".to_string(),
+            proj_name: None,
+            issues: vec![GateIssue::SourcesUnchecked {
+                checked: 1,
+                total: 2,
+            }],
+            status: BulkPublishStatus::Skipped {
+                reason: "未通过发布前检查".to_string(),
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_value(item).unwrap(),
+            json!({
+                "proj_id": "pub-proj",
+                "proj_name": null,
+                "issues": [{ "kind": "sources_unchecked", "checked": 1, "total": 2 }],
+                "status": "skipped",
+                "reason": "未通过发布前检查",
+            })
+        );
+    }
+
+    #[test]
+    fn bulk_request_defaults_to_requiring_completion() {
+        let req: PublishProjsBulkReq =
+            serde_json::from_value(json!({ "proj_ids": ["a", "b"] })).unwrap();
+
+        assert!(req.require_complete);
+        assert!(!req.check_sources);
+        assert!(!req.dry_run);
+    }
+}
@@ -0,0 +1,151 @@
+// 批量 / 后台请求的限速：同一后端上的批量请求（分批富化、提交后校验等）共用一个限速器，
+// 按固定间隔放行并允许少量突发（GCRA），避免多个后台任务叠加后把请求打满。前台的单个请求不经过这里。
+// 等待会超出当前作用域的整体时限（见 request_budget）时不再排队，直接返回 DeadlineExceeded
+use std::{
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{error::AppError, request_budget};
+
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    burst: u32,
+    // 理论上下一个请求的到达时间（theoretical arrival time）
+    tat: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    // 每秒放行 per_second 个，空闲后最多连续放行 burst 个
+    pub(crate) fn new(per_second: u32, burst: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / per_second.max(1),
+            burst: burst.max(1),
+            tat: Mutex::new(None),
+        }
+    }
+
+    // 预约一个放行名额，返回需要等待的时长；等待会超过 max_wait 时不预约，返回 None
+    fn reserve(&self, now: Instant, max_wait: Option<Duration>) -> Option<Duration> {
+        let mut tat = self
+            .tat
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let arrival = tat.map_or(now, |tat| tat.max(now));
+        let tolerance = self.interval * (self.burst - 1);
+        let wait = arrival
+            .checked_sub(tolerance)
+            .map_or(Duration::ZERO, |allow_at| {
+                allow_at.saturating_duration_since(now)
+            });
+
+        if max_wait.is_some_and(|max_wait| wait > max_wait) {
+            return None;
+        }
+
+        *tat = Some(arrival + self.interval);
+
+        Some(wait)
+    }
+
+    // 等待放行
+    pub(crate) async fn acquire(&self) -> Result<(), AppError> {
+        let max_wait = request_budget::current().map(|budget| budget.remaining());
+
+        let wait = self
+            .reserve(Instant::now(), max_wait)
+            .ok_or(AppError::DeadlineExceeded)?;
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        Ok(())
+    }
+}
+
+static MOETRAN_LIMITER: LazyLock<RateLimiter> = LazyLock::new(|| RateLimiter::new(5, 5));
+
+static POPRAKO_LIMITER: LazyLock<RateLimiter> = LazyLock::new(|| RateLimiter::new(5, 3));
+
+pub(crate) fn moetran_limiter() -> &'static RateLimiter {
+    &MOETRAN_LIMITER
+}
+
+pub(crate) fn poprako_limiter() -> &'static RateLimiter {
+    &POPRAKO_LIMITER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request_budget::{with_budget, RequestBudget};
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn burst_is_let_through_then_requests_are_spaced() {
+        let limiter = RateLimiter::new(10, 3);
+        let now = Instant::now();
+
+        let waits: Vec<Duration> = (0..5)
+            .map(|_| limiter.reserve(now, None).unwrap())
+            .collect();
+
+        assert_eq!(
+            waits,
+            [
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::ZERO,
+                100 * MS,
+                200 * MS
+            ]
+        );
+    }
+
+    #[test]
+    fn idle_time_refills_the_burst() {
+        let limiter = RateLimiter::new(10, 2);
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            limiter.reserve(now, None).unwrap();
+        }
+
+        let later = now + Duration::from_secs(1);
+
+        assert_eq!(limiter.reserve(later, None), Some(Duration::ZERO));
+        assert_eq!(limiter.reserve(later, None), Some(Duration::ZERO));
+        assert_eq!(limiter.reserve(later, None), Some(100 * MS));
+    }
+
+    #[test]
+    fn wait_beyond_limit_does_not_take_a_slot() {
+        let limiter = RateLimiter::new(10, 1);
+        let now = Instant::now();
+
+        limiter.reserve(now, None).unwrap();
+
+        assert_eq!(limiter.reserve(now, Some(50 * MS)), None);
+        assert_eq!(limiter.reserve(now, Some(100 * MS)), Some(100 * MS));
+    }
+
+    #[tokio::test]
+    async fn acquire_gives_up_when_budget_is_too_short() {
+        let limiter = RateLimiter::new(1, 1);
+
+        limiter.acquire().await.unwrap();
+
+        let started = Instant::now();
+        let err = with_budget(RequestBudget::from_ms(Some(100)), limiter.acquire())
+            .await
+            .unwrap_err();
+
+        assert!(err.is_deadline_exceeded());
+        assert!(started.elapsed() < 50 * MS);
+    }
+}
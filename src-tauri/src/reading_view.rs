@@ -0,0 +1,606 @@
+// 阅读版导出：给组外试读者的只读 HTML，每页为缓存的原图（按 max_width 缩小）叠加各 source 位置的译文，
+// 编号与 LabelPlus 导出一致。single_file 时图片以 data URI 内嵌为单个文件；folder 时输出 index.html 与 images/。
+// 设置 watermark_text 时在图片中烧入斜向条纹，并在每页叠加斜向水印文字。未缓存图片的页面以译文列表代替
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose, Engine};
+use image::{codecs::jpeg::JpegEncoder, RgbImage};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::{
+    contributions::fetch_file_sources,
+    defer::WarnDefer,
+    image_cache::cached_file_path,
+    natsort::compare_file_names,
+    project::{get_project_files, GetProjectFilesReq, MoetranSource},
+    translation_export::export_text,
+};
+
+const DEFAULT_MAX_WIDTH: u32 = 1200;
+const MIN_MAX_WIDTH: u32 = 320;
+
+const JPEG_QUALITY: u8 = 82;
+
+// 水印条纹：间距约为图片长边的 1/6，条纹宽度为间距的 1/8，与灰色按 WATERMARK_ALPHA 混合
+const WATERMARK_BANDS: u32 = 6;
+const WATERMARK_MIN_PERIOD: u32 = 48;
+const WATERMARK_ALPHA: f32 = 0.12;
+
+const STYLE: &str = r#"
+body { margin: 0; background: #2b2b2b; color: #eee; font-family: sans-serif; }
+main { max-width: 100%; margin: 0 auto; padding: 16px 0; }
+.page { margin: 0 auto 32px; width: fit-content; max-width: 100%; }
+.page h2 { font-size: 14px; font-weight: normal; color: #aaa; margin: 0 8px 6px; }
+.sheet { position: relative; max-width: 100%; overflow: hidden; }
+.sheet img { display: block; width: 100%; height: auto; user-select: none; -webkit-user-drag: none; }
+.label { position: absolute; transform: translate(-50%, -50%); max-width: 40%; padding: 2px 6px;
+  background: rgba(255, 255, 255, 0.88); color: #111; border-radius: 4px; white-space: pre-wrap;
+  font-size: clamp(10px, 1.6vw, 16px); line-height: 1.35; box-shadow: 0 1px 3px rgba(0, 0, 0, 0.4); }
+.label .no { color: #c0392b; font-weight: bold; margin-right: 4px; }
+.label.empty { background: rgba(255, 235, 180, 0.88); color: #777; }
+.wm { position: absolute; inset: 0; pointer-events: none; display: flex; flex-wrap: wrap;
+  align-content: space-around; justify-content: space-around; overflow: hidden; }
+.wm span { transform: rotate(-30deg); color: rgba(255, 255, 255, 0.22);
+  text-shadow: 0 0 2px rgba(0, 0, 0, 0.25); font-size: clamp(14px, 3vw, 32px); padding: 24px; }
+.missing { background: #3a3a3a; padding: 12px 16px; border-radius: 6px; width: min(720px, 100%); box-sizing: border-box; }
+.missing .note { color: #e0a040; font-size: 13px; }
+.missing li { white-space: pre-wrap; margin-bottom: 6px; }
+"#;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingViewLayout {
+    // 单个 HTML 文件，图片以 data URI 内嵌
+    #[default]
+    SingleFile,
+    // dest_path 为目录，写入 index.html 与 images/
+    Folder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingViewOptions {
+    // 为 true 时未翻译的 source 也显示（占位框）
+    #[serde(default)]
+    pub include_untranslated: bool,
+    #[serde(default)]
+    pub watermark_text: Option<String>,
+    // 图片的最大宽度（像素），更宽的图片等比缩小
+    #[serde(default)]
+    pub max_width: Option<u32>,
+    // 显示与 LabelPlus 导出一致的编号
+    #[serde(default = "default_numbering")]
+    pub numbering: bool,
+    #[serde(default)]
+    pub layout: ReadingViewLayout,
+}
+
+fn default_numbering() -> bool {
+    true
+}
+
+impl Default for ReadingViewOptions {
+    fn default() -> Self {
+        Self {
+            include_untranslated: false,
+            watermark_text: None,
+            max_width: None,
+            numbering: default_numbering(),
+            layout: ReadingViewLayout::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportReadingViewReq {
+    pub project_id: String,
+    pub target_id: String,
+    pub dest_path: String,
+    #[serde(default)]
+    pub options: ReadingViewOptions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadingViewReport {
+    // 实际写入的 HTML 文件
+    pub index_path: String,
+    pub pages: usize,
+    pub labels: usize,
+    // 没有缓存图片、以译文列表代替的页（文件名）
+    pub placeholder_pages: Vec<String>,
+    // 输出的总字节数（HTML 与图片）
+    pub bytes: u64,
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+// 译文中的换行保留（.label 使用 pre-wrap）；统一为 \n
+fn label_text(text: &str) -> String {
+    escape_html(&text.replace("\r\n", "\n"))
+}
+
+// 缩小后的尺寸；不超过 max_width 时保持原尺寸
+fn scaled_size(width: u32, height: u32, max_width: u32) -> (u32, u32) {
+    if width <= max_width || width == 0 {
+        return (width, height);
+    }
+
+    let scale = max_width as f64 / width as f64;
+
+    (max_width, ((height as f64 * scale).round() as u32).max(1))
+}
+
+// source 坐标是相对原图的比例（0..1），换算为缩小后图片上的像素位置，再按显示尺寸转为百分比：
+// 图片在页面上可能再被 max-width: 100% 缩小，百分比可保证标注与图片一起缩放
+fn label_position(x: f64, y: f64, width: u32, height: u32) -> (f64, f64) {
+    if width == 0 || height == 0 {
+        return (0.0, 0.0);
+    }
+
+    let px = (x.clamp(0.0, 1.0) * width as f64).round();
+    let py = (y.clamp(0.0, 1.0) * height as f64).round();
+
+    (px / width as f64 * 100.0, py / height as f64 * 100.0)
+}
+
+// 斜向（左下到右上）的半透明条纹；间距与条纹宽度按图片尺寸计算，缩小后再绘制以保持粗细一致
+fn apply_watermark(img: &mut RgbImage) {
+    let (width, height) = img.dimensions();
+
+    let period = (width.max(height) / WATERMARK_BANDS).max(WATERMARK_MIN_PERIOD);
+    let band = (period / 8).max(2);
+
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        if (x + y) % period >= band {
+            continue;
+        }
+
+        for channel in pixel.0.iter_mut() {
+            let blended = *channel as f32 * (1.0 - WATERMARK_ALPHA) + 128.0 * WATERMARK_ALPHA;
+            *channel = blended.round() as u8;
+        }
+    }
+}
+
+struct PageImage {
+    jpeg: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+// 在阻塞线程中执行：解码、缩小、加水印后编码为 JPEG
+fn render_page_image(path: &Path, max_width: u32, watermark: bool) -> Result<PageImage, String> {
+    let img = image::open(path).map_err(|err| format!("解码图片失败: {}", err))?;
+
+    let (width, height) = scaled_size(img.width(), img.height(), max_width);
+
+    let img = if (width, height) != (img.width(), img.height()) {
+        img.resize_exact(width, height, image::imageops::FilterType::Triangle)
+    } else {
+        img
+    };
+
+    let mut rgb = img.to_rgb8();
+
+    if watermark {
+        apply_watermark(&mut rgb);
+    }
+
+    let mut jpeg = Vec::new();
+
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode_image(&rgb)
+        .map_err(|err| format!("编码图片失败: {}", err))?;
+
+    Ok(PageImage {
+        jpeg,
+        width,
+        height,
+    })
+}
+
+// 编号为 source 在页内的顺序（从 1 开始），与 LabelPlus 导出一致；未翻译且不显示的 source 跳过但保留编号
+fn page_labels(
+    sources: &[MoetranSource],
+    include_untranslated: bool,
+) -> Vec<(usize, &MoetranSource, &str)> {
+    sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| (index + 1, source, export_text(source)))
+        .filter(|(_, _, text)| include_untranslated || !text.trim().is_empty())
+        .collect()
+}
+
+fn number_html(number: usize, numbering: bool) -> String {
+    match numbering {
+        true => format!("<span class=\"no\">{}</span>", number),
+        false => String::new(),
+    }
+}
+
+fn watermark_html(text: &str) -> String {
+    let span = format!("<span>{}</span>", escape_html(text));
+
+    format!("<div class=\"wm\">{}</div>", span.repeat(12))
+}
+
+fn page_html(
+    name: &str,
+    image_src: &str,
+    image: &PageImage,
+    labels: &[(usize, &MoetranSource, &str)],
+    options: &ReadingViewOptions,
+) -> String {
+    let mut html = format!(
+        "<section class=\"page\"><h2>{}</h2><div class=\"sheet\" style=\"width:{}px\"><img src=\"{}\" width=\"{}\" height=\"{}\" alt=\"\">",
+        escape_html(name),
+        image.width,
+        image_src,
+        image.width,
+        image.height
+    );
+
+    for (number, source, text) in labels {
+        let (left, top) = label_position(source.x, source.y, image.width, image.height);
+        let class = if text.trim().is_empty() {
+            "label empty"
+        } else {
+            "label"
+        };
+
+        html.push_str(&format!(
+            "<div class=\"{}\" style=\"left:{:.3}%;top:{:.3}%\">{}{}</div>",
+            class,
+            left,
+            top,
+            number_html(*number, options.numbering),
+            label_text(text)
+        ));
+    }
+
+    if let Some(text) = options.watermark_text.as_deref().filter(|t| !t.is_empty()) {
+        html.push_str(&watermark_html(text));
+    }
+
+    html.push_str("</div></section>\n");
+
+    html
+}
+
+fn placeholder_html(
+    name: &str,
+    labels: &[(usize, &MoetranSource, &str)],
+    options: &ReadingViewOptions,
+) -> String {
+    let mut html = format!(
+        "<section class=\"page missing\"><h2>{}</h2><p class=\"note\">本页图片未缓存，仅列出译文</p><ol>",
+        escape_html(name)
+    );
+
+    for (number, _, text) in labels {
+        html.push_str(&format!(
+            "<li value=\"{}\">{}</li>",
+            number,
+            label_text(text)
+        ));
+    }
+
+    html.push_str("</ol>");
+
+    if let Some(text) = options.watermark_text.as_deref().filter(|t| !t.is_empty()) {
+        html.push_str(&format!("<p class=\"note\">{}</p>", escape_html(text)));
+    }
+
+    html.push_str("</section>\n");
+
+    html
+}
+
+fn document_html(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"zh\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body oncontextmenu=\"return false\">\n<main>\n{}</main>\n</body>\n</html>\n",
+        escape_html(title),
+        STYLE,
+        body
+    )
+}
+
+async fn run_export(payload: &ExportReadingViewReq) -> Result<ReadingViewReport, String> {
+    let options = &payload.options;
+    let max_width = options
+        .max_width
+        .unwrap_or(DEFAULT_MAX_WIDTH)
+        .max(MIN_MAX_WIDTH);
+    let watermark = options
+        .watermark_text
+        .as_deref()
+        .is_some_and(|text| !text.trim().is_empty());
+
+    let dest = PathBuf::from(&payload.dest_path);
+
+    let (index_path, image_dir) = match options.layout {
+        ReadingViewLayout::SingleFile => (dest.clone(), None),
+        ReadingViewLayout::Folder => (dest.join("index.html"), Some(dest.join("images"))),
+    };
+
+    if let Some(dir) = &image_dir {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|err| format!("创建导出目录失败: {}", err))?;
+    }
+
+    let mut files = get_project_files(GetProjectFilesReq {
+        project_id: payload.project_id.clone().into(),
+        target_id: Some(payload.target_id.clone().into()),
+        sort: None,
+    })
+    .await?;
+
+    files.sort_by(|a, b| compare_file_names(&a.name, &b.name));
+
+    let mut body = String::new();
+    let mut report = ReadingViewReport {
+        index_path: index_path.to_string_lossy().to_string(),
+        pages: files.len(),
+        labels: 0,
+        placeholder_pages: Vec::new(),
+        bytes: 0,
+    };
+
+    for (page_index, file) in files.iter().enumerate() {
+        let sources = fetch_file_sources(file.id.to_string(), payload.target_id.clone()).await?;
+        let labels = page_labels(&sources, options.include_untranslated);
+
+        report.labels += labels.len();
+
+        let image = match cached_file_path(&payload.project_id, &file.id).await {
+            Some(path) => {
+                let rendered = tokio::task::spawn_blocking(move || {
+                    render_page_image(&path, max_width, watermark)
+                })
+                .await
+                .map_err(|err| format!("处理图片失败: {}", err))?;
+
+                match rendered {
+                    Ok(image) => Some(image),
+                    Err(err) => {
+                        tracing::warn!(file_id = %file.id, error = %err, "reading_view.image_failed");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let Some(image) = image else {
+            report.placeholder_pages.push(file.name.clone());
+            body.push_str(&placeholder_html(&file.name, &labels, options));
+            continue;
+        };
+
+        let src = match &image_dir {
+            Some(dir) => {
+                let name = format!("{:04}.jpg", page_index + 1);
+
+                fs::write(dir.join(&name), &image.jpeg)
+                    .await
+                    .map_err(|err| format!("写入图片失败: {}", err))?;
+
+                report.bytes += image.jpeg.len() as u64;
+
+                format!("images/{}", name)
+            }
+            None => format!(
+                "data:image/jpeg;base64,{}",
+                general_purpose::STANDARD.encode(&image.jpeg)
+            ),
+        };
+
+        body.push_str(&page_html(&file.name, &src, &image, &labels, options));
+    }
+
+    let title = dest
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "阅读版".to_string());
+
+    let html = document_html(&title, &body);
+
+    fs::write(&index_path, html.as_bytes())
+        .await
+        .map_err(|err| format!("写入导出文件失败: {}", err))?;
+
+    report.bytes += html.len() as u64;
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn export_reading_view(
+    payload: ExportReadingViewReq,
+) -> Result<ReadingViewReport, String> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        layout = ?payload.options.layout,
+        watermark = payload.options.watermark_text.is_some(),
+        "reading_view.export.start"
+    );
+
+    let mut defer = WarnDefer::new("reading_view.export");
+
+    let report = run_export(&payload).await?;
+
+    tracing::info!(
+        pages = report.pages,
+        labels = report.labels,
+        placeholders = report.placeholder_pages.len(),
+        bytes = report.bytes,
+        "reading_view.export.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    fn sources() -> Value {
+        let source = |id: &str, x: f64, content: Option<&str>| {
+            let translations = match content {
+                Some(content) => {
+                    json!([{ "id": format!("{}-t", id), "content": content, "proofread_content": null, "selected": true }])
+                }
+                None => json!([]),
+            };
+
+            json!({ "id": id, "x": x, "y": 0.5, "position_type": 1, "my_translation": null, "translations": translations })
+        };
+
+        json!([
+            source("s1", 0.25, Some("第一句\r\n<换行>")),
+            source("s2", 0.5, None),
+            source("s3", 0.75, Some("第三句")),
+        ])
+    }
+
+    #[test]
+    fn html_is_escaped_and_line_breaks_kept() {
+        assert_eq!(
+            escape_html(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+        assert_eq!(label_text("一\r\n二"), "一\n二");
+    }
+
+    #[test]
+    fn images_are_only_scaled_down() {
+        assert_eq!(scaled_size(800, 1200, 1200), (800, 1200));
+        assert_eq!(scaled_size(2400, 3601, 1200), (1200, 1801));
+        assert_eq!(scaled_size(5000, 1, 320), (320, 1));
+        assert_eq!(scaled_size(0, 10, 320), (0, 10));
+    }
+
+    #[test]
+    fn label_positions_are_clamped_percentages() {
+        assert_eq!(label_position(0.5, 0.25, 200, 400), (50.0, 25.0));
+        assert_eq!(label_position(-1.0, 2.0, 200, 400), (0.0, 100.0));
+        assert_eq!(label_position(0.5, 0.5, 0, 400), (0.0, 0.0));
+    }
+
+    #[test]
+    fn numbering_skips_hidden_sources_but_keeps_their_number() {
+        let sources: Vec<MoetranSource> = serde_json::from_value(sources()).unwrap();
+
+        let numbers = |include| -> Vec<usize> {
+            page_labels(&sources, include)
+                .iter()
+                .map(|(number, _, _)| *number)
+                .collect()
+        };
+
+        assert_eq!(numbers(false), [1, 3]);
+        assert_eq!(numbers(true), [1, 2, 3]);
+    }
+
+    #[test]
+    fn watermark_only_touches_band_pixels() {
+        let mut img = RgbImage::from_pixel(96, 96, image::Rgb([255, 255, 255]));
+        apply_watermark(&mut img);
+
+        // 间距取最小值 48，条纹宽 6
+        assert_ne!(img.get_pixel(0, 0).0, [255, 255, 255]);
+        assert_ne!(img.get_pixel(40, 13).0, [255, 255, 255]);
+        assert_eq!(img.get_pixel(10, 0).0, [255, 255, 255]);
+    }
+
+    #[test]
+    fn page_images_are_resized_and_reencoded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page.png");
+        RgbImage::from_pixel(640, 100, image::Rgb([10, 20, 30]))
+            .save(&path)
+            .unwrap();
+
+        let page = render_page_image(&path, 320, true).unwrap();
+
+        assert_eq!((page.width, page.height), (320, 50));
+        assert_eq!(&page.jpeg[..2], [0xFF, 0xD8]);
+
+        assert!(render_page_image(&dir.path().join("missing.png"), 320, false).is_err());
+    }
+
+    #[tokio::test]
+    async fn uncached_pages_fall_back_to_a_translation_list() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/rv-proj/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": "rv-f2", "name": "2.png", "source_count": 3, "url": "", "cover_url": "" },
+                { "id": "rv-f1", "name": "1.png", "source_count": 3, "url": "", "cover_url": "" },
+            ])))
+            .mount(&backends.moetran)
+            .await;
+
+        for file_id in ["rv-f1", "rv-f2"] {
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/files/{}/sources", file_id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(sources()))
+                .mount(&backends.moetran)
+                .await;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("试读.html");
+
+        let report = export_reading_view(ExportReadingViewReq {
+            project_id: "rv-proj".to_string(),
+            target_id: "rv-target".to_string(),
+            dest_path: dest.to_string_lossy().to_string(),
+            options: ReadingViewOptions {
+                watermark_text: Some("<仅供试读>".to_string()),
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!((report.pages, report.labels), (2, 4));
+        assert_eq!(report.placeholder_pages, ["1.png", "2.png"]);
+
+        let html = std::fs::read_to_string(&dest).unwrap();
+        assert_eq!(report.bytes, html.len() as u64);
+        assert!(html.contains("<title>试读</title>"));
+        assert!(html.contains("<li value=\"3\">第三句</li>"));
+        assert!(html.contains("&lt;换行&gt;"));
+        assert!(html.contains("&lt;仅供试读&gt;"));
+        assert!(html.find("1.png").unwrap() < html.find("2.png").unwrap());
+    }
+}
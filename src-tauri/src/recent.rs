@@ -0,0 +1,381 @@
+// 最近打开的项目：本地记录打开次数与时间，按 frecency 排序供首页直接渲染
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    project::{cached_proj_snapshot, ResProjectEnriched},
+    storage::{recent_projects, LOCAL_STORAGE},
+};
+
+// frecency 的半衰期：3 天未打开，权重减半
+const FRECENCY_HALF_LIFE_SECS: f64 = 3.0 * 24.0 * 3600.0;
+
+const DEFAULT_RECENT_LIMIT: usize = 5;
+
+// 打开次数越多、越近打开，得分越高
+fn frecency_score(open_count: i64, last_opened_at: i64, now: i64) -> f64 {
+    let age = (now - last_opened_at).max(0) as f64;
+
+    open_count.max(1) as f64 * 0.5_f64.powf(age / FRECENCY_HALF_LIFE_SECS)
+}
+
+// 得分高者在前，同分时最近打开的在前
+fn sort_by_frecency(items: &mut [RecentProjectItem]) {
+    items.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.last_opened_at.cmp(&a.last_opened_at))
+    });
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkProjectOpenedReq {
+    pub team_id: String,
+    pub proj_id: String,
+}
+
+#[tauri::command]
+pub async fn mark_project_opened(payload: MarkProjectOpenedReq) -> Result<(), String> {
+    tracing::debug!(team_id = %payload.team_id, proj_id = %payload.proj_id, "recent.mark_opened.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let snapshot =
+        cached_proj_snapshot(&payload.proj_id).and_then(|item| serde_json::to_string(&item).ok());
+
+    recent_projects::record_project_open(
+        storage.pool(),
+        &payload.team_id,
+        &payload.proj_id,
+        snapshot.as_deref(),
+    )
+    .await?;
+
+    tracing::debug!(team_id = %payload.team_id, proj_id = %payload.proj_id, "recent.mark_opened.ok");
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetRecentProjectsReq {
+    pub team_id: String,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecentProjectItem {
+    pub project_id: String,
+    pub open_count: i64,
+    pub last_opened_at: i64,
+    pub score: f64,
+    // 最近一次获取到的 enriched 数据（内存缓存优先，其次为本地快照）；可能已过期
+    pub project: Option<ResProjectEnriched>,
+}
+
+// 仅读取本地数据，不发起网络请求
+#[tauri::command]
+pub async fn get_recent_projects(
+    payload: GetRecentProjectsReq,
+) -> Result<Vec<RecentProjectItem>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = recent_projects::list_recent_projects(storage.pool(), &payload.team_id).await?;
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    let mut items: Vec<RecentProjectItem> = rows
+        .into_iter()
+        .map(|row| {
+            let project = cached_proj_snapshot(&row.project_id).or_else(|| {
+                row.snapshot
+                    .as_deref()
+                    .and_then(|raw| serde_json::from_str(raw).ok())
+            });
+
+            RecentProjectItem {
+                score: frecency_score(row.open_count, row.last_opened_at, now),
+                project_id: row.project_id,
+                open_count: row.open_count,
+                last_opened_at: row.last_opened_at,
+                project,
+            }
+        })
+        .collect();
+
+    sort_by_frecency(&mut items);
+
+    items.truncate(
+        payload
+            .limit
+            .map(|limit| limit as usize)
+            .unwrap_or(DEFAULT_RECENT_LIMIT),
+    );
+
+    tracing::debug!(team_id = %payload.team_id, count = items.len(), "recent.list.ok");
+
+    Ok(items)
+}
+
+// enriched 列表刷新后同步最近项目：更新已记录项目的快照。
+// 列表只含未完结（status=0）的项目，不在列表中并不代表项目已删除，因此这里不做清理
+pub(crate) async fn sync_recent_with_enriched(list: &[ResProjectEnriched]) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let mut by_team: HashMap<&str, Vec<&ResProjectEnriched>> = HashMap::new();
+
+    for item in list {
+        by_team.entry(item.team.id.as_str()).or_default().push(item);
+    }
+
+    for (team_id, items) in by_team {
+        let rows = match recent_projects::list_recent_projects(storage.pool(), team_id).await {
+            Ok(rows) => rows,
+            Err(err) => {
+                tracing::warn!(%team_id, error = %err, "recent.sync.list_failed");
+                continue;
+            }
+        };
+
+        let recorded: HashSet<&str> = rows.iter().map(|row| row.project_id.as_str()).collect();
+
+        for item in items
+            .iter()
+            .filter(|item| recorded.contains(item.id.as_str()))
+        {
+            let Ok(snapshot) = serde_json::to_string(item) else {
+                continue;
+            };
+
+            if let Err(err) = recent_projects::update_recent_snapshot(
+                storage.pool(),
+                team_id,
+                &item.id,
+                &snapshot,
+            )
+            .await
+            {
+                tracing::warn!(%team_id, proj_id = %item.id, error = %err, "recent.sync.snapshot_failed");
+            }
+        }
+    }
+}
+
+// 获取项目详情成功时记录一次打开（附带最新快照）
+pub(crate) async fn record_recent_open(item: &ResProjectEnriched) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let snapshot = serde_json::to_string(item).ok();
+
+    if let Err(err) = recent_projects::record_project_open(
+        storage.pool(),
+        &item.team.id,
+        &item.id,
+        snapshot.as_deref(),
+    )
+    .await
+    {
+        tracing::warn!(proj_id = %item.id, error = %err, "recent.record_open.failed");
+    }
+}
+
+// Moetran 明确返回项目不存在（404）时从最近项目中移除
+pub(crate) async fn forget_recent_project(proj_id: &str) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    match recent_projects::delete_recent_project_everywhere(storage.pool(), proj_id).await {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!(%proj_id, removed, "recent.pruned.not_found"),
+        Err(err) => tracing::warn!(%proj_id, error = %err, "recent.prune_failed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        error::AppError,
+        project::{get_project_enriched, GetProjectEnrichedReq},
+        test_support::{enriched_fixture, local_storage, MockBackends},
+    };
+
+    const DAY: i64 = 24 * 3600;
+    const NOW: i64 = 1_700_000_000;
+
+    fn item(id: &str, open_count: i64, last_opened_at: i64) -> RecentProjectItem {
+        RecentProjectItem {
+            project_id: id.to_string(),
+            open_count,
+            last_opened_at,
+            score: frecency_score(open_count, last_opened_at, NOW),
+            project: None,
+        }
+    }
+
+    fn ids(items: &[RecentProjectItem]) -> Vec<&str> {
+        items.iter().map(|item| item.project_id.as_str()).collect()
+    }
+
+    #[test]
+    fn score_halves_every_half_life() {
+        assert_eq!(frecency_score(1, NOW, NOW), 1.0);
+        assert!((frecency_score(1, NOW - 3 * DAY, NOW) - 0.5).abs() < 1e-9);
+        assert!((frecency_score(1, NOW - 6 * DAY, NOW) - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn future_timestamps_and_zero_counts_are_clamped() {
+        assert_eq!(frecency_score(4, NOW + DAY, NOW), 4.0);
+        assert_eq!(frecency_score(0, NOW, NOW), 1.0);
+    }
+
+    #[test]
+    fn frequent_projects_outrank_recent_ones_until_they_decay() {
+        // 8 次 × 0.5 = 4 > 1
+        let mut items = vec![item("fresh", 1, NOW), item("frequent", 8, NOW - 3 * DAY)];
+        sort_by_frecency(&mut items);
+        assert_eq!(ids(&items), ["frequent", "fresh"]);
+
+        // 8 次 × 0.5^4 = 0.5 < 1
+        let mut items = vec![item("stale", 8, NOW - 12 * DAY), item("fresh", 1, NOW)];
+        sort_by_frecency(&mut items);
+        assert_eq!(ids(&items), ["fresh", "stale"]);
+    }
+
+    #[test]
+    fn equal_scores_prefer_the_later_open() {
+        let mut earlier = item("earlier", 1, NOW - DAY);
+        let mut later = item("later", 1, NOW);
+        earlier.score = 1.0;
+        later.score = 1.0;
+
+        let mut items = vec![earlier, later];
+        sort_by_frecency(&mut items);
+        assert_eq!(ids(&items), ["later", "earlier"]);
+    }
+
+    fn res_project_json(id: &str, team_id: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "name": format!("项目 {}", id),
+            "source_count": 10,
+            "translated_source_count": 5,
+            "checked_source_count": 2,
+            "team": { "id": team_id, "avatar": "", "has_avatar": false, "name": "汉化组" },
+            "project_set": { "id": "default", "name": "默认项目集" },
+        })
+    }
+
+    async fn mount_enrichment_search(backends: &MockBackends) {
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": [],
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    async fn recorded(team_id: &str) -> Vec<(String, i64)> {
+        let storage = local_storage().await;
+
+        recent_projects::list_recent_projects(storage.pool(), team_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.project_id, row.open_count))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn opening_project_detail_records_an_open() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/recent-open-p1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(res_project_json("recent-open-p1", "recent-open-team")),
+            )
+            .mount(&backends.moetran)
+            .await;
+        mount_enrichment_search(&backends).await;
+
+        get_project_enriched(GetProjectEnrichedReq {
+            project_id: "recent-open-p1".into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            recorded("recent-open-team").await,
+            [("recent-open-p1".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn refreshing_a_list_without_the_project_keeps_it() {
+        let storage = local_storage().await;
+
+        recent_projects::record_project_open(storage.pool(), "recent-sync-team", "finished", None)
+            .await
+            .unwrap();
+
+        // 已完结的项目不在 status=0 列表中
+        sync_recent_with_enriched(&[enriched_fixture("active", "recent-sync-team")]).await;
+
+        assert_eq!(
+            recorded("recent-sync-team").await,
+            [("finished".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn not_found_project_is_forgotten() {
+        let backends = MockBackends::start().await;
+        let storage = local_storage().await;
+
+        recent_projects::record_project_open(storage.pool(), "recent-gone-team", "gone", None)
+            .await
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/gone"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "code": 404,
+                "message": "项目不存在",
+            })))
+            .mount(&backends.moetran)
+            .await;
+
+        let err = get_project_enriched(GetProjectEnrichedReq {
+            project_id: "gone".into(),
+        })
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, AppError::NotFound(_)));
+        assert!(recorded("recent-gone-team").await.is_empty());
+    }
+}
@@ -0,0 +1,486 @@
+// 修图工作量估算：框外 source（拟声词、画面上的文字）多且扎堆的页面通常需要修图。
+// 按页统计框外 source 的数量与聚集程度打分，分为 none / light / heavy 三档，供协调者估算工作量；
+// 最近一次估算结果保留在内存中，可按分级批量生成本地修图任务
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::unix_now,
+    defer::WarnDefer,
+    ids::{FileId, ProjectId, TargetId},
+    position_type::PositionType,
+    storage::{redraw_tasks, LOCAL_STORAGE},
+    text_stats::load_project_sources,
+};
+
+// 两个框外 source 的距离（坐标为页面宽高的比例）在此范围内时计入聚集分，越近分越高
+const CLUSTER_RADIUS: f64 = 0.15;
+
+// 默认阈值：有框外 source 即为 light；约 6 个分散的、或 3~4 个扎堆的为 heavy
+const DEFAULT_LIGHT_SCORE: f64 = 1.0;
+const DEFAULT_HEAVY_SCORE: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedrawBucket {
+    None,
+    Light,
+    Heavy,
+}
+
+impl RedrawBucket {
+    fn as_str(self) -> &'static str {
+        match self {
+            RedrawBucket::None => "none",
+            RedrawBucket::Light => "light",
+            RedrawBucket::Heavy => "heavy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RedrawThresholds {
+    // 分数达到该值为 light
+    pub light_score: f64,
+    // 分数达到该值为 heavy
+    pub heavy_score: f64,
+}
+
+impl Default for RedrawThresholds {
+    fn default() -> Self {
+        Self {
+            light_score: DEFAULT_LIGHT_SCORE,
+            heavy_score: DEFAULT_HEAVY_SCORE,
+        }
+    }
+}
+
+impl RedrawThresholds {
+    fn validate(self) -> Result<Self, String> {
+        if !self.light_score.is_finite() || !self.heavy_score.is_finite() {
+            return Err("阈值必须是有限数值".to_string());
+        }
+
+        if self.light_score <= 0.0 || self.heavy_score < self.light_score {
+            return Err(format!(
+                "阈值无效：需要 0 < light_score ({}) <= heavy_score ({})",
+                self.light_score, self.heavy_score
+            ));
+        }
+
+        Ok(self)
+    }
+
+    fn classify(&self, score: f64) -> RedrawBucket {
+        if score >= self.heavy_score {
+            RedrawBucket::Heavy
+        } else if score >= self.light_score {
+            RedrawBucket::Light
+        } else {
+            RedrawBucket::None
+        }
+    }
+}
+
+// 聚集分：两两之间按距离线性衰减（重合为 1，达到 CLUSTER_RADIUS 为 0）后求和
+pub(crate) fn cluster_score(points: &[(f64, f64)]) -> f64 {
+    let mut score = 0.0;
+
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            let distance = (a.0 - b.0).hypot(a.1 - b.1);
+            score += (1.0 - distance / CLUSTER_RADIUS).max(0.0);
+        }
+    }
+
+    score
+}
+
+// 页面分数：框外 source 数加聚集分
+pub(crate) fn page_score(points: &[(f64, f64)]) -> f64 {
+    points.len() as f64 + cluster_score(points)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PageRedrawEstimate {
+    pub file_id: FileId,
+    pub file_name: String,
+    pub outside_count: usize,
+    pub cluster_score: f64,
+    pub score: f64,
+    pub bucket: RedrawBucket,
+    // 拉取失败、改用本地快照估算
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RedrawTotals {
+    pub none: usize,
+    pub light: usize,
+    pub heavy: usize,
+    pub outside_sources: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedrawEstimateReport {
+    pub project_id: ProjectId,
+    pub target_id: TargetId,
+    pub thresholds: RedrawThresholds,
+    // 按项目中的文件顺序
+    pub pages: Vec<PageRedrawEstimate>,
+    pub totals: RedrawTotals,
+    pub estimated_at: i64,
+}
+
+// 最近一次估算结果：项目 id -> 报告，seed_redraw_tasks_from_estimate 据此生成任务
+static LAST_ESTIMATES: LazyLock<Mutex<HashMap<String, RedrawEstimateReport>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EstimateRedrawWorkloadReq {
+    pub project_id: ProjectId,
+    pub target_id: TargetId,
+    // 缺省时使用默认阈值
+    #[serde(default)]
+    pub thresholds: Option<RedrawThresholds>,
+}
+
+#[tauri::command]
+pub async fn estimate_redraw_workload(
+    payload: EstimateRedrawWorkloadReq,
+) -> Result<RedrawEstimateReport, String> {
+    let thresholds = payload.thresholds.unwrap_or_default().validate()?;
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        light_score = thresholds.light_score,
+        heavy_score = thresholds.heavy_score,
+        "moetran.project.redraw_estimate.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.project.redraw_estimate");
+
+    let files = load_project_sources(&payload.project_id, &payload.target_id).await?;
+
+    let mut totals = RedrawTotals::default();
+
+    let pages: Vec<PageRedrawEstimate> = files
+        .into_iter()
+        .map(|file| {
+            let points: Vec<(f64, f64)> = file
+                .sources
+                .iter()
+                .filter(|source| source.position_type == PositionType::Outside)
+                .map(|source| (source.x, source.y))
+                .collect();
+
+            let cluster = cluster_score(&points);
+            let score = page_score(&points);
+            let bucket = thresholds.classify(score);
+
+            match bucket {
+                RedrawBucket::None => totals.none += 1,
+                RedrawBucket::Light => totals.light += 1,
+                RedrawBucket::Heavy => totals.heavy += 1,
+            }
+            totals.outside_sources += points.len();
+
+            PageRedrawEstimate {
+                file_id: file.file_id.into(),
+                file_name: file.file_name,
+                outside_count: points.len(),
+                cluster_score: cluster,
+                score,
+                bucket,
+                stale: file.stale,
+            }
+        })
+        .collect();
+
+    let report = RedrawEstimateReport {
+        project_id: payload.project_id.clone(),
+        target_id: payload.target_id,
+        thresholds,
+        pages,
+        totals,
+        estimated_at: unix_now(),
+    };
+
+    if let Ok(mut last) = LAST_ESTIMATES.lock() {
+        last.insert(payload.project_id.to_string(), report.clone());
+    }
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        pages = report.pages.len(),
+        light = report.totals.light,
+        heavy = report.totals.heavy,
+        "moetran.project.redraw_estimate.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedrawTask {
+    pub project_id: ProjectId,
+    pub file_id: FileId,
+    pub file_name: String,
+    pub bucket: RedrawBucket,
+    pub score: f64,
+    pub outside_count: usize,
+    pub created_at: i64,
+}
+
+impl From<redraw_tasks::RedrawTaskRow> for RedrawTask {
+    fn from(row: redraw_tasks::RedrawTaskRow) -> Self {
+        Self {
+            project_id: row.proj_id.into(),
+            file_id: row.file_id.into(),
+            file_name: row.file_name,
+            bucket: match row.bucket.as_str() {
+                "heavy" => RedrawBucket::Heavy,
+                _ => RedrawBucket::Light,
+            },
+            score: row.score,
+            outside_count: row.outside_count as usize,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeedRedrawTasksReq {
+    pub project_id: ProjectId,
+    // 分级不低于该值的页面生成任务；缺省为 heavy，none 按 light 处理
+    #[serde(default)]
+    pub min_bucket: Option<RedrawBucket>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedRedrawTasksReport {
+    // 本次新建的任务
+    pub created: Vec<RedrawTask>,
+    // 已有任务而跳过的页面
+    pub existing: Vec<FileId>,
+}
+
+// 按最近一次估算结果为达到分级的页面生成修图任务；已有任务的页面保持不变
+#[tauri::command]
+pub async fn seed_redraw_tasks_from_estimate(
+    payload: SeedRedrawTasksReq,
+) -> Result<SeedRedrawTasksReport, String> {
+    let min_bucket = payload
+        .min_bucket
+        .unwrap_or(RedrawBucket::Heavy)
+        .max(RedrawBucket::Light);
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        min_bucket = min_bucket.as_str(),
+        "redraw_tasks.seed.start"
+    );
+
+    let mut defer = WarnDefer::new("redraw_tasks.seed");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let estimate = LAST_ESTIMATES
+        .lock()
+        .map_err(|_| "redraw estimate lock poisoned".to_string())?
+        .get(payload.project_id.as_str())
+        .cloned()
+        .ok_or_else(|| "该项目尚未估算修图工作量，请先运行估算".to_string())?;
+
+    let mut report = SeedRedrawTasksReport {
+        created: Vec::new(),
+        existing: Vec::new(),
+    };
+
+    for page in estimate
+        .pages
+        .iter()
+        .filter(|page| page.bucket >= min_bucket)
+    {
+        let row = redraw_tasks::RedrawTaskRow {
+            proj_id: payload.project_id.to_string(),
+            file_id: page.file_id.to_string(),
+            file_name: page.file_name.clone(),
+            bucket: page.bucket.as_str().to_string(),
+            score: page.score,
+            outside_count: page.outside_count as i64,
+            created_at: unix_now(),
+        };
+
+        if redraw_tasks::insert_redraw_task(storage.pool(), &row).await? {
+            report.created.push(row.into());
+        } else {
+            report.existing.push(page.file_id.clone());
+        }
+    }
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        created = report.created.len(),
+        existing = report.existing.len(),
+        "redraw_tasks.seed.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListRedrawTasksReq {
+    pub project_id: ProjectId,
+}
+
+#[tauri::command]
+pub async fn list_redraw_tasks(payload: ListRedrawTasksReq) -> Result<Vec<RedrawTask>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = redraw_tasks::list_redraw_tasks(storage.pool(), &payload.project_id).await?;
+
+    Ok(rows.into_iter().map(RedrawTask::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::local_storage;
+
+    #[test]
+    fn clustered_sources_score_higher_than_scattered_ones() {
+        assert_eq!(page_score(&[]), 0.0);
+        assert_eq!(page_score(&[(0.5, 0.5)]), 1.0);
+
+        // 重合的三个点两两计 1 分
+        let stacked = [(0.5, 0.5); 3];
+        assert_eq!(cluster_score(&stacked), 3.0);
+        assert_eq!(page_score(&stacked), 6.0);
+
+        let scattered = [(0.1, 0.1), (0.9, 0.1), (0.5, 0.9)];
+        assert_eq!(cluster_score(&scattered), 0.0);
+
+        // 半径一半处计 0.5 分，达到半径时为 0
+        let half = cluster_score(&[(0.0, 0.0), (CLUSTER_RADIUS / 2.0, 0.0)]);
+        assert!((half - 0.5).abs() < 1e-9);
+        assert_eq!(cluster_score(&[(0.0, 0.0), (0.0, CLUSTER_RADIUS)]), 0.0);
+    }
+
+    #[test]
+    fn thresholds_are_validated_and_classify_scores() {
+        let thresholds = RedrawThresholds::default().validate().unwrap();
+
+        assert_eq!(thresholds.classify(0.0), RedrawBucket::None);
+        assert_eq!(thresholds.classify(1.0), RedrawBucket::Light);
+        assert_eq!(thresholds.classify(5.99), RedrawBucket::Light);
+        assert_eq!(thresholds.classify(6.0), RedrawBucket::Heavy);
+
+        let invalid = [
+            (0.0, 1.0),
+            (2.0, 1.0),
+            (f64::NAN, 1.0),
+            (1.0, f64::INFINITY),
+        ];
+        for (light_score, heavy_score) in invalid {
+            let thresholds = RedrawThresholds {
+                light_score,
+                heavy_score,
+            };
+            assert!(thresholds.validate().is_err(), "{:?}", thresholds);
+        }
+    }
+
+    fn page(file_id: &str, score: f64, bucket: RedrawBucket) -> PageRedrawEstimate {
+        PageRedrawEstimate {
+            file_id: file_id.to_string().into(),
+            file_name: format!("{}.png", file_id),
+            outside_count: score as usize,
+            cluster_score: 0.0,
+            score,
+            bucket,
+            stale: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn tasks_are_seeded_once_per_page_from_the_last_estimate() {
+        local_storage().await;
+        let project_id: ProjectId = "redraw-proj".to_string().into();
+
+        let seed = |min_bucket| {
+            seed_redraw_tasks_from_estimate(SeedRedrawTasksReq {
+                project_id: project_id.clone(),
+                min_bucket,
+            })
+        };
+
+        assert!(seed(None).await.unwrap_err().contains("尚未估算"));
+
+        LAST_ESTIMATES.lock().unwrap().insert(
+            project_id.to_string(),
+            RedrawEstimateReport {
+                project_id: project_id.clone(),
+                target_id: "redraw-target".to_string().into(),
+                thresholds: RedrawThresholds::default(),
+                pages: vec![
+                    page("rf1", 0.0, RedrawBucket::None),
+                    page("rf2", 2.0, RedrawBucket::Light),
+                    page("rf3", 7.0, RedrawBucket::Heavy),
+                ],
+                totals: RedrawTotals::default(),
+                estimated_at: 0,
+            },
+        );
+
+        let heavy = seed(None).await.unwrap();
+        let created: Vec<String> = heavy
+            .created
+            .iter()
+            .map(|task| task.file_id.to_string())
+            .collect();
+        assert_eq!(created, ["rf3"]);
+
+        // none 按 light 处理；已有任务的页面不重复创建
+        let light = seed(Some(RedrawBucket::None)).await.unwrap();
+        let created: Vec<String> = light
+            .created
+            .iter()
+            .map(|task| task.file_id.to_string())
+            .collect();
+        let existing: Vec<String> = light.existing.iter().map(|id| id.to_string()).collect();
+        assert_eq!(created, ["rf2"]);
+        assert_eq!(existing, ["rf3"]);
+
+        let mut tasks: Vec<(String, RedrawBucket)> = list_redraw_tasks(ListRedrawTasksReq {
+            project_id: project_id.clone(),
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|task| (task.file_id.to_string(), task.bucket))
+        .collect();
+        tasks.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            tasks,
+            [
+                ("rf2".to_string(), RedrawBucket::Light),
+                ("rf3".to_string(), RedrawBucket::Heavy)
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// 组合命令的整体时限：一个命令内部会发出多个子请求，单个请求有超时，但总耗时没有上限。
+// 命令可接受 deadline_ms，在其作用域内（task-local）设置时限；http 层的每个子请求
+// 以 min(常规超时, 剩余时限) 作为超时，时限用尽后不再发出请求，返回 AppError::DeadlineExceeded，
+// 由命令据此返回已取得的部分结果并标记 deadline_exceeded
+use std::{future::Future, time::Duration};
+
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestBudget {
+    deadline: Instant,
+}
+
+impl RequestBudget {
+    // None 表示不限时
+    pub(crate) fn from_ms(deadline_ms: Option<u64>) -> Option<Self> {
+        deadline_ms.map(|ms| Self {
+            deadline: Instant::now() + Duration::from_millis(ms),
+        })
+    }
+
+    pub(crate) fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    // 子请求的超时：不超过剩余时限
+    pub(crate) fn clamp(&self, normal: Duration) -> Duration {
+        normal.min(self.remaining())
+    }
+}
+
+tokio::task_local! {
+    static BUDGET: RequestBudget;
+}
+
+// 在时限内执行 fut；budget 为 None 时不限时。
+// 注意 task-local 不会传递给 tokio::spawn 出的子任务
+pub(crate) async fn with_budget<F: Future>(budget: Option<RequestBudget>, fut: F) -> F::Output {
+    match budget {
+        Some(budget) => BUDGET.scope(budget, fut).await,
+        None => fut.await,
+    }
+}
+
+// 当前作用域的时限
+pub(crate) fn current() -> Option<RequestBudget> {
+    BUDGET.try_with(|budget| *budget).ok()
+}
+
+// 当前作用域设置了时限且已用尽
+pub(crate) fn exceeded() -> bool {
+    current().is_some_and(|budget| budget.expired())
+}
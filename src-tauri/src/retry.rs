@@ -0,0 +1,492 @@
+// 失败写操作的一键重试：登记的写命令因连接类错误失败时，把命令名与完整 payload 保存到本地数据库
+// （最多 MAX_ENTRIES 条，重启后仍可重试），错误改为 AppError::Retryable 返回；retry_command 按 token
+// 经同一命令重新执行。token 超过 retry_window_minutes 后失效；同一实体（如同一项目）上有更新的写操作时，
+// 之前的 token 作废，避免重试把较新的修改覆盖回旧值
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    clock::unix_now,
+    config::config,
+    error::AppError,
+    project::{
+        create_source, submit_translation, update_proj_status, update_source, update_translation,
+    },
+    storage::{
+        retry_tokens::{self, RetryTokenRow},
+        LOCAL_STORAGE,
+    },
+};
+
+const MAX_ENTRIES: i64 = 32;
+
+type RetryFuture = Pin<Box<dyn Future<Output = Result<Value, AppError>> + Send>>;
+type Dispatch = fn(Value) -> RetryFuture;
+
+struct RetryableDef {
+    command: &'static str,
+    dispatch: Dispatch,
+}
+
+// 可重试的命令；新增时在此登记，并在命令中使用 RetryScope
+const RETRYABLE: &[RetryableDef] = &[
+    RetryableDef {
+        command: "update_proj_status",
+        dispatch: |payload| redispatch(payload, update_proj_status),
+    },
+    RetryableDef {
+        command: "submit_translation",
+        dispatch: |payload| redispatch(payload, submit_translation),
+    },
+    RetryableDef {
+        command: "update_translation",
+        dispatch: |payload| redispatch(payload, update_translation),
+    },
+    RetryableDef {
+        command: "create_source",
+        dispatch: |payload| redispatch(payload, create_source),
+    },
+    RetryableDef {
+        command: "update_source",
+        dispatch: |payload| redispatch(payload, update_source),
+    },
+];
+
+// 反序列化 payload 后调用命令，结果转为 JSON
+fn redispatch<P, R, F>(payload: Value, command: fn(P) -> F) -> RetryFuture
+where
+    P: DeserializeOwned + Send + 'static,
+    R: Serialize,
+    F: Future<Output = Result<R, AppError>> + Send + 'static,
+{
+    let payload = serde_json::from_value::<P>(payload);
+
+    Box::pin(async move {
+        let payload =
+            payload.map_err(|err| AppError::Other(format!("重试参数格式错误: {}", err)))?;
+
+        let reply = command(payload).await?;
+
+        serde_json::to_value(reply)
+            .map_err(|err| AppError::Other(format!("序列化结果失败: {}", err)))
+    })
+}
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+fn window_secs() -> i64 {
+    config().retry_window_minutes * 60
+}
+
+// 进程号区分重启前后登记的 token
+fn new_token() -> String {
+    format!(
+        "r{:x}-{:x}-{:x}",
+        unix_now(),
+        std::process::id(),
+        NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+// 连接类错误（断网、超时、后端维护、离线模式、整体时限用尽）才值得重试
+fn is_retryable_error(err: &AppError) -> bool {
+    matches!(
+        err.root(),
+        AppError::Network(_)
+            | AppError::ServiceUnavailable { .. }
+            | AppError::Offline
+            | AppError::DeadlineExceeded
+    )
+}
+
+fn storage_pool() -> Result<&'static sqlx::SqlitePool, AppError> {
+    LOCAL_STORAGE
+        .get()
+        .map(|storage| storage.pool())
+        .ok_or_else(|| AppError::Storage("LOCAL_STORAGE not initialized".to_string()))
+}
+
+// 删除过期条目
+async fn prune(pool: &sqlx::SqlitePool, now: i64) -> Result<(), AppError> {
+    retry_tokens::delete_expired_retry_tokens(pool, now - window_secs())
+        .await
+        .map_err(AppError::Storage)?;
+
+    Ok(())
+}
+
+// 一次可重试的写操作：开始时作废同一实体上的旧 token，结束时按结果登记
+pub(crate) struct RetryScope {
+    command: &'static str,
+    entity: String,
+    payload: Option<Value>,
+}
+
+impl RetryScope {
+    // entity 形如 "proj:<id>"，同一实体上较新的写操作会使旧 token 失效
+    pub(crate) async fn begin(
+        command: &'static str,
+        entity: String,
+        payload: &impl Serialize,
+    ) -> Self {
+        if let Ok(pool) = storage_pool() {
+            match retry_tokens::delete_retry_tokens_for_entity(pool, &entity).await {
+                Ok(0) => {}
+                Ok(_) => tracing::info!(command, entity = %entity, "retry.invalidated"),
+                Err(err) => {
+                    tracing::warn!(command, entity = %entity, error = %err, "retry.invalidate_failed")
+                }
+            }
+        }
+
+        Self {
+            command,
+            entity,
+            payload: serde_json::to_value(payload).ok(),
+        }
+    }
+
+    // 连接类错误时登记并返回 AppError::Retryable；其余结果原样返回。登记失败时返回原错误
+    pub(crate) async fn finish<T>(self, result: Result<T, AppError>) -> Result<T, AppError> {
+        let err = match result {
+            Ok(value) => return Ok(value),
+            Err(err) if is_retryable_error(&err) => err,
+            Err(err) => return Err(err),
+        };
+
+        let Some(payload) = self.payload else {
+            return Err(err);
+        };
+
+        let Ok(pool) = storage_pool() else {
+            return Err(err);
+        };
+
+        let now = unix_now();
+
+        let row = RetryTokenRow {
+            token: new_token(),
+            command: self.command.to_string(),
+            entity: self.entity.clone(),
+            payload: payload.to_string(),
+            error: err.to_string(),
+            failed_at: now,
+        };
+
+        let registered = match prune(pool, now).await {
+            Ok(()) => retry_tokens::insert_retry_token(pool, &row, MAX_ENTRIES)
+                .await
+                .map_err(AppError::Storage),
+            Err(prune_err) => Err(prune_err),
+        };
+
+        if let Err(register_err) = registered {
+            tracing::warn!(command = self.command, error = %register_err, "retry.register_failed");
+            return Err(err);
+        }
+
+        tracing::info!(
+            command = self.command,
+            entity = %self.entity,
+            token = %row.token,
+            "retry.registered"
+        );
+
+        Err(AppError::Retryable {
+            command: row.command,
+            retry_token: row.token,
+            source: Box::new(err),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryableFailure {
+    pub retry_token: String,
+    pub command: String,
+    pub entity: String,
+    pub error: String,
+    pub failed_at: i64,
+    pub expires_at: i64,
+}
+
+// 按失败时间从新到旧
+#[tauri::command]
+pub async fn list_retryable_failures() -> Result<Vec<RetryableFailure>, AppError> {
+    let pool = storage_pool()?;
+
+    prune(pool, unix_now()).await?;
+
+    let window = window_secs();
+
+    let rows = retry_tokens::list_retry_tokens(pool)
+        .await
+        .map_err(AppError::Storage)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RetryableFailure {
+            expires_at: row.failed_at + window,
+            retry_token: row.token,
+            command: row.command,
+            entity: row.entity,
+            error: row.error,
+            failed_at: row.failed_at,
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetryCommandReq {
+    pub retry_token: String,
+}
+
+// 重新执行登记的命令并返回其结果；token 只能使用一次，再次失败时返回带新 token 的 Retryable 错误
+#[tauri::command]
+pub async fn retry_command(payload: RetryCommandReq) -> Result<Value, AppError> {
+    let pool = storage_pool()?;
+
+    prune(pool, unix_now()).await?;
+
+    let entry = retry_tokens::take_retry_token(pool, &payload.retry_token)
+        .await
+        .map_err(AppError::Storage)?
+        .ok_or_else(|| AppError::NotFound("重试已过期或已失效（之后有更新的修改）".to_string()))?;
+
+    let def = RETRYABLE
+        .iter()
+        .find(|def| def.command == entry.command)
+        .ok_or_else(|| AppError::InvalidInput(format!("命令 {} 不支持重试", entry.command)))?;
+
+    let stored = serde_json::from_str::<Value>(&entry.payload)
+        .map_err(|err| AppError::Storage(format!("重试参数已损坏: {}", err)))?;
+
+    tracing::info!(
+        command = def.command,
+        entity = %entry.entity,
+        token = %entry.token,
+        "retry.command.start"
+    );
+
+    let result = (def.dispatch)(stored).await;
+
+    tracing::info!(
+        command = def.command,
+        ok = result.is_ok(),
+        "retry.command.done"
+    );
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        project::CreateSourceReq,
+        test_support::{local_storage, MockBackends},
+    };
+
+    async fn registered(entity: &str) -> Vec<RetryableFailure> {
+        list_retryable_failures()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|failure| failure.entity == entity)
+            .collect()
+    }
+
+    async fn fail_with(entity: &str, err: AppError) -> AppError {
+        RetryScope::begin(
+            "update_source",
+            entity.to_string(),
+            &json!({ "id": entity }),
+        )
+        .await
+        .finish::<()>(Err(err))
+        .await
+        .unwrap_err()
+    }
+
+    #[test]
+    fn only_connection_errors_are_retryable() {
+        assert!(is_retryable_error(&AppError::Network("reset".into())));
+        assert!(is_retryable_error(&AppError::Offline));
+        assert!(is_retryable_error(&AppError::DeadlineExceeded));
+        assert!(is_retryable_error(
+            &AppError::Network("reset".into()).context("更新 source 失败")
+        ));
+        assert!(!is_retryable_error(&AppError::Conflict("busy".into())));
+        assert!(!is_retryable_error(&AppError::MoetranHttp {
+            status: 400,
+            body: String::new(),
+        }));
+    }
+
+    #[tokio::test]
+    async fn connection_failure_is_registered_and_typed() {
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+
+        let err = fail_with(
+            "retry:typed",
+            AppError::Network("request send error".into()),
+        )
+        .await;
+
+        let AppError::Retryable {
+            command,
+            retry_token,
+            source,
+        } = &err
+        else {
+            panic!("expected Retryable, got {:?}", err);
+        };
+
+        assert_eq!(command, "update_source");
+        assert!(source.is_network());
+        assert_eq!(err.to_string(), "request send error");
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["kind"], "Retryable");
+        assert_eq!(value["cause"], "Network");
+        assert_eq!(value["retry_token"], json!(retry_token));
+
+        let failures = registered("retry:typed").await;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(&failures[0].retry_token, retry_token);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_registered() {
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+
+        let err = fail_with("retry:conflict", AppError::Conflict("busy".into())).await;
+
+        assert!(err.is_conflict());
+        assert!(registered("retry:conflict").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn newer_write_on_same_entity_invalidates_token() {
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+
+        fail_with("retry:invalidate", AppError::Offline).await;
+        assert_eq!(registered("retry:invalidate").await.len(), 1);
+
+        RetryScope::begin("update_source", "retry:invalidate".to_string(), &json!({}))
+            .await
+            .finish(Ok(()))
+            .await
+            .unwrap();
+
+        assert!(registered("retry:invalidate").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn expired_tokens_are_pruned_and_rejected() {
+        let _backends = MockBackends::start().await;
+        let storage = local_storage().await;
+
+        let row = RetryTokenRow {
+            token: "retry-expired-token".to_string(),
+            command: "update_source".to_string(),
+            entity: "retry:expired".to_string(),
+            payload: "{}".to_string(),
+            error: "request send error".to_string(),
+            failed_at: unix_now() - window_secs() - 1,
+        };
+        retry_tokens::insert_retry_token(storage.pool(), &row, MAX_ENTRIES)
+            .await
+            .unwrap();
+
+        assert!(registered("retry:expired").await.is_empty());
+
+        let err = retry_command(RetryCommandReq {
+            retry_token: row.token,
+        })
+        .await
+        .unwrap_err();
+
+        assert!(err.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn retry_replays_the_stored_payload_once() {
+        let backends = MockBackends::start().await;
+        local_storage().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/files/retry-file/sources"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(json!({ "message": "x" })))
+            .up_to_n_times(1)
+            .mount(&backends.moetran)
+            .await;
+
+        let payload = CreateSourceReq {
+            file_id: "retry-file".into(),
+            x: 0.25,
+            y: 0.5,
+            position_type: Default::default(),
+            width: None,
+            height: None,
+        };
+
+        // 非连接类错误：不登记
+        let err = crate::project::create_source(payload.clone())
+            .await
+            .unwrap_err();
+        assert!(!err.is_retryable());
+
+        backends.moetran.reset().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/files/retry-file/sources"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("<html>维护中</html>"))
+            .up_to_n_times(1)
+            .mount(&backends.moetran)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/files/retry-file/sources"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "retry-source",
+                "x": 0.25,
+                "y": 0.5,
+                "position_type": 1,
+                "my_translation": null,
+            })))
+            .mount(&backends.moetran)
+            .await;
+
+        let err = crate::project::create_source(payload).await.unwrap_err();
+        let AppError::Retryable { retry_token, .. } = err else {
+            panic!("expected Retryable, got {:?}", err);
+        };
+
+        let reply = retry_command(RetryCommandReq {
+            retry_token: retry_token.clone(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(reply["id"], "retry-source");
+
+        let again = retry_command(RetryCommandReq { retry_token })
+            .await
+            .unwrap_err();
+        assert!(again.is_not_found());
+    }
+}
@@ -0,0 +1,347 @@
+// 会话身份一致性检查：Moetran 登录账号与本地保存的 PopRaKo token 所属用户必须一致，
+// 否则（如共用电脑残留了他人的 PopRaKo token）拒绝 PopRaKo 写操作，直到重新同步或用户明确确认
+use std::sync::RwLock;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+use time::OffsetDateTime;
+
+use crate::{
+    defer::WarnDefer,
+    events::{emit_event, IdentityMismatch},
+    http::moetran_get,
+    token::{get_moetran_token, get_poprako_token},
+    user::ResUser,
+};
+
+// 常见的用户 id claim 名，按优先级查找
+const USER_ID_CLAIMS: &[&str] = &["user_id", "userId", "uid", "sub", "id"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JwtError {
+    // 不是 JWT，或 payload 无法解析 / 不含用户 id
+    Malformed(String),
+    // exp 早于当前时间
+    Expired { exp: i64 },
+}
+
+impl std::fmt::Display for JwtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JwtError::Malformed(reason) => write!(f, "malformed token: {}", reason),
+            JwtError::Expired { exp } => write!(f, "token expired at {}", exp),
+        }
+    }
+}
+
+// 本地解码 JWT payload 中的用户 id（不校验签名，只用于一致性提示）
+pub(crate) fn decode_jwt_user_id(token: &str, now: i64) -> Result<String, JwtError> {
+    let mut parts = token.trim().split('.');
+
+    let payload = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(_), Some(payload), Some(_), None) if !payload.is_empty() => payload,
+        _ => return Err(JwtError::Malformed("expected three segments".to_string())),
+    };
+
+    let bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|err| JwtError::Malformed(format!("invalid base64: {}", err)))?;
+
+    let claims: Value = serde_json::from_slice(&bytes)
+        .map_err(|err| JwtError::Malformed(format!("invalid claims: {}", err)))?;
+
+    if let Some(exp) = claims.get("exp").and_then(Value::as_i64) {
+        if exp <= now {
+            return Err(JwtError::Expired { exp });
+        }
+    }
+
+    USER_ID_CLAIMS
+        .iter()
+        .find_map(|key| match claims.get(*key)? {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .ok_or_else(|| JwtError::Malformed("no user id claim".to_string()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityStatus {
+    // 尚未检查，或任一侧身份无法确定（不阻塞写操作）
+    Unknown,
+    Match,
+    Mismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionIdentity {
+    pub moetran_user_id: Option<String>,
+    pub moetran_user_name: Option<String>,
+    pub poprako_user_id: Option<String>,
+    pub status: IdentityStatus,
+    // 身份无法确定时的原因（如 token 已过期、无法解析）
+    pub reason: Option<String>,
+    // 用户已明确确认继续使用不一致的身份
+    pub confirmed: bool,
+    pub checked_at: Option<i64>,
+}
+
+const UNCHECKED: SessionIdentity = SessionIdentity {
+    moetran_user_id: None,
+    moetran_user_name: None,
+    poprako_user_id: None,
+    status: IdentityStatus::Unknown,
+    reason: None,
+    confirmed: false,
+    checked_at: None,
+};
+
+static IDENTITY: RwLock<SessionIdentity> = RwLock::new(UNCHECKED);
+
+pub(crate) fn current_identity() -> SessionIdentity {
+    match IDENTITY.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+fn store_identity(identity: SessionIdentity) {
+    match IDENTITY.write() {
+        Ok(mut guard) => *guard = identity,
+        Err(poisoned) => *poisoned.into_inner() = identity,
+    }
+}
+
+// 由 http 层在发出 PopRaKo 写请求前调用：身份不一致且未确认时返回错误体（JSON）
+pub(crate) fn ensure_poprako_writable() -> Result<(), String> {
+    let identity = current_identity();
+
+    if identity.status != IdentityStatus::Mismatch || identity.confirmed {
+        return Ok(());
+    }
+
+    let body = serde_json::json!({
+        "code": "identity_mismatch",
+        "message": "Moetran 账号与 PopRaKo 账号不一致，请重新同步或确认后再操作",
+        "identity": identity,
+    });
+
+    Err(body.to_string())
+}
+
+// token 变化后旧的检查结果失效（重新登录 / 同步即视为修复），等待下一次检查
+pub(crate) fn reset_identity() {
+    store_identity(UNCHECKED);
+}
+
+async fn resolve_identity() -> SessionIdentity {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    let mut identity = SessionIdentity {
+        checked_at: Some(now),
+        ..UNCHECKED
+    };
+
+    // 先确保 token 已从数据库载入内存缓存
+    match get_moetran_token().await {
+        Ok(Some(_)) => match moetran_get::<ResUser>("user/info", None).await {
+            Ok(user) => {
+                identity.moetran_user_id = Some(user.id);
+                identity.moetran_user_name = Some(user.name);
+            }
+            Err(err) => identity.reason = Some(format!("无法获取 Moetran 用户信息: {}", err)),
+        },
+        Ok(None) => identity.reason = Some("未登录 Moetran".to_string()),
+        Err(err) => identity.reason = Some(err),
+    }
+
+    match get_poprako_token().await {
+        Ok(Some(token)) => match decode_jwt_user_id(&token, now) {
+            Ok(user_id) => identity.poprako_user_id = Some(user_id),
+            Err(err) => identity.reason = Some(format!("无法识别 PopRaKo token: {}", err)),
+        },
+        Ok(None) => identity.reason = Some("未同步 PopRaKo".to_string()),
+        Err(err) => identity.reason = Some(err),
+    }
+
+    if let (Some(moetran), Some(poprako)) = (&identity.moetran_user_id, &identity.poprako_user_id) {
+        identity.status = if moetran == poprako {
+            IdentityStatus::Match
+        } else {
+            IdentityStatus::Mismatch
+        };
+    }
+
+    identity
+}
+
+// 执行一次检查并更新状态；同一对身份已被确认过时保留确认
+pub(crate) async fn refresh_identity(app: &AppHandle) -> SessionIdentity {
+    let previous = current_identity();
+    let mut identity = resolve_identity().await;
+
+    identity.confirmed = previous.confirmed
+        && previous.moetran_user_id == identity.moetran_user_id
+        && previous.poprako_user_id == identity.poprako_user_id;
+
+    store_identity(identity.clone());
+
+    if identity.status == IdentityStatus::Mismatch && !identity.confirmed {
+        tracing::warn!(
+            moetran_user_id = ?identity.moetran_user_id,
+            poprako_user_id = ?identity.poprako_user_id,
+            "session.identity.mismatch"
+        );
+
+        emit_event(app, IdentityMismatch(identity.clone()));
+    }
+
+    identity
+}
+
+// 登录或同步完成后由前端调用；启动时也会自动执行一次
+#[tauri::command]
+pub async fn check_session_identity(app: AppHandle) -> Result<SessionIdentity, String> {
+    tracing::info!("session.identity.check.start");
+
+    let mut defer = WarnDefer::new("session.identity.check");
+
+    let identity = refresh_identity(&app).await;
+
+    tracing::info!(status = ?identity.status, "session.identity.check.ok");
+
+    defer.success();
+
+    Ok(identity)
+}
+
+// 返回最近一次检查的结果（不发请求），供账号页展示
+#[tauri::command]
+pub async fn get_session_identity() -> Result<SessionIdentity, String> {
+    Ok(current_identity())
+}
+
+// 用户明确确认继续使用当前（不一致的）身份，解除写操作拦截
+#[tauri::command]
+pub async fn confirm_session_identity() -> Result<SessionIdentity, String> {
+    let mut identity = current_identity();
+
+    if identity.status != IdentityStatus::Mismatch {
+        return Err("当前身份无需确认".to_string());
+    }
+
+    identity.confirmed = true;
+    store_identity(identity.clone());
+
+    tracing::info!(
+        moetran_user_id = ?identity.moetran_user_id,
+        poprako_user_id = ?identity.poprako_user_id,
+        "session.identity.confirmed"
+    );
+
+    Ok(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        test_support::{MockBackends, TEST_MOETRAN_TOKEN},
+        token,
+    };
+
+    fn jwt(claims: Value) -> String {
+        let payload = general_purpose::URL_SAFE_NO_PAD.encode(claims.to_string());
+        format!("eyJhbGciOiJIUzI1NiJ9.{}.signature", payload)
+    }
+
+    #[test]
+    fn decodes_user_id_from_common_claims() {
+        assert_eq!(
+            decode_jwt_user_id(&jwt(json!({ "sub": "u-1", "uid": "u-2" })), 0).unwrap(),
+            "u-2"
+        );
+        assert_eq!(
+            decode_jwt_user_id(&jwt(json!({ "id": 42, "exp": 200 })), 100).unwrap(),
+            "42"
+        );
+        assert_eq!(
+            decode_jwt_user_id(&format!(" {}= ", jwt(json!({ "user_id": "u-3" }))), 0).unwrap(),
+            "u-3"
+        );
+    }
+
+    #[test]
+    fn rejects_expired_or_malformed_tokens() {
+        assert_eq!(
+            decode_jwt_user_id(&jwt(json!({ "sub": "u-1", "exp": 100 })), 100),
+            Err(JwtError::Expired { exp: 100 })
+        );
+
+        for token in [
+            "not-a-jwt".to_string(),
+            "a.b.c.d".to_string(),
+            "a..c".to_string(),
+            "a.!!!.c".to_string(),
+            jwt(json!({ "sub": "" })),
+            jwt(json!({ "name": "someone" })),
+        ] {
+            assert!(
+                matches!(decode_jwt_user_id(&token, 0), Err(JwtError::Malformed(_))),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn mismatched_identity_blocks_writes_until_confirmed() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "moetran-user",
+                "name": "akira",
+                "has_avatar": false,
+                "avatar": "",
+            })))
+            .mount(&backends.moetran)
+            .await;
+
+        let other = jwt(json!({ "sub": "someone-else" }));
+        token::use_demo_tokens(Some((TEST_MOETRAN_TOKEN, &other)));
+
+        let identity = resolve_identity().await;
+        assert_eq!(identity.status, IdentityStatus::Mismatch);
+        assert_eq!(identity.moetran_user_name.as_deref(), Some("akira"));
+        assert_eq!(identity.poprako_user_id.as_deref(), Some("someone-else"));
+
+        store_identity(identity);
+        let blocked = ensure_poprako_writable().unwrap_err();
+        assert!(blocked.contains("identity_mismatch"));
+
+        let confirmed = confirm_session_identity().await.unwrap();
+        assert!(confirmed.confirmed);
+        assert!(ensure_poprako_writable().is_ok());
+
+        // 重置后不再拦截，也无需确认
+        reset_identity();
+        assert!(ensure_poprako_writable().is_ok());
+        assert!(confirm_session_identity().await.is_err());
+
+        let same = jwt(json!({ "user_id": "moetran-user" }));
+        token::use_demo_tokens(Some((TEST_MOETRAN_TOKEN, &same)));
+        assert_eq!(resolve_identity().await.status, IdentityStatus::Match);
+    }
+}
@@ -0,0 +1,309 @@
+// 批量删除页面上的 source（如 OCR 导入的大量无用标记）：先拉取该页全部 source 校验归属，
+// dry_run 时只返回影响；执行时按固定间隔逐个删除，删除前写入本地回收站快照，部分失败时报告剩余 id
+use std::{collections::HashSet, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    error::AppError,
+    impact_check::{check_delete_impact, DeleteImpact, ImpactEntity},
+    project::{delete_source_remote, load_page_sources, GetPageSourcesReq, MoetranSource},
+    storage::{source_recycle, LOCAL_STORAGE},
+};
+
+// 相邻两次删除之间的间隔，避免瞬间打满 Moetran
+const BATCH_DELETE_INTERVAL: Duration = Duration::from_millis(250);
+// 后端要求等待（Retry-After）不超过该值时等待后重试一次，否则中止剩余删除
+const MAX_RETRY_AFTER_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceDeleteFailure {
+    pub source_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchDeleteReport {
+    pub file_id: String,
+    pub dry_run: bool,
+    // 本次选中的 source（按页面顺序）
+    pub selected: Vec<String>,
+    // 每个选中 source 的删除影响（会丢失的翻译数）
+    pub impacts: Vec<DeleteImpact>,
+    pub deleted: Vec<String>,
+    pub failed: Vec<SourceDeleteFailure>,
+    // 未删除的 id（失败 + 中止后未执行的）
+    pub remaining: Vec<String>,
+}
+
+async fn snapshot_source(file_id: &str, target_id: &str, source: &MoetranSource) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let snapshot = match serde_json::to_string(source) {
+        Ok(json) => json,
+        Err(err) => {
+            tracing::warn!(source_id = %source.id, %err, "moetran.sources.batch_delete.snapshot_failed");
+            return;
+        }
+    };
+
+    if let Err(err) = source_recycle::save_source_snapshot(
+        storage.pool(),
+        &source.id,
+        file_id,
+        target_id,
+        &snapshot,
+    )
+    .await
+    {
+        tracing::warn!(source_id = %source.id, error = %err, "moetran.sources.batch_delete.snapshot_failed");
+    }
+}
+
+// 删除单个 source；后端要求短暂等待时等待后重试一次
+async fn delete_with_backoff(source_id: &str) -> Result<(), AppError> {
+    match delete_source_remote(source_id).await {
+        Err(AppError::ServiceUnavailable {
+            retry_after: Some(secs),
+            ..
+        }) if secs <= MAX_RETRY_AFTER_SECS => {
+            tracing::info!(%source_id, secs, "moetran.sources.batch_delete.backoff");
+
+            tokio::time::sleep(Duration::from_secs(secs)).await;
+
+            delete_source_remote(source_id).await
+        }
+        other => other,
+    }
+}
+
+// 批量删除的公共流程：拉取该页 source，由 select 选出要删除的 source
+async fn delete_sources_core(
+    file_id: &str,
+    target_id: &str,
+    dry_run: bool,
+    select: impl FnOnce(&[MoetranSource]) -> Result<Vec<MoetranSource>, String>,
+) -> Result<BatchDeleteReport, String> {
+    // 同时刷新本地的翻译数记录，供影响检查使用
+    let page = load_page_sources(&GetPageSourcesReq {
+        file_id: file_id.into(),
+        target_id: target_id.into(),
+        reading_direction: None,
+        allow_stale: false,
+        mark_viewed: false,
+        project_id: None,
+    })
+    .await?;
+
+    let selected = select(&page)?;
+
+    let mut impacts = Vec::with_capacity(selected.len());
+    for source in &selected {
+        let entity = ImpactEntity::Source {
+            source_id: source.id.to_string(),
+        };
+        impacts.push(check_delete_impact(&entity).await);
+    }
+
+    let mut report = BatchDeleteReport {
+        file_id: file_id.to_string(),
+        dry_run,
+        selected: selected
+            .iter()
+            .map(|source| source.id.to_string())
+            .collect(),
+        impacts,
+        deleted: vec![],
+        failed: vec![],
+        remaining: vec![],
+    };
+
+    if dry_run {
+        report.remaining = report.selected.clone();
+        return Ok(report);
+    }
+
+    let mut aborted = false;
+
+    for (index, source) in selected.iter().enumerate() {
+        if aborted {
+            report.remaining.push(source.id.to_string());
+            continue;
+        }
+
+        if index > 0 {
+            tokio::time::sleep(BATCH_DELETE_INTERVAL).await;
+        }
+
+        snapshot_source(file_id, target_id, source).await;
+
+        match delete_with_backoff(&source.id).await {
+            Ok(()) => report.deleted.push(source.id.to_string()),
+            Err(err) => {
+                tracing::warn!(source_id = %source.id, error = %err, "moetran.sources.batch_delete.item_failed");
+
+                // 后端不可用（或已切换到离线模式）时继续删除只会连续失败，中止剩余部分
+                aborted = err.is_service_unavailable()
+                    || err.is_offline()
+                    || matches!(err, AppError::Network(_));
+
+                report.failed.push(SourceDeleteFailure {
+                    source_id: source.id.to_string(),
+                    message: err.to_string(),
+                });
+                report.remaining.push(source.id.to_string());
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteSourcesBatchReq {
+    pub file_id: String,
+    pub target_id: String,
+    pub source_ids: Vec<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[tauri::command]
+pub async fn delete_sources_batch(
+    payload: DeleteSourcesBatchReq,
+) -> Result<BatchDeleteReport, String> {
+    tracing::info!(
+        file_id = %payload.file_id,
+        count = payload.source_ids.len(),
+        dry_run = payload.dry_run,
+        "moetran.sources.batch_delete.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.sources.batch_delete");
+
+    let requested: HashSet<&str> = payload.source_ids.iter().map(String::as_str).collect();
+
+    let report = delete_sources_core(
+        &payload.file_id,
+        &payload.target_id,
+        payload.dry_run,
+        |page| {
+            let on_page: HashSet<&str> = page.iter().map(|source| source.id.as_str()).collect();
+
+            // 任一 id 不属于该页时整体拒绝，防止误删其他页面的 source
+            let foreign: Vec<&str> = payload
+                .source_ids
+                .iter()
+                .map(String::as_str)
+                .filter(|id| !on_page.contains(id))
+                .collect();
+
+            if !foreign.is_empty() {
+                return Err(format!(
+                    "以下 source 不属于该页面，已取消删除: {}",
+                    foreign.join(", ")
+                ));
+            }
+
+            Ok(page
+                .iter()
+                .filter(|source| requested.contains(source.id.as_str()))
+                .cloned()
+                .collect())
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        file_id = %payload.file_id,
+        deleted = report.deleted.len(),
+        remaining = report.remaining.len(),
+        "moetran.sources.batch_delete.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+// 页面上的矩形区域，坐标与 source 的 x / y 一致（相对图片宽高的 0..1）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct SourceRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl SourceRegion {
+    fn contains(&self, source: &MoetranSource) -> bool {
+        // 允许反向拖拽得到的负宽高
+        let (left, right) = if self.width >= 0.0 {
+            (self.x, self.x + self.width)
+        } else {
+            (self.x + self.width, self.x)
+        };
+        let (top, bottom) = if self.height >= 0.0 {
+            (self.y, self.y + self.height)
+        } else {
+            (self.y + self.height, self.y)
+        };
+
+        (left..=right).contains(&source.x) && (top..=bottom).contains(&source.y)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteSourcesInRegionReq {
+    pub file_id: String,
+    pub target_id: String,
+    pub rect: SourceRegion,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// 删除框选区域内的全部 source（“删除我圈中的所有标记”）
+#[tauri::command]
+pub async fn delete_sources_in_region(
+    payload: DeleteSourcesInRegionReq,
+) -> Result<BatchDeleteReport, String> {
+    tracing::info!(
+        file_id = %payload.file_id,
+        rect = ?payload.rect,
+        dry_run = payload.dry_run,
+        "moetran.sources.region_delete.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.sources.region_delete");
+
+    let rect = payload.rect;
+
+    let report = delete_sources_core(
+        &payload.file_id,
+        &payload.target_id,
+        payload.dry_run,
+        |page| {
+            Ok(page
+                .iter()
+                .filter(|source| rect.contains(source))
+                .cloned()
+                .collect())
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        file_id = %payload.file_id,
+        selected = report.selected.len(),
+        deleted = report.deleted.len(),
+        remaining = report.remaining.len(),
+        "moetran.sources.region_delete.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
@@ -0,0 +1,325 @@
+// 页面 source 拆分为两部分：与 target 无关的几何信息（id、坐标、位置类型），以及随 target 变化的翻译覆盖层。
+// 切换 target 时前端沿用已缓存的几何信息先行渲染，只需等待覆盖层。
+// Moetran 没有只返回翻译的接口，覆盖层仍来自 files/{id}/sources 的完整响应，但返回给前端的数据更少，
+// 几何信息只在与缓存不同时才随覆盖层一起返回
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    position_type::PositionType,
+    project::{load_page_sources, GetPageSourcesReq, MoetranSource, MoetranTranslation},
+};
+
+// 最多缓存的文件数，超过时整体清空
+const GEOMETRY_CACHE_MAX: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceGeometry {
+    pub id: String,
+    pub x: f64,
+    pub y: f64,
+    pub position_type: PositionType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationOverlay {
+    pub source_id: String,
+    pub my_translation: Option<MoetranTranslation>,
+    pub translations: Vec<MoetranTranslation>,
+    pub has_draft: bool,
+}
+
+// file_id -> 页面顺序的几何信息
+static GEOMETRY: LazyLock<Mutex<HashMap<String, Vec<SourceGeometry>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn split_sources(
+    sources: &[MoetranSource],
+) -> (Vec<SourceGeometry>, Vec<TranslationOverlay>) {
+    sources
+        .iter()
+        .map(|source| {
+            (
+                SourceGeometry {
+                    id: source.id.to_string(),
+                    x: source.x,
+                    y: source.y,
+                    position_type: source.position_type,
+                },
+                TranslationOverlay {
+                    source_id: source.id.to_string(),
+                    my_translation: source.my_translation.clone(),
+                    translations: source.translations.clone(),
+                    has_draft: source.has_draft,
+                },
+            )
+        })
+        .unzip()
+}
+
+// 记录文件最新的几何信息；返回是否与之前缓存的不同（没有缓存也视为不同）
+pub(crate) fn remember_geometry(file_id: &str, sources: &[MoetranSource]) -> bool {
+    let (geometry, _) = split_sources(sources);
+
+    let Ok(mut guard) = GEOMETRY.lock() else {
+        return true;
+    };
+
+    if guard.get(file_id) == Some(&geometry) {
+        return false;
+    }
+
+    if guard.len() >= GEOMETRY_CACHE_MAX {
+        guard.clear();
+    }
+
+    guard.insert(file_id.to_string(), geometry);
+
+    true
+}
+
+// source 被删除 / 位置修改后调用，下次打开时重新获取
+pub(crate) fn forget_geometry(file_id: &str) {
+    if let Ok(mut guard) = GEOMETRY.lock() {
+        guard.remove(file_id);
+    }
+}
+
+// 按 source id 找到所在文件并使其几何缓存失效
+pub(crate) fn forget_geometry_of_source(source_id: &str) {
+    if let Ok(mut guard) = GEOMETRY.lock() {
+        guard.retain(|_, geometry| !geometry.iter().any(|g| g.id == source_id));
+    }
+}
+
+pub(crate) fn cached_geometry(file_id: &str) -> Option<Vec<SourceGeometry>> {
+    GEOMETRY.lock().ok()?.get(file_id).cloned()
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergedSources {
+    pub sources: Vec<MoetranSource>,
+    // 有几何信息但覆盖层中没有（覆盖层获取之后新建的 source），按无翻译保留
+    pub missing_overlay: Vec<String>,
+    // 覆盖层中有但几何信息中没有（几何信息过期，或 source 已被删除），无法定位，丢弃
+    pub orphan_overlay: Vec<String>,
+}
+
+// 合并规则：
+// 1. 以几何信息的顺序为准；
+// 2. 几何信息中有、覆盖层中没有的 source 保留，翻译为空；
+// 3. 覆盖层中有、几何信息中没有的条目丢弃并报告，调用方应重新获取几何信息；
+// 4. 覆盖层中同一 source 出现多次时取最后一条
+pub(crate) fn merge_overlay(
+    geometry: &[SourceGeometry],
+    overlay: Vec<TranslationOverlay>,
+) -> MergedSources {
+    let known: HashSet<&str> = geometry.iter().map(|g| g.id.as_str()).collect();
+
+    let mut merged = MergedSources::default();
+    let mut by_source: HashMap<String, TranslationOverlay> = HashMap::new();
+
+    for item in overlay {
+        if known.contains(item.source_id.as_str()) {
+            by_source.insert(item.source_id.clone(), item);
+        } else if !merged.orphan_overlay.contains(&item.source_id) {
+            merged.orphan_overlay.push(item.source_id);
+        }
+    }
+
+    for g in geometry {
+        let (my_translation, translations, has_draft) = match by_source.remove(&g.id) {
+            Some(item) => (item.my_translation, item.translations, item.has_draft),
+            None => {
+                merged.missing_overlay.push(g.id.clone());
+                (None, vec![], false)
+            }
+        };
+
+        merged.sources.push(MoetranSource {
+            id: g.id.clone().into(),
+            x: g.x,
+            y: g.y,
+            position_type: g.position_type,
+            my_translation,
+            translations,
+            has_draft,
+            stale: None,
+        });
+    }
+
+    merged
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetPageGeometryReq {
+    pub file_id: String,
+}
+
+// 已缓存的几何信息（不发请求）；没有缓存时返回 None，前端应改用 get_page_sources
+#[tauri::command]
+pub async fn get_page_geometry(
+    payload: GetPageGeometryReq,
+) -> Result<Option<Vec<SourceGeometry>>, String> {
+    let geometry = cached_geometry(&payload.file_id);
+
+    tracing::debug!(
+        file_id = %payload.file_id,
+        cached = geometry.is_some(),
+        "moetran.sources.geometry.get"
+    );
+
+    Ok(geometry)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetPageTranslationsReq {
+    pub file_id: String,
+    pub target_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PageTranslations {
+    pub file_id: String,
+    pub target_id: String,
+    pub overlay: Vec<TranslationOverlay>,
+    // 仅当几何信息与获取前的缓存不同时返回，前端应以此替换本地的几何信息后再合并
+    pub geometry: Option<Vec<SourceGeometry>>,
+}
+
+// 获取某个 target 的翻译覆盖层（切换 target 时使用）
+#[tauri::command]
+pub async fn get_page_translations(
+    payload: GetPageTranslationsReq,
+) -> Result<PageTranslations, String> {
+    tracing::info!(
+        file_id = %payload.file_id,
+        target_id = %payload.target_id,
+        "moetran.sources.overlay.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.sources.overlay");
+
+    let before = cached_geometry(&payload.file_id);
+
+    // 拉取时已更新几何缓存
+    let sources = load_page_sources(&GetPageSourcesReq {
+        file_id: payload.file_id.clone().into(),
+        target_id: payload.target_id.clone().into(),
+        reading_direction: None,
+        allow_stale: false,
+        mark_viewed: false,
+        project_id: None,
+    })
+    .await?;
+
+    let (geometry, overlay) = split_sources(&sources);
+
+    let geometry_changed = before.as_ref() != Some(&geometry);
+
+    tracing::info!(
+        file_id = %payload.file_id,
+        count = overlay.len(),
+        geometry_changed,
+        "moetran.sources.overlay.ok"
+    );
+
+    defer.success();
+
+    Ok(PageTranslations {
+        file_id: payload.file_id,
+        target_id: payload.target_id,
+        overlay,
+        geometry: geometry_changed.then_some(geometry),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn page(ids: &[(&str, f64)]) -> Vec<MoetranSource> {
+        ids.iter()
+            .map(|(id, x)| {
+                serde_json::from_value(json!({
+                    "id": id,
+                    "x": x,
+                    "y": 0.5,
+                    "position_type": 2,
+                    "my_translation": null,
+                    "translations": [{
+                        "id": format!("{}-t", id),
+                        "content": format!("{} 的译文", id),
+                        "proofread_content": null,
+                        "selected": false,
+                    }],
+                    "has_draft": true,
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn ids(sources: &[MoetranSource]) -> Vec<String> {
+        sources.iter().map(|source| source.id.to_string()).collect()
+    }
+
+    #[test]
+    fn split_and_merge_round_trip() {
+        let sources = page(&[("s1", 0.1), ("s2", 0.2)]);
+        let (geometry, overlay) = split_sources(&sources);
+
+        let merged = merge_overlay(&geometry, overlay);
+
+        assert_eq!(
+            serde_json::to_value(&merged.sources).unwrap(),
+            serde_json::to_value(&sources).unwrap()
+        );
+        assert!(merged.missing_overlay.is_empty());
+        assert!(merged.orphan_overlay.is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_geometry_order_and_reports_mismatches() {
+        let (geometry, _) = split_sources(&page(&[("s1", 0.1), ("s2", 0.2), ("s3", 0.3)]));
+        let (_, mut overlay) = split_sources(&page(&[("s3", 0.0), ("gone", 0.0), ("s1", 0.0)]));
+
+        // 同一 source 出现多次时取最后一条
+        let mut later = overlay[0].clone();
+        later.has_draft = false;
+        overlay.push(later);
+        overlay.push(overlay[1].clone());
+
+        let merged = merge_overlay(&geometry, overlay);
+
+        assert_eq!(ids(&merged.sources), ["s1", "s2", "s3"]);
+        assert_eq!(merged.missing_overlay, ["s2"]);
+        assert_eq!(merged.orphan_overlay, ["gone"]);
+
+        assert!(merged.sources[1].translations.is_empty());
+        assert!(!merged.sources[2].has_draft);
+        assert_eq!(merged.sources[2].x, 0.3);
+    }
+
+    #[test]
+    fn geometry_cache_detects_changes_and_forgets_by_source() {
+        let file_id = "overlay-file";
+
+        assert!(remember_geometry(file_id, &page(&[("ov-1", 0.1)])));
+        // 只有翻译变化时几何信息不变
+        let mut retranslated = page(&[("ov-1", 0.1)]);
+        retranslated[0].translations.clear();
+        assert!(!remember_geometry(file_id, &retranslated));
+        assert!(remember_geometry(file_id, &page(&[("ov-1", 0.4)])));
+
+        assert_eq!(cached_geometry(file_id).unwrap()[0].x, 0.4);
+
+        forget_geometry_of_source("ov-1");
+        assert!(cached_geometry(file_id).is_none());
+    }
+}
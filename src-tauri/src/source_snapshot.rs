@@ -0,0 +1,259 @@
+// 页面 source 快照：再次打开页面时先返回上次拉取的 sources（标记 stale），同时在后台拉取最新数据，
+// 内容有变化时通过 "sources-updated" 事件推送。快照以 MessagePack 存入 SQLite，按内容哈希判断是否变化
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    project::{get_project_files, GetProjectFilesReq, MoetranSource},
+    storage::{source_snapshots, LOCAL_STORAGE},
+};
+
+// source id / translation id -> file id，写操作后据此找到需要失效的快照
+static SNAPSHOT_FILES: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const SNAPSHOT_FILES_MAX: usize = 20_000;
+
+// FNV-1a（64 位）：结果跨版本稳定，可直接持久化
+fn content_hash(bytes: &[u8]) -> i64 {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+
+    hash as i64
+}
+
+// 使用带字段名的编码，兼容 DTO 中的 serde(default) / skip_serializing_if
+fn encode(sources: &[MoetranSource]) -> Result<Vec<u8>, String> {
+    rmp_serde::to_vec_named(sources).map_err(|err| format!("编码 source 快照失败: {}", err))
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<Vec<MoetranSource>, String> {
+    rmp_serde::from_slice(bytes).map_err(|err| format!("解码 source 快照失败: {}", err))
+}
+
+// 记录页面上 source 与翻译所属的文件
+pub(crate) fn remember_snapshot_files(file_id: &str, sources: &[MoetranSource]) {
+    if let Ok(mut guard) = SNAPSHOT_FILES.lock() {
+        if guard.len() >= SNAPSHOT_FILES_MAX {
+            guard.clear();
+        }
+
+        for source in sources {
+            guard.insert(source.id.to_string(), file_id.to_string());
+
+            for translation in source.my_translation.iter().chain(&source.translations) {
+                guard.insert(translation.id.to_string(), file_id.to_string());
+            }
+        }
+    }
+}
+
+// 读取快照；不存在或无法解码时返回 None（旧格式视同没有快照）
+pub(crate) async fn load_snapshot(file_id: &str, target_id: &str) -> Option<Vec<MoetranSource>> {
+    let storage = LOCAL_STORAGE.get()?;
+
+    let row = match source_snapshots::load_source_snapshot(storage.pool(), file_id, target_id).await
+    {
+        Ok(row) => row?,
+        Err(err) => {
+            tracing::warn!(%file_id, error = %err, "moetran.sources.snapshot.load_failed");
+            return None;
+        }
+    };
+
+    match decode(&row.payload) {
+        Ok(sources) => {
+            tracing::debug!(%file_id, fetched_at = row.fetched_at, "moetran.sources.snapshot.hit");
+            Some(sources)
+        }
+        Err(err) => {
+            tracing::warn!(%file_id, error = %err, "moetran.sources.snapshot.decode_failed");
+            None
+        }
+    }
+}
+
+// 保存最新拉取的 sources，返回内容是否与原快照不同（无原快照也视为变化）
+pub(crate) async fn store_snapshot(
+    file_id: &str,
+    target_id: &str,
+    sources: &[MoetranSource],
+) -> bool {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return true;
+    };
+
+    let payload = match encode(sources) {
+        Ok(payload) => payload,
+        Err(err) => {
+            tracing::warn!(%file_id, error = %err, "moetran.sources.snapshot.encode_failed");
+            return true;
+        }
+    };
+
+    let hash = content_hash(&payload);
+
+    // 记录 MessagePack 与 JSON 的体积对比（仅 debug 日志开启时计算）
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        tracing::debug!(
+            %file_id,
+            msgpack_bytes = payload.len(),
+            json_bytes = serde_json::to_vec(sources).map(|json| json.len()).unwrap_or(0),
+            "moetran.sources.snapshot.size"
+        );
+    }
+
+    let previous = source_snapshots::load_source_snapshot(storage.pool(), file_id, target_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|row| row.content_hash);
+
+    if previous == Some(hash) {
+        return false;
+    }
+
+    if let Err(err) =
+        source_snapshots::save_source_snapshot(storage.pool(), file_id, target_id, &payload, hash)
+            .await
+    {
+        tracing::warn!(%file_id, error = %err, "moetran.sources.snapshot.save_failed");
+    }
+
+    true
+}
+
+pub(crate) async fn invalidate_file_snapshots(file_id: &str) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    if let Err(err) =
+        source_snapshots::delete_source_snapshots_for_file(storage.pool(), file_id).await
+    {
+        tracing::warn!(%file_id, error = %err, "moetran.sources.snapshot.invalidate_failed");
+    }
+}
+
+// source id 或 translation id 所在的文件（仅限加载过的页面）
+pub(crate) fn snapshot_file_of(id: &str) -> Option<String> {
+    SNAPSHOT_FILES
+        .lock()
+        .ok()
+        .and_then(|guard| guard.get(id).cloned())
+}
+
+// 按 source id 或 translation id 使所在页面的快照失效（未加载过的页面无快照可失效）
+pub(crate) async fn invalidate_snapshots_for(id: &str) {
+    if let Some(file_id) = snapshot_file_of(id) {
+        invalidate_file_snapshots(&file_id).await;
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClearSourcesSnapshotsReq {
+    pub project_id: String,
+}
+
+// 清除项目下所有页面的 source 快照，返回删除条数
+#[tauri::command]
+pub async fn clear_sources_snapshots(payload: ClearSourcesSnapshotsReq) -> Result<u64, String> {
+    tracing::info!(project_id = %payload.project_id, "moetran.sources.snapshot.clear.start");
+
+    let mut defer = WarnDefer::new("moetran.sources.snapshot.clear");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let files = get_project_files(GetProjectFilesReq {
+        project_id: payload.project_id.clone().into(),
+        target_id: None,
+        sort: None,
+    })
+    .await?;
+
+    let mut removed = 0;
+
+    for file in &files {
+        removed +=
+            source_snapshots::delete_source_snapshots_for_file(storage.pool(), &file.id).await?;
+    }
+
+    tracing::info!(project_id = %payload.project_id, removed, "moetran.sources.snapshot.clear.ok");
+
+    defer.success();
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::local_storage;
+
+    fn sources(content: &str) -> Vec<MoetranSource> {
+        serde_json::from_value(json!([{
+            "id": "snap-s1",
+            "x": 0.25,
+            "y": 0.5,
+            "position_type": 2,
+            "my_translation": null,
+            "translations": [{
+                "id": "snap-t1",
+                "content": content,
+                "proofread_content": null,
+                "selected": true,
+            }],
+            "has_draft": true,
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn content_hash_is_stable_fnv1a() {
+        assert_eq!(content_hash(b"") as u64, 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a") as u64, 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(content_hash(b"ab"), content_hash(b"ba"));
+    }
+
+    #[test]
+    fn snapshot_encoding_keeps_client_fields() {
+        let original = sources("译文");
+        let decoded = decode(&encode(&original).unwrap()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+        assert!(decoded[0].has_draft);
+        assert!(decode(b"\xc1not msgpack").is_err());
+    }
+
+    #[tokio::test]
+    async fn store_reports_changes_and_writes_invalidate_the_page() {
+        local_storage().await;
+
+        assert!(store_snapshot("snap-file", "snap-target", &sources("一")).await);
+        assert!(!store_snapshot("snap-file", "snap-target", &sources("一")).await);
+        assert!(store_snapshot("snap-file", "snap-target", &sources("二")).await);
+
+        let loaded = load_snapshot("snap-file", "snap-target").await.unwrap();
+        assert_eq!(loaded[0].translations[0].content, "二");
+
+        // 按翻译 id 找到所在页面并使其快照失效
+        remember_snapshot_files("snap-file", &loaded);
+        assert_eq!(snapshot_file_of("snap-t1").as_deref(), Some("snap-file"));
+
+        invalidate_snapshots_for("snap-t1").await;
+        assert!(load_snapshot("snap-file", "snap-target").await.is_none());
+    }
+}
@@ -0,0 +1,380 @@
+// 汉化组自定义的阶段状态标签：PopRaKo 只返回 0/1/2 的状态整数，显示用的文字与颜色由各组自行约定。
+// 设置表中只保存与内置默认不同的条目（键为 "status_labels.<team_id>"），读取时与默认合并；
+// enriched 项目列表按列表中出现的汉化组附带解析后的标签
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    project::ResProjectEnriched,
+    publish::{ProjStage, STAGE_STATUSES, STAGE_STATUS_COMPLETED, STAGE_STATUS_WIP},
+    storage::{settings, LOCAL_STORAGE},
+};
+
+// 设置表中的键前缀，完整键为 "status_labels.<team_id>"
+pub(crate) const LABELS_KEY_PREFIX: &str = "status_labels.";
+
+// 标签最多字符数（按字符而非字节计）
+const MAX_LABEL_CHARS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusLabel {
+    pub stage: ProjStage,
+    // PopRaKo 状态整数，见 publish::STAGE_STATUSES
+    pub status: i32,
+    pub label: String,
+    // #RGB 或 #RRGGBB
+    pub color: String,
+}
+
+fn default_label(stage: ProjStage, status: i32) -> StatusLabel {
+    let action = match stage {
+        ProjStage::Translating => "翻译",
+        ProjStage::Proofreading => "校对",
+        ProjStage::Typesetting => "嵌字",
+        ProjStage::Reviewing => "监修",
+    };
+
+    let (label, color) = match status {
+        STAGE_STATUS_COMPLETED => (format!("已{}", action), "#43a047"),
+        STAGE_STATUS_WIP => (format!("{}中", action), "#1e88e5"),
+        _ => (format!("待{}", action), "#9e9e9e"),
+    };
+
+    StatusLabel {
+        stage,
+        status,
+        label,
+        color: color.to_string(),
+    }
+}
+
+// 内置默认标签：每个阶段的每个状态各一条
+pub(crate) fn default_labels() -> Vec<StatusLabel> {
+    ProjStage::ALL
+        .into_iter()
+        .flat_map(|stage| {
+            STAGE_STATUSES
+                .into_iter()
+                .map(move |status| default_label(stage, status))
+        })
+        .collect()
+}
+
+fn is_hex_color(color: &str) -> bool {
+    let Some(digits) = color.strip_prefix('#') else {
+        return false;
+    };
+
+    matches!(digits.len(), 3 | 6) && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// 检查状态值、标签长度、颜色格式，以及同一 (stage, status) 不重复
+pub(crate) fn validate_labels(labels: &[StatusLabel]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+
+    for entry in labels {
+        let stage = entry.stage.as_str();
+
+        if !STAGE_STATUSES.contains(&entry.status) {
+            return Err(format!("{} 的状态值 {} 无效", stage, entry.status));
+        }
+
+        let label = entry.label.trim();
+
+        if label.is_empty() {
+            return Err(format!("{} 状态 {} 的标签不能为空", stage, entry.status));
+        }
+
+        if label.chars().count() > MAX_LABEL_CHARS {
+            return Err(format!(
+                "{} 状态 {} 的标签超过 {} 个字符",
+                stage, entry.status, MAX_LABEL_CHARS
+            ));
+        }
+
+        if !is_hex_color(&entry.color) {
+            return Err(format!(
+                "{} 状态 {} 的颜色 {} 格式错误",
+                stage, entry.status, entry.color
+            ));
+        }
+
+        if !seen.insert((entry.stage, entry.status)) {
+            return Err(format!("{} 状态 {} 重复设置", stage, entry.status));
+        }
+    }
+
+    Ok(())
+}
+
+// 默认标签按自定义条目覆盖，顺序与 default_labels 一致
+fn resolve(overrides: &[StatusLabel]) -> Vec<StatusLabel> {
+    default_labels()
+        .into_iter()
+        .map(|default| {
+            overrides
+                .iter()
+                .find(|entry| entry.stage == default.stage && entry.status == default.status)
+                .cloned()
+                .unwrap_or(default)
+        })
+        .collect()
+}
+
+pub(crate) fn labels_key(team_id: &str) -> String {
+    format!("{}{}", LABELS_KEY_PREFIX, team_id)
+}
+
+// 汉化组保存的自定义条目；未设置时为空
+async fn team_overrides(team_id: &str) -> Result<Vec<StatusLabel>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let Some(raw) = settings::get_setting(storage.pool(), &labels_key(team_id)).await? else {
+        return Ok(vec![]);
+    };
+
+    serde_json::from_str(&raw).map_err(|err| format!("状态标签格式错误: {}", err))
+}
+
+// 保存自定义条目（先校验）；为空时删除，恢复默认
+pub(crate) async fn save_team_overrides(
+    team_id: &str,
+    labels: &[StatusLabel],
+) -> Result<(), String> {
+    validate_labels(labels)?;
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let key = labels_key(team_id);
+
+    if labels.is_empty() {
+        return settings::delete_setting(storage.pool(), &key).await;
+    }
+
+    let raw =
+        serde_json::to_string(labels).map_err(|err| format!("序列化状态标签失败: {}", err))?;
+
+    settings::save_setting(storage.pool(), &key, &raw).await
+}
+
+// 为列表中出现的每个汉化组解析标签；读取失败的组使用默认标签并记录日志
+pub(crate) async fn labels_for_list(
+    list: &[ResProjectEnriched],
+) -> HashMap<String, Vec<StatusLabel>> {
+    let team_ids: HashSet<&str> = list.iter().map(|proj| proj.team.id.as_str()).collect();
+
+    let mut labels = HashMap::new();
+
+    for team_id in team_ids {
+        let overrides = team_overrides(team_id).await.unwrap_or_else(|err| {
+            tracing::warn!(team_id, error = %err, "status_labels.load_failed");
+            vec![]
+        });
+
+        labels.insert(team_id.to_string(), resolve(&overrides));
+    }
+
+    labels
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetStatusLabelsReq {
+    pub team_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusLabelsReply {
+    pub team_id: String,
+    // 与默认合并后的完整标签
+    pub labels: Vec<StatusLabel>,
+    // 该组自定义的条目；为空表示全部使用默认
+    pub customized: Vec<StatusLabel>,
+}
+
+async fn labels_reply(team_id: String) -> Result<StatusLabelsReply, String> {
+    let customized = team_overrides(&team_id).await?;
+
+    Ok(StatusLabelsReply {
+        team_id,
+        labels: resolve(&customized),
+        customized,
+    })
+}
+
+#[tauri::command]
+pub async fn get_status_labels(payload: GetStatusLabelsReq) -> Result<StatusLabelsReply, String> {
+    labels_reply(payload.team_id).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetStatusLabelsReq {
+    pub team_id: String,
+    // 只需包含要修改的条目；为空表示恢复默认
+    #[serde(default)]
+    pub labels: Vec<StatusLabel>,
+}
+
+#[tauri::command]
+pub async fn set_status_labels(payload: SetStatusLabelsReq) -> Result<StatusLabelsReply, String> {
+    tracing::info!(
+        team_id = %payload.team_id,
+        count = payload.labels.len(),
+        "status_labels.set.start"
+    );
+
+    let mut defer = WarnDefer::new("status_labels.set");
+
+    // 与默认相同的条目不保存，避免日后调整默认值时被旧设置遮住
+    let defaults = default_labels();
+    let labels: Vec<StatusLabel> = payload
+        .labels
+        .into_iter()
+        .map(|mut entry| {
+            entry.label = entry.label.trim().to_string();
+            entry
+        })
+        .filter(|entry| !defaults.contains(entry))
+        .collect();
+
+    save_team_overrides(&payload.team_id, &labels).await?;
+
+    let reply = labels_reply(payload.team_id).await?;
+
+    tracing::info!(
+        team_id = %reply.team_id,
+        customized = reply.customized.len(),
+        "status_labels.set.ok"
+    );
+
+    defer.success();
+
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::local_storage;
+
+    fn label(stage: ProjStage, status: i32, text: &str, color: &str) -> StatusLabel {
+        StatusLabel {
+            stage,
+            status,
+            label: text.to_string(),
+            color: color.to_string(),
+        }
+    }
+
+    #[test]
+    fn defaults_cover_every_stage_and_status() {
+        let defaults = default_labels();
+
+        assert_eq!(defaults.len(), ProjStage::ALL.len() * STAGE_STATUSES.len());
+        assert!(validate_labels(&defaults).is_ok());
+        assert!(defaults.contains(&label(
+            ProjStage::Typesetting,
+            STAGE_STATUS_WIP,
+            "嵌字中",
+            "#1e88e5"
+        )));
+    }
+
+    #[test]
+    fn invalid_labels_are_rejected() {
+        let stage = ProjStage::Translating;
+        let ok = label(stage, STAGE_STATUS_WIP, "翻译进行中", "#abc");
+        assert!(validate_labels(std::slice::from_ref(&ok)).is_ok());
+
+        let cases = [
+            (label(stage, 9, "x", "#abc"), "状态值 9 无效"),
+            (label(stage, STAGE_STATUS_WIP, "  ", "#abc"), "不能为空"),
+            (
+                label(stage, STAGE_STATUS_WIP, &"长".repeat(17), "#abc"),
+                "超过 16 个字符",
+            ),
+            (label(stage, STAGE_STATUS_WIP, "x", "#abcd"), "格式错误"),
+            (label(stage, STAGE_STATUS_WIP, "x", "red"), "格式错误"),
+        ];
+
+        for (entry, message) in cases {
+            let error = validate_labels(&[entry]).unwrap_err();
+            assert!(error.contains(message), "{}", error);
+        }
+
+        // 16 个多字节字符按字符计，不超限
+        assert!(
+            validate_labels(&[label(stage, STAGE_STATUS_WIP, &"长".repeat(16), "#abc")]).is_ok()
+        );
+
+        let error = validate_labels(&[ok.clone(), ok]).unwrap_err();
+        assert!(error.contains("重复设置"));
+    }
+
+    #[test]
+    fn overrides_replace_only_their_entry() {
+        let custom = label(
+            ProjStage::Reviewing,
+            STAGE_STATUS_COMPLETED,
+            "审完",
+            "#000000",
+        );
+
+        let resolved = resolve(std::slice::from_ref(&custom));
+        let defaults = default_labels();
+
+        assert_eq!(resolved.len(), defaults.len());
+        for (resolved, default) in resolved.iter().zip(&defaults) {
+            if (default.stage, default.status) == (custom.stage, custom.status) {
+                assert_eq!(resolved, &custom);
+            } else {
+                assert_eq!(resolved, default);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn only_entries_differing_from_defaults_are_saved() {
+        local_storage().await;
+        let team_id = "status-labels-team".to_string();
+
+        let unchanged = default_label(ProjStage::Translating, STAGE_STATUS_WIP);
+        let custom = label(
+            ProjStage::Proofreading,
+            STAGE_STATUS_WIP,
+            " 正在校 ",
+            "#fff",
+        );
+
+        let reply = set_status_labels(SetStatusLabelsReq {
+            team_id: team_id.clone(),
+            labels: vec![unchanged, custom],
+        })
+        .await
+        .unwrap();
+
+        let trimmed = label(ProjStage::Proofreading, STAGE_STATUS_WIP, "正在校", "#fff");
+        assert_eq!(reply.customized, std::slice::from_ref(&trimmed));
+        assert!(reply.labels.contains(&trimmed));
+
+        let reset = set_status_labels(SetStatusLabelsReq {
+            team_id: team_id.clone(),
+            labels: vec![],
+        })
+        .await
+        .unwrap();
+        assert!(reset.customized.is_empty());
+        assert_eq!(reset.labels, default_labels());
+
+        let storage = LOCAL_STORAGE.get().unwrap();
+        let raw = settings::get_setting(storage.pool(), &labels_key(&team_id))
+            .await
+            .unwrap();
+        assert_eq!(raw, None);
+    }
+}
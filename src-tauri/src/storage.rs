@@ -1,9 +1,41 @@
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::Serialize;
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous,
+};
+
+use crate::image_cache::{memory_cache_stats, ImageMemoryCacheStats};
 
 pub mod cache_metadata;
+pub mod cached_project_files;
+pub mod deadlines;
+pub mod file_activity;
+pub mod member_directory;
+pub mod pending_writes;
+pub mod project_cache;
+pub mod project_notes;
+pub mod project_prefs;
+pub mod project_snapshots;
+pub mod publish_records;
+pub mod recent_projects;
+pub mod redraw_tasks;
+pub mod retry_tokens;
+pub mod settings;
+pub mod source_recycle;
+pub mod source_snapshots;
 pub mod token;
+pub mod translation_drafts;
+pub mod usage_stats;
+pub mod verify_queue;
+
+// 同一时刻只有一个写连接能持有锁，连接数不宜过多
+const POOL_MAX_CONNECTIONS: u32 = 4;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct LocalStorage {
     pool: sqlx::SqlitePool,
@@ -25,12 +57,31 @@ impl LocalStorage {
 
         let database_url = format!("sqlite://{}", path.to_string_lossy());
 
-        let pool = sqlx::SqlitePool::connect(&database_url)
+        // WAL 允许读写并发；busy_timeout 让写冲突时等待而不是立即报 "database is locked"
+        let options = SqliteConnectOptions::from_str(&database_url)
+            .map_err(|err| format!("Invalid database url: {}", err))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(POOL_MAX_CONNECTIONS)
+            .connect_with(options)
             .await
             .map_err(|err| format!("Failed to connect to database: {}", err))?;
 
-        token::migrate_token_table(&pool).await?;
-        cache_metadata::migrate_cache_metadata_table(&pool).await?;
+        // 迁移放在同一事务中，中途失败时不会留下只建了一半的表结构
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|err| format!("Failed to begin migration transaction: {}", err))?;
+
+        migrate(&mut tx).await?;
+
+        tx.commit()
+            .await
+            .map_err(|err| format!("Failed to commit migrations: {}", err))?;
 
         LOCAL_STORAGE
             .set(Self { pool })
@@ -43,3 +94,136 @@ impl LocalStorage {
 }
 
 pub static LOCAL_STORAGE: OnceLock<LocalStorage> = OnceLock::new();
+
+// 按顺序建立 / 升级全部表；各迁移均可重复执行
+async fn migrate(conn: &mut SqliteConnection) -> Result<(), String> {
+    token::migrate_token_table(conn).await?;
+    cache_metadata::migrate_cache_metadata_table(conn).await?;
+    project_prefs::migrate_project_prefs_table(conn).await?;
+    pending_writes::migrate_pending_writes_table(conn).await?;
+    recent_projects::migrate_recent_projects_table(conn).await?;
+    translation_drafts::migrate_translation_drafts_table(conn).await?;
+    usage_stats::migrate_usage_stats_table(conn).await?;
+    settings::migrate_settings_table(conn).await?;
+    publish_records::migrate_publish_records_table(conn).await?;
+    source_recycle::migrate_source_recycle_table(conn).await?;
+    source_snapshots::migrate_source_snapshots_table(conn).await?;
+    deadlines::migrate_deadlines_table(conn).await?;
+    project_snapshots::migrate_project_snapshots_table(conn).await?;
+    file_activity::migrate_file_activity_table(conn).await?;
+    verify_queue::migrate_verify_queue_table(conn).await?;
+    redraw_tasks::migrate_redraw_tasks_table(conn).await?;
+    project_cache::migrate_project_cache_table(conn).await?;
+    cached_project_files::migrate_cached_project_files_table(conn).await?;
+    retry_tokens::migrate_retry_tokens_table(conn).await?;
+    project_notes::migrate_project_notes_table(conn).await?;
+    member_directory::migrate_member_directory_table(conn).await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageDiagnostics {
+    pub journal_mode: String,
+    pub busy_timeout_ms: i64,
+    pub pool_size: u32,
+    pub pool_idle: usize,
+    pub pool_max: u32,
+    // 内存图片缓存的占用与命中统计
+    pub image_memory_cache: ImageMemoryCacheStats,
+}
+
+// 本地数据库诊断信息（日志模式与连接池状态）
+#[tauri::command]
+pub async fn get_storage_diagnostics() -> Result<StorageDiagnostics, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let pool = storage.pool();
+
+    let (journal_mode,) = sqlx::query_as::<_, (String,)>("PRAGMA journal_mode")
+        .fetch_one(pool)
+        .await
+        .map_err(|err| format!("Failed to read journal_mode: {}", err))?;
+
+    let (busy_timeout_ms,) = sqlx::query_as::<_, (i64,)>("PRAGMA busy_timeout")
+        .fetch_one(pool)
+        .await
+        .map_err(|err| format!("Failed to read busy_timeout: {}", err))?;
+
+    let diagnostics = StorageDiagnostics {
+        journal_mode,
+        busy_timeout_ms,
+        pool_size: pool.size(),
+        pool_idle: pool.num_idle(),
+        pool_max: POOL_MAX_CONNECTIONS,
+        image_memory_cache: memory_cache_stats(),
+    };
+
+    tracing::debug!(?diagnostics, "storage.diagnostics.get");
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Connection;
+
+    use super::*;
+    use crate::test_support::local_storage;
+
+    #[tokio::test]
+    async fn migrations_can_run_again_on_an_existing_schema() {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+        migrate(&mut conn).await.unwrap();
+        migrate(&mut conn).await.unwrap();
+
+        let (tables,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'project_notes'",
+        )
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+        assert_eq!(tables, 1);
+    }
+
+    #[tokio::test]
+    async fn database_uses_wal_with_a_busy_timeout() {
+        local_storage().await;
+
+        let diagnostics = get_storage_diagnostics().await.unwrap();
+
+        assert_eq!(diagnostics.journal_mode, "wal");
+        assert_eq!(diagnostics.busy_timeout_ms, BUSY_TIMEOUT.as_millis() as i64);
+        assert_eq!(diagnostics.pool_max, POOL_MAX_CONNECTIONS);
+    }
+
+    #[tokio::test]
+    async fn concurrent_writes_wait_instead_of_failing() {
+        let pool = local_storage().await.pool().clone();
+
+        let writes = (0..16).map(|n| {
+            let pool = pool.clone();
+
+            tokio::spawn(async move {
+                project_notes::insert_note(
+                    &pool,
+                    "storage-team",
+                    &format!("storage-proj-{}", n),
+                    None,
+                    "并发写入",
+                    n,
+                    &[("storage-member".to_string(), "akira".to_string())],
+                )
+                .await
+            })
+        });
+
+        for write in writes.collect::<Vec<_>>() {
+            let note_id = write.await.unwrap().unwrap();
+            project_notes::delete_note(&pool, note_id).await.unwrap();
+        }
+    }
+}
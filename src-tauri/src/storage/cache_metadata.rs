@@ -1,6 +1,6 @@
 // 图片缓存元数据存储（SQLite）
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedProjectMetadata {
@@ -10,10 +10,74 @@ pub struct CachedProjectMetadata {
     pub file_count: i64,
     pub total_size_bytes: i64,
     pub cached_at: i64, // Unix timestamp
+    // 最近一次与远端文件列表比对的结果；从未比对时为 None
+    #[serde(default)]
+    pub remote_file_count: Option<i64>,
+    // 远端有、本地缺少的页数（远端新增的与未下载成功的）
+    #[serde(default)]
+    pub missing_count: Option<i64>,
+    #[serde(default)]
+    pub remote_checked_at: Option<i64>,
+    // 远端项目已不存在
+    #[serde(default)]
+    pub remote_deleted: bool,
 }
 
+type CachedProjectRow = (
+    String,
+    String,
+    String,
+    i64,
+    i64,
+    i64,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    bool,
+);
+
+fn from_row(row: CachedProjectRow) -> CachedProjectMetadata {
+    let (
+        project_id,
+        project_name,
+        status,
+        file_count,
+        total_size_bytes,
+        cached_at,
+        remote_file_count,
+        missing_count,
+        remote_checked_at,
+        remote_deleted,
+    ) = row;
+
+    CachedProjectMetadata {
+        project_id,
+        project_name,
+        status,
+        file_count,
+        total_size_bytes,
+        cached_at,
+        remote_file_count,
+        missing_count,
+        remote_checked_at,
+        remote_deleted,
+    }
+}
+
+const SELECT_COLUMNS: &str = "project_id, project_name, status, file_count, total_size_bytes, cached_at, remote_file_count, missing_count, remote_checked_at, remote_deleted";
+
+// 后来增加的列：旧库中不存在时补齐
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("remote_file_count", "INTEGER"),
+    ("missing_count", "INTEGER"),
+    ("remote_checked_at", "INTEGER"),
+    ("remote_deleted", "INTEGER NOT NULL DEFAULT 0"),
+    // 最近一次读取缓存图片的时间，超出容量上限时按此淘汰；从未读取时按 cached_at
+    ("last_accessed_at", "INTEGER"),
+];
+
 // 创建缓存元数据表
-pub async fn migrate_cache_metadata_table(pool: &SqlitePool) -> Result<(), String> {
+pub async fn migrate_cache_metadata_table(conn: &mut SqliteConnection) -> Result<(), String> {
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS cached_projects (
@@ -26,14 +90,35 @@ pub async fn migrate_cache_metadata_table(pool: &SqlitePool) -> Result<(), Strin
         )
         "#,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|err| format!("Failed to create cached_projects table: {}", err))?;
 
+    for (column, decl) in ADDED_COLUMNS {
+        let exists = sqlx::query_as::<_, (String,)>(
+            "SELECT name FROM pragma_table_info('cached_projects') WHERE name = ?",
+        )
+        .bind(column)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|err| format!("Failed to inspect cached_projects columns: {}", err))?
+        .is_some();
+
+        if !exists {
+            sqlx::query(&format!(
+                "ALTER TABLE cached_projects ADD COLUMN {} {}",
+                column, decl
+            ))
+            .execute(&mut *conn)
+            .await
+            .map_err(|err| format!("Failed to add cached_projects.{}: {}", column, err))?;
+        }
+    }
+
     Ok(())
 }
 
-// 插入或更新缓存元数据
+// 插入或更新缓存元数据（不修改远端比对结果，见 update_remote_summary）
 pub async fn upsert_cached_project(
     pool: &SqlitePool,
     metadata: &CachedProjectMetadata,
@@ -63,36 +148,84 @@ pub async fn upsert_cached_project(
     Ok(())
 }
 
-// 获取所有缓存项目列表
-pub async fn get_all_cached_projects(
+// 记录与远端文件列表比对的结果
+pub async fn update_remote_summary(
     pool: &SqlitePool,
-) -> Result<Vec<CachedProjectMetadata>, String> {
-    let rows = sqlx::query_as::<_, (String, String, String, i64, i64, i64)>(
+    project_id: &str,
+    remote_file_count: Option<i64>,
+    missing_count: Option<i64>,
+    remote_deleted: bool,
+    checked_at: i64,
+) -> Result<(), String> {
+    sqlx::query(
         r#"
-        SELECT project_id, project_name, status, file_count, total_size_bytes, cached_at
-        FROM cached_projects
-        ORDER BY cached_at DESC
+        UPDATE cached_projects
+        SET remote_file_count = ?, missing_count = ?, remote_deleted = ?, remote_checked_at = ?
+        WHERE project_id = ?
         "#,
     )
+    .bind(remote_file_count)
+    .bind(missing_count)
+    .bind(remote_deleted)
+    .bind(checked_at)
+    .bind(project_id)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to update cached project remote summary: {}", err))?;
+
+    Ok(())
+}
+
+// 获取所有缓存项目列表
+pub async fn get_all_cached_projects(
+    pool: &SqlitePool,
+) -> Result<Vec<CachedProjectMetadata>, String> {
+    let rows = sqlx::query_as::<_, CachedProjectRow>(&format!(
+        "SELECT {} FROM cached_projects ORDER BY cached_at DESC",
+        SELECT_COLUMNS
+    ))
     .fetch_all(pool)
     .await
     .map_err(|err| format!("Failed to fetch cached projects: {}", err))?;
 
-    Ok(rows
-        .into_iter()
-        .map(
-            |(project_id, project_name, status, file_count, total_size_bytes, cached_at)| {
-                CachedProjectMetadata {
-                    project_id,
-                    project_name,
-                    status,
-                    file_count,
-                    total_size_bytes,
-                    cached_at,
-                }
-            },
-        )
-        .collect())
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+// 记录项目缓存被读取的时间
+pub async fn touch_cached_project(
+    pool: &SqlitePool,
+    project_id: &str,
+    accessed_at: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE cached_projects SET last_accessed_at = ? WHERE project_id = ?")
+        .bind(accessed_at)
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to touch cached project: {}", err))?;
+
+    Ok(())
+}
+
+// 所有缓存项目的总大小与项目数
+pub async fn cached_projects_usage(pool: &SqlitePool) -> Result<(i64, i64), String> {
+    sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COALESCE(SUM(total_size_bytes), 0), COUNT(*) FROM cached_projects",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|err| format!("Failed to sum cached project sizes: {}", err))
+}
+
+// 按最近读取时间从早到晚列出缓存项目（project_id, total_size_bytes），供容量淘汰使用
+pub async fn list_least_recently_used(pool: &SqlitePool) -> Result<Vec<(String, i64)>, String> {
+    sqlx::query_as::<_, (String, i64)>(
+        "SELECT project_id, total_size_bytes FROM cached_projects \
+         ORDER BY COALESCE(last_accessed_at, cached_at), cached_at",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list cached projects by access time: {}", err))
 }
 
 // 删除缓存元数据
@@ -114,28 +247,94 @@ pub async fn get_cached_project_metadata(
     pool: &SqlitePool,
     project_id: &str,
 ) -> Result<Option<CachedProjectMetadata>, String> {
-    let row = sqlx::query_as::<_, (String, String, String, i64, i64, i64)>(
-        r#"
-        SELECT project_id, project_name, status, file_count, total_size_bytes, cached_at
-        FROM cached_projects
-        WHERE project_id = ?
-        "#,
-    )
+    let row = sqlx::query_as::<_, CachedProjectRow>(&format!(
+        "SELECT {} FROM cached_projects WHERE project_id = ?",
+        SELECT_COLUMNS
+    ))
     .bind(project_id)
     .fetch_optional(pool)
     .await
     .map_err(|err| format!("Failed to fetch cached project metadata: {}", err))?;
 
-    Ok(row.map(
-        |(project_id, project_name, status, file_count, total_size_bytes, cached_at)| {
-            CachedProjectMetadata {
-                project_id,
-                project_name,
-                status,
-                file_count,
-                total_size_bytes,
-                cached_at,
-            }
-        },
-    ))
+    Ok(row.map(from_row))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{sqlite::SqlitePoolOptions, Connection};
+
+    use super::*;
+
+    fn metadata(project_id: &str, file_count: i64) -> CachedProjectMetadata {
+        CachedProjectMetadata {
+            project_id: project_id.to_string(),
+            project_name: "项目".to_string(),
+            status: "completed".to_string(),
+            file_count,
+            total_size_bytes: 10,
+            cached_at: 1,
+            remote_file_count: None,
+            missing_count: None,
+            remote_checked_at: None,
+            remote_deleted: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn old_table_gains_remote_columns_and_upserts_keep_them() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        // 增加远端比对列之前的表结构
+        sqlx::query(
+            "CREATE TABLE cached_projects (project_id TEXT PRIMARY KEY, project_name TEXT NOT NULL, status TEXT NOT NULL, file_count INTEGER NOT NULL DEFAULT 0, total_size_bytes INTEGER NOT NULL DEFAULT 0, cached_at INTEGER NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO cached_projects VALUES ('old', '旧项目', 'completed', 3, 30, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        for _ in 0..2 {
+            let mut tx = conn.begin().await.unwrap();
+            migrate_cache_metadata_table(&mut tx).await.unwrap();
+            tx.commit().await.unwrap();
+        }
+        drop(conn);
+
+        let old = get_cached_project_metadata(&pool, "old")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(old.file_count, 3);
+        assert_eq!((old.remote_file_count, old.remote_deleted), (None, false));
+
+        upsert_cached_project(&pool, &metadata("p1", 2))
+            .await
+            .unwrap();
+        update_remote_summary(&pool, "p1", Some(5), Some(3), false, 100)
+            .await
+            .unwrap();
+
+        // 重新下载只更新下载相关的列
+        upsert_cached_project(&pool, &metadata("p1", 4))
+            .await
+            .unwrap();
+
+        let p1 = get_cached_project_metadata(&pool, "p1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(p1.file_count, 4);
+        assert_eq!(
+            (p1.remote_file_count, p1.missing_count, p1.remote_checked_at),
+            (Some(5), Some(3), Some(100))
+        );
+    }
 }
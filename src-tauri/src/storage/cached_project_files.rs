@@ -0,0 +1,128 @@
+// 已下载项目的文件列表（SQLite）：下载时传入的完整文件列表及每个文件下载时的 Content-Length，
+// retry_failed_downloads 据此找出缺失、为空或被截断的文件，无需前端再次传入文件列表
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedProjectFileRow {
+    pub project_id: String,
+    // 页面顺序（下载时 files 中的下标）
+    pub file_index: i64,
+    pub file_id: Option<String>,
+    pub url: String,
+    // 缓存中的文件名；尚未成功下载时为 None
+    pub file_name: Option<String>,
+    // 下载时的 Content-Length（字节）；未知时为 None
+    pub expected_size: Option<i64>,
+}
+
+type CachedProjectFileTuple = (
+    String,
+    i64,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<i64>,
+);
+
+fn from_tuple(row: CachedProjectFileTuple) -> CachedProjectFileRow {
+    let (project_id, file_index, file_id, url, file_name, expected_size) = row;
+
+    CachedProjectFileRow {
+        project_id,
+        file_index,
+        file_id,
+        url,
+        file_name,
+        expected_size,
+    }
+}
+
+// 创建项目文件列表表
+pub async fn migrate_cached_project_files_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS cached_project_files (
+            project_id TEXT NOT NULL,
+            file_index INTEGER NOT NULL,
+            file_id TEXT,
+            url TEXT NOT NULL,
+            file_name TEXT,
+            expected_size INTEGER,
+            PRIMARY KEY (project_id, file_index)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create cached_project_files table: {}", err))?;
+
+    Ok(())
+}
+
+// 用新的完整列表替换项目的旧记录
+pub async fn replace_project_files(
+    pool: &SqlitePool,
+    project_id: &str,
+    rows: &[CachedProjectFileRow],
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin project files transaction: {}", err))?;
+
+    sqlx::query("DELETE FROM cached_project_files WHERE project_id = ?")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to clear cached project files: {}", err))?;
+
+    for row in rows {
+        sqlx::query(
+            r#"
+            INSERT INTO cached_project_files
+                (project_id, file_index, file_id, url, file_name, expected_size)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&row.project_id)
+        .bind(row.file_index)
+        .bind(&row.file_id)
+        .bind(&row.url)
+        .bind(&row.file_name)
+        .bind(row.expected_size)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to insert cached project file: {}", err))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit cached project files: {}", err))
+}
+
+// 项目的文件列表（按页面顺序）
+pub async fn list_project_files(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<CachedProjectFileRow>, String> {
+    sqlx::query_as::<_, CachedProjectFileTuple>(
+        "SELECT project_id, file_index, file_id, url, file_name, expected_size \
+         FROM cached_project_files WHERE project_id = ? ORDER BY file_index",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map(|rows| rows.into_iter().map(from_tuple).collect())
+    .map_err(|err| format!("Failed to list cached project files: {}", err))
+}
+
+pub async fn delete_project_files(pool: &SqlitePool, project_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM cached_project_files WHERE project_id = ?")
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete cached project files: {}", err))?;
+
+    Ok(())
+}
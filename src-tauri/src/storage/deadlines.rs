@@ -0,0 +1,83 @@
+// 项目各阶段截止时间（本地记录，PopRaKo 暂无对应字段）
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 创建截止时间表
+pub async fn migrate_deadlines_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS project_deadlines (
+            proj_id TEXT NOT NULL,
+            stage TEXT NOT NULL,
+            due_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (proj_id, stage)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create project_deadlines table: {}", err))?;
+
+    Ok(())
+}
+
+// 设置（或清除）某阶段的截止时间
+pub async fn set_deadline(
+    pool: &SqlitePool,
+    proj_id: &str,
+    stage: &str,
+    due_at: Option<i64>,
+) -> Result<(), String> {
+    match due_at {
+        Some(due_at) => {
+            sqlx::query(
+                r#"
+            INSERT INTO project_deadlines (proj_id, stage, due_at, updated_at)
+            VALUES (?, ?, ?, strftime('%s', 'now'))
+            ON CONFLICT(proj_id, stage) DO UPDATE SET
+                due_at = excluded.due_at,
+                updated_at = excluded.updated_at
+            "#,
+            )
+            .bind(proj_id)
+            .bind(stage)
+            .bind(due_at)
+            .execute(pool)
+            .await
+        }
+        None => {
+            sqlx::query("DELETE FROM project_deadlines WHERE proj_id = ? AND stage = ?")
+                .bind(proj_id)
+                .bind(stage)
+                .execute(pool)
+                .await
+        }
+    }
+    .map_err(|err| format!("Failed to save project deadline: {}", err))?;
+
+    Ok(())
+}
+
+// 获取项目的全部截止时间 (stage, due_at)
+pub async fn get_project_deadlines(
+    pool: &SqlitePool,
+    proj_id: &str,
+) -> Result<Vec<(String, i64)>, String> {
+    sqlx::query_as::<_, (String, i64)>(
+        "SELECT stage, due_at FROM project_deadlines WHERE proj_id = ? ORDER BY due_at",
+    )
+    .bind(proj_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch project deadlines: {}", err))
+}
+
+// 获取全部截止时间 (proj_id, stage, due_at)，用于合并进项目列表
+pub async fn list_all_deadlines(pool: &SqlitePool) -> Result<Vec<(String, String, i64)>, String> {
+    sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT proj_id, stage, due_at FROM project_deadlines",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list project deadlines: {}", err))
+}
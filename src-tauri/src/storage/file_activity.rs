@@ -0,0 +1,150 @@
+// 我在各页面上的最近活动（SQLite）：提交 / 更新翻译与打开页面，用于按最近活动排序文件列表
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 每个项目最多保留的条目数（按 file_id + target_id 计）
+pub const FILE_ACTIVITY_PER_PROJECT: i64 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileActivityRow {
+    pub project_id: String,
+    pub file_id: String,
+    pub target_id: String,
+    pub last_action: String, // submit / update / view
+    pub at: i64,             // 最近一次活动（Unix timestamp）
+    pub edited_at: Option<i64>,
+}
+
+// 创建页面活动表
+pub async fn migrate_file_activity_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS file_activity (
+            project_id TEXT NOT NULL,
+            file_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            last_action TEXT NOT NULL,
+            at INTEGER NOT NULL,
+            edited_at INTEGER,
+            PRIMARY KEY (file_id, target_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create file_activity table: {}", err))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_file_activity_project ON file_activity (project_id, at)",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create file_activity index: {}", err))?;
+
+    Ok(())
+}
+
+// 记录一次活动（编辑时 edited_at 与 at 相同，查看时为 None），并裁剪该项目超出上限的旧条目。
+// 查看不覆盖 edit_sticky_secs 内的编辑：编辑后刷新或重新打开同一页面时，last_action 仍为编辑
+pub async fn record_file_activity(
+    pool: &SqlitePool,
+    row: &FileActivityRow,
+    edit_sticky_secs: i64,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin file activity transaction: {}", err))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO file_activity (project_id, file_id, target_id, last_action, at, edited_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(file_id, target_id) DO UPDATE SET
+            project_id = excluded.project_id,
+            last_action = CASE
+                WHEN excluded.edited_at IS NULL
+                     AND edited_at IS NOT NULL
+                     AND excluded.at - edited_at < ?
+                THEN last_action
+                ELSE excluded.last_action
+            END,
+            at = MAX(at, excluded.at),
+            edited_at = COALESCE(excluded.edited_at, edited_at)
+        "#,
+    )
+    .bind(&row.project_id)
+    .bind(&row.file_id)
+    .bind(&row.target_id)
+    .bind(&row.last_action)
+    .bind(row.at)
+    .bind(row.edited_at)
+    .bind(edit_sticky_secs)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to record file activity: {}", err))?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM file_activity
+        WHERE project_id = ?
+          AND rowid NOT IN (
+            SELECT rowid FROM file_activity
+            WHERE project_id = ?
+            ORDER BY at DESC
+            LIMIT ?
+          )
+        "#,
+    )
+    .bind(&row.project_id)
+    .bind(&row.project_id)
+    .bind(FILE_ACTIVITY_PER_PROJECT)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to trim file activity: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit file activity: {}", err))?;
+
+    Ok(())
+}
+
+// 项目的页面活动（按最近活动时间倒序）；target_id 为 None 时包含所有 target
+pub async fn list_file_activity(
+    pool: &SqlitePool,
+    project_id: &str,
+    target_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FileActivityRow>, String> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, i64, Option<i64>)>(
+        r#"
+        SELECT project_id, file_id, target_id, last_action, at, edited_at
+        FROM file_activity
+        WHERE project_id = ? AND (? IS NULL OR target_id = ?)
+        ORDER BY at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(target_id)
+    .bind(target_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list file activity: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(project_id, file_id, target_id, last_action, at, edited_at)| FileActivityRow {
+                project_id,
+                file_id,
+                target_id,
+                last_action,
+                at,
+                edited_at,
+            },
+        )
+        .collect())
+}
@@ -0,0 +1,60 @@
+// 最近一次完整拉取的团队成员目录（SQLite），离线模式下代替网络请求
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 创建成员目录缓存表
+pub async fn migrate_member_directory_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS cached_member_directory (
+            team_id TEXT PRIMARY KEY,
+            data TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create cached_member_directory table: {}", err))?;
+
+    Ok(())
+}
+
+// 保存团队的成员目录（data 为成员列表 JSON），覆盖旧记录
+pub async fn save_member_directory(
+    pool: &SqlitePool,
+    team_id: &str,
+    data: &str,
+    fetched_at: i64,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO cached_member_directory (team_id, data, fetched_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(team_id) DO UPDATE SET
+            data = excluded.data,
+            fetched_at = excluded.fetched_at
+        "#,
+    )
+    .bind(team_id)
+    .bind(data)
+    .bind(fetched_at)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to save member directory: {}", err))?;
+
+    Ok(())
+}
+
+// 读取团队的成员目录（data, fetched_at）；没有记录时返回 None
+pub async fn load_member_directory(
+    pool: &SqlitePool,
+    team_id: &str,
+) -> Result<Option<(String, i64)>, String> {
+    sqlx::query_as::<_, (String, i64)>(
+        "SELECT data, fetched_at FROM cached_member_directory WHERE team_id = ?",
+    )
+    .bind(team_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| format!("Failed to load member directory: {}", err))
+}
@@ -0,0 +1,179 @@
+// PopRaKo 待重试写操作队列（SQLite）
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingWriteRow {
+    pub id: i64,
+    pub proj_id: String,
+    pub kind: String,
+    pub dedupe_key: String,
+    pub payload: String, // JSON
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub queued_at: i64, // Unix timestamp
+}
+
+// 创建待重试写操作表
+pub async fn migrate_pending_writes_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS pending_poprako_writes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            proj_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            dedupe_key TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            queued_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create pending_poprako_writes table: {}", err))?;
+
+    Ok(())
+}
+
+// 入队：同一 dedupe_key 的旧操作会被新操作取代，避免刷新时先应用新值再被旧值覆盖
+pub async fn enqueue_pending_write(
+    pool: &SqlitePool,
+    proj_id: &str,
+    kind: &str,
+    dedupe_key: &str,
+    payload: &str,
+) -> Result<i64, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin pending write transaction: {}", err))?;
+
+    sqlx::query("DELETE FROM pending_poprako_writes WHERE dedupe_key = ?")
+        .bind(dedupe_key)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to supersede pending write: {}", err))?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO pending_poprako_writes (proj_id, kind, dedupe_key, payload, queued_at)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+        "#,
+    )
+    .bind(proj_id)
+    .bind(kind)
+    .bind(dedupe_key)
+    .bind(payload)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to enqueue pending write: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit pending write: {}", err))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+// 按入队顺序列出全部待重试写操作
+pub async fn list_pending_writes(pool: &SqlitePool) -> Result<Vec<PendingWriteRow>, String> {
+    let rows = sqlx::query_as::<
+        _,
+        (
+            i64,
+            String,
+            String,
+            String,
+            String,
+            i64,
+            Option<String>,
+            i64,
+        ),
+    >(
+        r#"
+        SELECT id, proj_id, kind, dedupe_key, payload, attempts, last_error, queued_at
+        FROM pending_poprako_writes
+        ORDER BY id ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch pending writes: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, proj_id, kind, dedupe_key, payload, attempts, last_error, queued_at)| {
+                PendingWriteRow {
+                    id,
+                    proj_id,
+                    kind,
+                    dedupe_key,
+                    payload,
+                    attempts,
+                    last_error,
+                    queued_at,
+                }
+            },
+        )
+        .collect())
+}
+
+// 记录一次失败的重试
+pub async fn mark_pending_write_failed(
+    pool: &SqlitePool,
+    id: i64,
+    error: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE pending_poprako_writes SET attempts = attempts + 1, last_error = ? WHERE id = ?",
+    )
+    .bind(error)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to update pending write: {}", err))?;
+
+    Ok(())
+}
+
+// 删除待重试写操作（成功、冲突丢弃或用户手动放弃）
+pub async fn delete_pending_write(pool: &SqlitePool, id: i64) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM pending_poprako_writes WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete pending write: {}", err))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// 删除同一 dedupe_key 的待重试写操作（同类写操作已直接成功时调用），返回删除条数
+pub async fn delete_pending_writes_by_key(
+    pool: &SqlitePool,
+    dedupe_key: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM pending_poprako_writes WHERE dedupe_key = ?")
+        .bind(dedupe_key)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete superseded pending writes: {}", err))?;
+
+    Ok(result.rows_affected())
+}
+
+// 删除某项目的全部待执行写操作，返回删除条数
+pub async fn delete_pending_writes_for_proj(
+    pool: &SqlitePool,
+    proj_id: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM pending_poprako_writes WHERE proj_id = ?")
+        .bind(proj_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete pending writes: {}", err))?;
+
+    Ok(result.rows_affected())
+}
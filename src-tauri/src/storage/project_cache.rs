@@ -0,0 +1,139 @@
+// 最近一次成功拉取的 enriched 项目列表（SQLite），后端不可达或重启后离线查看时使用
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedProjectRow {
+    // 列表维度："user" 或 "team:<team_id>"
+    pub scope: String,
+    // 在该列表中的位置（跨分页连续编号）
+    pub position: i64,
+    pub proj_id: String,
+    pub data: String,    // ResProjectEnriched（JSON）
+    pub fetched_at: i64, // Unix timestamp
+}
+
+type CachedProjectTuple = (String, i64, String, String, i64);
+
+fn from_tuple(row: CachedProjectTuple) -> CachedProjectRow {
+    let (scope, position, proj_id, data, fetched_at) = row;
+
+    CachedProjectRow {
+        scope,
+        position,
+        proj_id,
+        data,
+        fetched_at,
+    }
+}
+
+// 创建项目列表缓存表
+pub async fn migrate_project_cache_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS cached_projects_enriched (
+            scope TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            proj_id TEXT NOT NULL,
+            data TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL,
+            PRIMARY KEY (scope, proj_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create cached_projects_enriched table: {}", err))?;
+
+    Ok(())
+}
+
+// 用一页新结果替换 [start, end) 位置上的旧记录（包括已被删除或移到其他页的项目），
+// 之后只保留最近写入的 keep 条
+pub async fn replace_page(
+    pool: &SqlitePool,
+    scope: &str,
+    start: i64,
+    end: i64,
+    rows: &[CachedProjectRow],
+    keep: i64,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin project cache transaction: {}", err))?;
+
+    sqlx::query(
+        "DELETE FROM cached_projects_enriched WHERE scope = ? AND position >= ? AND position < ?",
+    )
+    .bind(scope)
+    .bind(start)
+    .bind(end)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to clear cached project page: {}", err))?;
+
+    for row in rows {
+        sqlx::query(
+            r#"
+            INSERT INTO cached_projects_enriched (scope, position, proj_id, data, fetched_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(scope, proj_id) DO UPDATE SET
+                position = excluded.position,
+                data = excluded.data,
+                fetched_at = excluded.fetched_at
+            "#,
+        )
+        .bind(&row.scope)
+        .bind(row.position)
+        .bind(&row.proj_id)
+        .bind(&row.data)
+        .bind(row.fetched_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to insert cached project: {}", err))?;
+    }
+
+    sqlx::query(
+        r#"
+        DELETE FROM cached_projects_enriched WHERE rowid NOT IN (
+            SELECT rowid FROM cached_projects_enriched
+            ORDER BY fetched_at DESC, position
+            LIMIT ?
+        )
+        "#,
+    )
+    .bind(keep)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to prune project cache: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit project cache: {}", err))
+}
+
+// 某个列表在 [start, end) 位置上的缓存（按位置）
+pub async fn list_range(
+    pool: &SqlitePool,
+    scope: &str,
+    start: i64,
+    end: i64,
+) -> Result<Vec<CachedProjectRow>, String> {
+    sqlx::query_as::<_, CachedProjectTuple>(
+        "SELECT scope, position, proj_id, data, fetched_at FROM cached_projects_enriched \
+         WHERE scope = ? AND position >= ? AND position < ? ORDER BY position",
+    )
+    .bind(scope)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map(|rows| rows.into_iter().map(from_tuple).collect())
+    .map_err(|err| format!("Failed to list cached projects: {}", err))
+}
+
+// 某个列表的全部缓存（按位置）
+pub async fn list_scope(pool: &SqlitePool, scope: &str) -> Result<Vec<CachedProjectRow>, String> {
+    list_range(pool, scope, 0, i64::MAX).await
+}
@@ -0,0 +1,238 @@
+// 项目备注（本地记录，PopRaKo 暂无对应接口）及备注中 @ 提及的成员
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectNoteRow {
+    pub id: i64,
+    pub team_id: String,
+    pub project_id: String,
+    // 作者的 member_id（未能获取时为 None）
+    pub author_member_id: Option<String>,
+    pub body: String,
+    pub created_at: i64, // Unix timestamp
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteMentionRow {
+    pub note_id: i64,
+    pub member_id: String,
+    // 保存时的名字，成员已不在目录中时用于显示
+    pub username: String,
+}
+
+type ProjectNoteTuple = (i64, String, String, Option<String>, String, i64);
+
+fn from_tuple(row: ProjectNoteTuple) -> ProjectNoteRow {
+    let (id, team_id, project_id, author_member_id, body, created_at) = row;
+
+    ProjectNoteRow {
+        id,
+        team_id,
+        project_id,
+        author_member_id,
+        body,
+        created_at,
+    }
+}
+
+// 创建备注表与提及表
+pub async fn migrate_project_notes_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS project_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            team_id TEXT NOT NULL,
+            project_id TEXT NOT NULL,
+            author_member_id TEXT,
+            body TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create project_notes table: {}", err))?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS note_mentions (
+            note_id INTEGER NOT NULL REFERENCES project_notes(id) ON DELETE CASCADE,
+            member_id TEXT NOT NULL,
+            username TEXT NOT NULL,
+            PRIMARY KEY (note_id, member_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create note_mentions table: {}", err))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_note_mentions_member ON note_mentions (member_id, note_id)",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create note_mentions index: {}", err))?;
+
+    Ok(())
+}
+
+// 保存备注及其提及的成员 (member_id, username)，返回备注 id
+pub async fn insert_note(
+    pool: &SqlitePool,
+    team_id: &str,
+    project_id: &str,
+    author_member_id: Option<&str>,
+    body: &str,
+    created_at: i64,
+    mentions: &[(String, String)],
+) -> Result<i64, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin project note transaction: {}", err))?;
+
+    let note_id = sqlx::query(
+        r#"
+        INSERT INTO project_notes (team_id, project_id, author_member_id, body, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(team_id)
+    .bind(project_id)
+    .bind(author_member_id)
+    .bind(body)
+    .bind(created_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to insert project note: {}", err))?
+    .last_insert_rowid();
+
+    for (member_id, username) in mentions {
+        sqlx::query(
+            "INSERT OR IGNORE INTO note_mentions (note_id, member_id, username) VALUES (?, ?, ?)",
+        )
+        .bind(note_id)
+        .bind(member_id)
+        .bind(username)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to insert note mention: {}", err))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit project note transaction: {}", err))?;
+
+    Ok(note_id)
+}
+
+// 某项目的全部备注（按时间倒序）
+pub async fn list_project_notes(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Vec<ProjectNoteRow>, String> {
+    let rows = sqlx::query_as::<_, ProjectNoteTuple>(
+        r#"
+        SELECT id, team_id, project_id, author_member_id, body, created_at
+        FROM project_notes
+        WHERE project_id = ?
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch project notes: {}", err))?;
+
+    Ok(rows.into_iter().map(from_tuple).collect())
+}
+
+// 汉化组内提及某成员、且晚于 since 的备注（按时间倒序）
+pub async fn list_notes_mentioning(
+    pool: &SqlitePool,
+    team_id: &str,
+    member_id: &str,
+    since: i64,
+) -> Result<Vec<ProjectNoteRow>, String> {
+    let rows = sqlx::query_as::<_, ProjectNoteTuple>(
+        r#"
+        SELECT n.id, n.team_id, n.project_id, n.author_member_id, n.body, n.created_at
+        FROM project_notes n
+        JOIN note_mentions m ON m.note_id = n.id
+        WHERE n.team_id = ? AND m.member_id = ? AND n.created_at > ?
+        ORDER BY n.created_at DESC, n.id DESC
+        "#,
+    )
+    .bind(team_id)
+    .bind(member_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch mentioning notes: {}", err))?;
+
+    Ok(rows.into_iter().map(from_tuple).collect())
+}
+
+// 一组备注中提及的成员
+pub async fn list_note_mentions(
+    pool: &SqlitePool,
+    note_ids: &[i64],
+) -> Result<Vec<NoteMentionRow>, String> {
+    if note_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; note_ids.len()].join(", ");
+    let sql = format!(
+        "SELECT note_id, member_id, username FROM note_mentions WHERE note_id IN ({}) ORDER BY note_id, rowid",
+        placeholders
+    );
+
+    let mut query = sqlx::query_as::<_, (i64, String, String)>(&sql);
+
+    for note_id in note_ids {
+        query = query.bind(note_id);
+    }
+
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|err| format!("Failed to fetch note mentions: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(note_id, member_id, username)| NoteMentionRow {
+            note_id,
+            member_id,
+            username,
+        })
+        .collect())
+}
+
+// 删除备注及其提及记录；返回是否存在
+pub async fn delete_note(pool: &SqlitePool, note_id: i64) -> Result<bool, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin project note transaction: {}", err))?;
+
+    sqlx::query("DELETE FROM note_mentions WHERE note_id = ?")
+        .bind(note_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to delete note mentions: {}", err))?;
+
+    let result = sqlx::query("DELETE FROM project_notes WHERE id = ?")
+        .bind(note_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to delete project note: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit project note transaction: {}", err))?;
+
+    Ok(result.rows_affected() > 0)
+}
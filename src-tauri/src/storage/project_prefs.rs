@@ -0,0 +1,60 @@
+// 项目级本地偏好存储（SQLite）
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 创建项目偏好表
+pub async fn migrate_project_prefs_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS project_prefs (
+            project_id TEXT PRIMARY KEY,
+            reading_direction TEXT,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create project_prefs table: {}", err))?;
+
+    Ok(())
+}
+
+// 获取项目的阅读方向偏好（未设置时返回 None）
+pub async fn get_reading_direction(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<Option<String>, String> {
+    let row = sqlx::query_as::<_, (Option<String>,)>(
+        "SELECT reading_direction FROM project_prefs WHERE project_id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch reading direction: {}", err))?;
+
+    Ok(row.and_then(|(direction,)| direction))
+}
+
+// 保存项目的阅读方向偏好
+pub async fn save_reading_direction(
+    pool: &SqlitePool,
+    project_id: &str,
+    direction: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO project_prefs (project_id, reading_direction, updated_at)
+        VALUES (?, ?, strftime('%s', 'now'))
+        ON CONFLICT(project_id) DO UPDATE SET
+            reading_direction = excluded.reading_direction,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(project_id)
+    .bind(direction)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to save reading direction: {}", err))?;
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+// 项目状态历史快照（SQLite）：每次成功拉取 enriched 列表后记录各项目的精简状态，每个项目保留最近若干条
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSnapshotRow {
+    pub proj_id: String,
+    pub fetched_at: i64, // Unix timestamp
+    pub data: String,    // 精简状态（JSON）
+}
+
+// 创建项目快照表
+pub async fn migrate_project_snapshots_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS project_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            proj_id TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL,
+            data TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create project_snapshots table: {}", err))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_project_snapshots_proj ON project_snapshots (proj_id, id)",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create project_snapshots index: {}", err))?;
+
+    Ok(())
+}
+
+// 在同一事务中记录一批快照：与该项目最新一条内容相同时只更新时间，否则新增并裁剪到 keep 条
+pub async fn record_snapshots(
+    pool: &SqlitePool,
+    rows: &[ProjectSnapshotRow],
+    keep: i64,
+) -> Result<(), String> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin project snapshot transaction: {}", err))?;
+
+    for row in rows {
+        let latest = sqlx::query_as::<_, (i64, String)>(
+            "SELECT id, data FROM project_snapshots WHERE proj_id = ? ORDER BY id DESC LIMIT 1",
+        )
+        .bind(&row.proj_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to fetch latest project snapshot: {}", err))?;
+
+        if let Some((id, data)) = latest {
+            if data == row.data {
+                sqlx::query("UPDATE project_snapshots SET fetched_at = ? WHERE id = ?")
+                    .bind(row.fetched_at)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|err| format!("Failed to touch project snapshot: {}", err))?;
+
+                continue;
+            }
+        }
+
+        sqlx::query("INSERT INTO project_snapshots (proj_id, fetched_at, data) VALUES (?, ?, ?)")
+            .bind(&row.proj_id)
+            .bind(row.fetched_at)
+            .bind(&row.data)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| format!("Failed to insert project snapshot: {}", err))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM project_snapshots
+            WHERE proj_id = ?
+              AND id NOT IN (
+                SELECT id FROM project_snapshots
+                WHERE proj_id = ?
+                ORDER BY id DESC
+                LIMIT ?
+              )
+            "#,
+        )
+        .bind(&row.proj_id)
+        .bind(&row.proj_id)
+        .bind(keep)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to trim project snapshots: {}", err))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit project snapshots: {}", err))?;
+
+    Ok(())
+}
+
+// 获取项目最近的快照（新的在前）
+pub async fn list_snapshots(
+    pool: &SqlitePool,
+    proj_id: &str,
+    limit: i64,
+) -> Result<Vec<ProjectSnapshotRow>, String> {
+    let rows = sqlx::query_as::<_, (String, i64, String)>(
+        r#"
+        SELECT proj_id, fetched_at, data
+        FROM project_snapshots
+        WHERE proj_id = ?
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(proj_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch project snapshots: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(proj_id, fetched_at, data)| ProjectSnapshotRow {
+            proj_id,
+            fetched_at,
+            data,
+        })
+        .collect())
+}
@@ -0,0 +1,88 @@
+// 项目发布记录（SQLite）：记录由本机发起的发布，以及发布时是否绕过了完成度检查
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 创建发布记录表
+pub async fn migrate_publish_records_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS publish_records (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            proj_id TEXT NOT NULL,
+            proj_name TEXT,
+            published_at INTEGER NOT NULL,
+            gate_bypassed INTEGER NOT NULL DEFAULT 0,
+            gate_issues TEXT
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create publish_records table: {}", err))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_publish_records_proj ON publish_records (proj_id)")
+        .execute(&mut *conn)
+        .await
+        .map_err(|err| format!("Failed to create publish_records index: {}", err))?;
+
+    Ok(())
+}
+
+// 记录一次成功的发布；gate_issues 为未通过的检查项（JSON），正常发布时为 None
+pub async fn record_publish(
+    pool: &SqlitePool,
+    proj_id: &str,
+    proj_name: Option<&str>,
+    gate_bypassed: bool,
+    gate_issues: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO publish_records (proj_id, proj_name, published_at, gate_bypassed, gate_issues)
+        VALUES (?, ?, strftime('%s', 'now'), ?, ?)
+        "#,
+    )
+    .bind(proj_id)
+    .bind(proj_name)
+    .bind(gate_bypassed)
+    .bind(gate_issues)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to record publish: {}", err))?;
+
+    Ok(())
+}
+
+// 有发布记录的项目（去重），附最近一次记录的项目名
+pub async fn list_published_proj_ids(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, Option<String>)>, String> {
+    sqlx::query_as::<_, (String, Option<String>)>(
+        r#"
+        SELECT proj_id, proj_name
+        FROM publish_records
+        WHERE id IN (SELECT MAX(id) FROM publish_records GROUP BY proj_id)
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list publish records: {}", err))
+}
+
+// 某时刻（含）之后的发布记录，按时间从新到旧
+pub async fn list_publishes_since(
+    pool: &SqlitePool,
+    since: i64,
+) -> Result<Vec<(String, Option<String>, i64)>, String> {
+    sqlx::query_as::<_, (String, Option<String>, i64)>(
+        r#"
+        SELECT proj_id, proj_name, published_at
+        FROM publish_records
+        WHERE published_at >= ?
+        ORDER BY published_at DESC, id DESC
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list publish records: {}", err))
+}
@@ -0,0 +1,165 @@
+// 最近打开的项目（SQLite），按汉化组分别记录
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 每个汉化组最多保留的条目数
+pub const RECENT_PROJECTS_PER_TEAM: i64 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProjectRow {
+    pub team_id: String,
+    pub project_id: String,
+    pub open_count: i64,
+    pub last_opened_at: i64,      // Unix timestamp
+    pub snapshot: Option<String>, // 最近一次 enriched 数据（JSON）
+}
+
+// 创建最近项目表
+pub async fn migrate_recent_projects_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS recent_projects (
+            team_id TEXT NOT NULL,
+            project_id TEXT NOT NULL,
+            open_count INTEGER NOT NULL DEFAULT 0,
+            last_opened_at INTEGER NOT NULL,
+            snapshot TEXT,
+            PRIMARY KEY (team_id, project_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create recent_projects table: {}", err))?;
+
+    Ok(())
+}
+
+// 记录一次打开，并裁剪该汉化组超出上限的旧条目
+pub async fn record_project_open(
+    pool: &SqlitePool,
+    team_id: &str,
+    project_id: &str,
+    snapshot: Option<&str>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin recent project transaction: {}", err))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO recent_projects (team_id, project_id, open_count, last_opened_at, snapshot)
+        VALUES (?, ?, 1, strftime('%s', 'now'), ?)
+        ON CONFLICT(team_id, project_id) DO UPDATE SET
+            open_count = open_count + 1,
+            last_opened_at = excluded.last_opened_at,
+            snapshot = COALESCE(excluded.snapshot, snapshot)
+        "#,
+    )
+    .bind(team_id)
+    .bind(project_id)
+    .bind(snapshot)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to record recent project: {}", err))?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM recent_projects
+        WHERE team_id = ?
+          AND project_id NOT IN (
+            SELECT project_id FROM recent_projects
+            WHERE team_id = ?
+            ORDER BY last_opened_at DESC
+            LIMIT ?
+          )
+        "#,
+    )
+    .bind(team_id)
+    .bind(team_id)
+    .bind(RECENT_PROJECTS_PER_TEAM)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to trim recent projects: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit recent project: {}", err))?;
+
+    Ok(())
+}
+
+// 更新已记录项目的 enriched 快照（未记录过的项目不会新增）
+pub async fn update_recent_snapshot(
+    pool: &SqlitePool,
+    team_id: &str,
+    project_id: &str,
+    snapshot: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE recent_projects SET snapshot = ? WHERE team_id = ? AND project_id = ?")
+        .bind(snapshot)
+        .bind(team_id)
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to update recent project snapshot: {}", err))?;
+
+    Ok(())
+}
+
+// 获取某汉化组的全部最近项目（按最近打开时间倒序）
+pub async fn list_recent_projects(
+    pool: &SqlitePool,
+    team_id: &str,
+) -> Result<Vec<RecentProjectRow>, String> {
+    let rows = sqlx::query_as::<_, (String, String, i64, i64, Option<String>)>(
+        r#"
+        SELECT team_id, project_id, open_count, last_opened_at, snapshot
+        FROM recent_projects
+        WHERE team_id = ?
+        ORDER BY last_opened_at DESC
+        "#,
+    )
+    .bind(team_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch recent projects: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(team_id, project_id, open_count, last_opened_at, snapshot)| RecentProjectRow {
+                team_id,
+                project_id,
+                open_count,
+                last_opened_at,
+                snapshot,
+            },
+        )
+        .collect())
+}
+
+// 从所有汉化组中删除某项目（服务端确认项目不存在时）
+pub async fn delete_recent_project_everywhere(
+    pool: &SqlitePool,
+    project_id: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM recent_projects WHERE project_id = ?")
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete recent project: {}", err))?;
+
+    Ok(result.rows_affected())
+}
+
+// 所有汉化组中最近项目的 enriched 快照（完整性检查用）
+pub async fn list_all_recent_snapshots(pool: &SqlitePool) -> Result<Vec<(String, String)>, String> {
+    sqlx::query_as::<_, (String, String)>(
+        "SELECT project_id, snapshot FROM recent_projects WHERE snapshot IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch recent snapshots: {}", err))
+}
@@ -0,0 +1,94 @@
+// 嵌字前的修图任务草稿（本地记录，PopRaKo 暂无按页的任务）
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedrawTaskRow {
+    pub proj_id: String,
+    pub file_id: String,
+    pub file_name: String,
+    // 生成时的分级：light / heavy
+    pub bucket: String,
+    pub score: f64,
+    pub outside_count: i64,
+    pub created_at: i64, // Unix timestamp
+}
+
+type RedrawTaskTuple = (String, String, String, String, f64, i64, i64);
+
+fn from_tuple(row: RedrawTaskTuple) -> RedrawTaskRow {
+    let (proj_id, file_id, file_name, bucket, score, outside_count, created_at) = row;
+
+    RedrawTaskRow {
+        proj_id,
+        file_id,
+        file_name,
+        bucket,
+        score,
+        outside_count,
+        created_at,
+    }
+}
+
+// 创建修图任务表
+pub async fn migrate_redraw_tasks_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS redraw_tasks (
+            proj_id TEXT NOT NULL,
+            file_id TEXT NOT NULL,
+            file_name TEXT NOT NULL,
+            bucket TEXT NOT NULL,
+            score REAL NOT NULL,
+            outside_count INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (proj_id, file_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create redraw_tasks table: {}", err))?;
+
+    Ok(())
+}
+
+// 插入任务；该页已有任务时保留原记录，返回是否新建
+pub async fn insert_redraw_task(pool: &SqlitePool, row: &RedrawTaskRow) -> Result<bool, String> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO redraw_tasks
+            (proj_id, file_id, file_name, bucket, score, outside_count, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(proj_id, file_id) DO NOTHING
+        "#,
+    )
+    .bind(&row.proj_id)
+    .bind(&row.file_id)
+    .bind(&row.file_name)
+    .bind(&row.bucket)
+    .bind(row.score)
+    .bind(row.outside_count)
+    .bind(row.created_at)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to insert redraw task: {}", err))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// 项目的全部修图任务（按生成时间）
+pub async fn list_redraw_tasks(
+    pool: &SqlitePool,
+    proj_id: &str,
+) -> Result<Vec<RedrawTaskRow>, String> {
+    sqlx::query_as::<_, RedrawTaskTuple>(
+        "SELECT proj_id, file_id, file_name, bucket, score, outside_count, created_at \
+         FROM redraw_tasks WHERE proj_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(proj_id)
+    .fetch_all(pool)
+    .await
+    .map(|rows| rows.into_iter().map(from_tuple).collect())
+    .map_err(|err| format!("Failed to list redraw tasks: {}", err))
+}
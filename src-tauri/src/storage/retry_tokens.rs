@@ -0,0 +1,156 @@
+// 可一键重试的失败写操作（SQLite），应用重启后 retry_token 仍然有效
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone)]
+pub struct RetryTokenRow {
+    pub token: String,
+    pub command: String,
+    pub entity: String,
+    pub payload: String, // JSON
+    pub error: String,
+    pub failed_at: i64, // Unix timestamp
+}
+
+type RetryTokenTuple = (String, String, String, String, String, i64);
+
+fn row_from_tuple(
+    (token, command, entity, payload, error, failed_at): RetryTokenTuple,
+) -> RetryTokenRow {
+    RetryTokenRow {
+        token,
+        command,
+        entity,
+        payload,
+        error,
+        failed_at,
+    }
+}
+
+// 创建重试登记表
+pub async fn migrate_retry_tokens_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS retry_tokens (
+            token TEXT PRIMARY KEY,
+            command TEXT NOT NULL,
+            entity TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            error TEXT NOT NULL,
+            failed_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create retry_tokens table: {}", err))?;
+
+    Ok(())
+}
+
+// 登记一条失败写操作，只保留最新的 max_entries 条
+pub async fn insert_retry_token(
+    pool: &SqlitePool,
+    row: &RetryTokenRow,
+    max_entries: i64,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin retry token transaction: {}", err))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO retry_tokens (token, command, entity, payload, error, failed_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&row.token)
+    .bind(&row.command)
+    .bind(&row.entity)
+    .bind(&row.payload)
+    .bind(&row.error)
+    .bind(row.failed_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to insert retry token: {}", err))?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM retry_tokens
+        WHERE rowid NOT IN (
+            SELECT rowid FROM retry_tokens ORDER BY failed_at DESC, rowid DESC LIMIT ?
+        )
+        "#,
+    )
+    .bind(max_entries)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to trim retry tokens: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit retry token: {}", err))?;
+
+    Ok(())
+}
+
+// 作废同一实体上的全部 token，返回删除的条数
+pub async fn delete_retry_tokens_for_entity(
+    pool: &SqlitePool,
+    entity: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM retry_tokens WHERE entity = ?")
+        .bind(entity)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to invalidate retry tokens: {}", err))?;
+
+    Ok(result.rows_affected())
+}
+
+// 删除失败时间早于 cutoff 的 token
+pub async fn delete_expired_retry_tokens(pool: &SqlitePool, cutoff: i64) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM retry_tokens WHERE failed_at < ?")
+        .bind(cutoff)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to prune retry tokens: {}", err))?;
+
+    Ok(result.rows_affected())
+}
+
+// 按失败时间从新到旧列出
+pub async fn list_retry_tokens(pool: &SqlitePool) -> Result<Vec<RetryTokenRow>, String> {
+    let rows = sqlx::query_as::<_, RetryTokenTuple>(
+        r#"
+        SELECT token, command, entity, payload, error, failed_at
+        FROM retry_tokens
+        ORDER BY failed_at DESC, rowid DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch retry tokens: {}", err))?;
+
+    Ok(rows.into_iter().map(row_from_tuple).collect())
+}
+
+// 取出并删除一条 token（只能使用一次）
+pub async fn take_retry_token(
+    pool: &SqlitePool,
+    token: &str,
+) -> Result<Option<RetryTokenRow>, String> {
+    let row = sqlx::query_as::<_, RetryTokenTuple>(
+        r#"
+        DELETE FROM retry_tokens
+        WHERE token = ?
+        RETURNING token, command, entity, payload, error, failed_at
+        "#,
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| format!("Failed to take retry token: {}", err))?;
+
+    Ok(row.map(row_from_tuple))
+}
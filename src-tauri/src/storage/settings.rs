@@ -0,0 +1,74 @@
+// 用户设置（SQLite key-value），作为配置解析的中间优先级来源
+use std::collections::HashMap;
+
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 创建设置表
+pub async fn migrate_settings_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create settings table: {}", err))?;
+
+    Ok(())
+}
+
+// 读取全部设置
+pub async fn list_settings(pool: &SqlitePool) -> Result<HashMap<String, String>, String> {
+    let rows = sqlx::query_as::<_, (String, String)>("SELECT key, value FROM settings")
+        .fetch_all(pool)
+        .await
+        .map_err(|err| format!("Failed to fetch settings: {}", err))?;
+
+    Ok(rows.into_iter().collect())
+}
+
+// 读取单项设置
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>, String> {
+    let row = sqlx::query_as::<_, (String,)>("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|err| format!("Failed to fetch setting: {}", err))?;
+
+    Ok(row.map(|(value,)| value))
+}
+
+// 写入（覆盖）单项设置
+pub async fn save_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, strftime('%s', 'now'))
+        ON CONFLICT(key) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to save setting: {}", err))?;
+
+    Ok(())
+}
+
+// 删除单项设置（恢复为环境变量或默认值）
+pub async fn delete_setting(pool: &SqlitePool, key: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete setting: {}", err))?;
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// 已删除 source 的本地快照（SQLite）：批量删除前写入，误删时可据此查看或手动恢复
+use sqlx::{SqliteConnection, SqlitePool};
+
+// 创建 source 回收站表
+pub async fn migrate_source_recycle_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS source_recycle (
+            source_id TEXT PRIMARY KEY,
+            file_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            snapshot TEXT NOT NULL,
+            deleted_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create source_recycle table: {}", err))?;
+
+    Ok(())
+}
+
+// 写入删除前的快照（含翻译，JSON）；同一 source 再次写入时覆盖
+pub async fn save_source_snapshot(
+    pool: &SqlitePool,
+    source_id: &str,
+    file_id: &str,
+    target_id: &str,
+    snapshot: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO source_recycle (source_id, file_id, target_id, snapshot, deleted_at)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+        ON CONFLICT(source_id) DO UPDATE SET
+            snapshot = excluded.snapshot,
+            deleted_at = excluded.deleted_at
+        "#,
+    )
+    .bind(source_id)
+    .bind(file_id)
+    .bind(target_id)
+    .bind(snapshot)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to save source snapshot: {}", err))?;
+
+    Ok(())
+}
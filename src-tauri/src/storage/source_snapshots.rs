@@ -0,0 +1,123 @@
+// 页面 source 快照（SQLite）：按 (file_id, target_id) 保存最近一次拉取的 sources（MessagePack）
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone)]
+pub struct SourceSnapshotRow {
+    pub payload: Vec<u8>,  // MessagePack 编码的 Vec<MoetranSource>
+    pub content_hash: i64, // payload 的 FNV-1a 哈希（按 i64 存储）
+    pub fetched_at: i64,   // Unix timestamp
+}
+
+// 创建 source 快照表
+pub async fn migrate_source_snapshots_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS source_snapshots (
+            file_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            payload BLOB NOT NULL,
+            content_hash INTEGER NOT NULL,
+            fetched_at INTEGER NOT NULL,
+            PRIMARY KEY (file_id, target_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create source_snapshots table: {}", err))?;
+
+    Ok(())
+}
+
+pub async fn load_source_snapshot(
+    pool: &SqlitePool,
+    file_id: &str,
+    target_id: &str,
+) -> Result<Option<SourceSnapshotRow>, String> {
+    let row = sqlx::query_as::<_, (Vec<u8>, i64, i64)>(
+        "SELECT payload, content_hash, fetched_at FROM source_snapshots WHERE file_id = ? AND target_id = ?",
+    )
+    .bind(file_id)
+    .bind(target_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| format!("Failed to load source snapshot: {}", err))?;
+
+    Ok(
+        row.map(|(payload, content_hash, fetched_at)| SourceSnapshotRow {
+            payload,
+            content_hash,
+            fetched_at,
+        }),
+    )
+}
+
+pub async fn save_source_snapshot(
+    pool: &SqlitePool,
+    file_id: &str,
+    target_id: &str,
+    payload: &[u8],
+    content_hash: i64,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO source_snapshots (file_id, target_id, payload, content_hash, fetched_at)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+        ON CONFLICT(file_id, target_id) DO UPDATE SET
+            payload = excluded.payload,
+            content_hash = excluded.content_hash,
+            fetched_at = excluded.fetched_at
+        "#,
+    )
+    .bind(file_id)
+    .bind(target_id)
+    .bind(payload)
+    .bind(content_hash)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to save source snapshot: {}", err))?;
+
+    Ok(())
+}
+
+// 删除某文件（所有 target）的快照，返回删除条数
+pub async fn delete_source_snapshots_for_file(
+    pool: &SqlitePool,
+    file_id: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM source_snapshots WHERE file_id = ?")
+        .bind(file_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete source snapshots: {}", err))?;
+
+    Ok(result.rows_affected())
+}
+
+// 列出全部快照的键与拉取时间（不含内容）
+pub async fn list_source_snapshot_keys(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String, i64)>, String> {
+    sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT file_id, target_id, fetched_at FROM source_snapshots",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list source snapshots: {}", err))
+}
+
+// 删除单个快照
+pub async fn delete_source_snapshot(
+    pool: &SqlitePool,
+    file_id: &str,
+    target_id: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM source_snapshots WHERE file_id = ? AND target_id = ?")
+        .bind(file_id)
+        .bind(target_id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete source snapshot: {}", err))?;
+
+    Ok(result.rows_affected() > 0)
+}
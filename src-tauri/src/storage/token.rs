@@ -1,6 +1,6 @@
-use sqlx::Row;
+use sqlx::{Row, SqliteConnection};
 
-pub async fn migrate_token_table(pool: &sqlx::SqlitePool) -> Result<(), String> {
+pub async fn migrate_token_table(conn: &mut SqliteConnection) -> Result<(), String> {
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS tokens (
@@ -10,7 +10,7 @@ pub async fn migrate_token_table(pool: &sqlx::SqlitePool) -> Result<(), String>
         );
         "#,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|err| format!("Failed to initialize database schema: {}", err))?;
 
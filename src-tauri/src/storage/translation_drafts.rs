@@ -0,0 +1,154 @@
+// 翻译草稿（SQLite）：编辑器防抖自动保存，崩溃或切页后可恢复
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationDraftRow {
+    pub source_id: String,
+    pub target_id: String,
+    pub file_id: String,
+    pub content: String,
+    pub updated_at: i64, // Unix timestamp
+}
+
+// 创建翻译草稿表
+pub async fn migrate_translation_drafts_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS translation_drafts (
+            source_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            file_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (source_id, target_id)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create translation_drafts table: {}", err))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_translation_drafts_file ON translation_drafts (file_id, target_id)",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create translation_drafts index: {}", err))?;
+
+    Ok(())
+}
+
+// 保存（覆盖）草稿
+pub async fn save_translation_draft(
+    pool: &SqlitePool,
+    source_id: &str,
+    target_id: &str,
+    file_id: &str,
+    content: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO translation_drafts (source_id, target_id, file_id, content, updated_at)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+        ON CONFLICT(source_id, target_id) DO UPDATE SET
+            file_id = excluded.file_id,
+            content = excluded.content,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(source_id)
+    .bind(target_id)
+    .bind(file_id)
+    .bind(content)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to save translation draft: {}", err))?;
+
+    Ok(())
+}
+
+// 获取某页（file + target）的全部草稿
+pub async fn get_file_drafts(
+    pool: &SqlitePool,
+    file_id: &str,
+    target_id: &str,
+) -> Result<Vec<TranslationDraftRow>, String> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, i64)>(
+        r#"
+        SELECT source_id, target_id, file_id, content, updated_at
+        FROM translation_drafts
+        WHERE file_id = ? AND target_id = ?
+        ORDER BY updated_at DESC
+        "#,
+    )
+    .bind(file_id)
+    .bind(target_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch translation drafts: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(source_id, target_id, file_id, content, updated_at)| TranslationDraftRow {
+                source_id,
+                target_id,
+                file_id,
+                content,
+                updated_at,
+            },
+        )
+        .collect())
+}
+
+// 获取全部草稿（完整性检查用）
+pub async fn list_all_drafts(pool: &SqlitePool) -> Result<Vec<TranslationDraftRow>, String> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, i64)>(
+        "SELECT source_id, target_id, file_id, content, updated_at FROM translation_drafts",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch translation drafts: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(source_id, target_id, file_id, content, updated_at)| TranslationDraftRow {
+                source_id,
+                target_id,
+                file_id,
+                content,
+                updated_at,
+            },
+        )
+        .collect())
+}
+
+// 删除单条草稿
+pub async fn delete_translation_draft(
+    pool: &SqlitePool,
+    source_id: &str,
+    target_id: &str,
+) -> Result<bool, String> {
+    let result =
+        sqlx::query("DELETE FROM translation_drafts WHERE source_id = ? AND target_id = ?")
+            .bind(source_id)
+            .bind(target_id)
+            .execute(pool)
+            .await
+            .map_err(|err| format!("Failed to delete translation draft: {}", err))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// 清理早于指定时间的草稿，返回删除条数
+pub async fn prune_drafts_before(pool: &SqlitePool, cutoff: i64) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM translation_drafts WHERE updated_at < ?")
+        .bind(cutoff)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to prune translation drafts: {}", err))?;
+
+    Ok(result.rows_affected())
+}
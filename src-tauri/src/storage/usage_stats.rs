@@ -0,0 +1,99 @@
+// API 使用量按天聚合统计（SQLite）
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStatRow {
+    pub day: String,    // YYYY-MM-DD（UTC）
+    pub family: String, // 规范化后的接口族，如 "GET api.moetran.com/v1/projects/{id}/files"
+    pub request_count: i64,
+    pub bytes: i64,
+}
+
+// 创建使用量统计表
+pub async fn migrate_usage_stats_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS usage_stats (
+            day TEXT NOT NULL,
+            family TEXT NOT NULL,
+            request_count INTEGER NOT NULL DEFAULT 0,
+            bytes INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, family)
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create usage_stats table: {}", err))?;
+
+    Ok(())
+}
+
+// 累加某天某接口族的请求数与字节数
+pub async fn add_usage(
+    pool: &SqlitePool,
+    day: &str,
+    family: &str,
+    request_count: i64,
+    bytes: i64,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO usage_stats (day, family, request_count, bytes)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(day, family) DO UPDATE SET
+            request_count = request_count + excluded.request_count,
+            bytes = bytes + excluded.bytes
+        "#,
+    )
+    .bind(day)
+    .bind(family)
+    .bind(request_count)
+    .bind(bytes)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to add usage stats: {}", err))?;
+
+    Ok(())
+}
+
+// 获取指定日期（含）之后的统计，按日期倒序、请求数倒序
+pub async fn list_usage_since(
+    pool: &SqlitePool,
+    since_day: &str,
+) -> Result<Vec<UsageStatRow>, String> {
+    let rows = sqlx::query_as::<_, (String, String, i64, i64)>(
+        r#"
+        SELECT day, family, request_count, bytes
+        FROM usage_stats
+        WHERE day >= ?
+        ORDER BY day DESC, request_count DESC
+        "#,
+    )
+    .bind(since_day)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to fetch usage stats: {}", err))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(day, family, request_count, bytes)| UsageStatRow {
+            day,
+            family,
+            request_count,
+            bytes,
+        })
+        .collect())
+}
+
+// 删除早于指定日期的统计
+pub async fn prune_usage_before(pool: &SqlitePool, day: &str) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM usage_stats WHERE day < ?")
+        .bind(day)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to prune usage stats: {}", err))?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,267 @@
+// 已提交翻译的持久化校验队列（SQLite）
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+pub const VERIFY_STATUS_VERIFIED: &str = "verified";
+pub const VERIFY_STATUS_LOST: &str = "lost";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyQueueRow {
+    pub id: i64,
+    pub source_id: String,
+    pub target_id: String,
+    pub translation_id: String,
+    // 所在文件，按页批量校验
+    pub file_id: String,
+    // 提交的内容（规范化后），丢失时用于一键重新提交
+    pub content: String,
+    pub content_hash: String,
+    pub status: String,
+    // 丢失原因：missing / altered
+    pub reason: Option<String>,
+    pub created_at: i64, // Unix timestamp
+    pub due_at: i64,
+}
+
+type VerifyQueueTuple = (
+    i64,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    i64,
+    i64,
+);
+
+const SELECT_COLUMNS: &str = "id, source_id, target_id, translation_id, file_id, content, \
+    content_hash, status, reason, created_at, due_at";
+
+fn from_tuple(row: VerifyQueueTuple) -> VerifyQueueRow {
+    let (
+        id,
+        source_id,
+        target_id,
+        translation_id,
+        file_id,
+        content,
+        content_hash,
+        status,
+        reason,
+        created_at,
+        due_at,
+    ) = row;
+
+    VerifyQueueRow {
+        id,
+        source_id,
+        target_id,
+        translation_id,
+        file_id,
+        content,
+        content_hash,
+        status,
+        reason,
+        created_at,
+        due_at,
+    }
+}
+
+// 创建校验队列表
+pub async fn migrate_verify_queue_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS verify_queue (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            translation_id TEXT NOT NULL,
+            file_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            reason TEXT,
+            created_at INTEGER NOT NULL,
+            due_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create verify_queue table: {}", err))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_verify_queue_status_due ON verify_queue (status, due_at)",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| format!("Failed to create verify_queue index: {}", err))?;
+
+    Ok(())
+}
+
+// 入队；同一翻译尚未校验的旧记录被取代
+pub async fn enqueue_verify(pool: &SqlitePool, row: &VerifyQueueRow) -> Result<i64, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| format!("Failed to begin verify queue transaction: {}", err))?;
+
+    sqlx::query("DELETE FROM verify_queue WHERE translation_id = ? AND status = 'pending'")
+        .bind(&row.translation_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Failed to supersede verify entry: {}", err))?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO verify_queue (
+            source_id, target_id, translation_id, file_id, content, content_hash,
+            status, created_at, due_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, 'pending', ?, ?)
+        "#,
+    )
+    .bind(&row.source_id)
+    .bind(&row.target_id)
+    .bind(&row.translation_id)
+    .bind(&row.file_id)
+    .bind(&row.content)
+    .bind(&row.content_hash)
+    .bind(row.created_at)
+    .bind(row.due_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| format!("Failed to enqueue verify entry: {}", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| format!("Failed to commit verify entry: {}", err))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+// 用户随后更新了译文内容时同步尚未校验的记录，避免把自己的修改当作“被改动”
+pub async fn update_pending_content(
+    pool: &SqlitePool,
+    translation_id: &str,
+    content: &str,
+    content_hash: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE verify_queue SET content = ?, content_hash = ? \
+         WHERE translation_id = ? AND status = 'pending'",
+    )
+    .bind(content)
+    .bind(content_hash)
+    .bind(translation_id)
+    .execute(pool)
+    .await
+    .map_err(|err| format!("Failed to update verify entry: {}", err))?;
+
+    Ok(())
+}
+
+// 到期待校验的记录（按到期时间顺序）
+pub async fn list_due(
+    pool: &SqlitePool,
+    now: i64,
+    limit: i64,
+) -> Result<Vec<VerifyQueueRow>, String> {
+    let rows = sqlx::query_as::<_, VerifyQueueTuple>(&format!(
+        "SELECT {} FROM verify_queue WHERE status = 'pending' AND due_at <= ? \
+         ORDER BY due_at ASC LIMIT ?",
+        SELECT_COLUMNS
+    ))
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list due verify entries: {}", err))?;
+
+    Ok(rows.into_iter().map(from_tuple).collect())
+}
+
+// 已丢失、尚未处理的记录（新的在前）
+pub async fn list_lost(pool: &SqlitePool) -> Result<Vec<VerifyQueueRow>, String> {
+    let rows = sqlx::query_as::<_, VerifyQueueTuple>(&format!(
+        "SELECT {} FROM verify_queue WHERE status = 'lost' ORDER BY created_at DESC",
+        SELECT_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|err| format!("Failed to list lost verify entries: {}", err))?;
+
+    Ok(rows.into_iter().map(from_tuple).collect())
+}
+
+pub async fn get_verify_entry(
+    pool: &SqlitePool,
+    id: i64,
+) -> Result<Option<VerifyQueueRow>, String> {
+    let row = sqlx::query_as::<_, VerifyQueueTuple>(&format!(
+        "SELECT {} FROM verify_queue WHERE id = ?",
+        SELECT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| format!("Failed to load verify entry: {}", err))?;
+
+    Ok(row.map(from_tuple))
+}
+
+// 标记校验结果（verified / lost）
+pub async fn mark_status(
+    pool: &SqlitePool,
+    id: i64,
+    status: &str,
+    reason: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE verify_queue SET status = ?, reason = ? WHERE id = ?")
+        .bind(status)
+        .bind(reason)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to update verify entry status: {}", err))?;
+
+    Ok(())
+}
+
+// 推迟校验（页面暂时拉取失败时）
+pub async fn postpone(pool: &SqlitePool, id: i64, due_at: i64) -> Result<(), String> {
+    sqlx::query("UPDATE verify_queue SET due_at = ? WHERE id = ?")
+        .bind(due_at)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to postpone verify entry: {}", err))?;
+
+    Ok(())
+}
+
+pub async fn delete_verify_entry(pool: &SqlitePool, id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM verify_queue WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|err| format!("Failed to delete verify entry: {}", err))?;
+
+    Ok(())
+}
+
+// 删除已校验的记录，以及创建时间早于 before 的全部记录，返回删除条数
+pub async fn prune_verify_queue(pool: &SqlitePool, before: i64) -> Result<u64, String> {
+    let result =
+        sqlx::query("DELETE FROM verify_queue WHERE status = 'verified' OR created_at < ?")
+            .bind(before)
+            .execute(pool)
+            .await
+            .map_err(|err| format!("Failed to prune verify queue: {}", err))?;
+
+    Ok(result.rows_affected())
+}
@@ -1,10 +1,15 @@
-use crate::{defer::WarnDefer, http::moetran_get};
+use crate::{
+    defer::WarnDefer,
+    http::{moetran_get, moetran_get_page},
+    ids::TeamId,
+    pagination::Page,
+};
 use serde::{Deserialize, Serialize};
 
 // 汉化组 DTO
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ResTeam {
-    pub id: String,
+    pub id: TeamId,
     pub avatar: String,
     pub has_avatar: bool,
     pub name: String,
@@ -39,3 +44,30 @@ pub async fn get_user_teams(payload: GetUserTeamsReq) -> Result<Vec<ResTeam>, St
 
     Ok(list)
 }
+
+// 同 get_user_teams，附带分页信息；get_user_teams 保留给尚未迁移的调用方
+#[tauri::command]
+pub async fn get_user_teams_page(payload: GetUserTeamsReq) -> Result<Page<ResTeam>, String> {
+    tracing::info!(
+        page = payload.page,
+        limit = payload.limit,
+        "user.teams.page.request.start"
+    );
+
+    let mut defer = WarnDefer::new("user.teams.page.request");
+
+    let page = moetran_get_page::<ResTeam>("user/teams", None, payload.page, payload.limit)
+        .await
+        .map_err(|err| format!("获取用户汉化组失败: {}", err))?;
+
+    tracing::info!(
+        count = page.items.len(),
+        total = ?page.total,
+        has_more = page.has_more,
+        "user.teams.page.request.ok"
+    );
+
+    defer.success();
+
+    Ok(page)
+}
@@ -0,0 +1,87 @@
+// 测试用的 mock 后端：启动本地 HTTP 服务（wiremock）作为 Moetran 与 PopRaKo，把 API 地址指向它们，
+// 使 moetran_* / poprako_* 走完整的 ApiClient 发送与响应解析流程。
+// 配置与 token 是进程级的全局状态，MockBackends 持有期间其余使用它的测试会排队等待
+use serde_json::json;
+use tokio::sync::{Mutex, MutexGuard, OnceCell};
+use wiremock::MockServer;
+
+use crate::{
+    config, http,
+    project::ResProjectEnriched,
+    storage::{LocalStorage, LOCAL_STORAGE},
+    token,
+};
+
+pub(crate) const TEST_MOETRAN_TOKEN: &str = "test-moetran-token";
+pub(crate) const TEST_POPRAKO_TOKEN: &str = "test-poprako-token";
+
+static BACKENDS_LOCK: Mutex<()> = Mutex::const_new(());
+static STORAGE_INIT: OnceCell<()> = OnceCell::const_new();
+
+pub(crate) struct MockBackends {
+    pub moetran: MockServer,
+    pub poprako: MockServer,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl MockBackends {
+    // 两个后端的 base URL 均为 {mock 地址}/v1/，mock 的路径应以 /v1/ 开头
+    pub(crate) async fn start() -> Self {
+        let guard = BACKENDS_LOCK.lock().await;
+
+        let moetran = MockServer::start().await;
+        let poprako = MockServer::start().await;
+
+        config::update_for_test(|config| {
+            config.moetran_api_base = format!("{}/v1/", moetran.uri());
+            config.poprako_api_base = format!("{}/v1/", poprako.uri());
+            config.offline_mode = false;
+            config.demo_mode = false;
+        });
+
+        http::set_provider(None);
+        token::use_demo_tokens(Some((TEST_MOETRAN_TOKEN, TEST_POPRAKO_TOKEN)));
+
+        Self {
+            moetran,
+            poprako,
+            _guard: guard,
+        }
+    }
+}
+
+// 在临时目录中初始化全局 LOCAL_STORAGE（整个测试进程共用一个库），
+// 使用它的测试应使用互不冲突的 id，或在持有 MockBackends 期间清理相关表
+pub(crate) async fn local_storage() -> &'static LocalStorage {
+    STORAGE_INIT
+        .get_or_init(|| async {
+            let dir = tempfile::tempdir().unwrap().keep();
+            let path = dir.join("test.db");
+
+            LocalStorage::init(&path.to_string_lossy()).await.unwrap();
+        })
+        .await;
+
+    LOCAL_STORAGE.get().unwrap()
+}
+
+// 用于 enriched 列表相关测试的项目数据
+pub(crate) fn enriched_fixture(id: &str, team_id: &str) -> ResProjectEnriched {
+    serde_json::from_value(json!({
+        "id": id,
+        "name": format!("项目 {}", id),
+        "source_count": 10,
+        "translated_source_count": 5,
+        "checked_source_count": 2,
+        "team": { "id": team_id, "avatar": "", "has_avatar": false, "name": "汉化组" },
+        "project_set": { "id": "default", "name": "默认项目集" },
+        "has_poprako": true,
+        "projset_index": 1,
+        "translating_status": 1,
+        "proofreading_status": 0,
+        "typesetting_status": 0,
+        "reviewing_status": 0,
+        "is_published": false,
+    }))
+    .unwrap()
+}
@@ -0,0 +1,586 @@
+// 项目文字量统计（按字数结算稿费用）：遍历项目某 target 下全部翻译，按成员或文件汇总
+// 中日韩字符数、拉丁单词数与行数；校对修改单独统计（按与原译文的差异部分计）。
+// 默认只统计被选定的翻译；结果按固定顺序排列，数据不变时重复统计结果一致
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    contributions::fetch_file_sources,
+    defer::WarnDefer,
+    project::{get_project_files, GetProjectFilesReq, MoetranSource, MoetranUserBrief},
+    source_snapshot::load_snapshot,
+    usage::csv_field,
+};
+
+// 同时拉取 sources 的文件数上限
+const TEXT_STATS_FETCH_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TextMetrics {
+    // 汉字、假名、谚文（不含标点）
+    pub cjk_chars: u64,
+    // 连续的拉丁字母 / 数字（可含词内的 ' 与 -）
+    pub latin_words: u64,
+    // 非空行数
+    pub lines: u64,
+}
+
+impl TextMetrics {
+    fn add(&mut self, other: TextMetrics) {
+        self.cjk_chars += other.cjk_chars;
+        self.latin_words += other.latin_words;
+        self.lines += other.lines;
+    }
+}
+
+// 假名中排除 ゠ 与中点 ・；长音符 ー 与叠字符号 々 算作字符
+fn is_cjk_char(c: char) -> bool {
+    matches!(c,
+        '\u{3005}'
+        | '\u{3041}'..='\u{309F}'
+        | '\u{30A1}'..='\u{30FA}'
+        | '\u{30FC}'..='\u{30FF}'
+        | '\u{31F0}'..='\u{31FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FF9D}'
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
+// 拉丁单词内可出现的字符（全角字母数字也计入）
+fn is_latin_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(c, '\u{00C0}'..='\u{024F}' | '\u{FF10}'..='\u{FF19}' | '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}')
+}
+
+// 统计一段文本
+pub(crate) fn measure(text: &str) -> TextMetrics {
+    let mut metrics = TextMetrics::default();
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        metrics.lines += 1;
+
+        let mut in_word = false;
+        let mut prev_word_char = false;
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if is_cjk_char(c) {
+                metrics.cjk_chars += 1;
+                in_word = false;
+                prev_word_char = false;
+                continue;
+            }
+
+            if is_latin_word_char(c) {
+                if !in_word {
+                    metrics.latin_words += 1;
+                    in_word = true;
+                }
+                prev_word_char = true;
+                continue;
+            }
+
+            // 词内的撇号 / 连字符（如 don't、re-run）不拆分单词
+            let joins_word = matches!(c, '\'' | '\u{2019}' | '-')
+                && prev_word_char
+                && chars.peek().is_some_and(|next| is_latin_word_char(*next));
+
+            if !joins_word {
+                in_word = false;
+            }
+            prev_word_char = false;
+        }
+    }
+
+    metrics
+}
+
+// 校对新增的部分：去掉与原译文相同的前缀与后缀（按字符），剩余部分即为改动，计入校对统计。
+// 只有删除的改动计为 0
+pub(crate) fn proofread_delta(original: &str, proofread: &str) -> TextMetrics {
+    let original: Vec<char> = original.chars().collect();
+    let proofread: Vec<char> = proofread.chars().collect();
+
+    let prefix = original
+        .iter()
+        .zip(&proofread)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let suffix = original[prefix..]
+        .iter()
+        .rev()
+        .zip(proofread[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let changed: String = proofread[prefix..proofread.len() - suffix].iter().collect();
+
+    measure(&changed)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextStatsGroupBy {
+    Member,
+    File,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TextStatsRow {
+    // 成员 id 或文件 id；作者信息缺失时为 None
+    pub key: Option<String>,
+    pub name: String,
+    // 计入统计的翻译数
+    pub translations: u64,
+    pub translated: TextMetrics,
+    // 有校对修改的翻译数
+    pub proofreads: u64,
+    // 校对相对原译文新增 / 改写的部分
+    pub proofread_added: TextMetrics,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TextStatsReport {
+    pub project_id: String,
+    pub target_id: String,
+    pub group_by: TextStatsGroupBy,
+    pub include_unselected: bool,
+    pub file_count: usize,
+    pub source_count: usize,
+    // 成员按 id 排序（作者未知的排在最后）；文件按项目中的顺序
+    pub rows: Vec<TextStatsRow>,
+    pub totals: TextStatsRow,
+    // 拉取失败、改用本地快照统计的文件 id；非空时结果可能不是最新
+    pub stale_files: Vec<String>,
+    // 写入的 CSV 路径
+    pub csv_path: Option<String>,
+}
+
+fn member_key(user: Option<&MoetranUserBrief>) -> (Option<String>, String) {
+    match user {
+        Some(user) if !user.id.is_empty() => (Some(user.id.to_string()), user.name.clone()),
+        _ => (None, "未知".to_string()),
+    }
+}
+
+// 分组行；成员名取首次出现（按文件、source 顺序）的名字，保证结果稳定
+struct Groups {
+    rows: BTreeMap<(bool, Option<String>), TextStatsRow>,
+}
+
+impl Groups {
+    fn row(&mut self, key: Option<String>, name: String) -> &mut TextStatsRow {
+        self.rows
+            .entry((key.is_none(), key.clone()))
+            .or_insert_with(|| TextStatsRow {
+                key,
+                name,
+                ..Default::default()
+            })
+    }
+}
+
+pub(crate) struct FileSources {
+    pub file_id: String,
+    pub file_name: String,
+    pub sources: Vec<MoetranSource>,
+    // 拉取失败、改用本地快照
+    pub stale: bool,
+}
+
+fn tally_file(
+    groups: &mut Groups,
+    file: &FileSources,
+    group_by: TextStatsGroupBy,
+    include_unselected: bool,
+) {
+    let file_group = || (Some(file.file_id.clone()), file.file_name.clone());
+
+    for source in &file.sources {
+        // my_translation 通常也出现在 translations 中，按 id 去重
+        let mut seen = HashSet::new();
+
+        for translation in source
+            .translations
+            .iter()
+            .chain(source.my_translation.iter())
+        {
+            if !seen.insert(translation.id.as_str()) {
+                continue;
+            }
+
+            if !translation.selected && !include_unselected {
+                continue;
+            }
+
+            let (key, name) = match group_by {
+                TextStatsGroupBy::Member => member_key(translation.user.as_ref()),
+                TextStatsGroupBy::File => file_group(),
+            };
+
+            let row = groups.row(key, name);
+            row.translations += 1;
+            row.translated.add(measure(&translation.content));
+
+            let Some(proofread) = translation
+                .proofread_content
+                .as_deref()
+                .filter(|content| !content.trim().is_empty())
+            else {
+                continue;
+            };
+
+            let (key, name) = match group_by {
+                TextStatsGroupBy::Member => member_key(translation.proofreader.as_ref()),
+                TextStatsGroupBy::File => file_group(),
+            };
+
+            let row = groups.row(key, name);
+            row.proofreads += 1;
+            row.proofread_added
+                .add(proofread_delta(&translation.content, proofread));
+        }
+    }
+}
+
+fn to_csv(report: &TextStatsReport) -> String {
+    let mut csv = String::from(
+        "key,name,translations,cjk_chars,latin_words,lines,proofreads,proofread_cjk_chars,proofread_latin_words,proofread_lines\n",
+    );
+
+    for row in report.rows.iter().chain(std::iter::once(&report.totals)) {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            csv_field(row.key.as_deref().unwrap_or_default()),
+            csv_field(&row.name),
+            row.translations,
+            row.translated.cjk_chars,
+            row.translated.latin_words,
+            row.translated.lines,
+            row.proofreads,
+            row.proofread_added.cjk_chars,
+            row.proofread_added.latin_words,
+            row.proofread_added.lines,
+        ));
+    }
+
+    csv
+}
+
+// 按文件顺序拉取项目某 target 下全部文件的 sources（限制并发；单个文件失败时改用本地快照）
+pub(crate) async fn load_project_sources(
+    project_id: &str,
+    target_id: &str,
+) -> Result<Vec<FileSources>, String> {
+    let files = get_project_files(GetProjectFilesReq {
+        project_id: project_id.into(),
+        target_id: Some(target_id.into()),
+        sort: None,
+    })
+    .await?;
+
+    let semaphore = Arc::new(Semaphore::new(TEXT_STATS_FETCH_CONCURRENCY));
+    let mut set = JoinSet::new();
+
+    for (index, file) in files.iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let file_id = file.id.clone();
+        let file_name = file.name.clone();
+        let target_id = target_id.to_string();
+
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (
+                index,
+                load_file_sources(file_id.into(), file_name, target_id).await,
+            )
+        });
+    }
+
+    // 按文件顺序排列，保证结果与完成顺序无关
+    let mut loaded: Vec<Option<FileSources>> = files.iter().map(|_| None).collect();
+
+    while let Some(joined) = set.join_next().await {
+        let (index, result) = joined.map_err(|err| format!("拉取任务异常: {}", err))?;
+        loaded[index] = Some(result?);
+    }
+
+    Ok(loaded.into_iter().flatten().collect())
+}
+
+// 拉取单个文件的 sources；失败时改用本地快照（没有快照则返回错误）
+async fn load_file_sources(
+    file_id: String,
+    file_name: String,
+    target_id: String,
+) -> Result<FileSources, String> {
+    match fetch_file_sources(file_id.clone(), target_id.clone()).await {
+        Ok(sources) => Ok(FileSources {
+            file_id,
+            file_name,
+            sources,
+            stale: false,
+        }),
+        Err(err) => match load_snapshot(&file_id, &target_id).await {
+            Some(sources) => {
+                tracing::warn!(%file_id, error = %err, "text_stats.sources.snapshot_fallback");
+
+                Ok(FileSources {
+                    file_id,
+                    file_name,
+                    sources,
+                    stale: true,
+                })
+            }
+            None => Err(err),
+        },
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetProjectTextStatsReq {
+    pub project_id: String,
+    pub target_id: String,
+    pub group_by: TextStatsGroupBy,
+    // 为 true 时未被选定的翻译也计入
+    #[serde(default)]
+    pub include_unselected: bool,
+    // 提供时同时导出 CSV（最后一行为合计）
+    #[serde(default)]
+    pub csv_path: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_project_text_stats(
+    payload: GetProjectTextStatsReq,
+) -> Result<TextStatsReport, String> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        group_by = ?payload.group_by,
+        include_unselected = payload.include_unselected,
+        "moetran.project.text_stats.start"
+    );
+
+    let mut defer = WarnDefer::new("moetran.project.text_stats");
+
+    let files = load_project_sources(&payload.project_id, &payload.target_id).await?;
+
+    let mut groups = Groups {
+        rows: BTreeMap::new(),
+    };
+    let mut source_count = 0;
+    let mut stale_files = Vec::new();
+
+    for file in &files {
+        source_count += file.sources.len();
+
+        if file.stale {
+            stale_files.push(file.file_id.clone());
+        }
+
+        tally_file(
+            &mut groups,
+            file,
+            payload.group_by,
+            payload.include_unselected,
+        );
+    }
+
+    let mut rows: Vec<TextStatsRow> = groups.rows.into_values().collect();
+
+    if payload.group_by == TextStatsGroupBy::File {
+        rows.sort_by_key(|row| {
+            files
+                .iter()
+                .position(|file| Some(&file.file_id) == row.key.as_ref())
+        });
+    }
+
+    let mut totals = TextStatsRow {
+        key: None,
+        name: "合计".to_string(),
+        ..Default::default()
+    };
+
+    for row in &rows {
+        totals.translations += row.translations;
+        totals.translated.add(row.translated);
+        totals.proofreads += row.proofreads;
+        totals.proofread_added.add(row.proofread_added);
+    }
+
+    let mut report = TextStatsReport {
+        project_id: payload.project_id.clone(),
+        target_id: payload.target_id.clone(),
+        group_by: payload.group_by,
+        include_unselected: payload.include_unselected,
+        file_count: files.len(),
+        source_count,
+        rows,
+        totals,
+        stale_files,
+        csv_path: None,
+    };
+
+    if let Some(path) = payload.csv_path {
+        tokio::fs::write(&path, to_csv(&report))
+            .await
+            .map_err(|err| format!("写入 CSV 失败: {}", err))?;
+
+        report.csv_path = Some(path);
+    }
+
+    tracing::info!(
+        project_id = %payload.project_id,
+        rows = report.rows.len(),
+        cjk_chars = report.totals.translated.cjk_chars,
+        stale_files = report.stale_files.len(),
+        "moetran.project.text_stats.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn metrics(cjk_chars: u64, latin_words: u64, lines: u64) -> TextMetrics {
+        TextMetrics {
+            cjk_chars,
+            latin_words,
+            lines,
+        }
+    }
+
+    #[test]
+    fn counts_cjk_characters_words_and_non_empty_lines() {
+        assert_eq!(
+            measure("你好 world, don't re-run!\n\n  \nカタカナー・テスト"),
+            metrics(10, 3, 2)
+        );
+
+        // 标点不计字数，全角字母数字算作单词
+        assert_eq!(measure("「嗯，好。」ＯＫ１ -- 'quoted'"), metrics(2, 2, 1));
+        assert_eq!(measure("end-"), metrics(0, 1, 1));
+    }
+
+    #[test]
+    fn proofread_delta_counts_only_the_changed_part() {
+        assert_eq!(
+            proofread_delta("今天天气好", "今天天气真好"),
+            metrics(1, 0, 1)
+        );
+        assert_eq!(
+            proofread_delta("今天天气好", "今天好"),
+            TextMetrics::default()
+        );
+        assert_eq!(proofread_delta("相同", "相同"), TextMetrics::default());
+        assert_eq!(proofread_delta("原文", "完全不同的 text"), metrics(5, 1, 1));
+    }
+
+    fn file(file_id: &str) -> FileSources {
+        let translation = |id: &str,
+                           user: &str,
+                           content: &str,
+                           selected: bool,
+                           proofread: Value| {
+            json!({
+                "id": id,
+                "content": content,
+                "proofread_content": proofread,
+                "selected": selected,
+                "user": if user.is_empty() { Value::Null } else { json!({ "id": user, "name": user.to_uppercase() }) },
+                "proofreader": { "id": "p1", "name": "P1" },
+            })
+        };
+
+        let own = translation("t1", "u1", "第一句", true, json!("第一句话"));
+        let sources = json!([{
+            "id": "s1", "x": 0.1, "y": 0.1, "position_type": 1,
+            "my_translation": own,
+            "translations": [
+                own,
+                translation("t2", "u2", "没选定", false, Value::Null),
+                translation("t3", "", "匿名", true, json!("  ")),
+            ],
+        }]);
+
+        FileSources {
+            file_id: file_id.to_string(),
+            file_name: format!("{}.png", file_id),
+            sources: serde_json::from_value(sources).unwrap(),
+            stale: false,
+        }
+    }
+
+    fn rows(group_by: TextStatsGroupBy, include_unselected: bool) -> Vec<TextStatsRow> {
+        let mut groups = Groups {
+            rows: BTreeMap::new(),
+        };
+
+        for id in ["f1", "f2"] {
+            tally_file(&mut groups, &file(id), group_by, include_unselected);
+        }
+
+        groups.rows.into_values().collect()
+    }
+
+    #[test]
+    fn member_rows_credit_translators_and_proofreaders() {
+        let rows = rows(TextStatsGroupBy::Member, false);
+        let summary: Vec<(Option<&str>, &str, u64, u64, u64)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.key.as_deref(),
+                    row.name.as_str(),
+                    row.translations,
+                    row.translated.cjk_chars,
+                    row.proofreads,
+                )
+            })
+            .collect();
+
+        // my_translation 不重复计数，未选定的不计入，空白校对不算修改，作者未知的排在最后
+        assert_eq!(
+            summary,
+            [
+                (Some("p1"), "P1", 0, 0, 2),
+                (Some("u1"), "U1", 2, 6, 0),
+                (None, "未知", 2, 4, 0),
+            ]
+        );
+        assert_eq!(rows[0].proofread_added, metrics(2, 0, 2));
+    }
+
+    #[test]
+    fn unselected_translations_can_be_included() {
+        let rows = rows(TextStatsGroupBy::File, true);
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows
+            .iter()
+            .all(|row| row.translations == 3 && row.proofreads == 1));
+        assert_eq!(rows[0].name, "f1.png");
+        assert_eq!(rows[0].translated.cjk_chars, 8);
+    }
+}
@@ -1,13 +1,70 @@
-use std::sync::RwLock;
+// 内存中的 token 放在 watch 通道里：读取只短暂借用当前值，写入整体替换，
+// 不会像 RwLock 那样因持锁时 panic 而中毒，导致之后所有请求都失败
+use std::sync::LazyLock;
+
+use reqwest::header::HeaderValue;
+use tokio::sync::watch;
 
 use crate::{
+    config::config,
+    connectivity::Backend,
     defer::WarnDefer,
+    member, session,
     storage::{token as storage_token, LOCAL_STORAGE},
 };
 
-static MOETRAN_TOKEN: RwLock<Option<String>> = RwLock::new(None);
+// token 与写入时构造好的 Authorization 头（http 层直接复用，不再逐个请求拼接）
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    auth_header: Result<HeaderValue, String>,
+}
+
+impl CachedToken {
+    fn new(token: String) -> Self {
+        let auth_header =
+            HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|err| err.to_string());
+
+        Self { token, auth_header }
+    }
+}
+
+type TokenSlot = watch::Sender<Option<CachedToken>>;
 
-static POPRAKO_TOKEN: RwLock<Option<String>> = RwLock::new(None);
+static MOETRAN_TOKEN: LazyLock<TokenSlot> = LazyLock::new(|| watch::Sender::new(None));
+
+static POPRAKO_TOKEN: LazyLock<TokenSlot> = LazyLock::new(|| watch::Sender::new(None));
+
+fn store_token(slot: &TokenSlot, token: Option<String>) {
+    slot.send_replace(token.map(CachedToken::new));
+}
+
+fn token_slot(backend: Backend) -> &'static TokenSlot {
+    match backend {
+        Backend::Moetran => &MOETRAN_TOKEN,
+        Backend::Poprako => &POPRAKO_TOKEN,
+    }
+}
+
+// token 变更订阅：每次写入（登录、刷新、登出、401 后清除、切换演示模式）后收到通知
+pub(crate) struct TokenChanges(watch::Receiver<Option<CachedToken>>);
+
+impl TokenChanges {
+    // 等待下一次写入，返回写入后的 token（清除时为 None）
+    pub(crate) async fn changed(&mut self) -> Option<String> {
+        // 发送端是静态变量，不会关闭
+        let _ = self.0.changed().await;
+
+        self.0
+            .borrow_and_update()
+            .as_ref()
+            .map(|cached| cached.token.clone())
+    }
+}
+
+pub(crate) fn subscribe(backend: Backend) -> TokenChanges {
+    TokenChanges(token_slot(backend).subscribe())
+}
 
 // 获取 Moetran token（从内存或数据库）
 #[tauri::command]
@@ -17,18 +74,19 @@ pub async fn get_moetran_token() -> Result<Option<String>, String> {
     let mut defer = WarnDefer::new("token.get_moetran");
 
     // 先检查内存缓存
-    {
-        let guard = MOETRAN_TOKEN
-            .read()
-            .map_err(|err| format!("Failed to read MOETRAN_TOKEN: {}", err))?;
+    if let Some(token) = cached_moetran_token() {
+        tracing::info!("token.get_moetran.ok");
 
-        if let Some(token) = &*guard {
-            tracing::info!("token.get_moetran.ok");
+        defer.success();
 
-            defer.success();
+        return Ok(Some(token));
+    }
 
-            return Ok(Some(token.clone()));
-        }
+    // 演示模式下不读取数据库中的真实 token
+    if config().demo_mode {
+        defer.success();
+
+        return Ok(None);
     }
 
     // 内存中没有，尝试从数据库加载
@@ -39,11 +97,7 @@ pub async fn get_moetran_token() -> Result<Option<String>, String> {
     match storage_token::get_moetran_token(storage.pool()).await {
         Ok(token) => {
             // 加载成功后更新内存缓存
-            let mut guard = MOETRAN_TOKEN
-                .write()
-                .map_err(|err| format!("Failed to write MOETRAN_TOKEN: {}", err))?;
-
-            *guard = Some(token.clone());
+            store_token(&MOETRAN_TOKEN, Some(token.clone()));
 
             tracing::info!("token.get_moetran.ok");
 
@@ -71,15 +125,15 @@ pub async fn save_moetran_token(token: String) -> Result<(), String> {
         .get()
         .ok_or("LOCAL_STORAGE not initialized".to_string())?;
 
-    // 保存到数据库
-    storage_token::save_moetran_token(storage.pool(), &token).await?;
+    // 保存到数据库（演示模式下只修改内存，保留真实 token）
+    if !config().demo_mode {
+        storage_token::save_moetran_token(storage.pool(), &token).await?;
+    }
 
     // 更新内存缓存
-    let mut guard = MOETRAN_TOKEN
-        .write()
-        .map_err(|err| format!("Failed to write MOETRAN_TOKEN: {}", err))?;
+    store_token(&MOETRAN_TOKEN, Some(token));
 
-    *guard = Some(token);
+    session::reset_identity();
 
     tracing::info!("token.save_moetran.ok");
 
@@ -99,15 +153,15 @@ pub async fn remove_moetran_token() -> Result<(), String> {
         .get()
         .ok_or("LOCAL_STORAGE not initialized".to_string())?;
 
-    // 从数据库删除
-    storage_token::remove_moetran_token(storage.pool()).await?;
+    // 从数据库删除（演示模式下只修改内存，保留真实 token）
+    if !config().demo_mode {
+        storage_token::remove_moetran_token(storage.pool()).await?;
+    }
 
     // 清空内存缓存
-    let mut guard = MOETRAN_TOKEN
-        .write()
-        .map_err(|err| format!("Failed to write MOETRAN_TOKEN: {}", err))?;
+    store_token(&MOETRAN_TOKEN, None);
 
-    *guard = None;
+    session::reset_identity();
 
     tracing::info!("token.remove_moetran.ok");
 
@@ -124,18 +178,19 @@ pub async fn get_poprako_token() -> Result<Option<String>, String> {
     let mut defer = WarnDefer::new("token.get_poprako");
 
     // 先检查内存缓存
-    {
-        let guard = POPRAKO_TOKEN
-            .read()
-            .map_err(|err| format!("Failed to read POPRAKO_TOKEN: {}", err))?;
+    if let Some(token) = cached_poprako_token() {
+        tracing::info!("token.get_poprako.ok");
 
-        if let Some(token) = &*guard {
-            tracing::info!("token.get_poprako.ok");
+        defer.success();
 
-            defer.success();
+        return Ok(Some(token));
+    }
 
-            return Ok(Some(token.clone()));
-        }
+    // 演示模式下不读取数据库中的真实 token
+    if config().demo_mode {
+        defer.success();
+
+        return Ok(None);
     }
 
     // 内存中没有，尝试从数据库加载
@@ -146,11 +201,7 @@ pub async fn get_poprako_token() -> Result<Option<String>, String> {
     match storage_token::get_poprako_token(storage.pool()).await {
         Ok(token) => {
             // 加载成功后更新内存缓存
-            let mut guard = POPRAKO_TOKEN
-                .write()
-                .map_err(|err| format!("Failed to write POPRAKO_TOKEN: {}", err))?;
-
-            *guard = Some(token.clone());
+            store_token(&POPRAKO_TOKEN, Some(token.clone()));
 
             tracing::info!("token.get_poprako.ok");
 
@@ -178,15 +229,16 @@ pub async fn save_poprako_token(token: String) -> Result<(), String> {
         .get()
         .ok_or("LOCAL_STORAGE not initialized".to_string())?;
 
-    // 保存到数据库
-    storage_token::save_poprako_token(storage.pool(), &token).await?;
+    // 保存到数据库（演示模式下只修改内存，保留真实 token）
+    if !config().demo_mode {
+        storage_token::save_poprako_token(storage.pool(), &token).await?;
+    }
 
     // 更新内存缓存
-    let mut guard = POPRAKO_TOKEN
-        .write()
-        .map_err(|err| format!("Failed to write POPRAKO_TOKEN: {}", err))?;
+    store_token(&POPRAKO_TOKEN, Some(token));
 
-    *guard = Some(token);
+    session::reset_identity();
+    member::forget_all_member_info();
 
     tracing::info!("token.save_poprako.ok");
 
@@ -206,15 +258,16 @@ pub async fn remove_poprako_token() -> Result<(), String> {
         .get()
         .ok_or("LOCAL_STORAGE not initialized".to_string())?;
 
-    // 从数据库删除
-    storage_token::remove_poprako_token(storage.pool()).await?;
+    // 从数据库删除（演示模式下只修改内存，保留真实 token）
+    if !config().demo_mode {
+        storage_token::remove_poprako_token(storage.pool()).await?;
+    }
 
     // 清空内存缓存
-    let mut guard = POPRAKO_TOKEN
-        .write()
-        .map_err(|err| format!("Failed to write POPRAKO_TOKEN: {}", err))?;
+    store_token(&POPRAKO_TOKEN, None);
 
-    *guard = None;
+    session::reset_identity();
+    member::forget_all_member_info();
 
     tracing::info!("token.remove_poprako.ok");
 
@@ -223,10 +276,105 @@ pub async fn remove_poprako_token() -> Result<(), String> {
     Ok(())
 }
 
+// 读取只借用当前值并立即克隆，不会等待写入方
 pub(crate) fn cached_moetran_token() -> Option<String> {
-    MOETRAN_TOKEN.read().ok().and_then(|guard| guard.clone())
+    MOETRAN_TOKEN
+        .borrow()
+        .as_ref()
+        .map(|cached| cached.token.clone())
+}
+
+// 后端返回 401 后清除内存中的 token（数据库中的记录保留，重新登录时覆盖）
+pub(crate) fn forget_cached_token(backend: Backend) {
+    store_token(token_slot(backend), None);
 }
 
 pub(crate) fn cached_poprako_token() -> Option<String> {
-    POPRAKO_TOKEN.read().ok().and_then(|guard| guard.clone())
+    POPRAKO_TOKEN
+        .borrow()
+        .as_ref()
+        .map(|cached| cached.token.clone())
+}
+
+// 当前 token 的 Authorization 头；没有 token 时为 None，token 含非法字符时为 Err
+pub(crate) fn moetran_auth_header() -> Option<Result<HeaderValue, String>> {
+    MOETRAN_TOKEN
+        .borrow()
+        .as_ref()
+        .map(|cached| cached.auth_header.clone())
+}
+
+pub(crate) fn poprako_auth_header() -> Option<Result<HeaderValue, String>> {
+    POPRAKO_TOKEN
+        .borrow()
+        .as_ref()
+        .map(|cached| cached.auth_header.clone())
+}
+
+// 切换演示模式时替换内存中的 token：开启时使用假 token（不落盘），
+// 关闭时清空，之后按需从数据库重新加载真实 token
+pub(crate) fn use_demo_tokens(tokens: Option<(&str, &str)>) {
+    let (moetran, poprako) = match tokens {
+        Some((moetran, poprako)) => (Some(moetran.to_string()), Some(poprako.to_string())),
+        None => (None, None),
+    };
+
+    store_token(&MOETRAN_TOKEN, moetran);
+    store_token(&POPRAKO_TOKEN, poprako);
+
+    session::reset_identity();
+    member::forget_all_member_info();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        local_storage, MockBackends, TEST_MOETRAN_TOKEN, TEST_POPRAKO_TOKEN,
+    };
+
+    #[tokio::test]
+    async fn panicking_writers_do_not_break_later_reads_or_writes() {
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+
+        // 持有读借用时 panic
+        let reader = tokio::spawn(async {
+            let _borrowed = MOETRAN_TOKEN.borrow();
+            panic!("reader panicked while holding the token");
+        });
+        assert!(reader.await.unwrap_err().is_panic());
+
+        // 写入过程中 panic
+        let writer = tokio::spawn(async {
+            POPRAKO_TOKEN.send_modify(|_| panic!("writer panicked while updating the token"));
+        });
+        assert!(writer.await.unwrap_err().is_panic());
+
+        let mut changes = subscribe(Backend::Moetran);
+
+        assert_eq!(cached_moetran_token().as_deref(), Some(TEST_MOETRAN_TOKEN));
+        assert_eq!(cached_poprako_token().as_deref(), Some(TEST_POPRAKO_TOKEN));
+
+        save_moetran_token("token-after-panic".to_string())
+            .await
+            .unwrap();
+        save_poprako_token("poprako-after-panic".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            changes.changed().await.as_deref(),
+            Some("token-after-panic")
+        );
+        assert_eq!(cached_moetran_token().as_deref(), Some("token-after-panic"));
+        assert_eq!(
+            cached_poprako_token().as_deref(),
+            Some("poprako-after-panic")
+        );
+        assert!(moetran_auth_header().unwrap().is_ok());
+        assert!(poprako_auth_header().unwrap().is_ok());
+
+        use_demo_tokens(Some((TEST_MOETRAN_TOKEN, TEST_POPRAKO_TOKEN)));
+    }
 }
@@ -0,0 +1,162 @@
+// PopRaKo 创建请求前的 Moetran token 检查：create_projset / create_proj 会把 Moetran token 转交给 PopRaKo，
+// token 过期时 PopRaKo 在内部调用 Moetran 失败，只返回笼统的 500，容易被误认为 PopRaKo 故障。
+// 发送前先用 user/info 探测一次（结果按 token 缓存几分钟），失效时直接返回 moetran_token_expired 错误
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+
+use crate::{http::moetran_get, token::get_moetran_token};
+
+const TOKEN_EXPIRED_CODE: &str = "moetran_token_expired";
+
+// 探测结果的缓存时间
+const PROBE_TTL: Duration = Duration::from_secs(5 * 60);
+
+struct Probe {
+    token: String,
+    checked_at: Instant,
+    valid: bool,
+}
+
+// 只缓存最近一次探测；重新登录后 token 变化，缓存自然失效
+static LAST_PROBE: Mutex<Option<Probe>> = Mutex::new(None);
+
+fn expired_error(message: &str) -> String {
+    serde_json::json!({
+        "code": TOKEN_EXPIRED_CODE,
+        "message": message,
+    })
+    .to_string()
+}
+
+fn cached_result(token: &str) -> Option<bool> {
+    let guard = LAST_PROBE.lock().ok()?;
+    let probe = guard.as_ref()?;
+
+    (probe.token == token && probe.checked_at.elapsed() < PROBE_TTL).then_some(probe.valid)
+}
+
+fn store_result(token: &str, valid: bool) {
+    if let Ok(mut guard) = LAST_PROBE.lock() {
+        *guard = Some(Probe {
+            token: token.to_string(),
+            checked_at: Instant::now(),
+            valid,
+        });
+    }
+}
+
+// Moetran 以 401 / 403 拒绝时视为 token 失效
+fn is_auth_rejection(err: &str) -> bool {
+    err.contains("status 401") || err.contains("status 403")
+}
+
+// 返回可转交给 PopRaKo 的 Moetran token；token 缺失或已失效时返回 moetran_token_expired 错误。
+// 探测因网络等原因失败时不拦截，由之后的请求给出实际错误
+pub(crate) async fn fresh_moetran_token() -> Result<String, String> {
+    let token = get_moetran_token()
+        .await?
+        .ok_or_else(|| expired_error("未找到 Moetran 登录信息，请重新登录"))?;
+
+    let valid = match cached_result(&token) {
+        Some(valid) => valid,
+        None => match moetran_get::<Value>("user/info", None).await {
+            Ok(_) => {
+                store_result(&token, true);
+                true
+            }
+            Err(err) if is_auth_rejection(&err.to_string()) => {
+                store_result(&token, false);
+                false
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "token.probe.inconclusive");
+                true
+            }
+        },
+    };
+
+    if !valid {
+        tracing::info!("token.probe.expired");
+
+        return Err(expired_error("Moetran 登录已过期，请重新登录后再创建"));
+    }
+
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        test_support::{MockBackends, TEST_POPRAKO_TOKEN},
+        token,
+    };
+
+    async fn mount_user_info(backends: &MockBackends, status: u16, times: u64) {
+        Mock::given(method("GET"))
+            .and(path("/v1/user/info"))
+            .respond_with(ResponseTemplate::new(status).set_body_json(json!({ "id": "u1" })))
+            .up_to_n_times(times)
+            .mount(&backends.moetran)
+            .await;
+    }
+
+    fn is_expired(result: Result<String, String>) -> bool {
+        result.is_err_and(|err| err.contains(TOKEN_EXPIRED_CODE))
+    }
+
+    #[tokio::test]
+    async fn probe_result_is_cached_per_token() {
+        let backends = MockBackends::start().await;
+        *LAST_PROBE.lock().unwrap() = None;
+
+        // 403 不会清除 token，便于观察缓存
+        mount_user_info(&backends, 403, 1).await;
+        mount_user_info(&backends, 200, 1).await;
+
+        assert!(is_expired(fresh_moetran_token().await));
+        assert!(is_expired(fresh_moetran_token().await));
+
+        // 重新登录后 token 变化，重新探测
+        token::use_demo_tokens(Some(("probe-new-token", TEST_POPRAKO_TOKEN)));
+        assert_eq!(fresh_moetran_token().await.unwrap(), "probe-new-token");
+        assert_eq!(fresh_moetran_token().await.unwrap(), "probe-new-token");
+
+        assert_eq!(backends.moetran.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn inconclusive_probe_does_not_block_or_cache() {
+        let backends = MockBackends::start().await;
+        *LAST_PROBE.lock().unwrap() = None;
+
+        mount_user_info(&backends, 404, 2).await;
+
+        assert!(fresh_moetran_token().await.is_ok());
+        assert!(fresh_moetran_token().await.is_ok());
+        assert_eq!(backends.moetran.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn only_auth_statuses_count_as_rejection() {
+        assert!(is_auth_rejection(
+            "http error: status 401 Unauthorized body: x"
+        ));
+        assert!(is_auth_rejection(
+            "http error: status 403 Forbidden body: x"
+        ));
+        assert!(!is_auth_rejection(
+            "http error: status 404 Not Found body: x"
+        ));
+    }
+}
@@ -0,0 +1,1018 @@
+// 项目翻译导出（LabelPlus 翻译稿 / 按页分文件的 ZIP / xlsx 表格）：逐页拉取 sources 并立即写入、落盘，
+// 每完成一页更新旁边的 "<输出文件>.partial.json"（页顺序、已完成页数与对应的文件长度）。
+// - labelplus：逐页追加到文本末尾
+// - zip：每页追加一个条目并重写目录，任何时刻已完成的部分都是可打开的归档
+// - xlsx：每页按已完成的全部行重写工作簿（行记录在进度文件中），先写临时文件再改名
+// 取消或中途出错时保留已写完的页并返回 partial = true；带 resume 重新导出时按该文件把输出恢复到
+// 最后一个完整页后继续，最终结果与一次性导出的内容逐字节相同。全部完成后删除该文件
+use std::{
+    collections::HashMap,
+    io::{Cursor, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+};
+
+use rust_xlsxwriter::{DocProperties, ExcelDateTime, Workbook};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs,
+    io::{AsyncSeekExt, AsyncWriteExt},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    clock::unix_now,
+    contributions::fetch_file_sources,
+    defer::WarnDefer,
+    natsort::compare_file_names,
+    position_type::PositionType,
+    project::{get_project_files, GetProjectFilesReq, MoetranSource},
+};
+
+// LabelPlus 文件头：版本、分组（组号从 1 开始）与备注
+const LABELPLUS_HEADER: &str = "1,0\n-\n框内\n框外\n-\n由 moetran-poprako 导出\n";
+
+// xlsx 的列
+const XLSX_HEADERS: [&str; 6] = ["页面", "序号", "X", "Y", "位置", "译文"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Labelplus,
+    Zip,
+    Xlsx,
+}
+
+impl ExportFormat {
+    fn as_str(self) -> &'static str {
+        match self {
+            ExportFormat::Labelplus => "labelplus",
+            ExportFormat::Zip => "zip",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ExportProgress {
+    pub total: usize,
+    pub completed: usize,
+}
+
+struct ExportJob {
+    cancel: AtomicBool,
+    total: AtomicUsize,
+    completed: AtomicUsize,
+}
+
+impl ExportJob {
+    fn progress(&self) -> ExportProgress {
+        ExportProgress {
+            total: self.total.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// 同一项目同时只允许一个导出任务
+static EXPORT_JOBS: LazyLock<Mutex<HashMap<String, Arc<ExportJob>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 持有期间占用该项目的导出登记，drop 时注销
+struct ExportJobGuard {
+    project_id: String,
+    job: Arc<ExportJob>,
+}
+
+impl Drop for ExportJobGuard {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = EXPORT_JOBS.lock() {
+            if jobs
+                .get(&self.project_id)
+                .is_some_and(|job| Arc::ptr_eq(job, &self.job))
+            {
+                jobs.remove(&self.project_id);
+            }
+        }
+    }
+}
+
+fn claim_export(project_id: &str) -> Result<ExportJobGuard, String> {
+    let mut jobs = EXPORT_JOBS
+        .lock()
+        .map_err(|_| "export jobs lock poisoned".to_string())?;
+
+    if jobs.contains_key(project_id) {
+        return Err("该项目正在导出，请等待完成或先取消".to_string());
+    }
+
+    let job = Arc::new(ExportJob {
+        cancel: AtomicBool::new(false),
+        total: AtomicUsize::new(0),
+        completed: AtomicUsize::new(0),
+    });
+
+    jobs.insert(project_id.to_string(), job.clone());
+
+    Ok(ExportJobGuard {
+        project_id: project_id.to_string(),
+        job,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ExportPage {
+    file_id: String,
+    name: String,
+}
+
+// xlsx 的一行（一个 source）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ExportRow {
+    page: String,
+    index: usize,
+    x: f64,
+    y: f64,
+    outside: bool,
+    text: String,
+}
+
+// 未完成导出的进度记录；pages 为开始导出时确定的页顺序，继续导出时沿用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PartialExport {
+    format: String,
+    project_id: String,
+    target_id: String,
+    pages: Vec<ExportPage>,
+    completed: usize,
+    // labelplus / zip：输出文件中前 completed 页（含文件头 / 归档目录）的字节数
+    bytes: u64,
+    // 已写完的各页的 source 数量，与 pages 前 completed 项一一对应
+    #[serde(default)]
+    source_counts: Vec<usize>,
+    // 开始导出的时间，xlsx 以此作为文档创建时间，使继续导出的结果与一次性导出相同
+    #[serde(default)]
+    started_at: i64,
+    // xlsx：已写完的页的全部行
+    #[serde(default)]
+    rows: Vec<ExportRow>,
+}
+
+fn sidecar_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".partial.json");
+    PathBuf::from(name)
+}
+
+async fn read_sidecar(path: &Path) -> Option<PartialExport> {
+    let raw = fs::read(path).await.ok()?;
+
+    serde_json::from_slice(&raw).ok()
+}
+
+// 先写临时文件再改名，避免进度记录本身写了一半
+async fn write_sidecar(path: &Path, partial: &PartialExport) -> Result<(), String> {
+    let raw =
+        serde_json::to_vec_pretty(partial).map_err(|err| format!("序列化导出进度失败: {}", err))?;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    fs::write(&tmp, raw)
+        .await
+        .map_err(|err| format!("写入导出进度失败: {}", err))?;
+
+    fs::rename(&tmp, path)
+        .await
+        .map_err(|err| format!("写入导出进度失败: {}", err))
+}
+
+// 导出的译文：选定翻译的校对稿（为空时用原译文）；没有选定时取第一条翻译
+pub(crate) fn export_text(source: &MoetranSource) -> &str {
+    let translation = source
+        .translations
+        .iter()
+        .find(|translation| translation.selected)
+        .or(source.translations.first())
+        .or(source.my_translation.as_ref());
+
+    let Some(translation) = translation else {
+        return "";
+    };
+
+    translation
+        .proofread_content
+        .as_deref()
+        .filter(|content| !content.trim().is_empty())
+        .unwrap_or(&translation.content)
+}
+
+fn is_outside(source: &MoetranSource) -> bool {
+    matches!(source.position_type, PositionType::Outside)
+}
+
+fn labelplus_page(name: &str, sources: &[MoetranSource]) -> String {
+    let mut block = format!("\n>>>>>>>>[{}]<<<<<<<<\n", name);
+
+    for (index, source) in sources.iter().enumerate() {
+        let group = if is_outside(source) { 2 } else { 1 };
+
+        block.push_str(&format!(
+            "----------------[{}]----------------[{:.3},{:.3},{}]\n{}\n\n",
+            index + 1,
+            source.x,
+            source.y,
+            group,
+            export_text(source).replace("\r\n", "\n")
+        ));
+    }
+
+    block
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportProjectTranslationsReq {
+    pub project_id: String,
+    pub target_id: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub format: ExportFormat,
+    // 为 true 且存在同一项目 / target / 格式的未完成导出时从中断处继续，否则重新导出
+    #[serde(default)]
+    pub resume: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedPage {
+    pub name: String,
+    pub source_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub output_path: String,
+    pub format: String,
+    pub pages_total: usize,
+    pub pages_written: usize,
+    // 本次从第几页继续（重新导出时为 0）
+    pub resumed_from: usize,
+    // 已写入的页（按导出顺序）及各页的 source 数量，包括此前中断时已写完的页
+    pub pages: Vec<ExportedPage>,
+    // 输出不完整：已写完的页可用，进度记录保留在 partial_path，可带 resume 继续
+    pub partial: bool,
+    pub cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_path: Option<String>,
+    // 未导出的页（文件名）
+    pub missing: Vec<String>,
+    // 拉取 sources 失败而中止导出的页（文件名），error 为对应的错误信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_page: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+async fn ordered_pages(project_id: &str, target_id: &str) -> Result<Vec<ExportPage>, String> {
+    let mut files = get_project_files(GetProjectFilesReq {
+        project_id: project_id.into(),
+        target_id: Some(target_id.into()),
+        sort: None,
+    })
+    .await?;
+
+    files.sort_by(|a, b| compare_file_names(&a.name, &b.name));
+
+    Ok(files
+        .into_iter()
+        .map(|file| ExportPage {
+            file_id: file.id.into(),
+            name: file.name,
+        })
+        .collect())
+}
+
+fn write_error(err: impl std::fmt::Display) -> String {
+    format!("写入导出文件失败: {}", err)
+}
+
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|err| format!("导出任务异常: {}", err))?
+}
+
+// labelplus：把输出截断到前 bytes 字节（最后一个完整页）后追加一页，返回新的长度
+async fn append_labelplus(output: &Path, bytes: u64, block: &[u8]) -> Result<u64, String> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .open(output)
+        .await
+        .map_err(|err| format!("打开导出文件失败: {}", err))?;
+
+    file.set_len(bytes).await.map_err(write_error)?;
+    file.seek(SeekFrom::End(0)).await.map_err(write_error)?;
+    file.write_all(block).await.map_err(write_error)?;
+
+    // 先让页内容落盘，再记录进度，中断后记录的长度内总是完整的页
+    file.sync_data().await.map_err(write_error)?;
+
+    Ok(bytes + block.len() as u64)
+}
+
+// 条目时间固定，使同样的内容得到同样的归档
+fn zip_options() -> SimpleFileOptions {
+    SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default())
+}
+
+// zip：空归档，返回其长度
+fn create_zip(output: &Path) -> Result<u64, String> {
+    let file = std::fs::File::create(output).map_err(|err| format!("创建导出文件失败: {}", err))?;
+
+    let mut file = ZipWriter::new(file).finish().map_err(write_error)?;
+
+    file.sync_data().map_err(write_error)?;
+    file.seek(SeekFrom::End(0)).map_err(write_error)
+}
+
+// zip：在前 bytes 字节（上一页完成时的归档）之后追加一个条目并重写目录，返回新的长度
+fn append_zip_entry(output: &Path, bytes: u64, name: &str, content: &[u8]) -> Result<u64, String> {
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(output)
+        .map_err(|err| format!("打开导出文件失败: {}", err))?;
+
+    file.set_len(bytes).map_err(write_error)?;
+
+    let mut zip =
+        ZipWriter::new_append(file).map_err(|err| format!("读取导出归档失败: {}", err))?;
+
+    zip.start_file(name, zip_options()).map_err(write_error)?;
+    zip.write_all(content).map_err(write_error)?;
+
+    let mut file = zip.finish().map_err(write_error)?;
+
+    file.sync_data().map_err(write_error)?;
+    file.seek(SeekFrom::End(0)).map_err(write_error)
+}
+
+// zip：输出的前 bytes 字节是否为恰好包含 entries 个条目的完整归档
+fn zip_prefix_valid(output: &Path, bytes: u64, entries: usize) -> bool {
+    let Ok(raw) = std::fs::read(output) else {
+        return false;
+    };
+
+    let Some(prefix) = usize::try_from(bytes).ok().and_then(|len| raw.get(..len)) else {
+        return false;
+    };
+
+    ZipArchive::new(Cursor::new(prefix)).is_ok_and(|archive| archive.len() == entries)
+}
+
+// xlsx：按全部行生成工作簿，先写临时文件再改名，输出总是完整的工作簿
+fn write_xlsx(output: &Path, rows: &[ExportRow], started_at: i64) -> Result<(), String> {
+    let xlsx_error = |err: rust_xlsxwriter::XlsxError| format!("生成 xlsx 失败: {}", err);
+
+    let mut workbook = Workbook::new();
+
+    let created = ExcelDateTime::from_timestamp(started_at).map_err(xlsx_error)?;
+    workbook.set_properties(&DocProperties::new().set_creation_datetime(&created));
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("译文").map_err(xlsx_error)?;
+
+    for (col, header) in XLSX_HEADERS.iter().enumerate() {
+        sheet
+            .write_string(0, col as u16, *header)
+            .map_err(xlsx_error)?;
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let line = index as u32 + 1;
+
+        sheet.write_string(line, 0, &row.page).map_err(xlsx_error)?;
+        sheet
+            .write_number(line, 1, row.index as f64)
+            .map_err(xlsx_error)?;
+        sheet.write_number(line, 2, row.x).map_err(xlsx_error)?;
+        sheet.write_number(line, 3, row.y).map_err(xlsx_error)?;
+        sheet
+            .write_string(line, 4, if row.outside { "框外" } else { "框内" })
+            .map_err(xlsx_error)?;
+        sheet.write_string(line, 5, &row.text).map_err(xlsx_error)?;
+    }
+
+    let raw = workbook.save_to_buffer().map_err(xlsx_error)?;
+
+    let mut tmp = output.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file =
+        std::fs::File::create(&tmp).map_err(|err| format!("创建导出文件失败: {}", err))?;
+    file.write_all(&raw).map_err(write_error)?;
+    file.sync_data().map_err(write_error)?;
+
+    std::fs::rename(&tmp, output).map_err(write_error)
+}
+
+fn xlsx_rows(name: &str, sources: &[MoetranSource]) -> Vec<ExportRow> {
+    sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| ExportRow {
+            page: name.to_string(),
+            index: index + 1,
+            x: source.x,
+            y: source.y,
+            outside: is_outside(source),
+            text: export_text(source).replace("\r\n", "\n"),
+        })
+        .collect()
+}
+
+// 创建只含文件头（labelplus）/ 空目录（zip）/ 表头（xlsx）的输出
+async fn create_output(
+    format: ExportFormat,
+    output: &Path,
+    partial: &mut PartialExport,
+) -> Result<(), String> {
+    match format {
+        ExportFormat::Labelplus => {
+            fs::write(output, "")
+                .await
+                .map_err(|err| format!("创建导出文件失败: {}", err))?;
+
+            partial.bytes = append_labelplus(output, 0, LABELPLUS_HEADER.as_bytes()).await?;
+        }
+        ExportFormat::Zip => {
+            let output = output.to_path_buf();
+
+            partial.bytes = blocking(move || create_zip(&output)).await?;
+        }
+        ExportFormat::Xlsx => {
+            let output = output.to_path_buf();
+            let started_at = partial.started_at;
+
+            blocking(move || write_xlsx(&output, &[], started_at)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// 写入一页并更新 partial 中的长度 / 行
+async fn write_page(
+    format: ExportFormat,
+    output: &Path,
+    partial: &mut PartialExport,
+    name: &str,
+    sources: &[MoetranSource],
+) -> Result<(), String> {
+    match format {
+        ExportFormat::Labelplus => {
+            let block = labelplus_page(name, sources);
+
+            partial.bytes = append_labelplus(output, partial.bytes, block.as_bytes()).await?;
+        }
+        ExportFormat::Zip => {
+            // 每页一个可单独打开的 LabelPlus 翻译稿
+            let content = format!("{}{}", LABELPLUS_HEADER, labelplus_page(name, sources));
+            let entry = format!("{}.txt", name);
+            let output = output.to_path_buf();
+            let bytes = partial.bytes;
+
+            partial.bytes =
+                blocking(move || append_zip_entry(&output, bytes, &entry, content.as_bytes()))
+                    .await?;
+        }
+        ExportFormat::Xlsx => {
+            let mut rows = partial.rows.clone();
+            rows.extend(xlsx_rows(name, sources));
+
+            let output = output.to_path_buf();
+            let started_at = partial.started_at;
+
+            partial.rows =
+                blocking(move || write_xlsx(&output, &rows, started_at).map(|()| rows)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// labelplus / zip：丢弃最后一个完整页之后的内容（继续导出前中断时写了一半的页）
+async fn trim_output(format: ExportFormat, output: &Path, bytes: u64) -> Result<(), String> {
+    if format == ExportFormat::Xlsx {
+        return Ok(());
+    }
+
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .open(output)
+        .await
+        .map_err(|err| format!("打开导出文件失败: {}", err))?;
+
+    file.set_len(bytes)
+        .await
+        .map_err(|err| format!("截断导出文件失败: {}", err))?;
+
+    file.sync_data().await.map_err(write_error)
+}
+
+// 可继续的进度记录：格式、项目与 target 一致，且输出仍包含记录中已完成的页
+async fn resumable(
+    sidecar: &Path,
+    output: &Path,
+    payload: &ExportProjectTranslationsReq,
+) -> Option<PartialExport> {
+    let partial = read_sidecar(sidecar).await?;
+
+    if partial.format != payload.format.as_str()
+        || partial.project_id != payload.project_id
+        || partial.target_id != payload.target_id
+        || partial.completed > partial.pages.len()
+        || partial.source_counts.len() != partial.completed
+    {
+        return None;
+    }
+
+    let len = fs::metadata(output).await.ok()?.len();
+
+    let intact = match payload.format {
+        ExportFormat::Labelplus => len >= partial.bytes,
+        // 写入条目时中断会覆盖原有目录，此时无法继续，只能重新导出
+        ExportFormat::Zip => {
+            let output = output.to_path_buf();
+            let (bytes, entries) = (partial.bytes, partial.completed);
+
+            blocking(move || Ok(zip_prefix_valid(&output, bytes, entries)))
+                .await
+                .unwrap_or(false)
+        }
+        ExportFormat::Xlsx => partial.rows.len() == partial.source_counts.iter().sum::<usize>(),
+    };
+
+    intact.then_some(partial)
+}
+
+async fn run_export(
+    job: &ExportJob,
+    payload: &ExportProjectTranslationsReq,
+) -> Result<ExportReport, String> {
+    let output = PathBuf::from(&payload.output_path);
+    let sidecar = sidecar_path(&output);
+    let format = payload.format;
+
+    let resumed = match payload.resume {
+        true => resumable(&sidecar, &output, payload).await,
+        false => None,
+    };
+
+    let mut partial = match resumed {
+        Some(partial) => {
+            tracing::info!(
+                format = %partial.format,
+                completed = partial.completed,
+                bytes = partial.bytes,
+                "translation_export.resume"
+            );
+
+            partial
+        }
+        None => {
+            let pages = ordered_pages(&payload.project_id, &payload.target_id).await?;
+
+            let mut partial = PartialExport {
+                format: format.as_str().to_string(),
+                project_id: payload.project_id.clone(),
+                target_id: payload.target_id.clone(),
+                pages,
+                completed: 0,
+                bytes: 0,
+                source_counts: Vec::new(),
+                started_at: unix_now(),
+                rows: Vec::new(),
+            };
+
+            create_output(format, &output, &mut partial).await?;
+
+            write_sidecar(&sidecar, &partial).await?;
+
+            partial
+        }
+    };
+
+    let resumed_from = partial.completed;
+
+    job.total.store(partial.pages.len(), Ordering::Relaxed);
+    job.completed.store(partial.completed, Ordering::Relaxed);
+
+    let mut cancelled = false;
+    let mut failed_page = None;
+    let mut error = None;
+
+    while partial.completed < partial.pages.len() {
+        if job.cancel.load(Ordering::Relaxed) {
+            cancelled = true;
+            break;
+        }
+
+        let page = partial.pages[partial.completed].clone();
+
+        let sources =
+            match fetch_file_sources(page.file_id.clone(), payload.target_id.clone()).await {
+                Ok(sources) => sources,
+                Err(err) => {
+                    tracing::warn!(
+                        file_id = %page.file_id,
+                        name = %page.name,
+                        error = %err,
+                        "translation_export.page_fetch_failed"
+                    );
+                    failed_page = Some(page.name.clone());
+                    error = Some(err);
+                    break;
+                }
+            };
+
+        write_page(format, &output, &mut partial, &page.name, &sources).await?;
+
+        partial.completed += 1;
+        partial.source_counts.push(sources.len());
+
+        write_sidecar(&sidecar, &partial).await?;
+
+        job.completed.store(partial.completed, Ordering::Relaxed);
+    }
+
+    trim_output(format, &output, partial.bytes).await?;
+
+    let complete = partial.completed == partial.pages.len();
+
+    if complete {
+        if let Err(err) = fs::remove_file(&sidecar).await {
+            tracing::warn!(error = %err, "translation_export.sidecar_remove_failed");
+        }
+    }
+
+    Ok(ExportReport {
+        output_path: payload.output_path.clone(),
+        format: partial.format.clone(),
+        pages_total: partial.pages.len(),
+        pages_written: partial.completed,
+        resumed_from,
+        pages: partial
+            .pages
+            .iter()
+            .zip(&partial.source_counts)
+            .map(|(page, &source_count)| ExportedPage {
+                name: page.name.clone(),
+                source_count,
+            })
+            .collect(),
+        partial: !complete,
+        cancelled,
+        partial_path: (!complete).then(|| sidecar.to_string_lossy().to_string()),
+        missing: partial.pages[partial.completed..]
+            .iter()
+            .map(|page| page.name.clone())
+            .collect(),
+        failed_page,
+        error,
+    })
+}
+
+// 按 format 导出；取消或出错时返回 partial = true 的结果（而不是错误），已写完的页保留
+#[tauri::command]
+pub async fn export_project_translations(
+    payload: ExportProjectTranslationsReq,
+) -> Result<ExportReport, String> {
+    tracing::info!(
+        project_id = %payload.project_id,
+        target_id = %payload.target_id,
+        format = payload.format.as_str(),
+        resume = payload.resume,
+        "translation_export.start"
+    );
+
+    let mut defer = WarnDefer::new("translation_export");
+
+    let guard = claim_export(&payload.project_id)?;
+
+    let report = run_export(&guard.job, &payload).await?;
+
+    tracing::info!(
+        pages_total = report.pages_total,
+        pages_written = report.pages_written,
+        resumed_from = report.resumed_from,
+        partial = report.partial,
+        cancelled = report.cancelled,
+        "translation_export.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectExportJobReq {
+    pub project_id: String,
+}
+
+// 取消项目进行中的导出（当前页写完后停止），返回是否存在该任务
+#[tauri::command]
+pub async fn cancel_project_export(payload: ProjectExportJobReq) -> Result<bool, String> {
+    let jobs = EXPORT_JOBS
+        .lock()
+        .map_err(|_| "export jobs lock poisoned".to_string())?;
+
+    match jobs.get(&payload.project_id) {
+        Some(job) => {
+            job.cancel.store(true, Ordering::Relaxed);
+            tracing::info!(progress = ?job.progress(), "translation_export.cancel.ok");
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// 查询项目进行中的导出进度；没有导出任务时返回 None
+#[tauri::command]
+pub async fn get_project_export_progress(
+    payload: ProjectExportJobReq,
+) -> Result<Option<ExportProgress>, String> {
+    let jobs = EXPORT_JOBS
+        .lock()
+        .map_err(|_| "export jobs lock poisoned".to_string())?;
+
+    Ok(jobs.get(&payload.project_id).map(|job| job.progress()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    const PAGES: [(&str, &str); 3] = [
+        ("exp-f1", "p1.jpg"),
+        ("exp-f2", "p2.jpg"),
+        ("exp-f10", "p10.jpg"),
+    ];
+
+    fn sources_json(page: &str) -> serde_json::Value {
+        json!([
+            {
+                "id": format!("{}-s1", page),
+                "x": 0.125,
+                "y": 0.5,
+                "position_type": 1,
+                "my_translation": null,
+                "translations": [{
+                    "id": format!("{}-t1", page),
+                    "content": format!("{} 的译文\r\n第二行", page),
+                    "proofread_content": null,
+                    "selected": true,
+                }],
+            },
+            {
+                "id": format!("{}-s2", page),
+                "x": 0.75,
+                "y": 0.25,
+                "position_type": 2,
+                "my_translation": null,
+                "translations": [],
+            },
+        ])
+    }
+
+    async fn mount_project(backends: &MockBackends) {
+        // 故意打乱顺序，导出按自然排序
+        let files: Vec<serde_json::Value> = PAGES
+            .iter()
+            .rev()
+            .map(|(id, name)| {
+                json!({ "id": id, "name": name, "source_count": 2, "url": "https://img/x", "cover_url": "" })
+            })
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/exp-proj/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(files))
+            .mount(&backends.moetran)
+            .await;
+
+        for (id, name) in PAGES {
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/files/{}/sources", id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(sources_json(name)))
+                .mount(&backends.moetran)
+                .await;
+        }
+    }
+
+    fn job(cancelled: bool) -> ExportJob {
+        ExportJob {
+            cancel: AtomicBool::new(cancelled),
+            total: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+        }
+    }
+
+    fn request(output: &Path, format: ExportFormat, resume: bool) -> ExportProjectTranslationsReq {
+        ExportProjectTranslationsReq {
+            project_id: "exp-proj".to_string(),
+            target_id: "exp-target".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            format,
+            resume,
+        }
+    }
+
+    // 归档中各条目的名称与内容；xlsx 的文档属性含创建时间，不参与比较
+    fn archive_entries(raw: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut archive = ZipArchive::new(Cursor::new(raw)).unwrap();
+
+        (0..archive.len())
+            .map(|index| {
+                let mut entry = archive.by_index(index).unwrap();
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).unwrap();
+                (entry.name().to_string(), content)
+            })
+            .filter(|(name, _)| name != "docProps/core.xml")
+            .collect()
+    }
+
+    fn entry_names(raw: &[u8]) -> Vec<String> {
+        archive_entries(raw)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn labelplus_page_numbers_sources_and_groups_outside_boxes() {
+        let sources: Vec<MoetranSource> = serde_json::from_value(sources_json("p1.jpg")).unwrap();
+
+        assert_eq!(
+            labelplus_page("p1.jpg", &sources),
+            "\n>>>>>>>>[p1.jpg]<<<<<<<<\n\
+             ----------------[1]----------------[0.125,0.500,1]\np1.jpg 的译文\n第二行\n\n\
+             ----------------[2]----------------[0.750,0.250,2]\n\n\n"
+        );
+    }
+
+    async fn export_with_failed_page(
+        backends: &MockBackends,
+        output: &Path,
+        format: ExportFormat,
+    ) -> ExportReport {
+        // 用不会被自动重试的状态码，保证这一页确实失败
+        Mock::given(method("GET"))
+            .and(path("/v1/files/exp-f2/sources"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({ "message": "x" })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&backends.moetran)
+            .await;
+
+        run_export(&job(false), &request(output, format, false))
+            .await
+            .unwrap()
+    }
+
+    async fn check_resume_is_byte_stable(format: ExportFormat, extension: &str) {
+        let backends = MockBackends::start().await;
+        mount_project(&backends).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let interrupted = dir.path().join(format!("interrupted.{}", extension));
+        let fresh = dir.path().join(format!("fresh.{}", extension));
+
+        let report = export_with_failed_page(&backends, &interrupted, format).await;
+
+        assert!(report.partial);
+        assert_eq!(report.pages_written, 1);
+        assert_eq!(report.failed_page.as_deref(), Some("p2.jpg"));
+        assert_eq!(report.missing, ["p2.jpg", "p10.jpg"]);
+        assert!(sidecar_path(&interrupted).exists());
+
+        // 中断后已完成的部分仍可直接使用
+        let partial_raw = std::fs::read(&interrupted).unwrap();
+        match format {
+            ExportFormat::Labelplus => {
+                let text = String::from_utf8(partial_raw).unwrap();
+                assert!(text.starts_with(LABELPLUS_HEADER));
+                assert!(text.contains("[p1.jpg]") && !text.contains("[p2.jpg]"));
+            }
+            ExportFormat::Zip => assert_eq!(entry_names(&partial_raw), ["p1.jpg.txt"]),
+            ExportFormat::Xlsx => assert!(entry_names(&partial_raw)
+                .iter()
+                .any(|name| name == "xl/worksheets/sheet1.xml")),
+        }
+
+        let resumed = run_export(&job(false), &request(&interrupted, format, true))
+            .await
+            .unwrap();
+
+        assert!(!resumed.partial);
+        assert_eq!(resumed.resumed_from, 1);
+        assert_eq!(resumed.pages_written, 3);
+        assert!(!sidecar_path(&interrupted).exists());
+
+        let complete = run_export(&job(false), &request(&fresh, format, false))
+            .await
+            .unwrap();
+        assert!(!complete.partial);
+
+        let resumed_raw = std::fs::read(&interrupted).unwrap();
+        let fresh_raw = std::fs::read(&fresh).unwrap();
+
+        match format {
+            ExportFormat::Xlsx => {
+                assert_eq!(archive_entries(&resumed_raw), archive_entries(&fresh_raw))
+            }
+            _ => assert_eq!(resumed_raw, fresh_raw),
+        }
+    }
+
+    #[tokio::test]
+    async fn labelplus_resume_is_byte_stable() {
+        check_resume_is_byte_stable(ExportFormat::Labelplus, "txt").await;
+    }
+
+    #[tokio::test]
+    async fn zip_resume_is_byte_stable() {
+        check_resume_is_byte_stable(ExportFormat::Zip, "zip").await;
+    }
+
+    #[tokio::test]
+    async fn xlsx_resume_matches_a_fresh_export() {
+        check_resume_is_byte_stable(ExportFormat::Xlsx, "xlsx").await;
+    }
+
+    #[tokio::test]
+    async fn cancelled_zip_is_a_valid_archive_and_resumes() {
+        let backends = MockBackends::start().await;
+        mount_project(&backends).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("cancelled.zip");
+
+        let report = run_export(&job(true), &request(&output, ExportFormat::Zip, false))
+            .await
+            .unwrap();
+
+        assert!(report.cancelled && report.partial);
+        assert_eq!(report.pages_written, 0);
+        assert!(entry_names(&std::fs::read(&output).unwrap()).is_empty());
+
+        let resumed = run_export(&job(false), &request(&output, ExportFormat::Zip, true))
+            .await
+            .unwrap();
+
+        assert!(!resumed.partial);
+        assert_eq!(
+            entry_names(&std::fs::read(&output).unwrap()),
+            ["p1.jpg.txt", "p2.jpg.txt", "p10.jpg.txt"]
+        );
+    }
+
+    #[tokio::test]
+    async fn resume_with_another_format_starts_over() {
+        let backends = MockBackends::start().await;
+        mount_project(&backends).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("switched.out");
+
+        export_with_failed_page(&backends, &output, ExportFormat::Labelplus).await;
+
+        let report = run_export(&job(false), &request(&output, ExportFormat::Zip, true))
+            .await
+            .unwrap();
+
+        assert_eq!(report.resumed_from, 0);
+        assert_eq!(report.format, "zip");
+        assert_eq!(entry_names(&std::fs::read(&output).unwrap()).len(), 3);
+    }
+}
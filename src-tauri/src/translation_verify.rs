@@ -0,0 +1,563 @@
+// 提交后的持久化校验（verify_submits 开启时）：submit_translation 成功后把翻译记入 verify_queue，
+// 后台任务在几分钟后按页重新拉取 sources，确认翻译仍存在且内容一致；不一致时标记为 lost 并发送
+// "translation-lost" 事件，前端可用 resubmit_lost_translation 一键重新提交。
+// 同一页的记录合并为一次请求，页与页之间间隔发送，并经过 Moetran 的共用限速器；离线模式下暂停，超过保留期的记录删除
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::{
+    clock::unix_now,
+    config::config,
+    contributions::fetch_file_sources,
+    events::{emit_event, TranslationLost},
+    normalize::WithNormalization,
+    project::{submit_translation, MoetranSource, MoetranTranslation, SubmitTranslationReq},
+    rate_limit::moetran_limiter,
+    source_snapshot::snapshot_file_of,
+    storage::{verify_queue, LOCAL_STORAGE},
+};
+
+// 提交后多久校验
+const VERIFY_DELAY_SECS: i64 = 5 * 60;
+
+// 页面拉取失败时推迟多久再试
+const VERIFY_RETRY_SECS: i64 = 10 * 60;
+
+// 记录的保留期（含未能校验的与已丢失的）
+const VERIFY_RETENTION_SECS: i64 = 24 * 3600;
+
+const VERIFY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+// 两次页面请求之间的间隔，校验是低优先级的后台工作
+const VERIFY_FILE_PACING: std::time::Duration = std::time::Duration::from_millis(1500);
+
+// 每轮最多处理的记录数
+const VERIFY_BATCH_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LostReason {
+    // source 或翻译已不存在
+    Missing,
+    // 翻译仍在，但内容与提交时不同
+    Altered,
+}
+
+impl LostReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            LostReason::Missing => "missing",
+            LostReason::Altered => "altered",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "altered" => LostReason::Altered,
+            _ => LostReason::Missing,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VerifyOutcome {
+    Verified,
+    Lost {
+        reason: LostReason,
+        current_content: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LostTranslation {
+    pub queue_id: i64,
+    pub source_id: String,
+    pub target_id: String,
+    pub translation_id: String,
+    pub file_id: String,
+    pub reason: LostReason,
+    // 提交时的内容，重新提交时使用
+    pub content: String,
+    // 内容被改动时的当前内容
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_content: Option<String>,
+    pub submitted_at: i64,
+}
+
+fn content_hash(content: &str) -> String {
+    let digest = Sha256::digest(content.replace("\r\n", "\n").as_bytes());
+
+    general_purpose::STANDARD_NO_PAD.encode(&digest[..16])
+}
+
+fn find_translation<'a>(
+    sources: &'a [MoetranSource],
+    source_id: &str,
+    translation_id: &str,
+) -> Option<&'a MoetranTranslation> {
+    let source = sources.iter().find(|source| source.id == source_id)?;
+
+    source
+        .translations
+        .iter()
+        .chain(source.my_translation.as_ref())
+        .find(|translation| translation.id == translation_id)
+}
+
+// 按页面上最新的 sources 判断一条记录
+fn check_entry(entry: &verify_queue::VerifyQueueRow, sources: &[MoetranSource]) -> VerifyOutcome {
+    match find_translation(sources, &entry.source_id, &entry.translation_id) {
+        None => VerifyOutcome::Lost {
+            reason: LostReason::Missing,
+            current_content: None,
+        },
+        Some(translation) if content_hash(&translation.content) == entry.content_hash => {
+            VerifyOutcome::Verified
+        }
+        Some(translation) => VerifyOutcome::Lost {
+            reason: LostReason::Altered,
+            current_content: Some(translation.content.clone()),
+        },
+    }
+}
+
+// 按 (file_id, target_id) 分组，同一页只请求一次
+fn group_by_page(
+    rows: Vec<verify_queue::VerifyQueueRow>,
+) -> BTreeMap<(String, String), Vec<verify_queue::VerifyQueueRow>> {
+    let mut groups: BTreeMap<(String, String), Vec<verify_queue::VerifyQueueRow>> = BTreeMap::new();
+
+    for row in rows {
+        groups
+            .entry((row.file_id.clone(), row.target_id.clone()))
+            .or_default()
+            .push(row);
+    }
+
+    groups
+}
+
+fn lost_translation(
+    row: verify_queue::VerifyQueueRow,
+    reason: LostReason,
+    current_content: Option<String>,
+) -> LostTranslation {
+    LostTranslation {
+        queue_id: row.id,
+        source_id: row.source_id,
+        target_id: row.target_id,
+        translation_id: row.translation_id,
+        file_id: row.file_id,
+        reason,
+        content: row.content,
+        current_content,
+        submitted_at: row.created_at,
+    }
+}
+
+// 记录一次成功的提交；未开启校验、或所在页面未知（未通过页面加载过）时不记录
+pub(crate) fn enqueue_submitted(
+    source_id: &str,
+    target_id: &str,
+    translation: &MoetranTranslation,
+) {
+    if !config().verify_submits {
+        return;
+    }
+
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let Some(file_id) = snapshot_file_of(source_id) else {
+        tracing::debug!(%source_id, "translation_verify.enqueue.file_unknown");
+        return;
+    };
+
+    let created_at = unix_now();
+
+    let row = verify_queue::VerifyQueueRow {
+        id: 0,
+        source_id: source_id.to_string(),
+        target_id: target_id.to_string(),
+        translation_id: translation.id.to_string(),
+        file_id,
+        content: translation.content.clone(),
+        content_hash: content_hash(&translation.content),
+        status: String::new(),
+        reason: None,
+        created_at,
+        due_at: created_at + VERIFY_DELAY_SECS,
+    };
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = verify_queue::enqueue_verify(storage.pool(), &row).await {
+            tracing::warn!(translation_id = %row.translation_id, error = %err, "translation_verify.enqueue_failed");
+        }
+    });
+}
+
+// 用户随后更新了译文内容：同步待校验记录中的内容
+pub(crate) fn sync_updated_content(translation: &MoetranTranslation) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    let translation_id = translation.id.clone();
+    let content = translation.content.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let hash = content_hash(&content);
+
+        if let Err(err) =
+            verify_queue::update_pending_content(storage.pool(), &translation_id, &content, &hash)
+                .await
+        {
+            tracing::warn!(%translation_id, error = %err, "translation_verify.sync_failed");
+        }
+    });
+}
+
+// 处理一轮到期的记录，返回 (已确认, 已丢失) 条数
+// 丢失的翻译交给 on_lost（后台任务中发送 "translation-lost" 事件）
+async fn verify_round(mut on_lost: impl FnMut(LostTranslation)) -> Result<(usize, usize), String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+    let pool = storage.pool();
+
+    let pruned = verify_queue::prune_verify_queue(pool, unix_now() - VERIFY_RETENTION_SECS).await?;
+
+    if pruned > 0 {
+        tracing::debug!(pruned, "translation_verify.pruned");
+    }
+
+    let due = verify_queue::list_due(pool, unix_now(), VERIFY_BATCH_LIMIT).await?;
+
+    let mut verified = 0;
+    let mut lost = 0;
+
+    for (index, ((file_id, target_id), rows)) in group_by_page(due).into_iter().enumerate() {
+        // 中途切换到离线模式时停止，剩余记录下一轮再处理
+        if config().offline_mode || !config().verify_submits {
+            break;
+        }
+
+        if index > 0 {
+            tokio::time::sleep(VERIFY_FILE_PACING).await;
+        }
+
+        let fetched = match moetran_limiter().acquire().await {
+            Ok(()) => fetch_file_sources(file_id.clone(), target_id).await,
+            Err(err) => Err(err.to_string()),
+        };
+
+        let sources = match fetched {
+            Ok(sources) => sources,
+            Err(err) => {
+                tracing::info!(%file_id, error = %err, "translation_verify.fetch_failed");
+
+                for row in &rows {
+                    verify_queue::postpone(pool, row.id, unix_now() + VERIFY_RETRY_SECS).await?;
+                }
+
+                continue;
+            }
+        };
+
+        for row in rows {
+            match check_entry(&row, &sources) {
+                VerifyOutcome::Verified => {
+                    verify_queue::mark_status(
+                        pool,
+                        row.id,
+                        verify_queue::VERIFY_STATUS_VERIFIED,
+                        None,
+                    )
+                    .await?;
+                    verified += 1;
+                }
+                VerifyOutcome::Lost {
+                    reason,
+                    current_content,
+                } => {
+                    verify_queue::mark_status(
+                        pool,
+                        row.id,
+                        verify_queue::VERIFY_STATUS_LOST,
+                        Some(reason.as_str()),
+                    )
+                    .await?;
+
+                    tracing::warn!(
+                        translation_id = %row.translation_id,
+                        source_id = %row.source_id,
+                        ?reason,
+                        "translation_verify.lost"
+                    );
+
+                    on_lost(lost_translation(row, reason, current_content));
+                    lost += 1;
+                }
+            }
+        }
+    }
+
+    Ok((verified, lost))
+}
+
+// 启动后台校验任务；未开启校验或离线模式时跳过
+pub(crate) fn spawn_verifier(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(VERIFY_POLL_INTERVAL).await;
+
+            if !config().verify_submits || config().offline_mode {
+                continue;
+            }
+
+            match verify_round(|lost| emit_event(&app, TranslationLost(lost))).await {
+                Ok((0, 0)) => {}
+                Ok((verified, lost)) => {
+                    tracing::info!(verified, lost, "translation_verify.round.ok");
+                }
+                Err(err) => tracing::warn!(error = %err, "translation_verify.round.failed"),
+            }
+        }
+    });
+}
+
+// 校验发现丢失、尚未处理的翻译（应用重启后事件已错过时使用）
+#[tauri::command]
+pub async fn list_lost_translations() -> Result<Vec<LostTranslation>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = verify_queue::list_lost(storage.pool()).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let reason = LostReason::parse(row.reason.as_deref().unwrap_or_default());
+            lost_translation(row, reason, None)
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResubmitLostTranslationReq {
+    pub queue_id: i64,
+}
+
+// 以提交时的内容重新提交（原样提交，不再规范化），成功后移除该记录；新的提交会重新进入校验
+#[tauri::command]
+pub async fn resubmit_lost_translation(
+    payload: ResubmitLostTranslationReq,
+) -> Result<WithNormalization<MoetranTranslation>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let row = verify_queue::get_verify_entry(storage.pool(), payload.queue_id)
+        .await?
+        .filter(|row| row.status == verify_queue::VERIFY_STATUS_LOST)
+        .ok_or_else(|| "该记录不存在或未被标记为丢失".to_string())?;
+
+    tracing::info!(
+        queue_id = row.id,
+        source_id = %row.source_id,
+        "translation_verify.resubmit.start"
+    );
+
+    let reply = submit_translation(SubmitTranslationReq {
+        source_id: row.source_id.clone().into(),
+        target_id: row.target_id.clone().into(),
+        content: row.content.clone(),
+        raw: true,
+        team_id: None,
+        allow_empty: true,
+    })
+    .await?;
+
+    verify_queue::delete_verify_entry(storage.pool(), row.id).await?;
+
+    tracing::info!(
+        queue_id = row.id,
+        translation_id = %reply.value.id,
+        "translation_verify.resubmit.ok"
+    );
+
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        config,
+        test_support::{local_storage, MockBackends},
+    };
+
+    fn queued(
+        id: i64,
+        file_id: &str,
+        source_id: &str,
+        content: &str,
+    ) -> verify_queue::VerifyQueueRow {
+        verify_queue::VerifyQueueRow {
+            id,
+            source_id: source_id.to_string(),
+            target_id: "verify-target".to_string(),
+            translation_id: format!("t-{}", source_id),
+            file_id: file_id.to_string(),
+            content: content.to_string(),
+            content_hash: content_hash(content),
+            status: String::new(),
+            reason: None,
+            created_at: unix_now() - VERIFY_DELAY_SECS,
+            due_at: unix_now() - 1,
+        }
+    }
+
+    fn translation(source_id: &str, content: &str) -> serde_json::Value {
+        json!({
+            "id": format!("t-{}", source_id),
+            "content": content,
+            "proofread_content": null,
+            "selected": false,
+        })
+    }
+
+    fn page_json() -> serde_json::Value {
+        json!([
+            {
+                "id": "s-present",
+                "x": 0.1,
+                "y": 0.1,
+                "position_type": 1,
+                "my_translation": null,
+                "translations": [translation("s-present", "你好")],
+            },
+            {
+                "id": "s-altered",
+                "x": 0.2,
+                "y": 0.2,
+                "position_type": 1,
+                "my_translation": translation("s-altered", "被改过的"),
+            },
+        ])
+    }
+
+    #[test]
+    fn entries_are_checked_against_the_page() {
+        let sources: Vec<MoetranSource> = serde_json::from_value(page_json()).unwrap();
+
+        assert_eq!(
+            check_entry(&queued(1, "f", "s-present", "你好"), &sources),
+            VerifyOutcome::Verified
+        );
+        assert_eq!(
+            check_entry(&queued(2, "f", "s-altered", "原文"), &sources),
+            VerifyOutcome::Lost {
+                reason: LostReason::Altered,
+                current_content: Some("被改过的".to_string()),
+            }
+        );
+        assert_eq!(
+            check_entry(&queued(3, "f", "s-missing", "不见了"), &sources),
+            VerifyOutcome::Lost {
+                reason: LostReason::Missing,
+                current_content: None,
+            }
+        );
+    }
+
+    #[test]
+    fn line_endings_do_not_change_the_hash() {
+        assert_eq!(
+            content_hash("第一行\r\n第二行"),
+            content_hash("第一行\n第二行")
+        );
+        assert_ne!(content_hash("第一行"), content_hash("第二行"));
+    }
+
+    #[test]
+    fn rows_are_grouped_per_page() {
+        let groups = group_by_page(vec![
+            queued(1, "f1", "a", "x"),
+            queued(2, "f2", "b", "x"),
+            queued(3, "f1", "c", "x"),
+        ]);
+
+        let sizes: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|((file_id, _), rows)| (file_id.as_str(), rows.len()))
+            .collect();
+        assert_eq!(sizes, [("f1", 2), ("f2", 1)]);
+    }
+
+    #[tokio::test]
+    async fn round_fetches_each_page_once_and_reports_lost_entries() {
+        let backends = MockBackends::start().await;
+        let pool = local_storage().await.pool();
+        config::update_for_test(|config| config.verify_submits = true);
+
+        Mock::given(method("GET"))
+            .and(path("/v1/files/verify-file/sources"))
+            .and(query_param("target_id", "verify-target"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page_json()))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        for (source_id, content) in [
+            ("s-present", "你好"),
+            ("s-altered", "原文"),
+            ("s-missing", "不见了"),
+        ] {
+            verify_queue::enqueue_verify(pool, &queued(0, "verify-file", source_id, content))
+                .await
+                .unwrap();
+        }
+
+        let mut lost = Vec::new();
+        let result = verify_round(|item| lost.push(item)).await;
+
+        config::update_for_test(|config| config.verify_submits = false);
+
+        assert_eq!(result.unwrap(), (1, 2));
+
+        lost.sort_by(|a, b| a.source_id.cmp(&b.source_id));
+        let reported: Vec<(&str, LostReason, &str)> = lost
+            .iter()
+            .map(|item| (item.source_id.as_str(), item.reason, item.content.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                ("s-altered", LostReason::Altered, "原文"),
+                ("s-missing", LostReason::Missing, "不见了"),
+            ]
+        );
+
+        let still_lost = verify_queue::list_lost(pool).await.unwrap();
+        assert_eq!(
+            still_lost
+                .iter()
+                .filter(|row| row.file_id == "verify-file")
+                .count(),
+            2
+        );
+    }
+}
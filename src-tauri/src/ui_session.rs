@@ -0,0 +1,312 @@
+// 界面会话状态（上次所在的汉化组与页面）：前端防抖保存，启动时读取。
+// 读取时由后端校验：已退出的汉化组、本地不再记录的项目路由会被剔除，前端不会跳转到失效页面
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    storage::{recent_projects, settings, LOCAL_STORAGE},
+    team::{get_user_teams, GetUserTeamsReq},
+};
+
+// 存放在设置表中的键（不属于配置项，config 解析时会忽略）
+const UI_SESSION_KEY: &str = "ui_session";
+
+// 会话结构变化时递增；版本不符的旧数据直接丢弃
+const UI_SESSION_VERSION: u32 = 1;
+
+const TEAMS_CHECK_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiRoute {
+    // 前端路由名称（如 "project-detail"）
+    pub name: String,
+    // 路由指向的项目；None 表示与项目无关的页面
+    #[serde(default)]
+    pub project_id: Option<String>,
+    // 其余路由参数原样保存
+    #[serde(default)]
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UiSession {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub team_id: Option<String>,
+    #[serde(default)]
+    pub route: Option<UiRoute>,
+    // 窗口尺寸等纯前端状态，后端不解读
+    #[serde(default)]
+    pub extra: Option<Value>,
+}
+
+// 剔除失效部分，返回被剔除的字段名。
+// team_ids 为 None 表示无法获取汉化组列表（如离线），此时保留 team_id
+pub(crate) fn prune_session(
+    session: &mut UiSession,
+    team_ids: Option<&HashSet<String>>,
+    recent_project_ids: &HashSet<String>,
+) -> Vec<&'static str> {
+    let mut pruned = Vec::new();
+
+    if let (Some(team_id), Some(team_ids)) = (&session.team_id, team_ids) {
+        if !team_ids.contains(team_id) {
+            session.team_id = None;
+            pruned.push("team_id");
+        }
+    }
+
+    // 路由属于某个汉化组，汉化组无效时一并丢弃
+    let route_valid = match &session.route {
+        None => true,
+        Some(_) if session.team_id.is_none() => false,
+        Some(route) => match &route.project_id {
+            Some(project_id) => recent_project_ids.contains(project_id),
+            None => true,
+        },
+    };
+
+    if !route_valid {
+        session.route = None;
+        pruned.push("route");
+    }
+
+    pruned
+}
+
+async fn load_session() -> Result<Option<UiSession>, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let Some(raw) = settings::get_setting(storage.pool(), UI_SESSION_KEY).await? else {
+        return Ok(None);
+    };
+
+    match serde_json::from_str::<UiSession>(&raw) {
+        Ok(session) if session.version == UI_SESSION_VERSION => Ok(Some(session)),
+        Ok(session) => {
+            tracing::info!(version = session.version, "ui_session.version_mismatch");
+            Ok(None)
+        }
+        Err(err) => {
+            tracing::warn!(%err, "ui_session.decode_failed");
+            Ok(None)
+        }
+    }
+}
+
+async fn store_session(session: &UiSession) -> Result<(), String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let raw = serde_json::to_string(session).map_err(|err| format!("序列化会话失败: {}", err))?;
+
+    settings::save_setting(storage.pool(), UI_SESSION_KEY, &raw).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SaveUiSessionReq {
+    pub state_json: String,
+}
+
+// 保存界面会话（前端已防抖）；结构不合法时拒绝
+#[tauri::command]
+pub async fn save_ui_session(payload: SaveUiSessionReq) -> Result<(), String> {
+    let mut session: UiSession = serde_json::from_str(&payload.state_json)
+        .map_err(|err| format!("会话数据格式错误: {}", err))?;
+
+    session.version = UI_SESSION_VERSION;
+
+    store_session(&session).await?;
+
+    tracing::debug!(team_id = ?session.team_id, route = ?session.route.as_ref().map(|r| &r.name), "ui_session.save.ok");
+
+    Ok(())
+}
+
+// 读取上次的界面会话，剔除已失效的汉化组与项目路由；没有可用会话时返回 None
+#[tauri::command]
+pub async fn get_ui_session() -> Result<Option<UiSession>, String> {
+    tracing::info!("ui_session.get.start");
+
+    let Some(mut session) = load_session().await? else {
+        return Ok(None);
+    };
+
+    let team_ids = match get_user_teams(GetUserTeamsReq {
+        page: 1,
+        limit: TEAMS_CHECK_LIMIT,
+    })
+    .await
+    {
+        Ok(teams) => Some(
+            teams
+                .into_iter()
+                .map(|team| team.id.into())
+                .collect::<HashSet<_>>(),
+        ),
+        Err(err) => {
+            tracing::warn!(%err, "ui_session.teams.fetch_failed");
+            None
+        }
+    };
+
+    // 项目是否存在只查本地最近项目记录，不发网络请求
+    let recent_project_ids = match (&session.team_id, LOCAL_STORAGE.get()) {
+        (Some(team_id), Some(storage)) => {
+            recent_projects::list_recent_projects(storage.pool(), team_id)
+                .await?
+                .into_iter()
+                .map(|row| row.project_id)
+                .collect()
+        }
+        _ => HashSet::new(),
+    };
+
+    let pruned = prune_session(&mut session, team_ids.as_ref(), &recent_project_ids);
+
+    if !pruned.is_empty() {
+        tracing::info!(?pruned, "ui_session.pruned");
+
+        store_session(&session).await?;
+    }
+
+    tracing::info!(team_id = ?session.team_id, "ui_session.get.ok");
+
+    Ok(Some(session))
+}
+
+// ========== 界面偏好（供偏好配置文件导入导出） ==========
+
+// 会话 extra 中的界面偏好（缩放、快捷键等）；没有会话或 extra 不是对象时为空
+pub(crate) async fn load_ui_prefs() -> Result<Map<String, Value>, String> {
+    Ok(load_session()
+        .await?
+        .and_then(|session| match session.extra {
+            Some(Value::Object(prefs)) => Some(prefs),
+            _ => None,
+        })
+        .unwrap_or_default())
+}
+
+// 将偏好合并进会话 extra（同名覆盖，其余保留）
+pub(crate) async fn merge_ui_prefs(prefs: Map<String, Value>) -> Result<(), String> {
+    let mut session = load_session().await?.unwrap_or_default();
+
+    session.version = UI_SESSION_VERSION;
+
+    let mut extra = match session.extra.take() {
+        Some(Value::Object(extra)) => extra,
+        _ => Map::new(),
+    };
+
+    extra.extend(prefs);
+    session.extra = Some(Value::Object(extra));
+
+    store_session(&session).await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::{local_storage, MockBackends};
+
+    fn ids(items: &[&str]) -> HashSet<String> {
+        items.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn session(team_id: &str, project_id: Option<&str>) -> UiSession {
+        UiSession {
+            version: UI_SESSION_VERSION,
+            team_id: Some(team_id.to_string()),
+            route: Some(UiRoute {
+                name: "project-detail".to_string(),
+                project_id: project_id.map(str::to_string),
+                params: None,
+            }),
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn valid_session_is_kept() {
+        let mut kept = session("t1", Some("p1"));
+
+        let pruned = prune_session(&mut kept, Some(&ids(&["t1"])), &ids(&["p1"]));
+
+        assert!(pruned.is_empty());
+        assert_eq!(kept, session("t1", Some("p1")));
+    }
+
+    #[test]
+    fn left_team_drops_its_route() {
+        let mut left = session("t-left", None);
+
+        let pruned = prune_session(&mut left, Some(&ids(&["t1"])), &ids(&[]));
+
+        assert_eq!(pruned, ["team_id", "route"]);
+        assert_eq!(left.team_id, None);
+        assert_eq!(left.route, None);
+    }
+
+    #[test]
+    fn unknown_project_route_is_dropped() {
+        let mut stale = session("t1", Some("p-gone"));
+
+        let pruned = prune_session(&mut stale, Some(&ids(&["t1"])), &ids(&["p1"]));
+
+        assert_eq!(pruned, ["route"]);
+        assert_eq!(stale.team_id.as_deref(), Some("t1"));
+    }
+
+    #[test]
+    fn team_is_kept_when_teams_are_unavailable() {
+        let mut offline = session("t1", None);
+
+        assert!(prune_session(&mut offline, None, &ids(&[])).is_empty());
+        assert!(offline.route.is_some());
+    }
+
+    #[tokio::test]
+    async fn saved_session_round_trips_and_keeps_merged_prefs() {
+        // 会话保存在同一个设置键下，持有 MockBackends 避免与其他测试交错
+        let _backends = MockBackends::start().await;
+        local_storage().await;
+
+        save_ui_session(SaveUiSessionReq {
+            state_json: json!({
+                "team_id": "t1",
+                "route": { "name": "home" },
+                "extra": { "zoom": 1.5, "theme": "dark" },
+            })
+            .to_string(),
+        })
+        .await
+        .unwrap();
+
+        let loaded = load_session().await.unwrap().unwrap();
+        assert_eq!(loaded.version, UI_SESSION_VERSION);
+        assert_eq!(loaded.route.unwrap().name, "home");
+
+        let prefs: Map<String, Value> = [("zoom".to_string(), json!(2))].into_iter().collect();
+        merge_ui_prefs(prefs).await.unwrap();
+
+        assert_eq!(
+            Value::Object(load_ui_prefs().await.unwrap()),
+            json!({ "zoom": 2, "theme": "dark" })
+        );
+
+        assert!(save_ui_session(SaveUiSessionReq {
+            state_json: "[1, 2]".to_string(),
+        })
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,170 @@
+// Moetran 文件 url 带签名，数小时后过期（CDN 返回 403 / 410）。
+// 过期时重新获取所属项目的文件列表取得新 url：同一项目并发失败只拉取一次列表，且两次拉取之间至少间隔
+// MIN_REFRESH_INTERVAL；调用方用新 url 重试一次，仍失败时返回原始错误
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::project::{get_project_files, GetProjectFilesReq};
+
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+struct ProjectUrls {
+    // 开始拉取的时间：在此之前失败的请求都可直接使用这次的结果
+    fetched_at: Instant,
+    // file_id -> url
+    urls: HashMap<String, String>,
+}
+
+type RefreshSlot = Arc<tokio::sync::Mutex<Option<ProjectUrls>>>;
+
+// project_id -> 最近一次拉取的结果；拉取期间持有该项目的锁，其他失败的请求等待并复用结果
+static REFRESHES: LazyLock<Mutex<HashMap<String, RefreshSlot>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 签名过期时 CDN 返回的状态码
+pub(crate) fn is_expired_status(status: u16) -> bool {
+    status == 403 || status == 410
+}
+
+// 从 "... status 403 Forbidden" 形式的错误信息中识别签名过期
+pub(crate) fn is_expired_url_error(err: &str) -> bool {
+    err.split("status ")
+        .skip(1)
+        .filter_map(|rest| rest.get(..3)?.parse::<u16>().ok())
+        .any(is_expired_status)
+}
+
+fn slot(project_id: &str) -> RefreshSlot {
+    let mut guard = REFRESHES.lock().unwrap_or_else(|err| err.into_inner());
+
+    guard.entry(project_id.to_string()).or_default().clone()
+}
+
+// failed_at 为请求失败（发现 url 过期）的时间。返回 None 表示无法取得新 url
+pub(crate) async fn refreshed_url(
+    project_id: &str,
+    file_id: &str,
+    failed_at: Instant,
+) -> Option<String> {
+    let slot = slot(project_id);
+    let mut guard = slot.lock().await;
+
+    let covered = guard
+        .as_ref()
+        .is_some_and(|state| state.fetched_at >= failed_at);
+    let rate_limited = guard
+        .as_ref()
+        .is_some_and(|state| state.fetched_at.elapsed() < MIN_REFRESH_INTERVAL);
+
+    if !covered && !rate_limited {
+        let fetched_at = Instant::now();
+
+        tracing::info!(%project_id, "url_refresh.files.start");
+
+        match get_project_files(GetProjectFilesReq {
+            project_id: project_id.into(),
+            target_id: None,
+            sort: None,
+        })
+        .await
+        {
+            Ok(files) => {
+                let urls: HashMap<String, String> = files
+                    .into_iter()
+                    .filter_map(|file| Some((file.id.into(), file.url?)))
+                    .collect();
+
+                tracing::info!(%project_id, count = urls.len(), "url_refresh.files.ok");
+
+                *guard = Some(ProjectUrls { fetched_at, urls });
+            }
+            Err(err) => {
+                tracing::warn!(%project_id, error = %err, "url_refresh.files.failed");
+
+                // 失败也计入间隔，避免每个过期请求都再拉一次
+                *guard = Some(ProjectUrls {
+                    fetched_at,
+                    urls: HashMap::new(),
+                });
+            }
+        }
+    } else if !covered {
+        tracing::debug!(%project_id, "url_refresh.rate_limited");
+    }
+
+    guard.as_ref()?.urls.get(file_id).cloned()
+}
+
+// 过期的 url 换成新 url；新 url 与原 url 相同（未能刷新）时返回 None
+pub(crate) async fn replacement_url(
+    project_id: &str,
+    file_id: &str,
+    expired_url: &str,
+    failed_at: Instant,
+) -> Option<String> {
+    refreshed_url(project_id, file_id, failed_at)
+        .await
+        .filter(|url| url != expired_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    #[test]
+    fn expired_signatures_are_recognised_from_errors() {
+        assert!(is_expired_url_error("下载失败: status 403 Forbidden"));
+        assert!(is_expired_url_error(
+            "HTTP status 500, then status 410 Gone"
+        ));
+        assert!(!is_expired_url_error("status 404 Not Found"));
+        assert!(!is_expired_url_error("status 40"));
+        assert!(!is_expired_url_error("connection reset"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_failures_share_one_rate_limited_refresh() {
+        let backends = MockBackends::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/projects/url-proj/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": "uf1", "name": "1.png", "source_count": 0, "url": "https://cdn/uf1?sig=new", "cover_url": "" },
+                { "id": "uf2", "name": "2.png", "source_count": 0, "url": "https://cdn/uf2?sig=new", "cover_url": "" },
+            ])))
+            .expect(1)
+            .mount(&backends.moetran)
+            .await;
+
+        let failed_at = Instant::now();
+
+        let (first, second) = tokio::join!(
+            refreshed_url("url-proj", "uf1", failed_at),
+            refreshed_url("url-proj", "uf2", failed_at),
+        );
+        assert_eq!(first.as_deref(), Some("https://cdn/uf1?sig=new"));
+        assert_eq!(second.as_deref(), Some("https://cdn/uf2?sig=new"));
+
+        // 之后才失败的请求在间隔内不会再次拉取，沿用上次的结果
+        assert_eq!(
+            replacement_url("url-proj", "uf1", "https://cdn/uf1?sig=old", Instant::now()).await,
+            Some("https://cdn/uf1?sig=new".to_string())
+        );
+        // 新 url 与失败的 url 相同，说明未能刷新
+        assert_eq!(
+            replacement_url("url-proj", "uf1", "https://cdn/uf1?sig=new", Instant::now()).await,
+            None
+        );
+        assert_eq!(refreshed_url("url-proj", "missing", failed_at).await, None);
+
+        backends.moetran.verify().await;
+    }
+}
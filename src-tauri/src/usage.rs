@@ -0,0 +1,380 @@
+// API 使用量统计：按天、按接口族累计请求数与响应字节数，定期落盘
+// 只记录规范化后的路径（id 段折叠为 {id}），不记录查询参数、请求体或任何 token
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    config::config,
+    defer::WarnDefer,
+    instance_lock,
+    storage::{usage_stats, LOCAL_STORAGE},
+};
+
+const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+const DEFAULT_USAGE_DAYS: u32 = 7;
+
+#[derive(Default, Clone, Copy)]
+struct UsageCounter {
+    requests: u64,
+    bytes: u64,
+}
+
+// 尚未落盘的统计，key: (day, family)
+static PENDING_USAGE: LazyLock<Mutex<HashMap<(String, String), UsageCounter>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn day_string(offset_days: i64) -> String {
+    (OffsetDateTime::now_utc() - time::Duration::days(offset_days))
+        .date()
+        .to_string()
+}
+
+// 纯数字，或较长且包含数字的字母数字串（ObjectId / UUID 等）视为 id
+fn looks_like_id(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+
+    if segment.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+
+    segment.len() >= 16
+        && segment.chars().any(|c| c.is_ascii_digit())
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+// 将路径中的 id 段折叠为 {id}：/v1/projects/5f3a.../files -> /v1/projects/{id}/files
+fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if looks_like_id(segment) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn endpoint_family(method: &str, url: &reqwest::Url) -> String {
+    format!(
+        "{} {}{}",
+        method,
+        url.host_str().unwrap_or(""),
+        normalize_path(url.path())
+    )
+}
+
+// 由 http 层在每次收到响应后调用
+pub(crate) fn record_request(method: &str, url: &reqwest::Url, bytes: u64) {
+    let key = (day_string(0), endpoint_family(method, url));
+
+    if let Ok(mut guard) = PENDING_USAGE.lock() {
+        let counter = guard.entry(key).or_default();
+        counter.requests += 1;
+        counter.bytes += bytes;
+    }
+}
+
+// 将内存中的统计写入 SQLite；写入失败的部分放回内存，下次再试
+pub(crate) async fn flush_usage() -> Result<(), String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let pending = match PENDING_USAGE.lock() {
+        Ok(mut guard) => std::mem::take(&mut *guard),
+        Err(_) => return Err("usage stats lock poisoned".to_string()),
+    };
+
+    let mut failed = Vec::new();
+    let mut last_err = None;
+
+    for ((day, family), counter) in pending {
+        if let Err(err) = usage_stats::add_usage(
+            storage.pool(),
+            &day,
+            &family,
+            counter.requests as i64,
+            counter.bytes as i64,
+        )
+        .await
+        {
+            last_err = Some(err);
+            failed.push(((day, family), counter));
+        }
+    }
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    if let Ok(mut guard) = PENDING_USAGE.lock() {
+        for (key, counter) in failed {
+            let entry = guard.entry(key).or_default();
+            entry.requests += counter.requests;
+            entry.bytes += counter.bytes;
+        }
+    }
+
+    Err(last_err.unwrap_or_default())
+}
+
+// 启动后台落盘任务（存储初始化完成后调用），启动时顺带清理过期统计
+pub(crate) fn spawn_usage_flusher() {
+    tauri::async_runtime::spawn(async move {
+        if let Some(storage) = LOCAL_STORAGE.get() {
+            let cutoff = day_string(config().usage_retention_days);
+
+            match usage_stats::prune_usage_before(storage.pool(), &cutoff).await {
+                Ok(removed) if removed > 0 => tracing::info!(removed, "usage.prune.ok"),
+                Ok(_) => {}
+                Err(err) => tracing::warn!(error = %err, "usage.prune.failed"),
+            }
+        }
+
+        loop {
+            tokio::time::sleep(USAGE_FLUSH_INTERVAL).await;
+
+            if !instance_lock::owns_lock() {
+                continue;
+            }
+
+            if let Err(err) = flush_usage().await {
+                tracing::warn!(error = %err, "usage.flush.failed");
+            }
+        }
+    });
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetUsageStatsReq {
+    #[serde(default)]
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UsageFamilyStat {
+    pub family: String,
+    pub request_count: i64,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UsageDayStat {
+    pub day: String,
+    pub families: Vec<UsageFamilyStat>,
+}
+
+async fn load_usage(days: Option<u32>) -> Result<Vec<usage_stats::UsageStatRow>, String> {
+    // 先把内存中的统计落盘，保证返回的数据包含当前会话
+    flush_usage().await?;
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let days = days.unwrap_or(DEFAULT_USAGE_DAYS).max(1) as i64;
+
+    usage_stats::list_usage_since(storage.pool(), &day_string(days - 1)).await
+}
+
+#[tauri::command]
+pub async fn get_usage_stats(payload: GetUsageStatsReq) -> Result<Vec<UsageDayStat>, String> {
+    tracing::info!(days = ?payload.days, "usage.stats.get.start");
+
+    let mut defer = WarnDefer::new("usage.stats.get");
+
+    let rows = load_usage(payload.days).await?;
+
+    // rows 已按 day 倒序排列，相邻同日的行合并为一组
+    let mut result: Vec<UsageDayStat> = Vec::new();
+
+    for row in rows {
+        let family = UsageFamilyStat {
+            family: row.family,
+            request_count: row.request_count,
+            bytes: row.bytes,
+        };
+
+        match result.last_mut() {
+            Some(day) if day.day == row.day => day.families.push(family),
+            _ => result.push(UsageDayStat {
+                day: row.day,
+                families: vec![family],
+            }),
+        }
+    }
+
+    tracing::info!(days = result.len(), "usage.stats.get.ok");
+
+    defer.success();
+
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportUsageStatsCsvReq {
+    pub path: String,
+    #[serde(default)]
+    pub days: Option<u32>,
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// 导出 CSV，返回写入的数据行数
+#[tauri::command]
+pub async fn export_usage_stats_csv(payload: ExportUsageStatsCsvReq) -> Result<usize, String> {
+    tracing::info!(path = %payload.path, days = ?payload.days, "usage.stats.export.start");
+
+    let mut defer = WarnDefer::new("usage.stats.export");
+
+    let rows = load_usage(payload.days).await?;
+
+    let mut csv = String::from("day,family,request_count,bytes\n");
+
+    for row in &rows {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            row.day,
+            csv_field(&row.family),
+            row.request_count,
+            row.bytes
+        ));
+    }
+
+    tokio::fs::write(&payload.path, csv)
+        .await
+        .map_err(|err| format!("写入 CSV 失败: {}", err))?;
+
+    tracing::info!(path = %payload.path, rows = rows.len(), "usage.stats.export.ok");
+
+    defer.success();
+
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::local_storage;
+
+    #[test]
+    fn id_segments_are_folded() {
+        assert_eq!(
+            normalize_path("/v1/projects/5f3a1b2c3d4e5f6a7b8c9d0e/files"),
+            "/v1/projects/{id}/files"
+        );
+        assert_eq!(
+            normalize_path("/v1/files/12345/sources"),
+            "/v1/files/{id}/sources"
+        );
+        assert_eq!(
+            normalize_path("/api/v1/projs/550e8400-e29b-41d4-a716-446655440000"),
+            "/api/v1/projs/{id}"
+        );
+        // 短的字母数字段与不含数字的长段保持原样
+        assert_eq!(normalize_path("/v1/user/info"), "/v1/user/info");
+        assert_eq!(
+            normalize_path("/v1/v2beta/translations"),
+            "/v1/v2beta/translations"
+        );
+        assert_eq!(
+            normalize_path("/v1/members/searchsearchsearch"),
+            "/v1/members/searchsearchsearch"
+        );
+    }
+
+    #[test]
+    fn family_has_method_host_and_no_query() {
+        let url =
+            reqwest::Url::parse("https://api.moetran.com/v1/projects/123/files?page=2&token=x")
+                .unwrap();
+
+        assert_eq!(
+            endpoint_family("GET", &url),
+            "GET api.moetran.com/v1/projects/{id}/files"
+        );
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("GET host/v1/x"), "GET host/v1/x");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[tokio::test]
+    async fn usage_accumulates_per_day_and_family() {
+        let pool = local_storage().await.pool();
+
+        for (day, family, requests, bytes) in [
+            ("2000-01-01", "GET usage-test/a", 1, 10),
+            ("2000-01-01", "GET usage-test/a", 2, 5),
+            ("2000-01-02", "GET usage-test/b", 4, 1),
+            ("2000-01-02", "GET usage-test/a", 1, 1),
+        ] {
+            usage_stats::add_usage(pool, day, family, requests, bytes)
+                .await
+                .unwrap();
+        }
+
+        let rows: Vec<(String, String, i64, i64)> =
+            usage_stats::list_usage_since(pool, "2000-01-01")
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|row| row.family.starts_with("GET usage-test/"))
+                .map(|row| (row.day, row.family, row.request_count, row.bytes))
+                .collect();
+
+        assert_eq!(
+            rows,
+            [
+                (
+                    "2000-01-02".to_string(),
+                    "GET usage-test/b".to_string(),
+                    4,
+                    1
+                ),
+                (
+                    "2000-01-02".to_string(),
+                    "GET usage-test/a".to_string(),
+                    1,
+                    1
+                ),
+                (
+                    "2000-01-01".to_string(),
+                    "GET usage-test/a".to_string(),
+                    3,
+                    15
+                ),
+            ]
+        );
+
+        assert_eq!(
+            usage_stats::prune_usage_before(pool, "2000-01-02")
+                .await
+                .unwrap(),
+            1
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     defer::WarnDefer,
+    error::AppError,
     http::{moetran_get, poprako_post_opt},
 };
 use serde::{Deserialize, Serialize};
@@ -28,21 +29,24 @@ pub struct ResSync {
 
 // 执行 PopRaKo 用户同步（包含登录）
 #[tauri::command]
-pub async fn sync_user(payload: ReqSync) -> Result<ResSync, String> {
+pub async fn sync_user(payload: ReqSync) -> Result<ResSync, AppError> {
     tracing::info!(username = %payload.username, "poprako.sync.request.start");
 
     let mut defer = WarnDefer::new("poprako.sync.request");
 
     let reply: PoprakoEnvelope<ResSync> = poprako_post_opt("sync", Some(payload))
         .await
-        .map_err(|err| format!("Failed to sync user to Poprako: {}", err))?;
+        .map_err(|err| err.context("Failed to sync user to Poprako"))?;
 
     if reply.code != 200 && reply.code != 201 {
         let msg = reply
             .message
             .unwrap_or_else(|| "Poprako sync failed".to_string());
 
-        return Err(msg);
+        return Err(AppError::PoprakoBusiness {
+            code: reply.code,
+            message: msg,
+        });
     }
 
     let data = reply
@@ -67,14 +71,14 @@ pub struct ResUser {
 
 // 获取当前用户信息
 #[tauri::command]
-pub async fn get_user_info() -> Result<ResUser, String> {
+pub async fn get_user_info() -> Result<ResUser, AppError> {
     tracing::info!("user.info.request.start");
 
     let mut defer = WarnDefer::new("user.info.request");
 
     let body: ResUser = moetran_get("user/info", None)
         .await
-        .map_err(|err| format!("Failed to get user info: {}", err))?;
+        .map_err(|err| err.context("Failed to get user info"))?;
 
     tracing::info!("user.info.request.ok");
 
@@ -0,0 +1,342 @@
+// 表单字段错误：PopRaKo 对非法参数返回 422，响应体形如
+// {"detail": [{"loc": ["body", "projset_name"], "msg": "...", "type": "value_error"}]}。
+// 解析为 ValidationErrors（字段名为 loc 去掉开头的 "body" 后以 "." 连接），
+// 与命令自身的预校验使用同一结构，前端据此在对应输入框旁提示；无法识别的响应体保留原始错误信息
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::AppError;
+
+const VALIDATION_ERROR_CODE: &str = "validation_failed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldErrorSource {
+    // 命令发出请求前的本地检查
+    Client,
+    // PopRaKo 返回的 422
+    Server,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    // 点分字段名，如 projset_name、target_languages.0；无法定位到字段时为空字符串
+    pub field: String,
+    pub message: String,
+    // 错误类别：服务端为其 type（如 value_error.any_str.max_length），本地为 missing / too_long 等
+    pub kind: String,
+    pub source: FieldErrorSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationErrors {
+    code: &'static str,
+    pub message: String,
+    pub fields: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self {
+            code: VALIDATION_ERROR_CODE,
+            message: "提交的内容未通过校验".to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    // 记录一条本地预校验错误
+    pub fn client(&mut self, field: &str, kind: &str, message: impl Into<String>) {
+        self.fields.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+            kind: kind.to_string(),
+            source: FieldErrorSource::Client,
+        });
+    }
+
+    // 字段非空（去除首尾空白后）
+    pub fn require(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.client(field, "missing", "不能为空");
+        }
+    }
+
+    // 字段长度（按字符计）不超过 max
+    pub fn max_chars(&mut self, field: &str, value: &str, max: usize) {
+        if value.chars().count() > max {
+            self.client(field, "too_long", format!("不能超过 {} 个字符", max));
+        }
+    }
+
+    // 有本地错误时作为 AppError::Validation 返回（与服务端 422 同一形式），否则放行
+    pub fn check(self) -> Result<(), AppError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            fields = ?self.fields.iter().map(|f| f.field.as_str()).collect::<Vec<_>>(),
+            "validation.client.rejected"
+        );
+
+        Err(AppError::Validation(self))
+    }
+}
+
+impl Default for ValidationErrors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 序列化为 JSON，作为错误信息（尚未迁移到 AppError 的前端代码按此解析）
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match serde_json::to_string(self) {
+            Ok(json) => write!(f, "{}", json),
+            Err(_) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// loc 中的 "body" 只表示参数位置，不是字段名的一部分；数字下标原样保留
+fn field_name(loc: &Value) -> String {
+    match loc {
+        Value::String(name) => name.clone(),
+        Value::Array(parts) => {
+            let parts: Vec<String> = parts
+                .iter()
+                .filter_map(|part| match part {
+                    Value::String(name) => Some(name.clone()),
+                    Value::Number(index) => Some(index.to_string()),
+                    _ => None,
+                })
+                .collect();
+
+            let skip = usize::from(parts.first().is_some_and(|first| first == "body"));
+
+            parts[skip..].join(".")
+        }
+        _ => String::new(),
+    }
+}
+
+fn server_error(field: String, message: String, kind: Option<&str>) -> FieldError {
+    FieldError {
+        field,
+        message,
+        kind: kind.unwrap_or("invalid").to_string(),
+        source: FieldErrorSource::Server,
+    }
+}
+
+fn parse_entry(entry: &Value) -> Option<FieldError> {
+    let message = entry
+        .get("msg")
+        .or_else(|| entry.get("message"))
+        .and_then(Value::as_str)?;
+
+    let field = entry
+        .get("loc")
+        .or_else(|| entry.get("field"))
+        .map(field_name)
+        .unwrap_or_default();
+
+    let kind = entry
+        .get("type")
+        .or_else(|| entry.get("kind"))
+        .and_then(Value::as_str);
+
+    Some(server_error(field, message.to_string(), kind))
+}
+
+// 解析 422 响应体。除标准的 detail 数组外，也接受 { code, data } 包裹、
+// 单个错误对象，以及 { 字段名: 消息 | [消息] } 形式的 detail；都无法识别时返回 None
+pub(crate) fn parse_validation_body(body: &str) -> Option<ValidationErrors> {
+    let value: Value = serde_json::from_str(body).ok()?;
+
+    let detail = value
+        .get("detail")
+        .or_else(|| value.get("data").and_then(|data| data.get("detail")))
+        .or_else(|| value.get("errors"))?;
+
+    let fields: Vec<FieldError> = match detail {
+        Value::Array(entries) => entries.iter().filter_map(parse_entry).collect(),
+        Value::Object(map) if map.contains_key("msg") || map.contains_key("message") => {
+            parse_entry(detail).into_iter().collect()
+        }
+        Value::Object(map) => map
+            .iter()
+            .flat_map(|(field, messages)| {
+                let messages: Vec<&str> = match messages {
+                    Value::String(message) => vec![message.as_str()],
+                    Value::Array(list) => list.iter().filter_map(Value::as_str).collect(),
+                    _ => Vec::new(),
+                };
+
+                messages
+                    .into_iter()
+                    .map(|message| server_error(field.clone(), message.to_string(), None))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if fields.is_empty() {
+        return None;
+    }
+
+    let mut errors = ValidationErrors::new();
+    errors.fields = fields;
+
+    Some(errors)
+}
+
+// PopRaKo 请求失败时的命令错误：字段错误原样返回（前端可解析），其余错误加上上下文说明
+pub(crate) fn poprako_error(context: &str, err: AppError) -> AppError {
+    match err {
+        AppError::Validation(errors) => AppError::Validation(errors),
+        err => err.context(context),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(errors: &ValidationErrors) -> Vec<(&str, &str, &str)> {
+        errors
+            .fields
+            .iter()
+            .map(|f| (f.field.as_str(), f.message.as_str(), f.kind.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn single_field_422_is_parsed() {
+        let body = r#"{"detail": [{"loc": ["body", "projset_name"], "msg": "ensure this value has at most 64 characters", "type": "value_error.any_str.max_length"}]}"#;
+
+        let errors = parse_validation_body(body).unwrap();
+
+        assert_eq!(
+            fields(&errors),
+            [(
+                "projset_name",
+                "ensure this value has at most 64 characters",
+                "value_error.any_str.max_length"
+            )]
+        );
+        assert_eq!(errors.fields[0].source, FieldErrorSource::Server);
+    }
+
+    #[test]
+    fn multi_field_422_keeps_order_and_indices() {
+        let body = r#"{"code": 422, "data": {"detail": [
+            {"loc": ["body", "proj_name"], "msg": "field required", "type": "value_error.missing"},
+            {"loc": ["body", "target_languages", 0], "msg": "unknown language"},
+            {"loc": ["query", "team_id"], "msg": "bad id", "type": "type_error"}
+        ]}}"#;
+
+        let errors = parse_validation_body(body).unwrap();
+
+        assert_eq!(
+            fields(&errors),
+            [
+                ("proj_name", "field required", "value_error.missing"),
+                ("target_languages.0", "unknown language", "invalid"),
+                ("query.team_id", "bad id", "type_error"),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_standard_detail_shapes_are_accepted() {
+        let single = parse_validation_body(
+            r#"{"detail": {"field": "projset_description", "message": "too long", "kind": "too_long"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            fields(&single),
+            [("projset_description", "too long", "too_long")]
+        );
+
+        let map = parse_validation_body(
+            r#"{"errors": {"projset_name": ["重复", "过长"], "team_id": "无效"}}"#,
+        )
+        .unwrap();
+        let mut map = fields(&map);
+        map.sort();
+        assert_eq!(
+            map,
+            [
+                ("projset_name", "过长", "invalid"),
+                ("projset_name", "重复", "invalid"),
+                ("team_id", "无效", "invalid"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unrecognised_bodies_fall_back_to_the_raw_error() {
+        for body in [
+            "<html>Unprocessable</html>",
+            r#"{"detail": "Unprocessable Entity"}"#,
+            r#"{"detail": []}"#,
+            r#"{"detail": [{"loc": ["body", "x"]}]}"#,
+            r#"{"message": "bad"}"#,
+        ] {
+            assert_eq!(parse_validation_body(body), None, "{}", body);
+        }
+
+        let raw = AppError::PoprakoHttp {
+            status: 422,
+            body: "<html>Unprocessable</html>".to_string(),
+        };
+        let err = poprako_error("创建项目集失败", raw);
+        assert_eq!(err.kind(), "PoprakoHttp");
+        assert!(err.to_string().starts_with("创建项目集失败: "));
+
+        // 字段错误不加上下文，保持可解析
+        let validation = poprako_error(
+            "创建项目集失败",
+            AppError::Validation(parse_validation_body(r#"{"detail": {"msg": "x"}}"#).unwrap()),
+        );
+        assert!(matches!(validation, AppError::Validation(_)));
+    }
+
+    #[test]
+    fn client_checks_share_the_server_shape() {
+        assert!(ValidationErrors::new().check().is_ok());
+
+        let mut errors = ValidationErrors::new();
+        errors.require("team_id", "  ");
+        errors.require("projset_name", "名字");
+        errors.max_chars("projset_name", "名字太长了", 4);
+
+        let err = errors.check().unwrap_err();
+        assert_eq!(err.kind(), "Validation");
+
+        let AppError::Validation(errors) = &err else {
+            panic!("unexpected error: {:?}", err);
+        };
+        assert_eq!(
+            fields(errors),
+            [
+                ("team_id", "不能为空", "missing"),
+                ("projset_name", "不能超过 4 个字符", "too_long"),
+            ]
+        );
+        assert!(errors
+            .fields
+            .iter()
+            .all(|f| f.source == FieldErrorSource::Client));
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["errors"]["code"], "validation_failed");
+        assert_eq!(value["errors"]["fields"][0]["source"], "client");
+    }
+}
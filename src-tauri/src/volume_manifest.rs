@@ -0,0 +1,894 @@
+// 按卷清单批量创建项目：清单（TOML 或 JSON，按扩展名区分）描述项目集与各话的名称、简介、序号与可选的目标语言，
+// 先整体校验（清单内重名 / 重复序号、与项目集现有项目的序号冲突、语言代码），再按需创建项目集
+// （按名称精确匹配已有的），然后逐个创建项目，结果写入清单旁的 "<清单名>.report.json"。
+// 重新执行同一清单时，项目集内已有同名项目的话直接跳过，中途失败后可原样重跑
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defer::WarnDefer,
+    project::{
+        create_proj, create_projset, get_team_poprako_projsets, CreateProjReq, CreateProjsetReq,
+        GetTeamPoprakoProjsetsReq,
+    },
+    projset_index::fetch_projset_projs,
+};
+
+// 与创建项目对话框一致的默认值
+const DEFAULT_SOURCE_LANGUAGE: &str = "ja";
+const DEFAULT_TARGET_LANGUAGE: &str = "zh-CN";
+const DEFAULT_ROLE: &str = "63d87c24b8bebd75ff934265";
+
+// 相邻两次创建请求的间隔，避免短时间内大量写请求
+const CREATE_INTERVAL: Duration = Duration::from_millis(800);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestProjset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestChapter {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // 项目集内序号（从 1 开始）
+    pub index: u32,
+    // 不提供时使用清单的 target_languages
+    #[serde(default)]
+    pub target_languages: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeManifest {
+    pub projset: ManifestProjset,
+    #[serde(default)]
+    pub source_language: Option<String>,
+    #[serde(default)]
+    pub target_languages: Option<Vec<String>>,
+    #[serde(default)]
+    pub allow_apply_type: i32,
+    #[serde(default)]
+    pub application_check_type: i32,
+    #[serde(default)]
+    pub default_role: Option<String>,
+    pub chapters: Vec<ManifestChapter>,
+}
+
+impl VolumeManifest {
+    fn source_language(&self) -> &str {
+        self.source_language
+            .as_deref()
+            .unwrap_or(DEFAULT_SOURCE_LANGUAGE)
+    }
+
+    fn targets_for(&self, chapter: &ManifestChapter) -> Vec<String> {
+        chapter
+            .target_languages
+            .clone()
+            .or_else(|| self.target_languages.clone())
+            .unwrap_or_else(|| vec![DEFAULT_TARGET_LANGUAGE.to_string()])
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestIssue {
+    // 问题所在的话；为 None 时针对整个清单或项目集
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<String>,
+    pub message: String,
+}
+
+fn issue(chapter: Option<&str>, message: String) -> ManifestIssue {
+    ManifestIssue {
+        chapter: chapter.map(str::to_string),
+        message,
+    }
+}
+
+// 形如 ja、zh-CN、zh-Hant-TW 的语言代码
+fn is_language_code(code: &str) -> bool {
+    let mut parts = code.split('-');
+
+    let primary_ok = parts.next().is_some_and(|primary| {
+        (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_lowercase())
+    });
+
+    primary_ok
+        && parts.all(|part| {
+            (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+// 名称比较只去掉首尾空白
+fn name_key(name: &str) -> &str {
+    name.trim()
+}
+
+// 只依赖清单本身的检查
+fn validate_manifest(manifest: &VolumeManifest) -> Vec<ManifestIssue> {
+    let mut issues = Vec::new();
+
+    if name_key(&manifest.projset.name).is_empty() {
+        issues.push(issue(None, "项目集名称不能为空".to_string()));
+    }
+
+    if manifest.chapters.is_empty() {
+        issues.push(issue(None, "清单中没有任何话".to_string()));
+    }
+
+    if !is_language_code(manifest.source_language()) {
+        issues.push(issue(
+            None,
+            format!("源语言代码 {} 无效", manifest.source_language()),
+        ));
+    }
+
+    let mut names = HashSet::new();
+    let mut indices: HashMap<u32, &str> = HashMap::new();
+
+    for chapter in &manifest.chapters {
+        let name = name_key(&chapter.name);
+        let label = Some(chapter.name.as_str());
+
+        if name.is_empty() {
+            issues.push(issue(label, "名称不能为空".to_string()));
+        } else if !names.insert(name) {
+            issues.push(issue(label, "清单中存在同名的话".to_string()));
+        }
+
+        if chapter.index == 0 {
+            issues.push(issue(label, "序号从 1 开始".to_string()));
+        } else if let Some(other) = indices.insert(chapter.index, name) {
+            issues.push(issue(
+                label,
+                format!("序号 {} 与「{}」重复", chapter.index, other),
+            ));
+        }
+
+        let targets = manifest.targets_for(chapter);
+
+        if targets.is_empty() {
+            issues.push(issue(label, "目标语言不能为空".to_string()));
+        }
+
+        for code in targets.iter().filter(|code| !is_language_code(code)) {
+            issues.push(issue(label, format!("目标语言代码 {} 无效", code)));
+        }
+    }
+
+    issues
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    Create,
+    // 项目集已存在（按名称匹配），或项目集内已有同名项目
+    Existing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedProjset {
+    pub name: String,
+    pub action: PlanAction,
+    pub projset_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedChapter {
+    pub name: String,
+    pub index: u32,
+    pub target_languages: Vec<String>,
+    pub action: PlanAction,
+    // 已存在时为现有项目的 id
+    pub proj_id: Option<String>,
+}
+
+// 现有项目：名称 -> (proj_id, 序号)
+type ExistingProjs = HashMap<String, (String, u32)>;
+
+// 与项目集现有项目比对：同名的话视为已创建（续跑时跳过）；其余与现有序号冲突的报错
+fn plan_chapters(
+    manifest: &VolumeManifest,
+    existing: &ExistingProjs,
+    issues: &mut Vec<ManifestIssue>,
+) -> Vec<PlannedChapter> {
+    let used_indices: HashMap<u32, &str> = existing
+        .iter()
+        .map(|(name, (_, index))| (*index, name.as_str()))
+        .collect();
+
+    manifest
+        .chapters
+        .iter()
+        .map(|chapter| {
+            let target_languages = manifest.targets_for(chapter);
+
+            if let Some((proj_id, index)) = existing.get(name_key(&chapter.name)) {
+                if *index != chapter.index {
+                    tracing::info!(
+                        name = %chapter.name,
+                        existing_index = index,
+                        manifest_index = chapter.index,
+                        "volume_manifest.existing_index_differs"
+                    );
+                }
+
+                return PlannedChapter {
+                    name: chapter.name.clone(),
+                    index: chapter.index,
+                    target_languages,
+                    action: PlanAction::Existing,
+                    proj_id: Some(proj_id.clone()),
+                };
+            }
+
+            if let Some(other) = used_indices.get(&chapter.index) {
+                issues.push(issue(
+                    Some(&chapter.name),
+                    format!("序号 {} 已被项目集内的「{}」使用", chapter.index, other),
+                ));
+            }
+
+            PlannedChapter {
+                name: chapter.name.clone(),
+                index: chapter.index,
+                target_languages,
+                action: PlanAction::Create,
+                proj_id: None,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterResult {
+    pub name: String,
+    pub index: u32,
+    // created / skipped / failed
+    pub outcome: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proj_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestReport {
+    pub manifest_path: String,
+    pub dry_run: bool,
+    pub projset: PlannedProjset,
+    pub chapters: Vec<PlannedChapter>,
+    // 有问题时不创建任何内容
+    pub issues: Vec<ManifestIssue>,
+    // 实际执行的结果（dry_run 或校验未通过时为空）
+    pub results: Vec<ChapterResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_path: Option<String>,
+}
+
+fn report_path(manifest_path: &Path) -> PathBuf {
+    let stem = manifest_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "manifest".to_string());
+
+    manifest_path.with_file_name(format!("{}.report.json", stem))
+}
+
+async fn write_report(path: &Path, report: &ManifestReport) -> Result<(), String> {
+    let raw =
+        serde_json::to_vec_pretty(report).map_err(|err| format!("序列化创建报告失败: {}", err))?;
+
+    tokio::fs::write(path, raw)
+        .await
+        .map_err(|err| format!("写入创建报告失败: {}", err))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManifestFormat {
+    Toml,
+    Json,
+}
+
+impl ManifestFormat {
+    // .toml 按 TOML 解析，其余按 JSON
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+}
+
+fn parse_manifest(raw: &str, format: ManifestFormat) -> Result<VolumeManifest, String> {
+    match format {
+        ManifestFormat::Toml => {
+            toml::from_str(raw).map_err(|err: toml::de::Error| match err.span() {
+                Some(span) => {
+                    let line = raw[..span.start].matches('\n').count() + 1;
+                    format!("清单格式错误（第 {} 行）: {}", line, err.message())
+                }
+                None => format!("清单格式错误: {}", err.message()),
+            })
+        }
+        ManifestFormat::Json => {
+            let mut de = serde_json::Deserializer::from_str(raw);
+
+            serde_path_to_error::deserialize(&mut de)
+                .map_err(|err| format!("清单格式错误（{}）: {}", err.path(), err.inner()))
+        }
+    }
+}
+
+async fn load_manifest(path: &Path) -> Result<VolumeManifest, String> {
+    let raw = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| format!("读取清单失败: {}", err))?;
+
+    parse_manifest(&raw, ManifestFormat::from_path(path))
+}
+
+async fn find_projset_id(team_id: &str, name: &str) -> Result<Option<String>, String> {
+    let projsets = get_team_poprako_projsets(GetTeamPoprakoProjsetsReq {
+        team_id: team_id.into(),
+    })
+    .await?;
+
+    Ok(projsets
+        .into_iter()
+        .find(|projset| name_key(&projset.projset_name) == name_key(name))
+        .map(|projset| projset.projset_id.into()))
+}
+
+async fn existing_projs(projset_id: &str) -> Result<ExistingProjs, String> {
+    let projs = fetch_projset_projs(projset_id).await?;
+
+    Ok(projs
+        .into_iter()
+        .map(|proj| {
+            (
+                name_key(&proj.proj_name).to_string(),
+                (proj.proj_id.into(), proj.projset_index),
+            )
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateProjectsFromManifestReq {
+    pub team_id: String,
+    pub manifest_path: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[tauri::command]
+pub async fn create_projects_from_manifest(
+    payload: CreateProjectsFromManifestReq,
+) -> Result<ManifestReport, String> {
+    tracing::info!(
+        team_id = %payload.team_id,
+        manifest_path = %payload.manifest_path,
+        dry_run = payload.dry_run,
+        "volume_manifest.create.start"
+    );
+
+    let mut defer = WarnDefer::new("volume_manifest.create");
+
+    let manifest_path = PathBuf::from(&payload.manifest_path);
+    let manifest = load_manifest(&manifest_path).await?;
+
+    let mut issues = validate_manifest(&manifest);
+
+    let projset_id = find_projset_id(&payload.team_id, &manifest.projset.name).await?;
+
+    let existing = match &projset_id {
+        Some(projset_id) => existing_projs(projset_id).await?,
+        None => ExistingProjs::new(),
+    };
+
+    let chapters = plan_chapters(&manifest, &existing, &mut issues);
+
+    let mut report = ManifestReport {
+        manifest_path: payload.manifest_path.clone(),
+        dry_run: payload.dry_run,
+        projset: PlannedProjset {
+            name: manifest.projset.name.clone(),
+            action: match projset_id {
+                Some(_) => PlanAction::Existing,
+                None => PlanAction::Create,
+            },
+            projset_id,
+        },
+        chapters,
+        issues,
+        results: Vec::new(),
+        report_path: None,
+    };
+
+    if payload.dry_run || !report.issues.is_empty() {
+        tracing::info!(
+            issues = report.issues.len(),
+            dry_run = payload.dry_run,
+            "volume_manifest.create.planned"
+        );
+
+        defer.success();
+
+        return Ok(report);
+    }
+
+    let report_file = report_path(&manifest_path);
+    report.report_path = Some(report_file.to_string_lossy().to_string());
+
+    let projset_id = match report.projset.projset_id.clone() {
+        Some(projset_id) => projset_id,
+        None => {
+            create_projset(CreateProjsetReq {
+                projset_name: manifest.projset.name.clone(),
+                projset_description: manifest.projset.description.clone(),
+                team_id: payload.team_id.clone().into(),
+                allow_duplicate: false,
+            })
+            .await?;
+
+            // 创建接口只返回序号，按名称重新查出 id
+            let projset_id = find_projset_id(&payload.team_id, &manifest.projset.name)
+                .await?
+                .ok_or("项目集已创建，但未能在列表中找到".to_string())?;
+
+            report.projset.projset_id = Some(projset_id.clone());
+
+            projset_id
+        }
+    };
+
+    let chapters: HashMap<&str, &ManifestChapter> = manifest
+        .chapters
+        .iter()
+        .map(|chapter| (chapter.name.as_str(), chapter))
+        .collect();
+
+    let mut first_create = true;
+
+    for planned in report.chapters.clone() {
+        if planned.action == PlanAction::Existing {
+            report.results.push(ChapterResult {
+                name: planned.name,
+                index: planned.index,
+                outcome: "skipped".to_string(),
+                proj_id: planned.proj_id,
+                error: None,
+            });
+            continue;
+        }
+
+        if !first_create {
+            tokio::time::sleep(CREATE_INTERVAL).await;
+        }
+        first_create = false;
+
+        let description = chapters
+            .get(planned.name.as_str())
+            .map(|chapter| chapter.description.clone())
+            .unwrap_or_default();
+
+        let created = create_proj(CreateProjReq {
+            proj_name: planned.name.clone(),
+            proj_description: description,
+            team_id: payload.team_id.clone().into(),
+            projset_id: projset_id.clone().into(),
+            workset_index: planned.index as i32,
+            source_language: manifest.source_language().to_string(),
+            target_languages: planned.target_languages.clone(),
+            allow_apply_type: manifest.allow_apply_type,
+            application_check_type: manifest.application_check_type,
+            default_role: manifest
+                .default_role
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ROLE.to_string()),
+            allow_duplicate: false,
+            auto_index: false,
+        })
+        .await;
+
+        let result = match created {
+            Ok(created) => ChapterResult {
+                name: planned.name,
+                index: planned.index,
+                outcome: "created".to_string(),
+                proj_id: Some(created.data.proj_id.into()),
+                error: None,
+            },
+            Err(err) => {
+                tracing::warn!(name = %planned.name, error = %err, "volume_manifest.chapter.failed");
+
+                ChapterResult {
+                    name: planned.name,
+                    index: planned.index,
+                    outcome: "failed".to_string(),
+                    proj_id: None,
+                    error: Some(err.to_string()),
+                }
+            }
+        };
+
+        report.results.push(result);
+
+        // 每话之后写一次报告，进程中断时也能看到已完成的部分
+        write_report(&report_file, &report).await?;
+    }
+
+    write_report(&report_file, &report).await?;
+
+    let failed = report
+        .results
+        .iter()
+        .filter(|result| result.outcome == "failed")
+        .count();
+
+    tracing::info!(
+        results = report.results.len(),
+        failed,
+        "volume_manifest.create.ok"
+    );
+
+    defer.success();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::MockBackends;
+
+    const TOML_MANIFEST: &str = r#"
+target_languages = ["zh-CN"]
+
+[projset]
+name = "第一卷"
+description = "单行本第一卷"
+
+[[chapters]]
+name = "第 1 话"
+index = 1
+
+[[chapters]]
+name = "第 2 话"
+description = "彩页"
+index = 2
+target_languages = ["zh-TW", "en"]
+"#;
+
+    fn manifest(chapters: &[(&str, u32)]) -> VolumeManifest {
+        VolumeManifest {
+            projset: ManifestProjset {
+                name: "第一卷".to_string(),
+                description: String::new(),
+            },
+            source_language: None,
+            target_languages: None,
+            allow_apply_type: 0,
+            application_check_type: 0,
+            default_role: None,
+            chapters: chapters
+                .iter()
+                .map(|(name, index)| ManifestChapter {
+                    name: name.to_string(),
+                    description: String::new(),
+                    index: *index,
+                    target_languages: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn messages(issues: &[ManifestIssue]) -> Vec<String> {
+        issues
+            .iter()
+            .map(|issue| match &issue.chapter {
+                Some(chapter) => format!("{}: {}", chapter, issue.message),
+                None => issue.message.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn toml_manifest_fills_defaults_and_overrides_targets() {
+        let parsed = parse_manifest(TOML_MANIFEST, ManifestFormat::Toml).unwrap();
+
+        assert_eq!(parsed.projset.name, "第一卷");
+        assert_eq!(parsed.source_language(), DEFAULT_SOURCE_LANGUAGE);
+        assert_eq!(parsed.chapters.len(), 2);
+        assert_eq!(parsed.chapters[0].description, "");
+        assert_eq!(parsed.targets_for(&parsed.chapters[0]), ["zh-CN"]);
+        assert_eq!(parsed.targets_for(&parsed.chapters[1]), ["zh-TW", "en"]);
+        assert!(validate_manifest(&parsed).is_empty());
+    }
+
+    #[test]
+    fn json_and_toml_describe_the_same_manifest() {
+        let raw = json!({
+            "target_languages": ["zh-CN"],
+            "projset": { "name": "第一卷", "description": "单行本第一卷" },
+            "chapters": [
+                { "name": "第 1 话", "index": 1 },
+                {
+                    "name": "第 2 话",
+                    "description": "彩页",
+                    "index": 2,
+                    "target_languages": ["zh-TW", "en"],
+                },
+            ],
+        })
+        .to_string();
+
+        let from_json = parse_manifest(&raw, ManifestFormat::Json).unwrap();
+        let from_toml = parse_manifest(TOML_MANIFEST, ManifestFormat::Toml).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(from_json).unwrap(),
+            serde_json::to_value(from_toml).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_errors_name_the_problem() {
+        let toml_err = parse_manifest(
+            "[projset]\nname = \"卷\"\n\n[[chapters]]\nname = \"第 1 话\"\n",
+            ManifestFormat::Toml,
+        )
+        .unwrap_err();
+
+        assert!(toml_err.contains("第 4 行"), "{}", toml_err);
+        assert!(toml_err.contains("index"), "{}", toml_err);
+
+        let json_err = parse_manifest(
+            r#"{ "projset": { "name": "卷" }, "chapters": [{ "name": "第 1 话" }] }"#,
+            ManifestFormat::Json,
+        )
+        .unwrap_err();
+
+        assert!(json_err.contains("index"), "{}", json_err);
+    }
+
+    #[test]
+    fn format_follows_the_extension() {
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("/v/vol1.toml")),
+            ManifestFormat::Toml
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("/v/vol1.TOML")),
+            ManifestFormat::Toml
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("/v/vol1.json")),
+            ManifestFormat::Json
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("/v/vol1")),
+            ManifestFormat::Json
+        );
+        assert_eq!(
+            report_path(Path::new("/v/vol1.toml")),
+            Path::new("/v/vol1.report.json")
+        );
+    }
+
+    #[test]
+    fn language_codes() {
+        for code in ["ja", "zh-CN", "zh-Hant-TW", "yue"] {
+            assert!(is_language_code(code), "{}", code);
+        }
+
+        for code in ["", "JA", "zh_CN", "z", "zh-", "chinese"] {
+            assert!(!is_language_code(code), "{}", code);
+        }
+    }
+
+    #[test]
+    fn validation_reports_duplicates_indices_and_languages() {
+        let mut manifest = manifest(&[("第 1 话", 1), (" 第 1 话 ", 2), ("第 3 话", 1), ("", 0)]);
+        manifest.source_language = Some("JP".to_string());
+        manifest.chapters[2].target_languages = Some(vec!["zh_CN".to_string()]);
+
+        assert_eq!(
+            messages(&validate_manifest(&manifest)),
+            [
+                "源语言代码 JP 无效",
+                " 第 1 话 : 清单中存在同名的话",
+                "第 3 话: 序号 1 与「第 1 话」重复",
+                "第 3 话: 目标语言代码 zh_CN 无效",
+                ": 名称不能为空",
+                ": 序号从 1 开始",
+            ]
+        );
+
+        let mut empty = manifest;
+        empty.projset.name = "  ".to_string();
+        empty.source_language = None;
+        empty.chapters.clear();
+
+        assert_eq!(
+            messages(&validate_manifest(&empty)),
+            ["项目集名称不能为空", "清单中没有任何话"]
+        );
+    }
+
+    #[test]
+    fn existing_chapters_are_matched_by_name_and_skipped() {
+        let manifest = manifest(&[("第 1 话", 1), ("第 2 话", 2), ("第 3 话", 3)]);
+
+        let existing: ExistingProjs = HashMap::from([
+            // 同名但序号被手动改过，仍视为已创建
+            ("第 1 话".to_string(), ("proj-1".to_string(), 5)),
+            ("番外".to_string(), ("proj-x".to_string(), 3)),
+        ]);
+
+        let mut issues = Vec::new();
+        let planned = plan_chapters(&manifest, &existing, &mut issues);
+
+        let actions: Vec<(&str, PlanAction, Option<&str>)> = planned
+            .iter()
+            .map(|chapter| {
+                (
+                    chapter.name.as_str(),
+                    chapter.action,
+                    chapter.proj_id.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            actions,
+            [
+                ("第 1 话", PlanAction::Existing, Some("proj-1")),
+                ("第 2 话", PlanAction::Create, None),
+                ("第 3 话", PlanAction::Create, None),
+            ]
+        );
+        assert_eq!(
+            messages(&issues),
+            ["第 3 话: 序号 3 已被项目集内的「番外」使用"]
+        );
+    }
+
+    fn existing_proj(proj_id: &str, name: &str, index: u32) -> serde_json::Value {
+        json!({
+            "proj_id": proj_id,
+            "proj_name": name,
+            "projset_index": index,
+            "translating_status": 0,
+            "proofreading_status": 0,
+            "typesetting_status": 0,
+            "reviewing_status": 0,
+            "is_published": false,
+        })
+    }
+
+    async fn mount_volume(backends: &MockBackends, projs: Vec<serde_json::Value>) {
+        Mock::given(method("GET"))
+            .and(path("/v1/projsets"))
+            .and(query_param("team_id", "vm-team"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": [{
+                    "projset_id": "vm-set",
+                    "projset_name": "第一卷",
+                    "projset_description": null,
+                    "projset_serial": 1,
+                    "team_id": "vm-team",
+                }],
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": projs,
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+
+        // 续跑与试运行都不应发出创建请求
+        Mock::given(method("POST"))
+            .and(path("/v1/projs"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    fn write_manifest(dir: &Path) -> PathBuf {
+        let manifest_path = dir.join("vol1.toml");
+        std::fs::write(&manifest_path, TOML_MANIFEST).unwrap();
+
+        manifest_path
+    }
+
+    #[tokio::test]
+    async fn dry_run_plans_against_the_existing_projset() {
+        let backends = MockBackends::start().await;
+        mount_volume(&backends, vec![existing_proj("vm-p1", "第 1 话", 1)]).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = write_manifest(dir.path());
+
+        let report = create_projects_from_manifest(CreateProjectsFromManifestReq {
+            team_id: "vm-team".to_string(),
+            manifest_path: manifest_path.to_string_lossy().to_string(),
+            dry_run: true,
+        })
+        .await
+        .unwrap();
+
+        assert!(report.issues.is_empty());
+        assert_eq!(report.projset.action, PlanAction::Existing);
+        assert_eq!(report.projset.projset_id.as_deref(), Some("vm-set"));
+        assert_eq!(report.chapters[0].action, PlanAction::Existing);
+        assert_eq!(report.chapters[1].action, PlanAction::Create);
+        assert_eq!(report.chapters[1].target_languages, ["zh-TW", "en"]);
+        assert!(report.results.is_empty());
+        assert!(!report_path(&manifest_path).exists());
+    }
+
+    #[tokio::test]
+    async fn rerun_skips_chapters_that_were_already_created() {
+        let backends = MockBackends::start().await;
+        mount_volume(
+            &backends,
+            vec![
+                existing_proj("vm-p1", "第 1 话", 1),
+                existing_proj("vm-p2", "第 2 话", 2),
+            ],
+        )
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = write_manifest(dir.path());
+
+        let report = create_projects_from_manifest(CreateProjectsFromManifestReq {
+            team_id: "vm-team".to_string(),
+            manifest_path: manifest_path.to_string_lossy().to_string(),
+            dry_run: false,
+        })
+        .await
+        .unwrap();
+
+        let outcomes: Vec<(&str, Option<&str>)> = report
+            .results
+            .iter()
+            .map(|result| (result.outcome.as_str(), result.proj_id.as_deref()))
+            .collect();
+
+        assert_eq!(
+            outcomes,
+            [("skipped", Some("vm-p1")), ("skipped", Some("vm-p2"))]
+        );
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(report_path(&manifest_path)).unwrap()).unwrap();
+        assert_eq!(written["results"][1]["outcome"], "skipped");
+    }
+}
@@ -0,0 +1,891 @@
+// PopRaKo 写操作离线重试队列（write-behind）
+// 仅用于幂等的 PopRaKo 写操作：PopRaKo 暂时不可达时入队，后台按项目顺序重试
+use std::{collections::HashSet, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::{
+    config::config,
+    connectivity::{self, Backend, BackendStatus},
+    error::AppError,
+    events::{emit_event, WriteConflict, WriteFailed, WriteFlushed},
+    instance_lock,
+    project::{
+        fetch_poprako_proj, member_roles_in, post_proj_assign, put_proj_publish, put_proj_status,
+        MemberRoles,
+    },
+    session,
+    storage::{pending_writes, LOCAL_STORAGE},
+};
+
+const FLUSH_BASE_DELAY: Duration = Duration::from_secs(5);
+const FLUSH_MAX_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PoprakoWrite {
+    ProjStatus {
+        proj_id: String,
+        status_type: String,
+        new_status: i32,
+        // 入队时本地所见的状态；重放时服务端已不是该值说明期间有人改过
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        observed_status: Option<i32>,
+    },
+    Publish {
+        proj_id: String,
+    },
+    Assign {
+        proj_id: String,
+        member_id: String,
+        is_translator: bool,
+        is_proofreader: bool,
+        is_typesetter: bool,
+        is_redrawer: bool,
+        // 入队时本地所见的成员角色，含义同 observed_status
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        observed_roles: Option<MemberRoles>,
+    },
+}
+
+impl PoprakoWrite {
+    pub fn proj_id(&self) -> &str {
+        match self {
+            PoprakoWrite::ProjStatus { proj_id, .. }
+            | PoprakoWrite::Publish { proj_id }
+            | PoprakoWrite::Assign { proj_id, .. } => proj_id,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PoprakoWrite::ProjStatus { .. } => "proj_status",
+            PoprakoWrite::Publish { .. } => "publish",
+            PoprakoWrite::Assign { .. } => "assign",
+        }
+    }
+
+    // 同一 key 的新操作会取代队列中尚未应用的旧操作
+    fn dedupe_key(&self) -> String {
+        match self {
+            PoprakoWrite::ProjStatus {
+                proj_id,
+                status_type,
+                ..
+            } => format!("proj_status:{}:{}", proj_id, status_type),
+            PoprakoWrite::Publish { proj_id } => format!("publish:{}", proj_id),
+            PoprakoWrite::Assign {
+                proj_id, member_id, ..
+            } => format!("assign:{}:{}", proj_id, member_id),
+        }
+    }
+
+    async fn execute(&self) -> Result<(), AppError> {
+        match self {
+            PoprakoWrite::ProjStatus {
+                proj_id,
+                status_type,
+                new_status,
+                ..
+            } => put_proj_status(proj_id, status_type, *new_status).await,
+            PoprakoWrite::Publish { proj_id } => put_proj_publish(proj_id).await,
+            PoprakoWrite::Assign {
+                proj_id,
+                member_id,
+                is_translator,
+                is_proofreader,
+                is_typesetter,
+                is_redrawer,
+                ..
+            } => {
+                post_proj_assign(
+                    proj_id,
+                    member_id,
+                    *is_translator,
+                    *is_proofreader,
+                    *is_typesetter,
+                    *is_redrawer,
+                )
+                .await
+            }
+        }
+    }
+}
+
+// 写操作命令的返回：已立即生效，或已进入离线重试队列
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum WriteOutcome {
+    Applied,
+    Queued { pending_id: i64 },
+}
+
+// 连接类错误（断网、超时、后端维护、离线模式）才值得入队重试
+pub(crate) fn is_connectivity_error(err: &AppError) -> bool {
+    matches!(
+        err,
+        AppError::Network(_) | AppError::ServiceUnavailable { .. } | AppError::Offline
+    )
+}
+
+// 将立即执行失败的写操作持久化到重试队列
+pub(crate) async fn queue_write(
+    write: &PoprakoWrite,
+    err: &AppError,
+) -> Result<WriteOutcome, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let payload = serde_json::to_string(write)
+        .map_err(|err| format!("Failed to serialize pending write: {}", err))?;
+
+    let pending_id = pending_writes::enqueue_pending_write(
+        storage.pool(),
+        write.proj_id(),
+        write.kind(),
+        &write.dedupe_key(),
+        &payload,
+    )
+    .await?;
+
+    tracing::warn!(
+        pending_id,
+        proj_id = %write.proj_id(),
+        kind = write.kind(),
+        error = %err,
+        "poprako.write.queued"
+    );
+
+    Ok(WriteOutcome::Queued { pending_id })
+}
+
+// 写操作已直接成功：同一 dedupe_key 的排队旧操作作废，避免重放时用旧值覆盖
+pub(crate) async fn supersede_pending(write: &PoprakoWrite) {
+    let Some(storage) = LOCAL_STORAGE.get() else {
+        return;
+    };
+
+    match pending_writes::delete_pending_writes_by_key(storage.pool(), &write.dedupe_key()).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(
+            proj_id = %write.proj_id(),
+            kind = write.kind(),
+            count,
+            "poprako.write.superseded"
+        ),
+        Err(err) => tracing::warn!(
+            proj_id = %write.proj_id(),
+            kind = write.kind(),
+            error = %err,
+            "poprako.write.supersede.failed"
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingWriteEvent {
+    pub pending_id: i64,
+    pub proj_id: String,
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+enum FlushRound {
+    // 队列为空或本轮全部处理完
+    Drained,
+    // 仍不可达，需退避
+    Offline { retry_after: Option<u64> },
+    // 会话身份不一致，暂停执行（保留队列），等待重新同步或确认
+    Paused,
+}
+
+#[derive(Debug, PartialEq)]
+enum ConflictCheck {
+    Apply,
+    // 服务端已是目标状态，无需再写
+    AlreadyApplied,
+    Conflict(String),
+}
+
+// 以入队时所见的值为基准：服务端仍是该值才写入，已被他人改动则放弃；
+// 入队时本地没有可比对的值（旧版本入队的操作或项目未缓存）时直接写入
+fn compare_with_observed<T: PartialEq>(
+    current: &T,
+    target: &T,
+    observed: Option<&T>,
+    describe: impl Fn(&T) -> String,
+) -> ConflictCheck {
+    if current == target {
+        return ConflictCheck::AlreadyApplied;
+    }
+
+    match observed {
+        Some(observed) if observed != current => ConflictCheck::Conflict(format!(
+            "服务端已被修改为 {}（入队时为 {}），放弃写入",
+            describe(current),
+            describe(observed)
+        )),
+        _ => ConflictCheck::Apply,
+    }
+}
+
+fn describe_roles(roles: &MemberRoles) -> String {
+    let names: Vec<&str> = [
+        (roles.is_translator, "翻译"),
+        (roles.is_proofreader, "校对"),
+        (roles.is_typesetter, "嵌字"),
+        (roles.is_redrawer, "修图"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, name)| enabled.then_some(name))
+    .collect();
+
+    if names.is_empty() {
+        "无角色".to_string()
+    } else {
+        names.join("/")
+    }
+}
+
+// 刷新前与服务端当前状态比对，避免覆盖入队之后他人做出的修改
+async fn check_conflict(write: &PoprakoWrite) -> Result<ConflictCheck, AppError> {
+    let proj = match fetch_poprako_proj(write.proj_id()).await? {
+        Some(proj) => proj,
+        None => {
+            return Ok(ConflictCheck::Conflict(
+                "项目已不存在于 PopRaKo".to_string(),
+            ))
+        }
+    };
+
+    match write {
+        PoprakoWrite::ProjStatus {
+            status_type,
+            new_status,
+            observed_status,
+            ..
+        } => {
+            let current = match status_type.as_str() {
+                "translating" => proj.translating_status,
+                "proofreading" => proj.proofreading_status,
+                "typesetting" => proj.typesetting_status,
+                "reviewing" => proj.reviewing_status,
+                other => {
+                    return Ok(ConflictCheck::Conflict(format!(
+                        "未知的状态类型: {}",
+                        other
+                    )))
+                }
+            };
+
+            Ok(compare_with_observed(
+                &current,
+                new_status,
+                observed_status.as_ref(),
+                |status| status.to_string(),
+            ))
+        }
+        PoprakoWrite::Publish { .. } if proj.is_published => Ok(ConflictCheck::AlreadyApplied),
+        PoprakoWrite::Publish { .. } => Ok(ConflictCheck::Apply),
+        PoprakoWrite::Assign {
+            member_id,
+            is_translator,
+            is_proofreader,
+            is_typesetter,
+            is_redrawer,
+            observed_roles,
+            ..
+        } => {
+            // search 未返回成员列表时无从比对
+            let Some(members) = proj.members.as_deref() else {
+                return Ok(ConflictCheck::Apply);
+            };
+
+            let target = MemberRoles {
+                is_translator: *is_translator,
+                is_proofreader: *is_proofreader,
+                is_typesetter: *is_typesetter,
+                is_redrawer: *is_redrawer,
+            };
+
+            Ok(compare_with_observed(
+                &member_roles_in(members, member_id),
+                &target,
+                observed_roles.as_ref(),
+                describe_roles,
+            ))
+        }
+    }
+}
+
+fn write_event(
+    row: &pending_writes::PendingWriteRow,
+    message: Option<String>,
+) -> PendingWriteEvent {
+    PendingWriteEvent {
+        pending_id: row.id,
+        proj_id: row.proj_id.clone(),
+        kind: row.kind.clone(),
+        message,
+    }
+}
+
+// 单条待重试写操作的处理结果（由调用方转为前端事件）
+#[derive(Debug)]
+enum ReplayOutcome {
+    Flushed(PendingWriteEvent),
+    Conflict(PendingWriteEvent),
+    Failed(PendingWriteEvent),
+}
+
+async fn flush_once(app: &AppHandle) -> Result<FlushRound, String> {
+    replay_pending(|outcome| match outcome {
+        ReplayOutcome::Flushed(event) => emit_event(app, WriteFlushed(event)),
+        ReplayOutcome::Conflict(event) => emit_event(app, WriteConflict(event)),
+        ReplayOutcome::Failed(event) => emit_event(app, WriteFailed(event)),
+    })
+    .await
+}
+
+// 按入队顺序重放队列，每条处理完即回调 report
+async fn replay_pending(mut report: impl FnMut(ReplayOutcome)) -> Result<FlushRound, String> {
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = pending_writes::list_pending_writes(storage.pool()).await?;
+
+    if rows.is_empty() {
+        return Ok(FlushRound::Drained);
+    }
+
+    // 离线模式下保留队列，关闭后再写回，不累计失败次数
+    if config().offline_mode {
+        return Ok(FlushRound::Paused);
+    }
+
+    // 否则写请求会被 http 层拦截并被误判为服务端拒绝而丢弃
+    if session::ensure_poprako_writable().is_err() {
+        tracing::info!(count = rows.len(), "poprako.write.flush.paused");
+        return Ok(FlushRound::Paused);
+    }
+
+    tracing::info!(count = rows.len(), "poprako.write.flush.start");
+
+    // 某项目的操作失败后，本轮跳过该项目后续操作以保持顺序
+    let mut blocked_projects = HashSet::new();
+
+    for row in rows {
+        if blocked_projects.contains(&row.proj_id) {
+            continue;
+        }
+
+        let write: PoprakoWrite = match serde_json::from_str(&row.payload) {
+            Ok(write) => write,
+            Err(err) => {
+                pending_writes::delete_pending_write(storage.pool(), row.id).await?;
+                report(ReplayOutcome::Failed(write_event(
+                    &row,
+                    Some(err.to_string()),
+                )));
+                continue;
+            }
+        };
+
+        let check = match check_conflict(&write).await {
+            Ok(check) => check,
+            Err(err) if is_connectivity_error(&err) => {
+                pending_writes::mark_pending_write_failed(storage.pool(), row.id, &err.to_string())
+                    .await?;
+
+                return Ok(FlushRound::Offline {
+                    retry_after: retry_after_of(&err),
+                });
+            }
+            Err(err) => {
+                // 无法比对时保守处理：本轮跳过该项目
+                pending_writes::mark_pending_write_failed(storage.pool(), row.id, &err.to_string())
+                    .await?;
+                blocked_projects.insert(row.proj_id.clone());
+                continue;
+            }
+        };
+
+        match check {
+            ConflictCheck::AlreadyApplied => {
+                pending_writes::delete_pending_write(storage.pool(), row.id).await?;
+                report(ReplayOutcome::Flushed(write_event(&row, None)));
+                continue;
+            }
+            ConflictCheck::Conflict(reason) => {
+                tracing::warn!(pending_id = row.id, %reason, "poprako.write.flush.conflict");
+                pending_writes::delete_pending_write(storage.pool(), row.id).await?;
+                report(ReplayOutcome::Conflict(write_event(&row, Some(reason))));
+                continue;
+            }
+            ConflictCheck::Apply => {}
+        }
+
+        match write.execute().await {
+            Ok(()) => {
+                tracing::info!(pending_id = row.id, "poprako.write.flush.ok");
+                pending_writes::delete_pending_write(storage.pool(), row.id).await?;
+                report(ReplayOutcome::Flushed(write_event(&row, None)));
+            }
+            Err(err) if is_connectivity_error(&err) => {
+                pending_writes::mark_pending_write_failed(storage.pool(), row.id, &err.to_string())
+                    .await?;
+
+                return Ok(FlushRound::Offline {
+                    retry_after: retry_after_of(&err),
+                });
+            }
+            Err(err) => {
+                // 服务端明确拒绝，重试无意义
+                tracing::warn!(pending_id = row.id, error = %err, "poprako.write.flush.rejected");
+                pending_writes::delete_pending_write(storage.pool(), row.id).await?;
+                report(ReplayOutcome::Failed(write_event(
+                    &row,
+                    Some(err.to_string()),
+                )));
+                blocked_projects.insert(row.proj_id.clone());
+            }
+        }
+    }
+
+    Ok(FlushRound::Drained)
+}
+
+fn retry_after_of(err: &AppError) -> Option<u64> {
+    match err {
+        AppError::ServiceUnavailable { retry_after, .. } => *retry_after,
+        _ => None,
+    }
+}
+
+// 启动后台刷新任务：正常时按基础间隔轮询，离线时指数退避
+pub fn spawn_flusher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut delay = FLUSH_BASE_DELAY;
+
+        loop {
+            tokio::time::sleep(delay).await;
+
+            // 连通性检查显示 PopRaKo 仍在维护且给出了 Retry-After 时，不提前打扰
+            if let BackendStatus::Down {
+                retry_after: Some(secs),
+                ..
+            } = connectivity::current_status(Backend::Poprako)
+            {
+                delay = delay.max(Duration::from_secs(secs)).min(FLUSH_MAX_DELAY);
+            }
+
+            // 数据目录锁已不属于本进程时暂停，由持锁的实例负责重放
+            if !instance_lock::owns_lock() {
+                tracing::warn!("poprako.write.flush.not_lock_owner");
+                delay = FLUSH_MAX_DELAY;
+                continue;
+            }
+
+            delay = match flush_once(&app).await {
+                Ok(FlushRound::Drained) | Ok(FlushRound::Paused) => FLUSH_BASE_DELAY,
+                Ok(FlushRound::Offline { retry_after }) => {
+                    let backoff = (delay * 2).min(FLUSH_MAX_DELAY);
+
+                    retry_after
+                        .map(|secs| backoff.max(Duration::from_secs(secs)))
+                        .unwrap_or(backoff)
+                }
+                Err(err) => {
+                    tracing::error!(%err, "poprako.write.flush.failed");
+                    (delay * 2).min(FLUSH_MAX_DELAY)
+                }
+            };
+        }
+    });
+}
+
+// ================== 队列查看与手动放弃 ==================
+
+#[derive(Debug, Serialize)]
+pub struct PendingPoprakoWriteItem {
+    pub id: i64,
+    pub proj_id: String,
+    pub kind: String,
+    pub operation: Value,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub queued_at: i64,
+}
+
+#[tauri::command]
+pub async fn list_pending_poprako_writes() -> Result<Vec<PendingPoprakoWriteItem>, String> {
+    tracing::info!("poprako.write.pending.list.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    let rows = pending_writes::list_pending_writes(storage.pool()).await?;
+
+    let items: Vec<PendingPoprakoWriteItem> = rows
+        .into_iter()
+        .map(|row| PendingPoprakoWriteItem {
+            id: row.id,
+            proj_id: row.proj_id,
+            kind: row.kind,
+            operation: serde_json::from_str(&row.payload).unwrap_or(Value::Null),
+            attempts: row.attempts,
+            last_error: row.last_error,
+            queued_at: row.queued_at,
+        })
+        .collect();
+
+    tracing::info!(count = items.len(), "poprako.write.pending.list.ok");
+
+    Ok(items)
+}
+
+#[tauri::command]
+pub async fn discard_pending_write(id: i64) -> Result<(), String> {
+    tracing::info!(id, "poprako.write.pending.discard.start");
+
+    let storage = LOCAL_STORAGE
+        .get()
+        .ok_or("LOCAL_STORAGE not initialized".to_string())?;
+
+    if !pending_writes::delete_pending_write(storage.pool(), id).await? {
+        return Err(format!("待重试写操作不存在: {}", id));
+    }
+
+    tracing::info!(id, "poprako.write.pending.discard.ok");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::test_support::{local_storage, MockBackends};
+
+    fn roles(is_translator: bool, is_proofreader: bool) -> MemberRoles {
+        MemberRoles {
+            is_translator,
+            is_proofreader,
+            is_typesetter: false,
+            is_redrawer: false,
+        }
+    }
+
+    fn status_write(proj_id: &str, new_status: i32, observed_status: Option<i32>) -> PoprakoWrite {
+        PoprakoWrite::ProjStatus {
+            proj_id: proj_id.to_string(),
+            status_type: "translating".to_string(),
+            new_status,
+            observed_status,
+        }
+    }
+
+    fn assign_write(
+        proj_id: &str,
+        target: MemberRoles,
+        observed: Option<MemberRoles>,
+    ) -> PoprakoWrite {
+        PoprakoWrite::Assign {
+            proj_id: proj_id.to_string(),
+            member_id: "m1".to_string(),
+            is_translator: target.is_translator,
+            is_proofreader: target.is_proofreader,
+            is_typesetter: target.is_typesetter,
+            is_redrawer: target.is_redrawer,
+            observed_roles: observed,
+        }
+    }
+
+    #[test]
+    fn status_conflict_compares_with_observed_value() {
+        let describe = |status: &i32| status.to_string();
+
+        assert_eq!(
+            compare_with_observed(&1, &1, Some(&0), describe),
+            ConflictCheck::AlreadyApplied
+        );
+        assert_eq!(
+            compare_with_observed(&0, &1, Some(&0), describe),
+            ConflictCheck::Apply
+        );
+        // 回退（重新打开）也是合法写入，只要服务端仍是入队时的值
+        assert_eq!(
+            compare_with_observed(&2, &1, Some(&2), describe),
+            ConflictCheck::Apply
+        );
+        assert!(matches!(
+            compare_with_observed(&1, &2, Some(&0), describe),
+            ConflictCheck::Conflict(_)
+        ));
+        // 服务端被改为更靠前的值同样是他人的修改
+        assert!(matches!(
+            compare_with_observed(&0, &2, Some(&1), describe),
+            ConflictCheck::Conflict(_)
+        ));
+        assert_eq!(
+            compare_with_observed(&2, &1, None, describe),
+            ConflictCheck::Apply
+        );
+    }
+
+    #[test]
+    fn roles_conflict_compares_with_observed_roles() {
+        let target = roles(true, true);
+
+        assert_eq!(
+            compare_with_observed(
+                &roles(true, true),
+                &target,
+                Some(&roles(false, false)),
+                describe_roles
+            ),
+            ConflictCheck::AlreadyApplied
+        );
+        assert_eq!(
+            compare_with_observed(
+                &roles(true, false),
+                &target,
+                Some(&roles(true, false)),
+                describe_roles
+            ),
+            ConflictCheck::Apply
+        );
+
+        let ConflictCheck::Conflict(reason) = compare_with_observed(
+            &roles(false, false),
+            &target,
+            Some(&roles(true, false)),
+            describe_roles,
+        ) else {
+            panic!("expected conflict");
+        };
+        assert_eq!(reason, "服务端已被修改为 无角色（入队时为 翻译），放弃写入");
+    }
+
+    #[test]
+    fn legacy_payload_without_observed_value_still_decodes() {
+        let write: PoprakoWrite = serde_json::from_value(json!({
+            "kind": "proj_status",
+            "proj_id": "p1",
+            "status_type": "translating",
+            "new_status": 1,
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            write,
+            PoprakoWrite::ProjStatus {
+                observed_status: None,
+                ..
+            }
+        ));
+    }
+
+    async fn clear_queue() -> &'static sqlx::SqlitePool {
+        let pool = local_storage().await.pool();
+
+        sqlx::query("DELETE FROM pending_poprako_writes")
+            .execute(pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    async fn queued_kinds(pool: &sqlx::SqlitePool) -> Vec<String> {
+        pending_writes::list_pending_writes(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.kind)
+            .collect()
+    }
+
+    async fn mount_proj(backends: &MockBackends, proj_id: &str, translating_status: i32) {
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": [{
+                    "proj_id": proj_id,
+                    "proj_name": "测试项目",
+                    "projset_index": 1,
+                    "translating_status": translating_status,
+                    "proofreading_status": 0,
+                    "typesetting_status": 0,
+                    "reviewing_status": 0,
+                    "is_published": false,
+                    "members": [{
+                        "user_id": "u1",
+                        "member_id": "m1",
+                        "username": "成员",
+                        "is_admin": false,
+                        "is_translator": true,
+                        "is_proofreader": false,
+                        "is_typesetter": false,
+                        "is_principal": false,
+                    }],
+                }],
+                "message": null,
+            })))
+            .mount(&backends.poprako)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn direct_write_supersedes_queued_write_with_same_key() {
+        let _backends = MockBackends::start().await;
+        let pool = clear_queue().await;
+
+        queue_write(&status_write("p-supersede", 1, Some(0)), &AppError::Offline)
+            .await
+            .unwrap();
+        queue_write(
+            &assign_write("p-supersede", roles(true, true), None),
+            &AppError::Offline,
+        )
+        .await
+        .unwrap();
+
+        supersede_pending(&status_write("p-supersede", 2, Some(0))).await;
+
+        assert_eq!(queued_kinds(pool).await, ["assign"]);
+    }
+
+    #[tokio::test]
+    async fn requeue_moves_superseded_write_to_the_end() {
+        let _backends = MockBackends::start().await;
+        let pool = clear_queue().await;
+
+        for write in [
+            status_write("p-requeue", 1, Some(0)),
+            PoprakoWrite::Publish {
+                proj_id: "p-requeue".to_string(),
+            },
+            status_write("p-requeue", 2, Some(1)),
+        ] {
+            queue_write(&write, &AppError::Offline).await.unwrap();
+        }
+
+        assert_eq!(queued_kinds(pool).await, ["publish", "proj_status"]);
+    }
+
+    #[tokio::test]
+    async fn replay_applies_writes_in_queue_order() {
+        let backends = MockBackends::start().await;
+        let pool = clear_queue().await;
+
+        mount_proj(&backends, "p-order", 0).await;
+
+        Mock::given(method("PUT"))
+            .and(path("/v1/projs/p-order/status"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/p-order/assign"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&backends.poprako)
+            .await;
+
+        queue_write(&status_write("p-order", 1, Some(0)), &AppError::Offline)
+            .await
+            .unwrap();
+        queue_write(
+            &assign_write("p-order", roles(true, true), Some(roles(true, false))),
+            &AppError::Offline,
+        )
+        .await
+        .unwrap();
+
+        let mut outcomes = Vec::new();
+        let round = replay_pending(|outcome| outcomes.push(outcome))
+            .await
+            .unwrap();
+
+        assert!(matches!(round, FlushRound::Drained));
+        assert!(outcomes
+            .iter()
+            .all(|outcome| matches!(outcome, ReplayOutcome::Flushed(_))));
+        assert!(queued_kinds(pool).await.is_empty());
+
+        let writes: Vec<String> = backends
+            .poprako
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|request| request.url.path() != "/v1/projs/search")
+            .map(|request| format!("{} {}", request.method, request.url.path()))
+            .collect();
+        assert_eq!(
+            writes,
+            [
+                "PUT /v1/projs/p-order/status",
+                "POST /v1/projs/p-order/assign"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_drops_writes_changed_on_server_since_queued() {
+        let backends = MockBackends::start().await;
+        let pool = clear_queue().await;
+
+        // 入队时为 0，服务端已被他人改为 2
+        mount_proj(&backends, "p-conflict", 2).await;
+
+        Mock::given(method("PUT"))
+            .and(path("/v1/projs/p-conflict/status"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&backends.poprako)
+            .await;
+        // 入队时成员无角色，服务端已是翻译
+        Mock::given(method("POST"))
+            .and(path("/v1/projs/p-conflict/assign"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&backends.poprako)
+            .await;
+
+        queue_write(&status_write("p-conflict", 1, Some(0)), &AppError::Offline)
+            .await
+            .unwrap();
+        queue_write(
+            &assign_write("p-conflict", roles(false, true), Some(roles(false, false))),
+            &AppError::Offline,
+        )
+        .await
+        .unwrap();
+
+        let mut outcomes = Vec::new();
+        replay_pending(|outcome| outcomes.push(outcome))
+            .await
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes
+            .iter()
+            .all(|outcome| matches!(outcome, ReplayOutcome::Conflict(_))));
+        assert!(queued_kinds(pool).await.is_empty());
+    }
+}
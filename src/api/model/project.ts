@@ -23,6 +23,22 @@ export interface ResProject {
   projectSet: ResProjectSet;
 }
 
+export type ProjStage = 'translating' | 'proofreading' | 'typesetting' | 'reviewing';
+
+// 未完成阶段中最近的截止时间（due_at 为 Unix 秒）
+export interface NextDeadline {
+  stage: ProjStage;
+  dueAt: number;
+  overdue: boolean;
+}
+
+// 按名称搜索富化时的候选项目（confidence 为 0~1 的匹配分数）
+export interface NameCandidate {
+  id: string;
+  name: string;
+  confidence: number;
+}
+
 // enriched 项目 DTO（Moetran + PopRaKo）
 export interface ResProjectEnriched extends ResProject {
   hasPoprako: boolean;
@@ -41,4 +57,8 @@ export interface ResProjectEnriched extends ResProject {
   // Moetran 原生项目返回的 role 字段（若用户在项目内则为对象，否则为 null）
   // 只需用于判定是否为项目成员，不依赖具体结构
   role?: _ProjectRole | null;
+  nextDeadline?: NextDeadline;
+  // 按名称搜索时未能确定对应的 Moetran 项目：存在时需让用户从 candidates 中确认
+  matchConfidence?: number;
+  candidates?: NameCandidate[];
 }
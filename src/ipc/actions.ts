@@ -0,0 +1,71 @@
+import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from './errors';
+
+// 命令面板的操作目录（与后端 actions 模块对应）
+
+export interface ActionArgSpec {
+  name: string;
+  kind: 'string' | 'bool';
+  required: boolean;
+  // 未提供时由后端从上下文中取值
+  from_context?: 'team_id' | 'proj_id';
+}
+
+export interface ActionInfo {
+  id: string;
+  label: string;
+  args: ActionArgSpec[];
+  enabled: boolean;
+  disabled_reason?: string;
+  // 由前端执行（如切换汉化组），invokeAction 只返回校验后的参数
+  frontend: boolean;
+}
+
+export interface ActionContext {
+  team_id?: string | null;
+  proj_id?: string | null;
+}
+
+export type ActionOutcome =
+  | { kind: 'done'; result: unknown }
+  | { kind: 'frontend'; action_id: string; args: Record<string, unknown> };
+
+// 操作当前不可用时后端返回的错误
+export interface ActionDisabledError {
+  code: 'action_disabled';
+  action_id: string;
+  reason: string;
+}
+
+export function parseActionDisabledError(err: unknown): ActionDisabledError | null {
+  try {
+    const parsed = JSON.parse(errorMessage(err));
+    return parsed && parsed.code === 'action_disabled' ? (parsed as ActionDisabledError) : null;
+  } catch {
+    return null;
+  }
+}
+
+export async function listActions(context: ActionContext = {}): Promise<ActionInfo[]> {
+  try {
+    return await invoke<ActionInfo[]>('list_actions', { payload: { context } });
+  } catch (err) {
+    console.error('[ipc] listActions failed', { context, err });
+    throw err;
+  }
+}
+
+export async function invokeAction(
+  actionId: string,
+  args: Record<string, unknown> = {},
+  context: ActionContext = {},
+): Promise<ActionOutcome> {
+  try {
+    return await invoke<ActionOutcome>('invoke_action', {
+      payload: { action_id: actionId, args, context },
+    });
+  } catch (err) {
+    console.error('[ipc] invokeAction failed', { actionId, args, context, err });
+    throw err;
+  }
+}
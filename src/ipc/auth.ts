@@ -1,7 +1,34 @@
 import { invoke } from '@tauri-apps/api/core';
 import { ReqToken, ResCaptcha, ResToken } from '../api/model/auth';
+import { errorMessage } from './errors';
+import { EVENT_NAMES } from './events.gen';
 
-export async function getCaptcha(): Promise<ResCaptcha> {
+// 登录过期（后端返回 401）时发出的事件，重新登录（保存新 token）前每个后端只发送一次；
+// 收到后应弹出重新登录对话框。命令本身同时以 kind 为 AuthExpired 的错误失败（见 isAuthExpired）
+export const MOETRAN_AUTH_EXPIRED_EVENT = EVENT_NAMES.MoetranAuthExpired;
+export const POPRAKO_AUTH_EXPIRED_EVENT = EVENT_NAMES.PoprakoAuthExpired;
+
+// 验证码请求过于频繁时后端返回的错误
+export interface CaptchaRateLimitedError {
+  code: 'captcha_rate_limited';
+  message: string;
+  // 需等待的秒数
+  retry_after: number;
+}
+
+export function parseCaptchaRateLimitedError(error: unknown): CaptchaRateLimitedError | null {
+  try {
+    const parsed = JSON.parse(errorMessage(error));
+    return parsed && parsed.code === 'captcha_rate_limited'
+      ? (parsed as CaptchaRateLimitedError)
+      : null;
+  } catch {
+    return null;
+  }
+}
+
+// 有效期内重复调用返回同一张验证码；forceNew 为 true 时重新获取（“换一张”）
+export async function getCaptcha(forceNew = false): Promise<ResCaptcha> {
   try {
     // Raw shape from Rust
     interface RawResCaptcha {
@@ -9,7 +36,7 @@ export async function getCaptcha(): Promise<ResCaptcha> {
       info: string;
     }
 
-    const raw = await invoke<RawResCaptcha>('get_captcha');
+    const raw = await invoke<RawResCaptcha>('get_captcha', { forceNew });
     return { image: raw.image, info: raw.info };
   } catch (error) {
     console.error('Error in getCaptcha:', error);
@@ -0,0 +1,98 @@
+import { invoke } from '@tauri-apps/api/core';
+import { EVENT_NAMES, type ConfigEntry, type PoprakoHealth } from './events.gen';
+import { errorMessage } from './errors';
+
+// 单个后端的连通性状态（与后端 BackendStatus 对应）
+export type BackendStatus =
+  | { state: 'unknown' }
+  | { state: 'online' }
+  | { state: 'down'; status: number; retry_after: number | null; excerpt: string }
+  | { state: 'unreachable'; reason: string };
+
+export interface ConnectivityStatus {
+  moetran: BackendStatus;
+  poprako: BackendStatus;
+  // PopRaKo 自报的健康状态：poprako 为 online 而此项为 degraded / down 时是服务端的问题
+  poprako_health: PoprakoHealth['state'];
+  offline_mode: boolean;
+}
+
+// 离线模式切换时后端发出的事件，payload 为 { enabled: boolean }
+export const OFFLINE_MODE_CHANGED_EVENT = EVENT_NAMES.OfflineModeChanged;
+
+// 离线模式下请求被拦截时，错误信息以该前缀开头
+const OFFLINE_ERROR_PREFIX = 'offline_mode';
+
+export function isOfflineError(err: unknown): boolean {
+  return errorMessage(err).includes(OFFLINE_ERROR_PREFIX);
+}
+
+// 获取最近一次观测到的后端连通性
+export async function getConnectivityStatus(): Promise<ConnectivityStatus> {
+  try {
+    return await invoke<ConnectivityStatus>('get_connectivity_status');
+  } catch (err) {
+    console.error('[ipc] getConnectivityStatus failed', err);
+    throw err;
+  }
+}
+
+// 开关离线模式：开启后不再发出网络请求，只使用本地缓存
+export async function setOfflineMode(enabled: boolean): Promise<ConnectivityStatus> {
+  try {
+    return await invoke<ConnectivityStatus>('set_offline_mode', {
+      payload: { enabled },
+    });
+  } catch (err) {
+    console.error('[ipc] setOfflineMode failed', { enabled, err });
+    throw err;
+  }
+}
+
+export type { PoprakoHealth };
+
+// PopRaKo 健康状态变化时后端发出的事件，payload 为 PoprakoHealth
+export const POPRAKO_HEALTH_CHANGED_EVENT = EVENT_NAMES.PoprakoHealthChanged;
+
+// PopRaKo 服务端健康状态（结果短暂缓存）；旧版服务端不支持时 supported 为 false、state 为 unknown
+export async function getPoprakoHealth(): Promise<PoprakoHealth> {
+  try {
+    return await invoke<PoprakoHealth>('get_poprako_health');
+  } catch (err) {
+    console.error('[ipc] getPoprakoHealth failed', err);
+    throw err;
+  }
+}
+
+// Moetran / PopRaKo API 地址；source 为 env 时由环境变量指定，设置中的值不会生效
+export interface ApiEndpoints {
+  moetran_api_base: ConfigEntry;
+  poprako_api_base: ConfigEntry;
+}
+
+export async function getApiEndpoints(): Promise<ApiEndpoints> {
+  try {
+    return await invoke<ApiEndpoints>('get_api_endpoints');
+  } catch (err) {
+    console.error('[ipc] getApiEndpoints failed', err);
+    throw err;
+  }
+}
+
+// 未传的地址不修改，传空字符串恢复默认；地址须为 http / https，修改后立即生效
+export async function setApiEndpoints(endpoints: {
+  moetranApiBase?: string;
+  poprakoApiBase?: string;
+}): Promise<ApiEndpoints> {
+  try {
+    return await invoke<ApiEndpoints>('set_api_endpoints', {
+      payload: {
+        moetran_api_base: endpoints.moetranApiBase,
+        poprako_api_base: endpoints.poprakoApiBase,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] setApiEndpoints failed', { endpoints, err });
+    throw err;
+  }
+}
@@ -0,0 +1,40 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// 数据目录（本地数据库与图片缓存所在）。修改后下次启动生效，已有数据不会被移动
+
+export type DataDirSource = 'env' | 'chosen' | 'legacy' | 'platform' | 'fallback';
+
+export interface DataDirInfo {
+  // 当前使用的数据目录
+  current: string;
+  source: DataDirSource;
+  // 已选择的根目录（未选择时为 null）
+  chosen: string | null;
+  // 未选择时使用的平台默认根目录
+  platform_default: string | null;
+  // 设置了环境变量 APP_DIR，无法修改
+  overridden_by_env: boolean;
+  // 下次启动时使用的目录与当前不同，需要重启
+  restart_required: boolean;
+}
+
+export async function getDataDir(): Promise<DataDirInfo> {
+  try {
+    return await invoke<DataDirInfo>('get_data_dir');
+  } catch (err) {
+    console.error('[ipc] getDataDir failed', err);
+    throw err;
+  }
+}
+
+// path 须为可写入的绝对路径；传 null 恢复默认
+export async function setDataDir(path: string | null): Promise<DataDirInfo> {
+  try {
+    return await invoke<DataDirInfo>('set_data_dir', {
+      payload: { path },
+    });
+  } catch (err) {
+    console.error('[ipc] setDataDir failed', { path, err });
+    throw err;
+  }
+}
@@ -0,0 +1,40 @@
+import { invoke } from '@tauri-apps/api/core';
+import { EVENT_NAMES } from './events.gen';
+import { errorMessage } from './errors';
+
+// 演示模式：请求由后端内置的演示数据应答，不访问真实后端，写操作只在内存中生效
+
+export interface DemoModeStatus {
+  enabled: boolean;
+}
+
+// 切换演示模式时后端发出的事件，payload 为 { enabled: boolean }；前端据此显示演示横幅
+export const DEMO_MODE_CHANGED_EVENT = EVENT_NAMES.DemoModeChanged;
+
+// 演示模式下访问真实后端被拦截或接口不受支持时，错误信息以该前缀开头
+const DEMO_ERROR_PREFIX = 'demo_mode';
+
+export function isDemoModeError(err: unknown): boolean {
+  return errorMessage(err).includes(DEMO_ERROR_PREFIX);
+}
+
+export async function getDemoMode(): Promise<DemoModeStatus> {
+  try {
+    return await invoke<DemoModeStatus>('get_demo_mode');
+  } catch (err) {
+    console.error('[ipc] getDemoMode failed', err);
+    throw err;
+  }
+}
+
+// 切换后无需重启；开启时演示数据重新生成
+export async function setDemoMode(enabled: boolean): Promise<DemoModeStatus> {
+  try {
+    return await invoke<DemoModeStatus>('set_demo_mode', {
+      payload: { enabled },
+    });
+  } catch (err) {
+    console.error('[ipc] setDemoMode failed', { enabled, err });
+    throw err;
+  }
+}
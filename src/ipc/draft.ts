@@ -0,0 +1,72 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// 翻译草稿：编辑器防抖保存到本地 SQLite，应用崩溃或 Moetran 不可用时不丢失正在输入的译文。
+// 提交 / 更新翻译成功后后端会自动删除对应草稿
+
+export interface TranslationDraft {
+  source_id: string;
+  target_id: string;
+  content: string;
+  // Unix 时间戳（秒）
+  updated_at: number;
+}
+
+export async function saveTranslationDraft(params: {
+  sourceId: string;
+  targetId: string;
+  fileId: string;
+  content: string;
+}): Promise<void> {
+  try {
+    await invoke<void>('save_translation_draft', {
+      payload: {
+        source_id: params.sourceId,
+        target_id: params.targetId,
+        file_id: params.fileId,
+        content: params.content,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] saveTranslationDraft failed', { sourceId: params.sourceId, err });
+    throw err;
+  }
+}
+
+// 某页（file + target）的全部草稿，按修改时间从新到旧
+export async function getTranslationDrafts(
+  fileId: string,
+  targetId: string
+): Promise<TranslationDraft[]> {
+  try {
+    return await invoke<TranslationDraft[]>('get_translation_drafts', {
+      payload: { file_id: fileId, target_id: targetId },
+    });
+  } catch (err) {
+    console.error('[ipc] getTranslationDrafts failed', { fileId, targetId, err });
+    throw err;
+  }
+}
+
+// 放弃草稿，返回是否存在该草稿
+export async function clearTranslationDraft(sourceId: string, targetId: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>('clear_translation_draft', {
+      payload: { source_id: sourceId, target_id: targetId },
+    });
+  } catch (err) {
+    console.error('[ipc] clearTranslationDraft failed', { sourceId, targetId, err });
+    throw err;
+  }
+}
+
+// 删除最后修改早于 days 天前的草稿（缺省时按 draft_retention_days），返回删除条数
+export async function purgeOldDrafts(days?: number): Promise<number> {
+  try {
+    return await invoke<number>('purge_old_drafts', {
+      payload: { days: days ?? null },
+    });
+  } catch (err) {
+    console.error('[ipc] purgeOldDrafts failed', { days, err });
+    throw err;
+  }
+}
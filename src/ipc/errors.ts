@@ -0,0 +1,95 @@
+// 命令返回的结构化错误（与后端 AppError 对应）。已迁移的命令以 { kind, message, ... } 对象抛出，
+// 其余命令仍抛出字符串；message 与原先的字符串错误相同，按字符串识别错误时先用 errorMessage 取出
+
+export type AppErrorKind =
+  | 'Network'
+  | 'AuthExpired'
+  | 'PoprakoBusiness'
+  | 'MoetranHttp'
+  | 'PoprakoHttp'
+  | 'ServiceUnavailable'
+  | 'Offline'
+  | 'DeadlineExceeded'
+  | 'Validation'
+  | 'Storage'
+  | 'InvalidInput'
+  | 'NotFound'
+  | 'Conflict'
+  | 'DeleteBlocked'
+  | 'DuplicateName'
+  | 'InsufficientDisk'
+  | 'AlreadyInProgress'
+  | 'Retryable'
+  | 'Other';
+
+// 删除 / 移除前的影响检查结果（DeleteBlocked 错误携带）
+export type ImpactCount = { state: 'known'; count: number } | { state: 'unknown'; reason: string };
+
+export interface DeleteImpact {
+  entity: { kind: 'source' | 'file' | 'target' | 'member' } & Record<string, string>;
+  items: { label: string; count: ImpactCount }[];
+}
+
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+  // AuthExpired：哪个后端的登录已过期
+  backend?: 'moetran' | 'poprako';
+  // PoprakoBusiness：响应中的 code
+  code?: number;
+  // MoetranHttp / PoprakoHttp / ServiceUnavailable：HTTP 状态码
+  status?: number;
+  body?: string;
+  retry_after?: number | null;
+  // Validation：字段错误（结构同 project.ts 中的 ValidationErrors）
+  errors?: unknown;
+  // DeleteBlocked：会受影响的关联数据，确认后以 force 重试
+  impact?: DeleteImpact;
+  // InsufficientDisk：所需与可用的字节数，以及“释放空间”入口对应的清理命令
+  required?: number;
+  available?: number;
+  cleanup_command?: string;
+  // AlreadyInProgress：正在下载的项目与该下载任务的进度
+  project_id?: string;
+  progress?: { total: number; completed: number; failed: number };
+  // Retryable：已登记重试（见 retry.ts），cause 为原错误的类别
+  retry_token?: string;
+  command?: string;
+  cause?: AppErrorKind;
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return (
+    typeof err === 'object' &&
+    err !== null &&
+    typeof (err as AppError).kind === 'string' &&
+    typeof (err as AppError).message === 'string'
+  );
+}
+
+// 错误的文字说明：结构化错误取 message，其余按字符串处理
+export function errorMessage(err: unknown): string {
+  if (isAppError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  return String(err);
+}
+
+// 请求的资源不存在（如项目已被删除）
+export function isNotFound(err: unknown): boolean {
+  return isAppError(err) && err.kind === 'NotFound';
+}
+
+// 后端以 409 拒绝操作（如项目中仍有未校对的原文），应提示用户处理后再试
+export function isConflict(err: unknown): boolean {
+  return isAppError(err) && err.kind === 'Conflict';
+}
+
+// 删除 / 移除会影响关联数据，需展示 impact 并由用户确认
+export function isDeleteBlocked(err: unknown): err is AppError & { impact: DeleteImpact } {
+  return isAppError(err) && err.kind === 'DeleteBlocked';
+}
+
+// 登录已过期，需要重新登录
+export function isAuthExpired(err: unknown): boolean {
+  return isAppError(err) && err.kind === 'AuthExpired';
+}
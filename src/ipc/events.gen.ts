@@ -0,0 +1,131 @@
+// 由 src-tauri/src/events.rs 生成（UPDATE_EVENT_BINDINGS=1 cargo test events:: 重新生成），请勿手动修改
+
+export interface MoetranUserBrief {
+  id: string;
+  name: string;
+}
+
+export interface MoetranTranslation {
+  id: string;
+  content: string;
+  proofread_content: string | null;
+  selected: boolean;
+  user: MoetranUserBrief | null;
+  proofreader: MoetranUserBrief | null;
+}
+
+export interface MoetranSource {
+  id: string;
+  x: number;
+  y: number;
+  position_type: number;
+  my_translation: MoetranTranslation | null;
+  translations: MoetranTranslation[];
+  has_draft: boolean;
+  stale?: boolean;
+}
+
+export interface BulkPublishSummary {
+  dry_run: boolean;
+  ready: number;
+  published: number;
+  skipped: number;
+  failed: number;
+}
+
+export interface PendingWriteEvent {
+  pending_id: number;
+  proj_id: string;
+  kind: string;
+  message?: string;
+}
+
+export interface ConfigEntry {
+  key: string;
+  value: string;
+  source: 'env' | 'db' | 'default';
+  runtime_tunable: boolean;
+}
+
+export interface SessionIdentity {
+  moetran_user_id: string | null;
+  moetran_user_name: string | null;
+  poprako_user_id: string | null;
+  status: 'unknown' | 'match' | 'mismatch';
+  reason: string | null;
+  confirmed: boolean;
+  checked_at: number | null;
+}
+
+export interface PoprakoHealth {
+  state: 'unknown' | 'up' | 'degraded' | 'down' | 'unreachable';
+  supported: boolean;
+  version: string | null;
+  uptime_seconds: number | null;
+  components: Record<string, string>;
+  metrics: Record<string, number>;
+  reason?: string;
+  checked_at: number;
+}
+
+export interface DownloadReport {
+  downloaded: number;
+  skipped: number;
+  failed: number[];
+}
+
+export interface LostTranslation {
+  queue_id: number;
+  source_id: string;
+  target_id: string;
+  translation_id: string;
+  file_id: string;
+  reason: 'missing' | 'altered';
+  content: string;
+  current_content?: string;
+  submitted_at: number;
+}
+
+export const EVENT_NAMES = {
+  SourcesUpdated: 'sources-updated',
+  BulkPublished: 'publish://bulk-completed',
+  WriteFlushed: 'poprako-write-flushed',
+  WriteFailed: 'poprako-write-failed',
+  WriteConflict: 'poprako-write-conflict',
+  OfflineModeChanged: 'offline-mode-changed',
+  DemoModeChanged: 'demo-mode-changed',
+  ConfigChanged: 'config-changed',
+  IdentityMismatch: 'session://identity-mismatch',
+  PoprakoHealthChanged: 'poprako-health-changed',
+  TranslationLost: 'translation-lost',
+  MoetranAuthExpired: 'auth://moetran-expired',
+  PoprakoAuthExpired: 'auth://poprako-expired',
+  DownloadProgressed: 'image-cache://progress',
+  DownloadFinished: 'image-cache://done',
+  CacheEvicted: 'image-cache://evicted',
+  UploadProgressed: 'upload://progress',
+  MentionReceived: 'project-notes://mentioned',
+} as const;
+
+export interface EventPayloads {
+  'sources-updated': { file_id: string; target_id: string; sources: MoetranSource[] };
+  'publish://bulk-completed': BulkPublishSummary;
+  'poprako-write-flushed': PendingWriteEvent;
+  'poprako-write-failed': PendingWriteEvent;
+  'poprako-write-conflict': PendingWriteEvent;
+  'offline-mode-changed': { enabled: boolean };
+  'demo-mode-changed': { enabled: boolean };
+  'config-changed': ConfigEntry[];
+  'session://identity-mismatch': SessionIdentity;
+  'poprako-health-changed': PoprakoHealth;
+  'translation-lost': LostTranslation;
+  'auth://moetran-expired': Record<string, never>;
+  'auth://poprako-expired': Record<string, never>;
+  'image-cache://progress': { project_id: string; completed: number; total: number; failed: number; current_file_index: number };
+  'image-cache://done': { project_id: string; status: 'completed' | 'failed' | 'cancelled' | 'error'; report: DownloadReport | null; message: string | null };
+  'image-cache://evicted': { project_ids: string[]; used_bytes: number; limit_bytes: number };
+  'upload://progress': { project_id: string; completed: number; total: number; failed: number; current_file_name: string; bytes_sent: number; bytes_total: number };
+  'project-notes://mentioned': { note_id: number; team_id: string; project_id: string; project_name: string | null; body: string };
+}
+
+export type AppEventName = keyof EventPayloads;
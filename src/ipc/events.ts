@@ -0,0 +1,18 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { AppEventName, EventPayloads } from './events.gen';
+
+export { EVENT_NAMES } from './events.gen';
+export type { AppEventName, EventPayloads } from './events.gen';
+
+// 按事件名推断 payload 类型的监听封装
+export async function listenEvent<K extends AppEventName>(
+  name: K,
+  handler: (payload: EventPayloads[K]) => void
+): Promise<UnlistenFn> {
+  try {
+    return await listen<EventPayloads[K]>(name, event => handler(event.payload));
+  } catch (err) {
+    console.error('[ipc] listenEvent failed', { name, err });
+    throw err;
+  }
+}
@@ -1,13 +1,50 @@
 // 图片缓存相关 IPC 调用
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+import { isAppError, type AppError } from './errors';
+import { EVENT_NAMES, type DownloadReport } from './events.gen';
 
 export interface FileDownloadInfo {
   url: string;
+  // 提供时缓存按文件 id 命名，读取不受文件顺序变化影响
+  id?: string;
 }
 
 export interface CachedFileData {
   b64: string;
   content_type: string;
+  width: number | null;
+  height: number | null;
+  // 长条图：应使用分块渲染（generateTiles / getTile）
+  is_tall: boolean;
+  tiles_ready: boolean;
+}
+
+export interface TileInfo {
+  n: number;
+  offset_y: number;
+  height: number;
+}
+
+export interface TileIndex {
+  source_width: number;
+  source_height: number;
+  tile_height: number;
+  tiles: TileInfo[];
+  complete: boolean;
+}
+
+export interface CachedTileData {
+  b64: string;
+  content_type: string;
+  offset_y: number;
+  height: number;
+}
+
+export interface CachedThumbnailData {
+  b64: string;
+  content_type: string;
+  width: number;
+  height: number;
 }
 
 export interface CachedProjectMetadata {
@@ -17,6 +54,13 @@ export interface CachedProjectMetadata {
   fileCount: number;
   totalSizeBytes: number;
   cachedAt: number; // Unix timestamp
+  // 最近一次与远端比对的结果（从未比对时为 null）
+  remoteFileCount: number | null;
+  // 远端有、本地缺少的页数
+  missingCount: number | null;
+  remoteCheckedAt: number | null;
+  // 远端项目已不存在
+  remoteDeleted: boolean;
 }
 
 // Raw snake_case 结构体，用于接收 Rust 后端数据
@@ -27,6 +71,25 @@ interface RawCachedProjectMetadata {
   file_count: number;
   total_size_bytes: number;
   cached_at: number;
+  remote_file_count?: number | null;
+  missing_count?: number | null;
+  remote_checked_at?: number | null;
+  remote_deleted?: boolean;
+}
+
+function mapRawCachedProject(r: RawCachedProjectMetadata): CachedProjectMetadata {
+  return {
+    projectId: r.project_id,
+    projectName: r.project_name,
+    status: r.status,
+    fileCount: r.file_count,
+    totalSizeBytes: r.total_size_bytes,
+    cachedAt: r.cached_at,
+    remoteFileCount: r.remote_file_count ?? null,
+    missingCount: r.missing_count ?? null,
+    remoteCheckedAt: r.remote_checked_at ?? null,
+    remoteDeleted: r.remote_deleted ?? false,
+  };
 }
 
 /**
@@ -43,19 +106,33 @@ export async function checkFileCache(projectId: string): Promise<boolean> {
   }
 }
 
+export type { DownloadReport };
+
+// 每个文件下载结束后发出，payload 含 completed / total / failed 与 current_file_index
+export const DOWNLOAD_PROGRESS_EVENT = EVENT_NAMES.DownloadProgressed;
+// 整个下载结束后发出，payload 含 status 与 report（出错或取消时为 message）
+export const DOWNLOAD_DONE_EVENT = EVENT_NAMES.DownloadFinished;
+// 缓存超出容量上限、淘汰了部分项目后发出，payload 含被删除的 project_ids
+export const CACHE_EVICTED_EVENT = EVENT_NAMES.CacheEvicted;
+
 /**
- * 下载整个项目的所有图片到本地缓存
+ * 下载整个项目的所有图片到本地缓存；部分文件失败时不抛出，
+ * 返回值的 failed 为失败文件在 files 中的下标，可只重新下载这些文件
  */
 export async function downloadProjectFiles(
   projectId: string,
   projectName: string,
-  files: FileDownloadInfo[]
-): Promise<void> {
+  files: FileDownloadInfo[],
+  // wait: 该项目已在下载时等待其结束；否则立即以 AlreadyInProgress 错误返回
+  options: { verifyFreshness?: boolean; wait?: boolean } = {}
+): Promise<DownloadReport> {
   try {
-    await invoke('download_project_files', {
+    return await invoke<DownloadReport>('download_project_files', {
       projectId,
       projectName,
       files,
+      verifyFreshness: options.verifyFreshness ?? false,
+      wait: options.wait ?? false,
     });
   } catch (error) {
     console.error('Error in downloadProjectFiles:', { projectId, projectName, files, error });
@@ -63,6 +140,97 @@ export async function downloadProjectFiles(
   }
 }
 
+/**
+ * 只重新下载上次下载中缺失、为空或被截断的文件（文件列表由后端在下载时保存）。
+ * 旧版本下载的缓存没有文件列表，此时会报错，需要重新下载整个项目
+ */
+export async function retryFailedDownloads(projectId: string): Promise<DownloadReport> {
+  try {
+    return await invoke<DownloadReport>('retry_failed_downloads', { projectId });
+  } catch (error) {
+    console.error('Error in retryFailedDownloads:', { projectId, error });
+    throw error;
+  }
+}
+
+export interface DownloadProgress {
+  // 需要下载的文件数（检查已缓存文件之前为 0）
+  total: number;
+  completed: number;
+  failed: number;
+}
+
+// 该项目已有下载任务进行中时后端返回的错误
+export type DownloadInProgressError = AppError & {
+  kind: 'AlreadyInProgress';
+  project_id: string;
+  progress: DownloadProgress;
+};
+
+export function parseDownloadInProgressError(error: unknown): DownloadInProgressError | null {
+  return isAppError(error) && error.kind === 'AlreadyInProgress'
+    ? (error as DownloadInProgressError)
+    : null;
+}
+
+/**
+ * 取消项目进行中的下载，返回是否存在该任务
+ */
+export async function cancelProjectDownload(projectId: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>('cancel_project_download', { projectId });
+  } catch (error) {
+    console.error('Error in cancelProjectDownload:', { projectId, error });
+    throw error;
+  }
+}
+
+/**
+ * 查询项目进行中的下载进度，没有下载任务时返回 null
+ */
+export async function getProjectDownloadProgress(
+  projectId: string
+): Promise<DownloadProgress | null> {
+  try {
+    return await invoke<DownloadProgress | null>('get_project_download_progress', { projectId });
+  } catch (error) {
+    console.error('Error in getProjectDownloadProgress:', { projectId, error });
+    throw error;
+  }
+}
+
+export type FreshnessState = 'fresh' | 'outdated' | 'not_cached' | 'unknown';
+
+export interface FileFreshness {
+  index: number;
+  file_id: string | null;
+  state: FreshnessState;
+  // low：服务端未提供 ETag / Last-Modified，仅比较了文件大小
+  confidence: 'high' | 'low';
+  reason?: string;
+}
+
+export interface CacheFreshnessReport {
+  project_id: string;
+  checked: number;
+  outdated: number;
+  entries: FileFreshness[];
+}
+
+/**
+ * 检查缓存文件是否在远端被替换
+ */
+export async function checkCacheFreshness(projectId: string): Promise<CacheFreshnessReport> {
+  try {
+    return await invoke<CacheFreshnessReport>('check_cache_freshness', {
+      projectId,
+    });
+  } catch (error) {
+    console.error('Error in checkCacheFreshness:', { projectId, error });
+    throw error;
+  }
+}
+
 /**
  * 删除项目的图片缓存
  */
@@ -78,16 +246,27 @@ export async function deleteFileCache(projectId: string): Promise<void> {
 }
 
 /**
- * 从本地缓存读取图片（base64 编码）
+ * 缓存图片的 moeimg:// 地址，可直接用作 <img src> 或 fetch（支持 Range）。
+ * 未缓存时请求返回 404；不经过 IPC，也不做 base64 编码
+ */
+export function cachedImageUrl(projectId: string, fileIndex: number, fileId?: string): string {
+  const path = fileId ? `${projectId}/${fileIndex}/${fileId}` : `${projectId}/${fileIndex}`;
+  return convertFileSrc(path, 'moeimg');
+}
+
+/**
+ * 从本地缓存读取图片（base64 编码）。慢路径：大图经 IPC 传输较慢，新代码请使用 cachedImageUrl
  */
 export async function loadCachedFile(
   projectId: string,
-  fileIndex: number
+  fileIndex: number,
+  fileId?: string
 ): Promise<CachedFileData | null> {
   try {
     return await invoke<CachedFileData>('load_cached_file', {
       projectId,
       fileIndex,
+      fileId: fileId ?? null,
     });
   } catch (error: any) {
     // 如果是缓存目录不存在，这是正常的，不应该抛出错误
@@ -100,6 +279,99 @@ export async function loadCachedFile(
   }
 }
 
+/**
+ * 将长条图切分为固定高度的分块（已生成的分块会跳过）
+ */
+export async function generateTiles(
+  projectId: string,
+  fileIndex: number,
+  fileId?: string,
+  tileHeight?: number
+): Promise<TileIndex> {
+  try {
+    return await invoke<TileIndex>('generate_tiles', {
+      projectId,
+      fileIndex,
+      fileId: fileId ?? null,
+      tileHeight: tileHeight ?? null,
+    });
+  } catch (error) {
+    console.error('Error in generateTiles:', { projectId, fileIndex, error });
+    throw error;
+  }
+}
+
+/**
+ * 取消进行中的分块生成
+ */
+export async function cancelTileGeneration(
+  projectId: string,
+  fileIndex: number,
+  fileId?: string
+): Promise<boolean> {
+  return await invoke<boolean>('cancel_tile_generation', {
+    projectId,
+    fileIndex,
+    fileId: fileId ?? null,
+  });
+}
+
+/**
+ * 读取单个分块（base64 编码）
+ */
+export async function getTile(
+  projectId: string,
+  fileIndex: number,
+  tileN: number,
+  fileId?: string
+): Promise<CachedTileData> {
+  try {
+    return await invoke<CachedTileData>('get_tile', {
+      projectId,
+      fileIndex,
+      fileId: fileId ?? null,
+      tileN,
+    });
+  } catch (error) {
+    console.error('Error in getTile:', { projectId, fileIndex, tileN, error });
+    throw error;
+  }
+}
+
+/**
+ * 读取缩略图（base64 编码的 JPEG），长边不超过 maxEdge（默认 256，范围 64~1024）
+ */
+export async function getThumbnail(
+  projectId: string,
+  fileIndex: number,
+  fileId?: string,
+  maxEdge?: number
+): Promise<CachedThumbnailData> {
+  try {
+    return await invoke<CachedThumbnailData>('get_thumbnail', {
+      projectId,
+      fileIndex,
+      fileId: fileId ?? null,
+      maxEdge: maxEdge ?? null,
+    });
+  } catch (error) {
+    console.error('Error in getThumbnail:', { projectId, fileIndex, maxEdge, error });
+    throw error;
+  }
+}
+
+/**
+ * 清空内存中的图片缓存（整页、缩略图与分块），返回清除的条目数；磁盘缓存不受影响
+ */
+export async function clearImageMemoryCache(): Promise<number> {
+  try {
+    return await invoke<number>('clear_image_memory_cache');
+  } catch (error) {
+    console.error('Error in clearImageMemoryCache:', { error });
+    throw error;
+  }
+}
+
 /**
  * 获取所有缓存项目列表
  */
@@ -107,14 +379,7 @@ export async function getAllCachedProjects(): Promise<CachedProjectMetadata[]> {
   try {
     const raw = await invoke<RawCachedProjectMetadata[]>('get_all_cached_projects_list');
 
-    return (raw || []).map(r => ({
-      projectId: r.project_id,
-      projectName: r.project_name,
-      status: r.status,
-      fileCount: r.file_count,
-      totalSizeBytes: r.total_size_bytes,
-      cachedAt: r.cached_at,
-    }));
+    return (raw || []).map(mapRawCachedProject);
   } catch (error) {
     console.error('Error in getAllCachedProjects:', error);
     throw error;
@@ -128,11 +393,257 @@ export async function getCachedProjectInfo(
   projectId: string
 ): Promise<CachedProjectMetadata | null> {
   try {
-    return await invoke<CachedProjectMetadata | null>('get_cached_project_info', {
+    const raw = await invoke<RawCachedProjectMetadata | null>('get_cached_project_info', {
       projectId,
     });
+
+    return raw ? mapRawCachedProject(raw) : null;
   } catch (error) {
     console.error('Error in getCachedProjectInfo:', { projectId, error });
     throw error;
   }
 }
+
+export interface CacheUsage {
+  used_bytes: number;
+  limit_bytes: number;
+  project_count: number;
+}
+
+/**
+ * 查询图片磁盘缓存的占用与上限
+ */
+export async function getCacheUsage(): Promise<CacheUsage> {
+  try {
+    return await invoke<CacheUsage>('get_cache_usage');
+  } catch (error) {
+    console.error('Error in getCacheUsage:', error);
+    throw error;
+  }
+}
+
+/**
+ * 修改图片磁盘缓存上限（字节，传 null 恢复默认 2 GB）；超出新上限时立即按最近读取时间淘汰项目
+ */
+export async function setCacheLimit(bytes: number | null): Promise<CacheUsage> {
+  try {
+    return await invoke<CacheUsage>('set_cache_limit', { bytes });
+  } catch (error) {
+    console.error('Error in setCacheLimit:', { bytes, error });
+    throw error;
+  }
+}
+
+export interface ManifestDiff {
+  // 远端新增的文件 id
+  added: string[];
+  // 远端已删除的文件（无文件 id 的缓存为 "#<页面索引>"）
+  removed: string[];
+  url_changed: string[];
+  not_downloaded: number;
+}
+
+export interface CachedProjectRemoteSummary {
+  project_id: string;
+  remote_deleted: boolean;
+  cached_file_count: number;
+  remote_file_count: number | null;
+  missing_count: number | null;
+  // 缓存没有清单时为 null
+  diff: ManifestDiff | null;
+  checked_at: number;
+}
+
+/**
+ * 与远端文件列表比对，更新缓存记录中的远端文件数与缺少的页数；远端项目已删除时标记而不报错
+ */
+export async function refreshCachedProjectInfo(
+  projectId: string
+): Promise<CachedProjectRemoteSummary> {
+  try {
+    return await invoke<CachedProjectRemoteSummary>('refresh_cached_project_info', {
+      projectId,
+    });
+  } catch (error) {
+    console.error('Error in refreshCachedProjectInfo:', { projectId, error });
+    throw error;
+  }
+}
+
+export type LegacyProjectOutcome = {
+  project_id: string;
+  file_count: number;
+  total_size_bytes: number;
+  unrecognized: string[];
+} & (
+  | { status: 'migrated' }
+  | { status: 'would_migrate' }
+  | { status: 'skipped'; reason: string }
+  | { status: 'failed'; message: string }
+);
+
+export interface LegacyMigrationReport {
+  legacy_dir: string;
+  dry_run: boolean;
+  already_migrated: boolean;
+  projects: LegacyProjectOutcome[];
+}
+
+/**
+ * 迁移旧版本的图片缓存（启动时会自动执行一次）；dryRun 为 true 时只返回将会执行的操作
+ */
+export async function migrateLegacyCache(dryRun: boolean): Promise<LegacyMigrationReport> {
+  try {
+    return await invoke<LegacyMigrationReport>('migrate_legacy_cache', { dryRun });
+  } catch (error) {
+    console.error('Error in migrateLegacyCache:', { dryRun, error });
+    throw error;
+  }
+}
+
+// 磁盘空间不足（下载前估算不足，或下载中磁盘已满）时后端返回的错误
+export type InsufficientDiskError = AppError & {
+  kind: 'InsufficientDisk';
+  required: number;
+  available: number;
+  // 用于“释放空间”入口的清理命令
+  cleanup_command: string;
+};
+
+export function parseInsufficientDiskError(error: unknown): InsufficientDiskError | null {
+  return isAppError(error) && error.kind === 'InsufficientDisk'
+    ? (error as InsufficientDiskError)
+    : null;
+}
+
+export type IntegritySeverity = 'info' | 'warning' | 'error';
+
+export interface IntegrityFinding {
+  check: string;
+  severity: IntegritySeverity;
+  subject: string;
+  message: string;
+  fixable: boolean;
+  fixed: boolean;
+  fix_error?: string;
+}
+
+export interface IntegrityCheckSummary {
+  name: string;
+  findings: number;
+  error?: string;
+}
+
+export interface IntegrityReport {
+  fix: boolean;
+  checks: IntegrityCheckSummary[];
+  errors: IntegrityFinding[];
+  warnings: IntegrityFinding[];
+  infos: IntegrityFinding[];
+}
+
+/**
+ * 检查本地数据（缓存、草稿、快照、待执行写操作等）的一致性；fix 为 true 时执行可安全自动修复的部分
+ */
+export async function runIntegrityCheck(fix: boolean): Promise<IntegrityReport> {
+  try {
+    return await invoke<IntegrityReport>('run_integrity_check', { payload: { fix } });
+  } catch (error) {
+    console.error('Error in runIntegrityCheck:', { fix, error });
+    throw error;
+  }
+}
+
+// ========== 缓存打包导出 / 导入（离线活动用） ==========
+
+export interface ExportCachesReport {
+  dest_path: string;
+  projects: number;
+  files: number;
+  bytes: number;
+  // 本机没有缓存、未写入归档的项目
+  not_cached: string[];
+  // 已取消：未生成归档文件
+  cancelled: boolean;
+}
+
+/**
+ * 把选定项目的图片缓存、清单与元数据导出为单个归档文件，供其他机器离线导入
+ */
+export async function exportCaches(projectIds: string[], destPath: string): Promise<ExportCachesReport> {
+  try {
+    return await invoke<ExportCachesReport>('export_caches', {
+      payload: { project_ids: projectIds, dest_path: destPath },
+    });
+  } catch (error) {
+    console.error('Error in exportCaches:', { projectIds, destPath, error });
+    throw error;
+  }
+}
+
+// 本机已有同一项目缓存时：跳过 / 归档更新时替换 / 总是替换
+export type OverwritePolicy = 'skip_existing' | 'replace_older' | 'always_replace';
+
+export interface ImportedProject {
+  project_id: string;
+  project_name: string;
+  outcome: 'imported' | 'replaced' | 'skipped';
+  files_written: number;
+  // 校验不通过而未导入的文件，联网后重新下载可补齐
+  rejected: string[];
+}
+
+export interface ImportCachesReport {
+  projects: ImportedProject[];
+  cancelled: boolean;
+  // 归档中途损坏或写入失败；此前已导入的项目保留
+  error?: string;
+}
+
+/**
+ * 导入缓存归档；归档中途损坏或被取消时返回已导入部分的结果
+ */
+export async function importCaches(
+  srcPath: string,
+  overwritePolicy: OverwritePolicy = 'skip_existing'
+): Promise<ImportCachesReport> {
+  try {
+    return await invoke<ImportCachesReport>('import_caches', {
+      payload: { src_path: srcPath, overwrite_policy: overwritePolicy },
+    });
+  } catch (error) {
+    console.error('Error in importCaches:', { srcPath, overwritePolicy, error });
+    throw error;
+  }
+}
+
+export interface CacheTransferProgress {
+  kind: 'export' | 'import';
+  total_bytes: number;
+  processed_bytes: number;
+  projects_done: number;
+}
+
+/**
+ * 取消进行中的缓存导出 / 导入，返回是否存在该任务
+ */
+export async function cancelCacheTransfer(): Promise<boolean> {
+  try {
+    return await invoke<boolean>('cancel_cache_transfer');
+  } catch (error) {
+    console.error('Error in cancelCacheTransfer:', { error });
+    throw error;
+  }
+}
+
+/**
+ * 查询进行中的缓存导出 / 导入进度；没有任务时返回 null
+ */
+export async function getCacheTransferProgress(): Promise<CacheTransferProgress | null> {
+  try {
+    return await invoke<CacheTransferProgress | null>('get_cache_transfer_progress');
+  } catch (error) {
+    console.error('Error in getCacheTransferProgress:', { error });
+    throw error;
+  }
+}
@@ -66,8 +66,12 @@ export async function searchMembersByName(params: GetMembersParams): Promise<Res
   }
 }
 
-// 获取当前登录用户在指定 team 中的成员信息（含 is_admin 标记）
-export async function getMemberInfo(teamId: string): Promise<ResMemberInfo> {
+// 获取当前登录用户在指定 team 中的成员信息（含 is_admin 标记）；后端会缓存一段时间，
+// forceRefresh 为 true 时忽略缓存
+export async function getMemberInfo(
+  teamId: string,
+  forceRefresh: boolean = false
+): Promise<ResMemberInfo> {
   try {
     // Raw response shape from PopRaKo (snake_case)
     interface RawMemberInfo {
@@ -80,7 +84,9 @@ export async function getMemberInfo(teamId: string): Promise<ResMemberInfo> {
       is_redrawer?: boolean;
     }
 
-    const raw = await invoke<RawMemberInfo>('get_member_info', { payload: { team_id: teamId } });
+    const raw = await invoke<RawMemberInfo>('get_member_info', {
+      payload: { team_id: teamId, force_refresh: forceRefresh },
+    });
     return {
       memberId: raw.member_id,
       isAdmin: raw.is_admin === true,
@@ -96,6 +102,18 @@ export async function getMemberInfo(teamId: string): Promise<ResMemberInfo> {
   }
 }
 
+// 清除后端缓存的成员信息（调整成员角色后调用）；返回是否存在缓存
+export async function invalidateMemberInfoCache(teamId: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>('invalidate_member_info_cache', {
+      payload: { team_id: teamId },
+    });
+  } catch (error) {
+    console.error('Error in invalidateMemberInfoCache:', { teamId, error });
+    throw error;
+  }
+}
+
 // 获取团队中所有成员及其最后活跃时间
 export async function getActiveMembers(
   teamId: string,
@@ -143,3 +161,115 @@ export async function getActiveMembers(
     throw error;
   }
 }
+
+// 项目成员核对结果（字段保持后端 snake_case）
+export interface AuditMemberRoles {
+  is_translator: boolean;
+  is_proofreader: boolean;
+  is_typesetter: boolean;
+  is_redrawer: boolean;
+}
+
+export interface AuditAssignee {
+  user_id: string;
+  member_id: string;
+  name: string;
+  roles: AuditMemberRoles;
+  is_principal: boolean;
+}
+
+export interface MemberAuditReport {
+  project_id: string;
+  target_id: string;
+  assignees_known: boolean;
+  team_known: boolean;
+  team_flags_known: boolean;
+  contributors_not_assigned: {
+    user_id: string;
+    name: string;
+    translations: number;
+    proofreads: number;
+  }[];
+  assignees_without_contributions: AuditAssignee[];
+  assignees_not_in_team: AuditAssignee[];
+  principals_without_flags: AuditAssignee[];
+}
+
+// 核对项目指派成员与实际贡献者；markdownPath 指定时同时写出 Markdown 报告
+export async function auditProjectMembers(
+  projectId: string,
+  targetId: string,
+  markdownPath?: string
+): Promise<MemberAuditReport> {
+  try {
+    return await invoke<MemberAuditReport>('audit_project_members', {
+      payload: {
+        project_id: projectId,
+        target_id: targetId,
+        markdown_path: markdownPath ?? null,
+      },
+    });
+  } catch (error) {
+    console.error('Error in auditProjectMembers:', { projectId, targetId, error });
+    throw error;
+  }
+}
+
+export interface MentionResolution {
+  resolved: { raw: string; member_id: string; user_id: string; username: string }[];
+  // 仅前缀匹配或有多个同名成员，需要用户改写（带空格的名字用 @"名字"）
+  ambiguous: { raw: string; candidates: string[] }[];
+  unknown: string[];
+  member_ids: string[];
+}
+
+// 解析文本中的 @成员 提及
+export async function resolveMentions(teamId: string, text: string): Promise<MentionResolution> {
+  try {
+    return await invoke<MentionResolution>('resolve_mentions_in_text', {
+      payload: { team_id: teamId, text },
+    });
+  } catch (error) {
+    console.error('Error in resolveMentions:', { teamId, error });
+    throw error;
+  }
+}
+
+export interface TeamMemberRoles {
+  is_translator: boolean;
+  is_proofreader: boolean;
+  is_typesetter: boolean;
+  is_redrawer: boolean;
+}
+
+// 把 Moetran 用户加入团队，返回新成员的 member_id；权限不足 / 已是成员时抛出对应的业务错误
+export async function addTeamMember(
+  teamId: string,
+  userId: string,
+  roles: TeamMemberRoles
+): Promise<string> {
+  try {
+    return await invoke<string>('add_team_member', {
+      payload: { team_id: teamId, user_id: userId, roles },
+    });
+  } catch (error) {
+    console.error('Error in addTeamMember:', { teamId, userId, error });
+    throw error;
+  }
+}
+
+// 未传 force 时，若成员仍参与未发布的项目，后端会以 DeleteBlocked 错误拒绝（见 isDeleteBlocked）
+export async function removeTeamMember(
+  teamId: string,
+  memberId: string,
+  force = false
+): Promise<void> {
+  try {
+    await invoke<void>('remove_team_member', {
+      payload: { team_id: teamId, member_id: memberId, force },
+    });
+  } catch (error) {
+    console.error('Error in removeTeamMember:', { teamId, memberId, error });
+    throw error;
+  }
+}
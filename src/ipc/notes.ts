@@ -0,0 +1,87 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { MentionResolution } from './member';
+
+// 项目备注：PopRaKo 暂无备注接口，只保存在本地。正文中的 @成员（带空格的名字用 @"名字"）
+// 在保存时解析为 member_id，读取时按成员当前名字渲染
+
+export interface NoteMention {
+  member_id: string;
+  username: string;
+  // 成员已不在汉化组目录中时为 false（显示保存时的名字）
+  in_directory: boolean;
+}
+
+export interface ProjectNote {
+  id: number;
+  team_id: string;
+  project_id: string;
+  author_member_id: string | null;
+  author_name: string | null;
+  body: string;
+  created_at: number;
+  mentions: NoteMention[];
+}
+
+export interface AddProjectNoteReply {
+  note: ProjectNote;
+  // 歧义 / 未知的提及没有记录，需要提示用户改写
+  resolution: MentionResolution;
+}
+
+export interface MyMention {
+  note: ProjectNote;
+  project_name: string | null;
+  project_set_name: string | null;
+}
+
+// 保存备注；提及了当前用户时后端会发出 project-notes://mentioned 事件
+export async function addProjectNote(
+  teamId: string,
+  projectId: string,
+  body: string
+): Promise<AddProjectNoteReply> {
+  try {
+    return await invoke<AddProjectNoteReply>('add_project_note', {
+      payload: { team_id: teamId, project_id: projectId, body },
+    });
+  } catch (error) {
+    console.error('Error in addProjectNote:', { teamId, projectId, error });
+    throw error;
+  }
+}
+
+// 项目的全部备注（按时间倒序）
+export async function listProjectNotes(teamId: string, projectId: string): Promise<ProjectNote[]> {
+  try {
+    return await invoke<ProjectNote[]>('list_project_notes', {
+      payload: { team_id: teamId, project_id: projectId },
+    });
+  } catch (error) {
+    console.error('Error in listProjectNotes:', { teamId, projectId, error });
+    throw error;
+  }
+}
+
+// 提及我的备注（按时间倒序）；since 为 Unix 时间戳（秒）
+export async function getMyMentions(teamId: string, since?: number): Promise<MyMention[]> {
+  try {
+    return await invoke<MyMention[]>('get_my_mentions', {
+      payload: { team_id: teamId, since: since ?? null },
+    });
+  } catch (error) {
+    console.error('Error in getMyMentions:', { teamId, since, error });
+    throw error;
+  }
+}
+
+// 删除备注；返回是否存在
+export async function deleteProjectNote(noteId: number): Promise<boolean> {
+  try {
+    return await invoke<boolean>('delete_project_note', {
+      payload: { note_id: noteId },
+    });
+  } catch (error) {
+    console.error('Error in deleteProjectNote:', { noteId, error });
+    throw error;
+  }
+}
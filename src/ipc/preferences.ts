@@ -0,0 +1,59 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// 编辑器偏好配置文件（汉化组分发统一偏好）；界面偏好的键带 "ui." 前缀
+
+export interface ExportPreferencesProfileReply {
+  path: string;
+  exported: string[];
+  // includeKeys 中不可导出或当前没有值的键
+  skipped: string[];
+}
+
+export interface PreferenceChange {
+  key: string;
+  current: unknown | null;
+  incoming: unknown;
+  // 该项由环境变量指定，导入的值不会生效
+  overridden_by_env: boolean;
+}
+
+export interface ImportPreferencesProfileReply {
+  // false 表示只是预览
+  applied: boolean;
+  profile_version: number;
+  changes: PreferenceChange[];
+  unchanged: number;
+  // 更新版本的配置文件中本版本不认识的键，已跳过
+  unknown_keys: string[];
+  failed: { key: string; error: string }[];
+}
+
+// includeKeys 为空时导出全部偏好
+export async function exportPreferencesProfile(
+  path: string,
+  includeKeys: string[] = [],
+): Promise<ExportPreferencesProfileReply> {
+  try {
+    return await invoke<ExportPreferencesProfileReply>('export_preferences_profile', {
+      payload: { path, include_keys: includeKeys },
+    });
+  } catch (err) {
+    console.error('[ipc] exportPreferencesProfile failed', { path, includeKeys, err });
+    throw err;
+  }
+}
+
+// overwrite 为 false 时只返回与当前值的差异，确认后再以 true 调用
+export async function importPreferencesProfile(
+  path: string,
+  overwrite: boolean,
+): Promise<ImportPreferencesProfileReply> {
+  try {
+    return await invoke<ImportPreferencesProfileReply>('import_preferences_profile', {
+      payload: { path, overwrite },
+    });
+  } catch (err) {
+    console.error('[ipc] importPreferencesProfile failed', { path, overwrite, err });
+    throw err;
+  }
+}
@@ -1,8 +1,10 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { ResProjectEnriched } from '../api/model/project';
+import { EVENT_NAMES, type LostTranslation } from './events.gen';
+import type { NameCandidate, ProjStage, ResProjectEnriched } from '../api/model/project';
 import type { ResMember } from '../api/model/member';
 import type { ResTeam } from '../api/model/team';
 import type { ResAssignment } from '../api/model/assignment';
+import { errorMessage, isAppError, type AppError } from './errors';
 
 // Private raw (snake_case) interfaces from Rust/PopRaKo responses
 interface RawPoprakoMember {
@@ -36,6 +38,9 @@ interface RawResProject {
   principals?: string[] | null;
   // Moetran 原生项目可能返回的 role 字段（object | null）
   role?: RawProjectRole | null;
+  next_deadline?: { stage: ProjStage; due_at: number; overdue: boolean } | null;
+  match_confidence?: number | null;
+  candidates?: NameCandidate[] | null;
 }
 
 // 私有类型：Raw team shape from backend (snake_case or camelCase tolerant)
@@ -125,6 +130,15 @@ function mapRawProject(r: RawResProject): ResProjectEnriched {
         .map(m => m.user_id ?? m.member_id),
     // passthrough Moetran `role` for native projects; frontend will only check null/non-null
     role: r.role ?? null,
+    nextDeadline: r.next_deadline
+      ? {
+          stage: r.next_deadline.stage,
+          dueAt: r.next_deadline.due_at,
+          overdue: r.next_deadline.overdue,
+        }
+      : undefined,
+    matchConfidence: r.match_confidence ?? undefined,
+    candidates: r.candidates ?? undefined,
   } as ResProjectEnriched;
 }
 
@@ -171,22 +185,78 @@ function mapRawShownProject(raw: RawShownProject): TeamShownProject {
 // NOTE: backend now serializes DTOs using `camelCase` (serde rename_all).
 // Use the frontend DTOs from `src/api/model` as the invoke return types.
 
+// enriched 列表的返回：PopRaKo 补充失败时仍返回 Moetran 数据，并附带 enrichmentError
+interface RawProjectsEnrichedReply {
+  items: RawResProject[];
+  enrichment_error: string | null;
+  status_labels?: Record<string, StatusLabel[]>;
+  deadline_exceeded?: boolean;
+  pagination?: RawPageMeta | null;
+}
+
+interface RawPageMeta {
+  total: number | null;
+  page: number;
+  limit: number;
+  has_more: boolean;
+}
+
+export interface PageInfo {
+  // 服务端未提供且无法推断时为 null
+  total: number | null;
+  page: number;
+  limit: number;
+  hasMore: boolean;
+}
+
+export interface ProjectsEnrichedResult {
+  items: ResProjectEnriched[];
+  // 非空表示 PopRaKo 数据暂不可用（列表仅含 Moetran 信息）
+  enrichmentError: string | null;
+  // 列表中各汉化组的状态标签，按 team id 索引
+  statusLabels: Record<string, StatusLabel[]>;
+  // 请求的 deadlineMs 已用尽，items 为部分结果（缓存数据或缺少 PopRaKo 信息）
+  deadlineExceeded: boolean;
+  // 分页信息；返回的是离线兜底缓存时为 null
+  pagination: PageInfo | null;
+}
+
+function mapRawEnrichedReply(raw: RawProjectsEnrichedReply | null): ProjectsEnrichedResult {
+  return {
+    items: (raw?.items || []).map(r => mapRawProject(r)),
+    enrichmentError: raw?.enrichment_error ?? null,
+    statusLabels: raw?.status_labels ?? {},
+    deadlineExceeded: raw?.deadline_exceeded ?? false,
+    pagination: raw?.pagination
+      ? {
+          total: raw.pagination.total,
+          page: raw.pagination.page,
+          limit: raw.pagination.limit,
+          hasMore: raw.pagination.has_more,
+        }
+      : null,
+  };
+}
+
 // 获取当前用户的 enriched 项目列表
 export async function getUserProjectsEnriched(params: {
   page: number;
   limit: number;
-}): Promise<ResProjectEnriched[]> {
+  // 整体时限（毫秒），超出时返回部分结果
+  deadlineMs?: number;
+}): Promise<ProjectsEnrichedResult> {
   try {
     console.log('Invoking getUserProjectsEnriched with params', params);
 
-    const raw = await invoke<RawResProject[]>('get_user_projects_enriched', {
+    const raw = await invoke<RawProjectsEnrichedReply>('get_user_projects_enriched', {
       payload: {
         page: params.page,
         limit: params.limit,
+        deadline_ms: params.deadlineMs ?? null,
       },
     });
 
-    return (raw || []).map(r => mapRawProject(r));
+    return mapRawEnrichedReply(raw);
   } catch (error) {
     console.error('Error in getUserProjectsEnriched:', { params, error });
     throw error;
@@ -286,28 +356,75 @@ export async function searchTeamProjectsEnriched(
   }
 }
 
+// 单个项目的 enriched 详情；项目在 Moetran 中不存在时抛出 kind 为 NotFound 的错误（见 isNotFound）
+export async function getProjectEnriched(projectId: string): Promise<ResProjectEnriched> {
+  try {
+    const raw = await invoke<RawResProject>('get_project_enriched', {
+      payload: { project_id: projectId },
+    });
+
+    return mapRawProject(raw);
+  } catch (error) {
+    console.error('Error in getProjectEnriched:', { projectId, error });
+    throw error;
+  }
+}
+
 // 获取团队的 enriched 项目列表（无筛选，分页）
 export async function getTeamProjectsEnriched(params: {
   teamId: string;
   page: number;
   limit: number;
-}): Promise<ResProjectEnriched[]> {
+  // 整体时限（毫秒），超出时返回部分结果
+  deadlineMs?: number;
+}): Promise<ProjectsEnrichedResult> {
   try {
-    const raw = await invoke<RawResProject[]>('get_team_projects_enriched', {
+    const raw = await invoke<RawProjectsEnrichedReply>('get_team_projects_enriched', {
       payload: {
         team_id: params.teamId,
         page: params.page,
         limit: params.limit,
+        deadline_ms: params.deadlineMs ?? null,
       },
     });
 
-    return (raw || []).map(r => mapRawProject(r));
+    return mapRawEnrichedReply(raw);
   } catch (error) {
     console.error('Error in getTeamProjectsEnriched:', { params, error });
     throw error;
   }
 }
 
+export interface CachedProjectsEnriched {
+  items: ResProjectEnriched[];
+  // 最早一页的拉取时间（Unix 秒）；没有缓存时为 null
+  fetchedAt: number | null;
+  // 拉取时间已较久（超过 5 分钟），可提示“显示的是 x 小时前的数据”
+  stale: boolean;
+}
+
+// 最近一次成功拉取的项目列表（不发起网络请求）；teamId 缺省时为当前用户的项目
+export async function getCachedProjectsEnriched(teamId?: string): Promise<CachedProjectsEnriched> {
+  try {
+    const raw = await invoke<{
+      projects: RawResProject[];
+      fetched_at: number | null;
+      stale: boolean;
+    }>('get_cached_projects_enriched', {
+      payload: { team_id: teamId ?? null },
+    });
+
+    return {
+      items: (raw?.projects || []).map(r => mapRawProject(r)),
+      fetchedAt: raw?.fetched_at ?? null,
+      stale: raw?.stale ?? false,
+    };
+  } catch (error) {
+    console.error('Error in getCachedProjectsEnriched:', { teamId, error });
+    throw error;
+  }
+}
+
 export async function listTeamShownProjects(params: {
   teamId: string;
   page?: number;
@@ -329,22 +446,110 @@ export async function listTeamShownProjects(params: {
   }
 }
 
+// 创建前重名检查命中的已有项目集 / 项目
+export interface NameMatch {
+  kind: 'projset' | 'proj';
+  id: string;
+  name: string;
+  serial: number | null;
+}
+
+interface RawNameMatch {
+  kind: 'projset' | 'proj';
+  id: string;
+  name: string;
+  serial?: number | null;
+}
+
+function mapRawNameMatch(raw: RawNameMatch): NameMatch {
+  return { kind: raw.kind, id: raw.id, name: raw.name, serial: raw.serial ?? null };
+}
+
+// 重名被拦截时的错误（后端以 kind 为 DuplicateName 的 AppError 抛出，existing 为已有实体）
+export interface DuplicateNameError {
+  code: 'duplicate_name';
+  message: string;
+  existing: NameMatch;
+}
+
+export function parseDuplicateNameError(error: unknown): DuplicateNameError | null {
+  if (!isAppError(error) || error.kind !== 'DuplicateName') return null;
+
+  return {
+    code: 'duplicate_name',
+    message: error.message,
+    existing: mapRawNameMatch((error as AppError & { existing: RawNameMatch }).existing),
+  };
+}
+
+// 创建项目集 / 项目前检查到 Moetran 登录已失效时的错误体（invoke 抛出的字符串为该 JSON），需重新登录
+export interface MoetranTokenExpiredError {
+  code: 'moetran_token_expired';
+  message: string;
+}
+
+export function parseMoetranTokenExpiredError(error: unknown): MoetranTokenExpiredError | null {
+  try {
+    const parsed = JSON.parse(errorMessage(error));
+    if (parsed?.code !== 'moetran_token_expired') return null;
+
+    return { code: 'moetran_token_expired', message: parsed.message };
+  } catch {
+    return null;
+  }
+}
+
+// 字段校验错误（kind 为 Validation 的 AppError 的 errors）：来自命令的本地预校验或 PopRaKo 的 422。
+// field 为点分字段名（如 projset_name、target_languages.0），与请求参数的 snake_case 字段名一致；
+// 无法定位到具体字段时为空字符串
+export interface FieldError {
+  field: string;
+  message: string;
+  kind: string;
+  source: 'client' | 'server';
+}
+
+export interface ValidationErrors {
+  code: 'validation_failed';
+  message: string;
+  fields: FieldError[];
+}
+
+export function parseValidationErrors(error: unknown): ValidationErrors | null {
+  if (isAppError(error) && error.kind === 'Validation') {
+    const errors = error.errors as ValidationErrors | undefined;
+    if (errors && Array.isArray(errors.fields)) return errors;
+  }
+
+  // 尚未迁移的命令仍以 JSON 字符串抛出
+  try {
+    const parsed = JSON.parse(errorMessage(error));
+    if (parsed?.code !== 'validation_failed' || !Array.isArray(parsed.fields)) return null;
+
+    return { code: 'validation_failed', message: parsed.message, fields: parsed.fields };
+  } catch {
+    return null;
+  }
+}
+
 // PopRaKo 创建项目集请求参数
 export interface CreateProjsetPayload {
   projsetName: string;
   projsetDescription: string;
   teamId: string;
-  mtrToken: string;
+  allowDuplicate?: boolean;
 }
 
 export interface CreateProjsetResult {
   projsetSerial: number;
+  nearMatches: NameMatch[];
 }
 
 // 调用 PopRaKo /projset/create 的 IPC 封装
 // Raw shape from Rust (snake_case)
 interface RawCreateProjsetResult {
   projset_serial: number;
+  near_matches?: RawNameMatch[];
 }
 
 export async function createProjset(payload: CreateProjsetPayload): Promise<CreateProjsetResult> {
@@ -354,11 +559,14 @@ export async function createProjset(payload: CreateProjsetPayload): Promise<Crea
         projset_name: payload.projsetName,
         projset_description: payload.projsetDescription,
         team_id: payload.teamId,
-        mtr_token: payload.mtrToken,
+        allow_duplicate: payload.allowDuplicate ?? false,
       },
     });
 
-    return { projsetSerial: raw.projset_serial };
+    return {
+      projsetSerial: raw.projset_serial,
+      nearMatches: (raw.near_matches || []).map(mapRawNameMatch),
+    };
   } catch (error) {
     console.error('Error in createProjset:', { payload, error });
     throw error;
@@ -371,19 +579,22 @@ export interface CreateProjPayload {
   projDescription: string;
   teamId: string;
   projsetId: string;
-  mtrAuth: string;
   worksetIndex: number;
   sourceLanguage: string;
   targetLanguages: string[];
   allowApplyType: number;
   applicationCheckType: number;
   defaultRole: string;
+  allowDuplicate?: boolean;
+  // 为 true（或 worksetIndex 为负数）时由后端自动使用项目集的下一个序号
+  autoIndex?: boolean;
 }
 
 export interface CreateProjResult {
   projId: string;
   projSerial: number;
   projsetIndex: number;
+  nearMatches: NameMatch[];
 }
 
 // 调用 PopRaKo /proj/create 的 IPC 封装
@@ -391,6 +602,7 @@ interface RawCreateProjResult {
   proj_id: string;
   proj_serial: number;
   projset_index: number;
+  near_matches?: RawNameMatch[];
 }
 
 export async function createProj(payload: CreateProjPayload): Promise<CreateProjResult> {
@@ -401,13 +613,14 @@ export async function createProj(payload: CreateProjPayload): Promise<CreateProj
         proj_description: payload.projDescription,
         team_id: payload.teamId,
         projset_id: payload.projsetId,
-        mtr_auth: payload.mtrAuth,
         workset_index: payload.worksetIndex,
         source_language: payload.sourceLanguage,
         target_languages: payload.targetLanguages,
         allow_apply_type: payload.allowApplyType,
         application_check_type: payload.applicationCheckType,
         default_role: payload.defaultRole,
+        allow_duplicate: payload.allowDuplicate ?? false,
+        auto_index: payload.autoIndex ?? false,
       },
     });
 
@@ -415,6 +628,7 @@ export async function createProj(payload: CreateProjPayload): Promise<CreateProj
       projId: raw.proj_id,
       projSerial: raw.proj_serial,
       projsetIndex: raw.projset_index,
+      nearMatches: (raw.near_matches || []).map(mapRawNameMatch),
     };
   } catch (error) {
     console.error('Error in createProj:', { payload, error });
@@ -422,6 +636,27 @@ export async function createProj(payload: CreateProjPayload): Promise<CreateProj
   }
 }
 
+// 项目集序号检查结果：下一个序号、缺号与重复序号
+export interface ProjsetIndexReport {
+  projset_id: string;
+  proj_count: number;
+  max_index: number | null;
+  next_index: number;
+  gaps: number[];
+  duplicates: { index: number; proj_ids: string[] }[];
+}
+
+export async function getNextProjsetIndex(projsetId: string): Promise<ProjsetIndexReport> {
+  try {
+    return await invoke<ProjsetIndexReport>('get_next_projset_index', {
+      payload: { projset_id: projsetId },
+    });
+  } catch (err) {
+    console.error('[ipc] getNextProjsetIndex failed', { projsetId, err });
+    throw err;
+  }
+}
+
 // PopRaKo 项目集 DTO（简化版，只保留 Creator 需要的字段）
 export interface PoprakoProjsetInfo {
   projsetId: string;
@@ -459,29 +694,64 @@ export async function getTeamPoprakoProjsets(teamId: string): Promise<PoprakoPro
   }
 }
 
+// 修改 PopRaKo 项目集的名称与描述
+export interface UpdatePoprakoProjsetPayload {
+  projsetId: string;
+  projsetName: string;
+  projsetDescription?: string;
+}
+
+export async function updatePoprakoProjset(payload: UpdatePoprakoProjsetPayload): Promise<void> {
+  try {
+    await invoke<void>('update_poprako_projset', {
+      payload: {
+        projset_id: payload.projsetId,
+        projset_name: payload.projsetName,
+        projset_description: payload.projsetDescription ?? '',
+      },
+    });
+  } catch (error) {
+    console.error('Error in updatePoprakoProjset:', { payload, error });
+    throw error;
+  }
+}
+
+// 删除 PopRaKo 项目集；项目集中仍有项目时后端返回 code 为 409 的错误
+export async function deletePoprakoProjset(projsetId: string): Promise<void> {
+  try {
+    await invoke<void>('delete_poprako_projset', {
+      payload: { projset_id: projsetId },
+    });
+  } catch (error) {
+    console.error('Error in deletePoprakoProjset:', { projsetId, error });
+    throw error;
+  }
+}
+
 // 创建 PopRaKo 项目集
 export interface CreatePoprakoProjsetPayload {
   projsetName: string;
   projsetDescription?: string;
   teamId: string;
-  mtrToken: string;
+  allowDuplicate?: boolean;
 }
 
 export async function createPoprakoProjset(
   payload: CreatePoprakoProjsetPayload
-): Promise<{ projsetSerial: number }> {
+): Promise<CreateProjsetResult> {
   try {
-    const raw = await invoke<{ projset_serial: number }>('create_poprako_projset', {
+    const raw = await invoke<RawCreateProjsetResult>('create_poprako_projset', {
       payload: {
         projset_name: payload.projsetName,
         projset_description: payload.projsetDescription ?? null,
         team_id: payload.teamId,
-        mtr_token: payload.mtrToken,
+        allow_duplicate: payload.allowDuplicate ?? false,
       },
     });
 
     return {
       projsetSerial: raw.projset_serial,
+      nearMatches: (raw.near_matches || []).map(mapRawNameMatch),
     };
   } catch (error) {
     console.error('Error in createPoprakoProjset:', { payload, error });
@@ -489,6 +759,9 @@ export async function createPoprakoProjset(
   }
 }
 
+// PopRaKo 写操作的结果：已生效，或 PopRaKo 暂时不可达、已进入离线重试队列（仅 queueOnFailure 时）
+export type WriteOutcome = { outcome: 'applied' } | { outcome: 'queued'; pending_id: number };
+
 // 指派成员到 PopRaKo 项目
 export interface AssignMemberPayload {
   projId: string;
@@ -497,11 +770,12 @@ export interface AssignMemberPayload {
   isProofreader: boolean;
   isTypesetter: boolean;
   isRedrawer: boolean;
+  queueOnFailure?: boolean;
 }
 
-export async function assignMemberToProj(payload: AssignMemberPayload): Promise<void> {
+export async function assignMemberToProj(payload: AssignMemberPayload): Promise<WriteOutcome> {
   try {
-    await invoke<void>('assign_member_to_proj', {
+    return await invoke<WriteOutcome>('assign_member_to_proj', {
       payload: {
         proj_id: payload.projId,
         member_id: payload.memberId,
@@ -509,6 +783,7 @@ export async function assignMemberToProj(payload: AssignMemberPayload): Promise<
         is_proofreader: payload.isProofreader,
         is_typesetter: payload.isTypesetter,
         is_redrawer: payload.isRedrawer,
+        queue_on_failure: payload.queueOnFailure ?? false,
       },
     });
   } catch (error) {
@@ -517,20 +792,80 @@ export async function assignMemberToProj(payload: AssignMemberPayload): Promise<
   }
 }
 
+export interface ProjMemberRoles {
+  is_translator: boolean;
+  is_proofreader: boolean;
+  is_typesetter: boolean;
+  is_redrawer: boolean;
+}
+
+// 修改已指派成员的角色；previous_value 为缓存中的旧角色（未缓存时为 null），失败时可据此回滚
+export async function updateProjMemberRoles(
+  projId: string,
+  memberId: string,
+  roles: ProjMemberRoles
+): Promise<{ new_value: ProjMemberRoles; previous_value: ProjMemberRoles | null; mutated_at: number }> {
+  try {
+    return await invoke('update_proj_member_roles', {
+      payload: { proj_id: projId, member_id: memberId, roles },
+    });
+  } catch (error) {
+    console.error('Error in updateProjMemberRoles:', { projId, memberId, error });
+    throw error;
+  }
+}
+
+export async function unassignMemberFromProj(projId: string, memberId: string): Promise<void> {
+  try {
+    await invoke<void>('unassign_member_from_proj', {
+      payload: { proj_id: projId, member_id: memberId },
+    });
+  } catch (error) {
+    console.error('Error in unassignMemberFromProj:', { projId, memberId, error });
+    throw error;
+  }
+}
+
+// 删除 PopRaKo 项目；被拒绝（如仍有未校对的原文）时抛出 kind 为 Conflict 的错误（见 isConflict）
+export async function deleteProj(projId: string): Promise<void> {
+  try {
+    await invoke<void>('delete_proj', {
+      payload: { proj_id: projId },
+    });
+  } catch (error) {
+    console.error('Error in deleteProj:', { projId, error });
+    throw error;
+  }
+}
+
+// 完结 Moetran 项目，使其不再出现在项目列表中；被拒绝时同样抛出 Conflict
+export async function archiveProj(projId: string): Promise<void> {
+  try {
+    await invoke<void>('archive_proj', {
+      payload: { proj_id: projId },
+    });
+  } catch (error) {
+    console.error('Error in archiveProj:', { projId, error });
+    throw error;
+  }
+}
+
 // Update project phase status (PopRaKo API #9)
 export interface UpdateProjStatusPayload {
   projId: string;
   statusType: 'translating' | 'proofreading' | 'typesetting' | 'reviewing';
   newStatus: number; // 0=pending, 1=wip, 2=completed
+  queueOnFailure?: boolean;
 }
 
-export async function updateProjStatus(payload: UpdateProjStatusPayload): Promise<void> {
+export async function updateProjStatus(payload: UpdateProjStatusPayload): Promise<WriteOutcome> {
   try {
-    await invoke<void>('update_proj_status', {
+    return await invoke<WriteOutcome>('update_proj_status', {
       payload: {
         proj_id: payload.projId,
         status_type: payload.statusType,
         new_status: payload.newStatus,
+        queue_on_failure: payload.queueOnFailure ?? false,
       },
     });
   } catch (error) {
@@ -542,13 +877,15 @@ export async function updateProjStatus(payload: UpdateProjStatusPayload): Promis
 // Publish project (PopRaKo API #10)
 export interface PublishProjPayload {
   projId: string;
+  queueOnFailure?: boolean;
 }
 
-export async function publishProj(payload: PublishProjPayload): Promise<void> {
+export async function publishProj(payload: PublishProjPayload): Promise<WriteOutcome> {
   try {
-    await invoke<void>('publish_proj', {
+    return await invoke<WriteOutcome>('publish_proj', {
       payload: {
         proj_id: payload.projId,
+        queue_on_failure: payload.queueOnFailure ?? false,
       },
     });
   } catch (error) {
@@ -557,6 +894,64 @@ export async function publishProj(payload: PublishProjPayload): Promise<void> {
   }
 }
 
+// 批量发布（发布前检查 PopRaKo 四个阶段是否均已完成，可选检查 Moetran 原文是否全部校对）
+export interface PublishProjsBulkPayload {
+  projIds: string[];
+  requireComplete?: boolean;
+  checkSources?: boolean;
+  dryRun?: boolean;
+}
+
+export type PublishGateIssue =
+  | {
+      kind: 'stage_incomplete';
+      stage: 'translating' | 'proofreading' | 'typesetting' | 'reviewing';
+      status: number;
+    }
+  | { kind: 'sources_unchecked'; checked: number; total: number };
+
+export type BulkPublishItem = {
+  proj_id: string;
+  proj_name: string | null;
+  issues: PublishGateIssue[];
+} & (
+  | { status: 'ready' }
+  | { status: 'published' }
+  | { status: 'skipped'; reason: string }
+  | { status: 'failed'; message: string }
+);
+
+export interface BulkPublishSummary {
+  dry_run: boolean;
+  ready: number;
+  published: number;
+  skipped: number;
+  failed: number;
+}
+
+export interface BulkPublishReply {
+  summary: BulkPublishSummary;
+  items: BulkPublishItem[];
+}
+
+export async function publishProjsBulk(
+  payload: PublishProjsBulkPayload
+): Promise<BulkPublishReply> {
+  try {
+    return await invoke<BulkPublishReply>('publish_projs_bulk', {
+      payload: {
+        proj_ids: payload.projIds,
+        require_complete: payload.requireComplete ?? true,
+        check_sources: payload.checkSources ?? false,
+        dry_run: payload.dryRun ?? false,
+      },
+    });
+  } catch (error) {
+    console.error('Error in publishProjsBulk:', { payload, error });
+    throw error;
+  }
+}
+
 // 获取 assignments（派活列表）
 interface RawResAssignment {
   proj_id: string;
@@ -615,6 +1010,12 @@ export interface ProjectFileInfo {
   sourceCount: number;
   url: string;
   coverUrl: string;
+  width?: number;
+  height?: number;
+  sizeBytes?: number;
+  safeStatus?: number;
+  // 后端未返回 url 的文件，url 为空字符串
+  broken: boolean;
 }
 
 export async function getProjectTargets(projectId: string): Promise<ProjectTargetInfo[]> {
@@ -643,22 +1044,32 @@ export async function getProjectTargets(projectId: string): Promise<ProjectTarge
   }
 }
 
+// sort 为 'recent_activity' 时我最近编辑 / 查看过的页面在前，其余按文件名自然顺序
+export type ProjectFileSort = 'recent_activity';
+
 export async function getProjectFiles(
   projectId: string,
-  targetId?: string
+  targetId?: string,
+  sort?: ProjectFileSort
 ): Promise<ProjectFileInfo[]> {
   try {
-    console.debug('[ipc] invoke get_project_files', { projectId, targetId });
+    console.debug('[ipc] invoke get_project_files', { projectId, targetId, sort });
     const payload: Record<string, string | undefined> = { project_id: projectId };
     if (targetId) payload.target_id = targetId;
+    if (sort) payload.sort = sort;
 
     const raw = await invoke<
       {
         id: string;
         name: string;
         source_count: number;
-        url: string;
+        url: string | null;
         cover_url?: string;
+        width?: number | null;
+        height?: number | null;
+        size_bytes?: number | null;
+        safe_status?: number | null;
+        broken?: boolean;
       }[]
     >('get_project_files', {
       payload,
@@ -670,8 +1081,13 @@ export async function getProjectFiles(
       id: f.id,
       name: f.name,
       sourceCount: f.source_count ?? 0,
-      url: f.url,
+      url: f.url ?? '',
       coverUrl: (f as any).cover_url ?? (f as any).coverUrl ?? '',
+      width: f.width ?? undefined,
+      height: f.height ?? undefined,
+      sizeBytes: f.size_bytes ?? undefined,
+      safeStatus: f.safe_status ?? undefined,
+      broken: f.broken ?? !f.url,
     }));
   } catch (err) {
     console.error('[ipc] getProjectFiles failed', { projectId, targetId, err });
@@ -686,6 +1102,34 @@ export interface PageTranslation {
   content: string;
   proofreadContent?: string;
   selected: boolean;
+  // 提交 / 更新时后端对内容做过的规范化（为空或缺省表示未改动）
+  normalized?: TextTransformation[];
+}
+
+// source 位置类型（1=框内，2=框外），由后端统一提供
+export interface PositionTypeInfo {
+  value: number;
+  key: 'inside' | 'outside';
+  label: string;
+  labelplusGroup: string | null;
+}
+
+export async function getPositionTypes(): Promise<PositionTypeInfo[]> {
+  try {
+    const raw = await invoke<
+      { value: number; key: 'inside' | 'outside'; label: string; labelplus_group: string | null }[]
+    >('get_position_types');
+
+    return (raw || []).map(p => ({
+      value: p.value,
+      key: p.key,
+      label: p.label,
+      labelplusGroup: p.labelplus_group,
+    }));
+  } catch (error) {
+    console.error('Error in getPositionTypes:', error);
+    throw error;
+  }
 }
 
 export interface PageSource {
@@ -695,139 +1139,307 @@ export interface PageSource {
   positionType: number;
   myTranslation?: PageTranslation;
   translations: PageTranslation[];
+  // 来自本地快照（allowStale 时），最新数据随后通过 SOURCES_UPDATED_EVENT 推送
+  stale?: boolean;
+}
+
+// 后台刷新到与快照不同的 sources 时触发，payload: { file_id, target_id, sources }
+export const SOURCES_UPDATED_EVENT = EVENT_NAMES.SourcesUpdated;
+
+interface RawPageSource {
+  id: string;
+  x: number;
+  y: number;
+  position_type: number;
+  my_translation?: {
+    id: string;
+    content: string;
+    proofread_content?: string;
+    selected: boolean;
+  };
+  translations: {
+    id: string;
+    content: string;
+    proofread_content?: string;
+    selected: boolean;
+  }[];
+  stale?: boolean;
+}
+
+function toPageSource(s: RawPageSource): PageSource {
+  return {
+    id: s.id,
+    x: s.x,
+    y: s.y,
+    positionType: s.position_type,
+    myTranslation: s.my_translation
+      ? {
+          id: s.my_translation.id,
+          content: s.my_translation.content,
+          proofreadContent: s.my_translation.proofread_content,
+          selected: s.my_translation.selected,
+        }
+      : undefined,
+    translations: (s.translations || []).map(t => ({
+      id: t.id,
+      content: t.content,
+      proofreadContent: t.proofread_content,
+      selected: t.selected,
+    })),
+    stale: s.stale,
+  };
 }
 
-export async function getPageSources(fileId: string, targetId: string): Promise<PageSource[]> {
+export async function getPageSources(
+  fileId: string,
+  targetId: string,
+  // markViewed：编辑器打开页面时传 true，记为最近查看的页面
+  options: { allowStale?: boolean; markViewed?: boolean; projectId?: string } = {}
+): Promise<PageSource[]> {
   try {
-    console.debug('[ipc] invoke get_page_sources', { fileId, targetId });
-    const raw = await invoke<
-      {
-        id: string;
-        x: number;
-        y: number;
-        position_type: number;
-        my_translation?: {
-          id: string;
-          content: string;
-          proofread_content?: string;
-          selected: boolean;
-        };
-        translations: {
-          id: string;
-          content: string;
-          proofread_content?: string;
-          selected: boolean;
-        }[];
-      }[]
-    >('get_page_sources', {
+    console.debug('[ipc] invoke get_page_sources', { fileId, targetId, options });
+    const raw = await invoke<RawPageSource[]>('get_page_sources', {
       payload: {
         file_id: fileId,
         target_id: targetId,
+        allow_stale: options.allowStale ?? false,
+        mark_viewed: options.markViewed ?? false,
+        project_id: options.projectId ?? null,
       },
     });
 
     console.debug('[ipc] get_page_sources result', { fileId, targetId, raw });
 
-    return (raw || []).map(s => ({
-      id: s.id,
-      x: s.x,
-      y: s.y,
-      positionType: s.position_type,
-      myTranslation: s.my_translation
-        ? {
-            id: s.my_translation.id,
-            content: s.my_translation.content,
-            proofreadContent: s.my_translation.proofread_content,
-            selected: s.my_translation.selected,
-          }
-        : undefined,
-      translations: (s.translations || []).map(t => ({
-        id: t.id,
-        content: t.content,
-        proofreadContent: t.proofread_content,
-        selected: t.selected,
-      })),
-    }));
+    return (raw || []).map(toPageSource);
   } catch (err) {
     console.error('[ipc] getPageSources failed', { fileId, targetId, err });
     throw err;
   }
 }
 
-export interface CreateSourcePayload {
-  fileId: string;
-  targetId: string;
-  positionType: number;
-  x: number;
-  y: number;
+export interface FilesSourcesBatch {
+  // fileId -> sources，只含拉取成功的页面
+  sources: Record<string, PageSource[]>;
+  // fileId -> 错误信息
+  failures: Record<string, string>;
 }
 
-export async function createSource(payload: CreateSourcePayload): Promise<PageSource> {
+// 一次拉取多页的 sources（并发请求），单页失败记录在 failures 中
+export async function getFilesSourcesBatch(
+  fileIds: string[],
+  targetId: string
+): Promise<FilesSourcesBatch> {
   try {
-    console.debug('[ipc] invoke create_source', payload);
-
     const raw = await invoke<{
-      id: string;
-      x: number;
-      y: number;
-      position_type: number;
-      my_translation?: {
-        id: string;
-        content: string;
-        proofread_content?: string | null;
-        selected: boolean;
-      };
-      translations?: {
-        id: string;
-        content: string;
-        proofread_content?: string | null;
-        selected: boolean;
-      }[];
-    }>('create_source', {
+      sources: Record<string, RawPageSource[]>;
+      failures: Record<string, string>;
+    }>('get_files_sources_batch', {
       payload: {
-        file_id: payload.fileId,
-        target_id: payload.targetId,
-        position_type: payload.positionType,
-        x: payload.x,
-        y: payload.y,
+        file_ids: fileIds,
+        target_id: targetId,
       },
     });
 
-    console.debug('[ipc] create_source result', { payload, raw });
+    const sources: Record<string, PageSource[]> = {};
+    for (const [fileId, list] of Object.entries(raw.sources || {})) {
+      sources[fileId] = (list || []).map(toPageSource);
+    }
 
-    return {
-      id: raw.id,
-      x: raw.x,
-      y: raw.y,
-      positionType: raw.position_type,
-      myTranslation: raw.my_translation
-        ? {
-            id: raw.my_translation.id,
-            content: raw.my_translation.content,
-            proofreadContent:
-              typeof raw.my_translation.proofread_content === 'string'
-                ? raw.my_translation.proofread_content
-                : undefined,
-            selected: raw.my_translation.selected,
-          }
-        : undefined,
-      translations: (raw.translations || []).map(item => ({
-        id: item.id,
-        content: item.content,
-        proofreadContent:
-          typeof item.proofread_content === 'string' ? item.proofread_content : undefined,
-        selected: item.selected,
-      })),
-    };
+    return { sources, failures: raw.failures || {} };
   } catch (err) {
-    console.error('[ipc] createSource failed', { payload, err });
+    console.error('[ipc] getFilesSourcesBatch failed', { fileIds, targetId, err });
     throw err;
   }
 }
 
-export interface UpdateSourcePayload {
-  sourceId: string;
-  positionType?: number;
+// 页面几何信息（与 target 无关），切换 target 时沿用
+export interface PageSourceGeometry {
+  id: string;
+  x: number;
+  y: number;
+  positionType: number;
+}
+
+interface RawTranslation {
+  id: string;
+  content: string;
+  proofread_content?: string | null;
+  selected: boolean;
+}
+
+export interface PageTranslationOverlay {
+  sourceId: string;
+  myTranslation?: PageTranslation;
+  translations: PageTranslation[];
+}
+
+function toPageTranslation(t: RawTranslation): PageTranslation {
+  return {
+    id: t.id,
+    content: t.content,
+    proofreadContent: typeof t.proofread_content === 'string' ? t.proofread_content : undefined,
+    selected: t.selected,
+  };
+}
+
+function toGeometry(raw: { id: string; x: number; y: number; position_type: number }[]) {
+  return raw.map(g => ({ id: g.id, x: g.x, y: g.y, positionType: g.position_type }));
+}
+
+// 已缓存的页面几何信息（不发请求）；没有缓存时为 null，应改用 getPageSources
+export async function getPageGeometry(fileId: string): Promise<PageSourceGeometry[] | null> {
+  try {
+    const raw = await invoke<{ id: string; x: number; y: number; position_type: number }[] | null>(
+      'get_page_geometry',
+      { payload: { file_id: fileId } }
+    );
+
+    return raw ? toGeometry(raw) : null;
+  } catch (err) {
+    console.error('[ipc] getPageGeometry failed', { fileId, err });
+    throw err;
+  }
+}
+
+// 获取某个 target 的翻译覆盖层；geometry 仅在页面几何信息有变化时返回
+export async function getPageTranslations(
+  fileId: string,
+  targetId: string
+): Promise<{ overlay: PageTranslationOverlay[]; geometry: PageSourceGeometry[] | null }> {
+  try {
+    const raw = await invoke<{
+      overlay: {
+        source_id: string;
+        my_translation: RawTranslation | null;
+        translations: RawTranslation[];
+      }[];
+      geometry: { id: string; x: number; y: number; position_type: number }[] | null;
+    }>('get_page_translations', {
+      payload: { file_id: fileId, target_id: targetId },
+    });
+
+    return {
+      overlay: (raw.overlay || []).map(o => ({
+        sourceId: o.source_id,
+        myTranslation: o.my_translation ? toPageTranslation(o.my_translation) : undefined,
+        translations: (o.translations || []).map(toPageTranslation),
+      })),
+      geometry: raw.geometry ? toGeometry(raw.geometry) : null,
+    };
+  } catch (err) {
+    console.error('[ipc] getPageTranslations failed', { fileId, targetId, err });
+    throw err;
+  }
+}
+
+// 合并几何信息与覆盖层，规则与后端 merge_overlay 一致：
+// 以几何信息顺序为准；缺少覆盖层的 source 保留为无翻译；没有几何信息的覆盖层条目丢弃（orphans 非空时应重新获取几何信息）
+export function mergePageOverlay(
+  geometry: PageSourceGeometry[],
+  overlay: PageTranslationOverlay[]
+): { sources: PageSource[]; orphans: string[] } {
+  const known = new Set(geometry.map(g => g.id));
+  const bySource = new Map<string, PageTranslationOverlay>();
+  const orphans: string[] = [];
+
+  for (const item of overlay) {
+    if (known.has(item.sourceId)) {
+      bySource.set(item.sourceId, item);
+    } else if (!orphans.includes(item.sourceId)) {
+      orphans.push(item.sourceId);
+    }
+  }
+
+  const sources = geometry.map(g => {
+    const item = bySource.get(g.id);
+
+    return {
+      id: g.id,
+      x: g.x,
+      y: g.y,
+      positionType: g.positionType,
+      myTranslation: item?.myTranslation,
+      translations: item?.translations ?? [],
+    };
+  });
+
+  return { sources, orphans };
+}
+
+export interface CreateSourcePayload {
+  fileId: string;
+  targetId: string;
+  positionType: number;
+  x: number;
+  y: number;
+}
+
+export async function createSource(payload: CreateSourcePayload): Promise<PageSource> {
+  try {
+    console.debug('[ipc] invoke create_source', payload);
+
+    const raw = await invoke<{
+      id: string;
+      x: number;
+      y: number;
+      position_type: number;
+      my_translation?: {
+        id: string;
+        content: string;
+        proofread_content?: string | null;
+        selected: boolean;
+      };
+      translations?: {
+        id: string;
+        content: string;
+        proofread_content?: string | null;
+        selected: boolean;
+      }[];
+    }>('create_source', {
+      payload: {
+        file_id: payload.fileId,
+        target_id: payload.targetId,
+        position_type: payload.positionType,
+        x: payload.x,
+        y: payload.y,
+      },
+    });
+
+    console.debug('[ipc] create_source result', { payload, raw });
+
+    return {
+      id: raw.id,
+      x: raw.x,
+      y: raw.y,
+      positionType: raw.position_type,
+      myTranslation: raw.my_translation
+        ? {
+            id: raw.my_translation.id,
+            content: raw.my_translation.content,
+            proofreadContent:
+              typeof raw.my_translation.proofread_content === 'string'
+                ? raw.my_translation.proofread_content
+                : undefined,
+            selected: raw.my_translation.selected,
+          }
+        : undefined,
+      translations: (raw.translations || []).map(item => ({
+        id: item.id,
+        content: item.content,
+        proofreadContent:
+          typeof item.proofread_content === 'string' ? item.proofread_content : undefined,
+        selected: item.selected,
+      })),
+    };
+  } catch (err) {
+    console.error('[ipc] createSource failed', { payload, err });
+    throw err;
+  }
+}
+
+export interface UpdateSourcePayload {
+  sourceId: string;
+  positionType?: number;
   x?: number;
   y?: number;
 }
@@ -894,13 +1506,15 @@ export async function updateSource(payload: UpdateSourcePayload): Promise<PageSo
   }
 }
 
-export async function deleteSource(sourceId: string): Promise<void> {
+// 未传 force 时，若 source 上已有翻译，后端会以 DeleteBlocked 错误拒绝（见 isDeleteBlocked）
+export async function deleteSource(sourceId: string, force = false): Promise<void> {
   try {
-    console.debug('[ipc] invoke delete_source', { sourceId });
+    console.debug('[ipc] invoke delete_source', { sourceId, force });
 
     await invoke('delete_source', {
       payload: {
         source_id: sourceId,
+        force,
       },
     });
 
@@ -911,6 +1525,92 @@ export async function deleteSource(sourceId: string): Promise<void> {
   }
 }
 
+// 删除项目中的图片文件；未传 force 时，若文件上已有 source，后端会以 DeleteBlocked 错误拒绝
+export async function deleteProjectFile(
+  fileId: string,
+  targetId: string,
+  force = false
+): Promise<void> {
+  try {
+    await invoke('delete_project_file', {
+      payload: { file_id: fileId, target_id: targetId, force },
+    });
+  } catch (err) {
+    console.error('[ipc] deleteProjectFile failed', { fileId, targetId, err });
+    throw err;
+  }
+}
+
+// 删除翻译目标；未传 force 时，若 target 上已有翻译，后端会以 DeleteBlocked 错误拒绝
+export async function deleteProjectTarget(
+  projectId: string,
+  targetId: string,
+  force = false
+): Promise<void> {
+  try {
+    await invoke('delete_project_target', {
+      payload: { project_id: projectId, target_id: targetId, force },
+    });
+  } catch (err) {
+    console.error('[ipc] deleteProjectTarget failed', { projectId, targetId, err });
+    throw err;
+  }
+}
+
+// 批量删除页面上的 source（dryRun 时只返回选中项与影响，不删除）
+export interface BatchDeleteSourcesReport {
+  file_id: string;
+  dry_run: boolean;
+  selected: string[];
+  impacts: unknown[];
+  deleted: string[];
+  failed: { source_id: string; message: string }[];
+  remaining: string[];
+}
+
+export async function deleteSourcesBatch(params: {
+  fileId: string;
+  targetId: string;
+  sourceIds: string[];
+  dryRun?: boolean;
+}): Promise<BatchDeleteSourcesReport> {
+  try {
+    return await invoke<BatchDeleteSourcesReport>('delete_sources_batch', {
+      payload: {
+        file_id: params.fileId,
+        target_id: params.targetId,
+        source_ids: params.sourceIds,
+        dry_run: params.dryRun ?? false,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] deleteSourcesBatch failed', { params, err });
+    throw err;
+  }
+}
+
+// 删除框选区域内的全部 source（坐标为相对图片宽高的 0..1）
+export async function deleteSourcesInRegion(params: {
+  fileId: string;
+  targetId: string;
+  rect: { x: number; y: number; width: number; height: number };
+  dryRun?: boolean;
+}): Promise<BatchDeleteSourcesReport> {
+  try {
+    return await invoke<BatchDeleteSourcesReport>('delete_sources_in_region', {
+      payload: {
+        file_id: params.fileId,
+        target_id: params.targetId,
+        rect: params.rect,
+        dry_run: params.dryRun ?? false,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] deleteSourcesInRegion failed', { params, err });
+    throw err;
+  }
+}
+
 export interface UpdateTranslationPayload {
   translationId: string;
   selected?: boolean;
@@ -929,11 +1629,15 @@ export async function submitTranslation(
       content: string;
       proofread_content?: string | null;
       selected: boolean;
+      normalized?: TextTransformation[];
     }>('submit_translation', {
       payload: {
         source_id: payload.sourceId,
         target_id: payload.targetId,
         content: payload.content,
+        raw: payload.raw ?? false,
+        team_id: payload.teamId ?? null,
+        allow_empty: payload.allowEmpty ?? false,
       },
     });
 
@@ -945,6 +1649,7 @@ export async function submitTranslation(
       proofreadContent:
         typeof raw.proofread_content === 'string' ? raw.proofread_content : undefined,
       selected: raw.selected,
+      normalized: raw.normalized ?? [],
     };
   } catch (err) {
     console.error('[ipc] submitTranslation failed', { payload, err });
@@ -956,13 +1661,53 @@ export interface SubmitTranslationPayload {
   sourceId: string;
   targetId: string;
   content: string;
+  // 为 true 时跳过内容规范化
+  raw?: boolean;
+  // 用于读取汉化组的标点规则
+  teamId?: string;
+  // 为 true 时允许提交空白译文（默认返回 empty_content 错误）
+  allowEmpty?: boolean;
+}
+
+// 各列表均按提交顺序；登录过期或进入离线模式后剩余条目列入 not_attempted
+export interface BatchSubmitReport {
+  succeeded: string[];
+  failed: { source_id: string; error: AppError }[];
+  not_attempted: string[];
+}
+
+// 一次提交多条翻译（有限并发）；单条失败不会中断其余条目
+export async function submitTranslationsBatch(
+  items: SubmitTranslationPayload[]
+): Promise<BatchSubmitReport> {
+  try {
+    return await invoke<BatchSubmitReport>('submit_translations_batch', {
+      payload: {
+        items: items.map(item => ({
+          source_id: item.sourceId,
+          target_id: item.targetId,
+          content: item.content,
+          raw: item.raw ?? false,
+          team_id: item.teamId ?? null,
+          allow_empty: item.allowEmpty ?? false,
+        })),
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] submitTranslationsBatch failed', { count: items.length, err });
+    throw err;
+  }
 }
 
 export interface UpdateTranslationPayload {
   translationId: string;
   selected?: boolean;
+  // '' 表示清除校对；只含空白时默认返回 empty_content 错误
   proofreadContent?: string;
   content?: string;
+  raw?: boolean;
+  teamId?: string;
+  allowEmpty?: boolean;
 }
 
 export async function updateTranslation(
@@ -988,6 +1733,18 @@ export async function updateTranslation(
     throw new Error('updateTranslation requires at least one field');
   }
 
+  if (payload.raw) {
+    request.raw = true;
+  }
+
+  if (payload.teamId) {
+    request.team_id = payload.teamId;
+  }
+
+  if (payload.allowEmpty) {
+    request.allow_empty = true;
+  }
+
   try {
     console.debug('[ipc] invoke update_translation', request);
 
@@ -996,6 +1753,7 @@ export async function updateTranslation(
       content: string;
       proofread_content?: string | null;
       selected: boolean;
+      normalized?: TextTransformation[];
     }>('update_translation', {
       payload: request,
     });
@@ -1008,6 +1766,7 @@ export async function updateTranslation(
       proofreadContent:
         typeof raw.proofread_content === 'string' ? raw.proofread_content : undefined,
       selected: raw.selected,
+      normalized: raw.normalized ?? [],
     };
   } catch (err) {
     console.error('[ipc] updateTranslation failed', { request, err });
@@ -1015,49 +1774,909 @@ export async function updateTranslation(
   }
 }
 
-export async function proxyImage(url: string): Promise<{ b64: string; content_type: string }> {
+// ========== 空白译文 ==========
+
+export interface EmptyContentError {
+  code: 'empty_content';
+  message: string;
+  field: 'content' | 'proofread_content';
+}
+
+// 提交 / 更新的内容只含空白（含零宽字符、NBSP、全角空格）时的错误
+export function parseEmptyContentError(error: unknown): EmptyContentError | null {
   try {
-    console.debug('[ipc] invoke proxy_image', { url });
-    const raw = await invoke<{ b64: string; content_type: string }>('proxy_image', {
-      url,
-    });
+    const parsed = JSON.parse(errorMessage(error));
+    if (parsed?.code !== 'empty_content') return null;
 
-    console.debug('[ipc] proxy_image result', { url, raw });
+    return { code: 'empty_content', message: parsed.message, field: parsed.field };
+  } catch {
+    return null;
+  }
+}
+
+export interface EmptyTranslation {
+  file_id: string;
+  file_name: string;
+  file_index: number;
+  source_id: string;
+  translation_id: string;
+  field: 'content' | 'proofread_content';
+  selected: boolean;
+  user: { id: string; name: string } | null;
+  // 同一 source 上还有非空白的翻译
+  has_other_content: boolean;
+}
+
+export interface EmptyTranslationsReport {
+  project_id: string;
+  target_id: string;
+  file_count: number;
+  source_count: number;
+  translations: EmptyTranslation[];
+  stale_files: string[];
+}
 
-    return raw as { b64: string; content_type: string };
+export async function findEmptyTranslations(params: {
+  projectId: string;
+  targetId: string;
+}): Promise<EmptyTranslationsReport> {
+  try {
+    return await invoke<EmptyTranslationsReport>('find_empty_translations', {
+      payload: {
+        project_id: params.projectId,
+        target_id: params.targetId,
+      },
+    });
   } catch (err) {
-    console.error('[ipc] proxyImage failed', { url, err });
+    console.error('[ipc] findEmptyTranslations failed', { params, err });
     throw err;
   }
 }
 
-// 上传项目文件（漫画页）
-export async function uploadProjectFile(
-  projectId: string,
-  fileName: string,
-  fileBytes: Uint8Array
-): Promise<void> {
+// ========== 译文规范化 ==========
+
+export type TextTransformation =
+  | 'normalized_line_endings'
+  | 'stripped_invisible'
+  | 'trimmed_trailing_whitespace'
+  | 'mapped_punctuation';
+
+export interface PunctuationRule {
+  from: string;
+  to: string;
+  // false 时只替换紧跟在中日文字符之后的出现
+  anywhere?: boolean;
+}
+
+export interface PunctuationRuleset {
+  rules: PunctuationRule[];
+}
+
+export interface PunctuationRulesetInfo {
+  team_id: string;
+  // null 表示未设置，提交时不替换标点
+  ruleset: PunctuationRuleset | null;
+  default_ruleset: PunctuationRuleset;
+}
+
+// 粘贴时预先规范化；不传 ruleset 时不替换标点
+export async function normalizeText(
+  content: string,
+  ruleset: PunctuationRuleset | null = null,
+): Promise<{ content: string; applied: TextTransformation[] }> {
   try {
-    console.debug('[ipc] invoke upload_project_file', {
-      projectId,
-      fileName,
-      size: fileBytes.length,
+    return await invoke<{ content: string; applied: TextTransformation[] }>('normalize_text', {
+      payload: { content, ruleset },
     });
+  } catch (err) {
+    console.error('[ipc] normalizeText failed', { err });
+    throw err;
+  }
+}
 
-    // 将 Uint8Array 转换为 number[] 以符合 Tauri invoke 序列化
-    const bytesArray = Array.from(fileBytes);
+export async function getPunctuationRuleset(teamId: string): Promise<PunctuationRulesetInfo> {
+  try {
+    return await invoke<PunctuationRulesetInfo>('get_punctuation_ruleset', {
+      payload: { team_id: teamId },
+    });
+  } catch (err) {
+    console.error('[ipc] getPunctuationRuleset failed', { teamId, err });
+    throw err;
+  }
+}
 
-    await invoke<void>('upload_project_file', {
-      payload: {
-        project_id: projectId,
-        file_name: fileName,
-        file_bytes: bytesArray,
-      },
+// ruleset 为 null 时删除汉化组的规则
+export async function setPunctuationRuleset(
+  teamId: string,
+  ruleset: PunctuationRuleset | null,
+): Promise<PunctuationRulesetInfo> {
+  try {
+    return await invoke<PunctuationRulesetInfo>('set_punctuation_ruleset', {
+      payload: { team_id: teamId, ruleset },
     });
+  } catch (err) {
+    console.error('[ipc] setPunctuationRuleset failed', { teamId, err });
+    throw err;
+  }
+}
+
+// 整页通过：没有选定翻译时的选择方式
+export type ApprovalFallback = 'skip' | 'single_only' | 'mine' | 'mine_or_single';
+
+export interface ApprovePageOptions {
+  copyContentWhenEmpty?: boolean;
+  onlySelected?: boolean;
+  overwrite?: boolean;
+  fallback?: ApprovalFallback;
+}
+
+export type SourceApprovalResult = { source_id: string } & (
+  | { outcome: 'approved'; translation_id: string }
+  | { outcome: 'skipped'; reason: string }
+  | { outcome: 'failed'; translation_id: string; message: string }
+);
+
+export interface ApprovePageReport {
+  file_id: string;
+  target_id: string;
+  approved: number;
+  skipped: number;
+  failed: number;
+  sources: SourceApprovalResult[];
+}
 
-    console.debug('[ipc] upload_project_file success', { projectId, fileName });
+// 校对“整页通过”：逐个 source 选定翻译（并可复制译文为校对内容），完成后后端会推送 sources-updated
+export async function approvePage(
+  fileId: string,
+  targetId: string,
+  options: ApprovePageOptions = {}
+): Promise<ApprovePageReport> {
+  const payload = {
+    file_id: fileId,
+    target_id: targetId,
+    options: {
+      copy_content_when_empty: options.copyContentWhenEmpty ?? false,
+      only_selected: options.onlySelected ?? false,
+      overwrite: options.overwrite ?? false,
+      fallback: options.fallback ?? 'single_only',
+    },
+  };
+
+  try {
+    return await invoke<ApprovePageReport>('approve_page', { payload });
   } catch (err) {
-    console.error('[ipc] uploadProjectFile failed', { projectId, fileName, err });
+    console.error('[ipc] approvePage failed', { payload, err });
+    throw err;
+  }
+}
+
+export interface ProxyImageReply {
+  b64: string;
+  content_type: string;
+  // 原 url 签名已过期，后端改用刷新后的 url 取得图片；调用方应替换本地保存的 url
+  refreshed_url?: string;
+}
+
+// file 用于 url 签名过期时由后端重新获取项目文件列表并重试
+export async function proxyImage(
+  url: string,
+  file?: { projectId: string; fileId?: string },
+): Promise<ProxyImageReply> {
+  try {
+    console.debug('[ipc] invoke proxy_image', { url, file });
+    const raw = await invoke<ProxyImageReply>('proxy_image', {
+      url,
+      projectId: file?.projectId ?? null,
+      fileId: file?.fileId ?? null,
+    });
+
+    console.debug('[ipc] proxy_image result', { url, raw });
+
+    return raw;
+  } catch (err) {
+    console.error('[ipc] proxyImage failed', { url, err });
+    throw err;
+  }
+}
+
+// 按后端统一的自然排序规则排序文件名（"img2" < "img10"，全角数字视同半角，忽略大小写）
+export async function sortFileNamesNatural(names: string[]): Promise<string[]> {
+  try {
+    return await invoke<string[]>('sort_file_names_natural', {
+      payload: { names },
+    });
+  } catch (err) {
+    console.error('[ipc] sortFileNamesNatural failed', { count: names.length, err });
+    throw err;
+  }
+}
+
+// 项目中已存在同名文件时的处理方式（默认 skip）
+export type OnDuplicate = 'skip' | 'error' | 'upload_anyway';
+
+// 上传项目文件（漫画页）；onDuplicate 为 error 且存在同名文件时抛出 kind 为 Conflict 的错误
+export async function uploadProjectFile(
+  projectId: string,
+  fileName: string,
+  fileBytes: Uint8Array,
+  onDuplicate: OnDuplicate = 'skip'
+): Promise<FileUploadOutcome> {
+  try {
+    console.debug('[ipc] invoke upload_project_file', {
+      projectId,
+      fileName,
+      size: fileBytes.length,
+    });
+
+    // 将 Uint8Array 转换为 number[] 以符合 Tauri invoke 序列化
+    const bytesArray = Array.from(fileBytes);
+
+    const outcome = await invoke<FileUploadOutcome>('upload_project_file', {
+      payload: {
+        project_id: projectId,
+        file_name: fileName,
+        file_bytes: bytesArray,
+        on_duplicate: onDuplicate,
+      },
+    });
+
+    console.debug('[ipc] upload_project_file success', { projectId, fileName, outcome });
+
+    return outcome;
+  } catch (err) {
+    console.error('[ipc] uploadProjectFile failed', { projectId, fileName, err });
+    throw err;
+  }
+}
+
+export type FileUploadOutcome =
+  | { outcome: 'uploaded'; bytes: number }
+  // 项目中已有同名文件，未上传；无法比较大小时 same_size 为 null
+  | { outcome: 'skipped_duplicate'; existing_file_id: string; same_size: boolean | null }
+  | { outcome: 'failed'; message: string };
+
+export interface FileUploadResult {
+  path: string;
+  file_name: string;
+  result: FileUploadOutcome;
+}
+
+// 多文件上传结果；files 按文件名自然排序（即实际上传顺序）
+export interface UploadFilesReport {
+  project_id: string;
+  uploaded: number;
+  failed: number;
+  // 因项目中已有同名文件而跳过的文件名
+  skipped_duplicates: string[];
+  files: FileUploadResult[];
+}
+
+// 从本地路径批量上传漫画页（后端读取文件，最多 3 个并发）；每个文件结束时发出 upload://progress 事件
+export async function uploadProjectFilesFromPaths(
+  projectId: string,
+  paths: string[],
+  onDuplicate: OnDuplicate = 'skip'
+): Promise<UploadFilesReport> {
+  try {
+    console.debug('[ipc] invoke upload_project_files_from_paths', {
+      projectId,
+      count: paths.length,
+    });
+
+    return await invoke<UploadFilesReport>('upload_project_files_from_paths', {
+      payload: {
+        project_id: projectId,
+        paths,
+        on_duplicate: onDuplicate,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] uploadProjectFilesFromPaths failed', { projectId, paths, err });
+    throw err;
+  }
+}
+
+export interface ProjectDeadlines {
+  proj_id: string;
+  // 按截止时间排序，due_at 为 Unix 秒
+  deadlines: { stage: ProjStage; due_at: number }[];
+  // 已完成阶段的截止时间不计入
+  next: { stage: ProjStage; due_at: number; overdue: boolean } | null;
+}
+
+// 设置项目某阶段的截止时间；dueAt 为 null 时清除
+export async function setProjectDeadline(
+  projId: string,
+  stage: ProjStage,
+  dueAt: number | null,
+): Promise<ProjectDeadlines> {
+  try {
+    return await invoke<ProjectDeadlines>('set_project_deadline', {
+      payload: { proj_id: projId, stage, due_at: dueAt },
+    });
+  } catch (err) {
+    console.error('[ipc] setProjectDeadline failed', { projId, stage, dueAt, err });
+    throw err;
+  }
+}
+
+export async function getProjectDeadlines(projId: string): Promise<ProjectDeadlines> {
+  try {
+    return await invoke<ProjectDeadlines>('get_project_deadlines', {
+      payload: { proj_id: projId },
+    });
+  } catch (err) {
+    console.error('[ipc] getProjectDeadlines failed', { projId, err });
+    throw err;
+  }
+}
+
+// 阶段状态的显示标签；status 为 PopRaKo 状态整数（0=待处理, 1=进行中, 2=已完成）
+export interface StatusLabel {
+  stage: ProjStage;
+  status: number;
+  label: string;
+  // #RGB 或 #RRGGBB
+  color: string;
+}
+
+export interface StatusLabelsReply {
+  team_id: string;
+  // 与默认合并后的完整标签
+  labels: StatusLabel[];
+  // 该组自定义的条目；为空表示全部使用默认
+  customized: StatusLabel[];
+}
+
+export async function getStatusLabels(teamId: string): Promise<StatusLabelsReply> {
+  try {
+    return await invoke<StatusLabelsReply>('get_status_labels', {
+      payload: { team_id: teamId },
+    });
+  } catch (err) {
+    console.error('[ipc] getStatusLabels failed', { teamId, err });
+    throw err;
+  }
+}
+
+// labels 只需包含要修改的条目；传空数组恢复默认。状态值无效、标签为空或超过 16 个字符、颜色格式错误时报错
+export async function setStatusLabels(
+  teamId: string,
+  labels: StatusLabel[],
+): Promise<StatusLabelsReply> {
+  try {
+    return await invoke<StatusLabelsReply>('set_status_labels', {
+      payload: { team_id: teamId, labels },
+    });
+  } catch (err) {
+    console.error('[ipc] setStatusLabels failed', { teamId, labels, err });
+    throw err;
+  }
+}
+
+export interface TextMetrics {
+  // 汉字、假名、谚文（不含标点）
+  cjk_chars: number;
+  latin_words: number;
+  // 非空行数
+  lines: number;
+}
+
+export type TextStatsGroupBy = 'member' | 'file';
+
+export interface TextStatsRow {
+  // 成员 id 或文件 id；作者信息缺失时为 null
+  key: string | null;
+  name: string;
+  translations: number;
+  translated: TextMetrics;
+  proofreads: number;
+  // 校对相对原译文新增 / 改写的部分
+  proofread_added: TextMetrics;
+}
+
+export interface TextStatsReport {
+  project_id: string;
+  target_id: string;
+  group_by: TextStatsGroupBy;
+  include_unselected: boolean;
+  file_count: number;
+  source_count: number;
+  rows: TextStatsRow[];
+  totals: TextStatsRow;
+  // 拉取失败、改用本地快照统计的文件；非空时结果可能不是最新
+  stale_files: string[];
+  csv_path: string | null;
+}
+
+// 项目文字量统计（稿费结算用）；默认只统计被选定的翻译，提供 csvPath 时同时导出 CSV
+export async function getProjectTextStats(params: {
+  projectId: string;
+  targetId: string;
+  groupBy: TextStatsGroupBy;
+  includeUnselected?: boolean;
+  csvPath?: string;
+}): Promise<TextStatsReport> {
+  try {
+    return await invoke<TextStatsReport>('get_project_text_stats', {
+      payload: {
+        project_id: params.projectId,
+        target_id: params.targetId,
+        group_by: params.groupBy,
+        include_unselected: params.includeUnselected ?? false,
+        csv_path: params.csvPath ?? null,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] getProjectTextStats failed', { params, err });
+    throw err;
+  }
+}
+
+// ========== 修图工作量估算 ==========
+
+export type RedrawBucket = 'none' | 'light' | 'heavy';
+
+export interface RedrawThresholds {
+  // 分数达到该值为 light
+  light_score: number;
+  // 分数达到该值为 heavy
+  heavy_score: number;
+}
+
+export interface PageRedrawEstimate {
+  file_id: string;
+  file_name: string;
+  // 框外 source 数
+  outside_count: number;
+  cluster_score: number;
+  // 框外 source 数加聚集分
+  score: number;
+  bucket: RedrawBucket;
+  // 拉取失败、改用本地快照估算
+  stale: boolean;
+}
+
+export interface RedrawEstimateReport {
+  project_id: string;
+  target_id: string;
+  thresholds: RedrawThresholds;
+  pages: PageRedrawEstimate[];
+  totals: {
+    none: number;
+    light: number;
+    heavy: number;
+    outside_sources: number;
+  };
+  estimated_at: number;
+}
+
+export interface RedrawTask {
+  project_id: string;
+  file_id: string;
+  file_name: string;
+  bucket: RedrawBucket;
+  score: number;
+  outside_count: number;
+  created_at: number;
+}
+
+export interface SeedRedrawTasksReport {
+  created: RedrawTask[];
+  // 已有任务而跳过的页面
+  existing: string[];
+}
+
+// 按框外 source 的数量与聚集程度估算各页的修图工作量；不传 thresholds 时使用默认阈值
+export async function estimateRedrawWorkload(
+  projectId: string,
+  targetId: string,
+  thresholds?: RedrawThresholds,
+): Promise<RedrawEstimateReport> {
+  try {
+    return await invoke<RedrawEstimateReport>('estimate_redraw_workload', {
+      payload: { project_id: projectId, target_id: targetId, thresholds: thresholds ?? null },
+    });
+  } catch (err) {
+    console.error('[ipc] estimateRedrawWorkload failed', { projectId, targetId, err });
+    throw err;
+  }
+}
+
+// 按最近一次估算为达到 minBucket（默认 heavy）的页面生成修图任务；需先调用 estimateRedrawWorkload
+export async function seedRedrawTasksFromEstimate(
+  projectId: string,
+  minBucket?: RedrawBucket,
+): Promise<SeedRedrawTasksReport> {
+  try {
+    return await invoke<SeedRedrawTasksReport>('seed_redraw_tasks_from_estimate', {
+      payload: { project_id: projectId, min_bucket: minBucket ?? null },
+    });
+  } catch (err) {
+    console.error('[ipc] seedRedrawTasksFromEstimate failed', { projectId, minBucket, err });
+    throw err;
+  }
+}
+
+export async function listRedrawTasks(projectId: string): Promise<RedrawTask[]> {
+  try {
+    return await invoke<RedrawTask[]>('list_redraw_tasks', {
+      payload: { project_id: projectId },
+    });
+  } catch (err) {
+    console.error('[ipc] listRedrawTasks failed', { projectId, err });
+    throw err;
+  }
+}
+
+// ========== 项目翻译导出（LabelPlus / ZIP / xlsx） ==========
+
+// zip：每页一个 LabelPlus 文本；xlsx：每条 source 一行
+export type ExportFormat = 'labelplus' | 'zip' | 'xlsx';
+
+export interface ExportedPage {
+  name: string;
+  source_count: number;
+}
+
+export interface ExportReport {
+  output_path: string;
+  format: string;
+  pages_total: number;
+  pages_written: number;
+  // 本次从第几页继续（重新导出时为 0）
+  resumed_from: number;
+  // 已写入的页（按导出顺序）及各页的 source 数量
+  pages: ExportedPage[];
+  // 输出不完整：已写完的页可用，带 resume 重新调用可从中断处继续
+  partial: boolean;
+  cancelled: boolean;
+  partial_path?: string;
+  // 未导出的页（文件名）
+  missing: string[];
+  // 拉取 sources 失败而中止导出的页，error 为对应的错误信息
+  failed_page?: string;
+  error?: string;
+}
+
+export interface ExportProgress {
+  total: number;
+  completed: number;
+}
+
+// 取消或中途出错时返回 partial = true 的结果而不是抛出
+export async function exportProjectTranslations(payload: {
+  projectId: string;
+  targetId: string;
+  outputPath: string;
+  format?: ExportFormat;
+  resume?: boolean;
+}): Promise<ExportReport> {
+  try {
+    return await invoke<ExportReport>('export_project_translations', {
+      payload: {
+        project_id: payload.projectId,
+        target_id: payload.targetId,
+        output_path: payload.outputPath,
+        format: payload.format ?? 'labelplus',
+        resume: payload.resume ?? false,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] exportProjectTranslations failed', { payload, err });
+    throw err;
+  }
+}
+
+// 导出 LabelPlus 翻译稿（页按文件名自然排序）；某页拉取失败时返回 partial = true 的结果，
+// failed_page / error 指明失败的页
+export async function exportLabelplus(
+  projectId: string,
+  targetId: string,
+  outputPath: string
+): Promise<ExportReport> {
+  try {
+    return await invoke<ExportReport>('export_labelplus', {
+      payload: { project_id: projectId, target_id: targetId, output_path: outputPath },
+    });
+  } catch (err) {
+    console.error('[ipc] exportLabelplus failed', { projectId, targetId, err });
+    throw err;
+  }
+}
+
+// 返回是否存在进行中的导出
+export async function cancelProjectExport(projectId: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>('cancel_project_export', {
+      payload: { project_id: projectId },
+    });
+  } catch (err) {
+    console.error('[ipc] cancelProjectExport failed', { projectId, err });
+    throw err;
+  }
+}
+
+export async function getProjectExportProgress(projectId: string): Promise<ExportProgress | null> {
+  try {
+    return await invoke<ExportProgress | null>('get_project_export_progress', {
+      payload: { project_id: projectId },
+    });
+  } catch (err) {
+    console.error('[ipc] getProjectExportProgress failed', { projectId, err });
+    throw err;
+  }
+}
+
+// ========== LabelPlus 翻译稿导入 ==========
+
+export interface LabelImportFailure {
+  page: string;
+  // 标记在页内的序号（从 1 开始）
+  label_index: number;
+  error: AppError;
+}
+
+export interface LabelplusImportReport {
+  dry_run: boolean;
+  pages_matched: number;
+  // 需要新建的 source 数
+  labels_to_create: number;
+  // 需要提交的译文数（非空且与已有译文不同）
+  translations_to_submit: number;
+  // 没有同名文件的页
+  unmatched_pages: string[];
+  sources_created: number;
+  translations_submitted: number;
+  failed: LabelImportFailure[];
+  // 登录过期或进入离线模式后中止
+  aborted: boolean;
+}
+
+// 文件格式错误时抛出 InvalidInput（含行号与列号）；dryRun 时只返回计划
+export async function importLabelplus(payload: {
+  projectId: string;
+  targetId: string;
+  filePath: string;
+  dryRun?: boolean;
+}): Promise<LabelplusImportReport> {
+  try {
+    return await invoke<LabelplusImportReport>('import_labelplus', {
+      payload: {
+        project_id: payload.projectId,
+        target_id: payload.targetId,
+        file_path: payload.filePath,
+        dry_run: payload.dryRun ?? false,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] importLabelplus failed', { payload, err });
+    throw err;
+  }
+}
+
+// ========== 阅读版导出 ==========
+
+export interface ReadingViewOptions {
+  // 未翻译的 source 也显示为占位框
+  includeUntranslated?: boolean;
+  // 设置后图片中烧入斜向条纹，并在每页叠加水印文字
+  watermarkText?: string;
+  // 图片最大宽度（像素），默认 1200
+  maxWidth?: number;
+  // 显示与 LabelPlus 导出一致的编号，默认 true
+  numbering?: boolean;
+  // single_file：单个 HTML（图片内嵌）；folder：destPath 为目录，写入 index.html 与 images/
+  layout?: 'single_file' | 'folder';
+}
+
+export interface ReadingViewReport {
+  index_path: string;
+  pages: number;
+  labels: number;
+  // 没有缓存图片、以译文列表代替的页
+  placeholder_pages: string[];
+  bytes: number;
+}
+
+export async function exportReadingView(payload: {
+  projectId: string;
+  targetId: string;
+  destPath: string;
+  options?: ReadingViewOptions;
+}): Promise<ReadingViewReport> {
+  const options = payload.options ?? {};
+
+  try {
+    return await invoke<ReadingViewReport>('export_reading_view', {
+      payload: {
+        project_id: payload.projectId,
+        target_id: payload.targetId,
+        dest_path: payload.destPath,
+        options: {
+          include_untranslated: options.includeUntranslated ?? false,
+          watermark_text: options.watermarkText ?? null,
+          max_width: options.maxWidth ?? null,
+          numbering: options.numbering ?? true,
+          layout: options.layout ?? 'single_file',
+        },
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] exportReadingView failed', { payload, err });
+    throw err;
+  }
+}
+
+// ========== 按卷清单批量创建项目 ==========
+
+export type ManifestPlanAction = 'create' | 'existing';
+
+export interface ManifestIssue {
+  // 为空时针对整个清单或项目集
+  chapter?: string;
+  message: string;
+}
+
+export interface ManifestChapterResult {
+  name: string;
+  index: number;
+  outcome: 'created' | 'skipped' | 'failed';
+  proj_id?: string;
+  error?: string;
+}
+
+export interface ManifestReport {
+  manifest_path: string;
+  dry_run: boolean;
+  projset: { name: string; action: ManifestPlanAction; projset_id: string | null };
+  chapters: {
+    name: string;
+    index: number;
+    target_languages: string[];
+    action: ManifestPlanAction;
+    proj_id: string | null;
+  }[];
+  // 不为空时未创建任何内容
+  issues: ManifestIssue[];
+  results: ManifestChapterResult[];
+  // 清单旁的 "<清单名>.report.json"
+  report_path?: string;
+}
+
+// manifestPath 为 .toml 或 .json 清单；dryRun 时只返回计划；重新执行同一清单会跳过项目集内已有的同名项目
+export async function createProjectsFromManifest(payload: {
+  teamId: string;
+  manifestPath: string;
+  dryRun?: boolean;
+}): Promise<ManifestReport> {
+  try {
+    return await invoke<ManifestReport>('create_projects_from_manifest', {
+      payload: {
+        team_id: payload.teamId,
+        manifest_path: payload.manifestPath,
+        dry_run: payload.dryRun ?? false,
+      },
+    });
+  } catch (err) {
+    console.error('[ipc] createProjectsFromManifest failed', { payload, err });
+    throw err;
+  }
+}
+
+// ========== 项目状态历史 ==========
+
+export type ProjStageName = 'translating' | 'proofreading' | 'typesetting' | 'reviewing';
+
+export type ProjectChange =
+  | { kind: 'renamed'; from: string; to: string }
+  | { kind: 'status_changed'; stage: ProjStageName; from: number; to: number }
+  | { kind: 'stage_completed'; stage: ProjStageName; from: number }
+  | { kind: 'stage_reopened'; stage: ProjStageName; to: number }
+  | {
+      kind: 'count_changed';
+      counter: 'sources' | 'translated' | 'checked';
+      from: number;
+      to: number;
+    }
+  | { kind: 'published' }
+  | { kind: 'unpublished' }
+  | { kind: 'members_changed'; from_count: number | null; to_count: number | null };
+
+export interface ProjectSnapshotEntry {
+  proj_id: string;
+  fetched_at: number;
+  name: string;
+  // 按 翻译 / 校对 / 嵌字 / 审核 的顺序，未知时为 null
+  statuses: (number | null)[];
+  source_count: number;
+  translated_source_count: number;
+  checked_source_count: number;
+  is_published: boolean | null;
+  member_hash: string | null;
+  member_count: number | null;
+  // 相对更早一条快照的变化（最早一条为空）
+  changes: ProjectChange[];
+}
+
+// 新的在前
+export async function getProjectSnapshotHistory(
+  projId: string,
+  limit?: number
+): Promise<ProjectSnapshotEntry[]> {
+  try {
+    return await invoke<ProjectSnapshotEntry[]>('get_project_snapshot_history', {
+      payload: { proj_id: projId, limit: limit ?? null },
+    });
+  } catch (err) {
+    console.error('[ipc] getProjectSnapshotHistory failed', { projId, err });
+    throw err;
+  }
+}
+
+// ========== 最近活动的页面 ==========
+
+export interface RecentFile {
+  file_id: string;
+  target_id: string;
+  last_action: 'submit' | 'update' | 'view';
+  at: number;
+  // 最近一次提交 / 更新的时间；只查看过时为 null
+  edited_at: number | null;
+}
+
+// 我在该项目 target 下最近提交、更新或打开过的页面（新的在前）
+export async function getRecentFiles(
+  projectId: string,
+  targetId: string,
+  limit?: number
+): Promise<RecentFile[]> {
+  try {
+    return await invoke<RecentFile[]>('get_recent_files', {
+      payload: { project_id: projectId, target_id: targetId, limit: limit ?? null },
+    });
+  } catch (err) {
+    console.error('[ipc] getRecentFiles failed', { projectId, targetId, err });
+    throw err;
+  }
+}
+
+// ========== 提交后的持久化校验 ==========
+
+export type { LostTranslation };
+
+// 校验发现丢失、尚未处理的翻译（错过 translation-lost 事件时使用）
+export async function listLostTranslations(): Promise<LostTranslation[]> {
+  try {
+    return await invoke<LostTranslation[]>('list_lost_translations');
+  } catch (err) {
+    console.error('[ipc] listLostTranslations failed', { err });
+    throw err;
+  }
+}
+
+// 以提交时的内容原样重新提交
+export async function resubmitLostTranslation(queueId: number): Promise<PageTranslation> {
+  try {
+    const raw = await invoke<{
+      id: string;
+      content: string;
+      proofread_content?: string | null;
+      selected: boolean;
+    }>('resubmit_lost_translation', {
+      payload: { queue_id: queueId },
+    });
+
+    return {
+      id: raw.id,
+      content: raw.content,
+      proofreadContent:
+        typeof raw.proofread_content === 'string' ? raw.proofread_content : undefined,
+      selected: raw.selected,
+      normalized: [],
+    };
+  } catch (err) {
+    console.error('[ipc] resubmitLostTranslation failed', { queueId, err });
     throw err;
   }
 }
@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+import { isAppError, type AppError } from './errors';
+
+// 失败写操作的一键重试：更新项目状态、提交 / 更新翻译、创建 / 移动 source 因网络类错误失败时，
+// 抛出 kind 为 'Retryable' 的 AppError，可凭 retry_token 调用 retryCommand 重新执行，无需重填表单；
+// 登记保存在本地数据库中，重启应用后仍可重试
+
+export type RetryableError = AppError & {
+  kind: 'Retryable';
+  retry_token: string;
+  command: string;
+};
+
+export function parseRetryableError(error: unknown): RetryableError | null {
+  return isAppError(error) && error.kind === 'Retryable' ? (error as RetryableError) : null;
+}
+
+export interface RetryableFailure {
+  retry_token: string;
+  command: string;
+  // 如 "proj:<id>:translating"，同一实体上之后的修改会使 token 失效
+  entity: string;
+  error: string;
+  failed_at: number;
+  expires_at: number;
+}
+
+// 按失败时间从新到旧
+export async function listRetryableFailures(): Promise<RetryableFailure[]> {
+  try {
+    return await invoke<RetryableFailure[]>('list_retryable_failures');
+  } catch (err) {
+    console.error('[ipc] listRetryableFailures failed', err);
+    throw err;
+  }
+}
+
+// 返回原命令的结果；token 只能使用一次，再次失败时错误中带有新的 token
+export async function retryCommand<T = unknown>(retryToken: string): Promise<T> {
+  try {
+    return await invoke<T>('retry_command', {
+      payload: { retry_token: retryToken },
+    });
+  } catch (err) {
+    console.error('[ipc] retryCommand failed', { retryToken, err });
+    throw err;
+  }
+}
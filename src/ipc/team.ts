@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { ResTeam } from '../api/model/team';
+import type { PageInfo } from './project';
 
 // 获取当前用户的汉化组列表
 export async function getUserTeams(params: { page: number; limit: number }): Promise<ResTeam[]> {
@@ -29,3 +30,98 @@ export async function getUserTeams(params: { page: number; limit: number }): Pro
     throw error;
   }
 }
+
+interface RawResTeamPage {
+  items: { id: string; avatar: string; has_avatar: boolean; name: string }[];
+  total: number | null;
+  page: number;
+  limit: number;
+  has_more: boolean;
+}
+
+// 同 getUserTeams，附带分页信息（总数来自 Moetran 的 X-Pagination-Count）
+export async function getUserTeamsPage(params: {
+  page: number;
+  limit: number;
+}): Promise<{ items: ResTeam[]; pagination: PageInfo }> {
+  try {
+    const raw = await invoke<RawResTeamPage>('get_user_teams_page', {
+      payload: { page: params.page, limit: params.limit },
+    });
+
+    return {
+      items: raw.items.map(r => ({
+        id: r.id,
+        avatar: r.avatar,
+        hasAvatar: !!r.has_avatar,
+        name: r.name,
+      })),
+      pagination: {
+        total: raw.total,
+        page: raw.page,
+        limit: raw.limit,
+        hasMore: raw.has_more,
+      },
+    };
+  } catch (err) {
+    console.error('[ipc] getUserTeamsPage failed', { params, err });
+    throw err;
+  }
+}
+
+export type DigestKind =
+  | 'new_project'
+  | 'new_assignment'
+  | 'status_change'
+  | 'completed'
+  | 'published';
+
+export interface DigestEntry {
+  proj_id: string;
+  proj_name: string;
+  // 发布记录为实际发布时间；其余为观察到变化的时间（Unix 秒）
+  at: number;
+  stage?: 'translating' | 'proofreading' | 'typesetting' | 'reviewing';
+  from_status?: number;
+  to_status?: number;
+  user_id?: string;
+  username?: string;
+}
+
+export interface TeamDigest {
+  team_id: string;
+  since: number;
+  generated_at: number;
+  // false 时未能拉取最新列表，只比较了本地快照
+  network_refresh: boolean;
+  refresh_error?: string;
+  // 从未标记过已读，只包含发布记录
+  first_visit: boolean;
+  // 固定顺序，每类一项；没有条目时 entries 为空数组
+  categories: { kind: DigestKind; entries: DigestEntry[] }[];
+  total: number;
+}
+
+// 上次标记已读以来汉化组内的变化；since（Unix 秒）不提供时为上次标记已读的时间
+export async function getTeamDigest(teamId: string, since?: number): Promise<TeamDigest> {
+  try {
+    return await invoke<TeamDigest>('get_team_digest', {
+      payload: { team_id: teamId, since: since ?? null },
+    });
+  } catch (err) {
+    console.error('[ipc] getTeamDigest failed', { teamId, since, err });
+    throw err;
+  }
+}
+
+// 返回新的已读时间（Unix 秒）
+export async function markDigestSeen(teamId: string): Promise<number> {
+  try {
+    return await invoke<number>('mark_digest_seen', {
+      payload: { team_id: teamId },
+    });
+  } catch (err) {
+    console.error('[ipc] markDigestSeen failed', { teamId, err });
+    throw err;
+  }
+}
@@ -0,0 +1,50 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// 上次的界面会话：汉化组、页面路由及其余纯前端状态
+export interface UiRoute {
+  name: string;
+  project_id?: string | null;
+  params?: unknown;
+}
+
+export interface UiSession {
+  version?: number;
+  team_id?: string | null;
+  route?: UiRoute | null;
+  extra?: unknown;
+}
+
+const SAVE_DEBOUNCE_MS = 1000;
+
+let pendingSave: ReturnType<typeof setTimeout> | null = null;
+
+export async function saveUiSession(session: UiSession): Promise<void> {
+  try {
+    await invoke<void>('save_ui_session', {
+      payload: { state_json: JSON.stringify(session) },
+    });
+  } catch (err) {
+    console.error('[ipc] saveUiSession failed', err);
+    throw err;
+  }
+}
+
+// 防抖保存：频繁切换页面时只写入最后一次状态
+export function saveUiSessionDebounced(session: UiSession): void {
+  if (pendingSave !== null) clearTimeout(pendingSave);
+
+  pendingSave = setTimeout(() => {
+    pendingSave = null;
+    saveUiSession(session).catch(() => {});
+  }, SAVE_DEBOUNCE_MS);
+}
+
+// 读取上次会话；已退出的汉化组与失效的项目路由已由后端剔除
+export async function getUiSession(): Promise<UiSession | null> {
+  try {
+    return await invoke<UiSession | null>('get_ui_session');
+  } catch (err) {
+    console.error('[ipc] getUiSession failed', err);
+    throw err;
+  }
+}
@@ -1,4 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { ReqToken } from '../api/model/auth';
+import type { ResTeam } from '../api/model/team';
 import type { ResSync, ResUser } from '../api/model/user';
 
 // PopRaKo 用户同步（使用 email 作为后端的 username 字段）
@@ -46,4 +48,128 @@ export async function getUserInfo(): Promise<ResUser> {
   }
 }
 
+// 会话身份：Moetran 账号与 PopRaKo token 所属账号是否一致
+export interface SessionIdentity {
+  moetranUserId: string | null;
+  moetranUserName: string | null;
+  poprakoUserId: string | null;
+  status: 'unknown' | 'match' | 'mismatch';
+  reason: string | null;
+  confirmed: boolean;
+  checkedAt: number | null;
+}
+
+interface RawSessionIdentity {
+  moetran_user_id: string | null;
+  moetran_user_name: string | null;
+  poprako_user_id: string | null;
+  status: 'unknown' | 'match' | 'mismatch';
+  reason: string | null;
+  confirmed: boolean;
+  checked_at: number | null;
+}
+
+function mapRawSessionIdentity(raw: RawSessionIdentity): SessionIdentity {
+  return {
+    moetranUserId: raw.moetran_user_id,
+    moetranUserName: raw.moetran_user_name,
+    poprakoUserId: raw.poprako_user_id,
+    status: raw.status,
+    reason: raw.reason,
+    confirmed: raw.confirmed,
+    checkedAt: raw.checked_at,
+  };
+}
+
+// 重新检查会话身份（不一致时后端会发出 session://identity-mismatch 事件）
+export async function checkSessionIdentity(): Promise<SessionIdentity> {
+  const raw = await invoke<RawSessionIdentity>('check_session_identity');
+  return mapRawSessionIdentity(raw);
+}
+
+// 获取最近一次的检查结果
+export async function getSessionIdentity(): Promise<SessionIdentity> {
+  const raw = await invoke<RawSessionIdentity>('get_session_identity');
+  return mapRawSessionIdentity(raw);
+}
+
+// 确认继续使用不一致的身份（解除 PopRaKo 写操作拦截）
+export async function confirmSessionIdentity(): Promise<SessionIdentity> {
+  const raw = await invoke<RawSessionIdentity>('confirm_session_identity');
+  return mapRawSessionIdentity(raw);
+}
+
+// ========== 一次完成登录 ==========
+
+// full：Moetran 与 PopRaKo 都已登录；moetran_only：PopRaKo 同步失败，仅 Moetran 可用；none：未登录，未保存任何 token
+export type SessionMode = 'full' | 'moetran_only' | 'none';
+
+export type BootstrapStep =
+  | 'acquire_token'
+  | 'save_moetran_token'
+  | 'user_info'
+  | 'sync_poprako'
+  | 'save_poprako_token'
+  | 'teams';
+
+export interface BootstrapSession {
+  mode: SessionMode;
+  user: ResUser | null;
+  // 拉取失败时为 null
+  teams: ResTeam[] | null;
+  poprakoSynced: boolean;
+  identity: SessionIdentity | null;
+  // 各步骤的失败（按发生顺序）；登录失败时也通过这里返回原因，而不是抛出异常
+  failures: { step: BootstrapStep; message: string }[];
+}
+
+// 登录、保存 token、同步 PopRaKo、拉取汉化组与身份检查一次完成
+export async function bootstrapSession(payload: ReqToken): Promise<BootstrapSession> {
+  try {
+    interface RawBootstrapSession {
+      mode: SessionMode;
+      user: { id: string; name: string; has_avatar: boolean; avatar: string } | null;
+      teams: { id: string; avatar: string; has_avatar: boolean; name: string }[] | null;
+      poprako_synced: boolean;
+      identity: RawSessionIdentity | null;
+      failures: { step: BootstrapStep; message: string }[];
+    }
+
+    const raw = await invoke<RawBootstrapSession>('bootstrap_session', {
+      payload: {
+        email: payload.email,
+        password: payload.password,
+        captcha: payload.captcha,
+        captcha_info: payload.info,
+      },
+    });
+
+    return {
+      mode: raw.mode,
+      user: raw.user
+        ? {
+            id: raw.user.id,
+            name: raw.user.name,
+            hasAvatar: raw.user.has_avatar,
+            avatar: raw.user.avatar,
+          }
+        : null,
+      teams: raw.teams
+        ? raw.teams.map(t => ({
+            id: t.id,
+            avatar: t.avatar,
+            hasAvatar: !!t.has_avatar,
+            name: t.name,
+          }))
+        : null,
+      poprakoSynced: raw.poprako_synced,
+      identity: raw.identity ? mapRawSessionIdentity(raw.identity) : null,
+      failures: raw.failures,
+    };
+  } catch (error) {
+    console.error('Error in bootstrapSession:', { email: payload.email, error });
+    throw error;
+  }
+}
+
 // (用户汉化组与项目相关接口已迁移到 team.ts / project.ts)